@@ -24,6 +24,7 @@ use jj_cli::cli_util::CommandHelper;
 use jj_cli::command_error::CommandError;
 use jj_cli::ui::Ui;
 use jj_lib::backend::Backend;
+use jj_lib::backend::BackendGcStats;
 use jj_lib::backend::BackendInitError;
 use jj_lib::backend::BackendLoadError;
 use jj_lib::backend::BackendResult;
@@ -205,4 +206,12 @@ impl Backend for JitBackend {
     fn gc(&self, index: &dyn Index, keep_newer: SystemTime) -> BackendResult<()> {
         self.inner.gc(index, keep_newer)
     }
+
+    fn gc_dry_run(
+        &self,
+        index: &dyn Index,
+        keep_newer: SystemTime,
+    ) -> BackendResult<BackendGcStats> {
+        self.inner.gc_dry_run(index, keep_newer)
+    }
 }
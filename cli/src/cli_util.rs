@@ -47,6 +47,7 @@ use clap::Command;
 use clap::FromArgMatches;
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
+use futures::StreamExt as _;
 use indexmap::IndexMap;
 use indexmap::IndexSet;
 use indoc::writedoc;
@@ -57,6 +58,7 @@ use jj_lib::backend::CommitId;
 use jj_lib::backend::MergedTreeId;
 use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
+use jj_lib::commit::CommitIteratorExt as _;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::config::ConfigLayer;
@@ -64,6 +66,7 @@ use jj_lib::config::ConfigMigrationRule;
 use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigSource;
 use jj_lib::config::StackedConfig;
+use jj_lib::conflicts::is_binary_file_conflict;
 use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
@@ -74,6 +77,8 @@ use jj_lib::id_prefix::IdPrefixContext;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::MergedTreeBuilder;
+use jj_lib::merged_tree::TreeDiffEntry;
 use jj_lib::object_id::ObjectId;
 use jj_lib::op_heads_store;
 use jj_lib::op_store::OpStoreError;
@@ -114,6 +119,7 @@ use jj_lib::rewrite::restore_tree;
 use jj_lib::settings::HumanByteSize;
 use jj_lib::settings::UserSettings;
 use jj_lib::str_util::StringPattern;
+use jj_lib::time_util::DatePatternContext;
 use jj_lib::transaction::Transaction;
 use jj_lib::view::View;
 use jj_lib::working_copy;
@@ -134,6 +140,7 @@ use jj_lib::workspace::Workspace;
 use jj_lib::workspace::WorkspaceLoadError;
 use jj_lib::workspace::WorkspaceLoader;
 use jj_lib::workspace::WorkspaceLoaderFactory;
+use pollster::FutureExt as _;
 use tracing::instrument;
 use tracing_chrome::ChromeLayerBuilder;
 use tracing_subscriber::prelude::*;
@@ -163,6 +170,12 @@ use crate::diff_util::DiffRenderer;
 use crate::formatter::FormatRecorder;
 use crate::formatter::Formatter;
 use crate::formatter::PlainTextFormatter;
+use crate::hooks::has_hooks;
+use crate::hooks::operation_json;
+use crate::hooks::run_post_hooks;
+use crate::hooks::run_pre_hooks;
+use crate::hooks::HookEvent;
+use crate::maintenance_util::maybe_start_auto_maintenance;
 use crate::merge_tools::DiffEditor;
 use crate::merge_tools::MergeEditor;
 use crate::merge_tools::MergeToolConfigError;
@@ -170,6 +183,7 @@ use crate::operation_templater::OperationTemplateLanguage;
 use crate::operation_templater::OperationTemplateLanguageExtension;
 use crate::revset_util;
 use crate::revset_util::RevsetExpressionEvaluator;
+use crate::secret_scan::SecretScanner;
 use crate::template_builder;
 use crate::template_builder::TemplateLanguage;
 use crate::template_parser::TemplateAliasesMap;
@@ -292,6 +306,7 @@ struct CommandHelperData {
     commit_template_extensions: Vec<Arc<dyn CommitTemplateLanguageExtension>>,
     operation_template_extensions: Vec<Arc<dyn OperationTemplateLanguageExtension>>,
     maybe_workspace_loader: Result<Box<dyn WorkspaceLoader>, CommandError>,
+    workspace_loader_factory: Box<dyn WorkspaceLoaderFactory>,
     store_factories: StoreFactories,
     working_copy_factories: WorkingCopyFactories,
 }
@@ -400,6 +415,28 @@ impl CommandHelper {
             .map_err(Clone::clone)
     }
 
+    /// Loads the repo of another workspace located at `path`, which is
+    /// resolved relative to the current working directory.
+    ///
+    /// Unlike `-R/--repository`, the repo config of that workspace isn't
+    /// loaded. This is useful for comparing the current repo with a backup
+    /// copy of it.
+    pub fn load_repo_loader_at(&self, path: &str) -> Result<RepoLoader, CommandError> {
+        let abs_path = self.cwd().join(path);
+        let abs_path = dunce::canonicalize(&abs_path).unwrap_or(abs_path);
+        let loader = self
+            .data
+            .workspace_loader_factory
+            .create(&abs_path)
+            .map_err(|err| map_workspace_load_error(err, Some(path)))?;
+        RepoLoader::init_from_file_system(
+            self.settings(),
+            loader.repo_path(),
+            &self.data.store_factories,
+        )
+        .map_err(|err| map_workspace_load_error(err.into(), Some(path)))
+    }
+
     /// Loads workspace and repo, then snapshots the working copy if allowed.
     #[instrument(skip(self, ui))]
     pub fn workspace_helper(&self, ui: &Ui) -> Result<WorkspaceCommandHelper, CommandError> {
@@ -597,7 +634,11 @@ impl CommandHelper {
         repo_loader: &RepoLoader,
     ) -> Result<Operation, CommandError> {
         if let Some(op_str) = &self.data.global_args.at_operation {
-            Ok(op_walk::resolve_op_for_load(repo_loader, op_str)?)
+            Ok(op_walk::resolve_op_for_load(
+                repo_loader,
+                op_str,
+                &date_pattern_context(self.settings()),
+            )?)
         } else {
             op_heads_store::resolve_op_heads(
                 repo_loader.op_heads_store().as_ref(),
@@ -631,6 +672,28 @@ impl CommandHelper {
         }
     }
 
+    /// Resolves the operations to inspect without merging divergent
+    /// operations.
+    ///
+    /// If `--at-op` isn't specified or is `@`, all current head operations are
+    /// returned, which may be more than one if there are divergent operations.
+    pub fn resolve_unmerged_operations(
+        &self,
+        repo_loader: &RepoLoader,
+    ) -> Result<Vec<Operation>, CommandError> {
+        match self.data.global_args.at_operation.as_deref() {
+            None | Some("@") => Ok(op_walk::get_current_head_ops(
+                repo_loader.op_store(),
+                repo_loader.op_heads_store().as_ref(),
+            )?),
+            Some(op_str) => Ok(vec![op_walk::resolve_op_for_load(
+                repo_loader,
+                op_str,
+                &date_pattern_context(self.settings()),
+            )?]),
+        }
+    }
+
     /// Creates helper for the repo whose view is supposed to be in sync with
     /// the working copy. If `--ignore-working-copy` is not specified, the
     /// returned helper will attempt to update the working copy.
@@ -647,6 +710,19 @@ impl CommandHelper {
     }
 }
 
+/// Creates context to interpret dates relative to the current time, or the
+/// fixed commit timestamp if configured.
+pub fn date_pattern_context(settings: &UserSettings) -> DatePatternContext {
+    let now = if let Some(timestamp) = settings.commit_timestamp() {
+        chrono::Local
+            .timestamp_millis_opt(timestamp.timestamp.0)
+            .unwrap()
+    } else {
+        chrono::Local::now()
+    };
+    now.into()
+}
+
 /// A ReadonlyRepo along with user-config-dependent derived data. The derived
 /// data is lazily loaded.
 struct ReadonlyUserRepo {
@@ -741,6 +817,7 @@ pub struct WorkspaceCommandEnvironment {
     settings: UserSettings,
     revset_aliases_map: RevsetAliasesMap,
     template_aliases_map: TemplateAliasesMap,
+    repo_path: PathBuf,
     path_converter: RepoPathUiConverter,
     workspace_id: WorkspaceId,
     immutable_heads_expression: Rc<UserRevsetExpression>,
@@ -763,6 +840,7 @@ impl WorkspaceCommandEnvironment {
             settings: settings.clone(),
             revset_aliases_map,
             template_aliases_map,
+            repo_path: workspace.repo_path().to_owned(),
             path_converter,
             workspace_id: workspace.workspace_id().to_owned(),
             immutable_heads_expression: RevsetExpression::root(),
@@ -782,22 +860,53 @@ impl WorkspaceCommandEnvironment {
         &self.workspace_id
     }
 
+    /// Parses the given strings as file patterns.
+    pub fn parse_file_patterns(
+        &self,
+        ui: &Ui,
+        values: &[String],
+    ) -> Result<FilesetExpression, CommandError> {
+        // TODO: This function might be superseded by parse_union_filesets(),
+        // but it would be weird if parse_union_*() had a special case for the
+        // empty arguments.
+        if values.is_empty() {
+            Ok(FilesetExpression::all())
+        } else if self.settings.get_bool("ui.allow-filesets")? {
+            self.parse_union_filesets(ui, values)
+        } else {
+            let expressions = values
+                .iter()
+                .map(|v| self.path_converter.parse_file_path(v))
+                .map_ok(FilesetExpression::prefix_path)
+                .try_collect()?;
+            Ok(FilesetExpression::union_all(expressions))
+        }
+    }
+
+    /// Parses the given fileset expressions and concatenates them all.
+    pub fn parse_union_filesets(
+        &self,
+        ui: &Ui,
+        file_args: &[String], // TODO: introduce FileArg newtype?
+    ) -> Result<FilesetExpression, CommandError> {
+        let mut diagnostics = FilesetDiagnostics::new();
+        let expressions: Vec<_> = file_args
+            .iter()
+            .map(|arg| fileset::parse_maybe_bare(&mut diagnostics, arg, &self.path_converter))
+            .try_collect()?;
+        print_parse_diagnostics(ui, "In fileset expression", &diagnostics)?;
+        Ok(FilesetExpression::union_all(expressions))
+    }
+
     pub(crate) fn revset_parse_context(&self) -> RevsetParseContext {
         let workspace_context = RevsetWorkspaceContext {
             path_converter: &self.path_converter,
             workspace_id: &self.workspace_id,
         };
-        let now = if let Some(timestamp) = self.settings.commit_timestamp() {
-            chrono::Local
-                .timestamp_millis_opt(timestamp.timestamp.0)
-                .unwrap()
-        } else {
-            chrono::Local::now()
-        };
         RevsetParseContext::new(
             &self.revset_aliases_map,
             self.settings.user_email(),
-            now.into(),
+            date_pattern_context(&self.settings),
             self.command.revset_extensions(),
             Some(workspace_context),
         )
@@ -899,6 +1008,40 @@ impl WorkspaceCommandEnvironment {
         Ok(commit_id_iter.next().transpose()?)
     }
 
+    /// Like `find_immutable_commit()`, but returns all the immutable commits.
+    fn find_immutable_commits<'a>(
+        &self,
+        repo: &dyn Repo,
+        commits: impl IntoIterator<Item = &'a CommitId>,
+    ) -> Result<HashSet<CommitId>, CommandError> {
+        if self.command.global_args().ignore_immutable {
+            let root_id = repo.store().root_commit_id();
+            return Ok(commits
+                .into_iter()
+                .filter(|id| *id == root_id)
+                .cloned()
+                .collect());
+        }
+
+        let id_prefix_context = IdPrefixContext::new(self.command.revset_extensions().clone());
+        let commits_revset = RevsetExpression::commits(commits.into_iter().cloned().collect_vec());
+        let mut expression = RevsetExpressionEvaluator::new(
+            repo,
+            self.command.revset_extensions().clone(),
+            &id_prefix_context,
+            self.immutable_expression(),
+        );
+        expression.intersect_with(&commits_revset);
+
+        let commit_ids: HashSet<CommitId> = expression
+            .evaluate_to_commit_ids()
+            .map_err(|e| {
+                config_error_with_message("Invalid `revset-aliases.immutable_heads()`", e)
+            })?
+            .try_collect()?;
+        Ok(commit_ids)
+    }
+
     /// Parses template of the given language into evaluation tree.
     ///
     /// `wrap_self` specifies the type of the top-level property, which should
@@ -931,6 +1074,7 @@ impl WorkspaceCommandEnvironment {
     ) -> CommitTemplateLanguage<'a> {
         CommitTemplateLanguage::new(
             repo,
+            &self.repo_path,
             &self.path_converter,
             &self.workspace_id,
             self.revset_parse_context(),
@@ -973,6 +1117,30 @@ impl SnapshotWorkingCopyError {
     }
 }
 
+fn protected_path_error(message: String) -> CommandError {
+    user_error_with_hint(
+        message,
+        "Pass `--allow-protected-paths` or configure the protected paths via \
+         `snapshot.protected-paths`.",
+    )
+}
+
+/// Returns the paths matching `matcher` which the commit modifies compared to
+/// its parents.
+fn modified_paths(
+    repo: &dyn Repo,
+    commit: &Commit,
+    matcher: &dyn Matcher,
+) -> BackendResult<Vec<RepoPathBuf>> {
+    let parent_tree = commit.parent_tree(repo)?;
+    let tree = commit.tree()?;
+    Ok(parent_tree
+        .diff_stream(&tree, matcher)
+        .map(|TreeDiffEntry { path, .. }| path)
+        .collect()
+        .block_on())
+}
+
 fn snapshot_command_error<E>(err: E) -> SnapshotWorkingCopyError
 where
     E: Into<CommandError>,
@@ -1067,6 +1235,20 @@ impl WorkspaceCommandHelper {
             .map_err(|err| err.into_command_error())
     }
 
+    /// Reloads the repo at the latest operation, so long-running commands can
+    /// see changes made by other processes without loading the workspace
+    /// again. The working copy isn't snapshotted.
+    #[instrument(skip_all)]
+    pub fn reload_repo_at_head(&mut self, ui: &Ui) -> Result<(), CommandError> {
+        let repo_loader = self.workspace.repo_loader();
+        let op_head = self.env.command.resolve_operation(ui, repo_loader)?;
+        if op_head.id() != self.repo().op_id() {
+            let repo = repo_loader.load_at(&op_head)?;
+            self.user_repo = ReadonlyUserRepo::new(repo);
+        }
+        Ok(())
+    }
+
     /// Imports new HEAD from the colocated Git repo.
     ///
     /// If the Git HEAD has changed, this function checks out the new Git HEAD.
@@ -1284,21 +1466,7 @@ to the current parents may contain changes from multiple commits.
         ui: &Ui,
         values: &[String],
     ) -> Result<FilesetExpression, CommandError> {
-        // TODO: This function might be superseded by parse_union_filesets(),
-        // but it would be weird if parse_union_*() had a special case for the
-        // empty arguments.
-        if values.is_empty() {
-            Ok(FilesetExpression::all())
-        } else if self.settings().get_bool("ui.allow-filesets")? {
-            self.parse_union_filesets(ui, values)
-        } else {
-            let expressions = values
-                .iter()
-                .map(|v| self.parse_file_path(v))
-                .map_ok(FilesetExpression::prefix_path)
-                .try_collect()?;
-            Ok(FilesetExpression::union_all(expressions))
-        }
+        self.env.parse_file_patterns(ui, values)
     }
 
     /// Parses the given fileset expressions and concatenates them all.
@@ -1307,13 +1475,7 @@ to the current parents may contain changes from multiple commits.
         ui: &Ui,
         file_args: &[String], // TODO: introduce FileArg newtype?
     ) -> Result<FilesetExpression, CommandError> {
-        let mut diagnostics = FilesetDiagnostics::new();
-        let expressions: Vec<_> = file_args
-            .iter()
-            .map(|arg| fileset::parse_maybe_bare(&mut diagnostics, arg, self.path_converter()))
-            .try_collect()?;
-        print_parse_diagnostics(ui, "In fileset expression", &diagnostics)?;
-        Ok(FilesetExpression::union_all(expressions))
+        self.env.parse_union_filesets(ui, file_args)
     }
 
     pub fn auto_tracking_matcher(&self, ui: &Ui) -> Result<Box<dyn Matcher>, CommandError> {
@@ -1331,6 +1493,84 @@ to the current parents may contain changes from multiple commits.
         Ok(expression.to_matcher())
     }
 
+    /// Returns matcher of the paths which commits must not modify, or `None`
+    /// if no paths are protected.
+    fn protected_paths_matcher(&self, ui: &Ui) -> Result<Option<Box<dyn Matcher>>, CommandError> {
+        if self.env.command.global_args().allow_protected_paths {
+            return Ok(None);
+        }
+        let mut diagnostics = FilesetDiagnostics::new();
+        let pattern = self.settings().get_string("snapshot.protected-paths")?;
+        let expression = fileset::parse(
+            &mut diagnostics,
+            &pattern,
+            &RepoPathUiConverter::Fs {
+                cwd: "".into(),
+                base: "".into(),
+            },
+        )?;
+        print_parse_diagnostics(ui, "In `snapshot.protected-paths`", &diagnostics)?;
+        if matches!(expression, FilesetExpression::None) {
+            return Ok(None);
+        }
+        Ok(Some(expression.to_matcher()))
+    }
+
+    /// Fails if a mutable commit rewritten in the transaction modifies
+    /// protected paths which the previous versions of the same change didn't
+    /// modify.
+    ///
+    /// Commits without predecessors, such as the ones fetched or imported from
+    /// Git, weren't rewritten by the command, so they aren't checked.
+    fn check_protected_paths(&self, ui: &Ui, repo: &MutableRepo) -> Result<(), CommandError> {
+        let Some(matcher) = self.protected_paths_matcher(ui)? else {
+            return Ok(());
+        };
+        let old_heads = repo
+            .base_repo()
+            .view()
+            .heads()
+            .iter()
+            .cloned()
+            .collect_vec();
+        let new_heads = repo.view().heads().iter().cloned().collect_vec();
+        let rewritten_commits: Vec<Commit> = revset::walk_revs(repo, &new_heads, &old_heads)?
+            .iter()
+            .commits(repo.store())
+            .filter_ok(|commit| !commit.predecessor_ids().is_empty())
+            .try_collect()?;
+        if rewritten_commits.is_empty() {
+            return Ok(());
+        }
+        let immutable_ids = self
+            .env
+            .find_immutable_commits(repo, rewritten_commits.iter().ids())?;
+        for commit in rewritten_commits {
+            if immutable_ids.contains(commit.id()) {
+                continue;
+            }
+            let mut allowed_paths = HashSet::new();
+            for predecessor in commit.predecessors() {
+                let predecessor = predecessor?;
+                // Changes squashed from other commits aren't allowed.
+                if predecessor.change_id() == commit.change_id() {
+                    allowed_paths.extend(modified_paths(repo, &predecessor, matcher.as_ref())?);
+                }
+            }
+            if let Some(path) = modified_paths(repo, &commit, matcher.as_ref())?
+                .into_iter()
+                .find(|path| !allowed_paths.contains(path))
+            {
+                return Err(protected_path_error(format!(
+                    "Change {} would modify protected path {}",
+                    short_change_hash(commit.change_id()),
+                    self.format_file_path(&path)
+                )));
+            }
+        }
+        Ok(())
+    }
+
     pub fn snapshot_options_with_start_tracking_matcher<'a>(
         &self,
         start_tracking_matcher: &'a dyn Matcher,
@@ -1514,7 +1754,7 @@ to the current parents may contain changes from multiple commits.
     }
 
     pub fn resolve_single_op(&self, op_str: &str) -> Result<Operation, OpsetEvaluationError> {
-        op_walk::resolve_op_with_repo(self.repo(), op_str)
+        op_walk::resolve_op_with_repo(self.repo(), op_str, &date_pattern_context(self.settings()))
     }
 
     /// Resolve a revset to a single revision. Return an error if the revset is
@@ -1534,6 +1774,33 @@ to the current parents may contain changes from multiple commits.
         )
     }
 
+    /// Resolve a revset to a single revision in the repo as of the given
+    /// operation. Return an error if the revset is empty or has multiple
+    /// revisions.
+    pub fn resolve_single_rev_at_op(
+        &self,
+        ui: &Ui,
+        revision_arg: &RevisionArg,
+        op: &Operation,
+    ) -> Result<Commit, CommandError> {
+        let repo = self.workspace().repo_loader().load_at(op)?;
+        let expression = self.parse_revset(ui, revision_arg)?.expression().clone();
+        let id_prefix_context = self.env.new_id_prefix_context();
+        let expression = RevsetExpressionEvaluator::new(
+            repo.as_ref(),
+            self.env.command.revset_extensions().clone(),
+            &id_prefix_context,
+            expression,
+        );
+        let should_hint_about_all_prefix = false;
+        revset_util::evaluate_revset_to_single_commit(
+            revision_arg.as_ref(),
+            &expression,
+            || self.commit_summary_template(),
+            should_hint_about_all_prefix,
+        )
+    }
+
     /// Evaluates revset expressions to non-empty set of commits. The returned
     /// set preserves the order of the input expressions.
     ///
@@ -1832,6 +2099,11 @@ to the current parents may contain changes from multiple commits.
         let options = self
             .snapshot_options_with_start_tracking_matcher(&auto_tracking_matcher)
             .map_err(snapshot_command_error)?;
+        let protected_paths_matcher = self
+            .protected_paths_matcher(ui)
+            .map_err(snapshot_command_error)?;
+        let secret_scanner =
+            SecretScanner::from_settings(self.settings()).map_err(snapshot_command_error)?;
 
         // Compare working-copy tree and operation with repo's, and reload as needed.
         let mut locked_ws = self
@@ -1891,7 +2163,7 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
                 Err(e) => return Err(snapshot_command_error(e)),
             };
         self.user_repo = ReadonlyUserRepo::new(repo);
-        let (new_tree_id, stats) = {
+        let (mut new_tree_id, stats) = {
             let mut options = options;
             let progress = crate::progress::snapshot_progress(ui);
             options.progress = progress.as_ref().map(|x| x as _);
@@ -1900,7 +2172,58 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
                 .snapshot(&options)
                 .map_err(snapshot_command_error)?
         };
-        if new_tree_id != *wc_commit.tree_id() {
+        let mut rejected_paths = vec![];
+        if let Some(matcher) = &protected_paths_matcher {
+            if new_tree_id != *wc_commit.tree_id() {
+                let repo = self.user_repo.repo.as_ref();
+                let old_tree = wc_commit.tree().map_err(snapshot_command_error)?;
+                let new_tree = repo
+                    .store()
+                    .get_root_tree(&new_tree_id)
+                    .map_err(snapshot_command_error)?;
+                // As in `check_protected_paths()`, the paths which the
+                // working-copy commit already modified may be changed further.
+                let allowed_paths: HashSet<_> = modified_paths(repo, &wc_commit, matcher.as_ref())
+                    .map_err(snapshot_command_error)?
+                    .into_iter()
+                    .collect();
+                rejected_paths = old_tree
+                    .diff_stream(&new_tree, matcher.as_ref())
+                    .map(|TreeDiffEntry { path, .. }| path)
+                    .collect::<Vec<_>>()
+                    .block_on();
+                rejected_paths.retain(|path| !allowed_paths.contains(path));
+                if !rejected_paths.is_empty() {
+                    // Leave the changes in the working copy, but not in the
+                    // snapshot.
+                    let mut tree_builder = MergedTreeBuilder::new(new_tree_id);
+                    for path in &rejected_paths {
+                        let value = old_tree.path_value(path).map_err(snapshot_command_error)?;
+                        tree_builder.set_or_remove(path.clone(), value);
+                    }
+                    new_tree_id = tree_builder
+                        .write_tree(repo.store())
+                        .map_err(snapshot_command_error)?;
+                }
+            }
+        }
+        let has_new_snapshot = new_tree_id != *wc_commit.tree_id();
+        if has_new_snapshot {
+            let old_tree = wc_commit.tree().map_err(snapshot_command_error)?;
+            let new_tree = self
+                .user_repo
+                .repo
+                .store()
+                .get_root_tree(&new_tree_id)
+                .map_err(snapshot_command_error)?;
+            if let Some(scanner) = &secret_scanner {
+                let findings = scanner
+                    .scan_trees(self.workspace_root(), &old_tree, &new_tree)
+                    .map_err(snapshot_command_error)?;
+                scanner
+                    .report_findings(ui, self.env.path_converter(), &findings)
+                    .map_err(snapshot_command_error)?;
+            }
             let mut tx =
                 start_repo_transaction(&self.user_repo.repo, self.env.command.string_args());
             tx.set_is_snapshot(true);
@@ -1911,7 +2234,7 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
                 .write()
                 .map_err(snapshot_command_error)?;
             mut_repo
-                .set_wc_commit(workspace_id, commit.id().clone())
+                .set_wc_commit(workspace_id.clone(), commit.id().clone())
                 .map_err(snapshot_command_error)?;
 
             // Rebase descendants
@@ -1938,11 +2261,37 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
                 .map_err(snapshot_command_error)?;
             self.user_repo = ReadonlyUserRepo::new(repo);
         }
+        if !rejected_paths.is_empty() {
+            // Reset the rejected paths so they're snapshotted again next time.
+            if let Some(commit) = get_wc_commit(&self.user_repo.repo)? {
+                locked_ws
+                    .locked_wc()
+                    .reset(&commit)
+                    .map_err(snapshot_command_error)?;
+            }
+        }
         locked_ws
             .finish(self.user_repo.repo.op_id().clone())
             .map_err(snapshot_command_error)?;
         print_snapshot_stats(ui, &stats, &self.env.path_converter)
             .map_err(snapshot_command_error)?;
+        print_rejected_protected_paths(ui, &rejected_paths, &self.env.path_converter)
+            .map_err(snapshot_command_error)?;
+        if has_new_snapshot {
+            let repo = &self.user_repo.repo;
+            let wc_commit_id = repo.view().get_wc_commit_id(&workspace_id);
+            run_post_hooks(
+                ui,
+                self.settings(),
+                self.workspace_root(),
+                HookEvent::PostSnapshot,
+                serde_json::json!({
+                    "operation": operation_json(repo.operation()),
+                    "working_copy_commit": wc_commit_id.map(|id| id.hex()),
+                }),
+            )
+            .map_err(snapshot_command_error)?;
+        }
         Ok(stats)
     }
 
@@ -1984,7 +2333,7 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
                 let conflicts = new_commit.tree()?.conflicts().collect_vec();
                 if !conflicts.is_empty() {
                     writeln!(formatter, "There are unresolved conflicts at these paths:")?;
-                    print_conflicted_paths(conflicts, formatter.as_mut(), self)?;
+                    print_conflicted_paths(conflicts, formatter.as_mut(), self, false)?;
                 }
             }
         }
@@ -2001,6 +2350,31 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
         }
     }
 
+    /// Runs the `pre-rewrite` hooks if the transaction rewrites or abandons
+    /// any commits, i.e. if any commits are no longer visible after it.
+    fn run_pre_rewrite_hooks(&self, tx: &Transaction) -> Result<(), CommandError> {
+        if !has_hooks(self.settings(), HookEvent::PreRewrite)? {
+            return Ok(());
+        }
+        let old_heads = tx.base_repo().view().heads().iter().cloned().collect_vec();
+        let new_heads = tx.repo().view().heads().iter().cloned().collect_vec();
+        let rewritten_ids: Vec<CommitId> = revset::walk_revs(tx.repo(), &old_heads, &new_heads)?
+            .iter()
+            .try_collect()?;
+        if rewritten_ids.is_empty() {
+            return Ok(());
+        }
+        run_pre_hooks(
+            self.settings(),
+            self.workspace_root(),
+            HookEvent::PreRewrite,
+            serde_json::json!({
+                "operation": operation_json(tx.base_repo().operation()),
+                "commits": rewritten_ids.iter().map(|id| id.hex()).collect_vec(),
+            }),
+        )
+    }
+
     fn finish_transaction(
         &mut self,
         ui: &Ui,
@@ -2015,6 +2389,8 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
         if num_rebased > 0 {
             writeln!(ui.status(), "Rebased {num_rebased} descendant commits")?;
         }
+        self.check_protected_paths(ui, tx.repo())?;
+        self.run_pre_rewrite_hooks(&tx)?;
 
         for (workspace_id, wc_commit_id) in tx.repo().view().wc_commit_ids().clone().iter().sorted()
         //sorting otherwise non deterministic order (bad for tests)
@@ -2073,6 +2449,19 @@ See https://jj-vcs.github.io/jj/latest/working-copy/#stale-working-copy \
         }
 
         self.report_repo_changes(ui, &old_repo)?;
+        run_post_hooks(
+            ui,
+            self.settings(),
+            self.workspace_root(),
+            HookEvent::PostOperation,
+            serde_json::json!({ "operation": operation_json(self.repo().operation()) }),
+        )?;
+        maybe_start_auto_maintenance(
+            self.settings(),
+            self.workspace_root(),
+            self.repo_path(),
+            self.repo(),
+        );
 
         let settings = self.settings();
         let missing_user_name = settings.user_name().is_empty();
@@ -2344,6 +2733,11 @@ impl WorkspaceCommandTransaction<'_> {
         self.tx.repo_mut()
     }
 
+    /// Adds a tag to the metadata of the operation to be committed.
+    pub fn set_tag(&mut self, key: String, value: String) {
+        self.tx.set_tag(key, value);
+    }
+
     pub fn check_out(&mut self, commit: &Commit) -> Result<Commit, CheckOutCommitError> {
         let workspace_id = self.helper.workspace_id().to_owned();
         self.id_prefix_context.take(); // invalidate
@@ -2476,6 +2870,7 @@ jj git init --colocate",
             err @ (StoreLoadError::ReadError { .. } | StoreLoadError::Backend(_)),
         ) => internal_error_with_message("The repository appears broken or inaccessible", err),
         WorkspaceLoadError::StoreLoadError(StoreLoadError::Signing(err)) => user_error(err),
+        WorkspaceLoadError::StoreLoadError(StoreLoadError::Config(err)) => err.into(),
         WorkspaceLoadError::WorkingCopyState(err) => internal_error(err),
         WorkspaceLoadError::NonUnicodePath | WorkspaceLoadError::Path(_) => user_error(err),
     }
@@ -2537,11 +2932,17 @@ fn update_stale_working_copy(
     Ok(stats)
 }
 
+/// Prints the conflicted paths with a summary of each conflict.
+///
+/// If `detect_binary` is true, the file contents are read to point out
+/// conflicts in binary files. This is slow for many conflicts, so it should
+/// only be enabled when listing conflicts is the purpose of the command.
 #[instrument(skip_all)]
 pub fn print_conflicted_paths(
     conflicts: Vec<(RepoPathBuf, BackendResult<MergedTreeValue>)>,
     formatter: &mut dyn Formatter,
     workspace_command: &WorkspaceCommandHelper,
+    detect_binary: bool,
 ) -> Result<(), CommandError> {
     let formatted_paths = conflicts
         .iter()
@@ -2552,7 +2953,8 @@ pub fn print_conflicted_paths(
         .into_iter()
         .map(|p| format!("{:width$}", p, width = max_path_len.min(32) + 3));
 
-    for ((_, conflict), formatted_path) in std::iter::zip(conflicts, formatted_paths) {
+    let store = workspace_command.repo().store();
+    for ((path, conflict), formatted_path) in std::iter::zip(conflicts, formatted_paths) {
         // TODO: Display the error for the path instead of failing the whole command if
         // `conflict` is an error?
         let conflict = conflict?.simplify();
@@ -2592,6 +2994,11 @@ pub fn print_conflicted_paths(
                 "difficult",
             );
         }
+        // Binary files can't be merged line by line, but their sides can be kept
+        // as separate files by `jj resolve --keep-both`.
+        if detect_binary && is_binary_file_conflict(store, &path, &conflict).block_on()? {
+            seen_objects.insert("binary content".to_string(), "difficult");
+        }
 
         write!(formatter, "{formatted_path} ")?;
         formatter.with_label("conflict_description", |formatter| {
@@ -2631,6 +3038,31 @@ pub fn print_conflicted_paths(
     Ok(())
 }
 
+fn print_rejected_protected_paths(
+    ui: &Ui,
+    paths: &[RepoPathBuf],
+    path_converter: &RepoPathUiConverter,
+) -> io::Result<()> {
+    if paths.is_empty() {
+        return Ok(());
+    }
+    writeln!(
+        ui.warning_default(),
+        "Refused to snapshot changes to protected paths:"
+    )?;
+    let mut formatter = ui.stderr_formatter();
+    for path in paths {
+        writeln!(formatter, "  {}", path_converter.format_file_path(path))?;
+    }
+    drop(formatter);
+    writeln!(
+        ui.hint_default(),
+        "The changes are left in the working copy. Pass `--allow-protected-paths` to snapshot \
+         them, or configure the protected paths via `snapshot.protected-paths`."
+    )?;
+    Ok(())
+}
+
 pub fn print_snapshot_stats(
     ui: &Ui,
     stats: &SnapshotStats,
@@ -3061,6 +3493,13 @@ pub struct GlobalArgs {
     /// `immutable_heads()` revset or the `immutable` template keyword.
     #[arg(long, global = true)]
     pub ignore_immutable: bool,
+    /// Allow commits to modify protected paths
+    ///
+    /// By default, Jujutsu refuses to snapshot or rewrite commits so that
+    /// they modify the paths configured in `snapshot.protected-paths`. This
+    /// option disables that check.
+    #[arg(long, global = true)]
+    pub allow_protected_paths: bool,
     /// Operation to load the repo at
     ///
     /// Operation to load the repo at. By default, Jujutsu loads the repo at the
@@ -3755,6 +4194,7 @@ impl CliRunner {
             commit_template_extensions: self.commit_template_extensions,
             operation_template_extensions: self.operation_template_extensions,
             maybe_workspace_loader,
+            workspace_loader_factory: self.workspace_loader_factory,
             store_factories: self.store_factories,
             working_copy_factories: self.working_copy_factories,
         };
@@ -23,9 +23,11 @@ use std::sync::Arc;
 use itertools::Itertools as _;
 use jj_lib::absorb::AbsorbError;
 use jj_lib::backend::BackendError;
+use jj_lib::bisect::BisectError;
 use jj_lib::config::ConfigFileSaveError;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigLoadError;
+use jj_lib::config::ConfigSchemaError;
 use jj_lib::dsl_util::Diagnostics;
 use jj_lib::fileset::FilePatternParseError;
 use jj_lib::fileset::FilesetParseError;
@@ -36,6 +38,8 @@ use jj_lib::op_heads_store::OpHeadsStoreError;
 use jj_lib::op_store::OpStoreError;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::op_walk::OpsetResolutionError;
+use jj_lib::opset::OpsetParseError;
+use jj_lib::opset::OpsetParseErrorKind;
 use jj_lib::repo::CheckOutCommitError;
 use jj_lib::repo::EditCommitError;
 use jj_lib::repo::RepoLoaderError;
@@ -281,6 +285,29 @@ impl From<ConfigLoadError> for CommandError {
     }
 }
 
+impl From<ConfigSchemaError> for CommandError {
+    fn from(err: ConfigSchemaError) -> Self {
+        let hint = match &err {
+            ConfigSchemaError::UnknownName { similar, .. } => format_similarity_hint(similar),
+            ConfigSchemaError::InvalidValue { .. } => None,
+        };
+        let mut cmd_err = user_error(err);
+        cmd_err.extend_hints(hint);
+        cmd_err
+    }
+}
+
+impl From<BisectError> for CommandError {
+    fn from(err: BisectError) -> Self {
+        match err {
+            BisectError::NoBadCommit => user_error_with_hint(
+                err,
+                "Use `jj bisect bad` to mark a commit that has the bug.",
+            ),
+        }
+    }
+}
+
 impl From<RewriteRootCommit> for CommandError {
     fn from(err: RewriteRootCommit) -> Self {
         internal_error_with_message("Attempted to rewrite the root commit", err)
@@ -345,6 +372,7 @@ impl From<WorkspaceInitError> for CommandError {
                 internal_error_with_message("Failed to access the repository", err)
             }
             WorkspaceInitError::SignInit(err) => user_error(err),
+            WorkspaceInitError::Config(err) => err.into(),
         }
     }
 }
@@ -362,6 +390,7 @@ impl From<OpHeadResolutionError> for CommandError {
 impl From<OpsetEvaluationError> for CommandError {
     fn from(err: OpsetEvaluationError) -> Self {
         match err {
+            OpsetEvaluationError::OpsetParse(err) => err.into(),
             OpsetEvaluationError::OpsetResolution(err) => {
                 let hint = opset_resolution_error_hint(&err);
                 let mut cmd_err = user_error(err);
@@ -375,6 +404,16 @@ impl From<OpsetEvaluationError> for CommandError {
     }
 }
 
+impl From<OpsetParseError> for CommandError {
+    fn from(err: OpsetParseError) -> Self {
+        let hint = opset_parse_error_hint(&err);
+        let mut cmd_err =
+            user_error_with_message(format!("Failed to parse opset: {}", err.kind()), err);
+        cmd_err.extend_hints(hint);
+        cmd_err
+    }
+}
+
 impl From<SnapshotError> for CommandError {
     fn from(err: SnapshotError) -> Self {
         internal_error_with_message("Failed to snapshot the working copy", err)
@@ -688,6 +727,21 @@ fn fileset_parse_error_hint(err: &FilesetParseError) -> Option<String> {
     }
 }
 
+fn opset_parse_error_hint(err: &OpsetParseError) -> Option<String> {
+    match err.kind() {
+        OpsetParseErrorKind::SyntaxError => Some(String::from(
+            "See https://jj-vcs.github.io/jj/latest/operation-log/ for opset syntax.",
+        )),
+        OpsetParseErrorKind::NoSuchFunction {
+            name: _,
+            candidates,
+        } => format_similarity_hint(candidates),
+        OpsetParseErrorKind::InvalidArguments { .. } | OpsetParseErrorKind::Expression(_) => {
+            find_source_parse_error_hint(&err)
+        }
+    }
+}
+
 fn opset_resolution_error_hint(err: &OpsetResolutionError) -> Option<String> {
     match err {
         OpsetResolutionError::MultipleOperations {
@@ -0,0 +1,812 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::error::Error as _;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::iter;
+use std::path::Path;
+use std::path::PathBuf;
+use std::slice;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use futures::StreamExt as _;
+use itertools::Itertools as _;
+use jj_lib::backend::Signature;
+use jj_lib::commit::Commit;
+use jj_lib::commit::CommitIteratorExt as _;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::TreeDiffEntry;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_heads_store::OpHeadsWatcher;
+use jj_lib::op_walk;
+use jj_lib::operation::Operation;
+use jj_lib::opset;
+use jj_lib::repo::Repo;
+use jj_lib::rewrite::RebaseOptions;
+use jj_lib::rewrite::SquashResult;
+use pollster::FutureExt as _;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use serde_json::Value;
+
+use super::describe::describe_commits;
+use super::new::write_new_commit;
+use super::rebase::check_rebase_destinations;
+use super::rebase::rebase_descendants_transaction;
+use super::squash::parent_destination;
+use super::squash::squash_into_destination;
+use super::squash::SquashedDescription;
+use crate::cli_util::date_pattern_context;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::DiffSelector;
+use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::description_util::add_commit_trailers;
+use crate::description_util::try_combine_messages;
+use crate::ui::Ui;
+
+/// Serve repository operations to editor integrations (experimental)
+///
+/// Runs a long-lived JSON-RPC 2.0 server, so integrations don't have to spawn
+/// a `jj` process per query. Requests and responses are JSON objects, one per
+/// line. By default, requests are read from stdin and responses are written to
+/// stdout. With `--socket`, the server listens on a Unix domain socket instead
+/// and serves any number of clients. Requests are processed one at a time.
+///
+/// The repo is reloaded at its latest operation before each request, so
+/// changes made by other processes are visible. The following methods are
+/// supported:
+///
+/// * `version`: The API version and the jj version.
+/// * `log`: Commits in the `revset` (default: `revsets.log`), up to `limit`.
+/// * `status`: The working-copy commit and its changed paths.
+/// * `diff`: Paths changed in the `revision` (default: `@`).
+/// * `describe`: Sets the `message` of the `revision` (default: `@`).
+/// * `new`: Creates and edits a new commit on top of the `parents` (default:
+///   `["@"]`), with an optional `message`.
+/// * `squash`: Moves changes from the `revision` (default: `@`) into its
+///   parent, or into the revision given by `into`. The `message` is required
+///   if both commits have descriptions.
+/// * `rebase`: Rebases the `revision` and its descendants onto the
+///   `destination` revisions.
+/// * `op_log`: Operations in the `ops` opset (default: all operations), up
+///   to `limit`.
+/// * `subscribe`: Sends a `repo_changed` notification to the client whenever
+///   the operation log changes.
+/// * `shutdown`: Stops the server.
+#[derive(clap::Args, Clone, Debug)]
+#[command(verbatim_doc_comment)]
+pub(crate) struct ApiArgs {
+    /// Listen on the Unix domain socket at this path
+    ///
+    /// A socket file left behind by a server which didn't exit cleanly is
+    /// replaced.
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+    socket: Option<PathBuf>,
+}
+
+/// Version of the request and response schema. Incremented on incompatible
+/// changes.
+const API_VERSION: u32 = 1;
+
+/// How often the operation heads are checked for changes by subscriptions.
+const SUBSCRIPTION_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const COMMAND_ERROR: i64 = -32000;
+
+type SharedWriter = Arc<Mutex<Box<dyn Write + Send>>>;
+
+type ClientId = usize;
+
+/// Event sent from client connections to the server.
+enum ClientEvent {
+    Connected {
+        client: ClientId,
+        writer: SharedWriter,
+    },
+    Request {
+        client: ClientId,
+        line: String,
+    },
+    Disconnected {
+        client: ClientId,
+    },
+}
+
+pub(crate) fn cmd_api(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ApiArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let mut server = Server::new(ui, workspace_command);
+    match &args.socket {
+        None => {
+            let (sender, receiver) = mpsc::channel();
+            let writer: SharedWriter = Arc::new(Mutex::new(Box::new(io::stdout())));
+            sender
+                .send(ClientEvent::Connected { client: 0, writer })
+                .unwrap();
+            thread::spawn(move || read_requests(0, io::stdin().lock(), &sender));
+            // The server stops when stdin is closed.
+            server.run(receiver, true);
+            Ok(())
+        }
+        Some(path) => serve_socket(ui, &mut server, path),
+    }
+}
+
+#[cfg(unix)]
+fn serve_socket(ui: &Ui, server: &mut Server, path: &Path) -> Result<(), CommandError> {
+    let listener = bind_socket(path).map_err(|err| {
+        user_error_with_message(format!("Failed to listen on {}", path.display()), err)
+    })?;
+    writeln!(ui.status(), "Listening on {}", path.display())?;
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for (client, stream) in (1..).zip(listener.incoming()) {
+            // Failed connections don't affect the other clients.
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let Ok(reader) = stream.try_clone() else {
+                continue;
+            };
+            let writer: SharedWriter = Arc::new(Mutex::new(Box::new(stream)));
+            if sender
+                .send(ClientEvent::Connected { client, writer })
+                .is_err()
+            {
+                break;
+            }
+            let sender = sender.clone();
+            thread::spawn(move || read_requests(client, BufReader::new(reader), &sender));
+        }
+    });
+    server.run(receiver, false);
+    std::fs::remove_file(path).ok();
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn serve_socket(_ui: &Ui, _server: &mut Server, _path: &Path) -> Result<(), CommandError> {
+    Err(user_error("--socket is only supported on Unix"))
+}
+
+/// Listens on the socket at `path`, replacing a stale socket file which no
+/// server is listening on.
+#[cfg(unix)]
+fn bind_socket(path: &Path) -> io::Result<std::os::unix::net::UnixListener> {
+    use std::os::unix::fs::FileTypeExt as _;
+    use std::os::unix::net::UnixListener;
+    use std::os::unix::net::UnixStream;
+
+    match UnixListener::bind(path) {
+        Err(err) if err.kind() == io::ErrorKind::AddrInUse => {
+            let is_socket = std::fs::symlink_metadata(path)?.file_type().is_socket();
+            if !is_socket || UnixStream::connect(path).is_ok() {
+                return Err(err);
+            }
+            std::fs::remove_file(path)?;
+            UnixListener::bind(path)
+        }
+        result => result,
+    }
+}
+
+/// Forwards the lines read from the client to the server until the client
+/// disconnects.
+fn read_requests(client: ClientId, reader: impl BufRead, events: &Sender<ClientEvent>) {
+    for line in reader.lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if events.send(ClientEvent::Request { client, line }).is_err() {
+            return;
+        }
+    }
+    events.send(ClientEvent::Disconnected { client }).ok();
+}
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    /// Absent for notifications, which don't get a response.
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug)]
+struct ApiError {
+    code: i64,
+    message: String,
+    data: Option<Value>,
+}
+
+impl ApiError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        ApiError {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl From<CommandError> for ApiError {
+    fn from(err: CommandError) -> Self {
+        let causes = iter::successors(err.error.source(), |err| err.source())
+            .map(|err| err.to_string())
+            .collect_vec();
+        ApiError {
+            code: COMMAND_ERROR,
+            message: err.error.to_string(),
+            data: Some(json!({ "causes": causes })),
+        }
+    }
+}
+
+type ApiMethod = fn(&Ui, &mut WorkspaceCommandHelper, Value) -> Result<Value, ApiError>;
+
+struct Client {
+    writer: SharedWriter,
+    subscription: Option<Subscription>,
+}
+
+/// Handles requests from all clients, one at a time. The workspace is loaded
+/// once, and the repo is reloaded at its latest operation for each request.
+struct Server<'a> {
+    ui: &'a Ui,
+    workspace_command: WorkspaceCommandHelper,
+    clients: HashMap<ClientId, Client>,
+}
+
+impl<'a> Server<'a> {
+    fn new(ui: &'a Ui, workspace_command: WorkspaceCommandHelper) -> Self {
+        Server {
+            ui,
+            workspace_command,
+            clients: HashMap::new(),
+        }
+    }
+
+    /// Processes events until a client requests a shutdown. If
+    /// `stop_on_disconnect` is set, the server also stops when a client
+    /// disconnects.
+    fn run(&mut self, events: Receiver<ClientEvent>, stop_on_disconnect: bool) {
+        for event in events {
+            match event {
+                ClientEvent::Connected { client, writer } => {
+                    let state = Client {
+                        writer,
+                        subscription: None,
+                    };
+                    self.clients.insert(client, state);
+                }
+                ClientEvent::Request { client, line } => {
+                    if self.handle_line(client, &line) {
+                        break;
+                    }
+                }
+                ClientEvent::Disconnected { client } => {
+                    self.disconnect(client);
+                    if stop_on_disconnect {
+                        break;
+                    }
+                }
+            }
+        }
+        for client in self.clients.keys().copied().collect_vec() {
+            self.disconnect(client);
+        }
+    }
+
+    fn disconnect(&mut self, client: ClientId) {
+        if let Some(Client {
+            subscription: Some(subscription),
+            ..
+        }) = self.clients.remove(&client)
+        {
+            subscription.stop();
+        }
+    }
+
+    /// Handles a request line from the `client`. Returns true if the server
+    /// should shut down.
+    fn handle_line(&mut self, client: ClientId, line: &str) -> bool {
+        if line.trim().is_empty() {
+            return false;
+        }
+        let mut shutdown = false;
+        let (id, response) = match serde_json::from_str::<Request>(line) {
+            Ok(request) => {
+                let response = match request.method.as_str() {
+                    "subscribe" => self.subscribe(client),
+                    "shutdown" => {
+                        shutdown = true;
+                        Ok(Value::Null)
+                    }
+                    method => self.handle_request(method, request.params),
+                };
+                (request.id, response)
+            }
+            Err(err) => (
+                Some(Value::Null),
+                Err(ApiError::new(PARSE_ERROR, err.to_string())),
+            ),
+        };
+        if let Some(id) = id {
+            let response = match response {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err(err) => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": err.code, "message": err.message, "data": err.data },
+                }),
+            };
+            // A failed send is noticed by the reader when the client
+            // disconnects.
+            if let Some(state) = self.clients.get(&client) {
+                send(&state.writer, &response).ok();
+            }
+        }
+        shutdown
+    }
+
+    fn handle_request(&mut self, method: &str, params: Value) -> Result<Value, ApiError> {
+        let handler: ApiMethod = match method {
+            "version" => {
+                return Ok(json!({
+                    "api_version": API_VERSION,
+                    "jj_version": env!("CARGO_PKG_VERSION"),
+                }))
+            }
+            "log" => |ui, workspace_command, params| {
+                Ok(api_log(ui, workspace_command, parse_params(params)?)?)
+            },
+            "status" => |ui, workspace_command, _params| Ok(api_status(ui, workspace_command)?),
+            "diff" => |ui, workspace_command, params| {
+                Ok(api_diff(ui, workspace_command, parse_params(params)?)?)
+            },
+            "describe" => |ui, workspace_command, params| {
+                Ok(api_describe(ui, workspace_command, parse_params(params)?)?)
+            },
+            "new" => |ui, workspace_command, params| {
+                Ok(api_new(ui, workspace_command, parse_params(params)?)?)
+            },
+            "squash" => |ui, workspace_command, params| {
+                Ok(api_squash(ui, workspace_command, parse_params(params)?)?)
+            },
+            "rebase" => |ui, workspace_command, params| {
+                Ok(api_rebase(ui, workspace_command, parse_params(params)?)?)
+            },
+            "op_log" => |ui, workspace_command, params| {
+                Ok(api_op_log(ui, workspace_command, parse_params(params)?)?)
+            },
+            _ => {
+                return Err(ApiError::new(
+                    METHOD_NOT_FOUND,
+                    format!("Unknown method: {method}"),
+                ))
+            }
+        };
+        self.workspace_command.reload_repo_at_head(self.ui)?;
+        self.workspace_command.maybe_snapshot(self.ui)?;
+        handler(self.ui, &mut self.workspace_command, params)
+    }
+
+    fn subscribe(&mut self, client: ClientId) -> Result<Value, ApiError> {
+        let Some(state) = self.clients.get_mut(&client) else {
+            return Ok(Value::Null);
+        };
+        if state.subscription.is_none() {
+            let repo_loader = self.workspace_command.workspace().repo_loader();
+            let watcher = OpHeadsWatcher::new(
+                repo_loader.op_heads_store().clone(),
+                SUBSCRIPTION_POLL_INTERVAL,
+            )
+            .map_err(CommandError::from)?;
+            state.subscription = Some(Subscription::start(watcher, state.writer.clone()));
+        }
+        Ok(json!({ "subscribed": true }))
+    }
+}
+
+fn send(writer: &SharedWriter, message: &Value) -> io::Result<()> {
+    let mut writer = writer.lock().unwrap();
+    writeln!(writer, "{message}")?;
+    writer.flush()
+}
+
+/// Watches the operation heads and notifies the client of changes.
+struct Subscription {
+    stopped: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+impl Subscription {
+    fn start(mut watcher: OpHeadsWatcher, writer: SharedWriter) -> Self {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let handle = thread::spawn({
+            let stopped = stopped.clone();
+            move || {
+                while !stopped.load(Ordering::Relaxed) {
+                    thread::sleep(SUBSCRIPTION_POLL_INTERVAL);
+                    // Read errors are likely transient, so try again later.
+                    let Ok(Some(op_heads)) = watcher.poll() else {
+                        continue;
+                    };
+                    let notification = json!({
+                        "jsonrpc": "2.0",
+                        "method": "repo_changed",
+                        "params": {
+                            "op_heads": op_heads.iter().map(|id| id.hex()).collect_vec(),
+                        },
+                    });
+                    if send(&writer, &notification).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Subscription { stopped, handle }
+    }
+
+    fn stop(self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        self.handle.join().unwrap();
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, ApiError> {
+    // Methods without parameters may be called with "params" omitted.
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|err| ApiError::new(INVALID_PARAMS, err.to_string()))
+}
+
+fn default_revision() -> String {
+    "@".to_owned()
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LogParams {
+    revset: Option<String>,
+    limit: Option<usize>,
+}
+
+fn api_log(
+    ui: &Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    params: LogParams,
+) -> Result<Value, CommandError> {
+    let revset = match params.revset {
+        Some(revset) => revset,
+        None => workspace_command.settings().get_string("revsets.log")?,
+    };
+    let commits: Vec<Commit> = workspace_command
+        .parse_revset(ui, &RevisionArg::from(revset))?
+        .evaluate_to_commits()?
+        .take(params.limit.unwrap_or(usize::MAX))
+        .try_collect()?;
+    let repo = workspace_command.repo().as_ref();
+    let commits: Vec<Value> = commits
+        .iter()
+        .map(|commit| commit_json(repo, commit))
+        .try_collect()?;
+    Ok(Value::from(commits))
+}
+
+fn api_status(
+    _ui: &Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+) -> Result<Value, CommandError> {
+    let repo = workspace_command.repo().as_ref();
+    let Some(wc_commit_id) = workspace_command.get_wc_commit_id() else {
+        return Ok(json!({ "working_copy": null, "changes": [] }));
+    };
+    let wc_commit = repo.store().get_commit(wc_commit_id)?;
+    let changes = diff_json(&wc_commit.parent_tree(repo)?, &wc_commit.tree()?)?;
+    Ok(json!({
+        "working_copy": commit_json(repo, &wc_commit)?,
+        "changes": changes,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DiffParams {
+    #[serde(default = "default_revision")]
+    revision: String,
+}
+
+fn api_diff(
+    ui: &Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    params: DiffParams,
+) -> Result<Value, CommandError> {
+    let commit = workspace_command.resolve_single_rev(ui, &RevisionArg::from(params.revision))?;
+    let repo = workspace_command.repo().as_ref();
+    let changes = diff_json(&commit.parent_tree(repo)?, &commit.tree()?)?;
+    Ok(Value::from(changes))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DescribeParams {
+    #[serde(default = "default_revision")]
+    revision: String,
+    message: String,
+}
+
+fn api_describe(
+    ui: &Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    params: DescribeParams,
+) -> Result<Value, CommandError> {
+    let commit = workspace_command.resolve_single_rev(ui, &RevisionArg::from(params.revision))?;
+    workspace_command.check_rewritable([commit.id()])?;
+    let mut tx = workspace_command.start_transaction();
+    let description = add_commit_trailers(ui, &tx, &commit, &params.message, false)?;
+    let mut commit_descriptions = HashMap::new();
+    if description != commit.description() {
+        commit_descriptions.insert(commit.id(), description);
+    }
+    let new_commit = describe_commits(ui, &mut tx, &commit_descriptions, false, None)?
+        .pop()
+        .unwrap_or_else(|| commit.clone());
+    let result = commit_json(tx.repo(), &new_commit)?;
+    tx.finish(ui, format!("describe commit {}", commit.id().hex()))?;
+    Ok(result)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewParams {
+    #[serde(default = "default_new_parents")]
+    parents: Vec<String>,
+    #[serde(default)]
+    message: String,
+}
+
+fn default_new_parents() -> Vec<String> {
+    vec![default_revision()]
+}
+
+fn api_new(
+    ui: &Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    params: NewParams,
+) -> Result<Value, CommandError> {
+    let parent_args = params
+        .parents
+        .into_iter()
+        .map(RevisionArg::from)
+        .collect_vec();
+    let parent_commits = workspace_command
+        .resolve_some_revsets_default_single(ui, &parent_args)?
+        .into_iter()
+        .collect_vec();
+    let mut tx = workspace_command.start_transaction();
+    let (new_commit, _) = write_new_commit(&mut tx, &parent_commits, vec![], params.message)?;
+    tx.edit(&new_commit)?;
+    let result = commit_json(tx.repo(), &new_commit)?;
+    tx.finish(ui, "new empty commit")?;
+    Ok(result)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SquashParams {
+    #[serde(default = "default_revision")]
+    revision: String,
+    into: Option<String>,
+    message: Option<String>,
+}
+
+fn api_squash(
+    ui: &Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    params: SquashParams,
+) -> Result<Value, CommandError> {
+    let source = workspace_command.resolve_single_rev(ui, &RevisionArg::from(params.revision))?;
+    let destination = match params.into {
+        Some(into) => workspace_command.resolve_single_rev(ui, &RevisionArg::from(into))?,
+        None => parent_destination(&source)?,
+    };
+    if source.id() == destination.id() {
+        return Err(user_error("Source and destination cannot be the same"));
+    }
+    // There's no editor to combine the descriptions with.
+    let description = match params.message {
+        Some(message) => message,
+        None => try_combine_messages(&[&source], &destination).ok_or_else(|| {
+            user_error("Both commits have descriptions, so a message must be specified")
+        })?,
+    };
+    workspace_command.check_rewritable([source.id(), destination.id()])?;
+    let text_editor = workspace_command.text_editor()?;
+    let mut tx = workspace_command.start_transaction();
+    let squash_result = squash_into_destination(
+        &mut tx,
+        slice::from_ref(&source),
+        &destination,
+        &EverythingMatcher,
+        &DiffSelector::NonInteractive,
+        SquashedDescription::Exact(description),
+        &text_editor,
+        false,
+    )?;
+    let result = match &squash_result {
+        SquashResult::NoChanges => Value::Null,
+        SquashResult::NewCommit(commit) => commit_json(tx.repo(), commit)?,
+    };
+    tx.finish(
+        ui,
+        format!("squash commits into {}", destination.id().hex()),
+    )?;
+    Ok(result)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RebaseParams {
+    revision: String,
+    destination: Vec<String>,
+}
+
+fn api_rebase(
+    ui: &Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    params: RebaseParams,
+) -> Result<Value, CommandError> {
+    let source = workspace_command.resolve_single_rev(ui, &RevisionArg::from(params.revision))?;
+    let destination_args = params
+        .destination
+        .into_iter()
+        .map(RevisionArg::from)
+        .collect_vec();
+    let new_parents = workspace_command
+        .resolve_some_revsets_default_single(ui, &destination_args)?
+        .into_iter()
+        .collect_vec();
+    workspace_command.check_rewritable([source.id()])?;
+    check_rebase_destinations(workspace_command.repo(), &new_parents, &source)?;
+    let stats = rebase_descendants_transaction(
+        ui,
+        workspace_command,
+        &new_parents.iter().ids().cloned().collect_vec(),
+        &[],
+        vec![source],
+        &RebaseOptions::default(),
+    )?;
+    Ok(json!({
+        "rebased": stats.num_rebased_targets + stats.num_rebased_descendants,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OpLogParams {
+    ops: Option<String>,
+    limit: Option<usize>,
+}
+
+fn api_op_log(
+    _ui: &Ui,
+    workspace_command: &mut WorkspaceCommandHelper,
+    params: OpLogParams,
+) -> Result<Value, CommandError> {
+    let current_op = workspace_command.repo().operation();
+    let limit = params.limit.unwrap_or(usize::MAX);
+    let ops: Vec<Operation> = match &params.ops {
+        Some(text) => {
+            let date_context = date_pattern_context(workspace_command.settings());
+            let expression = opset::parse(text, &date_context)?;
+            let repo_loader = workspace_command.workspace().repo_loader();
+            op_walk::resolve_opset_at(
+                repo_loader.op_store(),
+                slice::from_ref(current_op),
+                &expression,
+            )?
+            .take(limit)
+            .try_collect()?
+        }
+        None => op_walk::walk_ancestors(slice::from_ref(current_op))
+            .take(limit)
+            .try_collect()?,
+    };
+    let ops = ops.iter().map(operation_json).collect_vec();
+    Ok(Value::from(ops))
+}
+
+fn commit_json(repo: &dyn Repo, commit: &Commit) -> Result<Value, CommandError> {
+    Ok(json!({
+        "commit_id": commit.id().hex(),
+        "change_id": commit.change_id().reverse_hex(),
+        "parents": commit.parent_ids().iter().map(|id| id.hex()).collect_vec(),
+        "description": commit.description(),
+        "author": signature_json(commit.author()),
+        "committer": signature_json(commit.committer()),
+        "empty": commit.is_empty(repo)?,
+        "conflict": commit.has_conflict()?,
+    }))
+}
+
+fn signature_json(signature: &Signature) -> Value {
+    json!({
+        "name": signature.name,
+        "email": signature.email,
+        "timestamp": signature.timestamp.timestamp.0,
+    })
+}
+
+fn operation_json(op: &Operation) -> Value {
+    let metadata = op.metadata();
+    json!({
+        "id": op.id().hex(),
+        "parents": op.parent_ids().iter().map(|id| id.hex()).collect_vec(),
+        "description": metadata.description,
+        "user": format!("{}@{}", metadata.username, metadata.hostname),
+        "start_time": metadata.start_time.timestamp.0,
+        "end_time": metadata.end_time.timestamp.0,
+        "snapshot": metadata.is_snapshot,
+    })
+}
+
+fn diff_json(from_tree: &MergedTree, to_tree: &MergedTree) -> Result<Vec<Value>, CommandError> {
+    async {
+        let mut changes = vec![];
+        let mut tree_diff = from_tree.diff_stream(to_tree, &EverythingMatcher);
+        while let Some(TreeDiffEntry { path, values }) = tree_diff.next().await {
+            let (before, after) = values?;
+            let status = match (before.is_present(), after.is_present()) {
+                (true, true) => "modified",
+                (false, true) => "added",
+                (true, false) => "removed",
+                (false, false) => unreachable!(),
+            };
+            changes.push(json!({
+                "path": path.as_internal_file_string(),
+                "status": status,
+                "conflict": !after.is_resolved(),
+            }));
+        }
+        Ok::<_, CommandError>(changes)
+    }
+    .block_on()
+}
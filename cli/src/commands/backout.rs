@@ -14,6 +14,7 @@
 
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
+use jj_lib::commit::Commit;
 use jj_lib::object_id::ObjectId;
 use jj_lib::rewrite::merge_commit_trees;
 use tracing::instrument;
@@ -45,6 +46,12 @@ pub(crate) struct BackoutArgs {
         add = ArgValueCandidates::new(complete::all_revisions),
     )]
     destination: Vec<RevisionArg>,
+    /// Back out all the revisions in a single commit
+    ///
+    /// The revisions are backed out in reverse topological order. If their
+    /// reversed changes overlap, the new commit will have conflicts.
+    #[arg(long)]
+    combine: bool,
 }
 
 #[instrument(skip_all)]
@@ -78,15 +85,24 @@ pub(crate) fn cmd_backout(
         )
     };
     let mut new_base_tree = merge_commit_trees(tx.repo(), &parents)?;
+    if args.combine && to_back_out.len() > 1 {
+        for commit_to_back_out in &to_back_out {
+            let old_base_tree = commit_to_back_out.parent_tree(tx.repo())?;
+            let old_tree = commit_to_back_out.tree()?;
+            new_base_tree = new_base_tree.merge(&old_tree, &old_base_tree)?;
+        }
+        let new_parent_ids = parents.iter().map(|commit| commit.id().clone()).collect();
+        tx.repo_mut()
+            .new_commit(new_parent_ids, new_base_tree.id())
+            .set_description(combined_backout_description(&to_back_out))
+            .write()?;
+        tx.finish(ui, transaction_description)?;
+        return Ok(());
+    }
     for commit_to_back_out in to_back_out {
-        let commit_to_back_out_subject = commit_to_back_out
-            .description()
-            .lines()
-            .next()
-            .unwrap_or_default();
         let new_commit_description = format!(
             "Back out \"{}\"\n\nThis backs out commit {}.\n",
-            commit_to_back_out_subject,
+            first_line(&commit_to_back_out),
             &commit_to_back_out.id().hex()
         );
         let old_base_tree = commit_to_back_out.parent_tree(tx.repo())?;
@@ -105,3 +121,24 @@ pub(crate) fn cmd_backout(
 
     Ok(())
 }
+
+fn first_line(commit: &Commit) -> &str {
+    commit.description().lines().next().unwrap_or_default()
+}
+
+/// Describes a commit backing out all of `commits`, listing their subjects
+/// followed by one provenance line per commit.
+fn combined_backout_description(commits: &[Commit]) -> String {
+    let subjects = commits
+        .iter()
+        .map(|commit| format!("* \"{}\"\n", first_line(commit)))
+        .join("");
+    let provenance = commits
+        .iter()
+        .map(|commit| format!("This reverts commit {}.\n", commit.id().hex()))
+        .join("");
+    format!(
+        "Back out {} commits\n\n{subjects}\n{provenance}",
+        commits.len()
+    )
+}
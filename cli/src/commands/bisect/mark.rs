@@ -0,0 +1,110 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::bisect::BisectEvent;
+use jj_lib::bisect::Bisector;
+use jj_lib::bisect::NextStep;
+use jj_lib::object_id::ObjectId as _;
+
+use super::load_bisect_state;
+use super::print_next_step;
+use super::record_bisect_events;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectMarkArgs {
+    /// The commits to mark (defaults to the commit suggested for testing)
+    #[arg(
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions)
+    )]
+    revisions: Vec<RevisionArg>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum MarkKind {
+    Good,
+    Bad,
+    Skip,
+}
+
+impl MarkKind {
+    fn event(self, commit_id: CommitId) -> BisectEvent {
+        match self {
+            MarkKind::Good => BisectEvent::Good(commit_id),
+            MarkKind::Bad => BisectEvent::Bad(commit_id),
+            MarkKind::Skip => BisectEvent::Skip(commit_id),
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            MarkKind::Good => "good",
+            MarkKind::Bad => "bad",
+            MarkKind::Skip => "skipped",
+        }
+    }
+}
+
+pub(crate) fn cmd_bisect_mark(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectMarkArgs,
+    kind: MarkKind,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let mut state = load_bisect_state(&workspace_command)?;
+    let commit_ids: Vec<CommitId> = if args.revisions.is_empty() {
+        match Bisector::new(workspace_command.repo().as_ref(), &state)?.next_step()? {
+            NextStep::Evaluate { commit_id, .. } => vec![commit_id],
+            NextStep::Done(_) => {
+                return Err(user_error(
+                    "The bisection is finished, so there's no commit to mark",
+                ));
+            }
+        }
+    } else {
+        workspace_command
+            .parse_union_revsets(ui, &args.revisions)?
+            .evaluate_to_commit_ids()?
+            .try_collect()?
+    };
+
+    let events = commit_ids
+        .iter()
+        .map(|id| kind.event(id.clone()))
+        .collect_vec();
+    record_bisect_events(
+        &mut workspace_command,
+        &events,
+        format!(
+            "bisect: mark {} as {}",
+            commit_ids.iter().map(|id| id.hex()).join(", "),
+            kind.describe()
+        ),
+    )?;
+    for event in &events {
+        state.apply(event);
+    }
+    print_next_step(ui, &workspace_command, &state)?;
+    Ok(())
+}
@@ -0,0 +1,169 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod mark;
+mod reset;
+mod run;
+mod start;
+
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use jj_lib::bisect::BisectEvent;
+use jj_lib::bisect::BisectState;
+use jj_lib::bisect::BisectionResult;
+use jj_lib::bisect::Bisector;
+use jj_lib::bisect::NextStep;
+use jj_lib::bisect::BISECT_TAG;
+use jj_lib::repo::Repo as _;
+use tracing::instrument;
+
+use self::mark::cmd_bisect_mark;
+use self::mark::BisectMarkArgs;
+use self::mark::MarkKind;
+use self::reset::cmd_bisect_reset;
+use self::reset::BisectResetArgs;
+use self::run::cmd_bisect_run;
+use self::run::BisectRunArgs;
+use self::start::cmd_bisect_start;
+use self::start::BisectStartArgs;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Find the first bad commit by binary search
+///
+/// Start with `jj bisect start --bad <REVSET> --good <REVSETS>`, then either
+/// test the suggested commits yourself and mark them with `jj bisect good`
+/// and `jj bisect bad`, or let `jj bisect run <COMMAND>` test them
+/// automatically. The state of the bisection is recorded in the operation
+/// log.
+#[derive(Subcommand, Clone, Debug)]
+pub(crate) enum BisectCommand {
+    /// Mark commits as bad
+    Bad(BisectMarkArgs),
+    /// Mark commits as good
+    Good(BisectMarkArgs),
+    Reset(BisectResetArgs),
+    Run(BisectRunArgs),
+    /// Mark commits as untestable
+    Skip(BisectMarkArgs),
+    Start(BisectStartArgs),
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_bisect(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &BisectCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        BisectCommand::Bad(args) => cmd_bisect_mark(ui, command, args, MarkKind::Bad),
+        BisectCommand::Good(args) => cmd_bisect_mark(ui, command, args, MarkKind::Good),
+        BisectCommand::Reset(args) => cmd_bisect_reset(ui, command, args),
+        BisectCommand::Run(args) => cmd_bisect_run(ui, command, args),
+        BisectCommand::Skip(args) => cmd_bisect_mark(ui, command, args, MarkKind::Skip),
+        BisectCommand::Start(args) => cmd_bisect_start(ui, command, args),
+    }
+}
+
+/// Name of the workspace `jj bisect run` tests commits in.
+const SCRATCH_WORKSPACE_NAME: &str = "bisect";
+
+/// Location of the workspace `jj bisect run` tests commits in. It's inside
+/// the `.jj` directory so it's never snapshotted into the main workspace.
+fn scratch_workspace_path(workspace_command: &WorkspaceCommandHelper) -> PathBuf {
+    workspace_command
+        .workspace_root()
+        .join(".jj")
+        .join(SCRATCH_WORKSPACE_NAME)
+}
+
+fn load_bisect_state(
+    workspace_command: &WorkspaceCommandHelper,
+) -> Result<BisectState, CommandError> {
+    BisectState::load(workspace_command.repo().operation())?.ok_or_else(|| {
+        user_error_with_hint(
+            "No bisection in progress",
+            "Use `jj bisect start` to start one.",
+        )
+    })
+}
+
+/// Records `events` in a new operation that doesn't otherwise change the repo.
+fn record_bisect_events(
+    workspace_command: &mut WorkspaceCommandHelper,
+    events: &[BisectEvent],
+    description: impl Into<String>,
+) -> Result<(), CommandError> {
+    let mut tx = workspace_command.start_transaction();
+    tx.set_tag(BISECT_TAG.to_owned(), BisectEvent::format_tag_value(events));
+    tx.into_inner().commit(description)?;
+    Ok(())
+}
+
+/// Prints the commit to test next, or the result of the bisection.
+fn print_next_step(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    state: &BisectState,
+) -> Result<NextStep, CommandError> {
+    let repo = workspace_command.repo();
+    let step = Bisector::new(repo.as_ref(), state)?.next_step()?;
+    let Some(mut formatter) = ui.status_formatter() else {
+        return Ok(step);
+    };
+    match &step {
+        NextStep::Evaluate {
+            commit_id,
+            remaining,
+        } => {
+            let commit = repo.store().get_commit(commit_id)?;
+            write!(formatter, "Now test commit ")?;
+            workspace_command.write_commit_summary(formatter.as_mut(), &commit)?;
+            writeln!(formatter)?;
+            writeln!(formatter, "{remaining} commits left to test")?;
+        }
+        NextStep::Done(BisectionResult::Found(commit_ids)) => {
+            if let [commit_id] = &commit_ids[..] {
+                write!(formatter, "The first bad commit is ")?;
+                let commit = repo.store().get_commit(commit_id)?;
+                workspace_command.write_commit_summary(formatter.as_mut(), &commit)?;
+                writeln!(formatter)?;
+            } else {
+                writeln!(
+                    formatter,
+                    "The first bad commit is one of these, some of which were skipped:"
+                )?;
+                for commit_id in commit_ids {
+                    let commit = repo.store().get_commit(commit_id)?;
+                    write!(formatter, "  ")?;
+                    workspace_command.write_commit_summary(formatter.as_mut(), &commit)?;
+                    writeln!(formatter)?;
+                }
+            }
+        }
+        NextStep::Done(BisectionResult::Indeterminate) => {
+            writeln!(
+                formatter,
+                "No commit can be the first bad commit. Are some good commits descendants of bad \
+                 commits?"
+            )?;
+        }
+    }
+    Ok(step)
+}
@@ -0,0 +1,60 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::io::Write as _;
+
+use jj_lib::bisect::BisectEvent;
+use jj_lib::bisect::BISECT_TAG;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::op_store::WorkspaceId;
+
+use super::load_bisect_state;
+use super::scratch_workspace_path;
+use super::SCRATCH_WORKSPACE_NAME;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// End the bisection in progress
+///
+/// Also removes the workspace left behind if `jj bisect run` was interrupted.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectResetArgs {}
+
+pub(crate) fn cmd_bisect_reset(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &BisectResetArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    load_bisect_state(&workspace_command)?;
+    let scratch_path = scratch_workspace_path(&workspace_command);
+    let workspace_id = WorkspaceId::new(SCRATCH_WORKSPACE_NAME.to_owned());
+
+    let mut tx = workspace_command.start_transaction();
+    tx.set_tag(
+        BISECT_TAG.to_owned(),
+        BisectEvent::format_tag_value(&[BisectEvent::Reset]),
+    );
+    if tx.repo().view().get_wc_commit_id(&workspace_id).is_some() {
+        tx.repo_mut().remove_wc_commit(&workspace_id)?;
+    }
+    tx.into_inner().commit("bisect: reset")?;
+    if scratch_path.exists() {
+        fs::remove_dir_all(&scratch_path).context(&scratch_path)?;
+    }
+    writeln!(ui.status(), "Ended the bisection.")?;
+    Ok(())
+}
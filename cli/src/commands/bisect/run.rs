@@ -0,0 +1,175 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use jj_lib::bisect::BisectEvent;
+use jj_lib::bisect::BisectState;
+use jj_lib::bisect::NextStep;
+use jj_lib::bisect::BISECT_TAG;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::op_store::WorkspaceId;
+use jj_lib::repo::Repo as _;
+use jj_lib::workspace::Workspace;
+
+use super::load_bisect_state;
+use super::print_next_step;
+use super::scratch_workspace_path;
+use super::SCRATCH_WORKSPACE_NAME;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Test commits automatically by running a command
+///
+/// Each commit to test is checked out in a separate workspace in
+/// `.jj/bisect`, so the working copy of the current workspace isn't touched.
+/// The command is run in the root of that workspace. Its exit status decides
+/// how the commit is marked:
+///
+/// * 0 means the commit is good.
+/// * 125 means the commit can't be tested and is skipped.
+/// * Any other status from 1 to 127 means the commit is bad.
+/// * Any other status, or being terminated by a signal, aborts the bisection
+///   run. The commits tested so far remain marked.
+#[derive(clap::Args, Clone, Debug)]
+#[command(verbatim_doc_comment)]
+pub(crate) struct BisectRunArgs {
+    /// Command to test each commit with
+    command: String,
+    /// Arguments to pass to the command
+    args: Vec<String>,
+}
+
+pub(crate) fn cmd_bisect_run(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectRunArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let mut state = load_bisect_state(&workspace_command)?;
+    let scratch_path = scratch_workspace_path(&workspace_command);
+    let workspace_id = WorkspaceId::new(SCRATCH_WORKSPACE_NAME.to_owned());
+    if workspace_command
+        .repo()
+        .view()
+        .get_wc_commit_id(&workspace_id)
+        .is_some()
+    {
+        return Err(user_error_with_hint(
+            format!("Workspace named '{SCRATCH_WORKSPACE_NAME}' already exists"),
+            "If an earlier `jj bisect run` was interrupted, run `jj workspace forget bisect` \
+             to remove its workspace.",
+        ));
+    }
+    if scratch_path.exists() {
+        fs::remove_dir_all(&scratch_path).context(&scratch_path)?;
+    }
+    fs::create_dir_all(&scratch_path).context(&scratch_path)?;
+    let (scratch_workspace, repo) = Workspace::init_workspace_with_existing_repo(
+        &scratch_path,
+        workspace_command.repo_path(),
+        workspace_command.repo(),
+        command.get_working_copy_factory()?,
+        workspace_id.clone(),
+    )?;
+    drop(workspace_command);
+    let mut scratch_command = command.for_workable_repo(ui, scratch_workspace, repo)?;
+
+    // Events not yet recorded in the operation log. They're added to the next
+    // operation, which checks out the next commit to test. The workspace is
+    // removed even if the run fails.
+    let mut pending_events = vec![];
+    let outcome = run_bisection(
+        ui,
+        &mut scratch_command,
+        &mut state,
+        &mut pending_events,
+        args,
+        &scratch_path,
+    );
+
+    let mut tx = scratch_command.start_transaction();
+    record_pending_events(&mut tx, &mut pending_events);
+    tx.repo_mut().remove_wc_commit(&workspace_id)?;
+    tx.into_inner()
+        .commit("bisect: remove bisection workspace")?;
+    drop(scratch_command);
+    fs::remove_dir_all(&scratch_path).context(&scratch_path)?;
+    outcome
+}
+
+/// Tests commits in the scratch workspace until the bisection is finished.
+fn run_bisection(
+    ui: &mut Ui,
+    scratch_command: &mut WorkspaceCommandHelper,
+    state: &mut BisectState,
+    pending_events: &mut Vec<BisectEvent>,
+    args: &BisectRunArgs,
+    scratch_path: &Path,
+) -> Result<(), CommandError> {
+    loop {
+        let step = print_next_step(ui, scratch_command, state)?;
+        let NextStep::Evaluate { commit_id, .. } = step else {
+            return Ok(());
+        };
+        let commit = scratch_command.repo().store().get_commit(&commit_id)?;
+        let mut tx = scratch_command.start_transaction();
+        record_pending_events(&mut tx, pending_events);
+        tx.check_out(&commit)?;
+        tx.finish(ui, format!("bisect: check out commit {commit_id}"))?;
+
+        let status = Command::new(&args.command)
+            .args(&args.args)
+            .current_dir(scratch_path)
+            .status()
+            .map_err(|err| {
+                user_error_with_message(
+                    format!("Failed to execute external command '{}'", &args.command),
+                    err,
+                )
+            })?;
+        let event = match status.code() {
+            Some(0) => BisectEvent::Good(commit_id),
+            Some(125) => BisectEvent::Skip(commit_id),
+            Some(1..=127) => BisectEvent::Bad(commit_id),
+            Some(exit_code) => {
+                return Err(user_error(format!(
+                    "External command exited with {exit_code}, stopping the bisection run"
+                )));
+            }
+            None => {
+                return Err(user_error(format!(
+                    "External command was terminated by: {status}, stopping the bisection run"
+                )));
+            }
+        };
+        state.apply(&event);
+        pending_events.push(event);
+    }
+}
+
+fn record_pending_events(tx: &mut WorkspaceCommandTransaction, events: &mut Vec<BisectEvent>) {
+    if !events.is_empty() {
+        tx.set_tag(BISECT_TAG.to_owned(), BisectEvent::format_tag_value(events));
+        events.clear();
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::bisect::BisectEvent;
+use jj_lib::bisect::BisectState;
+use jj_lib::object_id::ObjectId as _;
+
+use super::print_next_step;
+use super::record_bisect_events;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Start a new bisection
+///
+/// Any bisection in progress is discarded.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct BisectStartArgs {
+    /// A commit that has the bug
+    #[arg(
+        long,
+        default_value = "@",
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions)
+    )]
+    bad: RevisionArg,
+    /// Commits that don't have the bug (can be repeated)
+    ///
+    /// If no good commits are given, all ancestors of the bad commit are
+    /// searched.
+    #[arg(
+        long,
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions)
+    )]
+    good: Vec<RevisionArg>,
+}
+
+pub(crate) fn cmd_bisect_start(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BisectStartArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let bad_commit = workspace_command.resolve_single_rev(ui, &args.bad)?;
+    let good_ids: Vec<_> = workspace_command
+        .parse_union_revsets(ui, &args.good)?
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+
+    let mut events = vec![
+        BisectEvent::Start,
+        BisectEvent::Bad(bad_commit.id().clone()),
+    ];
+    events.extend(good_ids.into_iter().map(BisectEvent::Good));
+    record_bisect_events(
+        &mut workspace_command,
+        &events,
+        format!("bisect: start with bad commit {}", bad_commit.id().hex()),
+    )?;
+
+    let mut state = BisectState::default();
+    for event in &events {
+        state.apply(event);
+    }
+    print_next_step(ui, &workspace_command, &state)?;
+    Ok(())
+}
@@ -76,11 +76,17 @@ pub struct BookmarkListArgs {
     /// By default, the specified name matches exactly. Use `glob:` prefix to
     /// select bookmarks by wildcard pattern. For details, see
     /// https://jj-vcs.github.io/jj/latest/revsets/#string-patterns.
+    ///
+    /// If combined with `--revisions`, only bookmarks matching both are shown.
     #[arg(value_parser = StringPattern::parse, add = ArgValueCandidates::new(complete::bookmarks))]
     names: Option<Vec<StringPattern>>,
 
     /// Show bookmarks whose local targets are in the given revisions
     ///
+    /// For example, `-r '~::trunk()'` lists bookmarks not merged into trunk.
+    /// If name patterns are also given, only bookmarks matching both are
+    /// shown.
+    ///
     /// Note that `-r deleted_bookmark` will not work since `deleted_bookmark`
     /// wouldn't have a local target.
     #[arg(long, short, value_name = "REVSETS")]
@@ -104,36 +110,45 @@ pub fn cmd_bookmark_list(
     let repo = workspace_command.repo();
     let view = repo.view();
 
-    // Like cmd_git_push(), names and revisions are OR-ed.
-    let bookmark_names_to_list = if args.names.is_some() || args.revisions.is_some() {
-        let mut bookmark_names: HashSet<&str> = HashSet::new();
-        if let Some(patterns) = &args.names {
-            bookmark_names.extend(
-                view.bookmarks()
-                    .filter(|&(name, _)| patterns.iter().any(|pattern| pattern.matches(name)))
-                    .map(|(name, _)| name),
-            );
-        }
-        if let Some(revisions) = &args.revisions {
-            // Match against local targets only, which is consistent with "jj git push".
-            let mut expression = workspace_command.parse_union_revsets(ui, revisions)?;
-            // Intersects with the set of local bookmark targets to minimize the lookup
-            // space.
-            expression.intersect_with(&RevsetExpression::bookmarks(StringPattern::everything()));
-            let filtered_targets: HashSet<_> =
-                expression.evaluate_to_commit_ids()?.try_collect()?;
-            bookmark_names.extend(
-                view.local_bookmarks()
-                    .filter(|(_, target)| {
-                        target.added_ids().any(|id| filtered_targets.contains(id))
-                    })
-                    .map(|(name, _)| name),
-            );
-        }
-        Some(bookmark_names)
-    } else {
-        None
-    };
+    // Unlike cmd_git_push(), names and revisions are AND-ed so that the
+    // revisions can narrow down the bookmarks selected by name.
+    let mut bookmark_names_to_list: Option<HashSet<&str>> = None;
+    if let Some(patterns) = &args.names {
+        let bookmark_names = view
+            .bookmarks()
+            .filter(|&(name, _)| patterns.iter().any(|pattern| pattern.matches(name)))
+            .map(|(name, _)| name)
+            .collect();
+        bookmark_names_to_list = Some(bookmark_names);
+    }
+    if let Some(revisions) = &args.revisions {
+        // Match against local targets only, which is consistent with "jj git push".
+        let mut expression = workspace_command.parse_union_revsets(ui, revisions)?;
+        // Intersects with the set of local bookmark targets to minimize the lookup
+        // space.
+        let bookmarks_expression = match &args.names {
+            Some(patterns) => RevsetExpression::union_all(
+                &patterns
+                    .iter()
+                    .map(|pattern| RevsetExpression::bookmarks(pattern.clone()))
+                    .collect_vec(),
+            ),
+            None => RevsetExpression::bookmarks(StringPattern::everything()),
+        };
+        expression.intersect_with(&bookmarks_expression);
+        let filtered_targets: HashSet<_> = expression.evaluate_to_commit_ids()?.try_collect()?;
+        let bookmark_names = view
+            .local_bookmarks()
+            .filter(|&(name, _)| {
+                bookmark_names_to_list
+                    .as_ref()
+                    .map_or(true, |bookmark_names| bookmark_names.contains(name))
+            })
+            .filter(|(_, target)| target.added_ids().any(|id| filtered_targets.contains(id)))
+            .map(|(name, _)| name)
+            .collect();
+        bookmark_names_to_list = Some(bookmark_names);
+    }
 
     let template = {
         let language = workspace_command.commit_template_language();
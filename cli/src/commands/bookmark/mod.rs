@@ -19,6 +19,7 @@ mod list;
 mod r#move;
 mod rename;
 mod set;
+mod status;
 mod track;
 mod untrack;
 
@@ -44,6 +45,8 @@ use self::rename::cmd_bookmark_rename;
 use self::rename::BookmarkRenameArgs;
 use self::set::cmd_bookmark_set;
 use self::set::BookmarkSetArgs;
+use self::status::cmd_bookmark_status;
+use self::status::BookmarkStatusArgs;
 use self::track::cmd_bookmark_track;
 use self::track::BookmarkTrackArgs;
 use self::untrack::cmd_bookmark_untrack;
@@ -78,6 +81,7 @@ pub enum BookmarkCommand {
     Rename(BookmarkRenameArgs),
     #[command(visible_alias("s"))]
     Set(BookmarkSetArgs),
+    Status(BookmarkStatusArgs),
     #[command(visible_alias("t"))]
     Track(BookmarkTrackArgs),
     Untrack(BookmarkUntrackArgs),
@@ -96,6 +100,7 @@ pub fn cmd_bookmark(
         BookmarkCommand::Move(args) => cmd_bookmark_move(ui, command, args),
         BookmarkCommand::Rename(args) => cmd_bookmark_rename(ui, command, args),
         BookmarkCommand::Set(args) => cmd_bookmark_set(ui, command, args),
+        BookmarkCommand::Status(args) => cmd_bookmark_status(ui, command, args),
         BookmarkCommand::Track(args) => cmd_bookmark_track(ui, command, args),
         BookmarkCommand::Untrack(args) => cmd_bookmark_untrack(ui, command, args),
     }
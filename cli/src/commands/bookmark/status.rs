@@ -0,0 +1,184 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use clap_complete::ArgValueCandidates;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_store::RefTarget;
+use jj_lib::str_util::StringPattern;
+use serde::Serialize;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::CommandError;
+use crate::commit_templater::CommitTemplateLanguage;
+use crate::commit_templater::RefName;
+use crate::complete;
+use crate::templater::TemplatePropertyError;
+use crate::ui::Ui;
+
+/// Show how bookmarks compare to their tracked remote bookmarks
+///
+/// For each tracked remote bookmark, shows whether the remote bookmark is
+/// in sync with the local bookmark, or is ahead of it, behind it, or diverged
+/// from it. Local bookmarks without tracked remote bookmarks aren't listed.
+///
+/// For information about bookmarks, see
+/// https://jj-vcs.github.io/jj/latest/bookmarks/.
+#[derive(clap::Args, Clone, Debug)]
+pub struct BookmarkStatusArgs {
+    /// Show bookmarks whose local name matches
+    ///
+    /// By default, the specified name matches exactly. Use `glob:` prefix to
+    /// select bookmarks by wildcard pattern. For details, see
+    /// https://jj-vcs.github.io/jj/latest/revsets/#string-patterns.
+    #[arg(value_parser = StringPattern::parse, add = ArgValueCandidates::new(complete::bookmarks))]
+    names: Option<Vec<StringPattern>>,
+
+    /// Show remote bookmarks belonging to this remote (can be repeated)
+    ///
+    /// By default, the specified remote name matches exactly. Use `glob:`
+    /// prefix to select remotes by wildcard pattern. For details, see
+    /// https://jj-vcs.github.io/jj/latest/revsets/#string-patterns.
+    #[arg(
+        long = "remote",
+        value_name = "REMOTE",
+        value_parser = StringPattern::parse,
+        add = ArgValueCandidates::new(complete::git_remotes),
+    )]
+    remotes: Option<Vec<StringPattern>>,
+
+    /// Render each remote bookmark using the given template
+    ///
+    /// All 0-argument methods of the `RefName` type are available as keywords.
+    ///
+    /// For the syntax, see https://jj-vcs.github.io/jj/latest/templates/
+    #[arg(long, short = 'T', conflicts_with = "json")]
+    template: Option<String>,
+
+    /// Print the statuses as JSON
+    ///
+    /// Each remote bookmark is emitted as an object with its name, remote,
+    /// status, commit counts, and target commit ids.
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct BookmarkStatusEntry {
+    name: String,
+    remote: String,
+    status: &'static str,
+    /// Number of commits the remote bookmark has but the local doesn't.
+    ahead: Option<usize>,
+    /// Number of commits the local bookmark has but the remote doesn't.
+    behind: Option<usize>,
+    local_targets: Vec<String>,
+    remote_targets: Vec<String>,
+}
+
+pub fn cmd_bookmark_status(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &BookmarkStatusArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let view = repo.view();
+
+    let mut remote_bookmarks = vec![];
+    for (name, bookmark_target) in view.bookmarks() {
+        if let Some(patterns) = &args.names {
+            if !patterns.iter().any(|pattern| pattern.matches(name)) {
+                continue;
+            }
+        }
+        let local_target = bookmark_target.local_target;
+        let tracking_remote_refs = bookmark_target
+            .remote_refs
+            .iter()
+            .filter(|&&(remote, remote_ref)| {
+                remote_ref.is_tracking() && !jj_lib::git::is_special_git_remote(remote)
+            })
+            .filter(|&&(remote, _)| {
+                args.remotes.as_ref().map_or(true, |patterns| {
+                    patterns.iter().any(|pattern| pattern.matches(remote))
+                })
+            });
+        for &(remote, remote_ref) in tracking_remote_refs {
+            let ref_name = RefName::remote(name, remote, remote_ref.clone(), local_target);
+            remote_bookmarks.push((name, remote, remote_ref, local_target, ref_name));
+        }
+    }
+
+    if args.json {
+        let to_command_error = |err: TemplatePropertyError| internal_error(err.0);
+        let entries: Vec<_> = remote_bookmarks
+            .iter()
+            .map(|(name, remote, remote_ref, local_target, ref_name)| {
+                let status = ref_name
+                    .tracking_status(repo.as_ref())
+                    .map_err(to_command_error)?;
+                let (ahead, behind) = if local_target.is_present() {
+                    let (ahead, _) = ref_name
+                        .tracking_ahead_count(repo.as_ref())
+                        .map_err(to_command_error)?;
+                    let (behind, _) = ref_name
+                        .tracking_behind_count(repo.as_ref())
+                        .map_err(to_command_error)?;
+                    (Some(ahead), Some(behind))
+                } else {
+                    (None, None)
+                };
+                Ok(BookmarkStatusEntry {
+                    name: name.to_string(),
+                    remote: remote.to_string(),
+                    status: status.as_str(),
+                    ahead,
+                    behind,
+                    local_targets: target_hexes(local_target),
+                    remote_targets: target_hexes(&remote_ref.target),
+                })
+            })
+            .collect::<Result<_, CommandError>>()?;
+        let json = serde_json::to_string_pretty(&entries).map_err(internal_error)?;
+        writeln!(ui.stdout(), "{json}")?;
+        return Ok(());
+    }
+
+    let template = {
+        let language = workspace_command.commit_template_language();
+        let text = match &args.template {
+            Some(value) => value.to_owned(),
+            None => workspace_command
+                .settings()
+                .get("templates.bookmark_status")?,
+        };
+        workspace_command
+            .parse_template(ui, &language, &text, CommitTemplateLanguage::wrap_ref_name)?
+            .labeled("bookmark_status")
+    };
+
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    for (_, _, _, _, ref_name) in &remote_bookmarks {
+        template.format(ref_name, formatter.as_mut())?;
+    }
+    Ok(())
+}
+
+fn target_hexes(target: &RefTarget) -> Vec<String> {
+    target.added_ids().map(|id| id.hex()).collect()
+}
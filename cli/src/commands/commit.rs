@@ -22,6 +22,7 @@ use crate::cli_util::CommandHelper;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::description_util::add_commit_trailers;
 use crate::description_util::description_template;
 use crate::description_util::edit_description;
 use crate::description_util::join_message_paragraphs;
@@ -67,6 +68,14 @@ pub(crate) struct CommitArgs {
         value_parser = parse_author
     )]
     author: Option<(String, String)>,
+    /// Add a `Signed-off-by` trailer for the configured user to the
+    /// description
+    ///
+    /// Trailers rendered by the `templates.commit_trailers` template are
+    /// added regardless. Trailers already present in the description aren't
+    /// added again.
+    #[arg(long)]
+    signoff: bool,
 }
 
 #[instrument(skip_all)]
@@ -140,6 +149,7 @@ new working-copy commit.
         let template = description_template(ui, &tx, "", &temp_commit)?;
         edit_description(&text_editor, &template)?
     };
+    let description = add_commit_trailers(ui, &tx, &commit, &description, args.signoff)?;
     commit_builder.set_description(description);
     let new_commit = commit_builder.write(tx.repo_mut())?;
 
@@ -0,0 +1,163 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::io::Write as _;
+
+use clap_complete::ArgValueCandidates;
+use jj_lib::config::ConfigNamePathBuf;
+use jj_lib::config::ConfigSource;
+use jj_lib::config::StackedConfig;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::config::resolved_config_values;
+use crate::config::AnnotatedValue;
+use crate::ui::Ui;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+enum ConfigDiffLevel {
+    /// Built-in default values
+    Default,
+    /// Environment variables overridden by config files
+    EnvBase,
+    /// User-level config files
+    User,
+    /// Repo-level config files
+    Repo,
+    /// Environment variables overriding config files
+    EnvOverrides,
+    /// Command-line arguments (`--config`, `--config-file`, etc.)
+    CommandArg,
+    /// All levels combined, as the effective config
+    Resolved,
+}
+
+impl ConfigDiffLevel {
+    fn source(self) -> Option<ConfigSource> {
+        match self {
+            ConfigDiffLevel::Default => Some(ConfigSource::Default),
+            ConfigDiffLevel::EnvBase => Some(ConfigSource::EnvBase),
+            ConfigDiffLevel::User => Some(ConfigSource::User),
+            ConfigDiffLevel::Repo => Some(ConfigSource::Repo),
+            ConfigDiffLevel::EnvOverrides => Some(ConfigSource::EnvOverrides),
+            ConfigDiffLevel::CommandArg => Some(ConfigSource::CommandArg),
+            ConfigDiffLevel::Resolved => None,
+        }
+    }
+}
+
+/// Compare config variables between two config levels
+///
+/// Variables set at the `TO` level are compared against the ones set at the
+/// `FROM` level as if `TO` took precedence:
+///
+/// * `added`: The variable is only set at the `TO` level.
+/// * `overridden`: The variable is set at both levels with different values.
+/// * `shadowed`: The variable set at the `FROM` level is hidden by a parent
+///   value or a table of the same name set at the `TO` level.
+///
+/// Variables that are only set at the `FROM` level and stay in effect, and
+/// variables set to the same value at both levels, aren't listed.
+#[derive(clap::Args, Clone, Debug)]
+#[command(verbatim_doc_comment)]
+pub struct ConfigDiffArgs {
+    /// The config level to compare from
+    #[arg(value_enum)]
+    from: ConfigDiffLevel,
+    /// The config level to compare to
+    #[arg(value_enum)]
+    to: ConfigDiffLevel,
+    /// An optional name of a specific config option to look up
+    #[arg(add = ArgValueCandidates::new(complete::config_keys))]
+    name: Option<ConfigNamePathBuf>,
+}
+
+#[instrument(skip_all)]
+pub fn cmd_config_diff(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ConfigDiffArgs,
+) -> Result<(), CommandError> {
+    let name_path = args.name.clone().unwrap_or_else(ConfigNamePathBuf::root);
+    let config = command.settings().config();
+    let from_values = effective_values_at(config, args.from, &name_path);
+    let to_values = effective_values_at(config, args.to, &name_path);
+
+    let mut entries = vec![];
+    for (name, to_annotated) in &to_values {
+        match from_values.get(name) {
+            Some(from_annotated) => {
+                let from_value = from_annotated.value.decorated("", "").to_string();
+                let to_value = to_annotated.value.decorated("", "").to_string();
+                if from_value != to_value {
+                    let text = format!("{from_value} -> {to_value}");
+                    entries.push((name, "overridden", text));
+                }
+            }
+            None => {
+                let to_value = to_annotated.value.decorated("", "").to_string();
+                entries.push((name, "added", to_value));
+            }
+        }
+    }
+    for (name, from_annotated) in &from_values {
+        if to_values.contains_key(name) {
+            continue;
+        }
+        let is_shadowed = to_values
+            .keys()
+            .any(|to_name| name.starts_with(to_name) || to_name.starts_with(name));
+        if is_shadowed {
+            let from_value = from_annotated.value.decorated("", "").to_string();
+            entries.push((name, "shadowed", from_value));
+        }
+    }
+    entries.sort_by(|(name1, ..), (name2, ..)| name1.cmp(name2));
+
+    if entries.is_empty() {
+        writeln!(ui.status(), "No differences found")?;
+        return Ok(());
+    }
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    for (name, kind, text) in &entries {
+        formatter.with_label("config_diff", |formatter| -> io::Result<()> {
+            write!(formatter.labeled(kind), "{kind}")?;
+            writeln!(formatter, ": {name} = {text}")
+        })?;
+    }
+    Ok(())
+}
+
+/// Collects values in effect at the given `level`, keyed by name.
+fn effective_values_at(
+    config: &StackedConfig,
+    level: ConfigDiffLevel,
+    filter_prefix: &ConfigNamePathBuf,
+) -> BTreeMap<ConfigNamePathBuf, AnnotatedValue> {
+    let mut level_config = StackedConfig::empty();
+    match level.source() {
+        Some(source) => level_config.extend_layers(config.layers_for(source).iter().cloned()),
+        None => level_config.extend_layers(config.layers().iter().cloned()),
+    }
+    resolved_config_values(&level_config, filter_prefix)
+        .into_iter()
+        .filter(|annotated| !annotated.is_overridden)
+        .map(|annotated| (annotated.name.clone(), annotated))
+        .collect()
+}
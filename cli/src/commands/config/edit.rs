@@ -12,17 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use jj_lib::config::ConfigLayer;
 use tracing::instrument;
 
+use super::warn_config_schema_error;
 use super::ConfigLevelArgs;
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
+use crate::config::config_schema;
 use crate::ui::Ui;
 
 /// Start an editor on a jj config file.
 ///
 /// Creates the file if it doesn't already exist regardless of what the editor
-/// does.
+/// does. After the editor exits, the file is checked for syntax errors, and
+/// unknown or invalid values of known config options are reported.
 #[derive(clap::Args, Clone, Debug)]
 pub struct ConfigEditArgs {
     #[command(flatten)]
@@ -31,7 +35,7 @@ pub struct ConfigEditArgs {
 
 #[instrument(skip_all)]
 pub fn cmd_config_edit(
-    _ui: &mut Ui,
+    ui: &mut Ui,
     command: &CommandHelper,
     args: &ConfigEditArgs,
 ) -> Result<(), CommandError> {
@@ -41,5 +45,11 @@ pub fn cmd_config_edit(
         file.save()?;
     }
     editor.edit_file(file.path())?;
+
+    // The edited file is kept as is even if it contains errors.
+    let layer = ConfigLayer::load_from_file(file.layer().source, file.path().to_owned())?;
+    for err in config_schema().validate_table(layer.data.as_table()) {
+        warn_config_schema_error(ui, &err)?;
+    }
     Ok(())
 }
@@ -12,14 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Write as _;
+use std::path::PathBuf;
+
 use clap_complete::ArgValueCandidates;
 use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigSource;
+use jj_lib::config::ConfigValue;
 use jj_lib::settings::UserSettings;
+use serde::Serialize;
 use tracing::instrument;
 
 use super::ConfigLevelArgs;
 use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::config::resolved_config_values;
@@ -54,8 +60,35 @@ pub struct ConfigListArgs {
     /// * `overridden: Boolean`: True if the value is shadowed by other.
     ///
     /// For the syntax, see https://jj-vcs.github.io/jj/latest/templates/
-    #[arg(long, short = 'T', verbatim_doc_comment)]
+    #[arg(long, short = 'T', verbatim_doc_comment, conflicts_with = "json")]
     template: Option<String>,
+    /// Print the variables as JSON
+    ///
+    /// Each variable is emitted as an object with its name, typed value,
+    /// source level, source file path (if any), and whether it is overridden.
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ConfigListEntry {
+    name: String,
+    value: serde_json::Value,
+    source: &'static str,
+    path: Option<PathBuf>,
+    overridden: bool,
+}
+
+impl ConfigListEntry {
+    fn new(annotated: &AnnotatedValue) -> Self {
+        ConfigListEntry {
+            name: annotated.name.to_string(),
+            value: config_value_to_json(&annotated.value),
+            source: config_source_name(annotated.source),
+            path: annotated.path.clone(),
+            overridden: annotated.is_overridden,
+        }
+    }
 }
 
 #[instrument(skip_all)]
@@ -64,6 +97,16 @@ pub fn cmd_config_list(
     command: &CommandHelper,
     args: &ConfigListArgs,
 ) -> Result<(), CommandError> {
+    let name_path = args.name.clone().unwrap_or_else(ConfigNamePathBuf::root);
+    let annotated_values = filtered_config_values(command, args, &name_path);
+
+    if args.json {
+        let entries: Vec<_> = annotated_values.iter().map(ConfigListEntry::new).collect();
+        let json = serde_json::to_string_pretty(&entries).map_err(internal_error)?;
+        writeln!(ui.stdout(), "{json}")?;
+        return Ok(());
+    }
+
     let template = {
         let language = config_template_language(command.settings());
         let text = match &args.template {
@@ -75,20 +118,6 @@ pub fn cmd_config_list(
             .labeled("config_list")
     };
 
-    let name_path = args.name.clone().unwrap_or_else(ConfigNamePathBuf::root);
-    let mut annotated_values = resolved_config_values(command.settings().config(), &name_path);
-    // The default layer could be excluded beforehand as layers[len..], but we
-    // can't do the same for "annotated.source == target_source" in order for
-    // resolved_config_values() to mark values overridden by the upper layers.
-    if let Some(target_source) = args.level.get_source_kind() {
-        annotated_values.retain(|annotated| annotated.source == target_source);
-    } else if !args.include_defaults {
-        annotated_values.retain(|annotated| annotated.source != ConfigSource::Default);
-    }
-    if !args.include_overridden {
-        annotated_values.retain(|annotated| !annotated.is_overridden);
-    }
-
     if !annotated_values.is_empty() {
         ui.request_pager();
         let mut formatter = ui.stdout_formatter();
@@ -106,6 +135,53 @@ pub fn cmd_config_list(
     Ok(())
 }
 
+fn filtered_config_values(
+    command: &CommandHelper,
+    args: &ConfigListArgs,
+    name_path: &ConfigNamePathBuf,
+) -> Vec<AnnotatedValue> {
+    let mut annotated_values = resolved_config_values(command.settings().config(), name_path);
+    // The default layer could be excluded beforehand as layers[len..], but we
+    // can't do the same for "annotated.source == target_source" in order for
+    // resolved_config_values() to mark values overridden by the upper layers.
+    if let Some(target_source) = args.level.get_source_kind() {
+        annotated_values.retain(|annotated| annotated.source == target_source);
+    } else if !args.include_defaults {
+        annotated_values.retain(|annotated| annotated.source != ConfigSource::Default);
+    }
+    if !args.include_overridden {
+        annotated_values.retain(|annotated| !annotated.is_overridden);
+    }
+    annotated_values
+}
+
+fn config_source_name(source: ConfigSource) -> &'static str {
+    match source {
+        ConfigSource::Default => "default",
+        ConfigSource::EnvBase => "env-base",
+        ConfigSource::User => "user",
+        ConfigSource::Repo => "repo",
+        ConfigSource::EnvOverrides => "env-overrides",
+        ConfigSource::CommandArg => "command-arg",
+    }
+}
+
+/// Converts TOML value to JSON value preserving its type.
+fn config_value_to_json(value: &ConfigValue) -> serde_json::Value {
+    match value {
+        ConfigValue::String(v) => v.value().clone().into(),
+        ConfigValue::Integer(v) => (*v.value()).into(),
+        ConfigValue::Float(v) => (*v.value()).into(),
+        ConfigValue::Boolean(v) => (*v.value()).into(),
+        ConfigValue::Datetime(v) => v.value().to_string().into(),
+        ConfigValue::Array(array) => array.iter().map(config_value_to_json).collect(),
+        ConfigValue::InlineTable(table) => table
+            .iter()
+            .map(|(k, v)| (k.to_owned(), config_value_to_json(v)))
+            .collect(),
+    }
+}
+
 // AnnotatedValue will be cloned internally in the templater. If the cloning
 // cost matters, wrap it with Rc.
 fn config_template_language(
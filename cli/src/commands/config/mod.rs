@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod diff;
 mod edit;
 mod get;
 mod list;
@@ -19,13 +20,17 @@ mod path;
 mod set;
 mod unset;
 
+use std::io;
 use std::path::Path;
 
 use itertools::Itertools as _;
 use jj_lib::config::ConfigFile;
+use jj_lib::config::ConfigSchemaError;
 use jj_lib::config::ConfigSource;
 use tracing::instrument;
 
+use self::diff::cmd_config_diff;
+use self::diff::ConfigDiffArgs;
 use self::edit::cmd_config_edit;
 use self::edit::ConfigEditArgs;
 use self::get::cmd_config_get;
@@ -122,6 +127,7 @@ impl ConfigLevelArgs {
 /// config, see https://jj-vcs.github.io/jj/latest/config/.
 #[derive(clap::Subcommand, Clone, Debug)]
 pub(crate) enum ConfigCommand {
+    Diff(ConfigDiffArgs),
     #[command(visible_alias("e"))]
     Edit(ConfigEditArgs),
     #[command(visible_alias("g"))]
@@ -143,6 +149,7 @@ pub(crate) fn cmd_config(
     subcommand: &ConfigCommand,
 ) -> Result<(), CommandError> {
     match subcommand {
+        ConfigCommand::Diff(args) => cmd_config_diff(ui, command, args),
         ConfigCommand::Edit(args) => cmd_config_edit(ui, command, args),
         ConfigCommand::Get(args) => cmd_config_get(ui, command, args),
         ConfigCommand::List(args) => cmd_config_list(ui, command, args),
@@ -151,3 +158,15 @@ pub(crate) fn cmd_config(
         ConfigCommand::Unset(args) => cmd_config_unset(ui, command, args),
     }
 }
+
+/// Prints schema validation error as a warning.
+fn warn_config_schema_error(ui: &Ui, err: &ConfigSchemaError) -> io::Result<()> {
+    writeln!(ui.warning_default(), "{err}")?;
+    if let ConfigSchemaError::UnknownName { similar, .. } = err {
+        if !similar.is_empty() {
+            let quoted_names = similar.iter().map(|name| format!(r#""{name}""#)).join(", ");
+            writeln!(ui.hint_default(), "Did you mean {quoted_names}?")?;
+        }
+    }
+    Ok(())
+}
@@ -17,20 +17,27 @@ use std::io;
 use clap_complete::ArgValueCandidates;
 use jj_lib::commit::Commit;
 use jj_lib::config::ConfigNamePathBuf;
+use jj_lib::config::ConfigSchemaError;
 use jj_lib::config::ConfigValue;
 use jj_lib::repo::Repo;
 use tracing::instrument;
 
+use super::warn_config_schema_error;
 use super::ConfigLevelArgs;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::config::config_schema;
 use crate::config::parse_value_or_bare_string;
 use crate::ui::Ui;
 
 /// Update config file to set the given option to a given value.
+///
+/// Values of known config options are checked against the config schema. A
+/// value of unexpected type is rejected, and an unknown option name within a
+/// known table is reported as a warning.
 #[derive(clap::Args, Clone, Debug)]
 pub struct ConfigSetArgs {
     #[arg(required = true, add = ArgValueCandidates::new(complete::leaf_config_keys))]
@@ -60,6 +67,13 @@ pub fn cmd_config_set(
 ) -> Result<(), CommandError> {
     let mut file = args.level.edit_config_file(command)?;
 
+    for err in config_schema().validate_value(&args.name, &args.value) {
+        match err {
+            ConfigSchemaError::UnknownName { .. } => warn_config_schema_error(ui, &err)?,
+            ConfigSchemaError::InvalidValue { .. } => return Err(err.into()),
+        }
+    }
+
     // If the user is trying to change the author config, we should warn them that
     // it won't affect the working copy author
     if args.name == ConfigNamePathBuf::from_iter(vec!["user", "name"]) {
@@ -14,56 +14,214 @@
 
 use std::fmt::Debug;
 use std::io::Write as _;
+use std::time::Instant;
 
+use clap::Subcommand;
 use jj_lib::default_index::AsCompositeIndex as _;
+use jj_lib::default_index::DefaultIndexStore;
 use jj_lib::default_index::DefaultReadonlyIndex;
+use jj_lib::default_index::IndexCompactionPolicy;
+use jj_lib::fsck::check_index_entries;
+use jj_lib::index::Index as _;
+use jj_lib::repo::RepoLoader;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::internal_error;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
 /// Show commit index stats
+///
+/// Without a subcommand, this is the same as `jj debug index stats`.
 #[derive(clap::Args, Clone, Debug)]
-pub struct DebugIndexArgs {}
+pub struct DebugIndexArgs {
+    #[command(subcommand)]
+    command: Option<DebugIndexCommand>,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+enum DebugIndexCommand {
+    Compact(DebugIndexCompactArgs),
+    Stats(DebugIndexStatsArgs),
+    Verify(DebugIndexVerifyArgs),
+}
+
+/// Show commit index stats, including the size of each segment
+#[derive(clap::Args, Clone, Debug, Default)]
+struct DebugIndexStatsArgs {
+    /// Also measure how long it takes to look up every indexed commit
+    #[arg(long)]
+    time_lookups: bool,
+}
+
+/// Check that the commit index agrees with the commit store
+///
+/// Every indexed commit must be readable from the store, and its indexed
+/// parents, change id, and generation number must be consistent with it.
+/// Problems can be fixed by rebuilding the index with `jj debug reindex`.
+#[derive(clap::Args, Clone, Debug)]
+struct DebugIndexVerifyArgs {}
+
+/// Squash commit index segments
+///
+/// By default, segments are squashed as configured by the `index.compaction`
+/// settings.
+#[derive(clap::Args, Clone, Debug)]
+struct DebugIndexCompactArgs {
+    /// Squash all segments into one
+    #[arg(long)]
+    full: bool,
+}
 
 pub fn cmd_debug_index(
     ui: &mut Ui,
     command: &CommandHelper,
-    _args: &DebugIndexArgs,
+    args: &DebugIndexArgs,
 ) -> Result<(), CommandError> {
+    match &args.command {
+        Some(DebugIndexCommand::Compact(args)) => cmd_debug_index_compact(ui, command, args),
+        Some(DebugIndexCommand::Stats(args)) => cmd_debug_index_stats(ui, command, args),
+        Some(DebugIndexCommand::Verify(args)) => cmd_debug_index_verify(ui, command, args),
+        None => cmd_debug_index_stats(ui, command, &DebugIndexStatsArgs::default()),
+    }
+}
+
+/// Loads the default index at the operation specified by `--at-op`.
+fn load_default_index(
+    ui: &Ui,
+    command: &CommandHelper,
+    repo_loader: &RepoLoader,
+    action: &str,
+) -> Result<DefaultReadonlyIndex, CommandError> {
     // Resolve the operation without loading the repo, so this command won't
     // update the index.
-    let workspace = command.load_workspace()?;
-    let repo_loader = workspace.repo_loader();
     let op = command.resolve_operation(ui, repo_loader)?;
     let index_store = repo_loader.index_store();
     let index = index_store
         .get_index_at_op(&op, repo_loader.store())
         .map_err(internal_error)?;
-    if let Some(default_index) = index.as_any().downcast_ref::<DefaultReadonlyIndex>() {
-        let stats = default_index.as_composite().stats();
-        writeln!(ui.stdout(), "Number of commits: {}", stats.num_commits)?;
-        writeln!(ui.stdout(), "Number of merges: {}", stats.num_merges)?;
+    match index.as_any().downcast_ref::<DefaultReadonlyIndex>() {
+        Some(default_index) => Ok(default_index.clone()),
+        None => Err(user_error(format!(
+            "Cannot {action} indexes of type '{}'",
+            index_store.name()
+        ))),
+    }
+}
+
+fn cmd_debug_index_stats(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugIndexStatsArgs,
+) -> Result<(), CommandError> {
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let default_index = load_default_index(ui, command, repo_loader, "get stats for")?;
+    let default_index_store = repo_loader
+        .index_store()
+        .as_any()
+        .downcast_ref::<DefaultIndexStore>();
+    let stats = default_index.as_composite().stats();
+    writeln!(ui.stdout(), "Number of commits: {}", stats.num_commits)?;
+    writeln!(ui.stdout(), "Number of merges: {}", stats.num_merges)?;
+    writeln!(
+        ui.stdout(),
+        "Max generation number: {}",
+        stats.max_generation_number
+    )?;
+    writeln!(ui.stdout(), "Number of heads: {}", stats.num_heads)?;
+    writeln!(ui.stdout(), "Number of changes: {}", stats.num_changes)?;
+    writeln!(ui.stdout(), "Stats per level:")?;
+    for (i, level) in stats.levels.iter().enumerate() {
+        let name = level.name.as_ref().unwrap();
+        writeln!(ui.stdout(), "  Level {i}:")?;
+        writeln!(ui.stdout(), "    Number of commits: {}", level.num_commits)?;
+        writeln!(ui.stdout(), "    Name: {name}")?;
+        if let Some(store) = default_index_store {
+            let size = store.segment_file_size(name).map_err(internal_error)?;
+            writeln!(ui.stdout(), "    Size: {size}")?;
+        }
+    }
+
+    if args.time_lookups {
+        let index = default_index.as_composite();
+        let commit_ids: Vec<_> = index.entries().map(|entry| entry.commit_id()).collect();
+        let start = Instant::now();
+        for id in &commit_ids {
+            default_index.has_id(id);
+        }
+        let elapsed = start.elapsed();
         writeln!(
             ui.stdout(),
-            "Max generation number: {}",
-            stats.max_generation_number
+            "Commit id lookups: {} in {elapsed:.2?}",
+            commit_ids.len()
         )?;
-        writeln!(ui.stdout(), "Number of heads: {}", stats.num_heads)?;
-        writeln!(ui.stdout(), "Number of changes: {}", stats.num_changes)?;
-        writeln!(ui.stdout(), "Stats per level:")?;
-        for (i, level) in stats.levels.iter().enumerate() {
-            writeln!(ui.stdout(), "  Level {i}:")?;
-            writeln!(ui.stdout(), "    Number of commits: {}", level.num_commits)?;
-            writeln!(ui.stdout(), "    Name: {}", level.name.as_ref().unwrap())?;
+        let start = Instant::now();
+        for id in &commit_ids {
+            default_index.shortest_unique_commit_id_prefix_len(id);
         }
-    } else {
+        let elapsed = start.elapsed();
+        writeln!(
+            ui.stdout(),
+            "Shortest prefix lookups: {} in {elapsed:.2?}",
+            commit_ids.len()
+        )?;
+    }
+    Ok(())
+}
+
+fn cmd_debug_index_verify(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &DebugIndexVerifyArgs,
+) -> Result<(), CommandError> {
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let default_index = load_default_index(ui, command, repo_loader, "verify")?;
+    let report = check_index_entries(repo_loader.store(), &default_index);
+    for issue in &report.issues {
+        writeln!(ui.stdout(), "{issue}")?;
+    }
+    writeln!(ui.status(), "Commits checked: {}", report.commits_checked)?;
+    let message = match report.issues.len() {
+        0 => return Ok(()),
+        1 => "Found 1 problem in the commit index".to_owned(),
+        n => format!("Found {n} problems in the commit index"),
+    };
+    Err(user_error_with_hint(
+        message,
+        "Run `jj debug reindex` to rebuild the commit index.",
+    ))
+}
+
+fn cmd_debug_index_compact(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugIndexCompactArgs,
+) -> Result<(), CommandError> {
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let op = command.resolve_operation(ui, repo_loader)?;
+    let index_store = repo_loader.index_store();
+    let Some(default_index_store) = index_store.as_any().downcast_ref::<DefaultIndexStore>() else {
         return Err(user_error(format!(
-            "Cannot get stats for indexes of type '{}'",
+            "Cannot compact indexes of type '{}'",
             index_store.name()
         )));
-    }
+    };
+    let policy = if args.full {
+        IndexCompactionPolicy::full()
+    } else {
+        IndexCompactionPolicy::from_settings(command.settings())?
+    };
+    let (num_before, num_after) = default_index_store
+        .compact_index_at_operation(&op, repo_loader.store(), &policy)
+        .map_err(internal_error)?;
+    writeln!(
+        ui.status(),
+        "Compacted commit index from {num_before} to {num_after} segments."
+    )?;
     Ok(())
 }
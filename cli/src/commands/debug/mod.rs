@@ -18,10 +18,15 @@ mod index;
 mod local_working_copy;
 mod operation;
 mod reindex;
+mod repro_bundle;
+mod repro_load;
 mod revset;
 mod snapshot;
+mod store;
 mod template;
 mod tree;
+mod warm_cache;
+mod watch;
 mod watchman;
 mod working_copy;
 
@@ -43,14 +48,24 @@ use self::operation::cmd_debug_operation;
 use self::operation::DebugOperationArgs;
 use self::reindex::cmd_debug_reindex;
 use self::reindex::DebugReindexArgs;
+use self::repro_bundle::cmd_debug_repro_bundle;
+use self::repro_bundle::DebugReproBundleArgs;
+use self::repro_load::cmd_debug_repro_load;
+use self::repro_load::DebugReproLoadArgs;
 use self::revset::cmd_debug_revset;
 use self::revset::DebugRevsetArgs;
 use self::snapshot::cmd_debug_snapshot;
 use self::snapshot::DebugSnapshotArgs;
+use self::store::cmd_debug_store;
+use self::store::DebugStoreCommand;
 use self::template::cmd_debug_template;
 use self::template::DebugTemplateArgs;
 use self::tree::cmd_debug_tree;
 use self::tree::DebugTreeArgs;
+use self::warm_cache::cmd_debug_warm_cache;
+use self::warm_cache::DebugWarmCacheArgs;
+use self::watch::cmd_debug_watch;
+use self::watch::DebugWatchArgs;
 use self::watchman::cmd_debug_watchman;
 use self::watchman::DebugWatchmanCommand;
 use self::working_copy::cmd_debug_working_copy;
@@ -71,10 +86,16 @@ pub enum DebugCommand {
     #[command(visible_alias = "view")]
     Operation(DebugOperationArgs),
     Reindex(DebugReindexArgs),
+    ReproBundle(DebugReproBundleArgs),
+    ReproLoad(DebugReproLoadArgs),
     Revset(DebugRevsetArgs),
     Snapshot(DebugSnapshotArgs),
+    #[command(subcommand)]
+    Store(DebugStoreCommand),
     Template(DebugTemplateArgs),
     Tree(DebugTreeArgs),
+    WarmCache(DebugWarmCacheArgs),
+    Watch(DebugWatchArgs),
     #[command(subcommand)]
     Watchman(DebugWatchmanCommand),
     WorkingCopy(DebugWorkingCopyArgs),
@@ -91,11 +112,16 @@ pub fn cmd_debug(
         DebugCommand::LocalWorkingCopy(args) => cmd_debug_local_working_copy(ui, command, args),
         DebugCommand::Operation(args) => cmd_debug_operation(ui, command, args),
         DebugCommand::Reindex(args) => cmd_debug_reindex(ui, command, args),
+        DebugCommand::ReproBundle(args) => cmd_debug_repro_bundle(ui, command, args),
+        DebugCommand::ReproLoad(args) => cmd_debug_repro_load(ui, command, args),
         DebugCommand::CopyDetection(args) => cmd_debug_copy_detection(ui, command, args),
         DebugCommand::Revset(args) => cmd_debug_revset(ui, command, args),
         DebugCommand::Snapshot(args) => cmd_debug_snapshot(ui, command, args),
+        DebugCommand::Store(args) => cmd_debug_store(ui, command, args),
         DebugCommand::Template(args) => cmd_debug_template(ui, command, args),
         DebugCommand::Tree(args) => cmd_debug_tree(ui, command, args),
+        DebugCommand::WarmCache(args) => cmd_debug_warm_cache(ui, command, args),
+        DebugCommand::Watch(args) => cmd_debug_watch(ui, command, args),
         DebugCommand::Watchman(args) => cmd_debug_watchman(ui, command, args),
         DebugCommand::WorkingCopy(args) => cmd_debug_working_copy(ui, command, args),
     }
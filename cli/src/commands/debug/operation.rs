@@ -19,6 +19,7 @@ use clap_complete::ArgValueCandidates;
 use jj_lib::object_id::ObjectId;
 use jj_lib::op_walk;
 
+use crate::cli_util::date_pattern_context;
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
 use crate::complete;
@@ -54,7 +55,11 @@ pub fn cmd_debug_operation(
     // even if e.g. the view object is broken.
     let workspace = command.load_workspace()?;
     let repo_loader = workspace.repo_loader();
-    let op = op_walk::resolve_op_for_load(repo_loader, &args.operation)?;
+    let op = op_walk::resolve_op_for_load(
+        repo_loader,
+        &args.operation,
+        &date_pattern_context(command.settings()),
+    )?;
     if args.display == OperationDisplay::Id {
         writeln!(ui.stdout(), "{}", op.id().hex())?;
         return Ok(());
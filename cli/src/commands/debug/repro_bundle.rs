@@ -0,0 +1,65 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::repro_bundle::ReproBundle;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Write a privacy-scrubbed bundle for reproducing bugs
+///
+/// The bundle contains the operation log, the views of all operations, the
+/// commit graph, and statistics of the commit index. Descriptions, author
+/// names, bookmark names, and file contents are left out. The bundle can be
+/// loaded into a new repo with `jj debug repro-load`.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugReproBundleArgs {
+    /// Path of the bundle file to write
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    output: PathBuf,
+    /// Include the file paths of each commit, with each path component
+    /// replaced by a hash
+    #[arg(long)]
+    include_trees: bool,
+}
+
+pub fn cmd_debug_repro_bundle(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugReproBundleArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let bundle = ReproBundle::collect(repo, args.include_trees).map_err(internal_error)?;
+    let output_path = command.cwd().join(&args.output);
+    let file = File::create(&output_path).context(&output_path)?;
+    serde_json::to_writer(BufWriter::new(file), &bundle).map_err(internal_error)?;
+    writeln!(
+        ui.status(),
+        "Wrote bundle of {} operations and {} commits to {}",
+        bundle.operations.len(),
+        bundle.commits.len(),
+        args.output.display()
+    )?;
+    Ok(())
+}
@@ -0,0 +1,85 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use jj_lib::file_util;
+use jj_lib::file_util::IoResultExt as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::repro_bundle::ReproBundle;
+use jj_lib::working_copy::CheckoutOptions;
+use jj_lib::workspace::Workspace;
+
+use crate::cli_util::update_working_copy;
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Load a bundle written by `jj debug repro-bundle` into a new repo
+///
+/// The bundled commits are recreated with empty descriptions and placeholder
+/// contents, so their IDs differ from the original ones. The operation log and
+/// views are recreated with the new commit IDs.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugReproLoadArgs {
+    /// Path of the bundle file to load
+    #[arg(value_hint = clap::ValueHint::FilePath)]
+    bundle: PathBuf,
+    /// The destination directory of the new repo
+    #[arg(value_hint = clap::ValueHint::DirPath)]
+    destination: PathBuf,
+}
+
+pub fn cmd_debug_repro_load(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugReproLoadArgs,
+) -> Result<(), CommandError> {
+    let bundle_path = command.cwd().join(&args.bundle);
+    let file = File::open(&bundle_path).context(&bundle_path)?;
+    let bundle: ReproBundle = serde_json::from_reader(BufReader::new(file))
+        .map_err(|err| user_error_with_message("Failed to parse bundle", err))?;
+
+    let wc_path = command.cwd().join(&args.destination);
+    file_util::create_or_reuse_dir(&wc_path).context(&wc_path)?;
+    let (mut workspace, repo) =
+        Workspace::init_local(&command.settings_for_new_workspace(&wc_path)?, &wc_path)?;
+    bundle
+        .load_into(&repo)
+        .map_err(|err| user_error_with_message("Failed to load bundle", err))?;
+
+    // Check out the bundled working-copy commit, so the working copy isn't
+    // left at the operation that initialized the repo.
+    let repo = workspace.repo_loader().load_at_head()?;
+    if let Some(wc_commit_id) = repo.view().get_wc_commit_id(workspace.workspace_id()) {
+        let wc_commit = repo.store().get_commit(wc_commit_id)?;
+        let options = CheckoutOptions {
+            conflict_marker_style: command.settings().get("ui.conflict-marker-style")?,
+        };
+        update_working_copy(&repo, &mut workspace, None, &wc_commit, &options)?;
+    }
+    writeln!(
+        ui.status(),
+        "Loaded bundle of {} operations and {} commits into \"{}\"",
+        bundle.operations.len(),
+        bundle.commits.len(),
+        args.destination.display()
+    )?;
+    Ok(())
+}
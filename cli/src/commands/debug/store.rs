@@ -0,0 +1,78 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Write as _;
+
+use clap::Subcommand;
+use jj_lib::local_backend::LocalBackend;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum DebugStoreCommand {
+    /// Show how objects are stored and how much space compression saves
+    ///
+    /// This command only works with the native backend.
+    Stats,
+}
+
+pub fn cmd_debug_store(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &DebugStoreCommand,
+) -> Result<(), CommandError> {
+    let workspace = command.load_workspace()?;
+    let store = workspace.repo_loader().store();
+    match subcommand {
+        DebugStoreCommand::Stats => {
+            let Some(backend) = store.backend_impl().downcast_ref::<LocalBackend>() else {
+                return Err(user_error(
+                    "This command requires a repo backed by the native backend",
+                ));
+            };
+            let stats = backend.stats()?;
+            writeln!(ui.stdout(), "Loose objects: {}", stats.loose_object_count)?;
+            writeln!(ui.stdout(), "Loose objects size: {}", stats.loose_bytes)?;
+            writeln!(ui.stdout(), "Packs: {}", stats.packs.pack_count)?;
+            writeln!(ui.stdout(), "Packed objects: {}", stats.packs.object_count)?;
+            writeln!(
+                ui.stdout(),
+                "Packed objects size: {}",
+                stats.packs.total_bytes
+            )?;
+            writeln!(ui.stdout(), "Chunked files: {}", stats.chunks.file_count)?;
+            writeln!(
+                ui.stdout(),
+                "Chunked files size: {}",
+                stats.chunks.logical_bytes
+            )?;
+            writeln!(ui.stdout(), "Chunks: {}", stats.chunks.chunk_count)?;
+            writeln!(
+                ui.stdout(),
+                "Chunks size on disk: {}",
+                stats.chunks.stored_bytes
+            )?;
+            let saved_bytes = stats
+                .chunks
+                .logical_bytes
+                .saturating_sub(stats.chunks.stored_bytes);
+            writeln!(ui.stdout(), "Saved by chunking: {saved_bytes}")?;
+        }
+    }
+    Ok(())
+}
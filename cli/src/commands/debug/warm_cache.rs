@@ -0,0 +1,65 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Write as _;
+
+use jj_lib::default_index::AsCompositeIndex as _;
+use jj_lib::default_index::DefaultReadonlyIndex;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::maintenance_util::load_commits_into_caches;
+use crate::maintenance_util::lower_process_priority;
+use crate::ui::Ui;
+
+/// Build caches and other derived data ahead of time
+///
+/// Indexes all commits at the current operation and loads the metadata of
+/// all reachable commits into the backend's caches, so that the next command
+/// doesn't have to. This can be run automatically after `jj git fetch` by
+/// setting `maintenance.warm-cache-after-fetch`.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugWarmCacheArgs {
+    /// Run at lower priority
+    #[arg(long, hide = true)]
+    background: bool,
+}
+
+pub fn cmd_debug_warm_cache(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugWarmCacheArgs,
+) -> Result<(), CommandError> {
+    if args.background {
+        lower_process_priority();
+    }
+    // Loading the repo builds the commit index if needed.
+    let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    let repo = workspace_command.repo();
+    if let Some(default_index) = repo
+        .readonly_index()
+        .as_any()
+        .downcast_ref::<DefaultReadonlyIndex>()
+    {
+        writeln!(
+            ui.status(),
+            "Indexed {} commits.",
+            default_index.as_composite().stats().num_commits
+        )?;
+    }
+    let count = load_commits_into_caches(repo)?;
+    writeln!(ui.status(), "Loaded {count} commits into caches.")?;
+    Ok(())
+}
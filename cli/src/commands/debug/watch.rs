@@ -0,0 +1,64 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Write as _;
+use std::time::Duration;
+
+use itertools::Itertools as _;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::op_heads_store::OpHeadsWatcher;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Wait for new operations and print the operation heads after each change
+///
+/// Operations committed by any process are reported, one line per change.
+/// This can be used by editor integrations and prompts to refresh when the
+/// repo changes.
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugWatchArgs {
+    /// Exit after this many changes
+    #[arg(long)]
+    count: Option<usize>,
+    /// How often to check for changes, in milliseconds
+    #[arg(long, default_value_t = 200)]
+    interval: u64,
+}
+
+pub fn cmd_debug_watch(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugWatchArgs,
+) -> Result<(), CommandError> {
+    // Don't load the repo so that the current state doesn't need to be
+    // resolved or snapshotted.
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let mut watcher = OpHeadsWatcher::new(
+        repo_loader.op_heads_store().clone(),
+        Duration::from_millis(args.interval),
+    )?;
+    let mut num_changes = 0;
+    while args.count.map_or(true, |count| num_changes < count) {
+        let op_heads = watcher.wait()?;
+        let mut stdout = ui.stdout();
+        writeln!(stdout, "{}", op_heads.iter().map(|id| id.hex()).join(" "))?;
+        stdout.flush()?;
+        num_changes += 1;
+    }
+    Ok(())
+}
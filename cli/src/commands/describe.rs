@@ -18,16 +18,20 @@ use std::io::Read;
 
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools;
+use jj_lib::backend::CommitId;
 use jj_lib::backend::Signature;
+use jj_lib::commit::Commit;
 use jj_lib::commit::CommitIteratorExt;
 use jj_lib::object_id::ObjectId;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::description_util::add_commit_trailers;
 use crate::description_util::description_template;
 use crate::description_util::edit_description;
 use crate::description_util::edit_multiple_descriptions;
@@ -88,6 +92,14 @@ pub(crate) struct DescribeArgs {
         value_parser = parse_author
     )]
     author: Option<(String, String)>,
+    /// Add a `Signed-off-by` trailer for the configured user to the
+    /// description
+    ///
+    /// Trailers rendered by the `templates.commit_trailers` template are
+    /// added regardless. Trailers already present in the description aren't
+    /// added again.
+    #[arg(long)]
+    signoff: bool,
 }
 
 #[instrument(skip_all)]
@@ -215,8 +227,16 @@ pub(crate) fn cmd_describe(
         }
     };
 
+    let commit_descriptions: Vec<(_, _)> = commit_descriptions
+        .into_iter()
+        .map(|(commit, description)| -> Result<_, CommandError> {
+            let description = add_commit_trailers(ui, &tx, commit, &description, args.signoff)?;
+            Ok((commit, description))
+        })
+        .try_collect()?;
+
     // Filter out unchanged commits to avoid rebasing descendants in
-    // `transform_descendants` below unnecessarily.
+    // `describe_commits()` below unnecessarily.
     let commit_descriptions: HashMap<_, _> = commit_descriptions
         .into_iter()
         .filter(|(commit, new_description)| {
@@ -229,7 +249,28 @@ pub(crate) fn cmd_describe(
         .map(|(commit, new_description)| (commit.id(), new_description))
         .collect();
 
-    let mut num_described = 0;
+    describe_commits(
+        ui,
+        &mut tx,
+        &commit_descriptions,
+        args.reset_author,
+        args.author.as_ref(),
+    )?;
+    tx.finish(ui, tx_description)?;
+    Ok(())
+}
+
+/// Rewrites the commits with the new descriptions, and rebases their
+/// descendants. If `reset_author` is set or an `author` is given, the author of
+/// the described commits is updated too. Returns the described commits.
+pub(crate) fn describe_commits(
+    ui: &Ui,
+    tx: &mut WorkspaceCommandTransaction,
+    commit_descriptions: &HashMap<&CommitId, String>,
+    reset_author: bool,
+    author: Option<&(String, String)>,
+) -> Result<Vec<Commit>, CommandError> {
+    let mut described_commits = vec![];
     let mut num_reparented = 0;
     // Even though `MutRepo::rewrite_commit` and `MutRepo::rebase_descendants` can
     // handle rewriting of a commit even if it is a descendant of another commit
@@ -246,11 +287,11 @@ pub(crate) fn cmd_describe(
             let mut commit_builder = rewriter.reparent();
             if let Some(description) = commit_descriptions.get(&old_commit_id) {
                 commit_builder = commit_builder.set_description(description);
-                if args.reset_author {
+                if reset_author {
                     let new_author = commit_builder.committer().clone();
                     commit_builder = commit_builder.set_author(new_author);
                 }
-                if let Some((name, email)) = args.author.clone() {
+                if let Some((name, email)) = author.cloned() {
                     let new_author = Signature {
                         name,
                         email,
@@ -258,20 +299,19 @@ pub(crate) fn cmd_describe(
                     };
                     commit_builder = commit_builder.set_author(new_author);
                 }
-                num_described += 1;
+                described_commits.push(commit_builder.write()?);
             } else {
+                commit_builder.write()?;
                 num_reparented += 1;
             }
-            commit_builder.write()?;
             Ok(())
         },
     )?;
-    if num_described > 1 {
-        writeln!(ui.status(), "Updated {num_described} commits")?;
+    if described_commits.len() > 1 {
+        writeln!(ui.status(), "Updated {} commits", described_commits.len())?;
     }
     if num_reparented > 0 {
         writeln!(ui.status(), "Rebased {num_reparented} descendant commits")?;
     }
-    tx.finish(ui, tx_description)?;
-    Ok(())
+    Ok(described_commits)
 }
@@ -15,6 +15,7 @@
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use itertools::Itertools;
+use jj_lib::commit::Commit;
 use jj_lib::copies::CopyRecords;
 use jj_lib::repo::Repo;
 use jj_lib::rewrite::merge_commit_trees;
@@ -40,6 +41,13 @@ use crate::ui::Ui;
 /// given revisions. If either is left out, it defaults to the working-copy
 /// commit. For example, `jj diff --from main` shows the changes from "main"
 /// (perhaps a bookmark name) to the working-copy commit.
+///
+/// With the `--from-op` and/or `--to-op` options, the `--from` and `--to`
+/// revisions are resolved in the repository as it was at the given operations.
+/// For example, `jj diff --from-op @-` shows how the working-copy commit's
+/// contents changed in the last operation, and
+/// `jj diff --from-op <op> --from main --to main` shows how the files of the
+/// "main" bookmark changed since the operation.
 #[derive(clap::Args, Clone, Debug)]
 #[command(mut_arg("ignore_all_space", |a| a.short('w')))]
 #[command(mut_arg("ignore_space_change", |a| a.short('b')))]
@@ -74,6 +82,22 @@ pub(crate) struct DiffArgs {
         add = ArgValueCandidates::new(complete::all_revisions)
     )]
     to: Option<RevisionArg>,
+    /// Resolve the `--from` revision at this operation
+    #[arg(
+        long,
+        conflicts_with = "revision",
+        value_name = "OPERATION",
+        add = ArgValueCandidates::new(complete::operations)
+    )]
+    from_op: Option<String>,
+    /// Resolve the `--to` revision at this operation
+    #[arg(
+        long,
+        conflicts_with = "revision",
+        value_name = "OPERATION",
+        add = ArgValueCandidates::new(complete::operations)
+    )]
+    to_op: Option<String>,
     /// Restrict the diff to these paths
     #[arg(
         value_name = "FILESETS",
@@ -98,13 +122,23 @@ pub(crate) fn cmd_diff(
     let resolve_revision = |r: &Option<RevisionArg>| {
         workspace_command.resolve_single_rev(ui, r.as_ref().unwrap_or(&RevisionArg::AT))
     };
+    let resolve_revision_at_op =
+        |r: &Option<RevisionArg>, op_str: &Option<String>| -> Result<Commit, CommandError> {
+            if let Some(op_str) = op_str {
+                let op = workspace_command.resolve_single_op(op_str)?;
+                let revision_arg = r.as_ref().unwrap_or(&RevisionArg::AT);
+                workspace_command.resolve_single_rev_at_op(ui, revision_arg, &op)
+            } else {
+                resolve_revision(r)
+            }
+        };
 
     let from_tree;
     let to_tree;
     let mut copy_records = CopyRecords::default();
-    if args.from.is_some() || args.to.is_some() {
-        let from = resolve_revision(&args.from)?;
-        let to = resolve_revision(&args.to)?;
+    if args.from.is_some() || args.to.is_some() || args.from_op.is_some() || args.to_op.is_some() {
+        let from = resolve_revision_at_op(&args.from, &args.from_op)?;
+        let to = resolve_revision_at_op(&args.to, &args.to_op)?;
         from_tree = from.tree()?;
         to_tree = to.tree()?;
 
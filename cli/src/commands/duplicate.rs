@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::io::Write;
 use std::rc::Rc;
 
@@ -34,6 +35,7 @@ use crate::cli_util::RevisionArg;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::description_util::add_duplicated_from_trailer;
 use crate::ui::Ui;
 
 /// Create new changes with the same content as existing ones
@@ -49,6 +51,11 @@ use crate::ui::Ui;
 /// `--insert-after` or `--insert-before` arguments are provided, the new
 /// children indicated by the arguments will be rebased onto the heads of the
 /// specified commits.
+///
+/// If `duplicate.record-origin` is enabled, a `Duplicated-from` trailer with
+/// the ID of the original commit is added to the description of each
+/// duplicated commit. It can be read back by the `duplicated_from()` template
+/// method.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct DuplicateArgs {
     /// The revision(s) to duplicate (default: @)
@@ -221,6 +228,19 @@ pub(crate) fn cmd_duplicate(
         }
     }
 
+    let target_descriptions: HashMap<CommitId, String> =
+        if tx.settings().get_bool("duplicate.record-origin")? {
+            to_duplicate
+                .iter()
+                .map(|commit_id| -> Result<_, CommandError> {
+                    let commit = tx.repo().store().get_commit(commit_id)?;
+                    Ok((commit_id.clone(), add_duplicated_from_trailer(&commit)))
+                })
+                .try_collect()?
+        } else {
+            HashMap::new()
+        };
+
     let num_to_duplicate = to_duplicate.len();
     let DuplicateCommitsStats {
         duplicated_commits,
@@ -229,11 +249,12 @@ pub(crate) fn cmd_duplicate(
         && args.insert_after.is_empty()
         && args.insert_before.is_empty()
     {
-        duplicate_commits_onto_parents(tx.repo_mut(), &to_duplicate)?
+        duplicate_commits_onto_parents(tx.repo_mut(), &to_duplicate, &target_descriptions)?
     } else {
         duplicate_commits(
             tx.repo_mut(),
             &to_duplicate,
+            &target_descriptions,
             &parent_commit_ids,
             &children_commit_ids,
         )?
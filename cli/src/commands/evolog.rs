@@ -12,21 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::convert::Infallible;
+use std::slice;
 
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools;
 use jj_lib::backend::BackendError;
+use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::dag_walk::topo_order_reverse_ok;
 use jj_lib::graph::reverse_graph;
 use jj_lib::graph::GraphEdge;
 use jj_lib::graph::GraphNode;
 use jj_lib::matchers::EverythingMatcher;
+use jj_lib::op_walk;
+use jj_lib::operation::Operation;
+use jj_lib::repo::Repo;
+use jj_lib::revset;
+use jj_lib::rewrite::find_visible_successors;
 use tracing::instrument;
 
 use super::log::get_node_template;
 use crate::cli_util::format_template;
+use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
 use crate::cli_util::RevisionArg;
@@ -34,23 +44,32 @@ use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::complete;
 use crate::diff_util::DiffFormatArgs;
+use crate::formatter::Formatter;
 use crate::graphlog::get_graphlog;
 use crate::graphlog::GraphStyle;
+use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
 /// Show how a change has evolved over time
 ///
 /// Lists the previous commits which a change has pointed to. The current commit
 /// of a change evolves when the change is updated, rebased, etc.
+///
+/// If multiple revisions are given, the evolution of all of them is shown in a
+/// single graph, which is useful for auditing how a whole stack evolved.
+///
+/// If a given revision is hidden, the visible commits it was rewritten into are
+/// listed in a hint.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct EvologArgs {
+    /// Follow changes from these revisions
     #[arg(
-        long, short,
+        long = "revision", short,
         default_value = "@",
-        value_name = "REVSET",
+        value_name = "REVSETS",
         add = ArgValueCandidates::new(complete::all_revisions),
     )]
-    revision: RevisionArg,
+    revisions: Vec<RevisionArg>,
     /// Limit number of revisions to show
     #[arg(long, short = 'n')]
     limit: Option<usize>,
@@ -72,6 +91,13 @@ pub(crate) struct EvologArgs {
     /// contaminated by unrelated changes.
     #[arg(long, short = 'p')]
     patch: bool,
+    /// Show the operation which created each revision
+    ///
+    /// The operation is rendered using the `templates.op_summary` template.
+    /// Revisions created by operations which are no longer in the operation
+    /// log are not annotated.
+    #[arg(long)]
+    operations: bool,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
 }
@@ -84,7 +110,25 @@ pub(crate) fn cmd_evolog(
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
 
-    let start_commit = workspace_command.resolve_single_rev(ui, &args.revision)?;
+    let start_commits: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    let repo = workspace_command.repo().as_ref();
+    for start_commit in &start_commits {
+        if is_visible(repo, start_commit) {
+            continue;
+        }
+        let successor_ids = find_visible_successors(repo, start_commit.id())?;
+        if !successor_ids.is_empty() {
+            writeln!(
+                ui.hint_default(),
+                "Commit {} is hidden. It was rewritten as {}",
+                short_commit_hash(start_commit.id()),
+                successor_ids.iter().map(short_commit_hash).join(", ")
+            )?;
+        }
+    }
 
     let diff_renderer = workspace_command.diff_renderer_for_log(&args.diff_format, args.patch)?;
     let graph_style = GraphStyle::from_settings(workspace_command.settings())?;
@@ -121,7 +165,7 @@ pub(crate) fn cmd_evolog(
     let formatter = formatter.as_mut();
 
     let mut commits = topo_order_reverse_ok(
-        vec![Ok(start_commit)],
+        start_commits.into_iter().map(Ok),
         |commit: &Commit| commit.id().clone(),
         |commit: &Commit| {
             let mut predecessors = commit.predecessors().collect_vec();
@@ -142,6 +186,12 @@ pub(crate) fn cmd_evolog(
     if let Some(n) = args.limit {
         commits.truncate(n);
     }
+    let creating_ops = if args.operations {
+        find_creating_operations(repo, workspace_command.repo().operation(), &commits)?
+    } else {
+        HashMap::new()
+    };
+    let op_summary_template = workspace_command.operation_summary_template();
     if !args.no_graph {
         let mut raw_output = formatter.raw()?;
         let mut graph = get_graphlog(graph_style, raw_output.as_mut());
@@ -175,6 +225,10 @@ pub(crate) fn cmd_evolog(
             if !buffer.ends_with(b"\n") {
                 buffer.push(b'\n');
             }
+            if let Some(op) = creating_ops.get(commit.id()) {
+                let mut formatter = ui.new_formatter(&mut buffer);
+                write_creating_operation(formatter.as_mut(), &op_summary_template, op)?;
+            }
             if let Some(renderer) = &diff_renderer {
                 let predecessors: Vec<_> = commit.predecessors().try_collect()?;
                 let mut formatter = ui.new_formatter(&mut buffer);
@@ -203,6 +257,9 @@ pub(crate) fn cmd_evolog(
         for commit in commits {
             with_content_format
                 .write(formatter, |formatter| template.format(&commit, formatter))?;
+            if let Some(op) = creating_ops.get(commit.id()) {
+                write_creating_operation(formatter, &op_summary_template, op)?;
+            }
             if let Some(renderer) = &diff_renderer {
                 let predecessors: Vec<_> = commit.predecessors().try_collect()?;
                 let width = ui.term_width();
@@ -220,3 +277,60 @@ pub(crate) fn cmd_evolog(
 
     Ok(())
 }
+
+fn write_creating_operation(
+    formatter: &mut dyn Formatter,
+    op_summary_template: &TemplateRenderer<Operation>,
+    op: &Operation,
+) -> std::io::Result<()> {
+    write!(formatter, "Created by operation: ")?;
+    op_summary_template.format(op, formatter)?;
+    writeln!(formatter)
+}
+
+/// Maps each of the `commit_ids` to the operation which created it, i.e. the
+/// oldest operation which made the commit visible.
+fn find_creating_operations(
+    repo: &dyn Repo,
+    head_op: &Operation,
+    commits: &[Commit],
+) -> Result<HashMap<CommitId, Operation>, CommandError> {
+    let commit_ids: HashSet<&CommitId> = commits.iter().map(|commit| commit.id()).collect();
+    // A commit can't have been created by an operation that finished before
+    // the commit was written, so the walk can stop at the first operation
+    // older than all of the commits.
+    let Some(oldest_commit_time) = commits
+        .iter()
+        .map(|commit| commit.committer().timestamp.timestamp.0)
+        .min()
+    else {
+        return Ok(HashMap::new());
+    };
+    let mut creating_ops = HashMap::new();
+    // Operations are visited from newest to oldest, so an operation which
+    // re-added a commit (e.g. by undoing an abandon) is overridden by the
+    // operation that originally created it.
+    for op in op_walk::walk_ancestors(slice::from_ref(head_op)) {
+        let op = op?;
+        if op.metadata().end_time.timestamp.0 < oldest_commit_time {
+            break;
+        }
+        let heads = op.view()?.heads().iter().cloned().collect_vec();
+        let mut parent_heads = vec![];
+        for parent_op in op.parents() {
+            parent_heads.extend(parent_op?.view()?.heads().iter().cloned());
+        }
+        for commit_id in revset::walk_revs(repo, &heads, &parent_heads)?.iter() {
+            let commit_id = commit_id?;
+            if commit_ids.contains(&commit_id) {
+                creating_ops.insert(commit_id, op.clone());
+            }
+        }
+    }
+    Ok(creating_ops)
+}
+
+fn is_visible(repo: &dyn Repo, commit: &Commit) -> bool {
+    repo.resolve_change_id(commit.change_id())
+        .is_some_and(|ids| ids.contains(commit.id()))
+}
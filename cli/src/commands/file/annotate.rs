@@ -12,10 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::io;
+use std::ops::Range;
+use std::time::Duration;
+
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use jj_lib::annotate::get_annotation_for_file;
+use jj_lib::annotate::AnnotateOptions;
 use jj_lib::annotate::FileAnnotation;
+use jj_lib::backend::ChangeId;
+use jj_lib::backend::CommitId;
+use jj_lib::backend::Timestamp;
 use jj_lib::commit::Commit;
 use jj_lib::repo::Repo;
 use jj_lib::revset::RevsetExpression;
@@ -26,15 +35,27 @@ use crate::cli_util::RevisionArg;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::formatter::Formatter;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
 /// Show the source change for each line of the target file.
 ///
 /// Annotates a revision line by line. Each line includes the source change that
-/// introduced the associated line. A path to the desired file must be provided.
+/// introduced the associated line. Lines are followed across renames and
+/// copies of the file. A path to the desired file must be provided.
 /// The per-line prefix for each line can be customized via
 /// template with the `templates.annotate_commit_summary` config variable.
+///
+/// With `--heatmap`, lines are colored by the age of the commit that
+/// introduced them. The ages are grouped into buckets by the
+/// `annotate.heatmap-buckets` config variable, which lists the upper bounds of
+/// the buckets in days. Lines in the N-th bucket (starting from 0 for the most
+/// recent one) are labeled `annotate age-N`.
+///
+/// With `-L START,END`, only the lines from START to END (1-based, inclusive)
+/// are annotated. This can be much faster for large files with long history
+/// since the search stops once all the requested lines are attributed.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct FileAnnotateArgs {
     /// the file to annotate
@@ -51,6 +72,34 @@ pub(crate) struct FileAnnotateArgs {
         add = ArgValueCandidates::new(complete::all_revisions)
     )]
     revision: Option<RevisionArg>,
+    /// What to attribute each line to
+    #[arg(long, value_enum, default_value = "commit")]
+    by: AnnotateBy,
+    /// Color lines by the age of the commits which introduced them
+    #[arg(long)]
+    heatmap: bool,
+    /// Show how long ago each line was introduced
+    #[arg(long)]
+    age: bool,
+    /// Only annotate the lines from START to END (1-based, inclusive)
+    #[arg(long, short = 'L', value_name = "START,END", value_parser = parse_line_range)]
+    lines: Option<Range<usize>>,
+    /// Ignore whitespace when comparing lines
+    ///
+    /// Lines are attributed to the commits which introduced their
+    /// non-whitespace content, so reformatting commits don't claim the lines
+    /// they only reindented.
+    #[arg(long, short = 'w')]
+    ignore_all_space: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+enum AnnotateBy {
+    /// Attribute lines to the commit which introduced them
+    Commit,
+    /// Attribute lines to the change which introduced them, folding all
+    /// rewritten versions of a change into its current commit
+    Change,
 }
 
 #[instrument(skip_all)]
@@ -85,27 +134,155 @@ pub(crate) fn cmd_file_annotate(
     // exclude the revisions, but will ignore diffs in those revisions as if
     // ancestor revisions had new content.
     let domain = RevsetExpression::all();
-    let annotation = get_annotation_for_file(repo.as_ref(), &starting_commit, &domain, &file_path)?;
+    let annotate_options = AnnotateOptions {
+        line_range: args.lines.clone(),
+        follow_renames: true,
+        ignore_whitespace: args.ignore_all_space,
+    };
+    let annotation = get_annotation_for_file(
+        repo.as_ref(),
+        &starting_commit,
+        &domain,
+        &file_path,
+        &annotate_options,
+    )?;
+    if let Some(range) = &args.lines {
+        let num_lines = annotation.lines().count();
+        if range.start >= num_lines {
+            return Err(user_error(format!(
+                "Line {start} is out of range: {ui_path} has only {num_lines} lines",
+                start = range.start + 1
+            )));
+        }
+    }
 
-    render_file_annotation(repo.as_ref(), ui, &template, &annotation)?;
+    let heatmap_buckets = if args.heatmap {
+        let buckets: Vec<u64> = workspace_command
+            .settings()
+            .get("annotate.heatmap-buckets")?;
+        Some(buckets)
+    } else {
+        None
+    };
+    let options = AnnotateRenderOptions {
+        by: args.by,
+        heatmap_buckets,
+        show_age: args.age,
+        line_range: args.lines.clone(),
+    };
+    render_file_annotation(repo.as_ref(), ui, &template, &annotation, &options)?;
     Ok(())
 }
 
+struct AnnotateRenderOptions {
+    by: AnnotateBy,
+    /// Upper bounds of the age buckets in days, if lines should be colored.
+    heatmap_buckets: Option<Vec<u64>>,
+    show_age: bool,
+    /// 0-based range of lines to render, or all lines if unspecified.
+    line_range: Option<Range<usize>>,
+}
+
 fn render_file_annotation(
     repo: &dyn Repo,
     ui: &mut Ui,
     template_render: &TemplateRenderer<Commit>,
     annotation: &FileAnnotation,
+    options: &AnnotateRenderOptions,
 ) -> Result<(), CommandError> {
+    let now = Timestamp::now();
+    let age_format = timeago::Formatter::new();
+    let mut change_commits = HashMap::new();
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
     for (line_no, (commit_id, line)) in annotation.lines().enumerate() {
+        if let Some(range) = &options.line_range {
+            if !range.contains(&line_no) {
+                continue;
+            }
+        }
         let commit_id = commit_id.expect("should reached to the empty ancestor");
-        let commit = repo.store().get_commit(commit_id)?;
-        template_render.format(&commit, formatter.as_mut())?;
-        write!(formatter, " {:>4}: ", line_no + 1)?;
-        formatter.write_all(line)?;
+        let commit = match options.by {
+            AnnotateBy::Commit => repo.store().get_commit(commit_id)?,
+            AnnotateBy::Change => commit_for_change(repo, &mut change_commits, commit_id)?,
+        };
+        let age = commit_age(&commit, &now);
+        let write_line = |formatter: &mut dyn Formatter| -> io::Result<()> {
+            template_render.format(&commit, formatter)?;
+            if options.show_age {
+                write!(formatter, " {}", age_format.convert(age))?;
+            }
+            write!(formatter, " {:>4}: ", line_no + 1)?;
+            formatter.write_all(line)
+        };
+        if let Some(buckets) = &options.heatmap_buckets {
+            let label = format!("age-{}", age_bucket(buckets, age));
+            formatter.with_label("annotate", |formatter| {
+                formatter.with_label(&label, write_line)
+            })?;
+        } else {
+            write_line(formatter.as_mut())?;
+        }
     }
 
     Ok(())
 }
+
+/// Parses 1-based inclusive `START,END` line numbers into a 0-based range.
+fn parse_line_range(s: &str) -> Result<Range<usize>, String> {
+    let (start, end) = s
+        .split_once(',')
+        .ok_or("Expected line range in the form START,END")?;
+    let parse = |n: &str| {
+        n.trim()
+            .parse::<usize>()
+            .map_err(|err| format!("Invalid line number {n:?}: {err}"))
+    };
+    let (start, end) = (parse(start)?, parse(end)?);
+    if start == 0 {
+        return Err("Line numbers start at 1".to_owned());
+    }
+    if end < start {
+        return Err(format!("End line {end} is before start line {start}"));
+    }
+    Ok(start - 1..end)
+}
+
+/// Returns the time elapsed since the commit was committed. Commits from the
+/// future are treated as brand new.
+fn commit_age(commit: &Commit, now: &Timestamp) -> Duration {
+    let millis = now.timestamp.0 - commit.committer().timestamp.timestamp.0;
+    Duration::from_millis(millis.try_into().unwrap_or(0))
+}
+
+/// Returns the index of the first bucket whose upper bound (in days) is
+/// greater than the `age`, or the number of buckets if there's no such bucket.
+fn age_bucket(buckets: &[u64], age: Duration) -> usize {
+    let days = age.as_secs() / (24 * 60 * 60);
+    buckets
+        .iter()
+        .position(|&max_days| days < max_days)
+        .unwrap_or(buckets.len())
+}
+
+/// Returns the commit representing the change of `commit_id`.
+///
+/// If the change has a single visible commit, that commit is used. Otherwise,
+/// e.g. if the change is hidden or divergent, the first commit seen for the
+/// change is used so that all lines from the change are attributed alike.
+fn commit_for_change(
+    repo: &dyn Repo,
+    change_commits: &mut HashMap<ChangeId, Commit>,
+    commit_id: &CommitId,
+) -> Result<Commit, CommandError> {
+    let commit = repo.store().get_commit(commit_id)?;
+    if let Some(change_commit) = change_commits.get(commit.change_id()) {
+        return Ok(change_commit.clone());
+    }
+    let change_commit = match repo.resolve_change_id(commit.change_id()).as_deref() {
+        Some([visible_id]) => repo.store().get_commit(visible_id)?,
+        _ => commit,
+    };
+    change_commits.insert(change_commit.change_id().clone(), change_commit.clone());
+    Ok(change_commit)
+}
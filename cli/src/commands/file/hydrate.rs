@@ -0,0 +1,70 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use itertools::Itertools as _;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::commands::sparse::update_sparse_patterns_with;
+use crate::ui::Ui;
+
+/// Materialize paths in a sparse or lazily cloned working copy
+///
+/// The given paths (and all files under them) are added to the working copy's
+/// sparse patterns, and are written to disk. Paths that are already present
+/// in the working copy are left as is.
+///
+/// This is a shorthand for `jj sparse set --add` that accepts paths relative
+/// to the current directory. It's mainly useful in repos cloned with
+/// `jj git clone --lazy`, where no files are materialized initially.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct FileHydrateArgs {
+    /// Paths to materialize
+    #[arg(required = true, value_hint = clap::ValueHint::AnyPath)]
+    paths: Vec<String>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_file_hydrate(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &FileHydrateArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let paths: Vec<_> = args
+        .paths
+        .iter()
+        .map(|path| workspace_command.parse_file_path(path))
+        .try_collect()?;
+    update_sparse_patterns_with(ui, &mut workspace_command, |_ui, old_patterns| {
+        let new_paths = paths
+            .iter()
+            .filter(|path| !old_patterns.iter().any(|pattern| path.starts_with(pattern)))
+            .cloned()
+            .collect_vec();
+        // Patterns covered by the new paths are no longer needed.
+        let kept_patterns = old_patterns
+            .iter()
+            .filter(|pattern| !new_paths.iter().any(|path| pattern.starts_with(path)))
+            .cloned()
+            .collect_vec();
+        Ok(kept_patterns
+            .into_iter()
+            .chain(new_paths)
+            .sorted_unstable()
+            .dedup()
+            .collect())
+    })
+}
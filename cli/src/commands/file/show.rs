@@ -17,8 +17,11 @@ use std::io::Write;
 
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
+use itertools::Itertools as _;
 use jj_lib::backend::BackendResult;
+use jj_lib::backend::FileId;
 use jj_lib::conflicts::materialize_merge_result;
+use jj_lib::conflicts::materialize_merge_result_to_bytes;
 use jj_lib::conflicts::materialize_tree_value;
 use jj_lib::conflicts::MaterializedTreeValue;
 use jj_lib::fileset::FilePattern;
@@ -35,13 +38,20 @@ use crate::cli_util::RevisionArg;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
+use crate::commit_templater::CommitTemplateLanguage;
+use crate::commit_templater::TreeEntry;
 use crate::complete;
+use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
 /// Print contents of files in a revision
 ///
 /// If the given path is a directory, files in the directory will be visited
 /// recursively.
+///
+/// If more than one file is printed, the contents of each file are preceded by
+/// a header, which can be customized by `-T` or the `templates.file_show_header`
+/// setting.
 #[derive(clap::Args, Clone, Debug)]
 pub(crate) struct FileShowArgs {
     /// The revision to get the file contents from
@@ -52,6 +62,19 @@ pub(crate) struct FileShowArgs {
         add = ArgValueCandidates::new(complete::all_revisions),
     )]
     revision: RevisionArg,
+    /// Render the header of each file using the given template
+    ///
+    /// If specified, the header is printed even if there's only one file.
+    ///
+    /// All 0-argument methods of the `TreeEntry` type are available as
+    /// keywords.
+    ///
+    /// For the syntax, see https://jj-vcs.github.io/jj/latest/templates/
+    #[arg(long, short = 'T', conflicts_with = "no_header")]
+    template: Option<String>,
+    /// Don't print file headers, only the raw contents
+    #[arg(long)]
+    no_header: bool,
     /// Paths to print
     #[arg(
         required = true,
@@ -74,6 +97,26 @@ pub(crate) fn cmd_file_show(
     // TODO: No need to add special case for empty paths when switching to
     // parse_union_filesets(). paths = [] should be "none()" if supported.
     let fileset_expression = workspace_command.parse_file_patterns(ui, &args.paths)?;
+    let header_template = if args.no_header {
+        None
+    } else {
+        let language = workspace_command.commit_template_language();
+        let text = match &args.template {
+            Some(value) => value.to_owned(),
+            None => workspace_command
+                .settings()
+                .get_string("templates.file_show_header")?,
+        };
+        let template = workspace_command
+            .parse_template(
+                ui,
+                &language,
+                &text,
+                CommitTemplateLanguage::wrap_tree_entry,
+            )?
+            .labeled("file_show_header");
+        Some(template)
+    };
 
     // Try fast path for single file entry
     if let Some(path) = get_single_path(&fileset_expression) {
@@ -83,19 +126,23 @@ pub(crate) fn cmd_file_show(
             return Err(user_error(format!("No such path: {ui_path}")));
         }
         if !value.is_tree() {
+            let header_template = header_template.filter(|_| args.template.is_some());
             ui.request_pager();
-            write_tree_entries(ui, &workspace_command, [(path, Ok(value))])?;
+            write_tree_entries(
+                ui,
+                &workspace_command,
+                header_template.as_ref(),
+                [(path, Ok(value))],
+            )?;
             return Ok(());
         }
     }
 
     let matcher = fileset_expression.to_matcher();
+    let entries = tree.entries_matching(matcher.as_ref()).collect_vec();
+    let header_template = header_template.filter(|_| args.template.is_some() || entries.len() > 1);
     ui.request_pager();
-    write_tree_entries(
-        ui,
-        &workspace_command,
-        tree.entries_matching(matcher.as_ref()),
-    )?;
+    write_tree_entries(ui, &workspace_command, header_template.as_ref(), entries)?;
     print_unmatched_explicit_paths(ui, &workspace_command, &fileset_expression, [&tree])?;
     Ok(())
 }
@@ -115,12 +162,17 @@ fn get_single_path(expression: &FilesetExpression) -> Option<&RepoPath> {
 fn write_tree_entries<P: AsRef<RepoPath>>(
     ui: &Ui,
     workspace_command: &WorkspaceCommandHelper,
+    header_template: Option<&TemplateRenderer<TreeEntry>>,
     entries: impl IntoIterator<Item = (P, BackendResult<MergedTreeValue>)>,
 ) -> Result<(), CommandError> {
     let repo = workspace_command.repo();
     for (path, result) in entries {
         let value = result?;
         let materialized = materialize_tree_value(repo.store(), path.as_ref(), value).block_on()?;
+        if let Some(template) = header_template {
+            write_file_with_header(ui, workspace_command, template, path.as_ref(), materialized)?;
+            continue;
+        }
         match materialized {
             MaterializedTreeValue::Absent => panic!("absent values should be excluded"),
             MaterializedTreeValue::AccessDenied(err) => {
@@ -155,3 +207,55 @@ fn write_tree_entries<P: AsRef<RepoPath>>(
     }
     Ok(())
 }
+
+/// Like `write_tree_entries()`, but prints the header before the contents.
+/// Only materialized conflicts are buffered to compute their size.
+fn write_file_with_header(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+    template: &TemplateRenderer<TreeEntry>,
+    path: &RepoPath,
+    materialized: MaterializedTreeValue,
+) -> Result<(), CommandError> {
+    let write_header = |blob_id: Option<FileId>, conflict_size: Option<usize>| -> io::Result<()> {
+        let entry = TreeEntry {
+            path: path.to_owned(),
+            blob_id,
+            conflict_size,
+        };
+        template.format(&entry, ui.stdout_formatter().as_mut())
+    };
+    let contents: Vec<u8> = match materialized {
+        MaterializedTreeValue::Absent => panic!("absent values should be excluded"),
+        MaterializedTreeValue::AccessDenied(err) => {
+            let ui_path = workspace_command.format_file_path(path);
+            writeln!(
+                ui.warning_default(),
+                "Path '{ui_path}' exists but access is denied: {err}"
+            )?;
+            return Ok(());
+        }
+        MaterializedTreeValue::File { id, mut reader, .. } => {
+            write_header(Some(id), None)?;
+            io::copy(&mut reader, &mut ui.stdout_formatter().as_mut())?;
+            return Ok(());
+        }
+        MaterializedTreeValue::FileConflict { contents, .. } => {
+            let conflict_marker_style = workspace_command.env().conflict_marker_style();
+            materialize_merge_result_to_bytes(&contents, conflict_marker_style).into()
+        }
+        MaterializedTreeValue::OtherConflict { id } => id.describe().into_bytes(),
+        MaterializedTreeValue::Symlink { .. } | MaterializedTreeValue::GitSubmodule(_) => {
+            let ui_path = workspace_command.format_file_path(path);
+            writeln!(
+                ui.warning_default(),
+                "Path '{ui_path}' exists but is not a file"
+            )?;
+            return Ok(());
+        }
+        MaterializedTreeValue::Tree(_) => panic!("entries should not contain trees"),
+    };
+    write_header(None, Some(contents.len()))?;
+    ui.stdout_formatter().write_all(&contents)?;
+    Ok(())
+}
@@ -15,6 +15,7 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::io::Write;
+use std::ops::RangeInclusive;
 use std::process::Stdio;
 use std::sync::mpsc::channel;
 
@@ -25,6 +26,8 @@ use jj_lib::backend::BackendError;
 use jj_lib::backend::CommitId;
 use jj_lib::backend::FileId;
 use jj_lib::backend::TreeValue;
+use jj_lib::diff::Diff;
+use jj_lib::diff::DiffHunkKind;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::fileset::FilesetExpression;
@@ -33,6 +36,7 @@ use jj_lib::merged_tree::MergedTree;
 use jj_lib::merged_tree::MergedTreeBuilder;
 use jj_lib::merged_tree::TreeDiffEntry;
 use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::revset::RevsetExpression;
@@ -51,6 +55,7 @@ use crate::command_error::config_error;
 use crate::command_error::print_parse_diagnostics;
 use crate::command_error::CommandError;
 use crate::complete;
+use crate::config::interpolate_variables;
 use crate::config::CommandNameAndArgs;
 use crate::ui::Ui;
 
@@ -88,6 +93,10 @@ use crate::ui::Ui;
 ///  - `enabled`: Enables or disables the tool. If omitted, the tool is enabled.
 ///    This is useful for defining disabled tools in user configuration that can
 ///    be enabled in individual repositories with one config setting.
+///  - `line-range`: Arguments used to pass a range of lines to the tool with
+///    `--since`. Arguments can contain the substrings `$first` and `$last`,
+///    which will be replaced with the 1-based, inclusive line numbers of the
+///    range. The arguments are appended to the `command` once for each range.
 ///
 /// For example, the following configuration defines how two code formatters
 /// (`clang-format` and `black`) will apply to three different file extensions
@@ -108,6 +117,20 @@ use crate::ui::Ui;
 /// currently unspecified, and may change between releases. If two tools affect
 /// the same file, the second tool to run will receive its input from the
 /// output of the first tool.
+///
+/// With `--since`, tools that define `line-range` are only asked to fix the
+/// lines that changed relative to the given revision. This is useful for
+/// formatting new code without reformatting the rest of a legacy file:
+///
+/// ```toml
+/// [fix.tools.clang-format]
+/// command = ["/usr/bin/clang-format", "--assume-filename=$path"]
+/// patterns = ["glob:'**/*.cc'"]
+/// line-range = ["--lines=$first:$last"]
+/// ```
+///
+/// Tools without `line-range` still fix the whole file. A file with no changed
+/// lines isn't passed to the tools that define `line-range`.
 #[derive(clap::Args, Clone, Debug)]
 #[command(verbatim_doc_comment)]
 pub(crate) struct FixArgs {
@@ -128,6 +151,14 @@ pub(crate) struct FixArgs {
     /// specified, all files in the repo will be fixed.
     #[arg(long)]
     include_unchanged_files: bool,
+    /// Only fix the lines changed since the given revision, for tools that
+    /// support line ranges
+    #[arg(
+        long,
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::all_revisions)
+    )]
+    since: Option<RevisionArg>,
 }
 
 #[instrument(skip_all)]
@@ -150,6 +181,10 @@ pub(crate) fn cmd_fix(
     let matcher = workspace_command
         .parse_file_patterns(ui, &args.paths)?
         .to_matcher();
+    let base_tree = match &args.since {
+        Some(since) => Some(workspace_command.resolve_single_rev(ui, since)?.tree()?),
+        None => None,
+    };
 
     let mut tx = workspace_command.start_transaction();
 
@@ -214,6 +249,7 @@ pub(crate) fn cmd_fix(
                         let tool_input = ToolInput {
                             file_id: id.clone(),
                             repo_path: repo_path.clone(),
+                            lines: get_line_selection(base_tree.as_ref(), &repo_path)?,
                         };
                         unique_tool_inputs.insert(tool_input.clone());
                         paths.insert(repo_path.clone());
@@ -250,11 +286,13 @@ pub(crate) fn cmd_fix(
             let mut changes = 0;
             for repo_path in repo_paths {
                 let old_value = old_tree.path_value(repo_path)?;
+                let lines = get_line_selection(base_tree.as_ref(), repo_path)?;
                 let new_value = old_value.map(|old_term| {
                     if let Some(TreeValue::File { id, executable }) = old_term {
                         let tool_input = ToolInput {
                             file_id: id.clone(),
                             repo_path: repo_path.clone(),
+                            lines: lines.clone(),
                         };
                         if let Some(new_id) = fixed_file_ids.get(&tool_input) {
                             return Some(TreeValue::File {
@@ -288,9 +326,6 @@ pub(crate) fn cmd_fix(
 }
 
 /// Represents the API between `jj fix` and the tools it runs.
-// TODO: Add the set of changed line/byte ranges, so those can be passed into code formatters via
-// flags. This will help avoid introducing unrelated changes when working on code with out of date
-// formatting.
 #[derive(PartialEq, Eq, Hash, Clone)]
 struct ToolInput {
     /// File content is the primary input, provided on the tool's standard
@@ -304,6 +339,51 @@ struct ToolInput {
     ///  - Update parts of the file's content that should be derived from the
     ///    file's path.
     repo_path: RepoPathBuf,
+
+    /// The lines to be fixed by tools that accept line ranges. This helps
+    /// avoid introducing unrelated changes when working on code with out of
+    /// date formatting.
+    lines: LineSelection,
+}
+
+/// Selects the lines of a file to be fixed.
+#[derive(PartialEq, Eq, Hash, Clone)]
+enum LineSelection {
+    /// All lines.
+    All,
+    /// The lines changed from the file content in the `--since` revision,
+    /// which is `None` if there was no such file.
+    ChangedFrom(Option<FileId>),
+}
+
+/// Returns the lines to be fixed at `repo_path` relative to the `base_tree`.
+fn get_line_selection(
+    base_tree: Option<&MergedTree>,
+    repo_path: &RepoPath,
+) -> Result<LineSelection, BackendError> {
+    let Some(base_tree) = base_tree else {
+        return Ok(LineSelection::All);
+    };
+    let base_file_id = match base_tree.path_value(repo_path)?.into_resolved() {
+        Ok(Some(TreeValue::File { id, .. })) => Some(id),
+        _ => None,
+    };
+    Ok(LineSelection::ChangedFrom(base_file_id))
+}
+
+/// Returns the 1-based, inclusive ranges of lines in `content` that are
+/// different from `base_content`.
+fn changed_line_ranges(base_content: &[u8], content: &[u8]) -> Vec<RangeInclusive<usize>> {
+    let mut ranges = Vec::new();
+    let mut first = 1;
+    for hunk in Diff::by_line([base_content, content]).hunks() {
+        let count = hunk.contents[1].split_inclusive(|b| *b == b'\n').count();
+        if hunk.kind == DiffHunkKind::Different && count > 0 {
+            ranges.push(first..=first + count - 1);
+        }
+        first += count;
+    }
+    ranges
 }
 
 /// Applies `run_tool()` to the inputs and stores the resulting file content.
@@ -337,9 +417,35 @@ fn fix_file_ids<'a>(
                 let mut old_content = vec![];
                 let mut read = store.read_file(&tool_input.repo_path, &tool_input.file_id)?;
                 read.read_to_end(&mut old_content)?;
+                let base_content = match &tool_input.lines {
+                    LineSelection::All => None,
+                    LineSelection::ChangedFrom(None) => Some(vec![]),
+                    LineSelection::ChangedFrom(Some(base_file_id)) => {
+                        let mut base_content = vec![];
+                        let mut read = store.read_file(&tool_input.repo_path, base_file_id)?;
+                        read.read_to_end(&mut base_content)?;
+                        Some(base_content)
+                    }
+                };
                 let new_content =
                     matching_tools.fold(old_content.clone(), |prev_content, tool_config| {
-                        match run_tool(&tool_config.command, tool_input, &prev_content) {
+                        // The changed lines are computed for each tool since the previous tool
+                        // may have moved them.
+                        let line_ranges = match (&base_content, &tool_config.line_range) {
+                            (Some(base_content), Some(_)) => {
+                                Some(changed_line_ranges(base_content, &prev_content))
+                            }
+                            _ => None,
+                        };
+                        if line_ranges.as_ref().is_some_and(|ranges| ranges.is_empty()) {
+                            return prev_content;
+                        }
+                        match run_tool(
+                            tool_config,
+                            tool_input,
+                            &prev_content,
+                            line_ranges.as_deref(),
+                        ) {
                             Ok(next_content) => next_content,
                             // TODO: Because the stderr is passed through, this isn't always failing
                             // silently, but it should do something better will the exit code, tool
@@ -366,25 +472,37 @@ fn fix_file_ids<'a>(
     Ok(result)
 }
 
-/// Runs the `tool_command` to fix the given file content.
+/// Runs the tool to fix the given file content.
 ///
 /// The `old_content` is assumed to be that of the `tool_input`'s `FileId`, but
 /// this is not verified.
 ///
+/// If `line_ranges` are specified, they are passed to the tool through its
+/// `line-range` arguments.
+///
 /// Returns the new file content, whose value will be the same as `old_content`
 /// unless the command introduced changes. Returns `None` if there were any
 /// failures when starting, stopping, or communicating with the subprocess.
 fn run_tool(
-    tool_command: &CommandNameAndArgs,
+    tool_config: &ToolConfig,
     tool_input: &ToolInput,
     old_content: &[u8],
+    line_ranges: Option<&[RangeInclusive<usize>]>,
 ) -> Result<Vec<u8>, ()> {
     // TODO: Pipe stderr so we can tell the user which commit, file, and tool it is
     // associated with.
     let mut vars: HashMap<&str, &str> = HashMap::new();
     vars.insert("path", tool_input.repo_path.as_internal_file_string());
-    let mut child = tool_command
-        .to_command_with_variables(&vars)
+    let mut command = tool_config.command.to_command_with_variables(&vars);
+    if let (Some(line_range_args), Some(line_ranges)) = (&tool_config.line_range, line_ranges) {
+        for range in line_ranges {
+            let first = range.start().to_string();
+            let last = range.end().to_string();
+            let range_vars = HashMap::from([("first", first), ("last", last)]);
+            command.args(interpolate_variables(line_range_args, &range_vars));
+        }
+    }
+    let mut child = command
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .spawn()
@@ -412,6 +530,9 @@ struct ToolConfig {
     matcher: Box<dyn Matcher>,
     /// Whether the tool is enabled
     enabled: bool,
+    /// The arguments used to pass each range of lines to be fixed, if the tool
+    /// supports it.
+    line_range: Option<Vec<String>>,
     // TODO: Store the `name` field here and print it with the command's stderr, to clearly
     // associate any errors/warnings with the tool and its configuration entry.
 }
@@ -431,6 +552,8 @@ struct RawToolConfig {
     patterns: Vec<String>,
     #[serde(default = "default_tool_enabled")]
     enabled: bool,
+    #[serde(default)]
+    line_range: Option<Vec<String>>,
 }
 
 fn default_tool_enabled() -> bool {
@@ -470,6 +593,7 @@ fn get_tools_config(ui: &mut Ui, settings: &UserSettings) -> Result<ToolsConfig,
                 command: tool.command,
                 matcher: expression.to_matcher(),
                 enabled: tool.enabled,
+                line_range: tool.line_range,
             })
         })
         .try_collect()?;
@@ -0,0 +1,123 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use jj_lib::default_index::DefaultIndexStore;
+use jj_lib::fsck::check_repo;
+use jj_lib::fsck::FsckReport;
+#[cfg(feature = "git")]
+use jj_lib::git_backend::GitBackend;
+use jj_lib::operation::Operation;
+use jj_lib::repo::RepoLoader;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Check the integrity of the repo
+///
+/// Verifies that all operations and their views can be loaded, that all
+/// commits, trees, and files reachable from them can be read, that stored
+/// objects match their hashes (with the native backend), and that the commit
+/// index agrees with the commits in the store.
+///
+/// Problems found in the commit index and in caches can be fixed with
+/// `--repair`. Other problems, such as missing or corrupt objects, can't be
+/// repaired automatically.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct FsckArgs {
+    /// Rebuild the commit index and caches derived from the store
+    #[arg(long)]
+    repair: bool,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_fsck(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &FsckArgs,
+) -> Result<(), CommandError> {
+    // Don't use the workspace helper, which would snapshot the working copy
+    // and possibly write new objects and operations.
+    let workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let op = command.resolve_operation(ui, repo_loader)?;
+    // The repo is dropped before repairing so the index files it maps can be
+    // removed on Windows.
+    let mut report = check_repo(&repo_loader.load_at(&op)?)?;
+
+    if args.repair {
+        repair(ui, repo_loader, &op)?;
+        let repo = repo_loader.load_at(&op)?;
+        report = check_repo(&repo)?;
+    }
+
+    for issue in &report.issues {
+        writeln!(ui.stdout(), "{issue}")?;
+    }
+    writeln!(
+        ui.status(),
+        "Operations checked: {}",
+        report.operations_checked
+    )?;
+    writeln!(ui.status(), "Commits checked: {}", report.commits_checked)?;
+    writeln!(ui.status(), "Trees checked: {}", report.trees_checked)?;
+    check_report(&report)
+}
+
+fn repair(ui: &Ui, repo_loader: &RepoLoader, op: &Operation) -> Result<(), CommandError> {
+    let index_store = repo_loader.index_store();
+    if let Some(default_index_store) = index_store.as_any().downcast_ref::<DefaultIndexStore>() {
+        default_index_store.reinit().map_err(internal_error)?;
+        default_index_store
+            .build_index_at_operation(op, repo_loader.store())
+            .map_err(internal_error)?;
+        writeln!(ui.status(), "Rebuilt commit index.")?;
+    }
+    #[cfg(feature = "git")]
+    if let Some(git_backend) = repo_loader
+        .store()
+        .backend_impl()
+        .downcast_ref::<GitBackend>()
+    {
+        git_backend
+            .clear_commit_metadata_cache()
+            .map_err(internal_error)?;
+        writeln!(ui.status(), "Cleared commit metadata cache.")?;
+    }
+    Ok(())
+}
+
+fn check_report(report: &FsckReport) -> Result<(), CommandError> {
+    if report.issues.is_empty() {
+        return Ok(());
+    }
+    let message = match report.issues.len() {
+        1 => "Found 1 problem".to_owned(),
+        n => format!("Found {n} problems"),
+    };
+    if report.issues.iter().any(|issue| issue.is_repairable()) {
+        Err(user_error_with_hint(
+            message,
+            "Run `jj fsck --repair` to rebuild the commit index and caches.",
+        ))
+    } else {
+        Err(user_error(message))
+    }
+}
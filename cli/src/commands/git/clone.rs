@@ -25,6 +25,7 @@ use jj_lib::repo::Repo;
 use jj_lib::str_util::StringPattern;
 use jj_lib::workspace::Workspace;
 
+use super::write_repository_level_config_template;
 use super::write_repository_level_trunk_alias;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::WorkspaceCommandHelper;
@@ -33,6 +34,7 @@ use crate::command_error::user_error;
 use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::commands::git::maybe_add_gitignore;
+use crate::commands::sparse::update_sparse_patterns_with;
 use crate::git_util::absolute_git_url;
 use crate::git_util::get_git_repo;
 use crate::git_util::map_git_error;
@@ -65,6 +67,12 @@ pub struct GitCloneArgs {
     /// Create a shallow clone of the given depth
     #[arg(long)]
     depth: Option<NonZeroU32>,
+    /// Don't write any files to the working copy
+    ///
+    /// Files can be materialized on demand with `jj file hydrate`. This makes
+    /// cloning a large repo much faster if only a few directories are needed.
+    #[arg(long, conflicts_with = "colocate")]
+    lazy: bool,
 }
 
 fn clone_destination_for_source(source: &str) -> Option<&str> {
@@ -147,6 +155,9 @@ pub fn cmd_git_clone(
     }
 
     let (mut workspace_command, stats) = clone_result?;
+    if args.lazy {
+        update_sparse_patterns_with(ui, &mut workspace_command, |_ui, _old_patterns| Ok(vec![]))?;
+    }
     if let Some(default_branch) = &stats.default_branch {
         write_repository_level_trunk_alias(
             ui,
@@ -171,6 +182,13 @@ pub fn cmd_git_clone(
             checkout_tx.finish(ui, "check out git remote's default branch")?;
         }
     }
+    if args.lazy {
+        writeln!(
+            ui.hint_default(),
+            "No files were written to the working copy. Use `jj file hydrate <path>` to \
+             materialize them."
+        )?;
+    }
     Ok(())
 }
 
@@ -189,6 +207,7 @@ fn do_git_clone(
     } else {
         Workspace::init_internal_git(&settings, wc_path)?
     };
+    write_repository_level_config_template(ui, &settings, workspace.repo_path())?;
     let git_repo = get_git_repo(repo.store())?;
     writeln!(
         ui.status(),
@@ -207,6 +226,7 @@ fn do_git_clone(
             &git_repo,
             remote_name,
             &[StringPattern::everything()],
+            &[],
             cb,
             &git_settings,
             depth,
@@ -221,6 +241,9 @@ fn do_git_clone(
         GitFetchError::InvalidBranchPattern => {
             unreachable!("we didn't provide any globs")
         }
+        GitFetchError::ConflictingBranchMapping(_) => {
+            unreachable!("we didn't provide any mappings")
+        }
     })?;
     print_git_import_stats(ui, fetch_tx.repo(), &stats.import_stats, true)?;
     fetch_tx.finish(ui, "fetch from git remote into empty repo")?;
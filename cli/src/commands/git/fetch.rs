@@ -12,19 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Write as _;
+
 use clap_complete::ArgValueCandidates;
+use itertools::Either;
 use itertools::Itertools;
 use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::git::GitFetchBranchMapping;
 use jj_lib::repo::Repo;
 use jj_lib::settings::UserSettings;
 use jj_lib::str_util::StringPattern;
+use jj_lib::str_util::StringPatternParseError;
 
 use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::commands::git::get_single_remote;
 use crate::complete;
 use crate::git_util::get_git_repo;
 use crate::git_util::git_fetch;
+use crate::maintenance_util::maybe_start_cache_warming;
 use crate::ui::Ui;
 
 /// Fetch from a Git remote
@@ -38,14 +47,21 @@ pub struct GitFetchArgs {
     /// By default, the specified name matches exactly. Use `glob:` prefix to
     /// expand `*` as a glob, e.g. `--branch 'glob:push-*'`. Other wildcard
     /// characters such as `?` are *not* supported.
+    ///
+    /// Use `<LOCAL>=<REMOTE>` to fetch the remote branch `<REMOTE>` as the
+    /// remote bookmark `<LOCAL>@<remote>`, e.g. `--branch upstream-main=main`.
+    /// This is useful when branches of the same name on different remotes
+    /// should be kept apart. The mapping is saved to the repo config as
+    /// `git.fetch-bookmark-mappings.<remote>.<LOCAL>` so that subsequent
+    /// fetches keep the remote bookmark up to date.
     #[arg(
         long, short,
         alias = "bookmark",
         default_value = "glob:*",
-        value_parser = StringPattern::parse,
+        value_parser = parse_branch_arg,
         add = ArgValueCandidates::new(complete::bookmarks),
     )]
-    branch: Vec<StringPattern>,
+    branch: Vec<BranchArg>,
     /// The remote to fetch from (only named remotes are supported, can be
     /// repeated)
     ///
@@ -62,6 +78,25 @@ pub struct GitFetchArgs {
     all_remotes: bool,
 }
 
+#[derive(Clone, Debug)]
+enum BranchArg {
+    Pattern(StringPattern),
+    Mapping(GitFetchBranchMapping),
+}
+
+fn parse_branch_arg(src: &str) -> Result<BranchArg, StringPatternParseError> {
+    // Patterns with a kind prefix can't be mapped.
+    if !src.contains(':') {
+        if let Some((local_branch, remote_branch)) = src.split_once('=') {
+            return Ok(BranchArg::Mapping(GitFetchBranchMapping {
+                remote_branch: remote_branch.to_owned(),
+                local_branch: local_branch.to_owned(),
+            }));
+        }
+    }
+    StringPattern::parse(src).map(BranchArg::Pattern)
+}
+
 #[tracing::instrument(skip(ui, command))]
 pub fn cmd_git_fetch(
     ui: &mut Ui,
@@ -77,15 +112,87 @@ pub fn cmd_git_fetch(
     } else {
         args.remotes.clone()
     };
+    let (patterns, mappings): (Vec<_>, Vec<_>) =
+        args.branch.iter().cloned().partition_map(|arg| match arg {
+            BranchArg::Pattern(pattern) => Either::Left(pattern),
+            BranchArg::Mapping(mapping) => Either::Right(mapping),
+        });
     let mut tx = workspace_command.start_transaction();
-    git_fetch(ui, &mut tx, &git_repo, &remotes, &args.branch)?;
+    git_fetch(ui, &mut tx, &git_repo, &remotes, &patterns, &mappings)?;
+    let has_changes = tx.repo().has_changes();
     tx.finish(
         ui,
         format!("fetch from git remote(s) {}", remotes.iter().join(",")),
     )?;
+    save_branch_mappings(ui, command, &workspace_command, &remotes, &mappings)?;
+    if has_changes {
+        maybe_start_cache_warming(
+            workspace_command.settings(),
+            workspace_command.workspace_root(),
+        );
+    }
+    Ok(())
+}
+
+/// Saves the fetched bookmark mappings to the repo config so that subsequent
+/// fetches don't delete the mapped remote bookmarks.
+fn save_branch_mappings(
+    ui: &Ui,
+    command: &CommandHelper,
+    workspace_command: &WorkspaceCommandHelper,
+    remotes: &[String],
+    mappings: &[GitFetchBranchMapping],
+) -> Result<(), CommandError> {
+    let view = workspace_command.repo().view();
+    let settings = workspace_command.settings();
+    let new_mappings = remotes
+        .iter()
+        .cartesian_product(mappings)
+        .filter(|(remote, mapping)| {
+            let name = mapping_config_name(remote, mapping);
+            view.get_remote_bookmark(&mapping.local_branch, remote)
+                .is_present()
+                && settings.get_string(name).ok().as_ref() != Some(&mapping.remote_branch)
+        })
+        .collect_vec();
+    if new_mappings.is_empty() {
+        return Ok(());
+    }
+    let mut files = command
+        .config_env()
+        .repo_config_files(command.raw_config())?;
+    let mut file = match files.pop() {
+        Some(file) if files.is_empty() => file,
+        _ => return Err(user_error("Cannot determine repo config file to edit")),
+    };
+    for (remote, mapping) in &new_mappings {
+        file.set_value(
+            mapping_config_name(remote, mapping),
+            mapping.remote_branch.as_str(),
+        )
+        .map_err(|err| user_error_with_message("Failed to save bookmark mapping", err))?;
+    }
+    file.save()?;
+    for (remote, mapping) in &new_mappings {
+        writeln!(
+            ui.status(),
+            "Saved bookmark mapping {}={} for remote {remote} to repo config",
+            mapping.local_branch,
+            mapping.remote_branch,
+        )?;
+    }
     Ok(())
 }
 
+fn mapping_config_name<'a>(remote: &'a str, mapping: &'a GitFetchBranchMapping) -> [&'a str; 4] {
+    [
+        "git",
+        "fetch-bookmark-mappings",
+        remote,
+        &mapping.local_branch,
+    ]
+}
+
 const DEFAULT_REMOTE: &str = "origin";
 
 fn get_default_fetch_remotes(
@@ -25,6 +25,7 @@ use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::workspace::Workspace;
 
+use super::write_repository_level_config_template;
 use super::write_repository_level_trunk_alias;
 use crate::cli_util::print_trackable_remote_bookmarks;
 use crate::cli_util::start_repo_transaction;
@@ -158,12 +159,14 @@ fn do_init(
     match &init_mode {
         GitInitMode::Colocate => {
             let (workspace, repo) = Workspace::init_colocated_git(&settings, workspace_root)?;
+            write_repository_level_config_template(ui, &settings, workspace.repo_path())?;
             let workspace_command = command.for_workable_repo(ui, workspace, repo)?;
             maybe_add_gitignore(&workspace_command)?;
         }
         GitInitMode::External(git_repo_path) => {
             let (workspace, repo) =
                 Workspace::init_external_git(&settings, workspace_root, git_repo_path)?;
+            write_repository_level_config_template(ui, &settings, workspace.repo_path())?;
             // Import refs first so all the reachable commits are indexed in
             // chronological order.
             let colocated = is_colocated_git_workspace(&workspace, &repo);
@@ -186,7 +189,8 @@ fn do_init(
             print_trackable_remote_bookmarks(ui, workspace_command.repo().view())?;
         }
         GitInitMode::Internal => {
-            Workspace::init_internal_git(&settings, workspace_root)?;
+            let (workspace, _repo) = Workspace::init_internal_git(&settings, workspace_root)?;
+            write_repository_level_config_template(ui, &settings, workspace.repo_path())?;
         }
     }
     Ok(())
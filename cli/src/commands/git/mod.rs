@@ -25,7 +25,13 @@ use std::path::Path;
 
 use clap::Subcommand;
 use jj_lib::config::ConfigFile;
+use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::config::ConfigLayer;
+use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigSource;
+use jj_lib::config::StackedConfig;
+use jj_lib::file_util::expand_home_path;
+use jj_lib::settings::UserSettings;
 
 use self::clone::cmd_git_clone;
 use self::clone::GitCloneArgs;
@@ -47,6 +53,7 @@ use crate::cli_util::CommandHelper;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
+use crate::config::resolved_config_values;
 use crate::ui::Ui;
 
 /// Commands for working with Git remotes and the underlying Git repo
@@ -108,15 +115,27 @@ fn get_single_remote(git_repo: &git2::Repository) -> Result<Option<String>, Comm
 }
 
 /// Sets repository level `trunk()` alias to the specified remote branch.
+///
+/// The alias is left alone if the repo config already defines it, e.g. because
+/// it was copied from the config template.
 fn write_repository_level_trunk_alias(
     ui: &Ui,
     repo_path: &Path,
     remote: &str,
     branch: &str,
 ) -> Result<(), CommandError> {
+    let name = ["revset-aliases", "trunk()"];
     let mut file = ConfigFile::load_or_empty(ConfigSource::Repo, repo_path.join("config.toml"))?;
-    file.set_value(["revset-aliases", "trunk()"], format!("{branch}@{remote}"))
-        .expect("initial repo config shouldn't have invalid values");
+    if let Ok(Some(_)) = file.layer().look_up_item(name) {
+        return Ok(());
+    }
+    file.set_value(name, format!("{branch}@{remote}"))
+        .map_err(|err| {
+            user_error_with_message(
+                r#"Failed to set the revset alias "trunk()" in the repo config"#,
+                err,
+            )
+        })?;
     file.save()?;
     writeln!(
         ui.status(),
@@ -124,3 +143,60 @@ fn write_repository_level_trunk_alias(
     )?;
     Ok(())
 }
+
+/// Copies the repo config template from `init.repo-config-file` and
+/// `init.repo-config` into the config file of a newly created repo.
+fn write_repository_level_config_template(
+    ui: &Ui,
+    settings: &UserSettings,
+    repo_path: &Path,
+) -> Result<(), CommandError> {
+    let mut values = vec![];
+    if let Some(path) = settings.get_string("init.repo-config-file").optional()? {
+        let layer = ConfigLayer::load_from_file(ConfigSource::Repo, expand_home_path(&path))?;
+        let mut config = StackedConfig::empty();
+        config.add_layer(layer);
+        let annotated_values = resolved_config_values(&config, &ConfigNamePathBuf::root());
+        values.extend(
+            annotated_values
+                .into_iter()
+                .map(|annotated| (annotated.name, annotated.value)),
+        );
+    }
+    let prefix: ConfigNamePathBuf = ["init", "repo-config"].into_iter().collect();
+    // Check the type of the table first. Values not in a table would be
+    // reported with an empty name otherwise.
+    if settings.get_table(&prefix).optional()?.is_some() {
+        let annotated_values = resolved_config_values(settings.config(), &prefix);
+        values.extend(
+            annotated_values
+                .into_iter()
+                .filter(|annotated| !annotated.is_overridden)
+                .map(|annotated| {
+                    let name = annotated
+                        .name
+                        .components()
+                        .skip(prefix.components().len())
+                        .cloned()
+                        .collect::<ConfigNamePathBuf>();
+                    (name, annotated.value)
+                }),
+        );
+    }
+    if values.is_empty() {
+        return Ok(());
+    }
+
+    let mut file = ConfigFile::load_or_empty(ConfigSource::Repo, repo_path.join("config.toml"))?;
+    for (name, value) in values {
+        file.set_value(&name, value).map_err(|err| {
+            user_error_with_message(format!("Failed to set {name} in the repo config"), err)
+        })?;
+    }
+    file.save()?;
+    writeln!(
+        ui.status(),
+        "Initialized repo config from the config template"
+    )?;
+    Ok(())
+}
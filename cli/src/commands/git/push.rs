@@ -50,6 +50,7 @@ use crate::cli_util::WorkspaceCommandHelper;
 use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::user_error;
 use crate::command_error::user_error_with_hint;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::commands::git::get_single_remote;
 use crate::complete;
@@ -58,6 +59,8 @@ use crate::git_util::get_git_repo;
 use crate::git_util::map_git_error;
 use crate::git_util::with_remote_git_callbacks;
 use crate::git_util::GitSidebandProgressMessageWriter;
+use crate::hooks::run_pre_hooks;
+use crate::hooks::HookEvent;
 use crate::ui::Ui;
 
 /// Push to a Git remote
@@ -307,9 +310,33 @@ pub fn cmd_git_push(
     };
     let commits_to_sign =
         validate_commits_ready_to_push(ui, &bookmark_updates, &remote, &tx, args, sign_behavior)?;
+    if !commits_to_sign.is_empty() {
+        if !tx.repo().store().signer().can_sign() {
+            return Err(user_error_with_hint(
+                "Cannot sign commits before pushing: no signing backend is configured",
+                "Set `signing.backend`, or disable `git.sign-on-push` to push unsigned commits.",
+            ));
+        }
+        if let Some(mut formatter) = ui.status_formatter() {
+            let verb = if args.dry_run {
+                "Would sign"
+            } else {
+                "Signing"
+            };
+            writeln!(
+                formatter,
+                "{verb} {} commits before pushing:",
+                commits_to_sign.len()
+            )?;
+            for commit in &commits_to_sign {
+                write!(formatter, "  ")?;
+                tx.write_commit_summary(formatter.as_mut(), commit)?;
+                writeln!(formatter)?;
+            }
+        }
+    }
     if !args.dry_run && !commits_to_sign.is_empty() {
         if let Some(sign_behavior) = sign_behavior {
-            let num_updated_signatures = commits_to_sign.len();
             let num_rebased_descendants;
             (num_rebased_descendants, bookmark_updates) = sign_commits_before_push(
                 &mut tx,
@@ -318,10 +345,6 @@ pub fn cmd_git_push(
                 bookmark_updates,
             )?;
             if let Some(mut formatter) = ui.status_formatter() {
-                writeln!(
-                    formatter,
-                    "Updated signatures of {num_updated_signatures} commits"
-                )?;
                 if num_rebased_descendants > 0 {
                     writeln!(
                         formatter,
@@ -342,6 +365,25 @@ pub fn cmd_git_push(
         return Ok(());
     }
 
+    run_pre_hooks(
+        tx.settings(),
+        tx.base_workspace_helper().workspace_root(),
+        HookEvent::PrePush,
+        serde_json::json!({
+            "remote": remote,
+            "bookmarks": bookmark_updates
+                .iter()
+                .map(|(name, update)| {
+                    serde_json::json!({
+                        "name": name,
+                        "old_target": update.old_target.as_ref().map(|id| id.hex()),
+                        "new_target": update.new_target.as_ref().map(|id| id.hex()),
+                    })
+                })
+                .collect_vec(),
+        }),
+    )?;
+
     let targets = GitBranchPushTargets {
         branch_updates: bookmark_updates,
     };
@@ -400,14 +442,11 @@ fn validate_commits_ready_to_push(
         .range(&RevsetExpression::commits(new_heads));
 
     let settings = workspace_helper.settings();
-    let is_private = if let Ok(revset) = settings.get_string("git.private-commits") {
-        workspace_helper
-            .parse_revset(ui, &RevisionArg::from(revset))?
-            .evaluate()?
-            .containing_fn()
-    } else {
-        Box::new(|_: &CommitId| Ok(false))
-    };
+    let private_revset = settings.get_string("git.private-commits")?;
+    let is_private = workspace_helper
+        .parse_revset(ui, &RevisionArg::from(private_revset.clone()))?
+        .evaluate()?
+        .containing_fn();
     let sign_settings = sign_behavior.map(|sign_behavior| {
         let mut sign_settings = settings.sign_settings();
         sign_settings.behavior = sign_behavior;
@@ -415,6 +454,10 @@ fn validate_commits_ready_to_push(
     });
 
     let mut commits_to_sign = vec![];
+    // Rejected commits are collected so all of them (and all the reasons for
+    // rejecting each) can be reported at once.
+    let mut rejected_commits = vec![];
+    let mut has_private_commits = false;
 
     for commit in workspace_helper
         .attach_revset_evaluator(commits_to_push)
@@ -439,11 +482,22 @@ fn validate_commits_ready_to_push(
         if commit.has_conflict()? {
             reasons.push("it has conflicts");
         }
-        let is_private = is_private(commit.id())?;
-        if !args.allow_private && is_private {
+        if !args.allow_private && is_private(commit.id())? {
             reasons.push("it is private");
+            has_private_commits = true;
         }
         if !reasons.is_empty() {
+            rejected_commits.push((commit, reasons));
+            continue;
+        }
+        if let Some(sign_settings) = &sign_settings {
+            if !commit.is_signed() && sign_settings.should_sign(commit.store_commit()) {
+                commits_to_sign.push(commit);
+            }
+        }
+    }
+    if !rejected_commits.is_empty() {
+        let mut error = if let [(commit, reasons)] = &*rejected_commits {
             let mut error = user_error(format!(
                 "Won't push commit {} since {}",
                 short_commit_hash(commit.id()),
@@ -451,24 +505,32 @@ fn validate_commits_ready_to_push(
             ));
             error.add_formatted_hint_with(|formatter| {
                 write!(formatter, "Rejected commit: ")?;
-                workspace_helper.write_commit_summary(formatter, &commit)?;
+                workspace_helper.write_commit_summary(formatter, commit)?;
                 Ok(())
             });
-            if !args.allow_private && is_private {
-                error.add_hint(format!(
-                    "Configured git.private-commits: '{}'",
-                    settings
-                        .get_string("git.private-commits")
-                        .expect("should have private-commits setting")
-                ));
-            }
-            return Err(error);
-        }
-        if let Some(sign_settings) = &sign_settings {
-            if !commit.is_signed() && sign_settings.should_sign(commit.store_commit()) {
-                commits_to_sign.push(commit);
+            error
+        } else {
+            let mut error = user_error(format!("Won't push {} commits", rejected_commits.len()));
+            for (commit, reasons) in &rejected_commits {
+                error.add_formatted_hint_with(|formatter| {
+                    write!(
+                        formatter,
+                        "Rejected commit since {}: ",
+                        reasons.join(" and ")
+                    )?;
+                    workspace_helper.write_commit_summary(formatter, commit)?;
+                    Ok(())
+                });
             }
+            error
+        };
+        if has_private_commits {
+            error.add_hint(format!(
+                "Configured git.private-commits: '{private_revset}'"
+            ));
+            error.add_hint("Use --allow-private to push private commits anyway");
         }
+        return Err(error);
     }
     Ok(commits_to_sign)
 }
@@ -476,7 +538,8 @@ fn validate_commits_ready_to_push(
 /// Signs commits before pushing.
 ///
 /// Returns the number of commits with rebased descendants and the updated list
-/// of bookmark names and corresponding [`BookmarkPushUpdate`]s.
+/// of bookmark names and corresponding [`BookmarkPushUpdate`]s. If signing
+/// fails, the transaction must be abandoned so that nothing is pushed.
 fn sign_commits_before_push(
     tx: &mut WorkspaceCommandTransaction,
     commits_to_sign: Vec<Commit>,
@@ -501,6 +564,15 @@ fn sign_commits_before_push(
                 old_to_new_commits_map.insert(old_commit_id, commit.id().clone());
             }
             Ok(())
+        })
+        .map_err(|err| {
+            let mut error =
+                user_error_with_message("Failed to sign commits, nothing was pushed", err);
+            error.add_hint(
+                "Fix the signing configuration, or disable `git.sign-on-push` to push unsigned \
+                 commits.",
+            );
+            error
         })?;
 
     let bookmark_updates = bookmark_updates
@@ -34,6 +34,12 @@ pub(crate) struct InitArgs {
     /// The destination directory
     #[arg(default_value = ".", value_hint = clap::ValueHint::DirPath)]
     destination: String,
+    /// Store objects in the object store at the given URL
+    ///
+    /// Only `file://` URLs (such as a mounted bucket) are supported by the
+    /// built-in object store.
+    #[arg(long, value_name = "URL")]
+    object_store: Option<String>,
 }
 
 #[instrument(skip_all)]
@@ -61,7 +67,12 @@ pub(crate) fn cmd_init(
 Set `ui.allow-init-native` to allow initializing a repo with the native backend.",
         ));
     }
-    Workspace::init_local(&command.settings_for_new_workspace(&wc_path)?, &wc_path)?;
+    let settings = command.settings_for_new_workspace(&wc_path)?;
+    if let Some(url) = &args.object_store {
+        Workspace::init_object_store(&settings, &wc_path, url)?;
+    } else {
+        Workspace::init_local(&settings, &wc_path)?;
+    }
 
     let relative_wc_path = file_util::relative_path(cwd, &wc_path);
     writeln!(
@@ -82,7 +82,11 @@ pub(crate) struct LogArgs {
     reversed: bool,
     /// Limit number of revisions to show
     ///
-    /// Applied after revisions are filtered and reordered.
+    /// Applied after revisions are filtered and reordered topologically, but
+    /// before being reversed.
+    ///
+    /// If not specified, this defaults to the `ui.log-default-limit` setting,
+    /// or no limit if the setting is unset.
     #[arg(long, short = 'n')]
     limit: Option<usize>,
     /// Don't show the graph, show a flat list of revisions
@@ -177,7 +181,13 @@ pub(crate) fn cmd_log(
         let mut formatter = ui.stdout_formatter();
         let formatter = formatter.as_mut();
 
-        let limit = args.limit.unwrap_or(usize::MAX);
+        let limit = match args.limit {
+            Some(limit) => limit,
+            None => settings
+                .get::<usize>("ui.log-default-limit")
+                .optional()?
+                .unwrap_or(usize::MAX),
+        };
 
         if !args.no_graph {
             let mut raw_output = formatter.raw()?;
@@ -192,13 +202,16 @@ pub(crate) fn cmd_log(
                         forward_iter.prioritize_branch(id.clone());
                     }
                 }
+                // Apply the limit before reversing so only the nodes to be
+                // shown are buffered.
+                let forward_iter = forward_iter.take(limit);
                 if args.reversed {
                     Box::new(reverse_graph(forward_iter)?.into_iter().map(Ok))
                 } else {
                     Box::new(forward_iter)
                 }
             };
-            for node in iter.take(limit) {
+            for node in iter {
                 let (commit_id, edges) = node?;
 
                 // The graph is keyed by (CommitId, is_synthetic)
@@ -280,11 +293,11 @@ pub(crate) fn cmd_log(
         } else {
             let iter: Box<dyn Iterator<Item = Result<CommitId, RevsetEvaluationError>>> =
                 if args.reversed {
-                    Box::new(revset.iter().reversed()?)
+                    Box::new(revset.iter().take(limit).reversed()?)
                 } else {
-                    Box::new(revset.iter())
+                    Box::new(revset.iter().take(limit))
                 };
-            for commit_or_error in iter.commits(store).take(limit) {
+            for commit_or_error in iter.commits(store) {
                 let commit = commit_or_error?;
                 with_content_format
                     .write(formatter, |formatter| template.format(&commit, formatter))?;
@@ -19,6 +19,7 @@ use std::rc::Rc;
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools;
 use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
 use jj_lib::commit::CommitIteratorExt;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
@@ -32,6 +33,7 @@ use tracing::instrument;
 use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
@@ -189,29 +191,13 @@ pub(crate) fn cmd_new(
     };
     workspace_command.check_rewritable(children_commits.iter().ids())?;
 
-    let parent_commit_ids_set: HashSet<CommitId> = parent_commit_ids.iter().cloned().collect();
-
     let mut tx = workspace_command.start_transaction();
-    let merged_tree = merge_commit_trees(tx.repo(), &parent_commits)?;
-    let new_commit = tx
-        .repo_mut()
-        .new_commit(parent_commit_ids, merged_tree.id())
-        .set_description(join_message_paragraphs(&args.message_paragraphs))
-        .write()?;
-
-    let mut num_rebased = 0;
-    for child_commit in children_commits {
-        let new_parent_ids = child_commit
-            .parent_ids()
-            .iter()
-            .filter(|id| !parent_commit_ids_set.contains(id))
-            .cloned()
-            .chain(std::iter::once(new_commit.id().clone()))
-            .collect_vec();
-        rebase_commit(tx.repo_mut(), child_commit, new_parent_ids)?;
-        num_rebased += 1;
-    }
-    num_rebased += tx.repo_mut().rebase_descendants()?;
+    let (new_commit, num_rebased) = write_new_commit(
+        &mut tx,
+        &parent_commits,
+        children_commits,
+        join_message_paragraphs(&args.message_paragraphs),
+    )?;
 
     if args.no_edit {
         if let Some(mut formatter) = ui.status_formatter() {
@@ -236,6 +222,40 @@ pub(crate) fn cmd_new(
     Ok(())
 }
 
+/// Creates a new commit on top of the `parent_commits`, and inserts it between
+/// the parents and the `children_commits`. Returns the new commit and the
+/// number of rebased commits.
+pub(crate) fn write_new_commit(
+    tx: &mut WorkspaceCommandTransaction,
+    parent_commits: &[Commit],
+    children_commits: Vec<Commit>,
+    description: String,
+) -> Result<(Commit, usize), CommandError> {
+    let parent_commit_ids = parent_commits.iter().ids().cloned().collect_vec();
+    let parent_commit_ids_set: HashSet<CommitId> = parent_commit_ids.iter().cloned().collect();
+    let merged_tree = merge_commit_trees(tx.repo(), parent_commits)?;
+    let new_commit = tx
+        .repo_mut()
+        .new_commit(parent_commit_ids, merged_tree.id())
+        .set_description(description)
+        .write()?;
+
+    let mut num_rebased = 0;
+    for child_commit in children_commits {
+        let new_parent_ids = child_commit
+            .parent_ids()
+            .iter()
+            .filter(|id| !parent_commit_ids_set.contains(id))
+            .cloned()
+            .chain(std::iter::once(new_commit.id().clone()))
+            .collect_vec();
+        rebase_commit(tx.repo_mut(), child_commit, new_parent_ids)?;
+        num_rebased += 1;
+    }
+    num_rebased += tx.repo_mut().rebase_descendants()?;
+    Ok((new_commit, num_rebased))
+}
+
 /// Ensure that there is no possible cycle between the potential children and
 /// parents of the new commit.
 fn ensure_no_commit_loop(
@@ -19,7 +19,10 @@ use std::slice;
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools as _;
 use jj_lib::op_walk;
+use jj_lib::operation::Operation;
+use jj_lib::workspace::Workspace;
 
+use crate::cli_util::date_pattern_context;
 use crate::cli_util::short_operation_hash;
 use crate::cli_util::CommandHelper;
 use crate::command_error::cli_error;
@@ -63,7 +66,9 @@ pub fn cmd_op_abandon(
         return Err(cli_error("--at-op is not respected"));
     }
     let current_head_ops = op_walk::get_current_head_ops(op_store, op_heads_store.as_ref())?;
-    let resolve_op = |op_str| op_walk::resolve_op_at(op_store, &current_head_ops, op_str);
+    let date_context = date_pattern_context(command.settings());
+    let resolve_op =
+        |op_str| op_walk::resolve_op_at(op_store, &current_head_ops, op_str, &date_context);
     let (abandon_root_op, abandon_head_ops) =
         if let Some((root_op_str, head_op_str)) = args.operation.split_once("..") {
             let root_op = if root_op_str.is_empty() {
@@ -102,19 +107,38 @@ pub fn cmd_op_abandon(
         return Err(err);
     }
 
-    // Reparent descendants, count the number of abandoned operations.
-    let stats = op_walk::reparent_range(
-        op_store.as_ref(),
+    abandon_operations(
+        ui,
+        command,
+        &mut workspace,
+        &abandon_root_op,
         &abandon_head_ops,
         &current_head_ops,
-        &abandon_root_op,
-    )?;
+    )
+}
+
+/// Abandons the operation range `root_op..head_ops` by reparenting the
+/// descendants up to `current_head_ops` onto `root_op`, and updates the
+/// operation heads and the working-copy operation accordingly.
+pub(crate) fn abandon_operations(
+    ui: &Ui,
+    command: &CommandHelper,
+    workspace: &mut Workspace,
+    root_op: &Operation,
+    head_ops: &[Operation],
+    current_head_ops: &[Operation],
+) -> Result<(), CommandError> {
+    let repo_loader = workspace.repo_loader();
+    let op_store = repo_loader.op_store();
+    let op_heads_store = repo_loader.op_heads_store().clone();
+    // Reparent descendants, count the number of abandoned operations.
+    let stats = op_walk::reparent_range(op_store.as_ref(), head_ops, current_head_ops, root_op)?;
     assert_eq!(
         current_head_ops.len(),
         stats.new_head_ids.len(),
         "all current_head_ops should be reparented as they aren't included in abandon_head_ops"
     );
-    let reparented_head_ops = || iter::zip(&current_head_ops, &stats.new_head_ids);
+    let reparented_head_ops = || iter::zip(current_head_ops, &stats.new_head_ids);
     if reparented_head_ops().all(|(old, new_id)| old.id() == new_id) {
         writeln!(ui.status(), "Nothing changed.")?;
         return Ok(());
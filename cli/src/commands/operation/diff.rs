@@ -14,46 +14,80 @@
 
 use std::collections::HashMap;
 use std::convert::Infallible;
+use std::slice;
 use std::sync::Arc;
 
 use clap_complete::ArgValueCandidates;
+use futures::StreamExt as _;
 use indexmap::IndexMap;
 use itertools::Itertools;
 use jj_lib::backend::ChangeId;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
+use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::copies::CopyRecords;
 use jj_lib::dag_walk;
 use jj_lib::graph::GraphEdge;
 use jj_lib::graph::TopoGroupedGraphIterator;
 use jj_lib::matchers::EverythingMatcher;
+use jj_lib::matchers::Matcher;
+use jj_lib::merged_tree::MergedTree;
 use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::op_store::RemoteRefState;
+use jj_lib::op_walk;
+use jj_lib::op_walk::OpsetEvaluationError;
+use jj_lib::operation::Operation;
+use jj_lib::opset;
+use jj_lib::opset::OpsetExpression;
 use jj_lib::refs::diff_named_ref_targets;
 use jj_lib::refs::diff_named_remote_refs;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
-use jj_lib::revset;
-use jj_lib::revset::RevsetIteratorExt as _;
-
+use jj_lib::repo::RepoLoader;
+use jj_lib::repo_path::RepoPathUiConverter;
+use jj_lib::rewrite::rebase_to_dest_parent;
+use jj_lib::time_util::DatePatternContext;
+use pollster::FutureExt as _;
+
+use super::is_inspecting_workable_repo;
+use super::resolve_ops_at_heads;
+use crate::cli_util::date_pattern_context;
+use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
+use crate::cli_util::WorkspaceCommandEnvironment;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::complete;
 use crate::diff_util::diff_formats_for_log;
+use crate::diff_util::get_copy_records;
+use crate::diff_util::write_diff_stat_summary;
+use crate::diff_util::DiffFormat;
 use crate::diff_util::DiffFormatArgs;
 use crate::diff_util::DiffRenderer;
+use crate::diff_util::DiffStatOptions;
+use crate::diff_util::DiffStats;
 use crate::formatter::Formatter;
 use crate::graphlog::get_graphlog;
 use crate::graphlog::GraphStyle;
+use crate::operation_templater::compute_operation_commits_diff;
+use crate::operation_templater::ModifiedChange;
+use crate::operation_templater::OperationTemplateLanguage;
 use crate::templater::TemplateRenderer;
 use crate::ui::Ui;
 
 /// Compare changes to the repository between two operations
+///
+/// With `--at-op=@` or `--ignore-working-copy`, divergent operations aren't
+/// reconciled, and the changes to each of the operation heads are shown if
+/// the target operation is `@`.
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationDiffArgs {
     /// Show repository changes in this operation, compared to its parent
+    ///
+    /// If a range `x..y` is given, changes from operation `x` to operation `y`
+    /// are shown.
     #[arg(
         long,
         visible_alias = "op",
@@ -74,6 +108,19 @@ pub struct OperationDiffArgs {
         add = ArgValueCandidates::new(complete::operations),
     )]
     to: Option<String>,
+    /// Show repository changes from the current operation of another repo
+    ///
+    /// The path should point to a workspace of a copy of this repo, such as a
+    /// backup. This can be used to audit what restoring from or syncing with
+    /// that copy would change. Commits in the other repo must also exist in
+    /// this repo's commit storage.
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["operation", "from"],
+        value_hint = clap::ValueHint::DirPath,
+    )]
+    from_repo: Option<String>,
     /// Don't show the graph, show a flat list of modified changes
     #[arg(long)]
     no_graph: bool,
@@ -84,6 +131,20 @@ pub struct OperationDiffArgs {
     /// contaminated by unrelated changes.
     #[arg(long, short = 'p')]
     patch: bool,
+    /// Show only changes to these paths
+    ///
+    /// Changes are shown if any of their commits modifies a matching path.
+    /// Diffs are limited to the matching paths.
+    #[arg(value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
+    paths: Vec<String>,
+    /// Render each added or removed commit using the given template
+    ///
+    /// The default template is read from the `templates.op_diff_change` config
+    /// variable.
+    ///
+    /// For the syntax, see https://jj-vcs.github.io/jj/latest/templates/
+    #[arg(long, short = 'T')]
+    template: Option<String>,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
 }
@@ -93,74 +154,238 @@ pub fn cmd_op_diff(
     command: &CommandHelper,
     args: &OperationDiffArgs,
 ) -> Result<(), CommandError> {
-    let workspace_command = command.workspace_helper(ui)?;
-    let workspace_env = workspace_command.env();
-    let repo_loader = workspace_command.workspace().repo_loader();
-    let settings = workspace_command.settings();
-    let from_op;
-    let to_op;
-    if args.from.is_some() || args.to.is_some() {
-        from_op = workspace_command.resolve_single_op(args.from.as_deref().unwrap_or("@"))?;
-        to_op = workspace_command.resolve_single_op(args.to.as_deref().unwrap_or("@"))?;
+    if is_inspecting_workable_repo(command) {
+        let workspace_command = command.workspace_helper(ui)?;
+        let current_op = workspace_command.repo().operation();
+        let repo_loader = workspace_command.workspace().repo_loader();
+        do_op_diff(
+            ui,
+            command,
+            workspace_command.env(),
+            repo_loader,
+            slice::from_ref(current_op),
+            args,
+        )
     } else {
-        to_op = workspace_command.resolve_single_op(args.operation.as_deref().unwrap_or("@"))?;
-        let to_op_parents: Vec<_> = to_op.parents().try_collect()?;
-        from_op = repo_loader.merge_operations(to_op_parents, None)?;
+        // Don't merge divergent operations so that each of them can be diffed.
+        let workspace = command.load_workspace()?;
+        let workspace_env = command.workspace_environment(ui, &workspace)?;
+        let repo_loader = workspace.repo_loader();
+        let current_ops = command.resolve_unmerged_operations(repo_loader)?;
+        do_op_diff(ui, command, &workspace_env, repo_loader, &current_ops, args)
     }
+}
+
+fn do_op_diff(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    workspace_env: &WorkspaceCommandEnvironment,
+    repo_loader: &RepoLoader,
+    current_ops: &[Operation],
+    args: &OperationDiffArgs,
+) -> Result<(), CommandError> {
+    let settings = repo_loader.settings();
+    let other_repo = match &args.from_repo {
+        Some(path) => Some(command.load_repo_loader_at(path)?.load_at_head()?),
+        None => None,
+    };
+    let date_context = date_pattern_context(settings);
+    let to_op_str = args.to.as_deref().unwrap_or("@");
+    let op_pairs: Vec<(Operation, Operation)> = if let Some(other_repo) = &other_repo {
+        resolve_ops_at_heads(repo_loader, current_ops, to_op_str, &date_context)?
+            .into_iter()
+            .map(|to_op| (other_repo.operation().clone(), to_op))
+            .collect()
+    } else if args.from.is_some() || args.to.is_some() {
+        let from_op = op_walk::resolve_op_at(
+            repo_loader.op_store(),
+            current_ops,
+            args.from.as_deref().unwrap_or("@"),
+            &date_context,
+        )?;
+        resolve_ops_at_heads(repo_loader, current_ops, to_op_str, &date_context)?
+            .into_iter()
+            .map(|to_op| (from_op.clone(), to_op))
+            .collect()
+    } else {
+        let op_str = args.operation.as_deref().unwrap_or("@");
+        if let Some((from_op, to_ops)) =
+            resolve_op_range(repo_loader, current_ops, op_str, &date_context)?
+        {
+            to_ops
+                .into_iter()
+                .map(|to_op| (from_op.clone(), to_op))
+                .collect()
+        } else {
+            resolve_ops_at_heads(repo_loader, current_ops, op_str, &date_context)?
+                .into_iter()
+                .map(|to_op| -> Result<_, CommandError> {
+                    let to_op_parents: Vec<_> = to_op.parents().try_collect()?;
+                    let from_op = repo_loader.merge_operations(to_op_parents, None)?;
+                    Ok((from_op, to_op))
+                })
+                .try_collect()?
+        }
+    };
     let graph_style = GraphStyle::from_settings(settings)?;
     let with_content_format = LogContentFormat::new(ui, settings)?;
-
-    let from_repo = repo_loader.load_at(&from_op)?;
-    let to_repo = repo_loader.load_at(&to_op)?;
-
-    // Create a new transaction starting from `to_repo`.
-    let mut tx = to_repo.start_transaction();
-    // Merge index from `from_repo` to `to_repo`, so commits in `from_repo` are
-    // accessible.
-    tx.repo_mut().merge_index(&from_repo);
-    let merged_repo = tx.repo();
-
-    let diff_renderer = {
-        let formats = diff_formats_for_log(settings, &args.diff_format, args.patch)?;
-        let path_converter = workspace_env.path_converter();
-        let conflict_marker_style = workspace_env.conflict_marker_style();
-        (!formats.is_empty())
-            .then(|| DiffRenderer::new(merged_repo, path_converter, conflict_marker_style, formats))
+    let path_matcher = if args.paths.is_empty() {
+        None
+    } else {
+        let expression = workspace_env.parse_file_patterns(ui, &args.paths)?;
+        Some(expression.to_matcher())
     };
-    let id_prefix_context = workspace_env.new_id_prefix_context();
-    let commit_summary_template = {
-        let language = workspace_env.commit_template_language(merged_repo, &id_prefix_context);
-        let text = settings.get_string("templates.commit_summary")?;
-        workspace_env.parse_template(ui, &language, &text, CommitTemplateLanguage::wrap_commit)?
+    let formats = diff_formats_for_log(settings, &args.diff_format, args.patch)?;
+    let path_converter = workspace_env.path_converter();
+    let conflict_marker_style = workspace_env.conflict_marker_style();
+    let stat_options = formats.iter().find_map(|format| match format {
+        DiffFormat::Stat(options) => Some(options.as_ref().clone()),
+        _ => None,
+    });
+    let op_summary_template = {
+        let current_op_id = match current_ops {
+            [current_op] => Some(current_op.id()),
+            _ => None,
+        };
+        let language = OperationTemplateLanguage::new(
+            repo_loader,
+            current_op_id,
+            workspace_env.operation_template_extensions(),
+        );
+        let text = settings.get_string("templates.op_summary")?;
+        workspace_env
+            .parse_template(
+                ui,
+                &language,
+                &text,
+                OperationTemplateLanguage::wrap_operation,
+            )?
+            .labeled("operation")
     };
 
-    let op_summary_template = workspace_command.operation_summary_template();
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
-    write!(formatter, "From operation: ")?;
-    op_summary_template.format(&from_op, &mut *formatter)?;
-    writeln!(formatter)?;
-    write!(formatter, "  To operation: ")?;
-    op_summary_template.format(&to_op, &mut *formatter)?;
-    writeln!(formatter)?;
+    for (i, (from_op, to_op)) in op_pairs.iter().enumerate() {
+        if i > 0 {
+            writeln!(formatter)?;
+        }
+        let from_repo = match &other_repo {
+            Some(repo) => repo.clone(),
+            None => repo_loader.load_at(from_op)?,
+        };
+        let to_repo = repo_loader.load_at(to_op)?;
+
+        // Create a new transaction starting from `to_repo`.
+        let mut tx = to_repo.start_transaction();
+        // Merge index from `from_repo` to `to_repo`, so commits in `from_repo` are
+        // accessible.
+        tx.repo_mut().merge_index(&from_repo);
+        let merged_repo = tx.repo();
+
+        let diff_renderer = (!formats.is_empty()).then(|| {
+            DiffRenderer::new(
+                merged_repo,
+                path_converter,
+                conflict_marker_style,
+                formats.clone(),
+            )
+        });
+        let id_prefix_context = workspace_env.new_id_prefix_context();
+        let commit_summary_template;
+        let change_template;
+        {
+            let language = workspace_env.commit_template_language(merged_repo, &id_prefix_context);
+            let text = settings.get_string("templates.commit_summary")?;
+            commit_summary_template = workspace_env.parse_template(
+                ui,
+                &language,
+                &text,
+                CommitTemplateLanguage::wrap_commit,
+            )?;
+            let text = match &args.template {
+                Some(value) => value.to_owned(),
+                None => settings.get_string("templates.op_diff_change")?,
+            };
+            change_template = workspace_env.parse_template(
+                ui,
+                &language,
+                &text,
+                CommitTemplateLanguage::wrap_commit,
+            )?;
+        }
 
-    show_op_diff(
-        ui,
-        formatter.as_mut(),
-        merged_repo,
-        &from_repo,
-        &to_repo,
-        &commit_summary_template,
-        (!args.no_graph).then_some(graph_style),
-        &with_content_format,
-        diff_renderer.as_ref(),
-    )
+        if let Some(path) = &args.from_repo {
+            writeln!(formatter, "From repository: {path}")?;
+        }
+        write!(formatter, "From operation: ")?;
+        op_summary_template.format(from_op, &mut *formatter)?;
+        writeln!(formatter)?;
+        write!(formatter, "  To operation: ")?;
+        op_summary_template.format(to_op, &mut *formatter)?;
+        writeln!(formatter)?;
+
+        show_op_diff(
+            ui,
+            formatter.as_mut(),
+            merged_repo,
+            &from_repo,
+            &to_repo,
+            &commit_summary_template,
+            &change_template,
+            (!args.no_graph).then_some(graph_style),
+            &with_content_format,
+            diff_renderer.as_ref(),
+            path_matcher.as_deref(),
+        )?;
+        if let Some(options) = &stat_options {
+            show_op_diff_stat_total(
+                formatter.as_mut(),
+                merged_repo,
+                &from_repo,
+                &to_repo,
+                path_matcher.as_deref(),
+                path_converter,
+                options,
+                conflict_marker_style,
+                &with_content_format,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Resolves the `x..y` range to the `x` operation and the `y` operations.
+/// Returns `None` if the `op_str` isn't a range. If `y` is omitted, it
+/// defaults to the current head operations.
+fn resolve_op_range(
+    repo_loader: &RepoLoader,
+    current_ops: &[Operation],
+    op_str: &str,
+    date_context: &DatePatternContext,
+) -> Result<Option<(Operation, Vec<Operation>)>, OpsetEvaluationError> {
+    let Ok(OpsetExpression::Range { roots, heads }) = opset::parse(op_str, date_context) else {
+        return Ok(None);
+    };
+    let op_store = repo_loader.op_store();
+    let from_op = op_walk::resolve_expression_at(op_store, current_ops, &roots, op_str)?;
+    let to_ops = match *heads {
+        OpsetExpression::All => current_ops.to_vec(),
+        ref heads => vec![op_walk::resolve_expression_at(
+            op_store,
+            current_ops,
+            heads,
+            op_str,
+        )?],
+    };
+    Ok(Some((from_op, to_ops)))
 }
 
 /// Computes and shows the differences between two operations, using the given
 /// `ReadonlyRepo`s for the operations.
 /// `current_repo` should contain a `Repo` with the indices of both repos merged
-/// into it.
+/// into it. Added and removed commits of changes are rendered using
+/// `change_template`. If `path_matcher` is given, only changes modifying the
+/// matching paths are shown, and their diffs are limited to those paths.
 #[allow(clippy::too_many_arguments)]
 pub fn show_op_diff(
     ui: &Ui,
@@ -169,11 +394,17 @@ pub fn show_op_diff(
     from_repo: &Arc<ReadonlyRepo>,
     to_repo: &Arc<ReadonlyRepo>,
     commit_summary_template: &TemplateRenderer<Commit>,
+    change_template: &TemplateRenderer<Commit>,
     graph_style: Option<GraphStyle>,
     with_content_format: &LogContentFormat,
     diff_renderer: Option<&DiffRenderer>,
+    path_matcher: Option<&dyn Matcher>,
 ) -> Result<(), CommandError> {
-    let changes = compute_operation_commits_diff(current_repo, from_repo, to_repo)?;
+    let mut changes = compute_operation_commits_diff(current_repo, from_repo, to_repo)?;
+    if let Some(matcher) = path_matcher {
+        changes = filter_changes_by_paths(current_repo, changes, matcher)?;
+    }
+    let matcher = path_matcher.unwrap_or(&EverythingMatcher);
 
     let commit_id_change_id_map: HashMap<CommitId, ChangeId> = changes
         .iter()
@@ -230,11 +461,7 @@ pub fn show_op_diff(
                 let mut buffer = vec![];
                 let within_graph = with_content_format.sub_width(graph.width(&change_id, &edges));
                 within_graph.write(ui.new_formatter(&mut buffer).as_mut(), |formatter| {
-                    write_modified_change_summary(
-                        formatter,
-                        commit_summary_template,
-                        modified_change,
-                    )
+                    write_modified_change_summary(formatter, change_template, modified_change)
                 })?;
                 if !buffer.ends_with(b"\n") {
                     buffer.push(b'\n');
@@ -244,8 +471,10 @@ pub fn show_op_diff(
                     show_change_diff(
                         ui,
                         formatter.as_mut(),
+                        current_repo,
                         diff_renderer,
                         modified_change,
+                        matcher,
                         within_graph.width(),
                     )?;
                 }
@@ -263,20 +492,64 @@ pub fn show_op_diff(
             for change_id in ordered_change_ids {
                 let modified_change = changes.get(&change_id).unwrap();
                 with_content_format.write(formatter, |formatter| {
-                    write_modified_change_summary(
-                        formatter,
-                        commit_summary_template,
-                        modified_change,
-                    )
+                    write_modified_change_summary(formatter, change_template, modified_change)
                 })?;
                 if let Some(diff_renderer) = &diff_renderer {
                     let width = with_content_format.width();
-                    show_change_diff(ui, formatter, diff_renderer, modified_change, width)?;
+                    show_change_diff(
+                        ui,
+                        formatter,
+                        current_repo,
+                        diff_renderer,
+                        modified_change,
+                        matcher,
+                        width,
+                    )?;
                 }
             }
         }
     }
 
+    let from_wc_commit_ids = from_repo.view().wc_commit_ids();
+    let to_wc_commit_ids = to_repo.view().wc_commit_ids();
+    let changed_working_copies =
+        itertools::chain(from_wc_commit_ids.keys(), to_wc_commit_ids.keys())
+            .sorted()
+            .dedup()
+            .filter_map(|workspace_id| {
+                let from_id = from_wc_commit_ids.get(workspace_id);
+                let to_id = to_wc_commit_ids.get(workspace_id);
+                (from_id != to_id).then_some((workspace_id, from_id, to_id))
+            })
+            .collect_vec();
+    if !changed_working_copies.is_empty() {
+        writeln!(formatter)?;
+        with_content_format.write(formatter, |formatter| {
+            writeln!(formatter, "Changed working copies:")
+        })?;
+        for (workspace_id, from_id, to_id) in changed_working_copies {
+            with_content_format.write(formatter, |formatter| {
+                writeln!(formatter, "{}:", workspace_id.as_str())?;
+                write_ref_target_summary(
+                    formatter,
+                    current_repo,
+                    commit_summary_template,
+                    &RefTarget::resolved(to_id.cloned()),
+                    true,
+                    None,
+                )?;
+                write_ref_target_summary(
+                    formatter,
+                    current_repo,
+                    commit_summary_template,
+                    &RefTarget::resolved(from_id.cloned()),
+                    false,
+                    None,
+                )
+            })?;
+        }
+    }
+
     let changed_local_bookmarks = diff_named_ref_targets(
         from_repo.view().local_bookmarks(),
         to_repo.view().local_bookmarks(),
@@ -382,16 +655,73 @@ pub fn show_op_diff(
     Ok(())
 }
 
+/// Shows the total number of files and lines changed by the modified changes
+/// between two operations, counted the same way as the diff stat of each
+/// change.
+#[allow(clippy::too_many_arguments)]
+fn show_op_diff_stat_total(
+    formatter: &mut dyn Formatter,
+    current_repo: &dyn Repo,
+    from_repo: &ReadonlyRepo,
+    to_repo: &ReadonlyRepo,
+    path_matcher: Option<&dyn Matcher>,
+    path_converter: &RepoPathUiConverter,
+    options: &DiffStatOptions,
+    conflict_marker_style: ConflictMarkerStyle,
+    with_content_format: &LogContentFormat,
+) -> Result<(), CommandError> {
+    let mut changes = compute_operation_commits_diff(current_repo, from_repo, to_repo)?;
+    if let Some(matcher) = path_matcher {
+        changes = filter_changes_by_paths(current_repo, changes, matcher)?;
+    }
+    let matcher = path_matcher.unwrap_or(&EverythingMatcher);
+    let mut num_changes = 0;
+    let mut files = 0;
+    let mut added = 0;
+    let mut removed = 0;
+    for modified_change in changes.values() {
+        for diff in get_change_diffs(current_repo, modified_change, matcher)? {
+            let tree_diff =
+                diff.from_tree
+                    .diff_stream_with_copies(&diff.to_tree, matcher, &diff.copy_records);
+            let stats = DiffStats::calculate(
+                current_repo.store(),
+                tree_diff,
+                path_converter,
+                options,
+                conflict_marker_style,
+            )?;
+            files += stats.count_files();
+            added += stats.count_lines_added();
+            removed += stats.count_lines_removed();
+        }
+        num_changes += 1;
+    }
+    if num_changes == 0 {
+        return Ok(());
+    }
+    writeln!(formatter)?;
+    with_content_format.write(formatter, |formatter| {
+        write!(
+            formatter,
+            "Total for {num_changes} change{}: ",
+            if num_changes == 1 { "" } else { "s" }
+        )?;
+        write_diff_stat_summary(formatter, files, added, removed)
+    })?;
+    Ok(())
+}
+
 /// Writes a summary for the given `ModifiedChange`.
-fn write_modified_change_summary(
+pub(super) fn write_modified_change_summary(
     formatter: &mut dyn Formatter,
-    commit_summary_template: &TemplateRenderer<Commit>,
+    change_template: &TemplateRenderer<Commit>,
     modified_change: &ModifiedChange,
 ) -> Result<(), std::io::Error> {
     for commit in &modified_change.added_commits {
         formatter.with_label("diff", |formatter| write!(formatter.labeled("added"), "+"))?;
         write!(formatter, " ")?;
-        commit_summary_template.format(commit, formatter)?;
+        change_template.format(commit, formatter)?;
         writeln!(formatter)?;
     }
     for commit in &modified_change.removed_commits {
@@ -399,14 +729,14 @@ fn write_modified_change_summary(
             write!(formatter.labeled("removed"), "-")
         })?;
         write!(formatter, " ")?;
-        commit_summary_template.format(commit, formatter)?;
+        change_template.format(commit, formatter)?;
         writeln!(formatter)?;
     }
     Ok(())
 }
 
 /// Writes a summary for the given `RefTarget`.
-fn write_ref_target_summary(
+pub(super) fn write_ref_target_summary(
     formatter: &mut dyn Formatter,
     repo: &dyn Repo,
     commit_summary_template: &TemplateRenderer<Commit>,
@@ -486,91 +816,150 @@ fn get_parent_changes(
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct ModifiedChange {
-    added_commits: Vec<Commit>,
-    removed_commits: Vec<Commit>,
-}
-
-/// Compute the changes in commits between two operations, returned as a
-/// `HashMap` from `ChangeId` to a `ModifiedChange` struct containing the added
-/// and removed commits for the change ID.
-fn compute_operation_commits_diff(
+/// Keeps the changes which have a commit modifying paths matched by `matcher`
+/// compared to its parents.
+fn filter_changes_by_paths(
     repo: &dyn Repo,
-    from_repo: &ReadonlyRepo,
-    to_repo: &ReadonlyRepo,
+    changes: IndexMap<ChangeId, ModifiedChange>,
+    matcher: &dyn Matcher,
 ) -> Result<IndexMap<ChangeId, ModifiedChange>, CommandError> {
-    let mut changes: IndexMap<ChangeId, ModifiedChange> = IndexMap::new();
-
-    let from_heads = from_repo.view().heads().iter().cloned().collect_vec();
-    let to_heads = to_repo.view().heads().iter().cloned().collect_vec();
-
-    // Find newly added commits in `to_repo` which were not present in
-    // `from_repo`.
-    for commit in revset::walk_revs(repo, &to_heads, &from_heads)?
-        .iter()
-        .commits(repo.store())
-    {
-        let commit = commit?;
-        let modified_change = changes
-            .entry(commit.change_id().clone())
-            .or_insert_with(|| ModifiedChange {
-                added_commits: vec![],
-                removed_commits: vec![],
-            });
-        modified_change.added_commits.push(commit);
+    let mut matching_changes = IndexMap::new();
+    for (change_id, modified_change) in changes {
+        let mut is_matching = false;
+        for commit in itertools::chain(
+            &modified_change.added_commits,
+            &modified_change.removed_commits,
+        ) {
+            let from_tree = commit.parent_tree(repo)?;
+            let to_tree = commit.tree()?;
+            let mut tree_diff = from_tree.diff_stream(&to_tree, matcher);
+            if let Some(entry) = tree_diff.next().block_on() {
+                entry.values?;
+                is_matching = true;
+                break;
+            }
+        }
+        if is_matching {
+            matching_changes.insert(change_id, modified_change);
+        }
     }
+    Ok(matching_changes)
+}
 
-    // Find commits which were hidden in `to_repo`.
-    for commit in revset::walk_revs(repo, &from_heads, &to_heads)?
-        .iter()
-        .commits(repo.store())
-    {
-        let commit = commit?;
-        let modified_change = changes
-            .entry(commit.change_id().clone())
-            .or_insert_with(|| ModifiedChange {
-                added_commits: vec![],
-                removed_commits: vec![],
-            });
-        modified_change.removed_commits.push(commit);
-    }
+/// Diff to show for one of the commits of a modified change.
+struct ChangeCommitDiff<'a> {
+    commit: &'a Commit,
+    /// Whether the diff undoes the commit's contents, which is the case if the
+    /// change was removed.
+    reversed: bool,
+    from_tree: MergedTree,
+    to_tree: MergedTree,
+    copy_records: CopyRecords,
+}
 
-    Ok(changes)
+/// Returns the diffs to show for a modified change.
+///
+/// For each added commit, the diff is computed from the removed commits rebased
+/// onto the added commit's parents, so the diff is not contaminated by
+/// unrelated changes. If the change has no added commits, the reverse patches
+/// of the removed commits are returned since the operation removed their
+/// contents from the visible history.
+fn get_change_diffs<'a>(
+    repo: &dyn Repo,
+    change: &'a ModifiedChange,
+    matcher: &dyn Matcher,
+) -> Result<Vec<ChangeCommitDiff<'a>>, CommandError> {
+    if change.added_commits.is_empty() {
+        assert!(
+            !change.removed_commits.is_empty(),
+            "ModifiedChange should have at least one entry"
+        );
+        change
+            .removed_commits
+            .iter()
+            .map(|commit| {
+                let mut copy_records = CopyRecords::default();
+                for parent_id in commit.parent_ids() {
+                    let records = get_copy_records(repo.store(), commit.id(), parent_id, matcher)?;
+                    copy_records.add_records(records)?;
+                }
+                Ok(ChangeCommitDiff {
+                    commit,
+                    reversed: true,
+                    from_tree: commit.tree()?,
+                    to_tree: commit.parent_tree(repo)?,
+                    copy_records,
+                })
+            })
+            .try_collect()
+    } else {
+        change
+            .added_commits
+            .iter()
+            .map(|commit| {
+                let from_tree = rebase_to_dest_parent(repo, &change.removed_commits, commit)?;
+                Ok(ChangeCommitDiff {
+                    commit,
+                    reversed: false,
+                    from_tree,
+                    to_tree: commit.tree()?,
+                    copy_records: CopyRecords::default(),
+                })
+            })
+            .try_collect()
+    }
 }
 
-/// Displays the diffs of a modified change. The output differs based on the
-/// commits added and removed for the change.
-/// If there is a single added and removed commit, the diff is shown between the
-/// removed commit and the added commit rebased onto the removed commit's
-/// parents. If there is only a single added or single removed commit, the diff
-/// is shown of that commit's contents.
+/// Displays the diffs of a modified change as computed by
+/// `get_change_diffs()`. Non-empty reverse diffs, and non-empty diffs of
+/// changes with multiple (divergent) commits, are preceded by a header naming
+/// the commit.
+#[allow(clippy::too_many_arguments)]
 fn show_change_diff(
     ui: &Ui,
     formatter: &mut dyn Formatter,
+    repo: &dyn Repo,
     diff_renderer: &DiffRenderer,
     change: &ModifiedChange,
+    matcher: &dyn Matcher,
     width: usize,
 ) -> Result<(), CommandError> {
-    match (&*change.removed_commits, &*change.added_commits) {
-        (predecessors @ ([] | [_]), [commit]) => {
-            // New or modified change. If the modification involved a rebase,
-            // show diffs from the rebased tree.
-            diff_renderer.show_inter_diff(
-                ui,
-                formatter,
-                predecessors,
-                commit,
-                &EverythingMatcher,
-                width,
-            )?;
-        }
-        ([commit], []) => {
-            // TODO: Should we show a reverse diff?
-            diff_renderer.show_patch(ui, formatter, commit, &EverythingMatcher, width)?;
+    let diffs = get_change_diffs(repo, change, matcher)?;
+    let needs_header = diffs.len() > 1;
+    for diff in &diffs {
+        let is_empty = diff.from_tree.id() == diff.to_tree.id();
+        if (needs_header || diff.reversed) && !is_empty {
+            write_change_commit_header(formatter, diff.commit, diff.reversed)?;
         }
-        ([_, _, ..], _) | (_, [_, _, ..]) => {}
-        ([], []) => panic!("ModifiedChange should have at least one entry"),
+        diff_renderer.show_diff(
+            ui,
+            formatter,
+            &diff.from_tree,
+            &diff.to_tree,
+            matcher,
+            &diff.copy_records,
+            width,
+        )?;
     }
     Ok(())
 }
+
+/// Writes a header to tell apart diffs of the commits of a change, and to
+/// indicate the direction of reverse diffs.
+fn write_change_commit_header(
+    formatter: &mut dyn Formatter,
+    commit: &Commit,
+    reversed: bool,
+) -> Result<(), std::io::Error> {
+    if reversed {
+        write!(formatter, "Reverse diff of removed commit ")?;
+    } else {
+        write!(formatter, "Commit ")?;
+    }
+    write!(
+        formatter.labeled("commit_id"),
+        "{}",
+        short_commit_hash(commit.id())
+    )?;
+    writeln!(formatter, ":")
+}
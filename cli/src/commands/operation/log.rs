@@ -12,20 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::Write as _;
 use std::slice;
+use std::time::Duration;
 
 use itertools::Itertools as _;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::graph::reverse_graph;
 use jj_lib::graph::GraphEdge;
-use jj_lib::op_store::OpStoreError;
+use jj_lib::graph::GraphNode;
+use jj_lib::op_heads_store::OpHeadsWatcher;
+use jj_lib::op_store::OpStoreResult;
+use jj_lib::op_store::OperationId;
 use jj_lib::op_walk;
 use jj_lib::operation::Operation;
+use jj_lib::opset;
 use jj_lib::repo::RepoLoader;
 use jj_lib::settings::UserSettings;
 
 use super::diff::show_op_diff;
+use super::is_inspecting_workable_repo;
+use crate::cli_util::date_pattern_context;
 use crate::cli_util::format_template;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
@@ -46,8 +56,29 @@ use crate::ui::Ui;
 /// Like other commands, `jj op log` snapshots the current working-copy changes
 /// and reconciles divergent operations. Use `--at-op=@ --ignore-working-copy`
 /// to inspect the current state without mutation.
+///
+/// With `--follow`, `jj op log` keeps running after showing the log, and
+/// prints new operations as they're created, e.g. by a script or another
+/// workspace. New operations are shown oldest first, without the graph.
+///
+/// With `--at-op` or `--ignore-working-copy`, divergent operations aren't
+/// reconciled, and the history of all operation heads is shown. Use
+/// `--op-heads` to list the operation heads only.
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationLogArgs {
+    /// Which operations to show
+    ///
+    /// The opset language supports operation IDs and symbols like `@-`,
+    /// ranges like `abc123..@`, `|`/`&`/`~` operators, and the `all()`,
+    /// `ancestors(x)`, `snapshots()`, `user(pattern)`,
+    /// `description(pattern)`, `after(date)` and `before(date)` functions.
+    #[arg(long, value_name = "OPSET")]
+    ops: Option<String>,
+    /// Show the current operation heads only
+    ///
+    /// Divergent operations aren't reconciled, so each of them is listed.
+    #[arg(long, conflicts_with = "ops")]
+    op_heads: bool,
     /// Limit number of operations to show
     ///
     /// Applied after operations are reordered.
@@ -56,6 +87,9 @@ pub struct OperationLogArgs {
     /// Show operations in the opposite order (older operations first)
     #[arg(long)]
     reversed: bool,
+    /// Keep running and show new operations as they're created
+    #[arg(long, conflicts_with = "reversed")]
+    follow: bool,
     /// Don't show the graph, show a flat list of operations
     #[arg(long)]
     no_graph: bool,
@@ -74,6 +108,12 @@ pub struct OperationLogArgs {
     /// contaminated by unrelated changes.
     #[arg(long, short = 'p')]
     patch: bool,
+    /// Show only changes to these paths
+    ///
+    /// Changes are shown if any of their commits modifies a matching path.
+    /// Diffs are limited to the matching paths.
+    #[arg(long, value_name = "FILESETS", value_hint = clap::ValueHint::AnyPath)]
+    paths: Vec<String>,
     #[command(flatten)]
     diff_format: DiffFormatArgs,
 }
@@ -83,20 +123,27 @@ pub fn cmd_op_log(
     command: &CommandHelper,
     args: &OperationLogArgs,
 ) -> Result<(), CommandError> {
-    if command.is_working_copy_writable() {
+    if is_inspecting_workable_repo(command) && !args.op_heads {
         let workspace_command = command.workspace_helper(ui)?;
         let current_op = workspace_command.repo().operation();
         let repo_loader = workspace_command.workspace().repo_loader();
-        do_op_log(ui, workspace_command.env(), repo_loader, current_op, args)
+        do_op_log(
+            ui,
+            workspace_command.env(),
+            repo_loader,
+            slice::from_ref(current_op),
+            args,
+        )
     } else {
         // Don't load the repo so that the operation history can be inspected
         // even with a corrupted repo state. For example, you can find the first
-        // bad operation id to be abandoned.
+        // bad operation id to be abandoned. Divergent operations aren't merged
+        // for the same reason.
         let workspace = command.load_workspace()?;
         let workspace_env = command.workspace_environment(ui, &workspace)?;
         let repo_loader = workspace.repo_loader();
-        let current_op = command.resolve_operation(ui, workspace.repo_loader())?;
-        do_op_log(ui, &workspace_env, repo_loader, &current_op, args)
+        let current_ops = command.resolve_unmerged_operations(repo_loader)?;
+        do_op_log(ui, &workspace_env, repo_loader, &current_ops, args)
     }
 }
 
@@ -104,7 +151,7 @@ fn do_op_log(
     ui: &mut Ui,
     workspace_env: &WorkspaceCommandEnvironment,
     repo_loader: &RepoLoader,
-    current_op: &Operation,
+    current_ops: &[Operation],
     args: &OperationLogArgs,
 ) -> Result<(), CommandError> {
     let settings = repo_loader.settings();
@@ -114,9 +161,13 @@ fn do_op_log(
     let template;
     let op_node_template;
     {
+        let current_op_id = match current_ops {
+            [current_op] => Some(current_op.id()),
+            _ => None,
+        };
         let language = OperationTemplateLanguage::new(
             repo_loader,
-            Some(current_op.id()),
+            current_op_id,
             workspace_env.operation_template_extensions(),
         );
         let text = match &args.template {
@@ -143,8 +194,15 @@ fn do_op_log(
     }
 
     let diff_formats = diff_formats_for_log(settings, &args.diff_format, args.patch)?;
+    let path_matcher = if args.paths.is_empty() {
+        None
+    } else {
+        let expression = workspace_env.parse_file_patterns(ui, &args.paths)?;
+        Some(expression.to_matcher())
+    };
     let maybe_show_op_diff = if args.op_diff || !diff_formats.is_empty() {
         let template_text = settings.get_string("templates.commit_summary")?;
+        let change_template_text = settings.get_string("templates.op_diff_change")?;
         let show = move |ui: &Ui,
                          formatter: &mut dyn Formatter,
                          op: &Operation,
@@ -155,16 +213,24 @@ fn do_op_log(
             let repo = repo_loader.load_at(op)?;
 
             let id_prefix_context = workspace_env.new_id_prefix_context();
-            let commit_summary_template = {
+            let commit_summary_template;
+            let change_template;
+            {
                 let language =
                     workspace_env.commit_template_language(repo.as_ref(), &id_prefix_context);
-                workspace_env.parse_template(
+                commit_summary_template = workspace_env.parse_template(
                     ui,
                     &language,
                     &template_text,
                     CommitTemplateLanguage::wrap_commit,
-                )?
-            };
+                )?;
+                change_template = workspace_env.parse_template(
+                    ui,
+                    &language,
+                    &change_template_text,
+                    CommitTemplateLanguage::wrap_commit,
+                )?;
+            }
             let path_converter = workspace_env.path_converter();
             let conflict_marker_style = workspace_env.conflict_marker_style();
             let diff_renderer = (!diff_formats.is_empty()).then(|| {
@@ -183,9 +249,11 @@ fn do_op_log(
                 &parent_repo,
                 &repo,
                 &commit_summary_template,
+                &change_template,
                 (!args.no_graph).then_some(graph_style),
                 with_content_format,
                 diff_renderer.as_ref(),
+                path_matcher.as_deref(),
             )
         };
         Some(show)
@@ -193,20 +261,52 @@ fn do_op_log(
         None
     };
 
-    ui.request_pager();
+    // Start watching before the log is shown so that no operation created in
+    // the meantime is missed.
+    let watcher = if args.follow {
+        Some(OpHeadsWatcher::new(
+            repo_loader.op_heads_store().clone(),
+            FOLLOW_POLL_INTERVAL,
+        )?)
+    } else {
+        ui.request_pager();
+        None
+    };
     let mut formatter = ui.stdout_formatter();
     let formatter = formatter.as_mut();
     let limit = args.limit.unwrap_or(usize::MAX);
-    let iter = op_walk::walk_ancestors(slice::from_ref(current_op));
+    let expression = args
+        .ops
+        .as_ref()
+        .map(|text| opset::parse(text, &date_pattern_context(settings)))
+        .transpose()?;
+    let is_selected = args.op_heads || expression.is_some();
+    let iter: Box<dyn Iterator<Item = Result<Operation, CommandError>>> = if args.op_heads {
+        // Show the latest head first, in the same order as walk_ancestors().
+        let mut ops = current_ops.to_vec();
+        ops.sort_by_key(|op| op.metadata().end_time.timestamp);
+        ops.reverse();
+        Box::new(ops.into_iter().map(Ok))
+    } else if let Some(expression) = &expression {
+        let ops = op_walk::resolve_opset_at(repo_loader.op_store(), current_ops, expression)?;
+        Box::new(ops.map(|op| op.map_err(CommandError::from)))
+    } else {
+        Box::new(op_walk::walk_ancestors(current_ops).map(|op| op.map_err(CommandError::from)))
+    };
 
     if !args.no_graph {
         let mut raw_output = formatter.raw()?;
         let mut graph = get_graphlog(graph_style, raw_output.as_mut());
-        let iter = iter.map(|op| -> Result<_, OpStoreError> {
-            let op = op?;
-            let edges = op.parents().map_ok(GraphEdge::direct).try_collect()?;
-            Ok((op, edges))
-        });
+        let iter: Box<dyn Iterator<Item = Result<_, CommandError>>> = if is_selected {
+            let ops: Vec<_> = iter.try_collect()?;
+            Box::new(selected_op_graph(ops)?.into_iter().map(Ok))
+        } else {
+            Box::new(iter.map(|op| -> Result<_, CommandError> {
+                let op = op?;
+                let edges = op.parents().map_ok(GraphEdge::direct).try_collect()?;
+                Ok((op, edges))
+            }))
+        };
         let iter_nodes: Box<dyn Iterator<Item = _>> = if args.reversed {
             Box::new(reverse_graph(iter)?.into_iter().map(Ok))
         } else {
@@ -253,9 +353,114 @@ fn do_op_log(
         }
     }
 
+    if let Some(watcher) = watcher {
+        formatter.flush()?;
+        follow_operations(repo_loader, watcher, current_ops, |op| {
+            with_content_format.write(formatter, |formatter| template.format(op, formatter))?;
+            if let Some(show) = &maybe_show_op_diff {
+                show(ui, formatter, op, &with_content_format)?;
+            }
+            formatter.flush()?;
+            Ok(())
+        })?;
+    }
+
     Ok(())
 }
 
+/// How often the operation heads are checked for new operations by `--follow`.
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Calls `show_op` for each operation created after the `current_ops` as the
+/// `watcher` reports new operation heads. Never returns unless an error occurs.
+fn follow_operations(
+    repo_loader: &RepoLoader,
+    mut watcher: OpHeadsWatcher,
+    current_ops: &[Operation],
+    mut show_op: impl FnMut(&Operation) -> Result<(), CommandError>,
+) -> Result<(), CommandError> {
+    let mut seen_ids: HashSet<OperationId> = op_walk::walk_ancestors(current_ops)
+        .map_ok(|op| op.id().clone())
+        .try_collect()?;
+    // The heads may have changed before the watcher was created.
+    let mut head_ids = watcher.op_heads().to_vec();
+    loop {
+        // Walk back from the heads until we reach operations we've seen.
+        let mut new_ops = vec![];
+        let mut pending_ops: Vec<Operation> = head_ids
+            .iter()
+            .map(|id| repo_loader.load_operation(id))
+            .try_collect()?;
+        while let Some(op) = pending_ops.pop() {
+            if !seen_ids.insert(op.id().clone()) {
+                continue;
+            }
+            for parent in op.parents() {
+                pending_ops.push(parent?);
+            }
+            new_ops.push(op);
+        }
+        // Parents always end before their children.
+        new_ops.sort_by_key(|op| op.metadata().end_time.timestamp);
+        for op in &new_ops {
+            show_op(op)?;
+        }
+        head_ids = watcher.wait()?.to_vec();
+    }
+}
+
+/// Builds graph nodes of the `selected_ops`, which must be sorted in reverse
+/// topological order. Each operation gets edges to its nearest selected
+/// ancestors. Edges to operations which aren't direct parents are indirect.
+///
+/// The operation log is walked only until all selected operations are visited.
+fn selected_op_graph(selected_ops: Vec<Operation>) -> OpStoreResult<Vec<GraphNode<Operation>>> {
+    let selected_ids: HashSet<&OperationId> = selected_ops.iter().map(|op| op.id()).collect();
+    let mut edges_map: HashMap<OperationId, Vec<GraphEdge<Operation>>> = HashMap::new();
+    // Selected operations reaching the key operation only through unselected
+    // ones, and whether the key is a direct parent of them.
+    let mut pending: HashMap<OperationId, Vec<(OperationId, bool)>> = HashMap::new();
+    let mut remaining = selected_ids.len();
+    for op in op_walk::walk_ancestors(&selected_ops) {
+        if remaining == 0 {
+            break;
+        }
+        let op = op?;
+        let descendants = pending.remove(op.id()).unwrap_or_default();
+        let reaching = if selected_ids.contains(op.id()) {
+            remaining -= 1;
+            for (id, direct) in descendants {
+                let edge = if direct {
+                    GraphEdge::direct(op.clone())
+                } else {
+                    GraphEdge::indirect(op.clone())
+                };
+                edges_map.entry(id).or_default().push(edge);
+            }
+            vec![(op.id().clone(), true)]
+        } else {
+            descendants.into_iter().map(|(id, _)| (id, false)).collect()
+        };
+        for parent_id in op.parent_ids() {
+            let entries = pending.entry(parent_id.clone()).or_default();
+            for (id, direct) in &reaching {
+                match entries.iter_mut().find(|(other, _)| other == id) {
+                    Some((_, other_direct)) => *other_direct |= direct,
+                    None => entries.push((id.clone(), *direct)),
+                }
+            }
+        }
+    }
+    let nodes = selected_ops
+        .into_iter()
+        .map(|op| {
+            let edges = edges_map.remove(op.id()).unwrap_or_default();
+            (op, edges)
+        })
+        .collect();
+    Ok(nodes)
+}
+
 fn get_node_template(style: GraphStyle, settings: &UserSettings) -> Result<String, ConfigGetError> {
     let symbol = settings.get_string("templates.op_log_node").optional()?;
     let default = if style.is_ascii() {
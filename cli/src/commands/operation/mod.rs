@@ -12,10 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-mod abandon;
+pub mod abandon;
 mod diff;
 mod log;
 mod restore;
+mod revert;
 mod show;
 pub mod undo;
 
@@ -24,10 +25,20 @@ use abandon::OperationAbandonArgs;
 use clap::Subcommand;
 use diff::cmd_op_diff;
 use diff::OperationDiffArgs;
+use jj_lib::backend::BackendResult;
+use jj_lib::op_store;
+use jj_lib::op_walk;
+use jj_lib::op_walk::OpsetEvaluationError;
+use jj_lib::operation::Operation;
+use jj_lib::repo::MutableRepo;
+use jj_lib::repo::RepoLoader;
+use jj_lib::time_util::DatePatternContext;
 use log::cmd_op_log;
 use log::OperationLogArgs;
 use restore::cmd_op_restore;
 use restore::OperationRestoreArgs;
+use revert::cmd_op_revert;
+use revert::OperationRevertArgs;
 use show::cmd_op_show;
 use show::OperationShowArgs;
 use undo::cmd_op_undo;
@@ -47,6 +58,7 @@ pub enum OperationCommand {
     Diff(OperationDiffArgs),
     Log(OperationLogArgs),
     Restore(OperationRestoreArgs),
+    Revert(OperationRevertArgs),
     Show(OperationShowArgs),
     Undo(OperationUndoArgs),
 }
@@ -61,15 +73,48 @@ pub fn cmd_operation(
         OperationCommand::Diff(args) => cmd_op_diff(ui, command, args),
         OperationCommand::Log(args) => cmd_op_log(ui, command, args),
         OperationCommand::Restore(args) => cmd_op_restore(ui, command, args),
+        OperationCommand::Revert(args) => cmd_op_revert(ui, command, args),
         OperationCommand::Show(args) => cmd_op_show(ui, command, args),
         OperationCommand::Undo(args) => cmd_op_undo(ui, command, args),
     }
 }
 
+/// Returns true if the working copy should be snapshotted and divergent
+/// operations be reconciled before inspecting the operation history.
+///
+/// If `--at-op` is specified, even if it's `@`, the history is inspected as is.
+fn is_inspecting_workable_repo(command: &CommandHelper) -> bool {
+    command.is_working_copy_writable() && command.global_args().at_operation.is_none()
+}
+
+/// Resolves `op_str` at the given `head_ops`. If `op_str` is `@`, all head
+/// operations are returned, which may be more than one if there are divergent
+/// operations.
+fn resolve_ops_at_heads(
+    repo_loader: &RepoLoader,
+    head_ops: &[Operation],
+    op_str: &str,
+    date_context: &DatePatternContext,
+) -> Result<Vec<Operation>, OpsetEvaluationError> {
+    if op_str == "@" {
+        Ok(head_ops.to_vec())
+    } else {
+        let op = op_walk::resolve_op_at(repo_loader.op_store(), head_ops, op_str, date_context)?;
+        Ok(vec![op])
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
 enum UndoWhatToRestore {
     /// The jj repo state and local bookmarks
     Repo,
+    /// The visible commits, without moving any refs. Commits pointed to by
+    /// the refs are kept visible
+    Heads,
+    /// The local bookmarks and tags
+    Bookmarks,
+    /// The working-copy commits of all workspaces
+    WorkingCopy,
     /// The remote-tracking bookmarks. Do not restore these if you'd like to
     /// push after the undo
     RemoteTracking,
@@ -79,28 +124,30 @@ const DEFAULT_UNDO_WHAT: [UndoWhatToRestore; 2] =
     [UndoWhatToRestore::Repo, UndoWhatToRestore::RemoteTracking];
 
 /// Restore only the portions of the view specified by the `what` argument
-fn view_with_desired_portions_restored(
-    view_being_restored: &jj_lib::op_store::View,
-    current_view: &jj_lib::op_store::View,
+fn restore_view_portions(
+    mut_repo: &mut MutableRepo,
+    view_being_restored: &op_store::View,
     what: &[UndoWhatToRestore],
-) -> jj_lib::op_store::View {
-    let repo_source = if what.contains(&UndoWhatToRestore::Repo) {
-        view_being_restored
-    } else {
-        current_view
-    };
-    let remote_source = if what.contains(&UndoWhatToRestore::RemoteTracking) {
-        view_being_restored
-    } else {
-        current_view
+) -> BackendResult<()> {
+    // `repo` is the combination of heads, bookmarks, and working copies.
+    let restores = |portion| {
+        what.contains(&portion)
+            || (portion != UndoWhatToRestore::RemoteTracking
+                && what.contains(&UndoWhatToRestore::Repo))
     };
-    jj_lib::op_store::View {
-        head_ids: repo_source.head_ids.clone(),
-        local_bookmarks: repo_source.local_bookmarks.clone(),
-        tags: repo_source.tags.clone(),
-        remote_views: remote_source.remote_views.clone(),
-        git_refs: current_view.git_refs.clone(),
-        git_head: current_view.git_head.clone(),
-        wc_commit_ids: repo_source.wc_commit_ids.clone(),
+    if restores(UndoWhatToRestore::Bookmarks) {
+        mut_repo.restore_local_bookmarks(view_being_restored);
+        mut_repo.restore_tags(view_being_restored);
+    }
+    if restores(UndoWhatToRestore::WorkingCopy) {
+        mut_repo.restore_wc_commits(view_being_restored)?;
+    }
+    if restores(UndoWhatToRestore::RemoteTracking) {
+        mut_repo.restore_remote_views(view_being_restored);
+    }
+    // The heads are restored last so that the restored refs stay visible.
+    if restores(UndoWhatToRestore::Heads) {
+        mut_repo.restore_heads(view_being_restored)?;
     }
+    Ok(())
 }
@@ -15,7 +15,7 @@
 use clap_complete::ArgValueCandidates;
 use jj_lib::object_id::ObjectId;
 
-use super::view_with_desired_portions_restored;
+use super::restore_view_portions;
 use super::UndoWhatToRestore;
 use super::DEFAULT_UNDO_WHAT;
 use crate::cli_util::CommandHelper;
@@ -52,12 +52,7 @@ pub fn cmd_op_restore(
     let mut workspace_command = command.workspace_helper(ui)?;
     let target_op = workspace_command.resolve_single_op(&args.operation)?;
     let mut tx = workspace_command.start_transaction();
-    let new_view = view_with_desired_portions_restored(
-        target_op.view()?.store_view(),
-        tx.base_repo().view().store_view(),
-        &args.what,
-    );
-    tx.repo_mut().set_view(new_view);
+    restore_view_portions(tx.repo_mut(), target_op.view()?.store_view(), &args.what)?;
     if let Some(mut formatter) = ui.status_formatter() {
         write!(formatter, "Restored to operation: ")?;
         let template = tx.base_workspace_helper().operation_summary_template();
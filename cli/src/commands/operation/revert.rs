@@ -0,0 +1,243 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::object_id::ObjectId;
+use jj_lib::op_store::RefTarget;
+use jj_lib::op_store::RemoteRef;
+use jj_lib::op_store::RemoteRefState;
+use jj_lib::refs::diff_named_ref_targets;
+use jj_lib::refs::diff_named_remote_refs;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo;
+
+use super::diff::write_modified_change_summary;
+use super::diff::write_ref_target_summary;
+use crate::cli_util::short_change_hash;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::formatter::Formatter;
+use crate::operation_templater::compute_operation_commits_diff;
+use crate::ui::Ui;
+
+/// Create a new operation that reverts the changes of an earlier operation
+///
+/// The commits, local bookmarks, tags, and remote bookmarks changed by the
+/// operation are computed like `jj op diff` does, and the inverse of these
+/// changes is applied on top of the current operation. Later operations are
+/// kept, unlike with `jj op restore`. Other changes of the operation, such as to
+/// the working-copy commit, aren't reverted unless they follow from the reverted
+/// commits.
+#[derive(clap::Args, Clone, Debug)]
+pub struct OperationRevertArgs {
+    /// The operation to revert
+    ///
+    /// Use `jj op log` to find an operation to revert.
+    #[arg(default_value = "@", add = ArgValueCandidates::new(complete::operations))]
+    operation: String,
+}
+
+pub fn cmd_op_revert(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &OperationRevertArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let bad_op = workspace_command.resolve_single_op(&args.operation)?;
+    let mut parent_ops = bad_op.parents();
+    let Some(parent_op) = parent_ops.next().transpose()? else {
+        return Err(user_error("Cannot revert repo initialization"));
+    };
+    if parent_ops.next().is_some() {
+        return Err(user_error("Cannot revert a merge operation"));
+    }
+
+    let mut tx = workspace_command.start_transaction();
+    let repo_loader = tx.base_repo().loader();
+    let bad_repo = repo_loader.load_at(&bad_op)?;
+    let parent_repo = repo_loader.load_at(&parent_op)?;
+    revert_operation_changes(ui, &mut tx, &bad_repo, &parent_repo, false)?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        write!(formatter, "Reverted operation: ")?;
+        let template = tx.base_workspace_helper().operation_summary_template();
+        template.format(&bad_op, formatter.as_mut())?;
+        writeln!(formatter)?;
+    }
+    tx.finish(ui, format!("revert operation {}", bad_op.id().hex()))?;
+
+    Ok(())
+}
+
+/// Reverts the changed commits, local bookmarks, tags, and remote bookmarks of
+/// the operation from `parent_repo` to `bad_repo`. If `interactive`, asks which
+/// changes should be reverted. Returns the number of reverted changes.
+pub(super) fn revert_operation_changes(
+    ui: &Ui,
+    tx: &mut WorkspaceCommandTransaction,
+    bad_repo: &ReadonlyRepo,
+    parent_repo: &ReadonlyRepo,
+    interactive: bool,
+) -> Result<usize, CommandError> {
+    // Commits hidden by the operation may be unknown to the current repo.
+    tx.repo_mut().merge_index(bad_repo);
+    tx.repo_mut().merge_index(parent_repo);
+
+    let changes = compute_operation_commits_diff(tx.repo(), parent_repo, bad_repo)?;
+    let changed_bookmarks = diff_named_ref_targets(
+        parent_repo.view().local_bookmarks(),
+        bad_repo.view().local_bookmarks(),
+    )
+    .collect_vec();
+    let changed_tags =
+        diff_named_ref_targets(parent_repo.view().tags(), bad_repo.view().tags()).collect_vec();
+    let changed_remote_bookmarks = diff_named_remote_refs(
+        parent_repo.view().all_remote_bookmarks(),
+        bad_repo.view().all_remote_bookmarks(),
+    )
+    // Updates to the local git repo follow from the local bookmarks.
+    .filter(|((_, remote_name), _)| !jj_lib::git::is_special_git_remote(remote_name))
+    .collect_vec();
+
+    let mut selected_changes = vec![];
+    let mut selected_bookmarks = vec![];
+    let mut selected_tags = vec![];
+    let mut selected_remote_bookmarks = vec![];
+    {
+        let repo = tx.repo();
+        let commit_summary_template = tx.commit_summary_template();
+        let change_template = if interactive {
+            let text = tx.settings().get_string("templates.op_diff_change")?;
+            Some(tx.parse_commit_template(ui, &text)?)
+        } else {
+            None
+        };
+        let confirm = |write_change: &dyn Fn(&mut dyn Formatter) -> Result<(), CommandError>|
+         -> Result<bool, CommandError> {
+            if !interactive {
+                return Ok(true);
+            }
+            if let Some(mut formatter) = ui.status_formatter() {
+                write_change(formatter.as_mut())?;
+            }
+            Ok(ui.prompt_yes_no("Undo this change?", None)?)
+        };
+        let write_ref_change = |formatter: &mut dyn Formatter,
+                                (old_target, new_target): (&RefTarget, &RefTarget),
+                                (old_prefix, new_prefix): (Option<&str>, Option<&str>)|
+         -> Result<(), CommandError> {
+            write_ref_target_summary(
+                formatter,
+                repo,
+                &commit_summary_template,
+                new_target,
+                true,
+                new_prefix,
+            )?;
+            write_ref_target_summary(
+                formatter,
+                repo,
+                &commit_summary_template,
+                old_target,
+                false,
+                old_prefix,
+            )
+        };
+
+        for (change_id, modified_change) in changes {
+            let selected = confirm(&|formatter| {
+                writeln!(formatter, "Change {}:", short_change_hash(&change_id))?;
+                let change_template = change_template.as_ref().unwrap();
+                write_modified_change_summary(formatter, change_template, &modified_change)?;
+                Ok(())
+            })?;
+            if selected {
+                selected_changes.push(modified_change);
+            }
+        }
+        for (name, (old_target, new_target)) in changed_bookmarks {
+            let selected = confirm(&|formatter| {
+                writeln!(formatter, "Bookmark {name}:")?;
+                write_ref_change(formatter, (old_target, new_target), (None, None))
+            })?;
+            if selected {
+                selected_bookmarks.push((name, old_target, new_target));
+            }
+        }
+        for (name, (old_target, new_target)) in changed_tags {
+            let selected = confirm(&|formatter| {
+                writeln!(formatter, "Tag {name}:")?;
+                write_ref_change(formatter, (old_target, new_target), (None, None))
+            })?;
+            if selected {
+                selected_tags.push((name, old_target, new_target));
+            }
+        }
+        let get_remote_ref_prefix = |remote_ref: &RemoteRef| match remote_ref.state {
+            RemoteRefState::New => "untracked",
+            RemoteRefState::Tracking => "tracked",
+        };
+        for ((name, remote_name), (old_ref, new_ref)) in changed_remote_bookmarks {
+            let selected = confirm(&|formatter| {
+                writeln!(formatter, "Remote bookmark {name}@{remote_name}:")?;
+                write_ref_change(
+                    formatter,
+                    (&old_ref.target, &new_ref.target),
+                    (
+                        Some(get_remote_ref_prefix(old_ref)),
+                        Some(get_remote_ref_prefix(new_ref)),
+                    ),
+                )
+            })?;
+            if selected {
+                selected_remote_bookmarks.push((name, remote_name, old_ref, new_ref));
+            }
+        }
+    }
+
+    let num_selected = selected_changes.len()
+        + selected_bookmarks.len()
+        + selected_tags.len()
+        + selected_remote_bookmarks.len();
+    let mut_repo = tx.repo_mut();
+    for modified_change in selected_changes {
+        let new_ids = modified_change
+            .removed_commits
+            .iter()
+            .map(|commit| commit.id().clone())
+            .collect_vec();
+        for added_commit in &modified_change.added_commits {
+            match modified_change.removed_commits.as_slice() {
+                [] => mut_repo.record_abandoned_commit(added_commit.id().clone()),
+                [removed_commit] => mut_repo
+                    .set_rewritten_commit(added_commit.id().clone(), removed_commit.id().clone()),
+                _ => mut_repo.set_divergent_rewrite(added_commit.id().clone(), new_ids.clone()),
+            }
+        }
+        mut_repo.add_heads(&modified_change.removed_commits)?;
+    }
+    for (name, old_target, new_target) in selected_bookmarks {
+        mut_repo.merge_local_bookmark(name, new_target, old_target);
+    }
+    for (name, old_target, new_target) in selected_tags {
+        mut_repo.merge_tag(name, new_target, old_target);
+    }
+    for (name, remote_name, old_ref, new_ref) in selected_remote_bookmarks {
+        mut_repo.merge_remote_bookmark(name, remote_name, new_ref, old_ref);
+    }
+    Ok(num_selected)
+}
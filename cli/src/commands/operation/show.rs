@@ -12,12 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::slice;
+
 use clap_complete::ArgValueCandidates;
 use itertools::Itertools;
+use jj_lib::operation::Operation;
+use jj_lib::repo::RepoLoader;
 
 use super::diff::show_op_diff;
+use super::is_inspecting_workable_repo;
+use super::resolve_ops_at_heads;
+use crate::cli_util::date_pattern_context;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::LogContentFormat;
+use crate::cli_util::WorkspaceCommandEnvironment;
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::complete;
@@ -25,9 +33,13 @@ use crate::diff_util::diff_formats_for_log;
 use crate::diff_util::DiffFormatArgs;
 use crate::diff_util::DiffRenderer;
 use crate::graphlog::GraphStyle;
+use crate::operation_templater::OperationTemplateLanguage;
 use crate::ui::Ui;
 
 /// Show changes to the repository in an operation
+///
+/// With `--at-op=@` or `--ignore-working-copy`, divergent operations aren't
+/// reconciled, and `@` shows each of the operation heads.
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationShowArgs {
     /// Show repository changes in this operation, compared to its parent(s)
@@ -52,60 +64,126 @@ pub fn cmd_op_show(
     command: &CommandHelper,
     args: &OperationShowArgs,
 ) -> Result<(), CommandError> {
-    let workspace_command = command.workspace_helper(ui)?;
-    let workspace_env = workspace_command.env();
-    let repo_loader = workspace_command.workspace().repo_loader();
-    let settings = workspace_command.settings();
-    let op = workspace_command.resolve_single_op(&args.operation)?;
-    let parents: Vec<_> = op.parents().try_collect()?;
-    let parent_op = repo_loader.merge_operations(parents, None)?;
-    let parent_repo = repo_loader.load_at(&parent_op)?;
-    let repo = repo_loader.load_at(&op)?;
-
-    let id_prefix_context = workspace_env.new_id_prefix_context();
-    let commit_summary_template = {
-        let language = workspace_env.commit_template_language(repo.as_ref(), &id_prefix_context);
-        let text = settings.get_string("templates.commit_summary")?;
-        workspace_env.parse_template(ui, &language, &text, CommitTemplateLanguage::wrap_commit)?
-    };
+    if is_inspecting_workable_repo(command) {
+        let workspace_command = command.workspace_helper(ui)?;
+        let current_op = workspace_command.repo().operation();
+        let repo_loader = workspace_command.workspace().repo_loader();
+        do_op_show(
+            ui,
+            workspace_command.env(),
+            repo_loader,
+            slice::from_ref(current_op),
+            args,
+        )
+    } else {
+        // Don't merge divergent operations so that each of them can be shown.
+        let workspace = command.load_workspace()?;
+        let workspace_env = command.workspace_environment(ui, &workspace)?;
+        let repo_loader = workspace.repo_loader();
+        let current_ops = command.resolve_unmerged_operations(repo_loader)?;
+        do_op_show(ui, &workspace_env, repo_loader, &current_ops, args)
+    }
+}
 
+fn do_op_show(
+    ui: &mut Ui,
+    workspace_env: &WorkspaceCommandEnvironment,
+    repo_loader: &RepoLoader,
+    current_ops: &[Operation],
+    args: &OperationShowArgs,
+) -> Result<(), CommandError> {
+    let settings = repo_loader.settings();
+    let ops = resolve_ops_at_heads(
+        repo_loader,
+        current_ops,
+        &args.operation,
+        &date_pattern_context(settings),
+    )?;
     let graph_style = GraphStyle::from_settings(settings)?;
     let with_content_format = LogContentFormat::new(ui, settings)?;
-    let diff_renderer = {
-        let formats = diff_formats_for_log(settings, &args.diff_format, args.patch)?;
-        let path_converter = workspace_env.path_converter();
-        let conflict_marker_style = workspace_env.conflict_marker_style();
-        (!formats.is_empty()).then(|| {
-            DiffRenderer::new(
-                repo.as_ref(),
-                path_converter,
-                conflict_marker_style,
-                formats,
-            )
-        })
-    };
+    let diff_formats = diff_formats_for_log(settings, &args.diff_format, args.patch)?;
 
     // TODO: Should we make this customizable via clap arg?
     let template = {
+        let current_op_id = match current_ops {
+            [current_op] => Some(current_op.id()),
+            _ => None,
+        };
+        let language = OperationTemplateLanguage::new(
+            repo_loader,
+            current_op_id,
+            workspace_env.operation_template_extensions(),
+        );
         let text = settings.get_string("templates.op_log")?;
-        workspace_command
-            .parse_operation_template(ui, &text)?
+        workspace_env
+            .parse_template(
+                ui,
+                &language,
+                &text,
+                OperationTemplateLanguage::wrap_operation,
+            )?
             .labeled("operation")
     };
 
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
-    template.format(&op, formatter.as_mut())?;
+    for (i, op) in ops.iter().enumerate() {
+        if i > 0 {
+            writeln!(formatter)?;
+        }
+        let parents: Vec<_> = op.parents().try_collect()?;
+        let parent_op = repo_loader.merge_operations(parents, None)?;
+        let parent_repo = repo_loader.load_at(&parent_op)?;
+        let repo = repo_loader.load_at(op)?;
+
+        let id_prefix_context = workspace_env.new_id_prefix_context();
+        let commit_summary_template;
+        let change_template;
+        {
+            let language =
+                workspace_env.commit_template_language(repo.as_ref(), &id_prefix_context);
+            let text = settings.get_string("templates.commit_summary")?;
+            commit_summary_template = workspace_env.parse_template(
+                ui,
+                &language,
+                &text,
+                CommitTemplateLanguage::wrap_commit,
+            )?;
+            let text = settings.get_string("templates.op_diff_change")?;
+            change_template = workspace_env.parse_template(
+                ui,
+                &language,
+                &text,
+                CommitTemplateLanguage::wrap_commit,
+            )?;
+        }
+        let diff_renderer = {
+            let path_converter = workspace_env.path_converter();
+            let conflict_marker_style = workspace_env.conflict_marker_style();
+            (!diff_formats.is_empty()).then(|| {
+                DiffRenderer::new(
+                    repo.as_ref(),
+                    path_converter,
+                    conflict_marker_style,
+                    diff_formats.clone(),
+                )
+            })
+        };
 
-    show_op_diff(
-        ui,
-        formatter.as_mut(),
-        repo.as_ref(),
-        &parent_repo,
-        &repo,
-        &commit_summary_template,
-        (!args.no_graph).then_some(graph_style),
-        &with_content_format,
-        diff_renderer.as_ref(),
-    )
+        template.format(op, formatter.as_mut())?;
+        show_op_diff(
+            ui,
+            formatter.as_mut(),
+            repo.as_ref(),
+            &parent_repo,
+            &repo,
+            &commit_summary_template,
+            &change_template,
+            (!args.no_graph).then_some(graph_style),
+            &with_content_format,
+            diff_renderer.as_ref(),
+            None,
+        )?;
+    }
+    Ok(())
 }
@@ -13,13 +13,19 @@
 // limitations under the License.
 
 use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
 use jj_lib::object_id::ObjectId;
 use jj_lib::repo::Repo;
+use jj_lib::revset;
+use jj_lib::rewrite::find_visible_successors;
 
-use super::view_with_desired_portions_restored;
+use super::restore_view_portions;
+use super::revert::revert_operation_changes;
 use super::UndoWhatToRestore;
 use super::DEFAULT_UNDO_WHAT;
+use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
+use crate::cli_util::WorkspaceCommandTransaction;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
@@ -29,6 +35,10 @@ use crate::ui::Ui;
 ///
 /// This undoes an individual operation by applying the inverse of the
 /// operation.
+///
+/// With `--interactive`, you're asked about each changed commit, local
+/// bookmark, tag, and remote bookmark in the operation, and only the selected
+/// changes are undone.
 #[derive(clap::Args, Clone, Debug)]
 pub struct OperationUndoArgs {
     /// The operation to undo
@@ -42,6 +52,10 @@ pub struct OperationUndoArgs {
     /// This option is EXPERIMENTAL.
     #[arg(long, value_enum, default_values_t = DEFAULT_UNDO_WHAT)]
     what: Vec<UndoWhatToRestore>,
+
+    /// Interactively choose which changes of the operation to undo
+    #[arg(long, short, conflicts_with = "what")]
+    interactive: bool,
 }
 
 pub fn cmd_op_undo(
@@ -63,13 +77,21 @@ pub fn cmd_op_undo(
     let repo_loader = tx.base_repo().loader();
     let bad_repo = repo_loader.load_at(&bad_op)?;
     let parent_repo = repo_loader.load_at(&parent_op)?;
-    tx.repo_mut().merge(&bad_repo, &parent_repo);
-    let new_view = view_with_desired_portions_restored(
-        tx.repo().view().store_view(),
-        tx.base_repo().view().store_view(),
-        &args.what,
-    );
-    tx.repo_mut().set_view(new_view);
+    if args.interactive {
+        let num_selected = revert_operation_changes(ui, &mut tx, &bad_repo, &parent_repo, true)?;
+        if num_selected == 0 {
+            return Err(user_error("No changes selected"));
+        }
+    } else {
+        tx.repo_mut().merge(&bad_repo, &parent_repo);
+        // Start over from the current view, and take only the requested
+        // portions from the merged one.
+        let merged_view = tx.repo().view().store_view().clone();
+        let base_view = tx.base_repo().view().store_view().clone();
+        tx.repo_mut().set_view(base_view);
+        restore_view_portions(tx.repo_mut(), &merged_view, &args.what)?;
+    }
+    warn_rewritten_restored_commits(ui, &tx)?;
     if let Some(mut formatter) = ui.status_formatter() {
         write!(formatter, "Undid operation: ")?;
         let template = tx.base_workspace_helper().operation_summary_template();
@@ -80,3 +102,43 @@ pub fn cmd_op_undo(
 
     Ok(())
 }
+
+/// Warns if the undo made commits visible again which have been rewritten by
+/// later operations, since both versions are visible now.
+fn warn_rewritten_restored_commits(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+) -> Result<(), CommandError> {
+    let repo = tx.repo();
+    let old_heads = tx.base_repo().view().heads().iter().cloned().collect_vec();
+    let new_heads = repo.view().heads().iter().cloned().collect_vec();
+    let mut rewritten_commits = vec![];
+    for commit_id in revset::walk_revs(repo, &new_heads, &old_heads)?.iter() {
+        let commit_id = commit_id?;
+        let successor_ids = find_visible_successors(repo, &commit_id)?;
+        if !successor_ids.is_empty() {
+            rewritten_commits.push((commit_id, successor_ids));
+        }
+    }
+    if rewritten_commits.is_empty() {
+        return Ok(());
+    }
+    writeln!(
+        ui.warning_default(),
+        "Restored commits which have been rewritten since:"
+    )?;
+    for (commit_id, successor_ids) in &rewritten_commits {
+        writeln!(
+            ui.stderr(),
+            "  {} (rewritten as {})",
+            short_commit_hash(commit_id),
+            successor_ids.iter().map(short_commit_hash).join(", ")
+        )?;
+    }
+    writeln!(
+        ui.hint_default(),
+        "Use `jj abandon` to drop the restored commits, or `jj resolve-divergence` to choose \
+         between the versions of a change."
+    )?;
+    Ok(())
+}
@@ -13,6 +13,8 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::Write as _;
 
 use clap_complete::ArgValueCandidates;
 use indexmap::IndexSet;
@@ -20,8 +22,12 @@ use itertools::Itertools;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::commit::CommitIteratorExt;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo as _;
+use jj_lib::revset::RevsetExpression;
 use tracing::instrument;
 
+use crate::cli_util::short_commit_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
 use crate::command_error::CommandError;
@@ -53,6 +59,8 @@ use crate::ui::Ui;
 /// Therefore, `jj parallelize '1 | 3'` is a no-op. That's because 2, which is
 /// not in the target set, was a descendant of 1 before, so it remains a
 /// descendant, and it was an ancestor of 3 before, so it remains an ancestor.
+/// A warning is printed for each edge from a revision in the set to a revision
+/// outside the set that keeps revisions in the set connected like this.
 #[derive(clap::Args, Clone, Debug)]
 #[command(verbatim_doc_comment)]
 pub(crate) struct ParallelizeArgs {
@@ -78,6 +86,7 @@ pub(crate) fn cmd_parallelize(
         .evaluate_to_commits()?
         .try_collect()?;
     workspace_command.check_rewritable(target_commits.iter().ids())?;
+    warn_about_intermediate_commits(ui, workspace_command.repo(), &target_commits)?;
 
     let mut tx = workspace_command.start_transaction();
 
@@ -145,3 +154,49 @@ pub(crate) fn cmd_parallelize(
 
     tx.finish(ui, format!("parallelize {} commits", target_commits.len()))
 }
+
+/// Warns about every edge from a target commit to a commit outside the target
+/// set which sits between two target commits. Such commits keep the target
+/// commits on either side of them connected, so those can't become siblings.
+fn warn_about_intermediate_commits(
+    ui: &Ui,
+    repo: &ReadonlyRepo,
+    target_commits: &[Commit],
+) -> Result<(), CommandError> {
+    let target_ids: HashSet<&CommitId> = target_commits.iter().ids().collect();
+    let targets_expression =
+        RevsetExpression::commits(target_ids.iter().copied().cloned().collect());
+    let intermediate_ids: Vec<CommitId> = targets_expression
+        .connected()
+        .minus(&targets_expression)
+        .evaluate(repo)?
+        .iter()
+        .try_collect()?;
+    for commit_id in intermediate_ids {
+        let commit = repo.store().get_commit(&commit_id)?;
+        let ancestor_ids = commit
+            .parent_ids()
+            .iter()
+            .filter(|id| target_ids.contains(id))
+            .collect_vec();
+        if ancestor_ids.is_empty() {
+            continue;
+        }
+        let descendant_ids: Vec<CommitId> = targets_expression
+            .intersection(&RevsetExpression::commit(commit_id.clone()).descendants())
+            .roots()
+            .evaluate(repo)?
+            .iter()
+            .try_collect()?;
+        for (ancestor_id, descendant_id) in ancestor_ids.iter().cartesian_product(&descendant_ids) {
+            writeln!(
+                ui.warning_default(),
+                "Commit {} is not being parallelized, so {} will remain a descendant of {}",
+                short_commit_hash(&commit_id),
+                short_commit_hash(descendant_id),
+                short_commit_hash(ancestor_id),
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Print a compact summary of the working-copy revision for shell prompts
+///
+/// Unlike most commands, this doesn't snapshot the working copy or start the
+/// pager, so it is cheap enough to run every time a prompt is drawn. The
+/// summary therefore doesn't reflect file changes made since the last `jj`
+/// command that snapshotted the working copy.
+///
+/// Nothing is printed if the workspace has no working-copy revision.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct PromptArgs {
+    /// Render the working-copy revision using the given template
+    ///
+    /// Defaults to the `templates.prompt` setting.
+    ///
+    /// For the syntax, see https://jj-vcs.github.io/jj/latest/templates/
+    #[arg(long, short = 'T')]
+    template: Option<String>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_prompt(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &PromptArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    let Some(wc_commit_id) = workspace_command.get_wc_commit_id() else {
+        return Ok(());
+    };
+    let commit = workspace_command.repo().store().get_commit(wc_commit_id)?;
+    let template_string = match &args.template {
+        Some(value) => value.to_string(),
+        None => workspace_command
+            .settings()
+            .get_string("templates.prompt")?,
+    };
+    let template = workspace_command.parse_commit_template(ui, &template_string)?;
+    let mut formatter = ui.stdout_formatter();
+    template.format(&commit, formatter.as_mut())?;
+    Ok(())
+}
@@ -192,6 +192,29 @@ pub(crate) struct RebaseArgs {
     /// parents.
     #[arg(long)]
     skip_emptied: bool,
+
+    /// How to rebase merge commits whose parents are rebased along with them
+    ///
+    /// By default, such merge commits are preserved: their new parents are
+    /// merged again, and the changes the merge commit made on top of its
+    /// merged parents are carried over. Conflicts between the new parents are
+    /// resolved the same way the merge commit resolved the same conflicts
+    /// between its old parents, like `git rerere` does. This also applies to
+    /// merges with more than two parents. With `linearize`,
+    /// they are rebased onto their first parent only, and the changes merged
+    /// in from their other parents are dropped.
+    #[arg(long, value_name = "STRATEGY", default_value = "preserve")]
+    rebase_merges: RebaseMergesStrategy,
+}
+
+/// Strategy for rebasing merge commits
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum RebaseMergesStrategy {
+    /// Merge the new parents again, reusing the merge commit's conflict
+    /// resolutions
+    Preserve,
+    /// Rebase merge commits onto their first parent only
+    Linearize,
 }
 
 #[derive(clap::Args, Clone, Debug)]
@@ -248,6 +271,8 @@ pub(crate) fn cmd_rebase(
             false => EmptyBehaviour::Keep,
         },
         simplify_ancestor_merge: false,
+        linearize_merges: args.rebase_merges == RebaseMergesStrategy::Linearize,
+        reuse_merge_resolutions: args.rebase_merges == RebaseMergesStrategy::Preserve,
     };
     let mut workspace_command = command.workspace_helper(ui)?;
     if !args.revisions.is_empty() {
@@ -341,7 +366,8 @@ fn rebase_source(
         &new_children,
         source_commits,
         rebase_options,
-    )
+    )?;
+    Ok(())
 }
 
 fn rebase_branch(
@@ -388,20 +414,22 @@ fn rebase_branch(
         &new_children,
         root_commits,
         &rebase_options,
-    )
+    )?;
+    Ok(())
 }
 
-fn rebase_descendants_transaction(
-    ui: &mut Ui,
+/// Rebases the `target_roots` and their descendants in a new transaction.
+pub(crate) fn rebase_descendants_transaction(
+    ui: &Ui,
     workspace_command: &mut WorkspaceCommandHelper,
     new_parent_ids: &[CommitId],
     new_children: &[Commit],
     target_roots: Vec<Commit>,
     rebase_options: &RebaseOptions,
-) -> Result<(), CommandError> {
+) -> Result<MoveCommitsStats, CommandError> {
     if target_roots.is_empty() {
         writeln!(ui.status(), "Nothing changed.")?;
-        return Ok(());
+        return Ok(MoveCommitsStats::default());
     }
 
     let mut tx = workspace_command.start_transaction();
@@ -425,7 +453,8 @@ fn rebase_descendants_transaction(
         rebase_options,
     )?;
     print_move_commits_stats(ui, &stats)?;
-    tx.finish(ui, tx_description)
+    tx.finish(ui, tx_description)?;
+    Ok(stats)
 }
 
 /// Computes the new parents and children for the given
@@ -555,7 +584,7 @@ fn ensure_no_commit_loop(
     Ok(())
 }
 
-fn check_rebase_destinations(
+pub(crate) fn check_rebase_destinations(
     repo: &Arc<ReadonlyRepo>,
     new_parents: &[Commit],
     commit: &Commit,
@@ -574,15 +603,23 @@ fn check_rebase_destinations(
 
 /// Print details about the provided [`MoveCommitsStats`].
 fn print_move_commits_stats(ui: &Ui, stats: &MoveCommitsStats) -> std::io::Result<()> {
-    let Some(mut formatter) = ui.status_formatter() else {
-        return Ok(());
-    };
     let &MoveCommitsStats {
         num_rebased_targets,
         num_rebased_descendants,
         num_skipped_rebases,
         num_abandoned,
+        num_linearized_merges,
     } = stats;
+    if num_linearized_merges > 0 {
+        writeln!(
+            ui.warning_default(),
+            "Linearized {num_linearized_merges} merge commits; changes from their other parents \
+             were dropped"
+        )?;
+    }
+    let Some(mut formatter) = ui.status_formatter() else {
+        return Ok(());
+    };
     if num_skipped_rebases > 0 {
         writeln!(
             formatter,
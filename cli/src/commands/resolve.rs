@@ -13,17 +13,30 @@
 // limitations under the License.
 
 use std::io::Write;
+use std::iter::zip;
 
 use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use itertools::Itertools;
+use jj_lib::backend::BackendResult;
+use jj_lib::backend::TreeValue;
+use jj_lib::commit::Commit;
+use jj_lib::merge::Merge;
+use jj_lib::merge::MergedTreeValue;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::MergedTreeBuilder;
 use jj_lib::object_id::ObjectId;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::repo_path::RepoPathComponent;
 use tracing::instrument;
 
 use crate::cli_util::print_conflicted_paths;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::cli_error;
+use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
 use crate::ui::Ui;
@@ -39,6 +52,9 @@ use crate::ui::Ui;
 /// Note that conflicts can also be resolved without using this command. You may
 /// edit the conflict markers in the conflicted file directly with a text
 /// editor.
+///
+/// Conflicts in binary files can't be resolved by a 3-way merge tool. Use
+/// `--keep-both` to keep the content of each side in a separate file instead.
 //  TODOs:
 //   - `jj resolve --editor` to resolve a conflict in the default text editor. Should work for
 //     conflicts with 3+ adds. Useful to resolve conflicts in a commit other than the current one.
@@ -63,6 +79,14 @@ pub(crate) struct ResolveArgs {
     /// Specify 3-way merge tool to be used
     #[arg(long, conflicts_with = "list", value_name = "NAME")]
     tool: Option<String>,
+    /// Resolve file conflicts by keeping each side in a separate file
+    ///
+    /// The conflicted file `<name>.<ext>` is replaced with the files
+    /// `<name>.side1.<ext>`, `<name>.side2.<ext>`, etc. Sides which deleted the
+    /// file are skipped. This is mostly useful for binary files, which can't be
+    /// merged line by line.
+    #[arg(long, conflicts_with_all = ["list", "tool"])]
+    keep_both: bool,
     /// Only resolve conflicts in these paths. You can use the `--list` argument
     /// to find paths to use here.
     #[arg(
@@ -101,9 +125,14 @@ pub(crate) fn cmd_resolve(
             conflicts,
             ui.stdout_formatter().as_mut(),
             &workspace_command,
+            true,
         );
     };
 
+    if args.keep_both {
+        return resolve_by_keeping_both(ui, workspace_command, &commit, &tree, conflicts);
+    }
+
     let repo_paths = conflicts
         .iter()
         .map(|(path, _)| path.as_ref())
@@ -135,7 +164,12 @@ pub(crate) fn cmd_resolve(
                     formatter,
                     "After this operation, some files at this revision still have conflicts:"
                 )?;
-                print_conflicted_paths(new_conflicts, formatter.as_mut(), &workspace_command)?;
+                print_conflicted_paths(
+                    new_conflicts,
+                    formatter.as_mut(),
+                    &workspace_command,
+                    false,
+                )?;
             }
         }
     }
@@ -145,3 +179,83 @@ pub(crate) fn cmd_resolve(
     }
     Ok(())
 }
+
+fn resolve_by_keeping_both(
+    ui: &mut Ui,
+    mut workspace_command: WorkspaceCommandHelper,
+    commit: &Commit,
+    tree: &MergedTree,
+    conflicts: Vec<(RepoPathBuf, BackendResult<MergedTreeValue>)>,
+) -> Result<(), CommandError> {
+    workspace_command.check_rewritable([commit.id()])?;
+    let mut tree_builder = MergedTreeBuilder::new(commit.tree_id().clone());
+    let mut kept_paths = vec![];
+    for (path, conflict) in conflicts {
+        let conflict = conflict?.simplify();
+        let ui_path = workspace_command.format_file_path(&path);
+        let (Some(file_ids), Some(executable)) =
+            (conflict.to_file_merge(), conflict.to_executable_merge())
+        else {
+            return Err(user_error(format!(
+                "Cannot keep both sides of {ui_path}: it is not a conflict between files"
+            )));
+        };
+        tree_builder.set_or_remove(path.clone(), Merge::absent());
+        let mut side_paths = vec![];
+        for (side, (id, executable)) in zip(file_ids.adds(), executable.adds()).enumerate() {
+            let Some(id) = id else {
+                continue;
+            };
+            let side_path = side_suffixed_path(&path, side + 1);
+            if tree.path_value(&side_path)?.is_present() {
+                return Err(user_error(format!(
+                    "Cannot keep both sides of {ui_path}: {} already exists",
+                    workspace_command.format_file_path(&side_path)
+                )));
+            }
+            let value = TreeValue::File {
+                id: id.clone(),
+                executable: *executable,
+            };
+            side_paths.push(workspace_command.format_file_path(&side_path));
+            tree_builder.set_or_remove(side_path, Merge::normal(value));
+        }
+        kept_paths.push((ui_path, side_paths));
+    }
+    let new_tree_id = tree_builder.write_tree(workspace_command.repo().store())?;
+
+    let mut tx = workspace_command.start_transaction();
+    tx.repo_mut()
+        .rewrite_commit(commit)
+        .set_tree_id(new_tree_id)
+        .write()?;
+    if let Some(mut formatter) = ui.status_formatter() {
+        for (ui_path, side_paths) in &kept_paths {
+            writeln!(
+                formatter,
+                "Kept sides of {ui_path} as {}",
+                side_paths.join(", ")
+            )?;
+        }
+    }
+    tx.finish(
+        ui,
+        format!(
+            "Resolve conflicts in commit {} by keeping both sides",
+            commit.id().hex()
+        ),
+    )?;
+    Ok(())
+}
+
+/// Returns `dir/name.sideN.ext` for the conflicted file `dir/name.ext`.
+fn side_suffixed_path(path: &RepoPath, side: usize) -> RepoPathBuf {
+    let (dir, name) = path.split().expect("conflicted path shouldn't be the root");
+    let name = name.as_internal_str();
+    let new_name = match name.rfind('.') {
+        // Dot files like ".gitattributes" have no extension
+        Some(pos) if pos > 0 => format!("{}.side{side}{}", &name[..pos], &name[pos..]),
+        _ => format!("{name}.side{side}"),
+    };
+    dir.join(RepoPathComponent::new(&new_name))
+}
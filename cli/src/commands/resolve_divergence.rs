@@ -0,0 +1,242 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write;
+
+use clap::ArgGroup;
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::commit::CommitIteratorExt;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::object_id::ObjectId;
+use jj_lib::repo::Repo;
+use jj_lib::rewrite::find_visible_successors;
+use tracing::instrument;
+
+use crate::cli_util::short_change_hash;
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::description_util::combine_messages;
+use crate::diff_util::DiffFormatArgs;
+use crate::ui::Ui;
+
+/// Show and resolve the visible copies of a divergent change
+///
+/// A change becomes divergent when more than one visible commit has its change
+/// ID, for example after rewriting it concurrently in two workspaces. Without a
+/// strategy flag, this command shows each copy along with its diff, and which
+/// copies were rewritten into others (e.g. if an older version was restored by
+/// `jj undo`). Pass one of the strategy flags to resolve the divergence:
+///
+/// * `--keep` keeps the selected copy and abandons the others. Descendants of
+///   the abandoned copies are rebased onto the kept one.
+///
+/// * `--merge` merges the changes in all copies into the selected copy and
+///   abandons the others. The descriptions are combined like in `jj squash`.
+///
+/// * `--split` keeps all copies, but gives every copy except the selected one
+///   a new change ID.
+///
+/// If the revset resolves to more than one copy, the first one (the most
+/// recent) is selected. `--keep` requires the revset to select a single copy.
+#[derive(clap::Args, Clone, Debug)]
+#[command(group(ArgGroup::new("strategy").args(&["keep", "merge", "split"])))]
+pub(crate) struct ResolveDivergenceArgs {
+    /// A revision of the divergent change
+    #[arg(
+        default_value = "@",
+        value_name = "REVSET",
+        add = ArgValueCandidates::new(complete::mutable_revisions)
+    )]
+    revision: RevisionArg,
+    /// Keep the selected copy and abandon the others
+    #[arg(long)]
+    keep: bool,
+    /// Merge the changes in all copies into the selected copy
+    #[arg(long)]
+    merge: bool,
+    /// Give every copy except the selected one a new change ID
+    #[arg(long)]
+    split: bool,
+    #[command(flatten)]
+    format: DiffFormatArgs,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_resolve_divergence(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &ResolveDivergenceArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let selected: Vec<Commit> = workspace_command
+        .parse_revset(ui, &args.revision)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    let Some(target) = selected.first().cloned() else {
+        return Err(user_error("Revset didn't resolve to any revisions"));
+    };
+    if selected
+        .iter()
+        .any(|commit| commit.change_id() != target.change_id())
+    {
+        return Err(user_error(
+            "Revset resolved to revisions of more than one change",
+        ));
+    }
+    let repo = workspace_command.repo().clone();
+    let copies: Vec<Commit> = repo
+        .resolve_change_id(target.change_id())
+        .unwrap_or_default()
+        .iter()
+        .map(|id| repo.store().get_commit(id))
+        .try_collect()?;
+    if copies.len() < 2 {
+        return Err(user_error(format!(
+            "Change {} is not divergent",
+            short_change_hash(target.change_id())
+        )));
+    }
+    let others = copies
+        .iter()
+        .filter(|commit| commit.id() != target.id())
+        .collect_vec();
+
+    if !(args.keep || args.merge || args.split) {
+        let diff_renderer = workspace_command.diff_renderer_for(&args.format)?;
+        ui.request_pager();
+        let mut formatter = ui.stdout_formatter();
+        let formatter = formatter.as_mut();
+        for (i, commit) in copies.iter().enumerate() {
+            if i > 0 {
+                writeln!(formatter)?;
+            }
+            write!(formatter, "Copy {} of {}: ", i + 1, copies.len())?;
+            workspace_command.write_commit_summary(formatter, commit)?;
+            writeln!(formatter)?;
+            let rewritten_as = find_visible_successors(repo.as_ref(), commit.id())?
+                .iter()
+                .filter_map(|id| copies.iter().position(|copy| copy.id() == id))
+                .map(|i| i + 1)
+                .collect_vec();
+            if !rewritten_as.is_empty() {
+                writeln!(
+                    formatter,
+                    "This copy was rewritten as copy {}",
+                    rewritten_as.iter().join(", ")
+                )?;
+            }
+            diff_renderer.show_patch(ui, formatter, commit, &EverythingMatcher, ui.term_width())?;
+        }
+        writeln!(
+            ui.hint_default(),
+            "Resolve the divergence with `--keep`, `--merge`, or `--split`."
+        )?;
+        return Ok(());
+    }
+
+    if args.keep && selected.len() > 1 {
+        return Err(user_error_with_hint(
+            "Revset resolved to more than one copy of the change",
+            "Select the copy to keep by its commit ID.",
+        ));
+    }
+    workspace_command.check_rewritable(copies.iter().ids())?;
+
+    let mut tx = workspace_command.start_transaction();
+    let tx_description;
+    if args.keep {
+        for commit in &others {
+            tx.repo_mut()
+                .set_rewritten_commit(commit.id().clone(), target.id().clone());
+        }
+        if let Some(mut formatter) = ui.status_formatter() {
+            write!(formatter, "Kept ")?;
+            tx.write_commit_summary(formatter.as_mut(), &target)?;
+            writeln!(formatter)?;
+        }
+        tx_description = format!(
+            "resolve divergence of {} by keeping commit {}",
+            target.change_id().reverse_hex(),
+            target.id().hex()
+        );
+    } else if args.merge {
+        let mut tree = target.tree()?;
+        for commit in &others {
+            let parent_tree = commit.parent_tree(tx.repo())?;
+            tree = tree.merge(&parent_tree, &commit.tree()?)?;
+        }
+        let description = {
+            let text_editor = tx.base_workspace_helper().text_editor()?;
+            combine_messages(&text_editor, &others, &target)?
+        };
+        let merged = tx
+            .repo_mut()
+            .rewrite_commit(&target)
+            .set_tree_id(tree.id())
+            .set_predecessors(copies.iter().ids().cloned().collect())
+            .set_description(description)
+            .write()?;
+        for commit in &others {
+            tx.repo_mut()
+                .set_rewritten_commit(commit.id().clone(), merged.id().clone());
+        }
+        if let Some(mut formatter) = ui.status_formatter() {
+            write!(formatter, "Merged into ")?;
+            tx.write_commit_summary(formatter.as_mut(), &merged)?;
+            writeln!(formatter)?;
+        }
+        tx_description = format!(
+            "resolve divergence of {} by merging into commit {}",
+            target.change_id().reverse_hex(),
+            target.id().hex()
+        );
+    } else {
+        let mut new_commits = vec![];
+        for commit in &others {
+            let new_commit = tx
+                .repo_mut()
+                .rewrite_commit(commit)
+                .generate_new_change_id()
+                .write()?;
+            tx.repo_mut()
+                .set_rewritten_commit(commit.id().clone(), new_commit.id().clone());
+            new_commits.push(new_commit);
+        }
+        if let Some(mut formatter) = ui.status_formatter() {
+            writeln!(formatter, "Gave new change IDs to:")?;
+            for commit in &new_commits {
+                write!(formatter, "  ")?;
+                tx.write_commit_summary(formatter.as_mut(), commit)?;
+                writeln!(formatter)?;
+            }
+        }
+        tx_description = format!(
+            "resolve divergence of {} by splitting it into {} changes",
+            target.change_id().reverse_hex(),
+            copies.len()
+        );
+    }
+    let num_rebased = tx.repo_mut().rebase_descendants()?;
+    if num_rebased > 0 {
+        writeln!(ui.status(), "Rebased {num_rebased} descendant commits")?;
+    }
+    tx.finish(ui, tx_description)?;
+    Ok(())
+}
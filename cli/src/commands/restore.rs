@@ -18,11 +18,15 @@ use clap_complete::ArgValueCandidates;
 use clap_complete::ArgValueCompleter;
 use indoc::formatdoc;
 use itertools::Itertools as _;
+use jj_lib::commit::Commit;
 use jj_lib::object_id::ObjectId;
+use jj_lib::repo::Repo as _;
 use tracing::instrument;
 
+use crate::cli_util::short_operation_hash;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::RevisionArg;
+use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::user_error;
 use crate::command_error::CommandError;
 use crate::complete;
@@ -37,6 +41,10 @@ use crate::ui::Ui;
 /// If only one of `--from` or `--to` is specified, the other one defaults to
 /// the working copy.
 ///
+/// With `--from-op`, the paths are restored from the working-copy commit of
+/// this workspace as of the given operation. This is useful for recovering
+/// file contents from before an operation such as `jj undo`.
+///
 /// When neither `--from` nor `--to` is specified, the command restores into the
 /// working copy from its parent(s). `jj restore` without arguments is similar
 /// to `jj abandon`, except that it leaves an empty revision with its
@@ -61,6 +69,14 @@ pub(crate) struct RestoreArgs {
         add = ArgValueCandidates::new(complete::all_revisions)
     )]
     from: Option<RevisionArg>,
+    /// Restore from the working-copy commit at this operation (source)
+    #[arg(
+        long,
+        value_name = "OPERATION",
+        conflicts_with = "from",
+        add = ArgValueCandidates::new(complete::operations),
+    )]
+    from_op: Option<String>,
     /// Revision to restore into (destination)
     #[arg(
         long,
@@ -80,7 +96,7 @@ pub(crate) struct RestoreArgs {
     #[arg(
         long, short,
         value_name = "REVSET",
-        conflicts_with_all = ["to", "from"],
+        conflicts_with_all = ["to", "from", "from_op"],
         add = ArgValueCandidates::new(complete::all_revisions),
     )]
     changes_in: Option<RevisionArg>,
@@ -119,11 +135,15 @@ pub(crate) fn cmd_restore(
              revision,\nuse `--to` or `--changes-in`.",
         ));
     }
-    if args.from.is_some() || args.to.is_some() {
+    if args.from.is_some() || args.from_op.is_some() || args.to.is_some() {
         to_commit = workspace_command
             .resolve_single_rev(ui, args.to.as_ref().unwrap_or(&RevisionArg::AT))?;
-        let from_commit = workspace_command
-            .resolve_single_rev(ui, args.from.as_ref().unwrap_or(&RevisionArg::AT))?;
+        let from_commit = if let Some(op_str) = &args.from_op {
+            resolve_wc_commit_at_op(&workspace_command, op_str)?
+        } else {
+            workspace_command
+                .resolve_single_rev(ui, args.from.as_ref().unwrap_or(&RevisionArg::AT))?
+        };
         from_tree = from_commit.tree()?;
         from_commits = vec![from_commit];
     } else {
@@ -191,3 +211,22 @@ pub(crate) fn cmd_restore(
     }
     Ok(())
 }
+
+/// Returns the working-copy commit of the current workspace as of the given
+/// operation.
+fn resolve_wc_commit_at_op(
+    workspace_command: &WorkspaceCommandHelper,
+    op_str: &str,
+) -> Result<Commit, CommandError> {
+    let op = workspace_command.resolve_single_op(op_str)?;
+    let repo = workspace_command.workspace().repo_loader().load_at(&op)?;
+    let workspace_id = workspace_command.workspace_id();
+    let commit_id = repo.view().get_wc_commit_id(workspace_id).ok_or_else(|| {
+        user_error(format!(
+            "Workspace '{}' doesn't have a working-copy commit at operation {}",
+            workspace_id.as_str(),
+            short_operation_hash(op.id())
+        ))
+    })?;
+    Ok(repo.store().get_commit(commit_id)?)
+}
@@ -0,0 +1,133 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use clap_complete::ArgValueCandidates;
+use indexmap::IndexMap;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::repo::Repo as _;
+use jj_lib::signing::SignBehavior;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Cryptographically sign revisions
+///
+/// Signs the given revisions with the configured signing backend, replacing
+/// any existing signatures. Descendants of the signed revisions are rebased.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct SignArgs {
+    /// What revision(s) to sign
+    ///
+    /// If no revisions are specified, this defaults to the `revsets.sign`
+    /// setting, or `reachable(@, mutable())` if it is not set.
+    #[arg(
+        long,
+        short,
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::mutable_revisions)
+    )]
+    revisions: Vec<RevisionArg>,
+    /// The key used for signing (defaults to the `signing.key` setting)
+    #[arg(long)]
+    key: Option<String>,
+}
+
+pub(crate) fn cmd_sign(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &SignArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    if !workspace_command.repo().store().signer().can_sign() {
+        return Err(user_error_with_hint(
+            "No signing backend is configured",
+            "Set `signing.backend` to sign commits. See \
+             https://jj-vcs.github.io/jj/latest/config/#commit-signing",
+        ));
+    }
+    let revisions = if args.revisions.is_empty() {
+        let revs = workspace_command.settings().get_string("revsets.sign")?;
+        vec![RevisionArg::from(revs)]
+    } else {
+        args.revisions.clone()
+    };
+    let commit_ids: Vec<_> = workspace_command
+        .parse_union_revsets(ui, &revisions)?
+        .evaluate_to_commit_ids()?
+        .try_collect()?;
+    workspace_command.check_rewritable(&commit_ids)?;
+    let commit_ids_set: HashSet<_> = commit_ids.iter().cloned().collect();
+
+    let mut tx = workspace_command.start_transaction();
+    let mut signed_commits = vec![];
+    let mut num_rebased = 0;
+    tx.repo_mut()
+        .transform_descendants(commit_ids, |rewriter| {
+            if commit_ids_set.contains(rewriter.old_commit().id()) {
+                let commit = rewriter
+                    .reparent()
+                    .set_sign_behavior(SignBehavior::Force)
+                    .set_sign_key(args.key.clone())
+                    .write()?;
+                signed_commits.push(commit);
+            } else if rewriter.parents_changed() {
+                rewriter.reparent().write()?;
+                num_rebased += 1;
+            }
+            Ok(())
+        })?;
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        // Group the commits by the key the backend reports for the new
+        // signatures, which may differ from the key that was asked for.
+        let mut commits_by_key: IndexMap<String, Vec<&Commit>> = IndexMap::new();
+        for commit in &signed_commits {
+            commits_by_key
+                .entry(signing_key_description(commit))
+                .or_default()
+                .push(commit);
+        }
+        for (key, commits) in &commits_by_key {
+            writeln!(formatter, "Signed {} commits with {key}:", commits.len())?;
+            for commit in commits {
+                write!(formatter, "  ")?;
+                tx.write_commit_summary(formatter.as_mut(), commit)?;
+                writeln!(formatter)?;
+            }
+        }
+        if num_rebased > 0 {
+            writeln!(formatter, "Rebased {num_rebased} descendant commits")?;
+        }
+    }
+    tx.finish(ui, format!("sign {} commits", signed_commits.len()))?;
+    Ok(())
+}
+
+/// Describes the key that signed `commit`, as reported by the signing backend.
+fn signing_key_description(commit: &Commit) -> String {
+    match commit.verification() {
+        Ok(Some(verification)) => match verification.key.or(verification.display) {
+            Some(key) => format!("key {key}"),
+            None => "the default key".to_owned(),
+        },
+        Ok(None) | Err(_) => "an unknown key".to_owned(),
+    }
+}
@@ -15,14 +15,13 @@
 use std::fmt::Write as _;
 use std::path::Path;
 
-use itertools::Itertools;
 use jj_lib::repo_path::RepoPathBuf;
 use tracing::instrument;
 
+use super::parse_sparse_patterns;
 use super::update_sparse_patterns_with;
 use crate::cli_util::CommandHelper;
 use crate::command_error::internal_error;
-use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::description_util::TextEditor;
 use crate::ui::Ui;
@@ -68,15 +67,5 @@ fn edit_sparse(
         .edit_str(content, Some(".jjsparse"))
         .map_err(|err| err.with_name("sparse patterns"))?;
 
-    content
-        .lines()
-        .filter(|line| !line.starts_with("JJ:"))
-        .map(|line| line.trim())
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            RepoPathBuf::from_relative_path(line).map_err(|err| {
-                user_error_with_message(format!("Failed to parse sparse pattern: {line}"), err)
-            })
-        })
-        .try_collect()
+    parse_sparse_patterns(content.lines().filter(|line| !line.starts_with("JJ:")))
 }
@@ -18,6 +18,7 @@ mod reset;
 mod set;
 
 use clap::Subcommand;
+use itertools::Itertools as _;
 use jj_lib::repo_path::RepoPathBuf;
 use tracing::instrument;
 
@@ -33,6 +34,7 @@ use crate::cli_util::print_checkout_stats;
 use crate::cli_util::CommandHelper;
 use crate::cli_util::WorkspaceCommandHelper;
 use crate::command_error::internal_error_with_message;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
@@ -60,7 +62,7 @@ pub(crate) fn cmd_sparse(
     }
 }
 
-fn update_sparse_patterns_with(
+pub(crate) fn update_sparse_patterns_with(
     ui: &mut Ui,
     workspace_command: &mut WorkspaceCommandHelper,
     f: impl FnOnce(&mut Ui, &[RepoPathBuf]) -> Result<Vec<RepoPathBuf>, CommandError>,
@@ -77,3 +79,20 @@ fn update_sparse_patterns_with(
     print_checkout_stats(ui, stats, &wc_commit)?;
     Ok(())
 }
+
+/// Parses sparse patterns from lines of text, one workspace-relative path per
+/// line. Blank lines are ignored.
+fn parse_sparse_patterns<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<RepoPathBuf>, CommandError> {
+    lines
+        .into_iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            RepoPathBuf::from_relative_path(line).map_err(|err| {
+                user_error_with_message(format!("Failed to parse sparse pattern: {line}"), err)
+            })
+        })
+        .try_collect()
+}
@@ -13,13 +13,20 @@
 // limitations under the License.
 
 use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::io::Read as _;
+use std::path::Path;
+use std::path::PathBuf;
 
 use itertools::Itertools as _;
 use jj_lib::repo_path::RepoPathBuf;
 use tracing::instrument;
 
+use super::parse_sparse_patterns;
 use super::update_sparse_patterns_with;
 use crate::cli_util::CommandHelper;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
@@ -28,6 +35,10 @@ use crate::ui::Ui;
 /// For example, if all you need is the `README.md` and the `lib/`
 /// directory, use `jj sparse set --clear --add README.md --add lib`.
 /// If you no longer need the `lib` directory, use `jj sparse set --remove lib`.
+///
+/// Patterns can also be read from a file, one per line, with `--from-file`.
+/// For example, `generate-paths | jj sparse set --clear --from-file -` replaces
+/// the patterns with the output of a script in a single step.
 #[derive(clap::Args, Clone, Debug)]
 pub struct SparseSetArgs {
     /// Patterns to add to the working copy
@@ -48,6 +59,12 @@ pub struct SparseSetArgs {
     /// Include no files in the working copy (combine with --add)
     #[arg(long)]
     clear: bool,
+    /// Read patterns to add from a file, one per line (use `-` for stdin)
+    ///
+    /// Blank lines are ignored. Combine with `--clear` to replace the existing
+    /// patterns.
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+    from_file: Option<PathBuf>,
 }
 
 #[instrument(skip_all)]
@@ -57,6 +74,10 @@ pub fn cmd_sparse_set(
     args: &SparseSetArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
+    let file_patterns = match &args.from_file {
+        Some(path) => read_sparse_patterns(path)?,
+        None => vec![],
+    };
     update_sparse_patterns_with(ui, &mut workspace_command, |_ui, old_patterns| {
         let mut new_patterns = HashSet::new();
         if !args.clear {
@@ -65,9 +86,27 @@ pub fn cmd_sparse_set(
                 new_patterns.remove(path);
             }
         }
-        for path in &args.add {
+        for path in args.add.iter().chain(&file_patterns) {
             new_patterns.insert(path.to_owned());
         }
         Ok(new_patterns.into_iter().sorted_unstable().collect())
     })
 }
+
+fn read_sparse_patterns(path: &Path) -> Result<Vec<RepoPathBuf>, CommandError> {
+    let content = if path == Path::new("-") {
+        let mut buffer = String::new();
+        io::stdin()
+            .read_to_string(&mut buffer)
+            .map_err(|err| user_error_with_message("Failed to read patterns from stdin", err))?;
+        buffer
+    } else {
+        fs::read_to_string(path).map_err(|err| {
+            user_error_with_message(
+                format!("Failed to read patterns from {}", path.display()),
+                err,
+            )
+        })?
+    };
+    parse_sparse_patterns(content.lines())
+}
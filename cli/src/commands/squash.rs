@@ -23,6 +23,7 @@ use jj_lib::object_id::ObjectId;
 use jj_lib::repo::Repo;
 use jj_lib::rewrite;
 use jj_lib::rewrite::CommitToSquash;
+use jj_lib::rewrite::SquashResult;
 use tracing::instrument;
 
 use crate::cli_util::CommandHelper;
@@ -35,6 +36,7 @@ use crate::command_error::CommandError;
 use crate::complete;
 use crate::description_util::combine_messages;
 use crate::description_util::join_message_paragraphs;
+use crate::description_util::TextEditor;
 use crate::ui::Ui;
 
 /// Move changes from a revision into another revision
@@ -141,15 +143,8 @@ pub(crate) fn cmd_squash(
     } else {
         let source = workspace_command
             .resolve_single_rev(ui, args.revision.as_ref().unwrap_or(&RevisionArg::AT))?;
-        let mut parents: Vec<_> = source.parents().try_collect()?;
-        if parents.len() != 1 {
-            return Err(user_error_with_hint(
-                "Cannot squash merge commits without a specified destination",
-                "Use `--into` to specify which parent to squash into",
-            ));
-        }
+        destination = parent_destination(&source)?;
         sources = vec![source];
-        destination = parents.pop().unwrap();
     }
 
     let matcher = workspace_command
@@ -164,22 +159,17 @@ pub(crate) fn cmd_squash(
 
     let mut tx = workspace_command.start_transaction();
     let tx_description = format!("squash commits into {}", destination.id().hex());
-    let source_commits = select_diff(&tx, &sources, &destination, &matcher, &diff_selector)?;
-    match rewrite::squash_commits(
-        tx.repo_mut(),
-        &source_commits,
+    match squash_into_destination(
+        &mut tx,
+        &sources,
         &destination,
+        &matcher,
+        &diff_selector,
+        description,
+        &text_editor,
         args.keep_emptied,
-        |abandoned_commits| match description {
-            SquashedDescription::Exact(description) => Ok(description),
-            SquashedDescription::UseDestination => Ok(destination.description().to_owned()),
-            SquashedDescription::Combine => {
-                let abandoned_commits = abandoned_commits.iter().map(|c| &c.commit).collect_vec();
-                combine_messages(&text_editor, &abandoned_commits, &destination)
-            }
-        },
     )? {
-        rewrite::SquashResult::NoChanges => {
+        SquashResult::NoChanges => {
             if diff_selector.is_interactive() {
                 return Err(user_error("No changes selected"));
             }
@@ -201,13 +191,56 @@ pub(crate) fn cmd_squash(
                 }
             }
         }
-        rewrite::SquashResult::NewCommit(_) => {}
+        SquashResult::NewCommit(_) => {}
     }
     tx.finish(ui, tx_description)?;
     Ok(())
 }
 
-enum SquashedDescription {
+/// Returns the parent of the `source` to squash into if no destination is
+/// specified.
+pub(crate) fn parent_destination(source: &Commit) -> Result<Commit, CommandError> {
+    let mut parents: Vec<_> = source.parents().try_collect()?;
+    if parents.len() != 1 {
+        return Err(user_error_with_hint(
+            "Cannot squash merge commits without a specified destination",
+            "Use `--into` to specify which parent to squash into",
+        ));
+    }
+    Ok(parents.pop().unwrap())
+}
+
+/// Moves the changes selected from the `sources` into the `destination`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn squash_into_destination(
+    tx: &mut WorkspaceCommandTransaction,
+    sources: &[Commit],
+    destination: &Commit,
+    matcher: &dyn Matcher,
+    diff_selector: &DiffSelector,
+    description: SquashedDescription,
+    text_editor: &TextEditor,
+    keep_emptied: bool,
+) -> Result<SquashResult, CommandError> {
+    let source_commits = select_diff(tx, sources, destination, matcher, diff_selector)?;
+    let result = rewrite::squash_commits(
+        tx.repo_mut(),
+        &source_commits,
+        destination,
+        keep_emptied,
+        |abandoned_commits| match description {
+            SquashedDescription::Exact(description) => Ok(description),
+            SquashedDescription::UseDestination => Ok(destination.description().to_owned()),
+            SquashedDescription::Combine => {
+                let abandoned_commits = abandoned_commits.iter().map(|c| &c.commit).collect_vec();
+                combine_messages(text_editor, &abandoned_commits, destination)
+            }
+        },
+    )?;
+    Ok(result)
+}
+
+pub(crate) enum SquashedDescription {
     // Use this exact description.
     Exact(String),
     // Use the destination's description and discard the descriptions of the
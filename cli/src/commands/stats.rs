@@ -0,0 +1,308 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::io::Write as _;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::copies::CopyRecords;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::repo::Repo as _;
+use jj_lib::settings::UserSettings;
+use rayon::iter::IntoParallelRefIterator as _;
+use rayon::iter::ParallelIterator as _;
+use serde::Serialize;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::internal_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::diff_util::get_file_line_stats;
+use crate::diff_util::DiffStatOptions;
+use crate::diff_util::FileLineStat;
+use crate::generic_templater::GenericTemplateLanguage;
+use crate::template_builder::TemplateLanguage as _;
+use crate::templater::TemplatePropertyExt as _;
+use crate::ui::Ui;
+
+/// Show statistics about the changes in a set of revisions
+///
+/// Lists the authors of the revisions, the directories and the files that were
+/// changed most often, along with the number of lines added and removed.
+/// Merge commits are compared against the merge of their parents.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct StatsArgs {
+    /// The revisions to collect statistics for
+    #[arg(
+        long,
+        short,
+        default_value = "::@",
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions)
+    )]
+    revisions: Vec<RevisionArg>,
+    /// The number of leading path components to group directories by
+    #[arg(long, default_value_t = 1, value_name = "DEPTH")]
+    depth: usize,
+    /// The maximum number of directories and files to show
+    #[arg(long, short = 'n', default_value_t = 10)]
+    limit: usize,
+    /// Render each entry using the given template
+    ///
+    /// The following keywords are defined:
+    ///
+    /// * `kind: String`: "author", "directory", or "file".
+    /// * `name: String`: Name and email of the author, or path of the
+    ///   directory or file.
+    /// * `commits: Integer`: Number of commits by the author or touching the
+    ///   path.
+    /// * `added: Integer`: Number of lines added.
+    /// * `removed: Integer`: Number of lines removed.
+    ///
+    /// For the syntax, see https://jj-vcs.github.io/jj/latest/templates/
+    #[arg(long, short = 'T', verbatim_doc_comment, conflicts_with = "json")]
+    template: Option<String>,
+    /// Print the statistics as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct StatsEntry {
+    #[serde(skip)]
+    kind: &'static str,
+    name: String,
+    commits: usize,
+    added: usize,
+    removed: usize,
+}
+
+impl StatsEntry {
+    fn new(kind: &'static str, name: String) -> Self {
+        StatsEntry {
+            kind,
+            name,
+            commits: 0,
+            added: 0,
+            removed: 0,
+        }
+    }
+
+    fn add_commit(&mut self, added: usize, removed: usize) {
+        self.commits += 1;
+        self.added += added;
+        self.removed += removed;
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Stats {
+    commits: usize,
+    added: usize,
+    removed: usize,
+    authors: Vec<StatsEntry>,
+    directories: Vec<StatsEntry>,
+    files: Vec<StatsEntry>,
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_stats(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &StatsArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let repo = workspace_command.repo();
+    let store = repo.store();
+    let commits: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .filter_ok(|commit| commit.id() != store.root_commit_id())
+        .try_collect()?;
+
+    // Merging parent trees needs the repo, so it's done upfront. Diffing the
+    // trees is the expensive part, and is spread across threads.
+    let tree_pairs: Vec<_> = commits
+        .iter()
+        .map(|commit| Ok((commit.parent_tree(repo.as_ref())?, commit.tree()?)))
+        .collect::<Result<_, CommandError>>()?;
+    let options = DiffStatOptions::from_settings(command.settings())?;
+    let conflict_marker_style = workspace_command.env().conflict_marker_style();
+    let file_stats: Vec<Vec<FileLineStat>> = tree_pairs
+        .par_iter()
+        .map(|(from_tree, to_tree)| {
+            let copy_records = CopyRecords::default();
+            let tree_diff =
+                from_tree.diff_stream_with_copies(to_tree, &EverythingMatcher, &copy_records);
+            get_file_line_stats(store, tree_diff, &options, conflict_marker_style)
+        })
+        .collect::<Result<_, _>>()?;
+
+    let stats = collect_stats(&commits, &file_stats, args.depth, args.limit);
+    if args.json {
+        let json = serde_json::to_string_pretty(&stats).map_err(internal_error)?;
+        writeln!(ui.stdout(), "{json}")?;
+        return Ok(());
+    }
+
+    let template = {
+        let language = stats_template_language(command.settings());
+        let text = match &args.template {
+            Some(value) => value.to_owned(),
+            None => command.settings().get_string("templates.stats")?,
+        };
+        command
+            .parse_template(ui, &language, &text, GenericTemplateLanguage::wrap_self)?
+            .labeled("stats")
+    };
+    ui.request_pager();
+    let mut formatter = ui.stdout_formatter();
+    writeln!(
+        formatter,
+        "{} commits, {} lines added, {} lines removed",
+        stats.commits, stats.added, stats.removed
+    )?;
+    for (heading, entries) in [
+        ("Authors", &stats.authors),
+        ("Directories", &stats.directories),
+        ("Files", &stats.files),
+    ] {
+        if entries.is_empty() {
+            continue;
+        }
+        writeln!(formatter)?;
+        writeln!(formatter, "{heading}:")?;
+        for entry in entries {
+            template.format(entry, formatter.as_mut())?;
+        }
+    }
+    Ok(())
+}
+
+fn collect_stats(
+    commits: &[Commit],
+    file_stats: &[Vec<FileLineStat>],
+    depth: usize,
+    limit: usize,
+) -> Stats {
+    let mut authors: HashMap<&str, StatsEntry> = HashMap::new();
+    let mut directories: HashMap<String, StatsEntry> = HashMap::new();
+    let mut files: HashMap<String, StatsEntry> = HashMap::new();
+    let mut total_added = 0;
+    let mut total_removed = 0;
+    for (commit, file_stats) in commits.iter().zip(file_stats) {
+        let commit_added = file_stats.iter().map(|stat| stat.added).sum();
+        let commit_removed = file_stats.iter().map(|stat| stat.removed).sum();
+        total_added += commit_added;
+        total_removed += commit_removed;
+        // Commits are in reverse topological order, so the name comes from
+        // the author's most recent commit.
+        let author = commit.author();
+        authors
+            .entry(&author.email)
+            .or_insert_with(|| {
+                StatsEntry::new("author", format!("{} <{}>", author.name, author.email))
+            })
+            .add_commit(commit_added, commit_removed);
+
+        let mut directory_stats: HashMap<String, (usize, usize)> = HashMap::new();
+        for stat in file_stats {
+            let path = stat.path.as_internal_file_string();
+            let (dir_added, dir_removed) = directory_stats
+                .entry(directory_prefix(path, depth))
+                .or_default();
+            *dir_added += stat.added;
+            *dir_removed += stat.removed;
+            files
+                .entry(path.to_owned())
+                .or_insert_with(|| StatsEntry::new("file", path.to_owned()))
+                .add_commit(stat.added, stat.removed);
+        }
+        for (dir, (added, removed)) in directory_stats {
+            directories
+                .entry(dir.clone())
+                .or_insert_with(|| StatsEntry::new("directory", dir))
+                .add_commit(added, removed);
+        }
+    }
+
+    Stats {
+        commits: commits.len(),
+        added: total_added,
+        removed: total_removed,
+        authors: sorted_entries(authors.into_values(), usize::MAX),
+        directories: sorted_entries(directories.into_values(), limit),
+        files: sorted_entries(files.into_values(), limit),
+    }
+}
+
+/// Returns the first `depth` directory components of `path`, or "." for files
+/// at the root.
+fn directory_prefix(path: &str, depth: usize) -> String {
+    let components = path.split('/').collect_vec();
+    let dirs = &components[..components.len() - 1];
+    if dirs.is_empty() || depth == 0 {
+        ".".to_owned()
+    } else {
+        format!("{}/", dirs[..depth.min(dirs.len())].join("/"))
+    }
+}
+
+/// Sorts entries by number of commits, then lines changed, and keeps the first
+/// `limit` of them.
+fn sorted_entries(entries: impl IntoIterator<Item = StatsEntry>, limit: usize) -> Vec<StatsEntry> {
+    entries
+        .into_iter()
+        .sorted_by(|a, b| {
+            (b.commits, b.added + b.removed)
+                .cmp(&(a.commits, a.added + a.removed))
+                .then_with(|| a.name.cmp(&b.name))
+        })
+        .take(limit)
+        .collect()
+}
+
+// StatsEntry will be cloned internally in the templater. If the cloning cost
+// matters, wrap it with Rc.
+fn stats_template_language(
+    settings: &UserSettings,
+) -> GenericTemplateLanguage<'static, StatsEntry> {
+    type L = GenericTemplateLanguage<'static, StatsEntry>;
+    let mut language = L::new(settings);
+    language.add_keyword("kind", |self_property| {
+        let out_property = self_property.map(|entry| entry.kind.to_owned());
+        Ok(L::wrap_string(out_property))
+    });
+    language.add_keyword("name", |self_property| {
+        let out_property = self_property.map(|entry| entry.name);
+        Ok(L::wrap_string(out_property))
+    });
+    language.add_keyword("commits", |self_property| {
+        let out_property = self_property.and_then(|entry| Ok(i64::try_from(entry.commits)?));
+        Ok(L::wrap_integer(out_property))
+    });
+    language.add_keyword("added", |self_property| {
+        let out_property = self_property.and_then(|entry| Ok(i64::try_from(entry.added)?));
+        Ok(L::wrap_integer(out_property))
+    });
+    language.add_keyword("removed", |self_property| {
+        let out_property = self_property.and_then(|entry| Ok(i64::try_from(entry.removed)?));
+        Ok(L::wrap_integer(out_property))
+    });
+    language
+}
@@ -0,0 +1,93 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::commit::CommitIteratorExt as _;
+use jj_lib::signing::SignBehavior;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Drop the cryptographic signatures of revisions
+///
+/// Unsigned revisions in the given set are left untouched. Descendants of the
+/// rewritten revisions are rebased.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct UnsignArgs {
+    /// What revision(s) to unsign
+    #[arg(
+        long,
+        short,
+        required = true,
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::mutable_revisions)
+    )]
+    revisions: Vec<RevisionArg>,
+}
+
+pub(crate) fn cmd_unsign(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &UnsignArgs,
+) -> Result<(), CommandError> {
+    let mut workspace_command = command.workspace_helper(ui)?;
+    let commits: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .filter_ok(|commit| commit.is_signed())
+        .try_collect()?;
+    workspace_command.check_rewritable(commits.iter().ids())?;
+    let commit_ids_set: HashSet<_> = commits.iter().ids().cloned().collect();
+
+    let mut tx = workspace_command.start_transaction();
+    let mut unsigned_commits = vec![];
+    let mut num_rebased = 0;
+    tx.repo_mut()
+        .transform_descendants(commits.iter().ids().cloned().collect(), |rewriter| {
+            if commit_ids_set.contains(rewriter.old_commit().id()) {
+                let commit = rewriter
+                    .reparent()
+                    .set_sign_behavior(SignBehavior::Drop)
+                    .write()?;
+                unsigned_commits.push(commit);
+            } else if rewriter.parents_changed() {
+                rewriter.reparent().write()?;
+                num_rebased += 1;
+            }
+            Ok(())
+        })?;
+
+    if let Some(mut formatter) = ui.status_formatter() {
+        if !unsigned_commits.is_empty() {
+            writeln!(formatter, "Unsigned {} commits:", unsigned_commits.len())?;
+            for commit in &unsigned_commits {
+                write!(formatter, "  ")?;
+                tx.write_commit_summary(formatter.as_mut(), commit)?;
+                writeln!(formatter)?;
+            }
+        }
+        if num_rebased > 0 {
+            writeln!(formatter, "Rebased {num_rebased} descendant commits")?;
+        }
+    }
+    tx.finish(ui, format!("unsign {} commits", unsigned_commits.len()))?;
+    Ok(())
+}
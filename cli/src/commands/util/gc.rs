@@ -12,36 +12,57 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fs;
+use std::io::Write as _;
 use std::slice;
 use std::time::Duration;
 use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 
+use itertools::Itertools as _;
+use jj_lib::config::ConfigGetResultExt as _;
+use jj_lib::local_working_copy::LocalWorkingCopy;
+use jj_lib::op_walk;
+use jj_lib::operation::Operation;
 use jj_lib::repo::Repo as _;
+use jj_lib::settings::UserSettings;
+use jj_lib::time_util::parse_datetime;
 
+use crate::cli_util::short_commit_hash;
+use crate::cli_util::short_operation_hash;
 use crate::cli_util::CommandHelper;
 use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
+use crate::commands::operation::abandon::abandon_operations;
 use crate::ui::Ui;
 
 /// Run backend-dependent garbage collection.
 ///
 /// To garbage-collect old operations and the commits/objects referenced by
-/// then, run `jj op abandon ..<some old operation>` before `jj util gc`.
+/// then, run `jj op abandon ..<some old operation>` before `jj util gc`, or
+/// configure `op-log.keep-days` or `op-log.keep-count` to abandon operations
+/// outside of these limits automatically.
 ///
 /// Previous versions of a change that are reachable via the evolution log are
 /// not garbage-collected.
+///
+/// In addition to pruning unreachable data, the backend may repack the
+/// remaining objects (`git gc` with the Git backend, or merging packs with the
+/// native backend), and temporary files left behind in the working-copy state
+/// directory by interrupted commands are removed.
 #[derive(clap::Args, Clone, Debug)]
 pub struct UtilGcArgs {
     /// Time threshold
     ///
-    /// By default, only obsolete objects and operations older than 2 weeks are
-    /// pruned.
-    ///
-    /// Only the string "now" can be passed to this parameter. Support for
-    /// arbitrary absolute and relative timestamps will come in a subsequent
-    /// release.
+    /// Only obsolete objects and operations older than this are pruned. This
+    /// can be "now" or a date such as "2 weeks ago" or "2024-02-01". Defaults
+    /// to the `gc.expire` setting, which is "2 weeks ago" unless configured.
     #[arg(long)]
     expire: Option<String>,
+    /// Report what would be removed without removing anything
+    #[arg(long)]
+    dry_run: bool,
 }
 
 pub fn cmd_util_gc(
@@ -54,16 +75,156 @@ pub fn cmd_util_gc(
             "Cannot garbage collect from a non-head operation",
         ));
     }
-    let keep_newer = match args.expire.as_deref() {
-        None => SystemTime::now() - Duration::from_secs(14 * 86400),
-        Some("now") => SystemTime::now() - Duration::ZERO,
-        _ => return Err(user_error("--expire only accepts 'now'")),
+    let expire = match &args.expire {
+        Some(expire) => expire.clone(),
+        None => command.settings().get_string("gc.expire")?,
     };
+    let keep_newer = parse_expire(&expire)?;
+    abandon_operations_beyond_retention(ui, command, args.dry_run)?;
     let workspace_command = command.workspace_helper(ui)?;
+    let local_working_copy = workspace_command
+        .working_copy()
+        .as_any()
+        .downcast_ref::<LocalWorkingCopy>();
+    let stale_temp_files = if let Some(working_copy) = local_working_copy {
+        working_copy.stale_temp_files(keep_newer)?
+    } else {
+        vec![]
+    };
 
     let repo = workspace_command.repo();
+    if args.dry_run {
+        let op_stats = repo
+            .op_store()
+            .gc_dry_run(slice::from_ref(repo.op_id()), keep_newer)?;
+        let backend_stats = repo.store().gc_dry_run(repo.index(), keep_newer)?;
+        let mut formatter = ui.stdout_formatter();
+        writeln!(
+            formatter,
+            "Operations to remove: {}",
+            op_stats.expired_operation_ids.len()
+        )?;
+        for id in &op_stats.expired_operation_ids {
+            writeln!(formatter, "  {}", short_operation_hash(id))?;
+        }
+        writeln!(
+            formatter,
+            "Views to remove: {}",
+            op_stats.expired_view_count
+        )?;
+        match op_stats.reclaimable_bytes {
+            Some(bytes) => writeln!(formatter, "Operation data to reclaim: {bytes} bytes")?,
+            None => writeln!(formatter, "Operation data to reclaim: unknown")?,
+        }
+        writeln!(
+            formatter,
+            "Commits to expire: {}",
+            backend_stats.expired_commit_ids.len()
+        )?;
+        for id in &backend_stats.expired_commit_ids {
+            writeln!(formatter, "  {}", short_commit_hash(id))?;
+        }
+        match backend_stats.reclaimable_bytes {
+            Some(bytes) => writeln!(formatter, "Object data to reclaim: {bytes} bytes")?,
+            None => writeln!(formatter, "Object data to reclaim: unknown")?,
+        }
+        writeln!(
+            formatter,
+            "Stale working-copy files to remove: {}",
+            stale_temp_files.len()
+        )?;
+        return Ok(());
+    }
+
     repo.op_store()
         .gc(slice::from_ref(repo.op_id()), keep_newer)?;
     repo.store().gc(repo.index(), keep_newer)?;
+    for path in &stale_temp_files {
+        // The file may have been removed concurrently.
+        fs::remove_file(path).ok();
+    }
     Ok(())
 }
+
+/// Abandons the operations outside of the `op-log.keep-days` and
+/// `op-log.keep-count` limits. With `dry_run`, only reports the number of
+/// operations to abandon.
+fn abandon_operations_beyond_retention(
+    ui: &Ui,
+    command: &CommandHelper,
+    dry_run: bool,
+) -> Result<(), CommandError> {
+    let mut workspace = command.load_workspace()?;
+    let repo_loader = workspace.repo_loader();
+    let current_head_ops = op_walk::get_current_head_ops(
+        repo_loader.op_store(),
+        repo_loader.op_heads_store().as_ref(),
+    )?;
+    let Some(boundary_op) = find_retention_boundary(command.settings(), &current_head_ops)? else {
+        return Ok(());
+    };
+    if dry_run {
+        // The root operation is never abandoned.
+        let count = op_walk::walk_ancestors(slice::from_ref(&boundary_op))
+            .process_results(|ops| ops.count())?
+            - 1;
+        writeln!(ui.stdout_formatter(), "Operations to abandon: {count}")?;
+        return Ok(());
+    }
+    let root_op = repo_loader.root_operation();
+    abandon_operations(
+        ui,
+        command,
+        &mut workspace,
+        &root_op,
+        slice::from_ref(&boundary_op),
+        &current_head_ops,
+    )
+}
+
+/// Finds the latest operation that is outside of the op log retention limits.
+/// Operations within any of the configured limits are kept, as well as the
+/// current head operations.
+fn find_retention_boundary(
+    settings: &UserSettings,
+    current_head_ops: &[Operation],
+) -> Result<Option<Operation>, CommandError> {
+    let keep_days: Option<u64> = settings.get("op-log.keep-days").optional()?;
+    let keep_count: Option<usize> = settings.get("op-log.keep-count").optional()?;
+    if keep_days.is_none() && keep_count.is_none() {
+        return Ok(None);
+    }
+    let keep_newer = keep_days.map(|days| {
+        SystemTime::now()
+            .checked_sub(Duration::from_secs(days.saturating_mul(24 * 60 * 60)))
+            .unwrap_or(UNIX_EPOCH)
+    });
+    for (index, op) in op_walk::walk_ancestors(current_head_ops).enumerate() {
+        let op = op?;
+        let end_millis = u64::try_from(op.metadata().end_time.timestamp.0).unwrap_or(0);
+        let end_time = UNIX_EPOCH + Duration::from_millis(end_millis);
+        if keep_count.is_some_and(|count| index < count)
+            || keep_newer.is_some_and(|time| end_time >= time)
+            || current_head_ops.contains(&op)
+        {
+            continue;
+        }
+        if op.parent_ids().is_empty() {
+            return Ok(None);
+        }
+        return Ok(Some(op));
+    }
+    Ok(None)
+}
+
+pub(crate) fn parse_expire(expire: &str) -> Result<SystemTime, CommandError> {
+    if expire == "now" {
+        return Ok(SystemTime::now() - Duration::ZERO);
+    }
+    let millis = parse_datetime(expire, chrono::Local::now()).map_err(|err| {
+        user_error_with_message(format!("Invalid expiration date {expire:?}"), err)
+    })?;
+    // Dates before the epoch can be clamped since nothing is older.
+    let millis = u64::try_from(millis.0).unwrap_or(0);
+    Ok(UNIX_EPOCH + Duration::from_millis(millis))
+}
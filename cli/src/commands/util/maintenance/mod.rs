@@ -0,0 +1,49 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod register;
+mod run;
+
+use clap::Subcommand;
+
+use self::register::cmd_util_maintenance_register;
+use self::register::UtilMaintenanceRegisterArgs;
+use self::run::cmd_util_maintenance_run;
+use self::run::UtilMaintenanceRunArgs;
+use crate::cli_util::CommandHelper;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Run or schedule repository maintenance
+///
+/// Maintenance prunes expired operations and objects, compacts the commit
+/// index, and warms caches. When automatic maintenance is enabled with
+/// `jj util maintenance register` (or the `maintenance.auto` setting), it runs
+/// in the background after enough operations or time have passed.
+#[derive(Subcommand, Clone, Debug)]
+pub enum UtilMaintenanceCommand {
+    Register(UtilMaintenanceRegisterArgs),
+    Run(UtilMaintenanceRunArgs),
+}
+
+pub fn cmd_util_maintenance(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    subcommand: &UtilMaintenanceCommand,
+) -> Result<(), CommandError> {
+    match subcommand {
+        UtilMaintenanceCommand::Register(args) => cmd_util_maintenance_register(ui, command, args),
+        UtilMaintenanceCommand::Run(args) => cmd_util_maintenance_run(ui, command, args),
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+
+use jj_lib::maintenance::MaintenanceState;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::user_error;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::ui::Ui;
+
+/// Enable automatic background maintenance for this repo
+///
+/// This sets `maintenance.auto = true` in the repo config. Maintenance will
+/// then run in the background once `maintenance.auto-operations` operations
+/// or `maintenance.auto-interval-hours` hours have passed since now.
+#[derive(clap::Args, Clone, Debug)]
+pub struct UtilMaintenanceRegisterArgs {}
+
+pub fn cmd_util_maintenance_register(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    _args: &UtilMaintenanceRegisterArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    let mut files = command
+        .config_env()
+        .repo_config_files(command.raw_config())?;
+    let mut file = match files.pop() {
+        Some(file) if files.is_empty() => file,
+        _ => return Err(user_error("Cannot determine repo config file to edit")),
+    };
+    file.set_value("maintenance.auto", true)
+        .map_err(|err| user_error_with_message("Failed to set maintenance.auto", err))?;
+    file.save()?;
+
+    // Start counting from the current operation instead of running
+    // maintenance right after the next command.
+    let repo = workspace_command.repo();
+    let state = MaintenanceState::new(workspace_command.repo_path());
+    state.record_run_if_unset(repo.op_id())?;
+    writeln!(ui.status(), "Enabled automatic maintenance for this repo.")?;
+    Ok(())
+}
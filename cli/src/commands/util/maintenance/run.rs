@@ -0,0 +1,134 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write as _;
+use std::slice;
+
+use jj_lib::default_index::DefaultIndexStore;
+use jj_lib::default_index::IndexCompactionPolicy;
+use jj_lib::maintenance::MaintenanceState;
+use jj_lib::repo::Repo as _;
+use jj_lib::simple_op_store::SimpleOpStore;
+
+use super::super::gc::parse_expire;
+use crate::cli_util::CommandHelper;
+use crate::command_error::internal_error;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::maintenance_util::auto_maintenance_schedule;
+use crate::maintenance_util::load_commits_into_caches;
+use crate::maintenance_util::lower_process_priority;
+use crate::ui::Ui;
+
+/// Maintenance task
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+enum MaintenanceTask {
+    /// Prune operations and objects older than `gc.expire`, and compress
+    /// operation store files if `operation.compress-objects` is enabled
+    Gc,
+    /// Compact the commit index and remove unused index files
+    Index,
+    /// Load reachable commits into caches
+    Cache,
+}
+
+/// Run maintenance tasks now
+///
+/// Tasks run in the order gc, index, cache. If another maintenance run is in
+/// progress, nothing is done.
+#[derive(clap::Args, Clone, Debug)]
+pub struct UtilMaintenanceRunArgs {
+    /// Task to run (can be repeated; defaults to all tasks)
+    #[arg(long = "task", value_name = "TASK")]
+    tasks: Vec<MaintenanceTask>,
+    /// Run only if automatic maintenance is enabled and due
+    #[arg(long, hide = true)]
+    auto: bool,
+}
+
+pub fn cmd_util_maintenance_run(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &UtilMaintenanceRunArgs,
+) -> Result<(), CommandError> {
+    if !command.is_at_head_operation() {
+        return Err(user_error(
+            "Cannot run maintenance from a non-head operation",
+        ));
+    }
+    let workspace_command = command.workspace_helper_no_snapshot(ui)?;
+    let repo = workspace_command.repo();
+    let state = MaintenanceState::new(workspace_command.repo_path());
+    let Some(_lock) = state.try_lock().map_err(internal_error)? else {
+        writeln!(ui.status(), "Maintenance is already running.")?;
+        return Ok(());
+    };
+    if args.auto {
+        // Another process may have completed maintenance before we took the
+        // lock, so check the schedule again.
+        let Some(schedule) = auto_maintenance_schedule(command.settings())? else {
+            return Ok(());
+        };
+        if !state.is_due(repo.operation(), &schedule)? {
+            return Ok(());
+        }
+        lower_process_priority();
+    }
+
+    let should_run = |task| args.tasks.is_empty() || args.tasks.contains(&task);
+    let keep_newer = parse_expire(&command.settings().get_string("gc.expire")?)?;
+    if should_run(MaintenanceTask::Gc) {
+        repo.op_store()
+            .gc(slice::from_ref(repo.op_id()), keep_newer)?;
+        repo.store().gc(repo.index(), keep_newer)?;
+        writeln!(ui.status(), "Pruned expired operations and objects.")?;
+        let op_store = repo.op_store();
+        if let Some(op_store) = op_store
+            .as_any()
+            .downcast_ref::<SimpleOpStore>()
+            .filter(|op_store| op_store.compresses_objects())
+        {
+            let count = op_store.compress_objects()?;
+            if count > 0 {
+                writeln!(ui.status(), "Compressed {count} operation store files.")?;
+            }
+        }
+    }
+    if should_run(MaintenanceTask::Index) {
+        let index_store = repo.loader().index_store();
+        if let Some(index_store) = index_store.as_any().downcast_ref::<DefaultIndexStore>() {
+            let policy = IndexCompactionPolicy::from_settings(command.settings())?;
+            let (num_before, num_after) = index_store
+                .compact_index_at_operation(repo.operation(), repo.store(), &policy)
+                .map_err(internal_error)?;
+            if num_after < num_before {
+                writeln!(
+                    ui.status(),
+                    "Compacted commit index from {num_before} to {num_after} segments."
+                )?;
+            }
+            let removed = index_store
+                .gc(repo.op_store().as_ref(), repo.store(), keep_newer)
+                .map_err(internal_error)?;
+            writeln!(ui.status(), "Removed {removed} unused index files.")?;
+        }
+    }
+    if should_run(MaintenanceTask::Cache) {
+        let count = load_commits_into_caches(repo)?;
+        writeln!(ui.status(), "Loaded {count} commits into caches.")?;
+    }
+
+    state.record_run(repo.op_id())?;
+    Ok(())
+}
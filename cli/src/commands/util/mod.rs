@@ -17,6 +17,7 @@ mod config_schema;
 mod exec;
 mod gc;
 mod install_man_pages;
+mod maintenance;
 mod markdown_help;
 
 use clap::Subcommand;
@@ -32,6 +33,8 @@ use self::gc::cmd_util_gc;
 use self::gc::UtilGcArgs;
 use self::install_man_pages::cmd_util_install_man_pages;
 use self::install_man_pages::UtilInstallManPagesArgs;
+use self::maintenance::cmd_util_maintenance;
+use self::maintenance::UtilMaintenanceCommand;
 use self::markdown_help::cmd_util_markdown_help;
 use self::markdown_help::UtilMarkdownHelp;
 use crate::cli_util::CommandHelper;
@@ -46,6 +49,8 @@ pub(crate) enum UtilCommand {
     Exec(UtilExecArgs),
     Gc(UtilGcArgs),
     InstallManPages(UtilInstallManPagesArgs),
+    #[command(subcommand)]
+    Maintenance(UtilMaintenanceCommand),
     MarkdownHelp(UtilMarkdownHelp),
 }
 
@@ -61,6 +66,7 @@ pub(crate) fn cmd_util(
         UtilCommand::Exec(args) => cmd_util_exec(ui, command, args),
         UtilCommand::Gc(args) => cmd_util_gc(ui, command, args),
         UtilCommand::InstallManPages(args) => cmd_util_install_man_pages(ui, command, args),
+        UtilCommand::Maintenance(subcommand) => cmd_util_maintenance(ui, command, subcommand),
         UtilCommand::MarkdownHelp(args) => cmd_util_markdown_help(ui, command, args),
     }
 }
@@ -0,0 +1,143 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap_complete::ArgValueCandidates;
+use itertools::Itertools as _;
+use jj_lib::commit::Commit;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::signing::SigStatus;
+use serde::Serialize;
+use tracing::instrument;
+
+use crate::cli_util::CommandHelper;
+use crate::cli_util::RevisionArg;
+use crate::command_error::internal_error;
+use crate::command_error::user_error;
+use crate::command_error::CommandError;
+use crate::complete;
+use crate::ui::Ui;
+
+/// Verify the cryptographic signatures of revisions
+///
+/// Prints the signature status of each revision: `good`, `bad`, `unknown` (if
+/// the signature couldn't be checked, e.g. because the key isn't trusted),
+/// `unsigned`, or `error`. Exits with an error unless all revisions have good
+/// signatures, so it can be used to require signed history in CI.
+#[derive(clap::Args, Clone, Debug)]
+pub(crate) struct VerifyArgs {
+    /// The revision(s) to verify
+    #[arg(
+        long,
+        short,
+        default_value = "@",
+        value_name = "REVSETS",
+        add = ArgValueCandidates::new(complete::all_revisions)
+    )]
+    revisions: Vec<RevisionArg>,
+    /// Print the results as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Serialize)]
+struct CommitVerification {
+    commit_id: String,
+    change_id: String,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl CommitVerification {
+    fn new(commit: &Commit) -> Self {
+        let mut verification = CommitVerification {
+            commit_id: commit.id().hex(),
+            change_id: commit.change_id().reverse_hex(),
+            status: "unsigned",
+            key: None,
+            display: None,
+            error: None,
+        };
+        match commit.verification() {
+            Ok(Some(result)) => {
+                verification.status = match result.status {
+                    SigStatus::Good => "good",
+                    SigStatus::Unknown => "unknown",
+                    SigStatus::Bad => "bad",
+                };
+                verification.key = result.key;
+                verification.display = result.display;
+            }
+            Ok(None) => {}
+            Err(err) => {
+                verification.status = "error";
+                verification.error = Some(err.to_string());
+            }
+        }
+        verification
+    }
+
+    fn is_good(&self) -> bool {
+        self.status == "good"
+    }
+}
+
+#[instrument(skip_all)]
+pub(crate) fn cmd_verify(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &VerifyArgs,
+) -> Result<(), CommandError> {
+    let workspace_command = command.workspace_helper(ui)?;
+    let commits: Vec<Commit> = workspace_command
+        .parse_union_revsets(ui, &args.revisions)?
+        .evaluate_to_commits()?
+        .try_collect()?;
+    let verifications = commits.iter().map(CommitVerification::new).collect_vec();
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&verifications).map_err(internal_error)?;
+        writeln!(ui.stdout(), "{json}")?;
+    } else {
+        ui.request_pager();
+        let mut formatter = ui.stdout_formatter();
+        for (commit, verification) in commits.iter().zip(&verifications) {
+            write!(formatter, "{:<8} ", verification.status)?;
+            workspace_command.write_commit_summary(formatter.as_mut(), commit)?;
+            match (&verification.display, &verification.key) {
+                (Some(display), Some(key)) => write!(formatter, " ({display}, key {key})")?,
+                (Some(display), None) => write!(formatter, " ({display})")?,
+                (None, Some(key)) => write!(formatter, " (key {key})")?,
+                (None, None) => {}
+            }
+            if let Some(error) = &verification.error {
+                write!(formatter, ": {error}")?;
+            }
+            writeln!(formatter)?;
+        }
+    }
+
+    let num_failed = verifications.iter().filter(|v| !v.is_good()).count();
+    match num_failed {
+        0 => Ok(()),
+        1 => Err(user_error("1 commit doesn't have a good signature")),
+        n => Err(user_error(format!(
+            "{n} commits don't have a good signature"
+        ))),
+    }
+}
@@ -13,17 +13,21 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::cell::RefCell;
 use std::cmp::max;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::io;
+use std::path::Path;
 use std::rc::Rc;
 
 use futures::stream::BoxStream;
+use futures::StreamExt as _;
 use itertools::Itertools as _;
 use jj_lib::backend::BackendResult;
 use jj_lib::backend::ChangeId;
 use jj_lib::backend::CommitId;
+use jj_lib::backend::FileId;
 use jj_lib::commit::Commit;
 use jj_lib::conflicts::ConflictMarkerStyle;
 use jj_lib::copies::CopiesTreeDiffEntry;
@@ -32,6 +36,7 @@ use jj_lib::extensions_map::ExtensionsMap;
 use jj_lib::fileset;
 use jj_lib::fileset::FilesetDiagnostics;
 use jj_lib::fileset::FilesetExpression;
+use jj_lib::git;
 use jj_lib::id_prefix::IdPrefixContext;
 use jj_lib::id_prefix::IdPrefixIndex;
 use jj_lib::matchers::Matcher;
@@ -41,6 +46,7 @@ use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::op_store::WorkspaceId;
 use jj_lib::repo::Repo;
+use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::revset;
 use jj_lib::revset::Revset;
@@ -56,7 +62,9 @@ use jj_lib::signing::SignResult;
 use jj_lib::signing::Verification;
 use jj_lib::store::Store;
 use once_cell::unsync::OnceCell;
+use pollster::FutureExt as _;
 
+use crate::description_util;
 use crate::diff_util;
 use crate::formatter::Formatter;
 use crate::revset_util;
@@ -66,6 +74,7 @@ use crate::template_builder::BuildContext;
 use crate::template_builder::CoreTemplateBuildFnTable;
 use crate::template_builder::CoreTemplatePropertyKind;
 use crate::template_builder::IntoTemplateProperty;
+use crate::template_builder::TemplateBuildFunctionFnMap;
 use crate::template_builder::TemplateBuildMethodFnMap;
 use crate::template_builder::TemplateLanguage;
 use crate::template_parser;
@@ -75,7 +84,9 @@ use crate::template_parser::TemplateDiagnostics;
 use crate::template_parser::TemplateParseError;
 use crate::template_parser::TemplateParseResult;
 use crate::templater;
+use crate::templater::Literal;
 use crate::templater::PlainTextFormattedProperty;
+use crate::templater::ShortestIdPrefix;
 use crate::templater::SizeHint;
 use crate::templater::Template;
 use crate::templater::TemplateFormatter;
@@ -92,6 +103,7 @@ pub trait CommitTemplateLanguageExtension {
 
 pub struct CommitTemplateLanguage<'repo> {
     repo: &'repo dyn Repo,
+    repo_path: &'repo Path,
     path_converter: &'repo RepoPathUiConverter,
     workspace_id: WorkspaceId,
     // RevsetParseContext doesn't borrow a repo, but we'll need 'repo lifetime
@@ -114,6 +126,7 @@ impl<'repo> CommitTemplateLanguage<'repo> {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         repo: &'repo dyn Repo,
+        repo_path: &'repo Path,
         path_converter: &'repo RepoPathUiConverter,
         workspace_id: &WorkspaceId,
         revset_parse_context: RevsetParseContext<'repo>,
@@ -134,6 +147,7 @@ impl<'repo> CommitTemplateLanguage<'repo> {
 
         CommitTemplateLanguage {
             repo,
+            repo_path,
             path_converter,
             workspace_id: workspace_id.clone(),
             revset_parse_context,
@@ -206,6 +220,7 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                     property,
                     function,
                     Self::wrap_commit,
+                    Self::wrap_commit_list,
                 )
             }
             CommitTemplatePropertyKind::RefName(property) => {
@@ -235,6 +250,7 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                     property,
                     function,
                     Self::wrap_ref_name,
+                    Self::wrap_ref_name_list,
                 )
             }
             CommitTemplatePropertyKind::CommitOrChangeId(property) => {
@@ -242,13 +258,13 @@ impl<'repo> TemplateLanguage<'repo> for CommitTemplateLanguage<'repo> {
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(self, diagnostics, build_ctx, property, function)
             }
-            CommitTemplatePropertyKind::ShortestIdPrefix(property) => {
-                let table = &self.build_fn_table.shortest_id_prefix_methods;
+            CommitTemplatePropertyKind::TreeDiff(property) => {
+                let table = &self.build_fn_table.tree_diff_methods;
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(self, diagnostics, build_ctx, property, function)
             }
-            CommitTemplatePropertyKind::TreeDiff(property) => {
-                let table = &self.build_fn_table.tree_diff_methods;
+            CommitTemplatePropertyKind::TreeEntry(property) => {
+                let table = &self.build_fn_table.tree_entry_methods;
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(self, diagnostics, build_ctx, property, function)
             }
@@ -330,18 +346,18 @@ impl<'repo> CommitTemplateLanguage<'repo> {
         CommitTemplatePropertyKind::CommitOrChangeId(Box::new(property))
     }
 
-    pub fn wrap_shortest_id_prefix(
-        property: impl TemplateProperty<Output = ShortestIdPrefix> + 'repo,
-    ) -> CommitTemplatePropertyKind<'repo> {
-        CommitTemplatePropertyKind::ShortestIdPrefix(Box::new(property))
-    }
-
     pub fn wrap_tree_diff(
         property: impl TemplateProperty<Output = TreeDiff> + 'repo,
     ) -> CommitTemplatePropertyKind<'repo> {
         CommitTemplatePropertyKind::TreeDiff(Box::new(property))
     }
 
+    pub fn wrap_tree_entry(
+        property: impl TemplateProperty<Output = TreeEntry> + 'repo,
+    ) -> CommitTemplatePropertyKind<'repo> {
+        CommitTemplatePropertyKind::TreeEntry(Box::new(property))
+    }
+
     fn wrap_cryptographic_signature_opt(
         property: impl TemplateProperty<Output = Option<CryptographicSignature>> + 'repo,
     ) -> CommitTemplatePropertyKind<'repo> {
@@ -358,8 +374,8 @@ pub enum CommitTemplatePropertyKind<'repo> {
     RefNameOpt(Box<dyn TemplateProperty<Output = Option<Rc<RefName>>> + 'repo>),
     RefNameList(Box<dyn TemplateProperty<Output = Vec<Rc<RefName>>> + 'repo>),
     CommitOrChangeId(Box<dyn TemplateProperty<Output = CommitOrChangeId> + 'repo>),
-    ShortestIdPrefix(Box<dyn TemplateProperty<Output = ShortestIdPrefix> + 'repo>),
     TreeDiff(Box<dyn TemplateProperty<Output = TreeDiff> + 'repo>),
+    TreeEntry(Box<dyn TemplateProperty<Output = TreeEntry> + 'repo>),
     CryptographicSignatureOpt(
         Box<dyn TemplateProperty<Output = Option<CryptographicSignature>> + 'repo>,
     ),
@@ -376,8 +392,8 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             CommitTemplatePropertyKind::RefNameOpt(_) => "Option<RefName>",
             CommitTemplatePropertyKind::RefNameList(_) => "List<RefName>",
             CommitTemplatePropertyKind::CommitOrChangeId(_) => "CommitOrChangeId",
-            CommitTemplatePropertyKind::ShortestIdPrefix(_) => "ShortestIdPrefix",
             CommitTemplatePropertyKind::TreeDiff(_) => "TreeDiff",
+            CommitTemplatePropertyKind::TreeEntry(_) => "TreeEntry",
             CommitTemplatePropertyKind::CryptographicSignatureOpt(_) => {
                 "Option<CryptographicSignature>"
             }
@@ -402,10 +418,10 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
                 Some(Box::new(property.map(|l| !l.is_empty())))
             }
             CommitTemplatePropertyKind::CommitOrChangeId(_) => None,
-            CommitTemplatePropertyKind::ShortestIdPrefix(_) => None,
             // TODO: boolean cast could be implemented, but explicit
             // diff.empty() method might be better.
             CommitTemplatePropertyKind::TreeDiff(_) => None,
+            CommitTemplatePropertyKind::TreeEntry(_) => None,
             CommitTemplatePropertyKind::CryptographicSignatureOpt(property) => {
                 Some(Box::new(property.map(|sig| sig.is_some())))
             }
@@ -441,10 +457,8 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             CommitTemplatePropertyKind::CommitOrChangeId(property) => {
                 Some(property.into_template())
             }
-            CommitTemplatePropertyKind::ShortestIdPrefix(property) => {
-                Some(property.into_template())
-            }
             CommitTemplatePropertyKind::TreeDiff(_) => None,
+            CommitTemplatePropertyKind::TreeEntry(_) => None,
             CommitTemplatePropertyKind::CryptographicSignatureOpt(_) => None,
         }
     }
@@ -462,8 +476,8 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             (CommitTemplatePropertyKind::RefNameOpt(_), _) => None,
             (CommitTemplatePropertyKind::RefNameList(_), _) => None,
             (CommitTemplatePropertyKind::CommitOrChangeId(_), _) => None,
-            (CommitTemplatePropertyKind::ShortestIdPrefix(_), _) => None,
             (CommitTemplatePropertyKind::TreeDiff(_), _) => None,
+            (CommitTemplatePropertyKind::TreeEntry(_), _) => None,
             (CommitTemplatePropertyKind::CryptographicSignatureOpt(_), _) => None,
         }
     }
@@ -484,8 +498,8 @@ impl<'repo> IntoTemplateProperty<'repo> for CommitTemplatePropertyKind<'repo> {
             (CommitTemplatePropertyKind::RefNameOpt(_), _) => None,
             (CommitTemplatePropertyKind::RefNameList(_), _) => None,
             (CommitTemplatePropertyKind::CommitOrChangeId(_), _) => None,
-            (CommitTemplatePropertyKind::ShortestIdPrefix(_), _) => None,
             (CommitTemplatePropertyKind::TreeDiff(_), _) => None,
+            (CommitTemplatePropertyKind::TreeEntry(_), _) => None,
             (CommitTemplatePropertyKind::CryptographicSignatureOpt(_), _) => None,
         }
     }
@@ -501,8 +515,8 @@ pub struct CommitTemplateBuildFnTable<'repo> {
     pub commit_methods: CommitTemplateBuildMethodFnMap<'repo, Commit>,
     pub ref_name_methods: CommitTemplateBuildMethodFnMap<'repo, Rc<RefName>>,
     pub commit_or_change_id_methods: CommitTemplateBuildMethodFnMap<'repo, CommitOrChangeId>,
-    pub shortest_id_prefix_methods: CommitTemplateBuildMethodFnMap<'repo, ShortestIdPrefix>,
     pub tree_diff_methods: CommitTemplateBuildMethodFnMap<'repo, TreeDiff>,
+    pub tree_entry_methods: CommitTemplateBuildMethodFnMap<'repo, TreeEntry>,
     pub cryptographic_signature_methods:
         CommitTemplateBuildMethodFnMap<'repo, CryptographicSignature>,
 }
@@ -510,13 +524,15 @@ pub struct CommitTemplateBuildFnTable<'repo> {
 impl<'repo> CommitTemplateBuildFnTable<'repo> {
     /// Creates new symbol table containing the builtin methods.
     fn builtin() -> Self {
+        let mut core = CoreTemplateBuildFnTable::builtin();
+        core.functions.extend(builtin_repo_functions());
         CommitTemplateBuildFnTable {
-            core: CoreTemplateBuildFnTable::builtin(),
+            core,
             commit_methods: builtin_commit_methods(),
             ref_name_methods: builtin_ref_name_methods(),
             commit_or_change_id_methods: builtin_commit_or_change_id_methods(),
-            shortest_id_prefix_methods: builtin_shortest_id_prefix_methods(),
             tree_diff_methods: builtin_tree_diff_methods(),
+            tree_entry_methods: builtin_tree_entry_methods(),
             cryptographic_signature_methods: builtin_cryptographic_signature_methods(),
         }
     }
@@ -527,8 +543,8 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             commit_methods: HashMap::new(),
             ref_name_methods: HashMap::new(),
             commit_or_change_id_methods: HashMap::new(),
-            shortest_id_prefix_methods: HashMap::new(),
             tree_diff_methods: HashMap::new(),
+            tree_entry_methods: HashMap::new(),
             cryptographic_signature_methods: HashMap::new(),
         }
     }
@@ -539,8 +555,8 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             commit_methods,
             ref_name_methods,
             commit_or_change_id_methods,
-            shortest_id_prefix_methods,
             tree_diff_methods,
+            tree_entry_methods,
             cryptographic_signature_methods,
         } = extension;
 
@@ -551,11 +567,8 @@ impl<'repo> CommitTemplateBuildFnTable<'repo> {
             &mut self.commit_or_change_id_methods,
             commit_or_change_id_methods,
         );
-        merge_fn_map(
-            &mut self.shortest_id_prefix_methods,
-            shortest_id_prefix_methods,
-        );
         merge_fn_map(&mut self.tree_diff_methods, tree_diff_methods);
+        merge_fn_map(&mut self.tree_entry_methods, tree_entry_methods);
         merge_fn_map(
             &mut self.cryptographic_signature_methods,
             cryptographic_signature_methods,
@@ -570,6 +583,7 @@ pub struct CommitKeywordCache<'repo> {
     tags_index: OnceCell<Rc<RefNamesIndex>>,
     git_refs_index: OnceCell<Rc<RefNamesIndex>>,
     is_immutable_fn: OnceCell<Rc<RevsetContainingFn<'repo>>>,
+    revset_containing_fns: RefCell<HashMap<String, Rc<RevsetContainingFn<'repo>>>>,
 }
 
 impl<'repo> CommitKeywordCache<'repo> {
@@ -602,6 +616,91 @@ impl<'repo> CommitKeywordCache<'repo> {
             Ok(revset.containing_fn().into())
         })
     }
+
+    /// Evaluates the user `revset`, and returns function that tests whether a
+    /// commit is contained in the revset.
+    ///
+    /// The result is cached by the revset text, so the same revset referenced
+    /// from multiple template expressions is evaluated only once.
+    pub fn revset_containing_fn(
+        &self,
+        language: &CommitTemplateLanguage<'repo>,
+        diagnostics: &mut TemplateDiagnostics,
+        span: pest::Span<'_>,
+        revset: &str,
+    ) -> TemplateParseResult<Rc<RevsetContainingFn<'repo>>> {
+        if let Some(is_contained) = self.revset_containing_fns.borrow().get(revset) {
+            return Ok(is_contained.clone());
+        }
+        let is_contained: Rc<RevsetContainingFn<'repo>> =
+            evaluate_user_revset(language, diagnostics, span, revset)?
+                .containing_fn()
+                .into();
+        self.revset_containing_fns
+            .borrow_mut()
+            .insert(revset.to_owned(), is_contained.clone());
+        Ok(is_contained)
+    }
+}
+
+/// Global functions describing the repository and workspace rather than a
+/// specific commit.
+fn builtin_repo_functions<'repo>(
+) -> TemplateBuildFunctionFnMap<'repo, CommitTemplateLanguage<'repo>> {
+    type L<'repo> = CommitTemplateLanguage<'repo>;
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = TemplateBuildFunctionFnMap::<L>::new();
+    map.insert(
+        "repo_path",
+        |language, _diagnostics, _build_ctx, function| {
+            function.expect_no_arguments()?;
+            let path = language.repo_path.to_string_lossy().into_owned();
+            Ok(L::wrap_string(Literal(path)))
+        },
+    );
+    map.insert(
+        "workspace_root",
+        |language, _diagnostics, _build_ctx, function| {
+            function.expect_no_arguments()?;
+            let RepoPathUiConverter::Fs { base, .. } = language.path_converter;
+            let path = base.to_string_lossy().into_owned();
+            Ok(L::wrap_string(Literal(path)))
+        },
+    );
+    map.insert(
+        "workspace_name",
+        |language, _diagnostics, _build_ctx, function| {
+            function.expect_no_arguments()?;
+            let name = language.workspace_id.as_str().to_owned();
+            Ok(L::wrap_string(Literal(name)))
+        },
+    );
+    map.insert(
+        "current_operation",
+        |language, _diagnostics, _build_ctx, function| {
+            function.expect_no_arguments()?;
+            let op_id = language.repo.base_repo().op_id().hex();
+            Ok(L::wrap_string(Literal(op_id)))
+        },
+    );
+    map.insert(
+        "remote_names",
+        |language, _diagnostics, _build_ctx, function| {
+            function.expect_no_arguments()?;
+            let names = language
+                .repo
+                .view()
+                .store_view()
+                .remote_views
+                .keys()
+                .filter(|name| !git::is_special_git_remote(name))
+                .cloned()
+                .collect();
+            Ok(L::wrap_string_list(Literal(names)))
+        },
+    );
+    map
 }
 
 fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Commit> {
@@ -645,6 +744,25 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(L::wrap_commit_list(out_property))
         },
     );
+    map.insert(
+        "duplicated_from",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let out_property = self_property.and_then(|commit| {
+                // The original commit may not exist in this repo, e.g. if the
+                // commit was fetched from elsewhere.
+                let maybe_id =
+                    description_util::parse_duplicated_from_trailer(commit.description())
+                        .filter(|id| id.as_bytes().len() == repo.store().commit_id_length())
+                        .filter(|id| repo.index().has_id(id));
+                Ok(maybe_id
+                    .map(|id| repo.store().get_commit(&id))
+                    .transpose()?)
+            });
+            Ok(L::wrap_commit_opt(out_property))
+        },
+    );
     map.insert(
         "author",
         |_language, _diagnostics, _build_ctx, self_property, function| {
@@ -856,13 +974,44 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
 
             let is_contained =
                 template_parser::expect_string_literal_with(revset_node, |revset, span| {
-                    Ok(evaluate_user_revset(language, diagnostics, span, revset)?.containing_fn())
+                    language
+                        .keyword_cache
+                        .revset_containing_fn(language, diagnostics, span, revset)
                 })?;
 
             let out_property = self_property.and_then(move |commit| Ok(is_contained(commit.id())?));
             Ok(L::wrap_boolean(out_property))
         },
     );
+    map.insert(
+        "contained_in_any",
+        |language, diagnostics, _build_ctx, self_property, function| {
+            let ([first_node], other_nodes) = function.expect_some_arguments()?;
+
+            let is_contained_fns: Vec<_> = itertools::chain([first_node], other_nodes)
+                .map(|revset_node| {
+                    template_parser::expect_string_literal_with(revset_node, |revset, span| {
+                        language.keyword_cache.revset_containing_fn(
+                            language,
+                            diagnostics,
+                            span,
+                            revset,
+                        )
+                    })
+                })
+                .try_collect()?;
+
+            let out_property = self_property.and_then(move |commit| {
+                for is_contained in &is_contained_fns {
+                    if is_contained(commit.id())? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            });
+            Ok(L::wrap_boolean(out_property))
+        },
+    );
     map.insert(
         "conflict",
         |_language, _diagnostics, _build_ctx, self_property, function| {
@@ -871,6 +1020,14 @@ fn builtin_commit_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Comm
             Ok(L::wrap_boolean(out_property))
         },
     );
+    map.insert(
+        "shallow",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|commit| Ok(commit.is_shallow()?));
+            Ok(L::wrap_boolean(out_property))
+        },
+    );
     map.insert(
         "empty",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -1001,6 +1158,33 @@ pub struct RefName {
     synced: bool,
 }
 
+/// Position of a tracked remote ref relative to the tracking local ref.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TrackingStatus {
+    /// Remote and local refs point to the same commits.
+    InSync,
+    /// Remote ref has commits which the local ref doesn't have.
+    Ahead,
+    /// Local ref has commits which the remote ref doesn't have.
+    Behind,
+    /// Both refs have commits which the other doesn't have.
+    Diverged,
+    /// Local ref has been deleted.
+    Deleted,
+}
+
+impl TrackingStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrackingStatus::InSync => "in-sync",
+            TrackingStatus::Ahead => "ahead",
+            TrackingStatus::Behind => "behind",
+            TrackingStatus::Diverged => "diverged",
+            TrackingStatus::Deleted => "deleted",
+        }
+    }
+}
+
 #[derive(Debug)]
 struct TrackingRef {
     /// Local ref target which tracks the other remote ref.
@@ -1116,7 +1300,7 @@ impl RefName {
     }
 
     /// Number of commits ahead of the tracking local ref.
-    fn tracking_ahead_count(&self, repo: &dyn Repo) -> Result<SizeHint, TemplatePropertyError> {
+    pub fn tracking_ahead_count(&self, repo: &dyn Repo) -> Result<SizeHint, TemplatePropertyError> {
         let Some(tracking) = &self.tracking_ref else {
             return Err(TemplatePropertyError("Not a tracked remote ref".into()));
         };
@@ -1131,7 +1315,10 @@ impl RefName {
     }
 
     /// Number of commits behind of the tracking local ref.
-    fn tracking_behind_count(&self, repo: &dyn Repo) -> Result<SizeHint, TemplatePropertyError> {
+    pub fn tracking_behind_count(
+        &self,
+        repo: &dyn Repo,
+    ) -> Result<SizeHint, TemplatePropertyError> {
         let Some(tracking) = &self.tracking_ref else {
             return Err(TemplatePropertyError("Not a tracked remote ref".into()));
         };
@@ -1144,6 +1331,25 @@ impl RefName {
             })
             .copied()
     }
+
+    /// Position relative to the tracking local ref.
+    pub fn tracking_status(
+        &self,
+        repo: &dyn Repo,
+    ) -> Result<TrackingStatus, TemplatePropertyError> {
+        if self.is_tracked() && !self.is_tracking_present() {
+            return Ok(TrackingStatus::Deleted);
+        }
+        let (ahead, _) = self.tracking_ahead_count(repo)?;
+        let (behind, _) = self.tracking_behind_count(repo)?;
+        let status = match (ahead > 0, behind > 0) {
+            (false, false) => TrackingStatus::InSync,
+            (true, false) => TrackingStatus::Ahead,
+            (false, true) => TrackingStatus::Behind,
+            (true, true) => TrackingStatus::Diverged,
+        };
+        Ok(status)
+    }
 }
 
 // If wrapping with Rc<T> becomes common, add generic impl for Rc<T>.
@@ -1281,6 +1487,16 @@ fn builtin_ref_name_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, Rc
             Ok(L::wrap_size_hint(out_property))
         },
     );
+    map.insert(
+        "tracking_status",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let repo = language.repo;
+            let out_property = self_property
+                .and_then(|ref_name| Ok(ref_name.tracking_status(repo)?.as_str().to_owned()));
+            Ok(L::wrap_string(out_property))
+        },
+    );
     map
 }
 
@@ -1459,75 +1675,6 @@ fn builtin_commit_or_change_id_methods<'repo>(
     map
 }
 
-pub struct ShortestIdPrefix {
-    pub prefix: String,
-    pub rest: String,
-}
-
-impl Template for ShortestIdPrefix {
-    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
-        write!(formatter.labeled("prefix"), "{}", self.prefix)?;
-        write!(formatter.labeled("rest"), "{}", self.rest)?;
-        Ok(())
-    }
-}
-
-impl ShortestIdPrefix {
-    fn to_upper(&self) -> Self {
-        Self {
-            prefix: self.prefix.to_ascii_uppercase(),
-            rest: self.rest.to_ascii_uppercase(),
-        }
-    }
-    fn to_lower(&self) -> Self {
-        Self {
-            prefix: self.prefix.to_ascii_lowercase(),
-            rest: self.rest.to_ascii_lowercase(),
-        }
-    }
-}
-
-fn builtin_shortest_id_prefix_methods<'repo>(
-) -> CommitTemplateBuildMethodFnMap<'repo, ShortestIdPrefix> {
-    type L<'repo> = CommitTemplateLanguage<'repo>;
-    // Not using maplit::hashmap!{} or custom declarative macro here because
-    // code completion inside macro is quite restricted.
-    let mut map = CommitTemplateBuildMethodFnMap::<ShortestIdPrefix>::new();
-    map.insert(
-        "prefix",
-        |_language, _diagnostics, _build_ctx, self_property, function| {
-            function.expect_no_arguments()?;
-            let out_property = self_property.map(|id| id.prefix);
-            Ok(L::wrap_string(out_property))
-        },
-    );
-    map.insert(
-        "rest",
-        |_language, _diagnostics, _build_ctx, self_property, function| {
-            function.expect_no_arguments()?;
-            let out_property = self_property.map(|id| id.rest);
-            Ok(L::wrap_string(out_property))
-        },
-    );
-    map.insert(
-        "upper",
-        |_language, _diagnostics, _build_ctx, self_property, function| {
-            function.expect_no_arguments()?;
-            let out_property = self_property.map(|id| id.to_upper());
-            Ok(L::wrap_shortest_id_prefix(out_property))
-        },
-    );
-    map.insert(
-        "lower",
-        |_language, _diagnostics, _build_ctx, self_property, function| {
-            function.expect_no_arguments()?;
-            let out_property = self_property.map(|id| id.to_lower());
-            Ok(L::wrap_shortest_id_prefix(out_property))
-        },
-    );
-    map
-}
-
 /// Pair of trees to be diffed.
 #[derive(Debug)]
 pub struct TreeDiff {
@@ -1562,6 +1709,34 @@ impl TreeDiff {
             .diff_stream_with_copies(&self.to_tree, &*self.matcher, &self.copy_records)
     }
 
+    /// Paths of the changed files, after the change.
+    fn changed_paths(&self) -> BackendResult<Vec<RepoPathBuf>> {
+        let mut diff_stream = self.diff_stream();
+        async {
+            let mut paths = vec![];
+            while let Some(CopiesTreeDiffEntry { path, values }) = diff_stream.next().await {
+                values?;
+                paths.push(path.target().to_owned());
+            }
+            Ok(paths)
+        }
+        .block_on()
+    }
+
+    fn stats(
+        &self,
+        path_converter: &RepoPathUiConverter,
+        conflict_marker_style: ConflictMarkerStyle,
+    ) -> Result<diff_util::DiffStats, diff_util::DiffRenderError> {
+        diff_util::DiffStats::calculate(
+            self.from_tree.store(),
+            self.diff_stream(),
+            path_converter,
+            &default_diff_stat_options(self.from_tree.store()),
+            conflict_marker_style,
+        )
+    }
+
     fn into_formatted<F, E>(self, show: F) -> TreeDiffFormatted<F>
     where
         F: Fn(&mut dyn Formatter, &Store, BoxStream<CopiesTreeDiffEntry>) -> Result<(), E>,
@@ -1611,14 +1786,13 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                 .transpose()?;
             let path_converter = language.path_converter;
             let conflict_marker_style = language.conflict_marker_style;
+            let line_diff = default_line_diff_options(language.repo.store());
             let template = (self_property, context_property)
                 .map(move |(diff, context)| {
                     // TODO: load defaults from UserSettings?
                     let options = diff_util::ColorWordsDiffOptions {
                         context: context.unwrap_or(diff_util::DEFAULT_CONTEXT_LINES),
-                        line_diff: diff_util::LineDiffOptions {
-                            compare_mode: diff_util::LineCompareMode::Exact,
-                        },
+                        line_diff: line_diff.clone(),
                         max_inline_alternation: Some(3),
                     };
                     diff.into_formatted(move |formatter, store, tree_diff| {
@@ -1651,13 +1825,12 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
                 })
                 .transpose()?;
             let conflict_marker_style = language.conflict_marker_style;
+            let line_diff = default_line_diff_options(language.repo.store());
             let template = (self_property, context_property)
                 .map(move |(diff, context)| {
                     let options = diff_util::UnifiedDiffOptions {
                         context: context.unwrap_or(diff_util::DEFAULT_CONTEXT_LINES),
-                        line_diff: diff_util::LineDiffOptions {
-                            compare_mode: diff_util::LineCompareMode::Exact,
-                        },
+                        line_diff: line_diff.clone(),
                     };
                     diff.into_formatted(move |formatter, store, tree_diff| {
                         diff_util::show_git_diff(
@@ -1685,13 +1858,10 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
             )?;
             let path_converter = language.path_converter;
             let conflict_marker_style = language.conflict_marker_style;
+            let options = default_diff_stat_options(language.repo.store());
             let template = (self_property, width_property)
                 .map(move |(diff, width)| {
-                    let options = diff_util::DiffStatOptions {
-                        line_diff: diff_util::LineDiffOptions {
-                            compare_mode: diff_util::LineCompareMode::Exact,
-                        },
-                    };
+                    let options = options.clone();
                     diff.into_formatted(move |formatter, store, tree_diff| {
                         diff_util::show_diff_stat(
                             formatter,
@@ -1723,9 +1893,157 @@ fn builtin_tree_diff_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, T
             Ok(L::wrap_template(template))
         },
     );
+    map.insert(
+        "shortstat",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let path_converter = language.path_converter;
+            let conflict_marker_style = language.conflict_marker_style;
+            let template = self_property
+                .map(move |diff| {
+                    diff.into_formatted(move |formatter, store, tree_diff| {
+                        let stats = diff_util::DiffStats::calculate(
+                            store,
+                            tree_diff,
+                            path_converter,
+                            &default_diff_stat_options(store),
+                            conflict_marker_style,
+                        )?;
+                        diff_util::write_diff_stat_summary(
+                            formatter,
+                            stats.count_files(),
+                            stats.count_lines_added(),
+                            stats.count_lines_removed(),
+                        )?;
+                        Ok::<(), diff_util::DiffRenderError>(())
+                    })
+                })
+                .into_template();
+            Ok(L::wrap_template(template))
+        },
+    );
+    map.insert(
+        "files",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let path_converter = language.path_converter;
+            let out_property = self_property.and_then(move |diff| {
+                let paths = diff.changed_paths()?;
+                Ok(paths
+                    .iter()
+                    .map(|path| path_converter.format_file_path(path))
+                    .collect())
+            });
+            Ok(L::wrap_string_list(out_property))
+        },
+    );
+    map.insert(
+        "lines_added",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let path_converter = language.path_converter;
+            let conflict_marker_style = language.conflict_marker_style;
+            let out_property = self_property.and_then(move |diff| {
+                let stats = diff.stats(path_converter, conflict_marker_style)?;
+                Ok(i64::try_from(stats.count_lines_added())?)
+            });
+            Ok(L::wrap_integer(out_property))
+        },
+    );
+    map.insert(
+        "lines_removed",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let path_converter = language.path_converter;
+            let conflict_marker_style = language.conflict_marker_style;
+            let out_property = self_property.and_then(move |diff| {
+                let stats = diff.stats(path_converter, conflict_marker_style)?;
+                Ok(i64::try_from(stats.count_lines_removed())?)
+            });
+            Ok(L::wrap_integer(out_property))
+        },
+    );
     // TODO: add types() and name_only()? or let users write their own template?
     // TODO: add support for external tools
-    // TODO: add files() or map() to support custom summary-like formatting?
+    map
+}
+
+// TODO: load defaults from UserSettings?
+fn default_line_diff_options(store: &Store) -> diff_util::LineDiffOptions {
+    diff_util::LineDiffOptions {
+        compare_mode: diff_util::LineCompareMode::Exact,
+        max_content_size: store.memory_budget().max_file_content_size(),
+    }
+}
+
+fn default_diff_stat_options(store: &Store) -> diff_util::DiffStatOptions {
+    diff_util::DiffStatOptions {
+        line_diff: default_line_diff_options(store),
+    }
+}
+
+/// File entry printed by `jj file show`.
+#[derive(Clone, Debug)]
+pub struct TreeEntry {
+    pub path: RepoPathBuf,
+    /// Id of the file contents, or `None` if the file is conflicted.
+    pub blob_id: Option<FileId>,
+    /// Size of the materialized conflict in bytes, or `None` if the file isn't
+    /// conflicted. The size of a resolved file is read from the store on
+    /// demand.
+    pub conflict_size: Option<usize>,
+}
+
+fn builtin_tree_entry_methods<'repo>() -> CommitTemplateBuildMethodFnMap<'repo, TreeEntry> {
+    type L<'repo> = CommitTemplateLanguage<'repo>;
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = CommitTemplateBuildMethodFnMap::<TreeEntry>::new();
+    map.insert(
+        "path",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let path_converter = language.path_converter;
+            let out_property =
+                self_property.map(|entry| path_converter.format_file_path(&entry.path));
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "size",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let store = language.repo.store().clone();
+            let out_property = self_property.and_then(move |entry| {
+                let size = match &entry.blob_id {
+                    Some(id) => {
+                        let mut reader = store.read_file(&entry.path, id)?;
+                        io::copy(&mut reader, &mut io::sink())?
+                    }
+                    None => u64::try_from(entry.conflict_size.unwrap_or(0))?,
+                };
+                Ok(i64::try_from(size)?)
+            });
+            Ok(L::wrap_integer(out_property))
+        },
+    );
+    map.insert(
+        "blob_id",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.map(|entry| entry.blob_id.map_or_else(String::new, |id| id.hex()));
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "conflict",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|entry| entry.blob_id.is_none());
+            Ok(L::wrap_boolean(out_property))
+        },
+    );
     map
 }
 
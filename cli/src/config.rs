@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::borrow::Cow;
+use std::cell::OnceCell;
 use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::env;
@@ -29,8 +30,10 @@ use jj_lib::config::ConfigLoadError;
 use jj_lib::config::ConfigMigrationRule;
 use jj_lib::config::ConfigNamePathBuf;
 use jj_lib::config::ConfigResolutionContext;
+use jj_lib::config::ConfigSchema;
 use jj_lib::config::ConfigSource;
 use jj_lib::config::ConfigValue;
+use jj_lib::config::EnvInterpolationMode;
 use jj_lib::config::StackedConfig;
 use regex::Captures;
 use regex::Regex;
@@ -44,6 +47,14 @@ use crate::command_error::CommandError;
 // TODO(#879): Consider generating entire schema dynamically vs. static file.
 pub const CONFIG_SCHEMA: &str = include_str!("config-schema.json");
 
+/// Returns the typed schema parsed from [`CONFIG_SCHEMA`].
+pub fn config_schema() -> &'static ConfigSchema {
+    static SCHEMA: once_cell::sync::Lazy<ConfigSchema> = once_cell::sync::Lazy::new(|| {
+        ConfigSchema::from_json(CONFIG_SCHEMA).expect("built-in config schema should be valid")
+    });
+    &SCHEMA
+}
+
 /// Parses a TOML value expression. Interprets the given value as string if it
 /// can't be parsed and doesn't look like a TOML expression.
 pub fn parse_value_or_bare_string(value_str: &str) -> Result<ConfigValue, toml_edit::TomlError> {
@@ -96,7 +107,8 @@ pub struct AnnotatedValue {
     pub value: ConfigValue,
     /// Source of the configuration value.
     pub source: ConfigSource,
-    // TODO: add source file path
+    /// Path to the source file, if available.
+    pub path: Option<PathBuf>,
     /// True if this value is overridden in higher precedence layers.
     pub is_overridden: bool,
 }
@@ -151,6 +163,7 @@ pub fn resolved_config_values(
                     name,
                     value,
                     source: layer.source,
+                    path: layer.path.clone(),
                     is_overridden,
                 });
             }
@@ -261,6 +274,7 @@ impl UnresolvedConfigEnv {
 pub struct ConfigEnv {
     home_dir: Option<PathBuf>,
     repo_path: Option<PathBuf>,
+    remote_urls: OnceCell<Vec<String>>,
     user_config_path: ConfigPath,
     repo_config_path: ConfigPath,
 }
@@ -279,6 +293,7 @@ impl ConfigEnv {
         Ok(ConfigEnv {
             home_dir,
             repo_path: None,
+            remote_urls: OnceCell::new(),
             user_config_path: env.resolve()?,
             repo_config_path: ConfigPath::Unavailable,
         })
@@ -344,6 +359,7 @@ impl ConfigEnv {
     /// is usually `.jj/repo`.
     pub fn reset_repo_path(&mut self, path: &Path) {
         self.repo_path = Some(path.to_owned());
+        self.remote_urls = OnceCell::new();
         self.repo_config_path = ConfigPath::new(Some(path.join("config.toml")));
     }
 
@@ -392,14 +408,43 @@ impl ConfigEnv {
         Ok(())
     }
 
-    /// Resolves conditional scopes within the current environment. Returns new
-    /// resolved config.
+    /// Resolves conditional scopes and `${NAME}` references within the current
+    /// environment. Returns new resolved config.
     pub fn resolve_config(&self, config: &RawConfig) -> Result<StackedConfig, ConfigGetError> {
+        // Remote URLs are read from the repository only if a scope is
+        // conditioned on them.
+        let remote_urls = || {
+            self.remote_urls
+                .get_or_init(|| match &self.repo_path {
+                    Some(path) => read_git_remote_urls(path),
+                    None => vec![],
+                })
+                .as_slice()
+        };
         let context = ConfigResolutionContext {
             home_dir: self.home_dir.as_deref(),
             repo_path: self.repo_path.as_deref(),
+            remote_urls: &remote_urls,
         };
-        jj_lib::config::resolve(config.as_ref(), &context)
+        let resolved = jj_lib::config::resolve(config.as_ref(), &context)?;
+        let mode: EnvInterpolationMode = resolved.get("config.env-interpolation")?;
+        jj_lib::config::interpolate_env(&resolved, mode, &|name| env::var(name).ok())
+    }
+}
+
+/// Reads URLs of the Git remotes configured in the repository at `repo_path`.
+///
+/// Errors are ignored since they will be reported when the repository is
+/// loaded.
+fn read_git_remote_urls(repo_path: &Path) -> Vec<String> {
+    #[cfg(feature = "git")]
+    {
+        jj_lib::git_backend::read_remote_urls(&repo_path.join("store")).unwrap_or_default()
+    }
+    #[cfg(not(feature = "git"))]
+    {
+        let _ = repo_path;
+        vec![]
     }
 }
 
@@ -524,9 +569,52 @@ fn env_overrides_layer() -> ConfigLayer {
     if let Ok(value) = env::var("JJ_EDITOR") {
         layer.set_value("ui.editor", value).unwrap();
     }
+    // Sort variables so that conflicting names are resolved deterministically.
+    let config_vars = env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+        .filter_map(|(name, value)| {
+            let path = parse_env_config_name(&name)?;
+            Some((name, path, value))
+        })
+        .sorted_by(|(a, _, _), (b, _, _)| a.cmp(b));
+    for (name, path, value_str) in config_vars {
+        let value = match parse_value_or_bare_string(&value_str) {
+            Ok(value) => value,
+            Err(err) => {
+                tracing::warn!(?err, ?name, "failed to parse config value from environment");
+                continue;
+            }
+        };
+        if let Err(err) = layer.set_value(&path, value) {
+            tracing::warn!(?err, ?name, "failed to set config value from environment");
+        }
+    }
     layer
 }
 
+/// Prefix of environment variables that override arbitrary config values.
+const ENV_CONFIG_PREFIX: &str = "JJ_CONFIG_";
+
+/// Parses `JJ_CONFIG_<NAME>` environment variable name into config name path.
+///
+/// `__` (double underscore) separates table keys, and `_` is translated to
+/// `-`. Keys are lowercased. For example, `JJ_CONFIG_UI__DIFF_EDITOR` is
+/// mapped to `ui.diff-editor`.
+fn parse_env_config_name(name: &str) -> Option<ConfigNamePathBuf> {
+    let rest = name.strip_prefix(ENV_CONFIG_PREFIX)?;
+    if rest.is_empty() {
+        return None;
+    }
+    let keys: Vec<String> = rest
+        .split("__")
+        .map(|key| key.to_ascii_lowercase().replace('_', "-"))
+        .collect();
+    if keys.iter().any(|key| key.is_empty()) {
+        return None;
+    }
+    Some(ConfigNamePathBuf::from_iter(keys))
+}
+
 /// Configuration source/data type provided as command-line argument.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ConfigArgKind {
@@ -755,6 +843,16 @@ mod tests {
         settings
     }
 
+    #[test]
+    fn test_config_schema_accepts_default_config() {
+        for layer in default_config_layers() {
+            assert_eq!(
+                config_schema().validate_table(layer.data.as_table()),
+                vec![]
+            );
+        }
+    }
+
     #[test]
     fn test_parse_value_or_bare_string() {
         let parse = |s: &str| parse_value_or_bare_string(s);
@@ -788,6 +886,35 @@ mod tests {
         assert!(parse("[table]\nkey = 'value'").is_err());
     }
 
+    #[test]
+    fn test_parse_env_config_name() {
+        assert_eq!(parse_env_config_name("JJ_CONFIG"), None);
+        assert_eq!(parse_env_config_name("JJ_CONFIG_"), None);
+        assert_eq!(parse_env_config_name("JJ_USER"), None);
+        assert_eq!(parse_env_config_name("JJ_CONFIG_UI__"), None);
+        assert_eq!(parse_env_config_name("JJ_CONFIG___UI"), None);
+        assert_eq!(
+            parse_env_config_name("JJ_CONFIG_UI"),
+            Some(ConfigNamePathBuf::from_iter(["ui"]))
+        );
+        assert_eq!(
+            parse_env_config_name("JJ_CONFIG_UI__COLOR"),
+            Some(ConfigNamePathBuf::from_iter(["ui", "color"]))
+        );
+        assert_eq!(
+            parse_env_config_name("JJ_CONFIG_UI__DIFF_EDITOR"),
+            Some(ConfigNamePathBuf::from_iter(["ui", "diff-editor"]))
+        );
+        assert_eq!(
+            parse_env_config_name("JJ_CONFIG_merge_tools__vimdiff__program"),
+            Some(ConfigNamePathBuf::from_iter([
+                "merge-tools",
+                "vimdiff",
+                "program"
+            ]))
+        );
+    }
+
     #[test]
     fn test_parse_config_arg_item() {
         assert!(parse_config_arg_item("").is_err());
@@ -944,6 +1071,7 @@ mod tests {
                     },
                 ),
                 source: EnvBase,
+                path: None,
                 is_overridden: false,
             },
             AnnotatedValue {
@@ -971,6 +1099,7 @@ mod tests {
                     },
                 ),
                 source: EnvBase,
+                path: None,
                 is_overridden: true,
             },
             AnnotatedValue {
@@ -998,6 +1127,7 @@ mod tests {
                     },
                 ),
                 source: Repo,
+                path: None,
                 is_overridden: false,
             },
         ]
@@ -1046,6 +1176,7 @@ mod tests {
                     },
                 ),
                 source: User,
+                path: None,
                 is_overridden: false,
             },
             AnnotatedValue {
@@ -1073,6 +1204,7 @@ mod tests {
                     },
                 ),
                 source: Repo,
+                path: None,
                 is_overridden: false,
             },
         ]
@@ -1367,6 +1499,7 @@ mod tests {
             Ok(ConfigEnv {
                 home_dir,
                 repo_path: None,
+                remote_urls: OnceCell::new(),
                 user_config_path: env.resolve()?,
                 repo_config_path: ConfigPath::Unavailable,
             })
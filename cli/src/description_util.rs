@@ -14,13 +14,18 @@ use itertools::Itertools;
 use jj_lib::backend::CommitId;
 use jj_lib::commit::Commit;
 use jj_lib::config::ConfigGetError;
+use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::file_util::IoResultExt as _;
 use jj_lib::file_util::PathError;
+use jj_lib::object_id::ObjectId as _;
 use jj_lib::settings::UserSettings;
+use jj_lib::trailer;
+use jj_lib::trailer::Trailer;
 use thiserror::Error;
 
 use crate::cli_util::short_commit_hash;
 use crate::cli_util::WorkspaceCommandTransaction;
+use crate::command_error::user_error_with_message;
 use crate::command_error::CommandError;
 use crate::config::CommandNameAndArgs;
 use crate::formatter::PlainTextFormatter;
@@ -283,6 +288,23 @@ where
     })
 }
 
+/// Combines the descriptions from the input commits without asking the user.
+/// If only one is non-empty, then that one is used. Returns `None` if more
+/// than one is non-empty.
+pub fn try_combine_messages(sources: &[&Commit], destination: &Commit) -> Option<String> {
+    let non_empty = sources
+        .iter()
+        .chain(std::iter::once(&destination))
+        .filter(|c| !c.description().is_empty())
+        .take(2)
+        .collect_vec();
+    match *non_empty.as_slice() {
+        [] => Some(String::new()),
+        [commit] => Some(commit.description().to_owned()),
+        _ => None,
+    }
+}
+
 /// Combines the descriptions from the input commits. If only one is non-empty,
 /// then that one is used. Otherwise we concatenate the messages and ask the
 /// user to edit the result in their editor.
@@ -291,20 +313,8 @@ pub fn combine_messages(
     sources: &[&Commit],
     destination: &Commit,
 ) -> Result<String, CommandError> {
-    let non_empty = sources
-        .iter()
-        .chain(std::iter::once(&destination))
-        .filter(|c| !c.description().is_empty())
-        .take(2)
-        .collect_vec();
-    match *non_empty.as_slice() {
-        [] => {
-            return Ok(String::new());
-        }
-        [commit] => {
-            return Ok(commit.description().to_owned());
-        }
-        _ => {}
+    if let Some(description) = try_combine_messages(sources, destination) {
+        return Ok(description);
     }
     // Produce a combined description with instructions for the user to edit.
     // Include empty descriptins too, so the user doesn't have to wonder why they
@@ -361,6 +371,75 @@ pub fn description_template(
     Ok(output.into_string_lossy())
 }
 
+/// Appends the trailers rendered by the `templates.commit_trailers` template,
+/// and a `Signed-off-by` trailer for the configured user if `signoff` is set,
+/// to the description of the commit.
+///
+/// Trailers already present in the description aren't added again, so this can
+/// be applied repeatedly. Empty descriptions are left unchanged.
+pub fn add_commit_trailers(
+    ui: &Ui,
+    tx: &WorkspaceCommandTransaction,
+    commit: &Commit,
+    description: &str,
+    signoff: bool,
+) -> Result<String, CommandError> {
+    let mut trailers = vec![];
+    if let Some(template_text) = tx
+        .settings()
+        .get_string("templates.commit_trailers")
+        .optional()?
+    {
+        let template = tx.parse_commit_template(ui, &template_text)?;
+        let mut output = Vec::new();
+        template
+            .format(commit, &mut PlainTextFormatter::new(&mut output))
+            .expect("write() to vec backed formatter should never fail");
+        trailers = trailer::parse_trailers(&output.into_string_lossy()).map_err(|err| {
+            user_error_with_message("Invalid output of `templates.commit_trailers`", err)
+        })?;
+    }
+    if signoff {
+        let settings = tx.settings();
+        trailers.push(Trailer {
+            key: "Signed-off-by".to_owned(),
+            value: format!("{} <{}>", settings.user_name(), settings.user_email()),
+        });
+    }
+    Ok(trailer::add_trailers(description, &trailers))
+}
+
+/// Trailer key recording the commit a duplicated commit was copied from.
+pub const DUPLICATED_FROM_TRAILER: &str = "Duplicated-from";
+
+/// Returns the description of the `original` commit with a `Duplicated-from`
+/// trailer pointing at it appended.
+///
+/// If the description is empty, the trailer is put after an empty subject line
+/// so that it can be parsed back as a trailer.
+pub fn add_duplicated_from_trailer(original: &Commit) -> String {
+    let trailer = Trailer {
+        key: DUPLICATED_FROM_TRAILER.to_owned(),
+        value: original.id().hex(),
+    };
+    let description = original.description();
+    if description.trim().is_empty() {
+        format!("\n\n{}: {}\n", trailer.key, trailer.value)
+    } else {
+        trailer::add_trailers(description, &[trailer])
+    }
+}
+
+/// Parses the ID of the commit recorded in the last `Duplicated-from` trailer
+/// of the description.
+pub fn parse_duplicated_from_trailer(description: &str) -> Option<CommitId> {
+    trailer::parse_description_trailers(description)
+        .into_iter()
+        .rev()
+        .find(|trailer| trailer.key == DUPLICATED_FROM_TRAILER)
+        .and_then(|trailer| CommitId::try_from_hex(&trailer.value).ok())
+}
+
 #[cfg(test)]
 mod tests {
     use indexmap::indexmap;
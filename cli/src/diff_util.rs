@@ -16,6 +16,7 @@ use std::borrow::Borrow;
 use std::cmp::max;
 use std::collections::HashSet;
 use std::io;
+use std::io::Read as _;
 use std::mem;
 use std::ops::Range;
 use std::path::Path;
@@ -34,9 +35,9 @@ use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
 use jj_lib::config::ConfigGetError;
 use jj_lib::config::ConfigGetResultExt as _;
-use jj_lib::conflicts::materialize_merge_result_to_bytes;
 use jj_lib::conflicts::materialized_diff_stream;
 use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::conflicts::MaterializedMergeReader;
 use jj_lib::conflicts::MaterializedTreeDiffEntry;
 use jj_lib::conflicts::MaterializedTreeValue;
 use jj_lib::copies::CopiesTreeDiffEntry;
@@ -60,6 +61,7 @@ use jj_lib::object_id::ObjectId;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::InvalidRepoPathError;
 use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
 use jj_lib::repo_path::RepoPathUiConverter;
 use jj_lib::rewrite::rebase_to_dest_parent;
 use jj_lib::settings::UserSettings;
@@ -195,7 +197,7 @@ fn diff_formats_from_args(
         formats.push(DiffFormat::ColorWords(Box::new(options)));
     }
     if args.stat {
-        let options = DiffStatOptions::from_args(args);
+        let options = DiffStatOptions::from_settings_and_args(settings, args)?;
         formats.push(DiffFormat::Stat(Box::new(options)));
     }
     if let Some(name) = &args.tool {
@@ -240,7 +242,7 @@ fn default_diff_format(
             Ok(DiffFormat::ColorWords(Box::new(options)))
         }
         "stat" => {
-            let options = DiffStatOptions::from_args(args);
+            let options = DiffStatOptions::from_settings_and_args(settings, args)?;
             Ok(DiffFormat::Stat(Box::new(options)))
         }
         _ => Err(ConfigGetError::Type {
@@ -487,11 +489,17 @@ pub fn get_copy_records<'a>(
 pub struct LineDiffOptions {
     /// How equivalence of lines is tested.
     pub compare_mode: LineCompareMode,
+    /// Files larger than this many bytes are shown as binary instead of being
+    /// loaded in full. `None` means unlimited.
+    pub max_content_size: Option<u64>,
     // TODO: add --ignore-blank-lines, etc. which aren't mutually exclusive.
 }
 
 impl LineDiffOptions {
-    fn from_args(args: &DiffFormatArgs) -> Self {
+    fn from_settings_and_args(
+        settings: &UserSettings,
+        args: &DiffFormatArgs,
+    ) -> Result<Self, ConfigGetError> {
         let compare_mode = if args.ignore_all_space {
             LineCompareMode::IgnoreAllSpace
         } else if args.ignore_space_change {
@@ -499,7 +507,11 @@ impl LineDiffOptions {
         } else {
             LineCompareMode::Exact
         };
-        LineDiffOptions { compare_mode }
+        let max_content_size = settings.memory_budget()?.max_file_content_size();
+        Ok(LineDiffOptions {
+            compare_mode,
+            max_content_size,
+        })
     }
 }
 
@@ -565,7 +577,7 @@ impl ColorWordsDiffOptions {
             .map_or_else(|| settings.get("diff.color-words.context"), Ok)?;
         Ok(ColorWordsDiffOptions {
             context,
-            line_diff: LineDiffOptions::from_args(args),
+            line_diff: LineDiffOptions::from_settings_and_args(settings, args)?,
             max_inline_alternation,
         })
     }
@@ -858,7 +870,10 @@ impl FileContent {
     }
 }
 
-fn file_content_for_diff(reader: &mut dyn io::Read) -> io::Result<FileContent> {
+fn file_content_for_diff(
+    reader: &mut dyn io::Read,
+    max_size: Option<u64>,
+) -> io::Result<FileContent> {
     // If this is a binary file, don't show the full contents.
     // Determine whether it's binary by whether the first 8k bytes contain a null
     // character; this is the same heuristic used by git as of writing: https://github.com/git/git/blob/eea0e59ffbed6e33d171ace5be13cde9faa41639/xdiff-interface.c#L192-L198
@@ -867,7 +882,19 @@ fn file_content_for_diff(reader: &mut dyn io::Read) -> io::Result<FileContent> {
     // only need to know the file size. To change that we'd have to extend all
     // the data backends to support getting the length.
     let mut contents = vec![];
-    reader.read_to_end(&mut contents)?;
+    if let Some(max_size) = max_size {
+        io::Read::take(reader, max_size.saturating_add(1)).read_to_end(&mut contents)?;
+        if contents.len() as u64 > max_size {
+            // Too large to diff within the memory budget. Only the start of
+            // the file is kept, which is enough to tell the sides apart.
+            return Ok(FileContent {
+                is_binary: true,
+                contents,
+            });
+        }
+    } else {
+        reader.read_to_end(&mut contents)?;
+    }
 
     let start = &contents[..PEEK_SIZE.min(contents.len())];
     Ok(FileContent {
@@ -880,6 +907,7 @@ fn diff_content(
     path: &RepoPath,
     value: MaterializedTreeValue,
     conflict_marker_style: ConflictMarkerStyle,
+    max_size: Option<u64>,
 ) -> io::Result<FileContent> {
     match value {
         MaterializedTreeValue::Absent => Ok(FileContent::empty()),
@@ -887,7 +915,9 @@ fn diff_content(
             is_binary: false,
             contents: format!("Access denied: {err}").into_bytes(),
         }),
-        MaterializedTreeValue::File { mut reader, .. } => file_content_for_diff(&mut reader),
+        MaterializedTreeValue::File { mut reader, .. } => {
+            file_content_for_diff(&mut reader, max_size)
+        }
         MaterializedTreeValue::Symlink { id: _, target } => Ok(FileContent {
             // Unix file paths can't contain null bytes.
             is_binary: false,
@@ -897,15 +927,14 @@ fn diff_content(
             is_binary: false,
             contents: format!("Git submodule checked out at {id}").into_bytes(),
         }),
-        // TODO: are we sure this is never binary?
         MaterializedTreeValue::FileConflict {
             id: _,
             contents,
             executable: _,
-        } => Ok(FileContent {
-            is_binary: false,
-            contents: materialize_merge_result_to_bytes(&contents, conflict_marker_style).into(),
-        }),
+        } => {
+            let mut reader = MaterializedMergeReader::new(&contents, conflict_marker_style);
+            file_content_for_diff(&mut reader, max_size)
+        }
         MaterializedTreeValue::OtherConflict { id } => Ok(FileContent {
             is_binary: false,
             contents: id.describe().into_bytes(),
@@ -979,7 +1008,12 @@ pub fn show_color_words_diff(
                     formatter.labeled("header"),
                     "Added {description} {right_ui_path}:"
                 )?;
-                let right_content = diff_content(right_path, right_value, conflict_marker_style)?;
+                let right_content = diff_content(
+                    right_path,
+                    right_value,
+                    conflict_marker_style,
+                    options.line_diff.max_content_size,
+                )?;
                 if right_content.is_empty() {
                     writeln!(formatter.labeled("empty"), "    (empty)")?;
                 } else if right_content.is_binary {
@@ -1041,8 +1075,18 @@ pub fn show_color_words_diff(
                         )
                     }
                 };
-                let left_content = diff_content(left_path, left_value, conflict_marker_style)?;
-                let right_content = diff_content(right_path, right_value, conflict_marker_style)?;
+                let left_content = diff_content(
+                    left_path,
+                    left_value,
+                    conflict_marker_style,
+                    options.line_diff.max_content_size,
+                )?;
+                let right_content = diff_content(
+                    right_path,
+                    right_value,
+                    conflict_marker_style,
+                    options.line_diff.max_content_size,
+                )?;
                 if left_path == right_path {
                     writeln!(
                         formatter.labeled("header"),
@@ -1070,7 +1114,12 @@ pub fn show_color_words_diff(
                     formatter.labeled("header"),
                     "Removed {description} {right_ui_path}:"
                 )?;
-                let left_content = diff_content(left_path, left_value, conflict_marker_style)?;
+                let left_content = diff_content(
+                    left_path,
+                    left_value,
+                    conflict_marker_style,
+                    options.line_diff.max_content_size,
+                )?;
                 if left_content.is_empty() {
                     writeln!(formatter.labeled("empty"), "    (empty)")?;
                 } else if left_content.is_binary {
@@ -1100,7 +1149,7 @@ pub fn show_file_by_file_diff(
      -> Result<PathBuf, DiffRenderError> {
         let fs_path = path.to_fs_path(wc_dir)?;
         std::fs::create_dir_all(fs_path.parent().unwrap())?;
-        let content = diff_content(path, value, conflict_marker_style)?;
+        let content = diff_content(path, value, conflict_marker_style, None)?;
         std::fs::write(&fs_path, content.contents)?;
         Ok(fs_path)
     };
@@ -1167,6 +1216,7 @@ fn git_diff_part(
     path: &RepoPath,
     value: MaterializedTreeValue,
     conflict_marker_style: ConflictMarkerStyle,
+    max_size: Option<u64>,
 ) -> Result<GitDiffPart, DiffRenderError> {
     const DUMMY_HASH: &str = "0000000000";
     let mode;
@@ -1193,7 +1243,7 @@ fn git_diff_part(
         } => {
             mode = if executable { "100755" } else { "100644" };
             hash = id.hex();
-            content = file_content_for_diff(&mut reader)?;
+            content = file_content_for_diff(&mut reader, max_size)?;
         }
         MaterializedTreeValue::Symlink { id, target } => {
             mode = "120000";
@@ -1217,11 +1267,8 @@ fn git_diff_part(
         } => {
             mode = if executable { "100755" } else { "100644" };
             hash = DUMMY_HASH.to_owned();
-            content = FileContent {
-                is_binary: false, // TODO: are we sure this is never binary?
-                contents: materialize_merge_result_to_bytes(&contents, conflict_marker_style)
-                    .into(),
-            };
+            let mut reader = MaterializedMergeReader::new(&contents, conflict_marker_style);
+            content = file_content_for_diff(&mut reader, max_size)?;
         }
         MaterializedTreeValue::OtherConflict { id } => {
             mode = "100644";
@@ -1261,7 +1308,7 @@ impl UnifiedDiffOptions {
             .map_or_else(|| settings.get("diff.git.context"), Ok)?;
         Ok(UnifiedDiffOptions {
             context,
-            line_diff: LineDiffOptions::from_args(args),
+            line_diff: LineDiffOptions::from_settings_and_args(settings, args)?,
         })
     }
 }
@@ -1510,8 +1557,18 @@ pub fn show_git_diff(
             let right_path_string = right_path.as_internal_file_string();
             let (left_value, right_value) = values?;
 
-            let left_part = git_diff_part(left_path, left_value, conflict_marker_style)?;
-            let right_part = git_diff_part(right_path, right_value, conflict_marker_style)?;
+            let left_part = git_diff_part(
+                left_path,
+                left_value,
+                conflict_marker_style,
+                options.line_diff.max_content_size,
+            )?;
+            let right_part = git_diff_part(
+                right_path,
+                right_value,
+                conflict_marker_style,
+                options.line_diff.max_content_size,
+            )?;
 
             formatter.with_label("file_header", |formatter| {
                 writeln!(
@@ -1631,10 +1688,23 @@ pub struct DiffStatOptions {
 }
 
 impl DiffStatOptions {
-    fn from_args(args: &DiffFormatArgs) -> Self {
-        DiffStatOptions {
-            line_diff: LineDiffOptions::from_args(args),
-        }
+    fn from_settings_and_args(
+        settings: &UserSettings,
+        args: &DiffFormatArgs,
+    ) -> Result<Self, ConfigGetError> {
+        Ok(DiffStatOptions {
+            line_diff: LineDiffOptions::from_settings_and_args(settings, args)?,
+        })
+    }
+
+    /// Options for counting lines exactly, within the configured memory budget.
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(DiffStatOptions {
+            line_diff: LineDiffOptions {
+                compare_mode: LineCompareMode::Exact,
+                max_content_size: settings.memory_budget()?.max_file_content_size(),
+            },
+        })
     }
 }
 
@@ -1651,6 +1721,21 @@ fn get_diff_stat(
     right_content: &FileContent,
     options: &DiffStatOptions,
 ) -> DiffStat {
+    let (added, removed) = count_changed_lines(left_content, right_content, options);
+    DiffStat {
+        path,
+        added,
+        removed,
+        is_deletion: right_content.contents.is_empty(),
+    }
+}
+
+/// Returns the number of lines added and removed between the two contents.
+fn count_changed_lines(
+    left_content: &FileContent,
+    right_content: &FileContent,
+    options: &DiffStatOptions,
+) -> (usize, usize) {
     // TODO: this matches git's behavior, which is to count the number of newlines
     // in the file. but that behavior seems unhelpful; no one really cares how
     // many `0x0a` characters are in an image.
@@ -1670,52 +1755,171 @@ fn get_diff_stat(
             }
         }
     }
-    DiffStat {
-        path,
-        added,
-        removed,
-        is_deletion: right_content.contents.is_empty(),
-    }
+    (added, removed)
 }
 
-pub fn show_diff_stat(
-    formatter: &mut dyn Formatter,
+/// Number of lines added and removed in a file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FileLineStat {
+    /// Path of the file after the change.
+    pub path: RepoPathBuf,
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Counts the lines added and removed in each file of `tree_diff`.
+pub fn get_file_line_stats(
     store: &Store,
     tree_diff: BoxStream<CopiesTreeDiffEntry>,
-    path_converter: &RepoPathUiConverter,
     options: &DiffStatOptions,
-    display_width: usize,
     conflict_marker_style: ConflictMarkerStyle,
-) -> Result<(), DiffRenderError> {
-    let mut stats: Vec<DiffStat> = vec![];
-    let mut unresolved_renames = HashSet::new();
-    let mut max_path_width = 0;
-    let mut max_diffs = 0;
-
+) -> Result<Vec<FileLineStat>, DiffRenderError> {
+    let mut stats = vec![];
     let mut diff_stream = materialized_diff_stream(store, tree_diff);
     async {
         while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
             let (left, right) = values?;
-            let left_path = path.source();
-            let right_path = path.target();
-            let left_content = diff_content(left_path, left, conflict_marker_style)?;
-            let right_content = diff_content(right_path, right, conflict_marker_style)?;
-
-            let left_ui_path = path_converter.format_file_path(left_path);
-            let path = if left_path == right_path {
-                left_ui_path
-            } else {
-                unresolved_renames.insert(left_ui_path);
-                path_converter.format_copied_path(left_path, right_path)
-            };
-            max_path_width = max(max_path_width, path.width());
-            let stat = get_diff_stat(path, &left_content, &right_content, options);
-            max_diffs = max(max_diffs, stat.added + stat.removed);
-            stats.push(stat);
+            let left_content = diff_content(
+                path.source(),
+                left,
+                conflict_marker_style,
+                options.line_diff.max_content_size,
+            )?;
+            let right_content = diff_content(
+                path.target(),
+                right,
+                conflict_marker_style,
+                options.line_diff.max_content_size,
+            )?;
+            let (added, removed) = count_changed_lines(&left_content, &right_content, options);
+            stats.push(FileLineStat {
+                path: path.target().to_owned(),
+                added,
+                removed,
+            });
         }
         Ok::<(), DiffRenderError>(())
     }
     .block_on()?;
+    Ok(stats)
+}
+
+/// Lines added and removed in each file of a diff, with paths formatted for
+/// display.
+pub struct DiffStats {
+    entries: Vec<DiffStat>,
+    unresolved_renames: HashSet<String>,
+}
+
+impl DiffStats {
+    /// Counts the lines added and removed in each file of `tree_diff`.
+    pub fn calculate(
+        store: &Store,
+        tree_diff: BoxStream<CopiesTreeDiffEntry>,
+        path_converter: &RepoPathUiConverter,
+        options: &DiffStatOptions,
+        conflict_marker_style: ConflictMarkerStyle,
+    ) -> Result<Self, DiffRenderError> {
+        let mut entries: Vec<DiffStat> = vec![];
+        let mut unresolved_renames = HashSet::new();
+        let mut diff_stream = materialized_diff_stream(store, tree_diff);
+        async {
+            while let Some(MaterializedTreeDiffEntry { path, values }) = diff_stream.next().await {
+                let (left, right) = values?;
+                let left_path = path.source();
+                let right_path = path.target();
+                let left_content = diff_content(
+                    left_path,
+                    left,
+                    conflict_marker_style,
+                    options.line_diff.max_content_size,
+                )?;
+                let right_content = diff_content(
+                    right_path,
+                    right,
+                    conflict_marker_style,
+                    options.line_diff.max_content_size,
+                )?;
+
+                let left_ui_path = path_converter.format_file_path(left_path);
+                let path = if left_path == right_path {
+                    left_ui_path
+                } else {
+                    unresolved_renames.insert(left_ui_path);
+                    path_converter.format_copied_path(left_path, right_path)
+                };
+                entries.push(get_diff_stat(path, &left_content, &right_content, options));
+            }
+            Ok::<(), DiffRenderError>(())
+        }
+        .block_on()?;
+        Ok(DiffStats {
+            entries,
+            unresolved_renames,
+        })
+    }
+
+    /// Entries to show, excluding the deletions of renamed files.
+    fn counted_entries(&self) -> impl Iterator<Item = &DiffStat> {
+        self.entries
+            .iter()
+            .filter(|stat| !(stat.is_deletion && self.unresolved_renames.contains(&stat.path)))
+    }
+
+    /// Number of files changed.
+    pub fn count_files(&self) -> usize {
+        self.counted_entries().count()
+    }
+
+    /// Total number of lines added.
+    pub fn count_lines_added(&self) -> usize {
+        self.counted_entries().map(|stat| stat.added).sum()
+    }
+
+    /// Total number of lines removed.
+    pub fn count_lines_removed(&self) -> usize {
+        self.counted_entries().map(|stat| stat.removed).sum()
+    }
+}
+
+pub fn show_diff_stat(
+    formatter: &mut dyn Formatter,
+    store: &Store,
+    tree_diff: BoxStream<CopiesTreeDiffEntry>,
+    path_converter: &RepoPathUiConverter,
+    options: &DiffStatOptions,
+    display_width: usize,
+    conflict_marker_style: ConflictMarkerStyle,
+) -> Result<(), DiffRenderError> {
+    let stats = DiffStats::calculate(
+        store,
+        tree_diff,
+        path_converter,
+        options,
+        conflict_marker_style,
+    )?;
+    show_diff_stats(formatter, &stats, display_width)?;
+    Ok(())
+}
+
+/// Renders a histogram of the `stats`, followed by a summary line.
+pub fn show_diff_stats(
+    formatter: &mut dyn Formatter,
+    stats: &DiffStats,
+    display_width: usize,
+) -> io::Result<()> {
+    let max_path_width = stats
+        .entries
+        .iter()
+        .map(|stat| stat.path.width())
+        .max()
+        .unwrap_or(0);
+    let max_diffs = stats
+        .entries
+        .iter()
+        .map(|stat| stat.added + stat.removed)
+        .max()
+        .unwrap_or(0);
 
     let number_padding = max_diffs.to_string().len();
     // 4 characters padding for the graph
@@ -1730,17 +1934,7 @@ pub fn show_diff_stat(
         max_bar_length as f64 / max_diffs as f64
     };
 
-    let mut total_added = 0;
-    let mut total_removed = 0;
-    let mut total_files = 0;
-    for stat in &stats {
-        if stat.is_deletion && unresolved_renames.contains(&stat.path) {
-            continue;
-        }
-
-        total_added += stat.added;
-        total_removed += stat.removed;
-        total_files += 1;
+    for stat in stats.counted_entries() {
         let bar_added = (stat.added as f64 * factor).ceil() as usize;
         let bar_removed = (stat.removed as f64 * factor).ceil() as usize;
         // replace start of path with ellipsis if the path is too long
@@ -1756,17 +1950,31 @@ pub fn show_diff_stat(
         write!(formatter.labeled("added"), "{}", "+".repeat(bar_added))?;
         writeln!(formatter.labeled("removed"), "{}", "-".repeat(bar_removed))?;
     }
+    write_diff_stat_summary(
+        formatter,
+        stats.count_files(),
+        stats.count_lines_added(),
+        stats.count_lines_removed(),
+    )
+}
+
+/// Writes the "N files changed, N insertions(+), N deletions(-)" summary line.
+pub fn write_diff_stat_summary(
+    formatter: &mut dyn Formatter,
+    files: usize,
+    added: usize,
+    removed: usize,
+) -> io::Result<()> {
     writeln!(
         formatter.labeled("stat-summary"),
         "{} file{} changed, {} insertion{}(+), {} deletion{}(-)",
-        total_files,
-        if total_files == 1 { "" } else { "s" },
-        total_added,
-        if total_added == 1 { "" } else { "s" },
-        total_removed,
-        if total_removed == 1 { "" } else { "s" },
-    )?;
-    Ok(())
+        files,
+        if files == 1 { "" } else { "s" },
+        added,
+        if added == 1 { "" } else { "s" },
+        removed,
+        if removed == 1 { "" } else { "s" },
+    )
 }
 
 pub fn show_types(
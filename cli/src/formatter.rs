@@ -44,6 +44,18 @@ pub trait Formatter: Write {
     fn push_label(&mut self, label: &str) -> io::Result<()>;
 
     fn pop_label(&mut self) -> io::Result<()>;
+
+    /// Starts a hyperlink to the `url`. Data written until `pop_hyperlink()`
+    /// becomes the link text. Formatters that don't emit hyperlinks ignore
+    /// this.
+    fn push_hyperlink(&mut self, _url: &str) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Ends the hyperlink started by `push_hyperlink()`.
+    fn pop_hyperlink(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 impl dyn Formatter + '_ {
@@ -149,7 +161,11 @@ pub struct FormatterFactory {
 enum FormatterFactoryKind {
     PlainText,
     Sanitized,
-    Color { rules: Arc<Rules>, debug: bool },
+    Color {
+        rules: Arc<Rules>,
+        debug: bool,
+        hyperlinks: bool,
+    },
 }
 
 impl FormatterFactory {
@@ -165,7 +181,12 @@ impl FormatterFactory {
 
     pub fn color(config: &StackedConfig, debug: bool) -> Result<Self, ConfigGetError> {
         let rules = Arc::new(rules_from_config(config)?);
-        let kind = FormatterFactoryKind::Color { rules, debug };
+        let hyperlinks = config.get("ui.hyperlinks")?;
+        let kind = FormatterFactoryKind::Color {
+            rules,
+            debug,
+            hyperlinks,
+        };
         Ok(FormatterFactory { kind })
     }
 
@@ -176,9 +197,13 @@ impl FormatterFactory {
         match &self.kind {
             FormatterFactoryKind::PlainText => Box::new(PlainTextFormatter::new(output)),
             FormatterFactoryKind::Sanitized => Box::new(SanitizingFormatter::new(output)),
-            FormatterFactoryKind::Color { rules, debug } => {
-                Box::new(ColorFormatter::new(output, rules.clone(), *debug))
-            }
+            FormatterFactoryKind::Color {
+                rules,
+                debug,
+                hyperlinks,
+            } => Box::new(
+                ColorFormatter::new(output, rules.clone(), *debug).with_hyperlinks(*hyperlinks),
+            ),
         }
     }
 
@@ -289,6 +314,10 @@ pub struct ColorFormatter<W: Write> {
     /// The debug string (space-separated labels) we last wrote to the output.
     /// Initialize to None to turn debug strings off.
     current_debug: Option<String>,
+    /// Whether to emit OSC 8 hyperlinks.
+    hyperlinks: bool,
+    /// Whether we've written the start of a hyperlink that hasn't been closed.
+    in_hyperlink: bool,
 }
 
 impl<W: Write> ColorFormatter<W> {
@@ -300,9 +329,17 @@ impl<W: Write> ColorFormatter<W> {
             cached_styles: HashMap::new(),
             current_style: Style::default(),
             current_debug: debug.then(String::new),
+            hyperlinks: false,
+            in_hyperlink: false,
         }
     }
 
+    /// Enables or disables OSC 8 hyperlinks.
+    pub fn with_hyperlinks(mut self, hyperlinks: bool) -> Self {
+        self.hyperlinks = hyperlinks;
+        self
+    }
+
     pub fn for_config(
         output: W,
         config: &StackedConfig,
@@ -562,6 +599,25 @@ impl<W: Write> Formatter for ColorFormatter<W> {
         }
         Ok(())
     }
+
+    fn push_hyperlink(&mut self, url: &str) -> io::Result<()> {
+        // Nested links aren't supported by terminals, and control characters
+        // could terminate the escape sequence early.
+        if !self.hyperlinks || self.in_hyperlink || url.chars().any(char::is_control) {
+            return Ok(());
+        }
+        write!(self.output, "\x1b]8;;{url}\x1b\\")?;
+        self.in_hyperlink = true;
+        Ok(())
+    }
+
+    fn pop_hyperlink(&mut self) -> io::Result<()> {
+        if self.in_hyperlink {
+            write!(self.output, "\x1b]8;;\x1b\\")?;
+            self.in_hyperlink = false;
+        }
+        Ok(())
+    }
 }
 
 impl<W: Write> Drop for ColorFormatter<W> {
@@ -570,6 +626,7 @@ impl<W: Write> Drop for ColorFormatter<W> {
         // because of an error), let's still try to reset any currently active style.
         self.labels.clear();
         self.write_new_style().ok();
+        self.pop_hyperlink().ok();
     }
 }
 
@@ -590,6 +647,8 @@ pub struct FormatRecorder {
 enum FormatOp {
     PushLabel(String),
     PopLabel,
+    PushHyperlink(String),
+    PopHyperlink,
     RawEscapeSequence(Vec<u8>),
 }
 
@@ -638,6 +697,8 @@ impl FormatRecorder {
             match op {
                 FormatOp::PushLabel(label) => formatter.push_label(label)?,
                 FormatOp::PopLabel => formatter.pop_label()?,
+                FormatOp::PushHyperlink(url) => formatter.push_hyperlink(url)?,
+                FormatOp::PopHyperlink => formatter.pop_hyperlink()?,
                 FormatOp::RawEscapeSequence(raw_escape_sequence) => {
                     formatter.raw()?.write_all(raw_escape_sequence)?;
                 }
@@ -685,6 +746,16 @@ impl Formatter for FormatRecorder {
         self.push_op(FormatOp::PopLabel);
         Ok(())
     }
+
+    fn push_hyperlink(&mut self, url: &str) -> io::Result<()> {
+        self.push_op(FormatOp::PushHyperlink(url.to_owned()));
+        Ok(())
+    }
+
+    fn pop_hyperlink(&mut self) -> io::Result<()> {
+        self.push_op(FormatOp::PopHyperlink);
+        Ok(())
+    }
 }
 
 fn write_sanitized(output: &mut impl Write, buf: &[u8]) -> Result<(), Error> {
@@ -947,6 +1018,39 @@ mod tests {
         insta::assert_snapshot!(String::from_utf8(output).unwrap(), @"before[38;5;1mfirst[39m[38;5;2msecond[39mafter");
     }
 
+    #[test]
+    fn test_color_formatter_hyperlink() {
+        let config = config_from_string(r#" colors.red = "red" "#);
+        let render = |hyperlinks: bool, url: &str| {
+            let mut output: Vec<u8> = vec![];
+            let mut formatter = ColorFormatter::for_config(&mut output, &config, false)
+                .unwrap()
+                .with_hyperlinks(hyperlinks);
+            write!(formatter, "before ").unwrap();
+            formatter.push_hyperlink(url).unwrap();
+            formatter.push_label("red").unwrap();
+            write!(formatter, "link").unwrap();
+            formatter.pop_label().unwrap();
+            formatter.pop_hyperlink().unwrap();
+            write!(formatter, " after").unwrap();
+            drop(formatter);
+            String::from_utf8(output)
+                .unwrap()
+                .escape_debug()
+                .to_string()
+        };
+        insta::assert_snapshot!(
+            render(true, "https://example.com/"),
+            @r"before \u{1b}]8;;https://example.com/\u{1b}\\\u{1b}[38;5;1mlink\u{1b}[39m\u{1b}]8;;\u{1b}\\ after");
+        insta::assert_snapshot!(
+            render(false, "https://example.com/"),
+            @r"before \u{1b}[38;5;1mlink\u{1b}[39m after");
+        // URL containing control characters isn't linked
+        insta::assert_snapshot!(
+            render(true, "https://example.com/\x1b"),
+            @r"before \u{1b}[38;5;1mlink\u{1b}[39m after");
+    }
+
     #[test]
     fn test_color_formatter_ansi_codes_in_text() {
         // Test that ANSI codes in the input text are escaped.
@@ -1246,6 +1350,27 @@ mod tests {
         insta::assert_snapshot!(String::from_utf8(output).unwrap(), @"Heading: ");
     }
 
+    #[test]
+    fn test_format_recorder_hyperlink() {
+        let mut recorder = FormatRecorder::new();
+        write!(recorder, "before ").unwrap();
+        recorder.push_hyperlink("https://example.com/").unwrap();
+        write!(recorder, "link").unwrap();
+        recorder.pop_hyperlink().unwrap();
+        write!(recorder, " after").unwrap();
+
+        let config = config_from_string("");
+        let mut output: Vec<u8> = vec![];
+        let mut formatter = ColorFormatter::for_config(&mut output, &config, false)
+            .unwrap()
+            .with_hyperlinks(true);
+        recorder.replay(&mut formatter).unwrap();
+        drop(formatter);
+        insta::assert_snapshot!(
+            String::from_utf8(output).unwrap().escape_debug().to_string(),
+            @r"before \u{1b}]8;;https://example.com/\u{1b}\\link\u{1b}]8;;\u{1b}\\ after");
+    }
+
     #[test]
     fn test_format_recorder() {
         let mut recorder = FormatRecorder::new();
@@ -35,7 +35,8 @@ use crate::templater::TemplateProperty;
 /// This template language only supports the core template property types (plus
 /// the self type `C`.) The self type `C` is usually a tuple or struct of value
 /// types. It's cloned several times internally. Keyword functions need to be
-/// registered to extract properties from the self object.
+/// registered to extract properties from the self object. Methods taking
+/// arguments can also be registered on the self object.
 pub struct GenericTemplateLanguage<'a, C> {
     settings: UserSettings,
     build_fn_table: GenericTemplateBuildFnTable<'a, C>,
@@ -54,14 +55,18 @@ impl<'a, C> GenericTemplateLanguage<'a, C> {
         keywords: GenericTemplateBuildKeywordFnMap<'a, C>,
         settings: &UserSettings,
     ) -> Self {
-        GenericTemplateLanguage {
+        let mut language = GenericTemplateLanguage {
             // Clone settings to keep lifetime simple. It's cheap.
             settings: settings.clone(),
             build_fn_table: GenericTemplateBuildFnTable {
                 core: CoreTemplateBuildFnTable::builtin(),
-                keywords,
+                methods: HashMap::new(),
             },
+        };
+        for (name, build) in keywords {
+            language.add_keyword(name, build);
         }
+        language
     }
 
     /// Registers new function that translates keyword to property.
@@ -83,7 +88,43 @@ impl<'a, C> GenericTemplateLanguage<'a, C> {
             ) -> TemplateParseResult<GenericTemplatePropertyKind<'a, C>>
             + 'a,
     {
-        self.build_fn_table.keywords.insert(name, Box::new(build));
+        let build_method: GenericTemplateBuildMethodFn<'a, C> = Box::new(
+            move |_language, _diagnostics, _build_ctx, self_property, function| {
+                function.expect_no_arguments()?;
+                build(self_property)
+            },
+        );
+        self.build_fn_table.methods.insert(name, build_method);
+    }
+
+    /// Registers new function that translates method call node of the self
+    /// type `C` to property.
+    ///
+    /// Unlike keywords, methods can take arguments, which are usually parsed
+    /// by the `template_builder::expect_*()` helpers. A method is called as
+    /// `self.name(..)` at the top level of a template.
+    ///
+    /// ```ignore
+    /// language.add_method("repeat", |language, diagnostics, build_ctx, self_property, function| {
+    ///     let [count_node] = function.expect_exact_arguments()?;
+    ///     let count_property =
+    ///         template_builder::expect_usize_expression(language, diagnostics, build_ctx, count_node)?;
+    ///     let out_property = (self_property, count_property).map(|(v, n)| v.to_string().repeat(n));
+    ///     Ok(GenericTemplateLanguage::wrap_string(out_property))
+    /// });
+    /// ```
+    pub fn add_method<F>(&mut self, name: &'static str, build: F)
+    where
+        F: Fn(
+                &Self,
+                &mut TemplateDiagnostics,
+                &BuildContext<GenericTemplatePropertyKind<'a, C>>,
+                Box<dyn TemplateProperty<Output = C> + 'a>,
+                &FunctionCallNode,
+            ) -> TemplateParseResult<GenericTemplatePropertyKind<'a, C>>
+            + 'a,
+    {
+        self.build_fn_table.methods.insert(name, Box::new(build));
     }
 }
 
@@ -120,11 +161,9 @@ impl<'a, C: 'a> TemplateLanguage<'a> for GenericTemplateLanguage<'a, C> {
                 table.build_method(self, diagnostics, build_ctx, property, function)
             }
             GenericTemplatePropertyKind::Self_(property) => {
-                let table = &self.build_fn_table.keywords;
+                let table = &self.build_fn_table.methods;
                 let build = template_parser::lookup_method(type_name, table, function)?;
-                // For simplicity, only 0-ary method is supported.
-                function.expect_no_arguments()?;
-                build(property)
+                build(self, diagnostics, build_ctx, property, function)
             }
         }
     }
@@ -219,8 +258,28 @@ pub type GenericTemplateBuildKeywordFn<'a, C> = Box<
 pub type GenericTemplateBuildKeywordFnMap<'a, C> =
     HashMap<&'static str, GenericTemplateBuildKeywordFn<'a, C>>;
 
+/// Function that translates method call node of the self type `C`.
+///
+/// Like the keyword function, the method function is allowed to capture
+/// resources.
+pub type GenericTemplateBuildMethodFn<'a, C> = Box<
+    dyn Fn(
+            &GenericTemplateLanguage<'a, C>,
+            &mut TemplateDiagnostics,
+            &BuildContext<GenericTemplatePropertyKind<'a, C>>,
+            Box<dyn TemplateProperty<Output = C> + 'a>,
+            &FunctionCallNode,
+        ) -> TemplateParseResult<GenericTemplatePropertyKind<'a, C>>
+        + 'a,
+>;
+
+/// Table of functions that translate method call node of the self type `C`.
+pub type GenericTemplateBuildMethodFnMap<'a, C> =
+    HashMap<&'static str, GenericTemplateBuildMethodFn<'a, C>>;
+
 /// Symbol table of methods available in the general-purpose template.
 struct GenericTemplateBuildFnTable<'a, C: 'a> {
     core: CoreTemplateBuildFnTable<'a, GenericTemplateLanguage<'a, C>>,
-    keywords: GenericTemplateBuildKeywordFnMap<'a, C>,
+    /// Keywords and methods of the self type `C`.
+    methods: GenericTemplateBuildMethodFnMap<'a, C>,
 }
@@ -14,6 +14,7 @@
 
 //! Git utilities shared by various commands.
 
+use std::collections::BTreeMap;
 use std::error;
 use std::io;
 use std::io::Read;
@@ -29,10 +30,13 @@ use std::time::Instant;
 use crossterm::terminal::Clear;
 use crossterm::terminal::ClearType;
 use itertools::Itertools;
+use jj_lib::config::ConfigGetError;
+use jj_lib::config::ConfigGetResultExt as _;
 use jj_lib::fmt_util::binary_prefix;
 use jj_lib::git;
 use jj_lib::git::FailedRefExport;
 use jj_lib::git::FailedRefExportReason;
+use jj_lib::git::GitFetchBranchMapping;
 use jj_lib::git::GitFetchError;
 use jj_lib::git::GitImportStats;
 use jj_lib::git::RefName;
@@ -41,6 +45,7 @@ use jj_lib::op_store::RefTarget;
 use jj_lib::op_store::RemoteRef;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
+use jj_lib::settings::UserSettings;
 use jj_lib::store::Store;
 use jj_lib::str_util::StringPattern;
 use jj_lib::workspace::Workspace;
@@ -631,16 +636,20 @@ pub fn git_fetch(
     git_repo: &git2::Repository,
     remotes: &[String],
     branch: &[StringPattern],
+    branch_mappings: &[GitFetchBranchMapping],
 ) -> Result<(), CommandError> {
     let git_settings = tx.settings().git_settings()?;
 
     for remote in remotes {
+        let remote_branch_mappings =
+            branch_mappings_for_remote(tx.settings(), remote, branch, branch_mappings)?;
         let stats = with_remote_git_callbacks(ui, None, |cb| {
             git::fetch(
                 tx.repo_mut(),
                 git_repo,
                 remote,
                 branch,
+                &remote_branch_mappings,
                 cb,
                 &git_settings,
                 None,
@@ -666,12 +675,54 @@ pub fn git_fetch(
         })?;
         print_git_import_stats(ui, tx.repo(), &stats.import_stats, true)?;
     }
-    warn_if_branches_not_found(
-        ui,
-        tx,
-        branch,
-        &remotes.iter().map(StringPattern::exact).collect_vec(),
-    )
+    let remote_patterns = remotes.iter().map(StringPattern::exact).collect_vec();
+    warn_if_branches_not_found(ui, tx, branch, &remote_patterns)?;
+    for mapping in branch_mappings {
+        let local_branch = StringPattern::exact(&mapping.local_branch);
+        if !has_remote_bookmarks_matching(tx, &local_branch, &remote_patterns) {
+            writeln!(
+                ui.warning_default(),
+                "No branch named `{}` found on any specified/configured remote",
+                mapping.remote_branch
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the `branch_mappings` followed by the mappings saved in the config
+/// for the `remote`.
+///
+/// Saved mappings are included only if the mapped remote bookmark is matched by
+/// the `branch` patterns. Otherwise, it would be deleted because no branch of
+/// that name exists on the remote.
+fn branch_mappings_for_remote(
+    settings: &UserSettings,
+    remote: &str,
+    branch: &[StringPattern],
+    branch_mappings: &[GitFetchBranchMapping],
+) -> Result<Vec<GitFetchBranchMapping>, ConfigGetError> {
+    let saved_mappings: BTreeMap<String, String> = settings
+        .get(["git", "fetch-bookmark-mappings", remote])
+        .optional()?
+        .unwrap_or_default();
+    let saved_mappings = saved_mappings
+        .into_iter()
+        .filter(|(local_branch, _)| {
+            branch.iter().any(|pattern| pattern.matches(local_branch))
+                && !branch_mappings
+                    .iter()
+                    .any(|mapping| mapping.local_branch == *local_branch)
+        })
+        .map(|(local_branch, remote_branch)| GitFetchBranchMapping {
+            remote_branch,
+            local_branch,
+        });
+    Ok(branch_mappings
+        .iter()
+        .cloned()
+        .chain(saved_mappings)
+        .collect())
 }
 
 fn warn_if_branches_not_found(
@@ -681,20 +732,7 @@ fn warn_if_branches_not_found(
     remotes: &[StringPattern],
 ) -> Result<(), CommandError> {
     for branch in branches {
-        let matches = remotes.iter().any(|remote| {
-            tx.repo()
-                .view()
-                .remote_bookmarks_matching(branch, remote)
-                .next()
-                .is_some()
-                || tx
-                    .base_repo()
-                    .view()
-                    .remote_bookmarks_matching(branch, remote)
-                    .next()
-                    .is_some()
-        });
-        if !matches {
+        if !has_remote_bookmarks_matching(tx, branch, remotes) {
             writeln!(
                 ui.warning_default(),
                 "No branch matching `{branch}` found on any specified/configured remote",
@@ -705,6 +743,26 @@ fn warn_if_branches_not_found(
     Ok(())
 }
 
+fn has_remote_bookmarks_matching(
+    tx: &WorkspaceCommandTransaction,
+    branch: &StringPattern,
+    remotes: &[StringPattern],
+) -> bool {
+    remotes.iter().any(|remote| {
+        tx.repo()
+            .view()
+            .remote_bookmarks_matching(branch, remote)
+            .next()
+            .is_some()
+            || tx
+                .base_repo()
+                .view()
+                .remote_bookmarks_matching(branch, remote)
+                .next()
+                .is_some()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::MAIN_SEPARATOR;
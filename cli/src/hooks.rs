@@ -0,0 +1,243 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! User-configurable hooks running external commands on jj events.
+
+use std::env;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::ExitStatus;
+use std::process::Stdio;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+use itertools::Itertools as _;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::operation::Operation;
+use jj_lib::settings::UserSettings;
+use thiserror::Error;
+
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::config::CommandNameAndArgs;
+use crate::ui::Ui;
+
+/// Environment variable set for hook processes. Hooks aren't run by jj
+/// commands invoked from within a hook.
+const HOOK_ENV_VAR: &str = "JJ_HOOK";
+
+/// Event which hooks can be attached to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookEvent {
+    /// After an operation has been committed to the repo.
+    PostOperation,
+    /// Before commits are rewritten.
+    PreRewrite,
+    /// After a snapshot of the working copy has been committed.
+    PostSnapshot,
+    /// Before bookmarks are pushed to a Git remote.
+    PrePush,
+}
+
+impl HookEvent {
+    /// Returns the name of the event as used in the config.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::PostOperation => "post-operation",
+            Self::PreRewrite => "pre-rewrite",
+            Self::PostSnapshot => "post-snapshot",
+            Self::PrePush => "pre-push",
+        }
+    }
+}
+
+/// Represents an entry in the `hooks` config table.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct HookConfig {
+    event: HookEvent,
+    command: CommandNameAndArgs,
+    /// Maximum run time of the command in seconds.
+    #[serde(default = "default_hook_timeout")]
+    timeout: u64,
+    #[serde(default = "default_hook_enabled")]
+    enabled: bool,
+}
+
+fn default_hook_timeout() -> u64 {
+    60
+}
+
+fn default_hook_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Error)]
+enum HookError {
+    #[error("Failed to run {command}")]
+    Spawn {
+        command: String,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Timed out after {0} seconds")]
+    Timeout(u64),
+    #[error("Exited with {0}")]
+    ExitStatus(ExitStatus),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Returns the enabled hooks attached to the `event`, sorted by name.
+///
+/// No hooks are returned if we're running within a hook.
+fn get_hooks(
+    settings: &UserSettings,
+    event: HookEvent,
+) -> Result<Vec<(&str, HookConfig)>, CommandError> {
+    if env::var_os(HOOK_ENV_VAR).is_some() {
+        return Ok(vec![]);
+    }
+    let hooks: Vec<(&str, HookConfig)> = settings
+        .table_keys("hooks")
+        // Sort keys early so errors are deterministic.
+        .sorted()
+        .map(|name| -> Result<_, CommandError> {
+            let hook: HookConfig = settings.get(["hooks", name])?;
+            Ok((name, hook))
+        })
+        .try_collect()?;
+    Ok(hooks
+        .into_iter()
+        .filter(|(_, hook)| hook.enabled && hook.event == event)
+        .collect())
+}
+
+/// Returns whether any enabled hooks are attached to the `event`, so callers
+/// can skip computing an expensive payload.
+pub fn has_hooks(settings: &UserSettings, event: HookEvent) -> Result<bool, CommandError> {
+    Ok(!get_hooks(settings, event)?.is_empty())
+}
+
+/// Runs the hooks attached to the `event`, which must be one of the `pre-*`
+/// events.
+///
+/// The `payload` object is passed as JSON on stdin. If a hook fails or times
+/// out, the remaining hooks aren't run and an error is returned, so the caller
+/// can abort the command.
+pub fn run_pre_hooks(
+    settings: &UserSettings,
+    workspace_root: &Path,
+    event: HookEvent,
+    payload: serde_json::Value,
+) -> Result<(), CommandError> {
+    assert!(matches!(event, HookEvent::PreRewrite | HookEvent::PrePush));
+    for (name, hook) in get_hooks(settings, event)? {
+        run_hook(&hook, workspace_root, event, &payload).map_err(|err| {
+            user_error_with_message(
+                format!("Hook `hooks.{name}` rejected the {}", event.name()),
+                err,
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Runs the hooks attached to the `event`, which must be one of the `post-*`
+/// events.
+///
+/// The `payload` object is passed as JSON on stdin. Since the event has already
+/// happened, hook failures are reported as warnings.
+pub fn run_post_hooks(
+    ui: &Ui,
+    settings: &UserSettings,
+    workspace_root: &Path,
+    event: HookEvent,
+    payload: serde_json::Value,
+) -> Result<(), CommandError> {
+    assert!(matches!(
+        event,
+        HookEvent::PostOperation | HookEvent::PostSnapshot
+    ));
+    for (name, hook) in get_hooks(settings, event)? {
+        if let Err(err) = run_hook(&hook, workspace_root, event, &payload) {
+            writeln!(ui.warning_default(), "Hook `hooks.{name}` failed: {err}")?;
+        }
+    }
+    Ok(())
+}
+
+fn run_hook(
+    hook: &HookConfig,
+    workspace_root: &Path,
+    event: HookEvent,
+    payload: &serde_json::Value,
+) -> Result<(), HookError> {
+    let mut input = payload.clone();
+    input["event"] = event.name().into();
+    input["workspace_root"] = workspace_root.to_string_lossy().into();
+
+    let mut command = hook.command.to_command();
+    command
+        .current_dir(workspace_root)
+        .env(HOOK_ENV_VAR, event.name())
+        .stdin(Stdio::piped())
+        // Hook output is meant for the user, so it shouldn't be mixed with
+        // our stdout.
+        .stdout(io::stderr())
+        .stderr(Stdio::inherit());
+    let mut child = command.spawn().map_err(|source| HookError::Spawn {
+        command: hook.command.split_name().into_owned(),
+        source,
+    })?;
+    let mut stdin = child.stdin.take().unwrap();
+    // Write from another thread so a hook that doesn't read its input can't
+    // block us past the timeout.
+    let writer = thread::spawn(move || {
+        // The hook may exit without reading its input.
+        let _ = stdin.write_all(&serde_json::to_vec(&input).unwrap());
+    });
+
+    let timeout = Duration::from_secs(hook.timeout);
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if start.elapsed() >= timeout {
+            child.kill()?;
+            child.wait()?;
+            return Err(HookError::Timeout(hook.timeout));
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    writer.join().unwrap();
+    if status.success() {
+        Ok(())
+    } else {
+        Err(HookError::ExitStatus(status))
+    }
+}
+
+/// Returns the JSON representation of the operation passed to hooks.
+pub fn operation_json(op: &Operation) -> serde_json::Value {
+    serde_json::json!({
+        "id": op.id().hex(),
+        "description": op.metadata().description,
+        "parents": op.parent_ids().iter().map(|id| id.hex()).collect_vec(),
+    })
+}
@@ -39,11 +39,14 @@ pub mod git_util {
     }
 }
 pub mod graphlog;
+pub mod hooks;
+pub mod maintenance_util;
 pub mod merge_tools;
 pub mod movement_util;
 pub mod operation_templater;
 mod progress;
 pub mod revset_util;
+pub mod secret_scan;
 pub mod template_builder;
 pub mod template_parser;
 pub mod templater;
@@ -0,0 +1,151 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Automatic background maintenance.
+
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::process::Stdio;
+use std::time::Duration;
+
+use jj_lib::config::ConfigGetError;
+#[cfg(feature = "git")]
+use jj_lib::git_backend::GitBackend;
+use jj_lib::maintenance::MaintenanceSchedule;
+use jj_lib::maintenance::MaintenanceState;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo as _;
+use jj_lib::revset::RevsetExpression;
+use jj_lib::settings::UserSettings;
+
+use crate::command_error::CommandError;
+
+/// Returns the schedule of automatic maintenance, or `None` if automatic
+/// maintenance is disabled.
+pub fn auto_maintenance_schedule(
+    settings: &UserSettings,
+) -> Result<Option<MaintenanceSchedule>, ConfigGetError> {
+    if !settings.get_bool("maintenance.auto")? {
+        return Ok(None);
+    }
+    let operations = settings.get::<usize>("maintenance.auto-operations")?;
+    let hours = settings.get::<u64>("maintenance.auto-interval-hours")?;
+    Ok(Some(MaintenanceSchedule {
+        operations,
+        interval: Duration::from_secs(hours * 60 * 60),
+    }))
+}
+
+/// Starts `jj util maintenance run --auto` in the background if automatic
+/// maintenance is enabled and due.
+///
+/// Maintenance is best-effort, so errors are only logged.
+pub fn maybe_start_auto_maintenance(
+    settings: &UserSettings,
+    workspace_root: &Path,
+    repo_path: &Path,
+    repo: &ReadonlyRepo,
+) {
+    let schedule = match auto_maintenance_schedule(settings) {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => return,
+        Err(err) => {
+            tracing::warn!(?err, "failed to read maintenance settings");
+            return;
+        }
+    };
+    let state = MaintenanceState::new(repo_path);
+    match state.is_due(repo.operation(), &schedule) {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(err) => {
+            tracing::warn!(?err, "failed to check maintenance state");
+            return;
+        }
+    }
+    let args = ["util", "maintenance", "run", "--auto"];
+    if let Err(err) = spawn_in_background(workspace_root, &args) {
+        tracing::warn!(?err, "failed to start background maintenance");
+    }
+}
+
+/// Starts `jj debug warm-cache` in the background if caches should be warmed
+/// after fetching.
+///
+/// This is best-effort, so errors are only logged.
+pub fn maybe_start_cache_warming(settings: &UserSettings, workspace_root: &Path) {
+    match settings.get_bool("maintenance.warm-cache-after-fetch") {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(err) => {
+            tracing::warn!(?err, "failed to read maintenance settings");
+            return;
+        }
+    }
+    let args = ["debug", "warm-cache", "--background"];
+    if let Err(err) = spawn_in_background(workspace_root, &args) {
+        tracing::warn!(?err, "failed to start background cache warming");
+    }
+}
+
+/// Loads all commits reachable from the visible heads into the backend's
+/// caches. Returns the number of commits loaded.
+pub fn load_commits_into_caches(repo: &ReadonlyRepo) -> Result<usize, CommandError> {
+    let revset = RevsetExpression::visible_heads()
+        .ancestors()
+        .evaluate(repo)?;
+    let mut count = 0;
+    for commit_id in revset.iter() {
+        repo.store().get_commit(&commit_id?)?;
+        count += 1;
+    }
+    #[cfg(feature = "git")]
+    if let Some(git_backend) = repo.store().backend_impl().downcast_ref::<GitBackend>() {
+        git_backend.flush_commit_metadata_cache();
+    }
+    Ok(count)
+}
+
+#[allow(clippy::zombie_processes)] // the child outlives us
+fn spawn_in_background(workspace_root: &Path, args: &[&str]) -> io::Result<()> {
+    let mut cmd = Command::new(env::current_exe()?);
+    cmd.args(args)
+        .arg("--ignore-working-copy")
+        .arg("--repository")
+        .arg(workspace_root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Detach from the terminal's process group so that Ctrl-C in the shell
+    // doesn't interrupt the background process.
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+    // The child isn't waited for. It will be reparented when we exit.
+    cmd.spawn()?;
+    Ok(())
+}
+
+/// Lowers the scheduling priority of the current process.
+pub fn lower_process_priority() {
+    #[cfg(unix)]
+    {
+        // SAFETY: nice() has no memory-safety requirements. The result is
+        // ignored since running at normal priority is fine.
+        unsafe {
+            libc::nice(10);
+        }
+    }
+}
@@ -13,17 +13,36 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::cell::RefCell;
+use std::cmp::max;
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
 use std::io;
+use std::rc::Rc;
 
+use indexmap::IndexMap;
 use itertools::Itertools as _;
+use jj_lib::backend::ChangeId;
+use jj_lib::backend::CommitId;
+use jj_lib::commit::Commit;
 use jj_lib::extensions_map::ExtensionsMap;
+use jj_lib::git;
+use jj_lib::id_prefix::OperationIdPrefixIndex;
 use jj_lib::object_id::ObjectId;
 use jj_lib::op_store::OperationId;
+use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::operation::Operation;
+use jj_lib::refs::diff_named_remote_refs;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo;
 use jj_lib::repo::RepoLoader;
+use jj_lib::revset;
+use jj_lib::revset::RevsetEvaluationError;
+use jj_lib::revset::RevsetIteratorExt as _;
 use jj_lib::settings::UserSettings;
+use once_cell::unsync::OnceCell;
 
 use crate::template_builder;
 use crate::template_builder::merge_fn_map;
@@ -36,11 +55,14 @@ use crate::template_builder::TemplateLanguage;
 use crate::template_parser;
 use crate::template_parser::FunctionCallNode;
 use crate::template_parser::TemplateDiagnostics;
+use crate::template_parser::TemplateParseError;
 use crate::template_parser::TemplateParseResult;
 use crate::templater::PlainTextFormattedProperty;
+use crate::templater::ShortestIdPrefix;
 use crate::templater::Template;
 use crate::templater::TemplateFormatter;
 use crate::templater::TemplateProperty;
+use crate::templater::TemplatePropertyError;
 use crate::templater::TemplatePropertyExt as _;
 use crate::templater::TimestampRange;
 
@@ -55,6 +77,8 @@ pub struct OperationTemplateLanguage {
     current_op_id: Option<OperationId>,
     build_fn_table: OperationTemplateBuildFnTable,
     cache_extensions: ExtensionsMap,
+    diff_summaries: Rc<OperationDiffSummaryCache>,
+    op_id_prefix_index: OnceCell<Rc<OperationIdPrefixIndex>>,
 }
 
 impl OperationTemplateLanguage {
@@ -81,8 +105,19 @@ impl OperationTemplateLanguage {
             current_op_id: current_op_id.cloned(),
             build_fn_table,
             cache_extensions,
+            diff_summaries: Rc::new(OperationDiffSummaryCache::new(repo_loader.clone())),
+            op_id_prefix_index: OnceCell::new(),
         }
     }
+
+    /// Loads index of all operations in the store, which operation ID
+    /// prefixes are resolved against.
+    fn op_id_prefix_index(&self) -> Result<&Rc<OperationIdPrefixIndex>, OpsetEvaluationError> {
+        self.op_id_prefix_index.get_or_try_init(|| {
+            let op_ids = self.repo_loader.op_store().operation_ids()?;
+            Ok(Rc::new(OperationIdPrefixIndex::new(op_ids)))
+        })
+    }
 }
 
 impl TemplateLanguage<'static> for OperationTemplateLanguage {
@@ -122,6 +157,18 @@ impl TemplateLanguage<'static> for OperationTemplateLanguage {
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(self, diagnostics, build_ctx, property, function)
             }
+            OperationTemplatePropertyKind::OperationList(property) => {
+                // TODO: migrate to table?
+                template_builder::build_unformattable_list_method(
+                    self,
+                    diagnostics,
+                    build_ctx,
+                    property,
+                    function,
+                    Self::wrap_operation,
+                    Self::wrap_operation_list,
+                )
+            }
             OperationTemplatePropertyKind::OperationId(property) => {
                 let table = &self.build_fn_table.operation_id_methods;
                 let build = template_parser::lookup_method(type_name, table, function)?;
@@ -142,6 +189,12 @@ impl OperationTemplateLanguage {
         OperationTemplatePropertyKind::Operation(Box::new(property))
     }
 
+    pub fn wrap_operation_list(
+        property: impl TemplateProperty<Output = Vec<Operation>> + 'static,
+    ) -> OperationTemplatePropertyKind {
+        OperationTemplatePropertyKind::OperationList(Box::new(property))
+    }
+
     pub fn wrap_operation_id(
         property: impl TemplateProperty<Output = OperationId> + 'static,
     ) -> OperationTemplatePropertyKind {
@@ -152,6 +205,7 @@ impl OperationTemplateLanguage {
 pub enum OperationTemplatePropertyKind {
     Core(CoreTemplatePropertyKind<'static>),
     Operation(Box<dyn TemplateProperty<Output = Operation>>),
+    OperationList(Box<dyn TemplateProperty<Output = Vec<Operation>>>),
     OperationId(Box<dyn TemplateProperty<Output = OperationId>>),
 }
 
@@ -160,6 +214,7 @@ impl IntoTemplateProperty<'static> for OperationTemplatePropertyKind {
         match self {
             OperationTemplatePropertyKind::Core(property) => property.type_name(),
             OperationTemplatePropertyKind::Operation(_) => "Operation",
+            OperationTemplatePropertyKind::OperationList(_) => "List<Operation>",
             OperationTemplatePropertyKind::OperationId(_) => "OperationId",
         }
     }
@@ -168,6 +223,9 @@ impl IntoTemplateProperty<'static> for OperationTemplatePropertyKind {
         match self {
             OperationTemplatePropertyKind::Core(property) => property.try_into_boolean(),
             OperationTemplatePropertyKind::Operation(_) => None,
+            OperationTemplatePropertyKind::OperationList(property) => {
+                Some(Box::new(property.map(|l| !l.is_empty())))
+            }
             OperationTemplatePropertyKind::OperationId(_) => None,
         }
     }
@@ -193,6 +251,7 @@ impl IntoTemplateProperty<'static> for OperationTemplatePropertyKind {
         match self {
             OperationTemplatePropertyKind::Core(property) => property.try_into_template(),
             OperationTemplatePropertyKind::Operation(_) => None,
+            OperationTemplatePropertyKind::OperationList(_) => None,
             OperationTemplatePropertyKind::OperationId(property) => Some(property.into_template()),
         }
     }
@@ -205,6 +264,7 @@ impl IntoTemplateProperty<'static> for OperationTemplatePropertyKind {
             ) => lhs.try_into_eq(rhs),
             (OperationTemplatePropertyKind::Core(_), _) => None,
             (OperationTemplatePropertyKind::Operation(_), _) => None,
+            (OperationTemplatePropertyKind::OperationList(_), _) => None,
             (OperationTemplatePropertyKind::OperationId(_), _) => None,
         }
     }
@@ -217,6 +277,7 @@ impl IntoTemplateProperty<'static> for OperationTemplatePropertyKind {
             ) => lhs.try_into_cmp(rhs),
             (OperationTemplatePropertyKind::Core(_), _) => None,
             (OperationTemplatePropertyKind::Operation(_), _) => None,
+            (OperationTemplatePropertyKind::OperationList(_), _) => None,
             (OperationTemplatePropertyKind::OperationId(_), _) => None,
         }
     }
@@ -269,6 +330,26 @@ fn builtin_operation_methods() -> OperationTemplateBuildMethodFnMap<Operation> {
     // Not using maplit::hashmap!{} or custom declarative macro here because
     // code completion inside macro is quite restricted.
     let mut map = OperationTemplateBuildMethodFnMap::<Operation>::new();
+    map.insert(
+        "changed_bookmark_count",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let diff_summaries = language.diff_summaries.clone();
+            let out_property = self_property
+                .and_then(move |op| Ok(diff_summaries.get(&op)?.changed_bookmarks.try_into()?));
+            Ok(L::wrap_integer(out_property))
+        },
+    );
+    map.insert(
+        "changed_commit_count",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let diff_summaries = language.diff_summaries.clone();
+            let out_property = self_property
+                .and_then(move |op| Ok(diff_summaries.get(&op)?.changed_commits.try_into()?));
+            Ok(L::wrap_integer(out_property))
+        },
+    );
     map.insert(
         "current_operation",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -294,18 +375,55 @@ fn builtin_operation_methods() -> OperationTemplateBuildMethodFnMap<Operation> {
             Ok(L::wrap_operation_id(out_property))
         },
     );
+    map.insert(
+        "parents",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|op| Ok(op.parents().try_collect()?));
+            Ok(L::wrap_operation_list(out_property))
+        },
+    );
     map.insert(
         "tags",
         |_language, _diagnostics, _build_ctx, self_property, function| {
             function.expect_no_arguments()?;
             let out_property = self_property.map(|op| {
-                // TODO: introduce map type
                 op.metadata()
                     .tags
                     .iter()
-                    .map(|(key, value)| format!("{key}: {value}"))
-                    .join("\n")
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect()
             });
+            Ok(L::wrap_string_map(out_property))
+        },
+    );
+    map.insert(
+        "diff_summary",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let diff_summaries = language.diff_summaries.clone();
+            let out_property =
+                self_property.and_then(move |op| Ok(diff_summaries.get(&op)?.to_string()));
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "is_working_copy_snapshot_only",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let diff_summaries = language.diff_summaries.clone();
+            let out_property = self_property
+                .and_then(move |op| Ok(diff_summaries.get(&op)?.working_copy_snapshot_only));
+            Ok(L::wrap_boolean(out_property))
+        },
+    );
+    map.insert(
+        "kind",
+        |language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let diff_summaries = language.diff_summaries.clone();
+            let out_property =
+                self_property.and_then(move |op| Ok(diff_summaries.get(&op)?.kind.to_owned()));
             Ok(L::wrap_string(out_property))
         },
     );
@@ -339,6 +457,23 @@ fn builtin_operation_methods() -> OperationTemplateBuildMethodFnMap<Operation> {
             Ok(L::wrap_string(out_property))
         },
     );
+    map.insert(
+        "workspaces",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.and_then(|op| {
+                let view = op.view()?;
+                Ok(view
+                    .wc_commit_ids()
+                    .iter()
+                    .map(|(workspace_id, commit_id)| {
+                        (workspace_id.as_str().to_owned(), commit_id.hex())
+                    })
+                    .collect())
+            });
+            Ok(L::wrap_string_map(out_property))
+        },
+    );
     map.insert(
         "root",
         |language, _diagnostics, _build_ctx, self_property, function| {
@@ -351,6 +486,315 @@ fn builtin_operation_methods() -> OperationTemplateBuildMethodFnMap<Operation> {
     map
 }
 
+/// How an operation changed the view, compared to its parent operations.
+#[derive(Clone, Debug, Default)]
+struct OperationDiffSummary {
+    /// Number of commits that became visible or hidden.
+    changed_commits: usize,
+    /// Number of new commits which don't replace an old commit of the same
+    /// change.
+    created_commits: usize,
+    /// Number of new commits which replace an old commit of the same change.
+    rewritten_commits: usize,
+    /// Number of changes whose commits all became hidden.
+    abandoned_commits: usize,
+    /// Number of local bookmarks that were created, moved, or deleted.
+    changed_bookmarks: usize,
+    /// Local bookmarks that were created, moved, or deleted, with the kind of
+    /// change.
+    bookmark_changes: Vec<(String, &'static str)>,
+    /// True if the operation only recorded new contents of working-copy
+    /// commits, keeping their parents and descriptions.
+    working_copy_snapshot_only: bool,
+    /// What the operation did. See `classify_operation()`.
+    kind: &'static str,
+}
+
+impl fmt::Display for OperationDiffSummary {
+    /// Formats the summary like "1 commit rewritten, bookmark main moved".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let commit_counts = [
+            (self.created_commits, "created"),
+            (self.rewritten_commits, "rewritten"),
+            (self.abandoned_commits, "abandoned"),
+        ];
+        let commit_parts =
+            commit_counts
+                .iter()
+                .filter(|(count, _)| *count > 0)
+                .map(|(count, verb)| match count {
+                    1 => format!("1 commit {verb}"),
+                    _ => format!("{count} commits {verb}"),
+                });
+        let bookmark_parts = self
+            .bookmark_changes
+            .iter()
+            .map(|(name, verb)| format!("bookmark {name} {verb}"));
+        write!(f, "{}", commit_parts.chain(bookmark_parts).join(", "))
+    }
+}
+
+/// Computes operation diff summaries on demand, and remembers them so the
+/// views aren't loaded again for each method call.
+struct OperationDiffSummaryCache {
+    repo_loader: RepoLoader,
+    summaries: RefCell<HashMap<OperationId, Rc<OperationDiffSummary>>>,
+}
+
+impl OperationDiffSummaryCache {
+    fn new(repo_loader: RepoLoader) -> Self {
+        OperationDiffSummaryCache {
+            repo_loader,
+            summaries: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, op: &Operation) -> Result<Rc<OperationDiffSummary>, TemplatePropertyError> {
+        if let Some(summary) = self.summaries.borrow().get(op.id()) {
+            return Ok(summary.clone());
+        }
+        let summary = Rc::new(compute_diff_summary(&self.repo_loader, op)?);
+        self.summaries
+            .borrow_mut()
+            .insert(op.id().clone(), summary.clone());
+        Ok(summary)
+    }
+}
+
+fn compute_diff_summary(
+    repo_loader: &RepoLoader,
+    op: &Operation,
+) -> Result<OperationDiffSummary, TemplatePropertyError> {
+    let parent_repos: Vec<_> = op
+        .parents()
+        .map(|parent| -> Result<_, TemplatePropertyError> { Ok(repo_loader.load_at(&parent?)?) })
+        .try_collect()?;
+    if parent_repos.is_empty() {
+        return Ok(OperationDiffSummary {
+            kind: "other",
+            ..Default::default()
+        });
+    }
+    // The index of the operation includes the commits of all its parents.
+    let repo = repo_loader.load_at(op)?;
+    let new_view = repo.view();
+    let parent_views = parent_repos.iter().map(|repo| repo.view()).collect_vec();
+    let old_heads = parent_views
+        .iter()
+        .flat_map(|view| view.heads())
+        .unique()
+        .cloned()
+        .collect_vec();
+    let new_heads = new_view.heads().iter().cloned().collect_vec();
+    let changes = compute_commits_diff(repo.as_ref(), &old_heads, &new_heads)?;
+
+    let mut changed_commits = 0;
+    let mut created_commits = 0;
+    let mut rewritten_commits = 0;
+    let mut abandoned_commits = 0;
+    for change in changes.values() {
+        changed_commits += change.added_commits.len() + change.removed_commits.len();
+        if change.removed_commits.is_empty() {
+            created_commits += change.added_commits.len();
+        } else if change.added_commits.is_empty() {
+            abandoned_commits += 1;
+        } else {
+            rewritten_commits += change.added_commits.len();
+        }
+    }
+    // Commits which were already known to a parent operation were made visible
+    // again, e.g. by an undo.
+    let restored_commits = changes
+        .values()
+        .flat_map(|change| &change.added_commits)
+        .filter(|commit| {
+            parent_repos
+                .iter()
+                .any(|parent_repo| parent_repo.index().has_id(commit.id()))
+        })
+        .count();
+
+    // With multiple parents, a bookmark counts as changed only if it differs
+    // from all of them.
+    let bookmark_changes = parent_views
+        .iter()
+        .flat_map(|view| view.local_bookmarks().map(|(name, _)| name))
+        .chain(new_view.local_bookmarks().map(|(name, _)| name))
+        .unique()
+        .sorted()
+        .filter_map(|name| {
+            let target = new_view.get_local_bookmark(name);
+            if parent_views
+                .iter()
+                .any(|view| view.get_local_bookmark(name) == target)
+            {
+                None
+            } else if target.is_absent() {
+                Some((name.to_owned(), "deleted"))
+            } else if parent_views
+                .iter()
+                .all(|view| view.get_local_bookmark(name).is_absent())
+            {
+                Some((name.to_owned(), "created"))
+            } else {
+                Some((name.to_owned(), "moved"))
+            }
+        })
+        .collect_vec();
+    let changed_bookmarks = bookmark_changes.len();
+
+    // Remote bookmarks moved to somewhere the local bookmark wasn't were
+    // fetched. Pushed remote bookmarks are moved to the local bookmark.
+    let fetched_bookmarks = match parent_views.as_slice() {
+        [parent_view] => diff_named_remote_refs(
+            parent_view.all_remote_bookmarks(),
+            new_view.all_remote_bookmarks(),
+        )
+        .filter(|((_, remote_name), _)| !git::is_special_git_remote(remote_name))
+        .filter(|((name, _), (old_ref, new_ref))| {
+            old_ref.target != new_ref.target
+                && *parent_view.get_local_bookmark(name) != new_ref.target
+        })
+        .count(),
+        _ => 0,
+    };
+
+    let working_copy_snapshot_only = {
+        let old_wc_ids: HashSet<&CommitId> = parent_views
+            .iter()
+            .flat_map(|view| view.wc_commit_ids().values())
+            .collect();
+        let new_wc_ids: HashSet<&CommitId> = new_view.wc_commit_ids().values().collect();
+        let mut added_commits = changes.values().flat_map(|change| &change.added_commits);
+        let mut removed_commits = changes.values().flat_map(|change| &change.removed_commits);
+        changed_commits > 0
+            && changed_bookmarks == 0
+            && added_commits.all(|commit| new_wc_ids.contains(commit.id()))
+            && removed_commits.all(|commit| old_wc_ids.contains(commit.id()))
+            && rewrites_contents_only(&changes)
+    };
+
+    let kind = classify_operation(
+        op,
+        fetched_bookmarks,
+        restored_commits,
+        rewritten_commits + abandoned_commits,
+    );
+    Ok(OperationDiffSummary {
+        changed_commits,
+        created_commits,
+        rewritten_commits,
+        abandoned_commits,
+        changed_bookmarks,
+        bookmark_changes,
+        working_copy_snapshot_only,
+        kind,
+    })
+}
+
+/// Classifies what the operation did from its metadata and how it changed the
+/// view. A fetch takes precedence over a restore of hidden commits, so undoing
+/// a fetch and fetching the same commits again are both fetches.
+fn classify_operation(
+    op: &Operation,
+    fetched_bookmarks: usize,
+    restored_commits: usize,
+    rewritten_or_abandoned_commits: usize,
+) -> &'static str {
+    if op.metadata().is_snapshot {
+        "snapshot"
+    } else if fetched_bookmarks > 0 {
+        "fetch"
+    } else if restored_commits > 0 {
+        "undo"
+    } else if rewritten_or_abandoned_commits > 0 {
+        "rewrite"
+    } else {
+        "other"
+    }
+}
+
+/// Returns true if each new commit of the `changes` replaces an old commit of
+/// the same change, with the same parents and description.
+fn rewrites_contents_only(changes: &IndexMap<ChangeId, ModifiedChange>) -> bool {
+    changes.values().all(|change| {
+        match (
+            change.added_commits.as_slice(),
+            change.removed_commits.as_slice(),
+        ) {
+            ([new_commit], [old_commit]) => {
+                old_commit.parent_ids() == new_commit.parent_ids()
+                    && old_commit.description() == new_commit.description()
+            }
+            _ => false,
+        }
+    })
+}
+
+/// Commits of a change which became visible or hidden between two operations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModifiedChange {
+    pub added_commits: Vec<Commit>,
+    pub removed_commits: Vec<Commit>,
+}
+
+/// Compute the changes in commits between two operations, returned as a
+/// `HashMap` from `ChangeId` to a `ModifiedChange` struct containing the added
+/// and removed commits for the change ID.
+pub fn compute_operation_commits_diff(
+    repo: &dyn Repo,
+    from_repo: &ReadonlyRepo,
+    to_repo: &ReadonlyRepo,
+) -> Result<IndexMap<ChangeId, ModifiedChange>, RevsetEvaluationError> {
+    let from_heads = from_repo.view().heads().iter().cloned().collect_vec();
+    let to_heads = to_repo.view().heads().iter().cloned().collect_vec();
+    compute_commits_diff(repo, &from_heads, &to_heads)
+}
+
+/// Like `compute_operation_commits_diff()`, but compares the commits visible
+/// from the `from_heads` and the `to_heads`. The `repo` must index the commits
+/// of both.
+pub fn compute_commits_diff(
+    repo: &dyn Repo,
+    from_heads: &[CommitId],
+    to_heads: &[CommitId],
+) -> Result<IndexMap<ChangeId, ModifiedChange>, RevsetEvaluationError> {
+    let mut changes: IndexMap<ChangeId, ModifiedChange> = IndexMap::new();
+
+    // Find newly added commits in `to_heads` which were not present in
+    // `from_heads`.
+    for commit in revset::walk_revs(repo, to_heads, from_heads)?
+        .iter()
+        .commits(repo.store())
+    {
+        let commit = commit?;
+        let modified_change = changes
+            .entry(commit.change_id().clone())
+            .or_insert_with(|| ModifiedChange {
+                added_commits: vec![],
+                removed_commits: vec![],
+            });
+        modified_change.added_commits.push(commit);
+    }
+
+    // Find commits which were hidden in `to_heads`.
+    for commit in revset::walk_revs(repo, from_heads, to_heads)?
+        .iter()
+        .commits(repo.store())
+    {
+        let commit = commit?;
+        let modified_change = changes
+            .entry(commit.change_id().clone())
+            .or_insert_with(|| ModifiedChange {
+                added_commits: vec![],
+                removed_commits: vec![],
+            });
+        modified_change.removed_commits.push(commit);
+    }
+
+    Ok(changes)
+}
+
 impl Template for OperationId {
     fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
         write!(formatter, "{}", self.hex())
@@ -384,5 +828,44 @@ fn builtin_operation_id_methods() -> OperationTemplateBuildMethodFnMap<Operation
             Ok(L::wrap_string(out_property))
         },
     );
+    map.insert(
+        "shortest",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let ([], [len_node]) = function.expect_arguments()?;
+            let len_property = len_node
+                .map(|node| {
+                    template_builder::expect_usize_expression(
+                        language,
+                        diagnostics,
+                        build_ctx,
+                        node,
+                    )
+                })
+                .transpose()?;
+            let index = match language.op_id_prefix_index() {
+                Ok(index) => index.clone(),
+                Err(err) => {
+                    // Not an error because we can still produce somewhat
+                    // reasonable output.
+                    diagnostics.add_warning(
+                        TemplateParseError::expression(
+                            "Failed to load operation ID prefix index",
+                            function.name_span,
+                        )
+                        .with_source(err),
+                    );
+                    Rc::new(OperationIdPrefixIndex::new(vec![]))
+                }
+            };
+            let out_property = (self_property, len_property).map(move |(id, len)| {
+                let mut hex = id.hex();
+                let prefix_len = index.shortest_unique_prefix_len(&id);
+                hex.truncate(max(prefix_len, len.unwrap_or(0)));
+                let rest = hex.split_off(prefix_len);
+                ShortestIdPrefix { prefix: hex, rest }
+            });
+            Ok(L::wrap_shortest_id_prefix(out_property))
+        },
+    );
     map
 }
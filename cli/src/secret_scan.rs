@@ -0,0 +1,414 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Scanning of snapshotted file contents for likely secrets.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::io::BufRead as _;
+use std::io::BufReader;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::Stdio;
+use std::thread;
+
+use futures::StreamExt as _;
+use itertools::Itertools as _;
+use jj_lib::backend::BackendError;
+use jj_lib::backend::BackendResult;
+use jj_lib::backend::TreeValue;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::merge::MergedTreeValue;
+use jj_lib::merged_tree::MergedTree;
+use jj_lib::merged_tree::TreeDiffEntry;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::repo_path::RepoPathUiConverter;
+use jj_lib::settings::UserSettings;
+use jj_lib::store::Store;
+use pollster::FutureExt as _;
+use regex::bytes::Regex;
+
+use crate::command_error::config_error_with_message;
+use crate::command_error::user_error_with_hint;
+use crate::command_error::user_error_with_message;
+use crate::command_error::CommandError;
+use crate::config::CommandNameAndArgs;
+use crate::ui::Ui;
+
+/// Minimum length of the tokens checked by `min-entropy`.
+const MIN_ENTROPY_TOKEN_LEN: usize = 20;
+
+/// What to do when likely secrets are found in snapshotted files.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SecretScanAction {
+    /// Don't scan files.
+    #[default]
+    None,
+    /// Snapshot the files, but print a warning.
+    Warn,
+    /// Refuse to snapshot the files.
+    Block,
+}
+
+/// Represents the `snapshot.secret-scan` config table.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct SecretScanConfig {
+    #[serde(default)]
+    action: SecretScanAction,
+    /// Map of rule names to regular expressions.
+    #[serde(default)]
+    rules: BTreeMap<String, String>,
+    /// Minimum Shannon entropy (in bits per byte) of tokens to be reported.
+    min_entropy: Option<f64>,
+    /// External command which exits with non-zero status if any of the files
+    /// listed on stdin contain secrets.
+    command: Option<CommandNameAndArgs>,
+}
+
+/// Likely secrets found in a file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SecretFinding {
+    pub path: RepoPathBuf,
+    /// Names of the rules which matched the file content.
+    pub rules: Vec<String>,
+}
+
+/// Scanner for likely secrets configured by `snapshot.secret-scan`.
+#[derive(Debug)]
+pub struct SecretScanner {
+    action: SecretScanAction,
+    rules: Vec<(String, Regex)>,
+    min_entropy: Option<f64>,
+    command: Option<CommandNameAndArgs>,
+}
+
+impl SecretScanner {
+    /// Loads the scanner from settings. Returns `None` if scanning is
+    /// disabled.
+    pub fn from_settings(settings: &UserSettings) -> Result<Option<Self>, CommandError> {
+        let config: SecretScanConfig = settings.get("snapshot.secret-scan")?;
+        if config.action == SecretScanAction::None {
+            return Ok(None);
+        }
+        let rules = config
+            .rules
+            .into_iter()
+            .map(|(name, pattern)| {
+                let regex = Regex::new(&pattern).map_err(|err| {
+                    config_error_with_message(
+                        format!("Invalid regex in `snapshot.secret-scan.rules.{name}`"),
+                        err,
+                    )
+                })?;
+                Ok::<_, CommandError>((name, regex))
+            })
+            .try_collect()?;
+        Ok(Some(SecretScanner {
+            action: config.action,
+            rules,
+            min_entropy: config.min_entropy,
+            command: config.command,
+        }))
+    }
+
+    /// Scans the files added or modified between `old_tree` and `new_tree`.
+    ///
+    /// Since `new_tree` is the snapshot, the contents of the files have
+    /// already been written to the store. Matches which were already present
+    /// in the old version of a file aren't reported again.
+    pub fn scan_trees(
+        &self,
+        workspace_root: &Path,
+        old_tree: &MergedTree,
+        new_tree: &MergedTree,
+    ) -> Result<Vec<SecretFinding>, CommandError> {
+        let store = new_tree.store();
+        let mut diff_stream = old_tree.diff_stream(new_tree, &EverythingMatcher);
+        let mut findings: BTreeMap<RepoPathBuf, Vec<String>> = BTreeMap::new();
+        let mut scanned_paths = vec![];
+        async {
+            while let Some(TreeDiffEntry { path, values }) = diff_stream.next().await {
+                let (before, after) = values?;
+                if !is_file(&after) {
+                    continue;
+                }
+                let rules = self.scan_file(store, &path, &before, &after).await?;
+                if !rules.is_empty() {
+                    findings.insert(path.clone(), rules);
+                }
+                scanned_paths.push(path);
+            }
+            Ok::<_, BackendError>(())
+        }
+        .block_on()?;
+        if let Some(command) = &self.command {
+            if !scanned_paths.is_empty() {
+                for path in run_scan_command(command, workspace_root, &scanned_paths)? {
+                    findings.entry(path).or_default().push("command".to_owned());
+                }
+            }
+        }
+        Ok(findings
+            .into_iter()
+            .map(|(path, rules)| SecretFinding { path, rules })
+            .collect())
+    }
+
+    /// Matches the rules and the entropy threshold against the new version of
+    /// a file line by line, and returns the names of the matched rules.
+    async fn scan_file(
+        &self,
+        store: &Store,
+        path: &RepoPath,
+        before: &MergedTreeValue,
+        after: &MergedTreeValue,
+    ) -> BackendResult<Vec<String>> {
+        if self.rules.is_empty() && self.min_entropy.is_none() {
+            return Ok(vec![]);
+        }
+        let mut old_matches: HashSet<(usize, Vec<u8>)> = HashSet::new();
+        let mut old_tokens: HashSet<Vec<u8>> = HashSet::new();
+        for_each_line(store, path, before, |line| {
+            for (i, (_, regex)) in self.rules.iter().enumerate() {
+                old_matches.extend(regex.find_iter(line).map(|m| (i, m.as_bytes().to_vec())));
+            }
+            if let Some(min_entropy) = self.min_entropy {
+                old_tokens.extend(high_entropy_tokens(line, min_entropy).map(<[u8]>::to_vec));
+            }
+        })
+        .await?;
+        let mut matched_rules = vec![false; self.rules.len()];
+        let mut matched_entropy = false;
+        for_each_line(store, path, after, |line| {
+            for (i, (_, regex)) in self.rules.iter().enumerate() {
+                if !matched_rules[i] {
+                    matched_rules[i] = regex
+                        .find_iter(line)
+                        .any(|m| !old_matches.contains(&(i, m.as_bytes().to_vec())));
+                }
+            }
+            if let Some(min_entropy) = self.min_entropy {
+                if !matched_entropy {
+                    matched_entropy = high_entropy_tokens(line, min_entropy)
+                        .any(|token| !old_tokens.contains(token));
+                }
+            }
+        })
+        .await?;
+        let mut rules = self
+            .rules
+            .iter()
+            .zip(matched_rules)
+            .filter(|(_, matched)| *matched)
+            .map(|((name, _), _)| name.clone())
+            .collect_vec();
+        if matched_entropy {
+            rules.push("min-entropy".to_owned());
+        }
+        Ok(rules)
+    }
+
+    /// Reports the `findings` according to the configured action. Returns an
+    /// error if the snapshot should be blocked.
+    pub fn report_findings(
+        &self,
+        ui: &Ui,
+        path_converter: &RepoPathUiConverter,
+        findings: &[SecretFinding],
+    ) -> Result<(), CommandError> {
+        if findings.is_empty() {
+            return Ok(());
+        }
+        let format_finding = |finding: &SecretFinding| {
+            format!(
+                "  {}: {}",
+                path_converter.format_file_path(&finding.path),
+                finding.rules.join(", ")
+            )
+        };
+        match self.action {
+            SecretScanAction::None => {}
+            SecretScanAction::Warn => {
+                writeln!(
+                    ui.warning_default(),
+                    "Snapshotted files with likely secrets:"
+                )?;
+                for finding in findings {
+                    writeln!(ui.warning_no_heading(), "{}", format_finding(finding))?;
+                }
+            }
+            SecretScanAction::Block => {
+                let mut message = "Refusing to snapshot files with likely secrets:".to_owned();
+                for finding in findings {
+                    write!(message, "\n{}", format_finding(finding)).unwrap();
+                }
+                return Err(user_error_with_hint(
+                    message,
+                    "Remove the secrets or ignore the files, or set \
+                     `snapshot.secret-scan.action = \"warn\"` to snapshot them anyway.",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_file(value: &MergedTreeValue) -> bool {
+    matches!(value.as_resolved(), Some(Some(TreeValue::File { .. })))
+}
+
+/// Calls `f` with each line of the file `value`, without reading the whole
+/// file into memory. Does nothing if the value isn't a file.
+async fn for_each_line(
+    store: &Store,
+    path: &RepoPath,
+    value: &MergedTreeValue,
+    mut f: impl FnMut(&[u8]),
+) -> BackendResult<()> {
+    let Some(Some(TreeValue::File { id, .. })) = value.as_resolved() else {
+        return Ok(());
+    };
+    let mut reader = BufReader::new(store.read_file_async(path, id).await?);
+    let mut line = vec![];
+    loop {
+        line.clear();
+        let len = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|err| BackendError::ReadFile {
+                path: path.to_owned(),
+                id: id.clone(),
+                source: err.into(),
+            })?;
+        if len == 0 {
+            return Ok(());
+        }
+        f(&line);
+    }
+}
+
+/// Returns tokens of `content` which look like random strings, such as keys
+/// encoded in base64 or hex.
+fn high_entropy_tokens(content: &[u8], min_entropy: f64) -> impl Iterator<Item = &[u8]> {
+    content
+        .split(|&b| !(b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'=' | b'_' | b'-')))
+        .filter(|token| token.len() >= MIN_ENTROPY_TOKEN_LEN)
+        .filter(move |token| shannon_entropy(token) >= min_entropy)
+}
+
+/// Calculates the Shannon entropy of the `token` in bits per byte.
+fn shannon_entropy(token: &[u8]) -> f64 {
+    let mut counts = [0_usize; 256];
+    for &b in token {
+        counts[usize::from(b)] += 1;
+    }
+    let len = token.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Runs the external scanner `command` once for all `paths`, which are
+/// passed on stdin one per line. Returns the paths the command reported
+/// likely secrets in.
+///
+/// The command reports secrets by exiting with non-zero status, and prints the
+/// paths of the files containing them on stdout. If it doesn't print any of
+/// the paths, all of them are reported.
+fn run_scan_command(
+    command: &CommandNameAndArgs,
+    workspace_root: &Path,
+    paths: &[RepoPathBuf],
+) -> Result<Vec<RepoPathBuf>, CommandError> {
+    let paths_by_name: HashMap<String, &RepoPathBuf> = paths
+        .iter()
+        .map(|path| {
+            let name = path.to_fs_path_unchecked(Path::new(""));
+            (name.to_string_lossy().into_owned(), path)
+        })
+        .collect();
+    let mut child = command
+        .to_command()
+        .current_dir(workspace_root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|err| {
+            user_error_with_message(
+                format!("Failed to run secret scanner {}", command.split_name()),
+                err,
+            )
+        })?;
+    let mut stdin = child.stdin.take().unwrap();
+    let output = thread::scope(|s| {
+        s.spawn(|| {
+            // The command may exit without reading its input.
+            for name in paths_by_name.keys().sorted() {
+                if writeln!(stdin, "{name}").is_err() {
+                    break;
+                }
+            }
+            drop(stdin);
+        });
+        child.wait_with_output()
+    })?;
+    if output.status.success() {
+        return Ok(vec![]);
+    }
+    let reported_paths = output
+        .stdout
+        .as_slice()
+        .lines()
+        .filter_map(|line| paths_by_name.get(line.ok()?.trim()))
+        .map(|&path| path.clone())
+        .collect_vec();
+    if reported_paths.is_empty() {
+        Ok(paths.to_vec())
+    } else {
+        Ok(reported_paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shannon_entropy() {
+        assert_eq!(shannon_entropy(b"aaaa"), 0.0);
+        assert_eq!(shannon_entropy(b"abab"), 1.0);
+        assert_eq!(shannon_entropy(b"abcd"), 2.0);
+    }
+
+    #[test]
+    fn test_high_entropy_tokens() {
+        let content = b"name = \"aaaaaaaaaaaaaaaaaaaaaaaa\"\n\
+                        key = \"q8Zt2LmX9vRk4NpW7sYc1HfB\"\n\
+                        short = \"xK3v\"\n";
+        assert_eq!(
+            high_entropy_tokens(content, 4.0).collect_vec(),
+            [b"q8Zt2LmX9vRk4NpW7sYc1HfB".as_slice()]
+        );
+    }
+}
@@ -15,6 +15,7 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::io;
+use std::iter;
 
 use itertools::Itertools as _;
 use jj_lib::backend::Signature;
@@ -34,6 +35,7 @@ use crate::template_parser::BinaryOp;
 use crate::template_parser::ExpressionKind;
 use crate::template_parser::ExpressionNode;
 use crate::template_parser::FunctionCallNode;
+use crate::template_parser::LambdaNode;
 use crate::template_parser::TemplateAliasesMap;
 use crate::template_parser::TemplateDiagnostics;
 use crate::template_parser::TemplateParseError;
@@ -43,7 +45,9 @@ use crate::template_parser::UnaryOp;
 use crate::templater::CoalesceTemplate;
 use crate::templater::ConcatTemplate;
 use crate::templater::ConditionalTemplate;
+use crate::templater::Duration;
 use crate::templater::Email;
+use crate::templater::HyperlinkTemplate;
 use crate::templater::LabelTemplate;
 use crate::templater::ListPropertyTemplate;
 use crate::templater::ListTemplate;
@@ -53,7 +57,10 @@ use crate::templater::PropertyPlaceholder;
 use crate::templater::RawEscapeSequenceTemplate;
 use crate::templater::ReformatTemplate;
 use crate::templater::SeparateTemplate;
+use crate::templater::ShortestIdPrefix;
 use crate::templater::SizeHint;
+use crate::templater::StringMap;
+use crate::templater::StringMapEntry;
 use crate::templater::Template;
 use crate::templater::TemplateProperty;
 use crate::templater::TemplatePropertyError;
@@ -71,6 +78,10 @@ pub trait TemplateLanguage<'a> {
     fn wrap_string_list(
         property: impl TemplateProperty<Output = Vec<String>> + 'a,
     ) -> Self::Property;
+    fn wrap_string_map(property: impl TemplateProperty<Output = StringMap> + 'a) -> Self::Property;
+    fn wrap_string_map_entry(
+        property: impl TemplateProperty<Output = StringMapEntry> + 'a,
+    ) -> Self::Property;
     fn wrap_boolean(property: impl TemplateProperty<Output = bool> + 'a) -> Self::Property;
     fn wrap_integer(property: impl TemplateProperty<Output = i64> + 'a) -> Self::Property;
     fn wrap_integer_opt(
@@ -82,10 +93,14 @@ pub trait TemplateLanguage<'a> {
     fn wrap_signature(property: impl TemplateProperty<Output = Signature> + 'a) -> Self::Property;
     fn wrap_email(property: impl TemplateProperty<Output = Email> + 'a) -> Self::Property;
     fn wrap_size_hint(property: impl TemplateProperty<Output = SizeHint> + 'a) -> Self::Property;
+    fn wrap_shortest_id_prefix(
+        property: impl TemplateProperty<Output = ShortestIdPrefix> + 'a,
+    ) -> Self::Property;
     fn wrap_timestamp(property: impl TemplateProperty<Output = Timestamp> + 'a) -> Self::Property;
     fn wrap_timestamp_range(
         property: impl TemplateProperty<Output = TimestampRange> + 'a,
     ) -> Self::Property;
+    fn wrap_duration(property: impl TemplateProperty<Output = Duration> + 'a) -> Self::Property;
 
     fn wrap_template(template: Box<dyn Template + 'a>) -> Self::Property;
     fn wrap_list_template(template: Box<dyn ListTemplate + 'a>) -> Self::Property;
@@ -125,6 +140,8 @@ macro_rules! impl_core_wrap_property_fns {
             $a, $crate::template_builder::CoreTemplatePropertyKind, $outer, {
                 wrap_string(String) => String,
                 wrap_string_list(Vec<String>) => StringList,
+                wrap_string_map($crate::templater::StringMap) => StringMap,
+                wrap_string_map_entry($crate::templater::StringMapEntry) => StringMapEntry,
                 wrap_boolean(bool) => Boolean,
                 wrap_integer(i64) => Integer,
                 wrap_integer_opt(Option<i64>) => IntegerOpt,
@@ -132,8 +149,10 @@ macro_rules! impl_core_wrap_property_fns {
                 wrap_signature(jj_lib::backend::Signature) => Signature,
                 wrap_email($crate::templater::Email) => Email,
                 wrap_size_hint($crate::templater::SizeHint) => SizeHint,
+                wrap_shortest_id_prefix($crate::templater::ShortestIdPrefix) => ShortestIdPrefix,
                 wrap_timestamp(jj_lib::backend::Timestamp) => Timestamp,
                 wrap_timestamp_range($crate::templater::TimestampRange) => TimestampRange,
+                wrap_duration($crate::templater::Duration) => Duration,
             }
         );
         fn wrap_template(
@@ -189,6 +208,8 @@ pub trait IntoTemplateProperty<'a> {
 pub enum CoreTemplatePropertyKind<'a> {
     String(Box<dyn TemplateProperty<Output = String> + 'a>),
     StringList(Box<dyn TemplateProperty<Output = Vec<String>> + 'a>),
+    StringMap(Box<dyn TemplateProperty<Output = StringMap> + 'a>),
+    StringMapEntry(Box<dyn TemplateProperty<Output = StringMapEntry> + 'a>),
     Boolean(Box<dyn TemplateProperty<Output = bool> + 'a>),
     Integer(Box<dyn TemplateProperty<Output = i64> + 'a>),
     IntegerOpt(Box<dyn TemplateProperty<Output = Option<i64>> + 'a>),
@@ -196,8 +217,10 @@ pub enum CoreTemplatePropertyKind<'a> {
     Signature(Box<dyn TemplateProperty<Output = Signature> + 'a>),
     Email(Box<dyn TemplateProperty<Output = Email> + 'a>),
     SizeHint(Box<dyn TemplateProperty<Output = SizeHint> + 'a>),
+    ShortestIdPrefix(Box<dyn TemplateProperty<Output = ShortestIdPrefix> + 'a>),
     Timestamp(Box<dyn TemplateProperty<Output = Timestamp> + 'a>),
     TimestampRange(Box<dyn TemplateProperty<Output = TimestampRange> + 'a>),
+    Duration(Box<dyn TemplateProperty<Output = Duration> + 'a>),
 
     // Both TemplateProperty and Template can represent a value to be evaluated
     // dynamically, which suggests that `Box<dyn Template + 'a>` could be
@@ -218,6 +241,8 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
         match self {
             CoreTemplatePropertyKind::String(_) => "String",
             CoreTemplatePropertyKind::StringList(_) => "List<String>",
+            CoreTemplatePropertyKind::StringMap(_) => "StringMap",
+            CoreTemplatePropertyKind::StringMapEntry(_) => "StringMapEntry",
             CoreTemplatePropertyKind::Boolean(_) => "Boolean",
             CoreTemplatePropertyKind::Integer(_) => "Integer",
             CoreTemplatePropertyKind::IntegerOpt(_) => "Option<Integer>",
@@ -225,8 +250,10 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             CoreTemplatePropertyKind::Signature(_) => "Signature",
             CoreTemplatePropertyKind::Email(_) => "Email",
             CoreTemplatePropertyKind::SizeHint(_) => "SizeHint",
+            CoreTemplatePropertyKind::ShortestIdPrefix(_) => "ShortestIdPrefix",
             CoreTemplatePropertyKind::Timestamp(_) => "Timestamp",
             CoreTemplatePropertyKind::TimestampRange(_) => "TimestampRange",
+            CoreTemplatePropertyKind::Duration(_) => "Duration",
             CoreTemplatePropertyKind::Template(_) => "Template",
             CoreTemplatePropertyKind::ListTemplate(_) => "ListTemplate",
         }
@@ -240,19 +267,27 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             CoreTemplatePropertyKind::StringList(property) => {
                 Some(Box::new(property.map(|l| !l.is_empty())))
             }
+            CoreTemplatePropertyKind::StringMap(property) => {
+                Some(Box::new(property.map(|m| !m.is_empty())))
+            }
+            CoreTemplatePropertyKind::StringMapEntry(_) => None,
             CoreTemplatePropertyKind::Boolean(property) => Some(property),
             CoreTemplatePropertyKind::Integer(_) => None,
             CoreTemplatePropertyKind::IntegerOpt(property) => {
                 Some(Box::new(property.map(|opt| opt.is_some())))
             }
-            CoreTemplatePropertyKind::ConfigValue(_) => None,
+            CoreTemplatePropertyKind::ConfigValue(property) => {
+                Some(Box::new(property.and_then(extract_config_value)))
+            }
             CoreTemplatePropertyKind::Signature(_) => None,
             CoreTemplatePropertyKind::Email(property) => {
                 Some(Box::new(property.map(|e| !e.0.is_empty())))
             }
             CoreTemplatePropertyKind::SizeHint(_) => None,
+            CoreTemplatePropertyKind::ShortestIdPrefix(_) => None,
             CoreTemplatePropertyKind::Timestamp(_) => None,
             CoreTemplatePropertyKind::TimestampRange(_) => None,
+            CoreTemplatePropertyKind::Duration(_) => None,
             // Template types could also be evaluated to boolean, but it's less likely
             // to apply label() or .map() and use the result as conditional. It's also
             // unclear whether ListTemplate should behave as a "list" or a "template".
@@ -285,6 +320,8 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
         match self {
             CoreTemplatePropertyKind::String(property) => Some(property.into_template()),
             CoreTemplatePropertyKind::StringList(property) => Some(property.into_template()),
+            CoreTemplatePropertyKind::StringMap(property) => Some(property.into_template()),
+            CoreTemplatePropertyKind::StringMapEntry(property) => Some(property.into_template()),
             CoreTemplatePropertyKind::Boolean(property) => Some(property.into_template()),
             CoreTemplatePropertyKind::Integer(property) => Some(property.into_template()),
             CoreTemplatePropertyKind::IntegerOpt(property) => Some(property.into_template()),
@@ -292,8 +329,10 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             CoreTemplatePropertyKind::Signature(property) => Some(property.into_template()),
             CoreTemplatePropertyKind::Email(property) => Some(property.into_template()),
             CoreTemplatePropertyKind::SizeHint(_) => None,
+            CoreTemplatePropertyKind::ShortestIdPrefix(property) => Some(property.into_template()),
             CoreTemplatePropertyKind::Timestamp(property) => Some(property.into_template()),
             CoreTemplatePropertyKind::TimestampRange(property) => Some(property.into_template()),
+            CoreTemplatePropertyKind::Duration(property) => Some(property.into_template()),
             CoreTemplatePropertyKind::Template(template) => Some(template),
             CoreTemplatePropertyKind::ListTemplate(template) => Some(template.into_template()),
         }
@@ -321,6 +360,8 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             }
             (CoreTemplatePropertyKind::String(_), _) => None,
             (CoreTemplatePropertyKind::StringList(_), _) => None,
+            (CoreTemplatePropertyKind::StringMap(_), _) => None,
+            (CoreTemplatePropertyKind::StringMapEntry(_), _) => None,
             (CoreTemplatePropertyKind::Boolean(_), _) => None,
             (CoreTemplatePropertyKind::Integer(_), _) => None,
             (CoreTemplatePropertyKind::IntegerOpt(_), _) => None,
@@ -328,8 +369,10 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             (CoreTemplatePropertyKind::Signature(_), _) => None,
             (CoreTemplatePropertyKind::Email(_), _) => None,
             (CoreTemplatePropertyKind::SizeHint(_), _) => None,
+            (CoreTemplatePropertyKind::ShortestIdPrefix(_), _) => None,
             (CoreTemplatePropertyKind::Timestamp(_), _) => None,
             (CoreTemplatePropertyKind::TimestampRange(_), _) => None,
+            (CoreTemplatePropertyKind::Duration(_), _) => None,
             (CoreTemplatePropertyKind::Template(_), _) => None,
             (CoreTemplatePropertyKind::ListTemplate(_), _) => None,
         }
@@ -345,6 +388,8 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             }
             (CoreTemplatePropertyKind::String(_), _) => None,
             (CoreTemplatePropertyKind::StringList(_), _) => None,
+            (CoreTemplatePropertyKind::StringMap(_), _) => None,
+            (CoreTemplatePropertyKind::StringMapEntry(_), _) => None,
             (CoreTemplatePropertyKind::Boolean(_), _) => None,
             (CoreTemplatePropertyKind::Integer(_), _) => None,
             (CoreTemplatePropertyKind::IntegerOpt(_), _) => None,
@@ -352,8 +397,10 @@ impl<'a> IntoTemplateProperty<'a> for CoreTemplatePropertyKind<'a> {
             (CoreTemplatePropertyKind::Signature(_), _) => None,
             (CoreTemplatePropertyKind::Email(_), _) => None,
             (CoreTemplatePropertyKind::SizeHint(_), _) => None,
+            (CoreTemplatePropertyKind::ShortestIdPrefix(_), _) => None,
             (CoreTemplatePropertyKind::Timestamp(_), _) => None,
             (CoreTemplatePropertyKind::TimestampRange(_), _) => None,
+            (CoreTemplatePropertyKind::Duration(_), _) => None,
             (CoreTemplatePropertyKind::Template(_), _) => None,
             (CoreTemplatePropertyKind::ListTemplate(_), _) => None,
         }
@@ -395,14 +442,18 @@ pub type TemplateBuildMethodFnMap<'a, L, T> =
 pub struct CoreTemplateBuildFnTable<'a, L: TemplateLanguage<'a> + ?Sized> {
     pub functions: TemplateBuildFunctionFnMap<'a, L>,
     pub string_methods: TemplateBuildMethodFnMap<'a, L, String>,
+    pub string_map_methods: TemplateBuildMethodFnMap<'a, L, StringMap>,
+    pub string_map_entry_methods: TemplateBuildMethodFnMap<'a, L, StringMapEntry>,
     pub boolean_methods: TemplateBuildMethodFnMap<'a, L, bool>,
     pub integer_methods: TemplateBuildMethodFnMap<'a, L, i64>,
     pub config_value_methods: TemplateBuildMethodFnMap<'a, L, ConfigValue>,
     pub email_methods: TemplateBuildMethodFnMap<'a, L, Email>,
     pub signature_methods: TemplateBuildMethodFnMap<'a, L, Signature>,
     pub size_hint_methods: TemplateBuildMethodFnMap<'a, L, SizeHint>,
+    pub shortest_id_prefix_methods: TemplateBuildMethodFnMap<'a, L, ShortestIdPrefix>,
     pub timestamp_methods: TemplateBuildMethodFnMap<'a, L, Timestamp>,
     pub timestamp_range_methods: TemplateBuildMethodFnMap<'a, L, TimestampRange>,
+    pub duration_methods: TemplateBuildMethodFnMap<'a, L, Duration>,
 }
 
 pub fn merge_fn_map<'s, F>(base: &mut HashMap<&'s str, F>, extension: HashMap<&'s str, F>) {
@@ -419,14 +470,18 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
         CoreTemplateBuildFnTable {
             functions: builtin_functions(),
             string_methods: builtin_string_methods(),
+            string_map_methods: builtin_string_map_methods(),
+            string_map_entry_methods: builtin_string_map_entry_methods(),
             boolean_methods: HashMap::new(),
-            integer_methods: HashMap::new(),
+            integer_methods: builtin_integer_methods(),
             config_value_methods: builtin_config_value_methods(),
             signature_methods: builtin_signature_methods(),
             email_methods: builtin_email_methods(),
             size_hint_methods: builtin_size_hint_methods(),
+            shortest_id_prefix_methods: builtin_shortest_id_prefix_methods(),
             timestamp_methods: builtin_timestamp_methods(),
             timestamp_range_methods: builtin_timestamp_range_methods(),
+            duration_methods: builtin_duration_methods(),
         }
     }
 
@@ -434,14 +489,18 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
         CoreTemplateBuildFnTable {
             functions: HashMap::new(),
             string_methods: HashMap::new(),
+            string_map_methods: HashMap::new(),
+            string_map_entry_methods: HashMap::new(),
             boolean_methods: HashMap::new(),
             integer_methods: HashMap::new(),
             config_value_methods: HashMap::new(),
             signature_methods: HashMap::new(),
             email_methods: HashMap::new(),
             size_hint_methods: HashMap::new(),
+            shortest_id_prefix_methods: HashMap::new(),
             timestamp_methods: HashMap::new(),
             timestamp_range_methods: HashMap::new(),
+            duration_methods: HashMap::new(),
         }
     }
 
@@ -449,26 +508,37 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
         let CoreTemplateBuildFnTable {
             functions,
             string_methods,
+            string_map_methods,
+            string_map_entry_methods,
             boolean_methods,
             integer_methods,
             config_value_methods,
             signature_methods,
             email_methods,
             size_hint_methods,
+            shortest_id_prefix_methods,
             timestamp_methods,
             timestamp_range_methods,
+            duration_methods,
         } = extension;
 
         merge_fn_map(&mut self.functions, functions);
         merge_fn_map(&mut self.string_methods, string_methods);
+        merge_fn_map(&mut self.string_map_methods, string_map_methods);
+        merge_fn_map(&mut self.string_map_entry_methods, string_map_entry_methods);
         merge_fn_map(&mut self.boolean_methods, boolean_methods);
         merge_fn_map(&mut self.integer_methods, integer_methods);
         merge_fn_map(&mut self.config_value_methods, config_value_methods);
         merge_fn_map(&mut self.signature_methods, signature_methods);
         merge_fn_map(&mut self.email_methods, email_methods);
         merge_fn_map(&mut self.size_hint_methods, size_hint_methods);
+        merge_fn_map(
+            &mut self.shortest_id_prefix_methods,
+            shortest_id_prefix_methods,
+        );
         merge_fn_map(&mut self.timestamp_methods, timestamp_methods);
         merge_fn_map(&mut self.timestamp_range_methods, timestamp_range_methods);
+        merge_fn_map(&mut self.duration_methods, duration_methods);
     }
 
     /// Translates the function call node `function` by using this symbol table.
@@ -510,8 +580,19 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
                     property,
                     function,
                     L::wrap_string,
+                    L::wrap_string_list,
                 )
             }
+            CoreTemplatePropertyKind::StringMap(property) => {
+                let table = &self.string_map_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(language, diagnostics, build_ctx, property, function)
+            }
+            CoreTemplatePropertyKind::StringMapEntry(property) => {
+                let table = &self.string_map_entry_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(language, diagnostics, build_ctx, property, function)
+            }
             CoreTemplatePropertyKind::Boolean(property) => {
                 let table = &self.boolean_methods;
                 let build = template_parser::lookup_method(type_name, table, function)?;
@@ -555,6 +636,11 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(language, diagnostics, build_ctx, property, function)
             }
+            CoreTemplatePropertyKind::ShortestIdPrefix(property) => {
+                let table = &self.shortest_id_prefix_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(language, diagnostics, build_ctx, property, function)
+            }
             CoreTemplatePropertyKind::Timestamp(property) => {
                 let table = &self.timestamp_methods;
                 let build = template_parser::lookup_method(type_name, table, function)?;
@@ -565,6 +651,11 @@ impl<'a, L: TemplateLanguage<'a> + ?Sized> CoreTemplateBuildFnTable<'a, L> {
                 let build = template_parser::lookup_method(type_name, table, function)?;
                 build(language, diagnostics, build_ctx, property, function)
             }
+            CoreTemplatePropertyKind::Duration(property) => {
+                let table = &self.duration_methods;
+                let build = template_parser::lookup_method(type_name, table, function)?;
+                build(language, diagnostics, build_ctx, property, function)
+            }
             CoreTemplatePropertyKind::Template(_) => {
                 // TODO: migrate to table?
                 Err(TemplateParseError::no_such_method(type_name, function))
@@ -911,14 +1002,160 @@ fn string_index_to_char_boundary(s: &str, i: isize) -> usize {
     }
 }
 
-fn builtin_config_value_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
-) -> TemplateBuildMethodFnMap<'a, L, ConfigValue> {
-    fn extract<'de, T: Deserialize<'de>>(value: ConfigValue) -> Result<T, TemplatePropertyError> {
-        T::deserialize(value.into_deserializer())
-            // map to err.message() because TomlError appends newline to it
-            .map_err(|err| TemplatePropertyError(err.message().into()))
+fn builtin_string_map_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
+) -> TemplateBuildMethodFnMap<'a, L, StringMap> {
+    fn entries(map: StringMap) -> Vec<StringMapEntry> {
+        map.into_iter()
+            .map(|(key, value)| StringMapEntry { key, value })
+            .collect()
+    }
+
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = TemplateBuildMethodFnMap::<L, StringMap>::new();
+    map.insert(
+        "contains_key",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [key_node] = function.expect_exact_arguments()?;
+            let key_property =
+                expect_plain_text_expression(language, diagnostics, build_ctx, key_node)?;
+            let out_property =
+                (self_property, key_property).map(|(map, key)| map.contains_key(&key));
+            Ok(L::wrap_boolean(out_property))
+        },
+    );
+    map.insert(
+        "get",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let [key_node] = function.expect_exact_arguments()?;
+            let key_property =
+                expect_plain_text_expression(language, diagnostics, build_ctx, key_node)?;
+            let out_property = (self_property, key_property)
+                .map(|(mut map, key)| map.remove(&key).unwrap_or_default());
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "keys",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|map| map.into_keys().collect());
+            Ok(L::wrap_string_list(out_property))
+        },
+    );
+    map.insert(
+        "values",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|map| map.into_values().collect());
+            Ok(L::wrap_string_list(out_property))
+        },
+    );
+    // Iteration is delegated to the list of entries.
+    for name in ["filter", "join", "len", "map"] {
+        map.insert(
+            name,
+            |language, diagnostics, build_ctx, self_property, function| {
+                build_formattable_list_method(
+                    language,
+                    diagnostics,
+                    build_ctx,
+                    self_property.map(entries),
+                    function,
+                    L::wrap_string_map_entry,
+                    |property| {
+                        L::wrap_string_map(property.map(|entries| {
+                            entries
+                                .into_iter()
+                                .map(|entry| (entry.key, entry.value))
+                                .collect()
+                        }))
+                    },
+                )
+            },
+        );
     }
+    map
+}
+
+fn builtin_string_map_entry_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
+) -> TemplateBuildMethodFnMap<'a, L, StringMapEntry> {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = TemplateBuildMethodFnMap::<L, StringMapEntry>::new();
+    map.insert(
+        "key",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|entry| entry.key);
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "value",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|entry| entry.value);
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map
+}
+
+fn extract_config_value<'de, T: Deserialize<'de>>(
+    value: ConfigValue,
+) -> Result<T, TemplatePropertyError> {
+    T::deserialize(value.into_deserializer())
+        // map to err.message() because TomlError appends newline to it
+        .map_err(|err| TemplatePropertyError(err.message().into()))
+}
+
+fn builtin_integer_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
+) -> TemplateBuildMethodFnMap<'a, L, i64> {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = TemplateBuildMethodFnMap::<L, i64>::new();
+    map.insert(
+        "format",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            let [format_node] = function.expect_exact_arguments()?;
+            let format =
+                template_parser::expect_string_literal_with(format_node, |format, span| {
+                    let format = text_util::IntegerFormat::parse(format).ok_or_else(|| {
+                        TemplateParseError::expression("Invalid integer format", span)
+                    })?;
+                    let max_width = text_util::IntegerFormat::MAX_WIDTH;
+                    if format.width() > max_width {
+                        return Err(TemplateParseError::expression(
+                            format!("Integer format width must not exceed {max_width}"),
+                            span,
+                        ));
+                    }
+                    Ok(format)
+                })?;
+            let out_property = self_property.map(move |value| format.format(value));
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "separate_thousands",
+        |language, diagnostics, build_ctx, self_property, function| {
+            let ([], [separator_node]) = function.expect_arguments()?;
+            let separator = if let Some(node) = separator_node {
+                expect_plain_text_expression(language, diagnostics, build_ctx, node)?
+            } else {
+                Box::new(Literal(",".to_owned()))
+            };
+            let out_property = (self_property, separator)
+                .map(|(value, separator)| text_util::separate_thousands(value, &separator));
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map
+}
 
+fn builtin_config_value_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
+) -> TemplateBuildMethodFnMap<'a, L, ConfigValue> {
     // Not using maplit::hashmap!{} or custom declarative macro here because
     // code completion inside macro is quite restricted.
     let mut map = TemplateBuildMethodFnMap::<L, ConfigValue>::new();
@@ -930,7 +1167,7 @@ fn builtin_config_value_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
         "as_boolean",
         |_language, _diagnostics, _build_ctx, self_property, function| {
             function.expect_no_arguments()?;
-            let out_property = self_property.and_then(extract);
+            let out_property = self_property.and_then(extract_config_value);
             Ok(L::wrap_boolean(out_property))
         },
     );
@@ -938,7 +1175,7 @@ fn builtin_config_value_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
         "as_integer",
         |_language, _diagnostics, _build_ctx, self_property, function| {
             function.expect_no_arguments()?;
-            let out_property = self_property.and_then(extract);
+            let out_property = self_property.and_then(extract_config_value);
             Ok(L::wrap_integer(out_property))
         },
     );
@@ -946,7 +1183,7 @@ fn builtin_config_value_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
         "as_string",
         |_language, _diagnostics, _build_ctx, self_property, function| {
             function.expect_no_arguments()?;
-            let out_property = self_property.and_then(extract);
+            let out_property = self_property.and_then(extract_config_value);
             Ok(L::wrap_string(out_property))
         },
     );
@@ -954,7 +1191,7 @@ fn builtin_config_value_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
         "as_string_list",
         |_language, _diagnostics, _build_ctx, self_property, function| {
             function.expect_no_arguments()?;
-            let out_property = self_property.and_then(extract);
+            let out_property = self_property.and_then(extract_config_value);
             Ok(L::wrap_string_list(out_property))
         },
     );
@@ -1085,6 +1322,46 @@ fn builtin_size_hint_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
     map
 }
 
+fn builtin_shortest_id_prefix_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
+) -> TemplateBuildMethodFnMap<'a, L, ShortestIdPrefix> {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = TemplateBuildMethodFnMap::<L, ShortestIdPrefix>::new();
+    map.insert(
+        "prefix",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|id| id.prefix);
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "rest",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|id| id.rest);
+            Ok(L::wrap_string(out_property))
+        },
+    );
+    map.insert(
+        "upper",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|id| id.to_upper());
+            Ok(L::wrap_shortest_id_prefix(out_property))
+        },
+    );
+    map.insert(
+        "lower",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(|id| id.to_lower());
+            Ok(L::wrap_shortest_id_prefix(out_property))
+        },
+    );
+    map
+}
+
 fn builtin_timestamp_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
 ) -> TemplateBuildMethodFnMap<'a, L, Timestamp> {
     // Not using maplit::hashmap!{} or custom declarative macro here because
@@ -1195,6 +1472,40 @@ fn builtin_timestamp_range_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
         |_language, _diagnostics, _build_ctx, self_property, function| {
             function.expect_no_arguments()?;
             let out_property = self_property.and_then(|time_range| Ok(time_range.duration()?));
+            Ok(L::wrap_duration(out_property))
+        },
+    );
+    map
+}
+
+fn builtin_duration_methods<'a, L: TemplateLanguage<'a> + ?Sized>(
+) -> TemplateBuildMethodFnMap<'a, L, Duration> {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map = TemplateBuildMethodFnMap::<L, Duration>::new();
+    map.insert(
+        "seconds",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.and_then(|duration| Ok(i64::try_from(duration.as_secs())?));
+            Ok(L::wrap_integer(out_property))
+        },
+    );
+    map.insert(
+        "milliseconds",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property =
+                self_property.and_then(|duration| Ok(i64::try_from(duration.as_millis())?));
+            Ok(L::wrap_integer(out_property))
+        },
+    );
+    map.insert(
+        "humanized",
+        |_language, _diagnostics, _build_ctx, self_property, function| {
+            function.expect_no_arguments()?;
+            let out_property = self_property.map(time_util::format_duration_humanized);
             Ok(L::wrap_string(out_property))
         },
     );
@@ -1228,8 +1539,10 @@ pub fn build_formattable_list_method<'a, L, O>(
     self_property: impl TemplateProperty<Output = Vec<O>> + 'a,
     function: &FunctionCallNode,
     // TODO: Generic L: WrapProperty<O> trait might be needed to support more
-    // list operations such as first()/slice(). For .map(), a simple callback works.
+    // list operations such as first()/slice(). For .map() and .filter(),
+    // simple callbacks work.
     wrap_item: impl Fn(PropertyPlaceholder<O>) -> L::Property,
+    wrap_list: impl Fn(Box<dyn TemplateProperty<Output = Vec<O>> + 'a>) -> L::Property,
 ) -> TemplateParseResult<L::Property>
 where
     L: TemplateLanguage<'a> + ?Sized,
@@ -1251,6 +1564,15 @@ where
                 });
             L::wrap_template(Box::new(template))
         }
+        "filter" => build_filter_operation(
+            language,
+            diagnostics,
+            build_ctx,
+            self_property,
+            function,
+            wrap_item,
+            wrap_list,
+        )?,
         "map" => build_map_operation(
             language,
             diagnostics,
@@ -1271,6 +1593,7 @@ pub fn build_unformattable_list_method<'a, L, O>(
     self_property: impl TemplateProperty<Output = Vec<O>> + 'a,
     function: &FunctionCallNode,
     wrap_item: impl Fn(PropertyPlaceholder<O>) -> L::Property,
+    wrap_list: impl Fn(Box<dyn TemplateProperty<Output = Vec<O>> + 'a>) -> L::Property,
 ) -> TemplateParseResult<L::Property>
 where
     L: TemplateLanguage<'a> + ?Sized,
@@ -1283,6 +1606,15 @@ where
             L::wrap_integer(out_property)
         }
         // No "join"
+        "filter" => build_filter_operation(
+            language,
+            diagnostics,
+            build_ctx,
+            self_property,
+            function,
+            wrap_item,
+            wrap_list,
+        )?,
         "map" => build_map_operation(
             language,
             diagnostics,
@@ -1320,20 +1652,9 @@ where
     let item_placeholder = PropertyPlaceholder::new();
     let item_template = template_parser::expect_lambda_with(lambda_node, |lambda, _span| {
         let item_fn = || wrap_item(item_placeholder.clone());
-        let mut local_variables = build_ctx.local_variables.clone();
-        if let [name] = lambda.params.as_slice() {
-            local_variables.insert(name, &item_fn);
-        } else {
-            return Err(TemplateParseError::expression(
-                "Expected 1 lambda parameters",
-                lambda.params_span,
-            ));
-        }
-        let inner_build_ctx = BuildContext {
-            local_variables,
-            self_variable: build_ctx.self_variable,
-        };
-        expect_template_expression(language, diagnostics, &inner_build_ctx, &lambda.body)
+        build_lambda_expression(build_ctx, lambda, &[&item_fn], |build_ctx, body| {
+            expect_template_expression(language, diagnostics, build_ctx, body)
+        })
     })?;
     let list_template = ListPropertyTemplate::new(
         self_property,
@@ -1345,6 +1666,76 @@ where
     Ok(L::wrap_list_template(Box::new(list_template)))
 }
 
+/// Builds expression that extracts iterable property and keeps the items for
+/// which the predicate evaluates to true.
+///
+/// `wrap_item()` is the function to wrap a list item of type `O` as a property,
+/// and `wrap_list()` is the function to wrap the filtered list.
+fn build_filter_operation<'a, L, O, P>(
+    language: &L,
+    diagnostics: &mut TemplateDiagnostics,
+    build_ctx: &BuildContext<L::Property>,
+    self_property: P,
+    function: &FunctionCallNode,
+    wrap_item: impl Fn(PropertyPlaceholder<O>) -> L::Property,
+    wrap_list: impl Fn(Box<dyn TemplateProperty<Output = Vec<O>> + 'a>) -> L::Property,
+) -> TemplateParseResult<L::Property>
+where
+    L: TemplateLanguage<'a> + ?Sized,
+    P: TemplateProperty + 'a,
+    P::Output: IntoIterator<Item = O>,
+    O: Clone + 'a,
+{
+    let [lambda_node] = function.expect_exact_arguments()?;
+    let item_placeholder = PropertyPlaceholder::new();
+    let item_predicate = template_parser::expect_lambda_with(lambda_node, |lambda, _span| {
+        let item_fn = || wrap_item(item_placeholder.clone());
+        build_lambda_expression(build_ctx, lambda, &[&item_fn], |build_ctx, body| {
+            expect_boolean_expression(language, diagnostics, build_ctx, body)
+        })
+    })?;
+    let out_property = self_property.and_then(move |items| {
+        items
+            .into_iter()
+            .filter_map(|item| {
+                // Evaluate the predicate with the item, then take it back.
+                item_placeholder.set(item);
+                let result = item_predicate.extract();
+                let item = item_placeholder.take().unwrap();
+                result.map(|pred| pred.then_some(item)).transpose()
+            })
+            .collect()
+    });
+    Ok(wrap_list(Box::new(out_property)))
+}
+
+/// Builds lambda expression to be evaluated with the provided arguments.
+///
+/// `arg_fns` is usually a slice of functions to wrap [`PropertyPlaceholder`]s.
+fn build_lambda_expression<'i, P, T>(
+    build_ctx: &BuildContext<'i, P>,
+    lambda: &LambdaNode<'i>,
+    arg_fns: &[&'i dyn Fn() -> P],
+    build_body: impl FnOnce(&BuildContext<'i, P>, &ExpressionNode<'i>) -> TemplateParseResult<T>,
+) -> TemplateParseResult<T> {
+    if lambda.params.len() != arg_fns.len() {
+        return Err(TemplateParseError::expression(
+            format!("Expected {} lambda parameters", arg_fns.len()),
+            lambda.params_span,
+        ));
+    }
+    let mut local_variables = build_ctx.local_variables.clone();
+    local_variables.extend(iter::zip(
+        lambda.params.iter().copied(),
+        arg_fns.iter().copied(),
+    ));
+    let inner_build_ctx = BuildContext {
+        local_variables,
+        self_variable: build_ctx.self_variable,
+    };
+    build_body(&inner_build_ctx, &lambda.body)
+}
+
 fn builtin_functions<'a, L: TemplateLanguage<'a> + ?Sized>() -> TemplateBuildFunctionFnMap<'a, L> {
     // Not using maplit::hashmap!{} or custom declarative macro here because
     // code completion inside macro is quite restricted.
@@ -1427,6 +1818,13 @@ fn builtin_functions<'a, L: TemplateLanguage<'a> + ?Sized>() -> TemplateBuildFun
             content, labels,
         ))))
     });
+    map.insert("hyperlink", |language, diagnostics, build_ctx, function| {
+        let [url_node, content_node] = function.expect_exact_arguments()?;
+        let url = expect_plain_text_expression(language, diagnostics, build_ctx, url_node)?;
+        let content = expect_template_expression(language, diagnostics, build_ctx, content_node)?;
+        let template = HyperlinkTemplate::new(url, content);
+        Ok(L::wrap_template(Box::new(template)))
+    });
     map.insert(
         "raw_escape_sequence",
         |language, diagnostics, build_ctx, function| {
@@ -2206,6 +2604,43 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn test_self_method_with_arguments() {
+        let mut env = TestTemplateEnv::new();
+        env.language.add_method(
+            "greet",
+            |language, diagnostics, build_ctx, self_property, function| {
+                let [name_node] = function.expect_exact_arguments()?;
+                let name_property =
+                    expect_plain_text_expression(language, diagnostics, build_ctx, name_node)?;
+                let out_property =
+                    (self_property, name_property).map(|((), name)| format!("Hello, {name}!"));
+                Ok(L::wrap_string(out_property))
+            },
+        );
+
+        insta::assert_snapshot!(env.render_ok(r#"self.greet("world")"#), @"Hello, world!");
+        insta::assert_snapshot!(
+            env.render_ok(r#"self.greet("a" ++ "b").upper()"#), @"HELLO, AB!");
+        insta::assert_snapshot!(env.parse_err(r#"self.greet()"#), @r#"
+         --> 1:12
+          |
+        1 | self.greet()
+          |            ^
+          |
+          = Function "greet": Expected 1 arguments
+        "#);
+        // Methods taking arguments can't be called as keywords
+        insta::assert_snapshot!(env.parse_err(r#"greet"#), @r#"
+         --> 1:1
+          |
+        1 | greet
+          | ^---^
+          |
+          = Keyword "greet" doesn't exist
+        "#);
+    }
+
     #[test]
     fn test_boolean_cast() {
         let mut env = TestTemplateEnv::new();
@@ -2392,6 +2827,41 @@ mod tests {
         env.add_alias("identity", "|x| x");
         insta::assert_snapshot!(env.render_ok(r#""a\nb\nc".lines().map(identity)"#), @"a b c");
 
+        insta::assert_snapshot!(
+            env.render_ok(r#""a\nb\nc".lines().filter(|s| s != "b")"#),
+            @"a c");
+        insta::assert_snapshot!(
+            env.render_ok(r#""a\nb\nc".lines().filter(|s| s != "b").join(",")"#),
+            @"a,c");
+        insta::assert_snapshot!(
+            env.render_ok(r#""a\nb\nc".lines().filter(|s| false).len()"#),
+            @"0");
+        // Global keyword in predicate
+        insta::assert_snapshot!(
+            env.render_ok(r#""a\nb\nc".lines().filter(|s| empty)"#),
+            @"a b c");
+        // Chained filter/map operations
+        insta::assert_snapshot!(
+            env.render_ok(r#""a\nb\nc".lines().filter(|s| s != "a").map(|s| s.upper())"#),
+            @"B C");
+        // Predicate must be a boolean expression
+        insta::assert_snapshot!(env.parse_err(r#""a".lines().filter(|s| s.len())"#), @r#"
+         --> 1:24
+          |
+        1 | "a".lines().filter(|s| s.len())
+          |                        ^-----^
+          |
+          = Expected expression of type "Boolean", but actual type is "Integer"
+        "#);
+        insta::assert_snapshot!(env.parse_err(r#""a".lines().filter(|a, b| true)"#), @r#"
+         --> 1:21
+          |
+        1 | "a".lines().filter(|a, b| true)
+          |                     ^--^
+          |
+          = Expected 1 lambda parameters
+        "#);
+
         // Not a lambda expression
         insta::assert_snapshot!(env.parse_err(r#""a".lines().map(empty)"#), @r###"
          --> 1:17
@@ -2445,6 +2915,51 @@ mod tests {
         "#);
     }
 
+    #[test]
+    fn test_string_map_method() {
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("tags", || {
+            L::wrap_string_map(Literal(StringMap::from([
+                ("b".to_owned(), "2".to_owned()),
+                ("a".to_owned(), "1".to_owned()),
+            ])))
+        });
+        env.add_keyword("no_tags", || L::wrap_string_map(Literal(StringMap::new())));
+
+        insta::assert_snapshot!(env.render_ok(r#"tags"#), @r"
+        a: 1
+        b: 2
+        ");
+        insta::assert_snapshot!(env.render_ok(r#"if(tags, "yes", "no")"#), @"yes");
+        insta::assert_snapshot!(env.render_ok(r#"if(no_tags, "yes", "no")"#), @"no");
+        insta::assert_snapshot!(env.render_ok(r#"tags.len()"#), @"2");
+        insta::assert_snapshot!(env.render_ok(r#"tags.get("a")"#), @"1");
+        insta::assert_snapshot!(env.render_ok(r#"tags.get("c")"#), @"");
+        insta::assert_snapshot!(env.render_ok(r#"tags.contains_key("b")"#), @"true");
+        insta::assert_snapshot!(env.render_ok(r#"tags.contains_key("c")"#), @"false");
+        insta::assert_snapshot!(env.render_ok(r#"tags.keys()"#), @"a b");
+        insta::assert_snapshot!(env.render_ok(r#"tags.values().join(",")"#), @"1,2");
+        insta::assert_snapshot!(env.render_ok(r#"tags.join(", ")"#), @"a: 1, b: 2");
+        insta::assert_snapshot!(
+            env.render_ok(r#"tags.map(|e| e.key() ++ "=" ++ e.value()).join("&")"#),
+            @"a=1&b=2");
+        insta::assert_snapshot!(
+            env.render_ok(r#"tags.filter(|e| e.value() != "1")"#),
+            @"b: 2");
+        insta::assert_snapshot!(
+            env.render_ok(r#"tags.filter(|e| e.key() == "a").keys()"#),
+            @"a");
+
+        insta::assert_snapshot!(env.parse_err(r#"tags.first()"#), @r#"
+         --> 1:6
+          |
+        1 | tags.first()
+          |      ^---^
+          |
+          = Method "first" doesn't exist for type "StringMap"
+        "#);
+    }
+
     #[test]
     fn test_string_method() {
         let mut env = TestTemplateEnv::new();
@@ -2530,6 +3045,41 @@ mod tests {
         insta::assert_snapshot!(env.render_ok(r#""abcdef".substr(-2, -4)"#), @"");
     }
 
+    #[test]
+    fn test_integer_method() {
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("big", || L::wrap_integer(Literal(1234567)));
+        env.add_keyword("small", || L::wrap_integer(Literal(-42)));
+
+        insta::assert_snapshot!(env.render_ok(r#"big.format("{:>9}")"#), @"  1234567");
+        insta::assert_snapshot!(env.render_ok(r#"small.format("{:06}|")"#), @"-00042|");
+        insta::assert_snapshot!(env.render_ok(r#"small.format("{:<5} lines")"#), @"-42   lines");
+
+        insta::assert_snapshot!(env.render_ok(r#"big.separate_thousands()"#), @"1,234,567");
+        insta::assert_snapshot!(env.render_ok(r#"big.separate_thousands("_")"#), @"1_234_567");
+        insta::assert_snapshot!(env.render_ok(r#"small.separate_thousands()"#), @"-42");
+        insta::assert_snapshot!(
+            env.render_ok(r#"pad_start(12, big.separate_thousands())"#),
+            @"   1,234,567");
+
+        insta::assert_snapshot!(env.parse_err(r#"big.format("{:x}")"#), @r#"
+         --> 1:12
+          |
+        1 | big.format("{:x}")
+          |            ^----^
+          |
+          = Invalid integer format
+        "#);
+        insta::assert_snapshot!(env.parse_err(r#"big.format("{:1001}")"#), @r#"
+         --> 1:12
+          |
+        1 | big.format("{:1001}")
+          |            ^-------^
+          |
+          = Integer format width must not exceed 1000
+        "#);
+    }
+
     #[test]
     fn test_config_value_method() {
         let mut env = TestTemplateEnv::new();
@@ -2568,6 +3118,13 @@ mod tests {
         insta::assert_snapshot!(
             env.render_ok("string_list.as_boolean()"),
             @"<Error: invalid type: sequence, expected a boolean>");
+
+        // Boolean values can be used as conditions
+        insta::assert_snapshot!(env.render_ok(r#"if(boolean, "yes", "no")"#), @"yes");
+        insta::assert_snapshot!(env.render_ok(r#"if(!boolean, "yes", "no")"#), @"no");
+        insta::assert_snapshot!(
+            env.render_ok(r#"if(integer, "yes", "no")"#),
+            @"<Error: invalid type: integer `42`, expected a boolean>");
     }
 
     #[test]
@@ -2735,6 +3292,33 @@ mod tests {
         "#);
     }
 
+    #[test]
+    fn test_duration_method() {
+        let mut env = TestTemplateEnv::new();
+        env.add_keyword("zero", || L::wrap_duration(Literal(Duration::ZERO)));
+        env.add_keyword("subsec", || {
+            L::wrap_duration(Literal(Duration::from_millis(250)))
+        });
+        env.add_keyword("minutes", || {
+            L::wrap_duration(Literal(Duration::from_millis(222_500)))
+        });
+        env.add_keyword("days", || {
+            L::wrap_duration(Literal(Duration::from_secs(2 * 86400 + 5 * 3600 + 59)))
+        });
+
+        insta::assert_snapshot!(env.render_ok(r#"zero"#), @"less than a microsecond");
+        insta::assert_snapshot!(env.render_ok(r#"minutes"#), @"3 minutes");
+
+        insta::assert_snapshot!(env.render_ok(r#"zero.seconds()"#), @"0");
+        insta::assert_snapshot!(env.render_ok(r#"minutes.seconds()"#), @"222");
+        insta::assert_snapshot!(env.render_ok(r#"minutes.milliseconds()"#), @"222500");
+
+        insta::assert_snapshot!(env.render_ok(r#"zero.humanized()"#), @"0ms");
+        insta::assert_snapshot!(env.render_ok(r#"subsec.humanized()"#), @"250ms");
+        insta::assert_snapshot!(env.render_ok(r#"minutes.humanized()"#), @"3m 42s");
+        insta::assert_snapshot!(env.render_ok(r#"days.humanized()"#), @"2d 5h");
+    }
+
     #[test]
     fn test_fill_function() {
         let mut env = TestTemplateEnv::new();
@@ -2982,6 +3566,33 @@ mod tests {
             @r#"]8;;http://example.com\Example]8;;\"#);
     }
 
+    #[test]
+    fn test_hyperlink_function() {
+        let mut env = TestTemplateEnv::new();
+        env.add_color("error", crossterm::style::Color::DarkRed);
+        env.add_keyword("bad_string", || L::wrap_string(new_error_property("Bad")));
+
+        // Hyperlinks aren't enabled in the test formatter
+        insta::assert_snapshot!(
+            env.render_ok(r#"hyperlink("https://example.com/", "Example")"#),
+            @"Example");
+        insta::assert_snapshot!(
+            env.render_ok(r#"hyperlink("https://example.com/" ++ 1, label("error", "Example"))"#),
+            @"[38;5;1mExample[39m");
+        insta::assert_snapshot!(
+            env.render_ok(r#"hyperlink(bad_string, "Example")"#),
+            @"<Error: Bad>");
+
+        insta::assert_snapshot!(env.parse_err(r#"hyperlink("https://example.com/")"#), @r#"
+         --> 1:11
+          |
+        1 | hyperlink("https://example.com/")
+          |           ^--------------------^
+          |
+          = Function "hyperlink": Expected 2 arguments
+        "#);
+    }
+
     #[test]
     fn test_coalesce_function() {
         let mut env = TestTemplateEnv::new();
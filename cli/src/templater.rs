@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::error;
 use std::fmt;
 use std::io;
@@ -117,6 +118,35 @@ impl Template for Email {
 // bounded to 0.
 pub type SizeHint = (usize, Option<usize>);
 
+/// Hex ID split into the shortest unambiguous prefix and the rest.
+pub struct ShortestIdPrefix {
+    pub prefix: String,
+    pub rest: String,
+}
+
+impl Template for ShortestIdPrefix {
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        write!(formatter.labeled("prefix"), "{}", self.prefix)?;
+        write!(formatter.labeled("rest"), "{}", self.rest)?;
+        Ok(())
+    }
+}
+
+impl ShortestIdPrefix {
+    pub fn to_upper(&self) -> Self {
+        Self {
+            prefix: self.prefix.to_ascii_uppercase(),
+            rest: self.rest.to_ascii_uppercase(),
+        }
+    }
+    pub fn to_lower(&self) -> Self {
+        Self {
+            prefix: self.prefix.to_ascii_lowercase(),
+            rest: self.rest.to_ascii_lowercase(),
+        }
+    }
+}
+
 impl Template for String {
     fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
         write!(formatter, "{self}")
@@ -146,16 +176,8 @@ pub struct TimestampRange {
 }
 
 impl TimestampRange {
-    // TODO: Introduce duration type, and move formatting to it.
-    pub fn duration(&self) -> Result<String, time_util::TimestampOutOfRange> {
-        let mut f = timeago::Formatter::new();
-        f.min_unit(timeago::TimeUnit::Microseconds).ago("");
-        let duration = time_util::format_duration(&self.start, &self.end, &f)?;
-        if duration == "now" {
-            Ok("less than a microsecond".to_owned())
-        } else {
-            Ok(duration)
-        }
+    pub fn duration(&self) -> Result<Duration, time_util::TimestampOutOfRange> {
+        time_util::duration_between(&self.start, &self.end)
     }
 }
 
@@ -168,12 +190,52 @@ impl Template for TimestampRange {
     }
 }
 
+/// Elapsed time, such as the duration of an operation.
+pub type Duration = std::time::Duration;
+
+impl Template for Duration {
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        let mut f = timeago::Formatter::new();
+        f.min_unit(timeago::TimeUnit::Microseconds).ago("");
+        let duration = f.convert(*self);
+        if duration == "now" {
+            write!(formatter, "less than a microsecond")
+        } else {
+            write!(formatter, "{duration}")
+        }
+    }
+}
+
 impl Template for Vec<String> {
     fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
         format_joined(formatter, self, " ")
     }
 }
 
+/// Map of string keys to string values, sorted by key.
+pub type StringMap = BTreeMap<String, String>;
+
+impl Template for StringMap {
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        format_joined_with(formatter, self, "\n", |formatter, (key, value)| {
+            write!(formatter, "{key}: {value}")
+        })
+    }
+}
+
+/// Key-value pair of [`StringMap`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StringMapEntry {
+    pub key: String,
+    pub value: String,
+}
+
+impl Template for StringMapEntry {
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        write!(formatter, "{}: {}", self.key, self.value)
+    }
+}
+
 impl Template for bool {
     fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
         let repr = if *self { "true" } else { "false" };
@@ -215,6 +277,38 @@ where
     }
 }
 
+/// Renders the content as a hyperlink to the URL if the formatter supports it.
+pub struct HyperlinkTemplate<P, T> {
+    url: P,
+    content: T,
+}
+
+impl<P, T> HyperlinkTemplate<P, T> {
+    pub fn new(url: P, content: T) -> Self
+    where
+        P: TemplateProperty<Output = String>,
+        T: Template,
+    {
+        HyperlinkTemplate { url, content }
+    }
+}
+
+impl<P, T> Template for HyperlinkTemplate<P, T>
+where
+    P: TemplateProperty<Output = String>,
+    T: Template,
+{
+    fn format(&self, formatter: &mut TemplateFormatter) -> io::Result<()> {
+        let url = match self.url.extract() {
+            Ok(url) => url,
+            Err(err) => return formatter.handle_error(err),
+        };
+        formatter.push_hyperlink(&url)?;
+        self.content.format(formatter)?;
+        formatter.pop_hyperlink()
+    }
+}
+
 pub struct RawEscapeSequenceTemplate<T>(pub T);
 
 impl<T: Template> Template for RawEscapeSequenceTemplate<T> {
@@ -757,6 +851,14 @@ impl<'a> TemplateFormatter<'a> {
         self.formatter.pop_label()
     }
 
+    pub fn push_hyperlink(&mut self, url: &str) -> io::Result<()> {
+        self.formatter.push_hyperlink(url)
+    }
+
+    pub fn pop_hyperlink(&mut self) -> io::Result<()> {
+        self.formatter.pop_hyperlink()
+    }
+
     pub fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> io::Result<()> {
         self.formatter.write_fmt(args)
     }
@@ -39,6 +39,164 @@ pub fn split_email(email: &str) -> (&str, Option<&str>) {
     }
 }
 
+/// Formats the `value` with the `separator` inserted between groups of three
+/// digits, e.g. `1,234,567`.
+pub fn separate_thousands(value: i64, separator: &str) -> String {
+    let digits = value.unsigned_abs().to_string();
+    let mut out = String::with_capacity(digits.len() * (1 + separator.len()) / 3 + 2);
+    if value < 0 {
+        out.push('-');
+    }
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push_str(separator);
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Parsed Rust-like format string of integer, such as `"{:>6} files"`.
+///
+/// The format string should contain exactly one `{}` or `{:spec}` placeholder,
+/// where `spec` is `[[fill]align][+][0][width]`. Literal braces are escaped as
+/// `{{` and `}}`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IntegerFormat {
+    prefix: String,
+    suffix: String,
+    fill: char,
+    align: IntegerAlign,
+    sign_plus: bool,
+    zero_pad: bool,
+    width: usize,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum IntegerAlign {
+    Left,
+    Center,
+    Right,
+}
+
+impl IntegerFormat {
+    /// Maximum `width` accepted by the format.
+    pub const MAX_WIDTH: usize = 1000;
+
+    /// Parses the format string. Returns `None` if it's invalid.
+    pub fn parse(format: &str) -> Option<Self> {
+        let mut prefix = String::new();
+        let mut suffix = String::new();
+        let mut spec = None;
+        let mut chars = format.chars().peekable();
+        while let Some(c) = chars.next() {
+            let out = if spec.is_none() {
+                &mut prefix
+            } else {
+                &mut suffix
+            };
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    out.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    out.push('}');
+                }
+                '{' if spec.is_none() => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next()? {
+                            '}' => break,
+                            c => placeholder.push(c),
+                        }
+                    }
+                    spec = Some(placeholder);
+                }
+                '{' | '}' => return None,
+                c => out.push(c),
+            }
+        }
+        let placeholder = spec?;
+        let spec = match placeholder.strip_prefix(':') {
+            Some(spec) => spec,
+            None if placeholder.is_empty() => "",
+            None => return None,
+        };
+        let mut format = IntegerFormat {
+            prefix,
+            suffix,
+            fill: ' ',
+            align: IntegerAlign::Right,
+            sign_plus: false,
+            zero_pad: false,
+            width: 0,
+        };
+        let parse_align = |c| match c {
+            '<' => Some(IntegerAlign::Left),
+            '^' => Some(IntegerAlign::Center),
+            '>' => Some(IntegerAlign::Right),
+            _ => None,
+        };
+        let mut rest = spec;
+        let mut spec_chars = spec.chars();
+        if let (Some(fill), Some(align)) =
+            (spec_chars.next(), spec_chars.next().and_then(parse_align))
+        {
+            format.fill = fill;
+            format.align = align;
+            rest = spec_chars.as_str();
+        } else if let Some(align) = spec.chars().next().and_then(parse_align) {
+            format.align = align;
+            rest = &spec[1..];
+        }
+        if let Some(r) = rest.strip_prefix('+') {
+            format.sign_plus = true;
+            rest = r;
+        }
+        if let Some(r) = rest.strip_prefix('0') {
+            format.zero_pad = true;
+            rest = r;
+        }
+        if !rest.is_empty() {
+            format.width = rest.parse().ok()?;
+        }
+        Some(format)
+    }
+
+    /// Minimum width of the formatted number, excluding the prefix and suffix.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Formats the `value` according to this format.
+    pub fn format(&self, value: i64) -> String {
+        let sign = if value < 0 {
+            "-"
+        } else if self.sign_plus {
+            "+"
+        } else {
+            ""
+        };
+        let digits = value.unsigned_abs().to_string();
+        let fill_width = self.width.saturating_sub(sign.len() + digits.len());
+        let body = if self.zero_pad {
+            format!("{sign}{}{digits}", "0".repeat(fill_width))
+        } else {
+            let (before, after) = match self.align {
+                IntegerAlign::Left => (0, fill_width),
+                IntegerAlign::Center => (fill_width / 2, fill_width - fill_width / 2),
+                IntegerAlign::Right => (fill_width, 0),
+            };
+            let before = self.fill.to_string().repeat(before);
+            let after = self.fill.to_string().repeat(after);
+            format!("{before}{sign}{digits}{after}")
+        };
+        [&self.prefix, &body, &self.suffix].concat()
+    }
+}
+
 /// Shortens `text` to `max_width` by removing leading characters. `ellipsis` is
 /// added if the `text` gets truncated.
 ///
@@ -523,6 +681,47 @@ mod tests {
         String::from_utf8(output).unwrap()
     }
 
+    #[test]
+    fn test_separate_thousands() {
+        assert_eq!(separate_thousands(0, ","), "0");
+        assert_eq!(separate_thousands(999, ","), "999");
+        assert_eq!(separate_thousands(1000, ","), "1,000");
+        assert_eq!(separate_thousands(1234567, "_"), "1_234_567");
+        assert_eq!(separate_thousands(-123456, ","), "-123,456");
+        assert_eq!(
+            separate_thousands(i64::MIN, ","),
+            "-9,223,372,036,854,775,808"
+        );
+    }
+
+    #[test]
+    fn test_integer_format() {
+        let format = |format: &str, value: i64| IntegerFormat::parse(format).unwrap().format(value);
+        assert_eq!(format("{}", 42), "42");
+        assert_eq!(format("{:}", -42), "-42");
+        assert_eq!(format("{:6}", 42), "    42");
+        assert_eq!(format("{:>6}", 42), "    42");
+        assert_eq!(format("{:<6}|", 42), "42    |");
+        assert_eq!(format("{:^6}|", 42), "  42  |");
+        assert_eq!(format("{:^5}|", 42), " 42  |");
+        assert_eq!(format("{:*<6}", 42), "42****");
+        assert_eq!(format("{:06}", -42), "-00042");
+        assert_eq!(format("{:+}", 42), "+42");
+        assert_eq!(format("{:+06}", 42), "+00042");
+        assert_eq!(format("{:2}", 12345), "12345");
+        assert_eq!(format("{{{:>4}}} files", 7), "{   7} files");
+
+        // Invalid formats
+        assert_eq!(IntegerFormat::parse(""), None);
+        assert_eq!(IntegerFormat::parse("no placeholder"), None);
+        assert_eq!(IntegerFormat::parse("{} {}"), None);
+        assert_eq!(IntegerFormat::parse("{"), None);
+        assert_eq!(IntegerFormat::parse("}{}"), None);
+        assert_eq!(IntegerFormat::parse("{0}"), None);
+        assert_eq!(IntegerFormat::parse("{:x}"), None);
+        assert_eq!(IntegerFormat::parse("{:>-6}"), None);
+    }
+
     #[test]
     fn test_elide_start() {
         // Empty string
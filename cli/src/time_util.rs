@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use chrono::format::StrftimeItems;
 use chrono::DateTime;
 use chrono::FixedOffset;
@@ -89,9 +91,33 @@ pub fn format_duration(
     to: &Timestamp,
     format: &timeago::Formatter,
 ) -> Result<String, TimestampOutOfRange> {
-    let duration = datetime_from_timestamp(to)?
+    let duration = duration_between(from, to)?;
+    Ok(format.convert(duration))
+}
+
+/// Returns the time elapsed from `from` to `to`, which must not be negative.
+pub fn duration_between(from: &Timestamp, to: &Timestamp) -> Result<Duration, TimestampOutOfRange> {
+    datetime_from_timestamp(to)?
         .signed_duration_since(datetime_from_timestamp(from)?)
         .to_std()
-        .map_err(|_: chrono::OutOfRangeError| TimestampOutOfRange)?;
-    Ok(format.convert(duration))
+        .map_err(|_: chrono::OutOfRangeError| TimestampOutOfRange)
+}
+
+/// Formats the `duration` compactly with at most two units, e.g. "3m 42s".
+pub fn format_duration_humanized(duration: Duration) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+    let secs = duration.as_secs();
+    if secs >= DAY {
+        format!("{}d {}h", secs / DAY, secs % DAY / HOUR)
+    } else if secs >= HOUR {
+        format!("{}h {}m", secs / HOUR, secs % HOUR / MINUTE)
+    } else if secs >= MINUTE {
+        format!("{}m {}s", secs / MINUTE, secs % MINUTE)
+    } else if secs > 0 {
+        format!("{secs}s")
+    } else {
+        format!("{}ms", duration.as_millis())
+    }
 }
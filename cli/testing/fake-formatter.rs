@@ -29,6 +29,7 @@ use itertools::Itertools;
 /// `fake-formatter --stdout foo --stderr bar --fail` is similar to
 ///   `echo foo; echo bar >&2; false`.
 /// `fake-formatter --tee foo` is similar to `tee foo`).
+/// `fake-formatter --uppercase --lines 2:3` only alters the lines 2 to 3.
 ///
 /// This program acts as a portable alternative to that class of shell commands.
 #[derive(Parser, Debug)]
@@ -64,6 +65,11 @@ struct Args {
     /// Duplicate stdout into this file.
     #[arg(long)]
     tee: Option<PathBuf>,
+
+    /// Only alter the lines in the given 1-based inclusive range, e.g. `2:4`.
+    /// Can be repeated. If unspecified, all lines are altered.
+    #[arg(long)]
+    lines: Vec<String>,
 }
 
 fn main() -> ExitCode {
@@ -72,6 +78,14 @@ fn main() -> ExitCode {
     if let Some(data) = args.stderr {
         eprint!("{data}");
     }
+    let line_ranges = args
+        .lines
+        .iter()
+        .map(|range| {
+            let (first, last) = range.split_once(':').unwrap();
+            first.parse::<usize>().unwrap()..=last.parse::<usize>().unwrap()
+        })
+        .collect_vec();
     let stdout = if let Some(data) = args.stdout {
         // Other content-altering flags don't apply to --stdout.
         assert!(!args.reverse);
@@ -82,12 +96,20 @@ fn main() -> ExitCode {
     } else {
         let mut stdout = std::io::stdin()
             .lines()
-            .map(|line| {
+            .enumerate()
+            .map(|(index, line)| {
+                let line = line.unwrap();
+                let line_number = index + 1;
+                if !line_ranges.is_empty()
+                    && !line_ranges.iter().any(|range| range.contains(&line_number))
+                {
+                    return format!("{line}\n");
+                }
                 format!("{}\n", {
                     let line = if args.reverse {
-                        line.unwrap().chars().rev().collect()
+                        line.chars().rev().collect()
                     } else {
-                        line.unwrap()
+                        line
                     };
                     if args.uppercase {
                         assert!(!args.lowercase);
@@ -13,7 +13,9 @@ mod test_absorb_command;
 mod test_acls;
 mod test_advance_bookmarks;
 mod test_alias;
+mod test_api_command;
 mod test_backout_command;
+mod test_bisect_command;
 mod test_bookmark_command;
 mod test_builtin_aliases;
 mod test_commit_command;
@@ -34,6 +36,7 @@ mod test_file_chmod_command;
 mod test_file_show_command;
 mod test_file_track_untrack_commands;
 mod test_fix_command;
+mod test_fsck_command;
 mod test_generate_md_cli_help;
 mod test_git_clone;
 mod test_git_colocated;
@@ -47,6 +50,7 @@ mod test_git_submodule;
 mod test_gitignores;
 mod test_global_opts;
 mod test_help_command;
+mod test_hooks;
 mod test_immutable_commits;
 mod test_init_command;
 mod test_interdiff_command;
@@ -55,23 +59,29 @@ mod test_new_command;
 mod test_next_prev_commands;
 mod test_operations;
 mod test_parallelize_command;
+mod test_prompt_command;
 mod test_rebase_command;
 mod test_repo_change_report;
 mod test_resolve_command;
+mod test_resolve_divergence_command;
 mod test_restore_command;
 mod test_revset_output;
 mod test_root;
+mod test_secret_scan;
 mod test_shell_completion;
 mod test_show_command;
+mod test_sign_command;
 mod test_simplify_parents_command;
 mod test_sparse_command;
 mod test_split_command;
 mod test_squash_command;
+mod test_stats_command;
 mod test_status_command;
 mod test_tag_command;
 mod test_templater;
 mod test_undo;
 mod test_unsquash_command;
 mod test_util_command;
+mod test_verify_command;
 mod test_working_copy;
 mod test_workspaces;
@@ -0,0 +1,188 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indoc::indoc;
+
+use crate::common::TestEnvironment;
+
+fn parse_responses(stdout: &str) -> Vec<serde_json::Value> {
+    stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_api_requests() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "contents\n").unwrap();
+
+    let requests = indoc! {r#"
+        {"jsonrpc": "2.0", "id": 1, "method": "version"}
+        {"jsonrpc": "2.0", "id": 2, "method": "status"}
+        {"jsonrpc": "2.0", "id": 3, "method": "describe", "params": {"message": "first\n"}}
+        {"jsonrpc": "2.0", "id": 4, "method": "new", "params": {"message": "second"}}
+        {"jsonrpc": "2.0", "id": 5, "method": "log", "params": {"revset": "::@ ~ root()"}}
+        {"jsonrpc": "2.0", "id": 6, "method": "diff", "params": {"revision": "@-"}}
+        {"jsonrpc": "2.0", "id": 7, "method": "op_log", "params": {"limit": 2}}
+    "#};
+    let (stdout, _stderr) = test_env.jj_cmd_stdin_ok(&repo_path, &["api"], requests);
+    let responses = parse_responses(&stdout);
+    assert_eq!(responses.len(), 7);
+    for (i, response) in responses.iter().enumerate() {
+        assert_eq!(response["id"], i + 1, "{response}");
+        assert!(response["error"].is_null(), "{response}");
+    }
+
+    assert_eq!(responses[0]["result"]["api_version"], 1);
+
+    let status = &responses[1]["result"];
+    assert!(status["working_copy"]["change_id"]
+        .as_str()
+        .unwrap()
+        .starts_with("qpvuntsmwlqt"));
+    assert_eq!(
+        status["changes"],
+        serde_json::json!([{"path": "file", "status": "added", "conflict": false}])
+    );
+
+    assert_eq!(responses[2]["result"]["description"], "first\n");
+    assert_eq!(responses[3]["result"]["description"], "second");
+    assert_eq!(responses[3]["result"]["empty"], true);
+
+    let log = responses[4]["result"].as_array().unwrap();
+    let descriptions: Vec<_> = log.iter().map(|commit| &commit["description"]).collect();
+    assert_eq!(descriptions, ["second", "first\n"]);
+    assert_eq!(log[0]["parents"], serde_json::json!([log[1]["commit_id"]]));
+    assert_eq!(log[1]["author"]["name"], "Test User");
+
+    assert_eq!(
+        responses[5]["result"],
+        serde_json::json!([{"path": "file", "status": "added", "conflict": false}])
+    );
+
+    let ops = responses[6]["result"].as_array().unwrap();
+    assert_eq!(ops.len(), 2);
+    assert_eq!(ops[0]["description"], "new empty commit");
+    assert_eq!(ops[0]["parents"], serde_json::json!([ops[1]["id"]]));
+
+    // The changes are visible to other commands
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-T",
+            r#""[" ++ description.first_line() ++ "]\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    [second]
+    [first]
+    []
+    ");
+}
+
+#[test]
+fn test_api_errors() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let requests = indoc! {r#"
+        not json
+        {"jsonrpc": "2.0", "id": 1, "method": "frobnicate"}
+        {"jsonrpc": "2.0", "id": 2, "method": "log", "params": {"revisions": "@"}}
+        {"jsonrpc": "2.0", "id": 3, "method": "describe", "params": {"revision": "root()", "message": ""}}
+        {"jsonrpc": "2.0", "method": "new"}
+        {"jsonrpc": "2.0", "id": 4, "method": "shutdown"}
+        {"jsonrpc": "2.0", "id": 5, "method": "version"}
+    "#};
+    let (stdout, _stderr) = test_env.jj_cmd_stdin_ok(&repo_path, &["api"], requests);
+    let responses = parse_responses(&stdout);
+    let errors: Vec<_> = responses
+        .iter()
+        .map(|response| (&response["id"], &response["error"]["code"]))
+        .collect();
+    insta::assert_debug_snapshot!(errors, @r"
+    [
+        (
+            Null,
+            Number(-32700),
+        ),
+        (
+            Number(1),
+            Number(-32601),
+        ),
+        (
+            Number(2),
+            Number(-32602),
+        ),
+        (
+            Number(3),
+            Number(-32000),
+        ),
+        (
+            Number(4),
+            Null,
+        ),
+    ]
+    ");
+    assert_eq!(
+        responses[3]["error"]["message"],
+        "The root commit 000000000000 is immutable"
+    );
+
+    // The notification was processed without a response
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-T",
+            r#""[" ++ description.first_line() ++ "]\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    []
+    []
+    []
+    ");
+}
+
+#[test]
+fn test_api_squash() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "second"]);
+    std::fs::write(repo_path.join("file"), "contents\n").unwrap();
+
+    let requests = indoc! {r#"
+        {"jsonrpc": "2.0", "id": 1, "method": "squash"}
+        {"jsonrpc": "2.0", "id": 2, "method": "squash", "params": {"message": "combined\n"}}
+    "#};
+    let (stdout, _stderr) = test_env.jj_cmd_stdin_ok(&repo_path, &["api"], requests);
+    let responses = parse_responses(&stdout);
+    assert_eq!(responses.len(), 2);
+    assert_eq!(
+        responses[0]["error"]["message"],
+        "Both commits have descriptions, so a message must be specified"
+    );
+    assert_eq!(responses[1]["result"]["description"], "combined\n");
+    assert_eq!(responses[1]["result"]["empty"], false);
+}
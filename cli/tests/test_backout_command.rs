@@ -188,6 +188,69 @@ fn test_backout_multiple() {
     "#);
 }
 
+#[test]
+fn test_backout_combine() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[], &[("a", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["a"], &[("a", "a\nb\n")]);
+    create_commit(
+        &test_env,
+        &repo_path,
+        "c",
+        &["b"],
+        &[("a", "a\nb\n"), ("b", "b\n")],
+    );
+    create_commit(&test_env, &repo_path, "d", &["c"], &[]);
+    create_commit(&test_env, &repo_path, "e", &["d"], &[("a", "a\nb\nc\n")]);
+
+    // Back out multiple commits in a single commit
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["backout", "-r", "b", "-r", "c", "-r", "e", "--combine"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r", "@+", "-T", "description"],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    Back out 3 commits
+
+    * "e"
+    * "c"
+    * "b"
+
+    This reverts commit 208f8612074af4c219d06568a8e1f04f2e80dc25.
+    This reverts commit 413337bbd11f7a6636c010d9e196acf801d8df2f.
+    This reverts commit 46cc97af6802301d8db381386e8485ff3ff24ae6.
+    "#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-s", "-r", "@+"]);
+    insta::assert_snapshot!(stdout, @r"
+    M a
+    D b
+    ");
+
+    // Backing out changes that later commits built on leaves conflicts
+    test_env.jj_cmd_ok(&repo_path, &["abandon", "@+"]);
+    test_env.jj_cmd_ok(&repo_path, &["backout", "-r", "b", "-r", "c", "--combine"]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "@+",
+            "-T",
+            r#"if(conflict, "conflict")"#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"conflict");
+}
+
 fn get_log_output(test_env: &TestEnvironment, cwd: &Path) -> String {
     let template = r#"commit_id.short() ++ " " ++ description"#;
     test_env.jj_cmd_success(cwd, &["log", "-T", template])
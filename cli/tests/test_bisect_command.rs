@@ -0,0 +1,149 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+
+use crate::common::TestEnvironment;
+
+/// Creates a linear history A-B-C-D-E, where "bug" is added in D.
+fn create_repo() -> (TestEnvironment, PathBuf) {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(r#"templates.commit_summary = 'description.first_line()'"#);
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "A"]);
+    for name in ["B", "C", "D", "E"] {
+        test_env.jj_cmd_ok(&repo_path, &["new", "-m", name]);
+        if name == "D" {
+            std::fs::write(repo_path.join("bug"), "").unwrap();
+        }
+    }
+    (test_env, repo_path)
+}
+
+#[test]
+fn test_bisect_manual() {
+    let (test_env, repo_path) = create_repo();
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["bisect", "start", "--bad", "@", "--good", "description(A)"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Now test commit C
+    3 commits left to test
+    ");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bisect", "good"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    Now test commit D
+    1 commits left to test
+    ");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bisect", "bad"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"The first bad commit is D");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bisect", "good"]);
+    insta::assert_snapshot!(stderr, @"Error: The bisection is finished, so there's no commit to mark");
+
+    // The working copy wasn't touched
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r", "@", "-T", "description"]);
+    insta::assert_snapshot!(stdout, @r"
+    @  E
+    │
+    ~
+    ");
+}
+
+#[test]
+fn test_bisect_mark_revisions() {
+    let (test_env, repo_path) = create_repo();
+    test_env.jj_cmd_ok(&repo_path, &["bisect", "start"]);
+
+    // Skipping commits leaves more than one candidate
+    test_env.jj_cmd_ok(&repo_path, &["bisect", "good", "description(B)"]);
+    test_env.jj_cmd_ok(&repo_path, &["bisect", "skip", "description(C)"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bisect", "bad", "description(D)"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r"
+    The first bad commit is one of these, some of which were skipped:
+      D
+      C
+    ");
+}
+
+#[test]
+fn test_bisect_reset() {
+    let (test_env, repo_path) = create_repo();
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bisect", "good"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: No bisection in progress
+    Hint: Use `jj bisect start` to start one.
+    ");
+
+    test_env.jj_cmd_ok(&repo_path, &["bisect", "start"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["bisect", "reset"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Ended the bisection.");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["bisect", "bad"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: No bisection in progress
+    Hint: Use `jj bisect start` to start one.
+    ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_bisect_run() {
+    let (test_env, repo_path) = create_repo();
+    test_env.jj_cmd_ok(&repo_path, &["bisect", "start", "--good", "description(A)"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["bisect", "run", "--", "sh", "-c", "test ! -e bug"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    assert!(stderr.ends_with("The first bad commit is D\n"), "{stderr}");
+
+    // The scratch workspace is gone, and the working copy wasn't touched
+    assert!(!repo_path.join(".jj").join("bisect").exists());
+    let stdout = test_env.jj_cmd_success(&repo_path, &["workspace", "list"]);
+    insta::assert_snapshot!(stdout, @"default: E");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r", "@", "-T", "description"]);
+    insta::assert_snapshot!(stdout, @r"
+    @  E
+    │
+    ~
+    ");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_bisect_run_aborted() {
+    let (test_env, repo_path) = create_repo();
+    test_env.jj_cmd_ok(&repo_path, &["bisect", "start"]);
+
+    let stderr =
+        test_env.jj_cmd_failure(&repo_path, &["bisect", "run", "--", "sh", "-c", "exit 200"]);
+    assert!(
+        stderr.ends_with("Error: External command exited with 200, stopping the bisection run\n"),
+        "{stderr}"
+    );
+    assert!(!repo_path.join(".jj").join("bisect").exists());
+}
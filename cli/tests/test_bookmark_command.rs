@@ -14,6 +14,8 @@
 
 use std::path::Path;
 
+use itertools::Itertools as _;
+
 use crate::common::TestEnvironment;
 
 #[test]
@@ -1410,6 +1412,137 @@ fn test_bookmark_list() {
     "###);
 }
 
+#[test]
+fn test_bookmark_status() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-bookmark = true");
+
+    // Initialize remote refs
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "remote"]);
+    let remote_path = test_env.env_root().join("remote");
+    for bookmark in [
+        "remote-ahead",
+        "remote-behind",
+        "remote-delete",
+        "remote-sync",
+        "remote-unsync",
+        "remote-untrack",
+    ] {
+        test_env.jj_cmd_ok(&remote_path, &["new", "root()", "-m", "base"]);
+        test_env.jj_cmd_ok(&remote_path, &["new", "-m", bookmark]);
+        test_env.jj_cmd_ok(&remote_path, &["bookmark", "create", bookmark]);
+    }
+    test_env.jj_cmd_ok(&remote_path, &["new"]);
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+
+    // Initialize local refs
+    let mut remote_git_path = remote_path;
+    remote_git_path.extend([".jj", "repo", "store", "git"]);
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", remote_git_path.to_str().unwrap(), "local"],
+    );
+    let local_path = test_env.env_root().join("local");
+    test_env.jj_cmd_ok(&local_path, &["new", "root()", "-m", "local-only"]);
+    test_env.jj_cmd_ok(&local_path, &["bookmark", "create", "local-only"]);
+
+    // Mutate refs in local repository
+    test_env.jj_cmd_ok(
+        &local_path,
+        &[
+            "bookmark",
+            "set",
+            "--allow-backwards",
+            "remote-ahead",
+            "-r=remote-ahead-",
+        ],
+    );
+    test_env.jj_cmd_ok(&local_path, &["new", "remote-behind", "-m", "local"]);
+    test_env.jj_cmd_ok(&local_path, &["bookmark", "set", "remote-behind"]);
+    test_env.jj_cmd_ok(&local_path, &["bookmark", "delete", "remote-delete"]);
+    test_env.jj_cmd_ok(
+        &local_path,
+        &[
+            "bookmark",
+            "set",
+            "--allow-backwards",
+            "remote-unsync",
+            "-r=local-only",
+        ],
+    );
+    test_env.jj_cmd_ok(
+        &local_path,
+        &["bookmark", "untrack", "remote-untrack@origin"],
+    );
+
+    // Local-only and non-tracking remote bookmarks aren't listed
+    let stdout = test_env.jj_cmd_success(&local_path, &["bookmark", "status"]);
+    insta::assert_snapshot!(stdout, @r"
+    remote-ahead@origin: ahead (ahead by 1 commits)
+    remote-behind@origin: behind (behind by 1 commits)
+    remote-delete@origin: deleted
+    remote-sync@origin: in-sync
+    remote-unsync@origin: diverged (ahead by 2 commits, behind by 1 commits)
+    ");
+
+    // Filtered by name and remote
+    let stdout = test_env.jj_cmd_success(
+        &local_path,
+        &[
+            "bookmark",
+            "status",
+            "remote-behind",
+            "remote-sync",
+            "--remote=origin",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    remote-behind@origin: behind (behind by 1 commits)
+    remote-sync@origin: in-sync
+    ");
+    let stdout = test_env.jj_cmd_success(&local_path, &["bookmark", "status", "--remote=upstream"]);
+    insta::assert_snapshot!(stdout, @"");
+
+    // Custom template
+    let stdout = test_env.jj_cmd_success(
+        &local_path,
+        &[
+            "bookmark",
+            "status",
+            "remote-unsync",
+            "-T",
+            r#"tracking_status ++ " " ++ tracking_ahead_count.exact() ++ "\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"diverged 2");
+
+    // JSON
+    let stdout = test_env.jj_cmd_success(&local_path, &["bookmark", "status", "--json"]);
+    let entries: Vec<serde_json::Value> = serde_json::from_str(&stdout).unwrap();
+    let summary = entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}@{} {} {} {} {} {}",
+                entry["name"].as_str().unwrap(),
+                entry["remote"].as_str().unwrap(),
+                entry["status"].as_str().unwrap(),
+                entry["ahead"],
+                entry["behind"],
+                entry["local_targets"].as_array().unwrap().len(),
+                entry["remote_targets"].as_array().unwrap().len(),
+            )
+        })
+        .join("\n");
+    insta::assert_snapshot!(summary, @r"
+    remote-ahead@origin ahead 1 0 1 1
+    remote-behind@origin behind 0 1 1 1
+    remote-delete@origin deleted null null 0 1
+    remote-sync@origin in-sync 0 0 1 1
+    remote-unsync@origin diverged 2 1 1 1
+    ");
+}
+
 #[test]
 fn test_bookmark_list_filtered() {
     let test_env = TestEnvironment::default();
@@ -1610,22 +1743,19 @@ fn test_bookmark_list_filtered() {
     "###);
     insta::assert_snapshot!(stderr, @"");
 
-    // Name pattern and revset are OR-ed.
+    // Name pattern and revset are AND-ed.
     let (stdout, stderr) = query(&["local-keep", "-rbookmarks(remote-rewrite)"]);
-    insta::assert_snapshot!(stdout, @r###"
-    local-keep: kpqxywon c7b4c09c (empty) local-keep
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"");
+    let (stdout, stderr) = query(&["glob:remote-*", "-r~::remote-keep"]);
+    insta::assert_snapshot!(stdout, @r#"
     remote-rewrite: xyxluytn e31634b6 (empty) rewritten
       @origin (ahead by 1 commits, behind by 1 commits): xyxluytn hidden 3e9a5af6 (empty) remote-rewrite
-    "###);
+    "#);
     insta::assert_snapshot!(stderr, @"");
 
-    // … but still filtered by --remote
-    let (stdout, stderr) = query(&[
-        "local-keep",
-        "-rbookmarks(remote-rewrite)",
-        "--remote",
-        "git",
-    ]);
+    // … and still filtered by --remote
+    let (stdout, stderr) = query(&["glob:*", "-r~::remote-keep", "--remote", "git"]);
     insta::assert_snapshot!(stdout, @r#"
     local-keep: kpqxywon c7b4c09c (empty) local-keep
       @git: kpqxywon c7b4c09c (empty) local-keep
@@ -1633,6 +1763,14 @@ fn test_bookmark_list_filtered() {
       @git: xyxluytn e31634b6 (empty) rewritten
     "#);
     insta::assert_snapshot!(stderr, @"");
+
+    // … and by --tracked
+    let (stdout, stderr) = query(&["-r~::remote-keep", "--tracked"]);
+    insta::assert_snapshot!(stdout, @r#"
+    remote-rewrite: xyxluytn e31634b6 (empty) rewritten
+      @origin (ahead by 1 commits, behind by 1 commits): xyxluytn hidden 3e9a5af6 (empty) remote-rewrite
+    "#);
+    insta::assert_snapshot!(stderr, @"");
 }
 
 #[test]
@@ -51,6 +51,17 @@ fn test_log_parents() {
     ~
     "###);
 
+    let template = r#"parents.filter(|c| !c.root()).map(|c| c.commit_id().short())"#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", template]);
+    insta::assert_snapshot!(stdout, @r###"
+    @    4db490c88528 230dd059e1b0
+    ├─╮
+    ○ │  230dd059e1b0
+    ├─╯
+    ○
+    ◆
+    "###);
+
     // Commit object isn't printable
     let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "-T", "parents"]);
     insta::assert_snapshot!(stderr, @r###"
@@ -612,6 +623,20 @@ fn test_log_bookmarks() {
     ◆
     "#);
 
+    let template = r#"bookmarks.filter(|b| !b.remote())"#;
+    let output = test_env.jj_cmd_success(&workspace_root, &["log", "-T", template]);
+    insta::assert_snapshot!(output, @r#"
+    @  bookmark2* new-bookmark
+    ○  unchanged
+    │ ○  bookmark3??
+    ├─╯
+    │ ○  bookmark3??
+    ├─╯
+    │ ○  bookmark1*
+    ├─╯
+    ◆
+    "#);
+
     let template = r#"separate(" ", "L:", local_bookmarks, "R:", remote_bookmarks)"#;
     let output = test_env.jj_cmd_success(&workspace_root, &["log", "-T", template]);
     insta::assert_snapshot!(output, @r#"
@@ -947,6 +972,73 @@ fn test_log_contained_in() {
     "###);
 }
 
+#[test]
+fn test_log_contained_in_any() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mA", "root()"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mB"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "main"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mC"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-mD", "root()"]);
+
+    // The same revset can be shared with contained_in()
+    let template = r#"
+    separate(" ",
+      description.first_line(),
+      bookmarks,
+      if(self.contained_in_any("description(A)", "visible_heads()"), "[any]"),
+      if(self.contained_in("description(A)"), "[A]"),
+    ) ++ "\n"
+    "#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r::", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    @  D [any]
+    │ ○  C [any]
+    │ ○  B main
+    │ ○  A [any] [A]
+    ├─╯
+    ◆
+    ");
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["log", "-r::", "-T", r#"self.contained_in_any()"#],
+    );
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Failed to parse template: Function "contained_in_any": Expected at least 1 arguments
+    Caused by:  --> 1:23
+      |
+    1 | self.contained_in_any()
+      |                       ^
+      |
+      = Function "contained_in_any": Expected at least 1 arguments
+    "#);
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "log",
+            "-r::",
+            "-T",
+            r#"self.contained_in_any("main", "maine")"#,
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Failed to parse template: Failed to evaluate revset
+    Caused by:
+    1:  --> 1:31
+      |
+    1 | self.contained_in_any("main", "maine")
+      |                               ^-----^
+      |
+      = Failed to evaluate revset
+    2: Revision "maine" doesn't exist
+    Hint: Did you mean "main"?
+    "#);
+}
+
 #[test]
 fn test_short_prefix_in_transaction() {
     let test_env = TestEnvironment::default();
@@ -1208,6 +1300,23 @@ fn test_log_diff_predefined_formats() {
      b
     +c
     "###);
+
+    // files(), line counts, and shortstat()
+    let template = r#"
+    concat(
+      diff.files().join(",") ++ "\n",
+      diff.lines_added() ++ " " ++ diff.lines_removed() ++ "\n",
+      diff.shortstat(),
+      self.diff("file2").shortstat(),
+    )
+    "#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-T", template]);
+    insta::assert_snapshot!(stdout, @r###"
+    file1,file2,rename-target
+    3 1
+    3 files changed, 3 insertions(+), 1 deletion(-)
+    1 file changed, 2 insertions(+), 1 deletion(-)
+    "###);
 }
 
 #[test]
@@ -1233,3 +1342,85 @@ fn test_signature_templates() {
     let stdout = test_env.jj_cmd_success(&repo_path, &["show", "-T", template]);
     insta::assert_snapshot!(stdout, @"good test-display signature");
 }
+
+#[test]
+fn test_repo_functions() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "origin"]);
+    let origin_path = test_env.env_root().join("origin");
+    let origin_git_repo_path = origin_path
+        .join(".jj")
+        .join("repo")
+        .join("store")
+        .join("git");
+    test_env.jj_cmd_ok(&origin_path, &["bookmark", "create", "main"]);
+    test_env.jj_cmd_ok(&origin_path, &["git", "export"]);
+
+    // No remotes in a freshly initialized repo
+    let output = test_env.jj_cmd_success(
+        &origin_path,
+        &["log", "--no-graph", "-r@", "-T", "remote_names()"],
+    );
+    insta::assert_snapshot!(output, @"");
+
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &[
+            "git",
+            "clone",
+            origin_git_repo_path.to_str().unwrap(),
+            "local",
+        ],
+    );
+    let workspace_root = test_env.env_root().join("local");
+    let render = |template| {
+        test_env.jj_cmd_success(
+            &workspace_root,
+            &[
+                "log",
+                "--no-graph",
+                "--ignore-working-copy",
+                "-r@",
+                "-T",
+                template,
+            ],
+        )
+    };
+
+    insta::assert_snapshot!(render("workspace_name()"), @"default");
+    insta::assert_snapshot!(render("remote_names()"), @"origin");
+    assert_eq!(render("workspace_root()"), workspace_root.to_str().unwrap());
+    let repo_path = workspace_root.join(".jj").join("repo");
+    assert_eq!(render("repo_path()"), repo_path.to_str().unwrap());
+
+    let op_id = test_env.jj_cmd_success(
+        &workspace_root,
+        &["op", "log", "--no-graph", "--limit=1", "-T", "id"],
+    );
+    assert_eq!(render("current_operation()"), op_id);
+
+    // Secondary workspaces report their own name and root, but share the repo
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["workspace", "add", "--name=second", "../second"],
+    );
+    let second_root = test_env.env_root().join("second");
+    let output = test_env.jj_cmd_success(
+        &second_root,
+        &[
+            "log",
+            "--no-graph",
+            "-r@",
+            "-T",
+            r#"workspace_name() ++ " " ++ workspace_root() ++ " " ++ repo_path()"#,
+        ],
+    );
+    assert_eq!(
+        output,
+        format!(
+            "second {} {}",
+            second_root.to_str().unwrap(),
+            repo_path.to_str().unwrap()
+        )
+    );
+}
@@ -77,6 +77,7 @@ fn test_bookmark_names() {
     --repository	Path to repository to operate on
     --ignore-working-copy	Don't snapshot the working copy, and don't update it
     --ignore-immutable	Allow rewriting immutable commits
+    --allow-protected-paths	Allow commits to modify protected paths
     --at-operation	Operation to load the repo at
     --debug	Enable debug logging
     --color	When to colorize output (always, never, debug, auto)
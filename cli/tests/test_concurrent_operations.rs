@@ -30,8 +30,45 @@ fn test_concurrent_operation_divergence() {
         &["describe", "-m", "message 2", "--at-op", "@-"],
     );
 
-    // "--at-op=@" disables op heads merging, and prints head operation ids.
-    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "log", "--at-op=@"]);
+    // "--at-op=@" disables op heads merging, and shows all head operations.
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["op", "log", "--at-op=@"]);
+    insta::assert_snapshot!(stdout, @r#"
+    ○  d74dff64472e test-username@host.example.com 2001-02-03 04:05:09.000 +07:00 - 2001-02-03 04:05:09.000 +07:00
+    │  describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    │  args: jj describe -m 'message 2' --at-op @-
+    │ ○  0162305507cc test-username@host.example.com 2001-02-03 04:05:08.000 +07:00 - 2001-02-03 04:05:08.000 +07:00
+    ├─╯  describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    │    args: jj describe -m 'message 1'
+    ○  eac759b9ab75 test-username@host.example.com 2001-02-03 04:05:07.000 +07:00 - 2001-02-03 04:05:07.000 +07:00
+    │  add workspace 'default'
+    ○  000000000000 root()
+    "#);
+    insta::assert_snapshot!(stderr, @"");
+
+    // "--op-heads" lists the head operations without merging them
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "log",
+            "--op-heads",
+            "--no-graph",
+            "-Tid.short() ++ '\n'",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    d74dff64472e
+    0162305507cc
+    ");
+
+    // "op show" and "op diff" show each head operation
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "show", "--ignore-working-copy"]);
+    assert!(stdout.contains("args: jj describe -m 'message 1'"));
+    assert!(stdout.contains("args: jj describe -m 'message 2' --at-op @-"));
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "diff", "--at-op=@"]);
+    assert!(stdout.contains("To operation: 0162305507cc"));
+    assert!(stdout.contains("To operation: d74dff64472e"));
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "diff", "--at-op=@", "--from=@-"]);
     insta::assert_snapshot!(stderr, @r#"
     Error: The "@" expression resolved to more than one operation
     Hint: Try specifying one of the operations by ID: 0162305507cc, d74dff64472e
@@ -161,6 +161,67 @@ fn test_config_list_all() {
     "###);
 }
 
+#[test]
+fn test_config_list_json() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+    [test-table]
+    x = true
+    y = [1, "b", 3.4, { z = "c" }]
+    "#,
+    );
+
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &[
+            "config",
+            "list",
+            "--json",
+            "--include-overridden",
+            "--config=test-table.x=42",
+            "test-table",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    [
+      {
+        "name": "test-table.x",
+        "value": true,
+        "source": "user",
+        "path": "$TEST_ENV/config/config0001.toml",
+        "overridden": true
+      },
+      {
+        "name": "test-table.y",
+        "value": [
+          1,
+          "b",
+          3.4,
+          {
+            "z": "c"
+          }
+        ],
+        "source": "user",
+        "path": "$TEST_ENV/config/config0001.toml",
+        "overridden": false
+      },
+      {
+        "name": "test-table.x",
+        "value": 42,
+        "source": "command-arg",
+        "path": null,
+        "overridden": false
+      }
+    ]
+    "#);
+
+    // Template and JSON output can't be combined
+    let stderr =
+        test_env.jj_cmd_cli_error(test_env.env_root(), &["config", "list", "--json", "-Tname"]);
+    assert!(stderr.contains("cannot be used with"), "{stderr}");
+}
+
 #[test]
 fn test_config_list_multiline_string() {
     let test_env = TestEnvironment::default();
@@ -255,6 +316,71 @@ fn test_config_list_layer() {
     "###);
 }
 
+#[test]
+fn test_config_diff() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    // Test with fresh new config file
+    let user_config_path = test_env.config_path().join("config.toml");
+    test_env.set_config_path(&user_config_path);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(
+        &user_config_path,
+        indoc! {r#"
+            test-key = "test-val"
+            test-layered-key = "test-original-val"
+            test-same-key = "test-same-val"
+            test-table.foo = 1
+        "#},
+    )
+    .unwrap();
+    std::fs::write(
+        repo_path.join(".jj/repo/config.toml"),
+        indoc! {r#"
+            test-layered-key = "test-layered-val"
+            test-repo-key = "test-repo-val"
+            test-same-key = "test-same-val"
+            test-table = "scalar"
+        "#},
+    )
+    .unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["config", "diff", "user", "repo"]);
+    insta::assert_snapshot!(stdout, @r#"
+    overridden: test-layered-key = "test-original-val" -> "test-layered-val"
+    added: test-repo-key = "test-repo-val"
+    added: test-table = "scalar"
+    shadowed: test-table.foo = 1
+    "#);
+
+    // Filtered by name
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["config", "diff", "user", "repo", "test-layered-key"],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    overridden: test-layered-key = "test-original-val" -> "test-layered-val"
+    "#);
+
+    // Resolved config against the built-in defaults
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["config", "diff", "default", "resolved", "user"],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    overridden: user.email = "" -> "test.user@example.com"
+    overridden: user.name = "" -> "Test User"
+    "#);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["config", "diff", "user", "repo", "test-same-key"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"No differences found");
+}
+
 #[test]
 fn test_config_layer_override_default() {
     let test_env = TestEnvironment::default();
@@ -419,6 +545,39 @@ fn test_config_layer_override_env() {
     "###);
 }
 
+#[test]
+fn test_config_layer_override_env_config_name() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(r#"ui.diff-editor = "user""#);
+
+    test_env.add_env_var("JJ_CONFIG_UI__DIFF_EDITOR", "env-override");
+    test_env.add_env_var("JJ_CONFIG_TEST__INT", "42");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["config", "list", "--include-overridden", "ui.diff-editor"],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    # ui.diff-editor = "user"
+    ui.diff-editor = "env-override"
+    "#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["config", "list", "test"]);
+    insta::assert_snapshot!(stdout, @"test.int = 42");
+
+    // Command argument takes precedence
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "config",
+            "get",
+            "ui.diff-editor",
+            "--config=ui.diff-editor=arg",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"arg");
+}
+
 #[test]
 fn test_config_layer_workspace() {
     let test_env = TestEnvironment::default();
@@ -498,6 +657,56 @@ fn test_config_set_bad_opts() {
     ");
 }
 
+#[test]
+fn test_config_set_schema_validation() {
+    let mut test_env = TestEnvironment::default();
+    // Test with fresh new config file
+    let user_config_path = test_env.config_path().join("config.toml");
+    test_env.set_config_path(&user_config_path);
+
+    // Value of unexpected type is rejected
+    let stderr = test_env.jj_cmd_failure(
+        test_env.env_root(),
+        &["config", "set", "--user", "ui.color", "tru"],
+    );
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Invalid value for ui.color: expected one of "always", "never", "debug", "auto"
+    "#);
+    let stderr = test_env.jj_cmd_failure(
+        test_env.env_root(),
+        &["config", "set", "--user", "ui.log-default-limit", "ten"],
+    );
+    insta::assert_snapshot!(stderr, @"Error: Invalid value for ui.log-default-limit: expected integer >= 0");
+    assert!(!user_config_path.exists());
+
+    // Unknown name within a known table is written with warning
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["config", "set", "--user", "ui.colour", "never"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Warning: Unknown config key ui.colour
+    Hint: Did you mean "ui.color"?
+    "#);
+
+    // Unknown top-level names are allowed
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["config", "set", "--user", "test-table.foo", "true"],
+    );
+    insta::assert_snapshot!(stderr, @"");
+
+    let user_config_toml = std::fs::read_to_string(&user_config_path).unwrap();
+    insta::assert_snapshot!(user_config_toml, @r#"
+    [ui]
+    colour = "never"
+
+    [test-table]
+    foo = true
+    "#);
+}
+
 #[test]
 fn test_config_set_for_user() {
     let mut test_env = TestEnvironment::default();
@@ -846,6 +1055,43 @@ fn test_config_edit_user_new_file() {
     );
 }
 
+#[test]
+fn test_config_edit_schema_validation() {
+    let mut test_env = TestEnvironment::default();
+    let user_config_path = test_env.config_path().join("config").join("file.toml");
+    let edit_script = test_env.set_up_fake_editor(); // added configuration is ignored
+    test_env.add_env_var("EDITOR", fake_editor_path());
+    test_env.set_config_path(&user_config_path);
+
+    std::fs::write(
+        &edit_script,
+        indoc! {"
+            write
+            test-key = 'ok'
+            ui.colour = 'never'
+
+            [[--scope]]
+            ui.color = 'tru'
+        "},
+    )
+    .unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(test_env.env_root(), &["config", "edit", "--user"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Warning: Unknown config key ui.colour
+    Hint: Did you mean "ui.color"?
+    Warning: Invalid value for ui.color: expected one of "always", "never", "debug", "auto"
+    "#);
+
+    // Syntax error is reported, but the edited file is kept
+    std::fs::write(&edit_script, "write\n[ui").unwrap();
+    let stderr = test_env.jj_cmd_failure(test_env.env_root(), &["config", "edit", "--user"]);
+    insta::assert_snapshot!(
+        stderr.lines().next().unwrap(),
+        @"Config error: Configuration cannot be parsed as TOML document");
+    assert_eq!(std::fs::read_to_string(&user_config_path).unwrap(), "[ui");
+}
+
 #[test]
 fn test_config_edit_repo() {
     let mut test_env = TestEnvironment::default();
@@ -1166,21 +1412,92 @@ fn test_config_conditional_without_home_dir() {
 }
 
 #[test]
-fn test_config_show_paths() {
-    let test_env = TestEnvironment::default();
-    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
-    let repo_path = test_env.env_root().join("repo");
-
+fn test_config_conditional_remotes() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo1"]);
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo2"]);
+    let repo1_path = test_env.env_root().join("repo1");
+    let repo2_path = test_env.env_root().join("repo2");
     test_env.jj_cmd_ok(
-        &repo_path,
-        &["config", "set", "--user", "ui.paginate", ":builtin"],
+        &repo1_path,
+        &[
+            "git",
+            "remote",
+            "add",
+            "origin",
+            "https://example.org/work/repo1",
+        ],
+    );
+    test_env.jj_cmd_ok(
+        &repo2_path,
+        &[
+            "git",
+            "remote",
+            "add",
+            "origin",
+            "https://example.com/oss/repo2",
+        ],
+    );
+    let user_config_path = test_env.env_root().join("config.toml");
+    test_env.set_config_path(&user_config_path);
+    std::fs::write(
+        &user_config_path,
+        indoc! {"
+            foo = 'global'
+            [[--scope]]
+            --when.remotes = ['*example.org/work/*']
+            foo = 'work'
+        "},
+    )
+    .unwrap();
+
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["config", "get", "foo"]);
+    insta::assert_snapshot!(stdout, @"global");
+    let stdout = test_env.jj_cmd_success(&repo1_path, &["config", "get", "foo"]);
+    insta::assert_snapshot!(stdout, @"work");
+    let stdout = test_env.jj_cmd_success(&repo2_path, &["config", "get", "foo"]);
+    insta::assert_snapshot!(stdout, @"global");
+}
+
+#[test]
+fn test_config_env_interpolation() {
+    let mut test_env = TestEnvironment::default();
+    test_env.add_env_var("JJ_TEST_DIR", "/path/to");
+    test_env.add_config(indoc! {r#"
+        test-table.defined = "${JJ_TEST_DIR}/file"
+        test-table.escaped = "$${JJ_TEST_DIR}"
+        test-table.undefined = "${JJ_TEST_UNDEFINED}"
+    "#});
+
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["config", "list", "test-table"]);
+    insta::assert_snapshot!(stdout, @r#"
+    test-table.defined = "/path/to/file"
+    test-table.escaped = "${JJ_TEST_DIR}"
+    test-table.undefined = "${JJ_TEST_UNDEFINED}"
+    "#);
+
+    let stderr = test_env.jj_cmd_failure(
+        test_env.env_root(),
+        &["config", "list", "--config=config.env-interpolation=strict"],
     );
+    insta::assert_snapshot!(stderr, @r"
+    Config error: Invalid type or value for test-table.undefined
+    Caused by: Environment variable JJ_TEST_UNDEFINED is not set
+    Hint: Check the config file: $TEST_ENV/config/config0002.toml
+    For help, see https://jj-vcs.github.io/jj/latest/config/.
+    ");
+}
+
+#[test]
+fn test_config_show_paths() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(r#"ui.paginate = ":builtin""#);
     let stderr = test_env.jj_cmd_failure(test_env.env_root(), &["st"]);
     insta::assert_snapshot!(stderr, @r"
     Config error: Invalid type or value for ui.paginate
     Caused by: unknown variant `:builtin`, expected `never` or `auto`
 
-    Hint: Check the config file: $TEST_ENV/config/config0001.toml
+    Hint: Check the config file: $TEST_ENV/config/config0002.toml
     For help, see https://jj-vcs.github.io/jj/latest/config/.
     ");
 }
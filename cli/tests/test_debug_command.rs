@@ -99,10 +99,115 @@ fn test_debug_index() {
       Level 0:
         Number of commits: 2
         Name: [hash]
+        Size: [bytes]
     "###
     );
 }
 
+#[test]
+fn test_debug_index_compact() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+
+    // The default policy keeps the small segment on top of the large one
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["debug", "index", "compact"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Compacted commit index from 2 to 2 segments.
+    "###);
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_path,
+        &[
+            "debug",
+            "index",
+            "compact",
+            "--config=index.compaction.max-segments=1",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Compacted commit index from 2 to 1 segments.
+    "###);
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "index"]);
+    assert_snapshot!(filter_index_stats(&stdout), @r###"
+    Number of commits: 4
+    Number of merges: 0
+    Max generation number: 3
+    Number of heads: 1
+    Number of changes: 4
+    Stats per level:
+      Level 0:
+        Number of commits: 4
+        Name: [hash]
+        Size: [bytes]
+    "###
+    );
+
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&workspace_path, &["debug", "index", "compact", "--full"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Compacted commit index from 2 to 1 segments.
+    "###);
+
+    // Small segments are squashed when new segments are written
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+    test_env.jj_cmd_ok(
+        &workspace_path,
+        &["new", "--config=index.compaction.min-segment-commits=100"],
+    );
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "index"]);
+    assert_snapshot!(filter_index_stats(&stdout), @r###"
+    Number of commits: 7
+    Number of merges: 0
+    Max generation number: 6
+    Number of heads: 1
+    Number of changes: 7
+    Stats per level:
+      Level 0:
+        Number of commits: 7
+        Name: [hash]
+        Size: [bytes]
+    "###
+    );
+}
+
+#[test]
+fn test_debug_index_stats_and_verify() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &["debug", "index", "stats", "--time-lookups"],
+    );
+    assert_snapshot!(filter_index_stats(&stdout), @r###"
+    Number of commits: 3
+    Number of merges: 0
+    Max generation number: 2
+    Number of heads: 1
+    Number of changes: 3
+    Stats per level:
+      Level 0:
+        Number of commits: 3
+        Name: [hash]
+        Size: [bytes]
+    Commit id lookups: 3 in [time]
+    Shortest prefix lookups: 3 in [time]
+    "###
+    );
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["debug", "index", "verify"]);
+    assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Commits checked: 3
+    "###);
+}
+
 #[test]
 fn test_debug_reindex() {
     let test_env = TestEnvironment::default();
@@ -121,9 +226,11 @@ fn test_debug_reindex() {
       Level 0:
         Number of commits: 3
         Name: [hash]
+        Size: [bytes]
       Level 1:
         Number of commits: 1
         Name: [hash]
+        Size: [bytes]
     "###
     );
     let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["debug", "reindex"]);
@@ -142,10 +249,62 @@ fn test_debug_reindex() {
       Level 0:
         Number of commits: 4
         Name: [hash]
+        Size: [bytes]
     "###
     );
 }
 
+#[test]
+fn test_debug_warm_cache() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+    let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_path, &["debug", "warm-cache"]);
+    assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Indexed 3 commits.
+    Loaded 3 commits into caches.
+    "###);
+}
+
+#[test]
+fn test_debug_store_stats() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "git-repo"]);
+    let stderr = test_env.jj_cmd_failure(
+        &test_env.env_root().join("git-repo"),
+        &["debug", "store", "stats"],
+    );
+    assert_snapshot!(stderr, @r###"
+    Error: This command requires a repo backed by the native backend
+    "###);
+
+    test_env.add_config("ui.allow-init-native = true");
+    test_env.add_config(r#"snapshot.max-new-file-size = "10MiB""#);
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    std::fs::write(
+        workspace_path.join("large"),
+        "0123456789abcdef".repeat(256 * 1024),
+    )
+    .unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["new"]);
+    let stdout = test_env.jj_cmd_success(&workspace_path, &["debug", "store", "stats"]);
+    assert_snapshot!(filter_store_stats(&stdout), @r###"
+    Loose objects: [count]
+    Loose objects size: [bytes]
+    Packs: 0
+    Packed objects: 0
+    Packed objects size: 0
+    Chunked files: 1
+    Chunked files size: [bytes]
+    Chunks: [count]
+    Chunks size on disk: [bytes]
+    Saved by chunking: [bytes]
+    "###);
+}
+
 #[test]
 fn test_debug_tree() {
     let test_env = TestEnvironment::default();
@@ -240,7 +399,58 @@ fn test_debug_operation_id() {
     );
 }
 
+#[test]
+fn test_debug_repro_bundle() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    std::fs::write(workspace_path.join("secret-file"), "secret contents").unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["commit", "-m", "secret description"]);
+    test_env.jj_cmd_ok(
+        &workspace_path,
+        &["bookmark", "create", "-r@-", "secret-bookmark"],
+    );
+    test_env.jj_cmd_ok(&workspace_path, &["new", "root()"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_path,
+        &["debug", "repro-bundle", "../bundle.json", "--include-trees"],
+    );
+    assert_snapshot!(stdout, @"");
+    assert_snapshot!(stderr, @"Wrote bundle of 6 operations and 5 commits to ../bundle.json");
+    let bundle = std::fs::read_to_string(test_env.env_root().join("bundle.json")).unwrap();
+    assert!(!bundle.contains("secret"), "{bundle}");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["debug", "repro-load", "bundle.json", "loaded"],
+    );
+    assert_snapshot!(stdout, @"");
+    assert_snapshot!(stderr, @r#"Loaded bundle of 6 operations and 5 commits into "loaded""#);
+
+    // The loaded repo has the same change graph and operation log
+    let loaded_path = test_env.env_root().join("loaded");
+    let template = r#"change_id.short() ++ " " ++ bookmarks.len() ++ "\n""#;
+    let log = |path| test_env.jj_cmd_success(path, &["log", "-r", "all()", "-T", template]);
+    assert_eq!(log(&loaded_path), log(&workspace_path));
+    let op_log =
+        |path| test_env.jj_cmd_success(path, &["op", "log", "--no-graph", "-T", r#""x\n""#]);
+    assert_eq!(op_log(&loaded_path).lines().count(), 6);
+}
+
 fn filter_index_stats(text: &str) -> String {
     let regex = Regex::new(r"    Name: [0-9a-z]+").unwrap();
-    regex.replace_all(text, "    Name: [hash]").to_string()
+    let text = regex.replace_all(text, "    Name: [hash]");
+    let size_regex = Regex::new(r"    Size: [1-9]\d*").unwrap();
+    let text = size_regex.replace_all(&text, "    Size: [bytes]");
+    let time_regex = Regex::new(r"(lookups: \d+) in .*").unwrap();
+    time_regex.replace_all(&text, "$1 in [time]").to_string()
+}
+
+fn filter_store_stats(text: &str) -> String {
+    let count_regex = Regex::new(r"(?m)^(Loose objects|Chunks): \d+$").unwrap();
+    let text = count_regex.replace_all(text, "$1: [count]");
+    let bytes_regex =
+        Regex::new(r"(?m)^(.* size( on disk)?|Saved by chunking): [1-9]\d*$").unwrap();
+    bytes_regex.replace_all(&text, "$1: [bytes]").to_string()
 }
@@ -763,6 +763,57 @@ fn test_describe_author() {
     "#);
 }
 
+#[test]
+fn test_describe_trailers() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(r#"templates.commit_trailers = '"Change-Id: " ++ change_id.short(12)'"#);
+    let get_description =
+        || test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-Tdescription"]);
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "subject", "--signoff"]);
+    insta::assert_snapshot!(get_description(), @r"
+    subject
+
+    Change-Id: qpvuntsmwlqt
+    Signed-off-by: Test User <test.user@example.com>
+    ");
+
+    // Trailers already present aren't added again
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["describe", "--no-edit", "--signoff"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+
+    // New trailers are appended to the existing trailer block
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "describe",
+            "-m",
+            "subject\n\nReviewed-by: Someone Else <someone@example.com>",
+        ],
+    );
+    insta::assert_snapshot!(get_description(), @r"
+    subject
+
+    Reviewed-by: Someone Else <someone@example.com>
+    Change-Id: qpvuntsmwlqt
+    ");
+
+    // Empty descriptions are left empty
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "", "--signoff"]);
+    insta::assert_snapshot!(get_description(), @"");
+
+    // The template output must consist of trailers
+    test_env.add_config(r#"templates.commit_trailers = '"not a trailer"'"#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["describe", "-m", "subject"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Invalid output of `templates.commit_trailers`
+    Caused by: Invalid trailer line: not a trailer
+    ");
+}
+
 #[test]
 fn test_describe_avoids_unc() {
     let mut test_env = TestEnvironment::default();
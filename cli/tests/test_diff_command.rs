@@ -223,6 +223,43 @@ fn test_diff_basic() {
     insta::assert_snapshot!(stderr, @"");
 }
 
+#[test]
+fn test_diff_from_op_to_op() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["debug", "snapshot"]);
+    std::fs::write(repo_path.join("file1"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["debug", "snapshot"]);
+
+    // The working-copy commit at the previous operation
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--from-op", "@-", "--git"]);
+    insta::assert_snapshot!(stdout, @r"
+    diff --git a/file1 b/file1
+    index 7898192261..6178079822 100644
+    --- a/file1
+    +++ b/file1
+    @@ -1,1 +1,1 @@
+    -a
+    +b
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--to-op", "@-", "--summary"]);
+    insta::assert_snapshot!(stdout, @"M file1");
+
+    // Revisions are resolved at the operation
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["diff", "--from-op", "@--", "--to-op", "@-", "--summary"],
+    );
+    insta::assert_snapshot!(stdout, @"A file1");
+
+    // Conflicts with -r
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["diff", "--from-op", "@-", "-r", "@"]);
+    assert!(stderr.contains("cannot be used with"), "{stderr}");
+}
+
 #[test]
 fn test_diff_empty() {
     let test_env = TestEnvironment::default();
@@ -2638,3 +2675,65 @@ fn test_diff_binary() {
     4 files changed, 6 insertions(+), 6 deletions(-)
     "###);
 }
+
+#[test]
+fn test_diff_over_memory_budget() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("small"), "a\n").unwrap();
+    std::fs::write(repo_path.join("large"), "line\n".repeat(100)).unwrap();
+
+    // Files larger than a quarter of the budget are shown as binary.
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--config=core.memory-budget=1KiB"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Added regular file large:
+        (binary)
+    Added regular file small:
+            1: a
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["diff", "--git", "--config=core.memory-budget=1KiB"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    diff --git a/large b/large
+    new file mode 100644
+    index 0000000000..b763d3cac0
+    Binary files /dev/null and b/large differ
+    diff --git a/small b/small
+    new file mode 100644
+    index 0000000000..7898192261
+    --- /dev/null
+    +++ b/small
+    @@ -0,0 +1,1 @@
+    +a
+    "###);
+
+    // The budget also applies to diffs rendered by templates.
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "-r@",
+            "--no-graph",
+            "-Tself.diff().color_words()",
+            "--config=core.memory-budget=1KiB",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    Added regular file large:
+        (binary)
+    Added regular file small:
+            1: a
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["diff", "--config=core.memory-budget=lots"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: Invalid type or value for core.memory-budget
+    Caused by: must start with a number
+    For help, see https://jj-vcs.github.io/jj/latest/config/.
+    "###);
+}
@@ -2398,6 +2398,96 @@ fn test_rebase_duplicates() {
     "#);
 }
 
+#[test]
+fn test_duplicate_record_origin() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("duplicate.record-origin = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    create_commit(&test_env, &repo_path, "a", &[]);
+    let a_id = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-ra", "-Tcommit_id"]);
+
+    test_env.jj_cmd_ok(&repo_path, &["duplicate", "a"]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "all() ~ root() ~ a",
+            "-T",
+            "description",
+        ],
+    );
+    assert_eq!(stdout, format!("a\n\nDuplicated-from: {a_id}\n"));
+
+    // Duplicating a duplicate keeps the earlier trailer, and the last one wins
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["bookmark", "create", "b", "-r", "all() ~ root() ~ a"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["duplicate", "b"]);
+    let template = r#"
+    separate(" ",
+      description.first_line(),
+      bookmarks,
+      if(duplicated_from(), "from " ++ duplicated_from().bookmarks()),
+    ) ++ "\n"
+    "#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r", "all() ~ root()", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    a from b
+    a b from a
+    a a
+    ");
+
+    // Empty descriptions get the trailer after an empty subject line
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()"]);
+    let empty_id =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-Tcommit_id"]);
+    test_env.jj_cmd_ok(&repo_path, &["duplicate"]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            r"description(regex:'^\n')",
+            "-T",
+            r#"if(duplicated_from(), duplicated_from().commit_id()) ++ "|" ++ description"#,
+        ],
+    );
+    assert_eq!(
+        stdout,
+        format!("{empty_id}|\n\nDuplicated-from: {empty_id}\n")
+    );
+
+    // A trailer pointing at a commit that doesn't exist is ignored
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "describe",
+            "-r@",
+            "-m",
+            "c\n\nDuplicated-from: 0123456789abcdef0123456789abcdef01234567",
+        ],
+    );
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r@",
+            "-T",
+            r#"if(duplicated_from(), "from", "none")"#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"none");
+}
+
 fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
     let template = r#"commit_id.short() ++ "   " ++ description.first_line()"#;
     test_env.jj_cmd_success(repo_path, &["log", "-T", template])
@@ -416,3 +416,81 @@ fn test_evolog_reverse_with_graph() {
        (empty) c+d+e
     ");
 }
+
+#[test]
+fn test_evolog_hidden_revision() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "c"]);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["evolog", "-r", "d8d5f980", "--no-graph"]);
+    insta::assert_snapshot!(stdout, @r"
+    qpvuntsm hidden test.user@example.com 2001-02-03 08:05:08 d8d5f980
+    (empty) a
+    qpvuntsm hidden test.user@example.com 2001-02-03 08:05:07 230dd059
+    (empty) (no description set)
+    ");
+    // The hint points to the current version, not to the intermediate ones.
+    assert!(stderr.starts_with("Hint: Commit d8d5f980"), "{stderr}");
+    assert!(stderr.contains("It was rewritten as 5cb22a87"), "{stderr}");
+
+    // No hint for visible revisions
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["evolog", "--no-graph"]);
+    insta::assert_snapshot!(stderr, @"");
+}
+
+#[test]
+fn test_evolog_multiple_revisions_with_operations() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(r#"templates.op_summary = 'if(tags, tags, description)'"#);
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "b2"]);
+    let template = r#"separate(" ", change_id.short(), description.first_line()) ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "evolog",
+            "-r",
+            "description(a)|description(b2)",
+            "--no-graph",
+            "--operations",
+            "-T",
+            template,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    rlvkpnrz b2
+    Created by operation: args: jj describe -m b2
+    rlvkpnrz b
+    Created by operation: args: jj new -m b
+    qpvuntsm a
+    Created by operation: args: jj describe -m a
+    qpvuntsm
+    Created by operation: add workspace 'default'
+    ");
+
+    // Without --operations, no annotations are shown
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "evolog",
+            "-r",
+            "description(b2)",
+            "--no-graph",
+            "-T",
+            template,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    rlvkpnrz b2
+    rlvkpnrz b
+    ");
+}
@@ -47,6 +47,111 @@ fn test_annotate_linear() {
     ");
 }
 
+#[test]
+fn test_annotate_line_range() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file.txt"), "line1\n").unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-m=initial", "--author=Foo <foo@example.org>"],
+    );
+
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=next"]);
+    append_to_file(&repo_path.join("file.txt"), "new text from new commit");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "annotate", "-L2,2", "file.txt"]);
+    insta::assert_snapshot!(stdout, @r"
+    kkmpptxz test.use 2001-02-03 08:05:10    2: new text from new commit
+    ");
+
+    // The end of the range may exceed the number of lines
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["file", "annotate", "--lines=1,10", "file.txt"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    qpvuntsm foo      2001-02-03 08:05:08    1: line1
+    kkmpptxz test.use 2001-02-03 08:05:10    2: new text from new commit
+    ");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["file", "annotate", "-L3,4", "file.txt"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Line 3 is out of range: file.txt has only 2 lines
+    ");
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["file", "annotate", "-L2,1", "file.txt"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: invalid value '2,1' for '--lines <START,END>': End line 1 is before start line 2
+
+    For more information, try '--help'.
+    ");
+
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["file", "annotate", "-L0,1", "file.txt"]);
+    insta::assert_snapshot!(stderr, @r"
+    error: invalid value '0,1' for '--lines <START,END>': Line numbers start at 1
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn test_annotate_renamed() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file.txt"), "line1\nline2\nline3\n").unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-m=initial", "--author=Foo <foo@example.org>"],
+    );
+
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=rename"]);
+    std::fs::rename(repo_path.join("file.txt"), repo_path.join("renamed.txt")).unwrap();
+    append_to_file(&repo_path.join("renamed.txt"), "line4");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "annotate", "renamed.txt"]);
+    insta::assert_snapshot!(stdout, @r"
+    qpvuntsm foo      2001-02-03 08:05:08    1: line1
+    qpvuntsm foo      2001-02-03 08:05:08    2: line2
+    qpvuntsm foo      2001-02-03 08:05:08    3: line3
+    kkmpptxz test.use 2001-02-03 08:05:10    4: line4
+    ");
+}
+
+#[test]
+fn test_annotate_ignore_all_space() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file.txt"), "line1\nline2\n").unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-m=initial", "--author=Foo <foo@example.org>"],
+    );
+
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=reformat"]);
+    std::fs::write(repo_path.join("file.txt"), "  line1\n\tline2\nline3\n").unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "annotate", "file.txt"]);
+    insta::assert_snapshot!(stdout.replace('\t', "<TAB>"), @r"
+    kkmpptxz test.use 2001-02-03 08:05:10    1:   line1
+    kkmpptxz test.use 2001-02-03 08:05:10    2: <TAB>line2
+    kkmpptxz test.use 2001-02-03 08:05:10    3: line3
+    ");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "annotate", "-w", "file.txt"]);
+    insta::assert_snapshot!(stdout.replace('\t', "<TAB>"), @r"
+    qpvuntsm foo      2001-02-03 08:05:08    1:   line1
+    qpvuntsm foo      2001-02-03 08:05:08    2: <TAB>line2
+    kkmpptxz test.use 2001-02-03 08:05:10    3: line3
+    ");
+}
+
 #[test]
 fn test_annotate_merge() {
     let test_env = TestEnvironment::default();
@@ -149,3 +254,92 @@ fn test_annotate_merge_one_sided_conflict_resolution() {
     zsuskuln test.use 2001-02-03 08:05:11    2: new text from new commit 1
     ");
 }
+
+#[test]
+fn test_annotate_by_change() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(
+        r#"templates.annotate_commit_summary = 'separate(" ", change_id.short(), description.first_line())'"#,
+    );
+
+    std::fs::write(repo_path.join("file.txt"), "line1\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=initial"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=next"]);
+    append_to_file(&repo_path.join("file.txt"), "new text from new commit");
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+    let old_commit_id = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r=@",
+            "-T=commit_id",
+            "--ignore-working-copy",
+        ],
+    );
+    // Rewrite the parent, which leaves the old commits hidden
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=rewritten", "@-"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["file", "annotate", "file.txt", "-r", &old_commit_id],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    qpvuntsm initial    1: line1
+    kkmpptxz next    2: new text from new commit
+    ");
+
+    // The hidden versions are attributed to the current commits of the changes
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "file",
+            "annotate",
+            "file.txt",
+            "-r",
+            &old_commit_id,
+            "--by=change",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    qpvuntsm rewritten    1: line1
+    kkmpptxz next    2: new text from new commit
+    ");
+}
+
+#[test]
+fn test_annotate_heatmap_and_age() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(
+        r#"
+        templates.annotate_commit_summary = 'description.first_line()'
+        annotate.heatmap-buckets = [1, 100000]
+        "#,
+    );
+
+    std::fs::write(repo_path.join("file.txt"), "line1\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m=initial"]);
+
+    // Test commits are from 2001, so they fall into the second bucket
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["file", "annotate", "file.txt", "--heatmap", "--color=debug"],
+    );
+    assert!(stdout.contains("annotate age-1"), "{stdout}");
+    assert!(!stdout.contains("annotate age-0"), "{stdout}");
+
+    // Without --heatmap, lines aren't labeled
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["file", "annotate", "file.txt", "--color=debug"],
+    );
+    assert!(!stdout.contains("annotate age-"), "{stdout}");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "annotate", "file.txt", "--age"]);
+    assert!(stdout.starts_with("initial "), "{stdout}");
+    assert!(stdout.contains(" years ago    1: line1"), "{stdout}");
+}
@@ -61,10 +61,12 @@ fn test_show() {
     c
     "###);
 
-    // Can print multiple files
+    // Can print multiple files, each preceded by a header
     let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "."]);
-    insta::assert_snapshot!(stdout, @r###"
+    insta::assert_snapshot!(stdout.replace('\\', "/"), @r###"
+    ==> dir/file2 <==
     c
+    ==> file1 <==
     b
     "###);
 
@@ -109,10 +111,56 @@ fn test_show_symlink() {
     // Can print multiple files
     let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["file", "show", "."]);
     insta::assert_snapshot!(stdout, @r###"
+    ==> dir/file2 <==
     c
+    ==> file1 <==
     a
     "###);
     insta::assert_snapshot!(stderr, @r###"
     Warning: Path 'symlink1' exists but is not a file
     "###);
 }
+
+#[test]
+fn test_show_header() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "bb\n").unwrap();
+
+    // Raw contents without headers
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "--no-header", "."]);
+    insta::assert_snapshot!(stdout, @r###"
+    a
+    bb
+    "###);
+
+    // Custom header template
+    let template = r#"path ++ " " ++ size ++ " " ++ blob_id.substr(0, 8) ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "-T", template, "."]);
+    insta::assert_snapshot!(stdout, @r###"
+    file1 2 78981922
+    a
+    file2 3 e0b3f1b0
+    bb
+    "###);
+
+    // The header is printed for a single file if the template is specified
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["file", "show", "-T", "path ++ \":\"", "file1"],
+    );
+    insta::assert_snapshot!(stdout, @"file1:a");
+
+    // Header template can be configured
+    test_env.add_config(r#"templates.file_show_header = '"--- " ++ path ++ "\n"'"#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "file1", "file2"]);
+    insta::assert_snapshot!(stdout, @r###"
+    --- file1
+    a
+    --- file2
+    bb
+    "###);
+}
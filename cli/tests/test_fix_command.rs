@@ -1151,3 +1151,69 @@ fn test_all_files() {
     child ddd
     "###);
 }
+
+#[test]
+fn test_fix_since_changed_lines() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let formatter_path = assert_cmd::cargo::cargo_bin("fake-formatter");
+    assert!(formatter_path.is_file());
+    let formatter = to_toml_value(formatter_path.to_str().unwrap());
+    test_env.add_config(format!(
+        r###"
+        [fix.tools.ranged]
+        command = [{formatter}, "--uppercase"]
+        patterns = ["file", "new"]
+        line-range = ["--lines=$first:$last"]
+
+        [fix.tools.whole]
+        command = [{formatter}, "--uppercase"]
+        patterns = ["legacy"]
+        "###,
+    ));
+
+    std::fs::write(repo_path.join("file"), "one\ntwo\nthree\nfour\n").unwrap();
+    std::fs::write(repo_path.join("legacy"), "a\nb\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "base"]);
+    std::fs::write(repo_path.join("file"), "one\nchanged\nthree\nfour\nadded\n").unwrap();
+    std::fs::write(repo_path.join("new"), "new\nfile\n").unwrap();
+    std::fs::write(repo_path.join("legacy"), "a\nb\nc\n").unwrap();
+
+    // Only the changed lines are passed to the tool with line ranges, and the
+    // tool without line ranges fixes the whole file.
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["fix", "-s", "@", "--since", "@-"]);
+    assert!(stderr.starts_with("Fixed 1 commits of 1 checked."));
+    let content = test_env.jj_cmd_success(&repo_path, &["file", "show", "file", "-r", "@"]);
+    insta::assert_snapshot!(content, @r"
+    one
+    CHANGED
+    three
+    four
+    ADDED
+    ");
+    let content = test_env.jj_cmd_success(&repo_path, &["file", "show", "new", "-r", "@"]);
+    insta::assert_snapshot!(content, @r"
+    NEW
+    FILE
+    ");
+    let content = test_env.jj_cmd_success(&repo_path, &["file", "show", "legacy", "-r", "@"]);
+    insta::assert_snapshot!(content, @r"
+    A
+    B
+    C
+    ");
+
+    // No lines changed since the revision itself
+    std::fs::write(repo_path.join("file"), "one\nlower\n").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["fix", "-s", "@", "--since", "@"]);
+    insta::assert_snapshot!(stderr, @r"
+    Fixed 0 commits of 1 checked.
+    Nothing changed.
+    ");
+    let content = test_env.jj_cmd_success(&repo_path, &["file", "show", "file", "-r", "@"]);
+    insta::assert_snapshot!(content, @r"
+    one
+    lower
+    ");
+}
@@ -0,0 +1,84 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::get_stderr_string;
+use crate::common::get_stdout_string;
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_fsck_clean_repo() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["fsck"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Operations checked: 2
+    Commits checked: 2
+    Trees checked: 1
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["fsck", "--repair"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebuilt commit index.
+    Cleared commit metadata cache.
+    Operations checked: 2
+    Commits checked: 2
+    Trees checked: 1
+    "###);
+}
+
+#[test]
+fn test_fsck_corrupt_object() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("ui.allow-init-native = true");
+    test_env.jj_cmd_ok(test_env.env_root(), &["init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+
+    let file_id = "bedfbb90d858c2d67b7ee8f7523be3d3b54004ef9e4f02f2ad79a1d05bfdfe49b81e3c92ebf99b504102b6bf003fa342587f5b3124c205f55204e8c4b4ce7d7c";
+    let object_path = repo_path.join(".jj/repo/store/files").join(file_id);
+    assert!(object_path.exists());
+    std::fs::write(&object_path, "b\n").unwrap();
+
+    let assert = test_env.jj_cmd(&repo_path, &["fsck"]).assert().code(1);
+    let stdout = test_env.normalize_output(&get_stdout_string(&assert));
+    let stderr = test_env.normalize_output(&get_stderr_string(&assert));
+    insta::assert_snapshot!(stdout, @r###"
+    Corrupt file object bedfbb90d858c2d67b7ee8f7523be3d3b54004ef9e4f02f2ad79a1d05bfdfe49b81e3c92ebf99b504102b6bf003fa342587f5b3124c205f55204e8c4b4ce7d7c
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Operations checked: 3
+    Commits checked: 3
+    Trees checked: 2
+    Error: Found 1 problem
+    "###);
+
+    // Corrupt objects can't be repaired
+    let assert = test_env
+        .jj_cmd(&repo_path, &["fsck", "--repair"])
+        .assert()
+        .code(1);
+    let stderr = test_env.normalize_output(&get_stderr_string(&assert));
+    insta::assert_snapshot!(stderr, @r###"
+    Rebuilt commit index.
+    Operations checked: 3
+    Commits checked: 3
+    Trees checked: 2
+    Error: Found 1 problem
+    "###);
+}
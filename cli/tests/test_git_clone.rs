@@ -471,6 +471,28 @@ fn test_git_clone_remote_default_bookmark() {
     "###);
 }
 
+#[test]
+fn test_git_clone_trunk_alias_from_config_template() {
+    let test_env = TestEnvironment::default();
+    let git_repo_path = test_env.env_root().join("source");
+    let git_repo = git2::Repository::init(git_repo_path).unwrap();
+    set_up_non_empty_git_repo(&git_repo);
+    test_env.add_config(r#"init.repo-config.revset-aliases.'trunk()' = "main@upstream""#);
+
+    // "trunk()" alias from the config template shouldn't be overwritten
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(test_env.env_root(), &["git", "clone", "source", "clone"]);
+    assert!(stderr.contains("Initialized repo config from the config template"));
+    assert!(!stderr.contains(r#"Setting the revset alias "trunk()""#));
+    let stdout = test_env.jj_cmd_success(
+        &test_env.env_root().join("clone"),
+        &["config", "list", "--repo", "revset-aliases.'trunk()'"],
+    );
+    insta::assert_snapshot!(stdout, @r#"
+    revset-aliases.'trunk()' = "main@upstream"
+    "#);
+}
+
 #[test]
 fn test_git_clone_ignore_working_copy() {
     let test_env = TestEnvironment::default();
@@ -691,6 +713,52 @@ fn test_git_clone_with_depth() {
     "#);
 }
 
+#[test]
+fn test_git_clone_lazy() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-bookmark = true");
+    let git_repo_path = test_env.env_root().join("source");
+    let git_repo = git2::Repository::init(git_repo_path).unwrap();
+    set_up_non_empty_git_repo(&git_repo);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["git", "clone", "--lazy", "source", "clone"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Fetching into new repo in "$TEST_ENV/clone"
+    bookmark: main@origin [new] tracked
+    Setting the revset alias "trunk()" to "main@origin"
+    Working copy now at: sqpuoqvx cad212e1 (empty) (no description set)
+    Parent commit      : mzyxwzks 9f01a0e0 main | message
+    Hint: No files were written to the working copy. Use `jj file hydrate <path>` to materialize them.
+    "#);
+    let clone_path = test_env.env_root().join("clone");
+    assert!(!clone_path.join("file").exists());
+
+    // Files can be materialized on demand
+    let (stdout, stderr) = test_env.jj_cmd_ok(&clone_path, &["file", "hydrate", "file"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Added 1 files, modified 0 files, removed 0 files");
+    assert!(clone_path.join("file").exists());
+    let stdout = test_env.jj_cmd_success(&clone_path, &["sparse", "list"]);
+    insta::assert_snapshot!(stdout, @"file");
+
+    // Lazy clones can't be colocated
+    let stderr = test_env.jj_cmd_cli_error(
+        test_env.env_root(),
+        &["git", "clone", "--lazy", "--colocate", "source", "clone2"],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    error: the argument '--lazy' cannot be used with '--colocate'
+
+    Usage: jj git clone --lazy <SOURCE> [DESTINATION]
+
+    For more information, try '--help'.
+    ");
+}
+
 #[test]
 fn test_git_clone_invalid_immutable_heads() {
     let test_env = TestEnvironment::default();
@@ -100,6 +100,27 @@ fn test_git_fetch_default_remote() {
     "###);
 }
 
+#[test]
+fn test_git_fetch_commit_modifying_protected_path() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-bookmark = true");
+    test_env.add_config(r#"snapshot.protected-paths = "file""#);
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_git_remote(&test_env, &repo_path, "origin");
+
+    // The fetched commit adds the protected path, but it wasn't created by jj,
+    // so it's not rejected even though the local bookmark makes it mutable.
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    insta::assert_snapshot!(stderr, @r###"
+    bookmark: origin@origin [new] tracked
+    "###);
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r###"
+    origin: oputwtnw ffecd2d6 message
+      @origin: oputwtnw ffecd2d6 message
+    "###);
+}
+
 #[test]
 fn test_git_fetch_single_remote() {
     let test_env = TestEnvironment::default();
@@ -876,6 +897,120 @@ fn test_git_fetch_bookmarks_some_missing() {
     "###);
 }
 
+#[test]
+fn test_git_fetch_bookmark_mapping() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_git_remote(&test_env, &repo_path, "rem1");
+    add_git_remote(&test_env, &repo_path, "rem2");
+
+    // The remote branch is fetched under the mapped name, and the mapping is
+    // saved to the repo config
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "fetch",
+            "--remote",
+            "rem1",
+            "--branch",
+            "upstream=rem1",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    bookmark: upstream@rem1 [new] untracked
+    Saved bookmark mapping upstream=rem1 for remote rem1 to repo config
+    ");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @"upstream@rem1: qxosxrvv 6a211027 message");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["config", "get", "git.fetch-bookmark-mappings.rem1.upstream"],
+    );
+    insta::assert_snapshot!(stdout, @"rem1");
+
+    // Fetching all branches keeps the mapped remote bookmark
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["git", "fetch", "--remote", "rem1"]);
+    assert!(stderr.contains("rem1@rem1"), "{stderr}");
+    assert!(!stderr.contains("upstream@rem1"), "{stderr}");
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r"
+    rem1@rem1: qxosxrvv 6a211027 message
+    upstream@rem1: qxosxrvv 6a211027 message
+    ");
+
+    // The saved mapping isn't saved again
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "fetch",
+            "--remote",
+            "rem1",
+            "--branch",
+            "upstream=rem1",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+
+    // Missing remote branch
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "fetch",
+            "--remote",
+            "rem1",
+            "--branch",
+            "other=noexist",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r"
+    Warning: No branch named `noexist` found on any specified/configured remote
+    Nothing changed.
+    ");
+
+    // Mappings can be combined with patterns matching the mapped name
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "git",
+            "fetch",
+            "--remote",
+            "rem2",
+            "--branch",
+            "glob:*",
+            "--branch",
+            "upstream=rem2",
+        ],
+    );
+    assert!(
+        stderr.ends_with("Saved bookmark mapping upstream=rem2 for remote rem2 to repo config\n"),
+        "{stderr}"
+    );
+    insta::assert_snapshot!(get_bookmark_output(&test_env, &repo_path), @r"
+    rem1@rem1: qxosxrvv 6a211027 message
+    rem2@rem2: yszkquru 2497a8a0 message
+    upstream@rem1: qxosxrvv 6a211027 message
+    upstream@rem2: yszkquru 2497a8a0 message
+    ");
+
+    // Two mappings to the same name are rejected
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "git",
+            "fetch",
+            "--remote",
+            "rem1",
+            "--branch",
+            "other=rem1",
+            "--branch",
+            "other=main",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @"Error: Remote-tracking bookmark 'other' would be fetched from more than one remote branch");
+}
+
 // See `test_undo_restore_commands.rs` for fetch-undo-push and fetch-undo-fetch
 // of the same bookmarks for various kinds of undo.
 #[test]
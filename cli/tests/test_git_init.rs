@@ -17,6 +17,7 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use indoc::formatdoc;
+use indoc::indoc;
 use test_case::test_case;
 
 use crate::common::get_stderr_string;
@@ -837,6 +838,55 @@ fn test_git_init_conditional_config() {
     ");
 }
 
+#[test]
+fn test_git_init_repo_config_template() {
+    let test_env = TestEnvironment::default();
+    let template_path = test_env.env_root().join("template.toml");
+    std::fs::write(
+        &template_path,
+        indoc! {r#"
+            revset-aliases.'trunk()' = "main@origin"
+            ui.default-command = "log"
+        "#},
+    )
+    .unwrap();
+    test_env.add_config(formatdoc! {r#"
+        [init]
+        repo-config-file = {template_path}
+        [init.repo-config]
+        revset-aliases.'trunk()' = "main@upstream"
+        revset-aliases.'immutable_heads()' = "trunk()"
+        "#,
+        template_path = to_toml_value(template_path.to_str().unwrap()),
+    });
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Initialized repo config from the config template
+    Initialized repo in "repo"
+    "#);
+
+    let workspace_root = test_env.env_root().join("repo");
+    let stdout = test_env.jj_cmd_success(&workspace_root, &["config", "list", "--repo"]);
+    insta::assert_snapshot!(stdout, @r#"
+    revset-aliases.'trunk()' = "main@upstream"
+    revset-aliases.'immutable_heads()' = "trunk()"
+    ui.default-command = "log"
+    "#);
+
+    // The template must be a table
+    test_env.add_config("init.repo-config = 'foo'");
+    let stderr = test_env.jj_cmd_failure(test_env.env_root(), &["git", "init", "repo2"]);
+    insta::assert_snapshot!(stderr.replace('\\', "/"), @r"
+    Config error: Invalid type or value for init.repo-config
+    Caused by: Expected a table, but is string
+
+    Hint: Check the config file: $TEST_ENV/config/config0003.toml
+    For help, see https://jj-vcs.github.io/jj/latest/config/.
+    ");
+}
+
 #[test]
 fn test_git_init_bad_wc_path() {
     let test_env = TestEnvironment::default();
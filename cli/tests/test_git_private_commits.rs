@@ -92,6 +92,7 @@ fn test_git_private_commits_block_pushing() {
     Error: Won't push commit aa3058ff8663 since it is private
     Hint: Rejected commit: yqosqzyt aa3058ff main* | (empty) private 1
     Hint: Configured git.private-commits: 'description(glob:'private*')'
+    Hint: Use --allow-private to push private commits anyway
     ");
 
     // May push when the commit is removed from git.private-commits
@@ -106,6 +107,45 @@ fn test_git_private_commits_block_pushing() {
     "#);
 }
 
+#[test]
+fn test_git_private_commits_default_lists_all() {
+    let (test_env, workspace_root) = set_up();
+    test_env.add_config(
+        r#"templates.commit_summary = 'if(description, description.first_line(), "(no description set)")'"#,
+    );
+
+    test_env.jj_cmd_ok(&workspace_root, &["new", "main", "-m=wip: first"]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m=public 3"]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m=Private: second"]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m=private scope"]);
+    test_env.jj_cmd_ok(&workspace_root, &["new"]);
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "set", "main"]);
+
+    // Unfinished commits are private by default, and all rejected commits are
+    // listed along with the reasons for rejecting them
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push", "--all"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Won't push 3 commits
+    Hint: Rejected commit since it has no description: (no description set)
+    Hint: Rejected commit since it is private: Private: second
+    Hint: Rejected commit since it is private: wip: first
+    Hint: Configured git.private-commits: 'description(glob-i:'wip:*') | description(glob-i:'private:*')'
+    Hint: Use --allow-private to push private commits anyway
+    ");
+
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &[
+            "git",
+            "push",
+            "--all",
+            "--allow-private",
+            "--allow-empty-description",
+            "--dry-run",
+        ],
+    );
+}
+
 #[test]
 fn test_git_private_commits_can_be_overridden() {
     let (test_env, workspace_root) = set_up();
@@ -120,6 +160,7 @@ fn test_git_private_commits_can_be_overridden() {
     Error: Won't push commit aa3058ff8663 since it is private
     Hint: Rejected commit: yqosqzyt aa3058ff main* | (empty) private 1
     Hint: Configured git.private-commits: 'description(glob:'private*')'
+    Hint: Use --allow-private to push private commits anyway
     ");
 
     // May push when the commit is removed from git.private-commits
@@ -174,6 +215,7 @@ fn test_git_private_commits_not_directly_in_line_block_pushing() {
     Error: Won't push commit f1253a9b1ea9 since it is private
     Hint: Rejected commit: yqosqzyt f1253a9b (empty) private 1
     Hint: Configured git.private-commits: 'description(glob:'private*')'
+    Hint: Use --allow-private to push private commits anyway
     ");
 }
 
@@ -280,5 +322,6 @@ fn test_git_private_commits_are_evaluated_separately_for_each_remote() {
     Error: Won't push commit 36b7ecd11ad9 since it is private
     Hint: Rejected commit: znkkpsqq 36b7ecd1 (empty) private 1
     Hint: Configured git.private-commits: 'description(glob:'private*')'
+    Hint: Use --allow-private to push private commits anyway
     ");
 }
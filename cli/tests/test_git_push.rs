@@ -1123,10 +1123,15 @@ fn test_git_push_missing_committer_in_immutable() {
             "--dry-run",
         ],
     );
-    insta::assert_snapshot!(stderr, @r"
-    Error: Won't push commit 7e61dc727a8f since it has no author and/or committer set
-    Hint: Rejected commit: yostqsxw 7e61dc72 imm | (empty) no committer email
-    ");
+    // The commit id of "no committer name" isn't printed anywhere else, so it's
+    // redacted.
+    insta::with_settings!({filters => vec![(r"yqosqzyt [0-9a-f]{8}", "yqosqzyt [commit id]")]}, {
+        insta::assert_snapshot!(stderr, @r"
+        Error: Won't push 2 commits
+        Hint: Rejected commit since it has no author and/or committer set: yostqsxw 7e61dc72 imm | (empty) no committer email
+        Hint: Rejected commit since it has no author and/or committer set: yqosqzyt [commit id] (empty) no committer name
+        ");
+    });
 
     test_env.add_config(r#"revset-aliases."immutable_heads()" = "imm""#);
     let (stdout, stderr) = test_env.jj_cmd_ok(
@@ -1454,13 +1459,21 @@ fn test_git_push_sign_on_push() {
     git.sign-on-push = true
     "#,
     );
+    // The unsigned "commit to be signed 1" isn't printed anywhere else, so its
+    // commit id is redacted.
+    let redact_unsigned_commit_id = vec![(r"vruxwmqv [0-9a-f]{8}", "vruxwmqv [commit id]")];
     let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push", "--dry-run"]);
     insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r#"
-    Changes to push to origin:
-      Move forward bookmark bookmark2 from 8476341eb395 to 8710e91a14a1
-    Dry-run requested, not pushing.
-    "#);
+    insta::with_settings!({filters => redact_unsigned_commit_id.clone()}, {
+        insta::assert_snapshot!(stderr, @r#"
+        Would sign 2 commits before pushing:
+          yostqsxw 8710e91a bookmark2* | (empty) commit to be signed 2
+          vruxwmqv [commit id] (empty) commit to be signed 1
+        Changes to push to origin:
+          Move forward bookmark bookmark2 from 8476341eb395 to 8710e91a14a1
+        Dry-run requested, not pushing.
+        "#);
+    });
     let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["log", "-T", template]);
     // There should be no signed commits after performing a dry run
     insta::assert_snapshot!(stdout, @r#"
@@ -1476,14 +1489,18 @@ fn test_git_push_sign_on_push() {
     insta::assert_snapshot!(stderr, @"");
     let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["git", "push"]);
     insta::assert_snapshot!(stdout, @"");
-    insta::assert_snapshot!(stderr, @r#"
-    Updated signatures of 2 commits
-    Rebased 2 descendant commits
-    Changes to push to origin:
-      Move forward bookmark bookmark2 from 8476341eb395 to a6259c482040
-    Working copy now at: kmkuslsw b5f47345 (empty) commit which should not be signed 2
-    Parent commit      : kpqxywon 90df08d3 (empty) commit which should not be signed 1
-    "#);
+    insta::with_settings!({filters => redact_unsigned_commit_id}, {
+        insta::assert_snapshot!(stderr, @r#"
+        Signing 2 commits before pushing:
+          yostqsxw 8710e91a bookmark2* | (empty) commit to be signed 2
+          vruxwmqv [commit id] (empty) commit to be signed 1
+        Rebased 2 descendant commits
+        Changes to push to origin:
+          Move forward bookmark bookmark2 from 8476341eb395 to a6259c482040
+        Working copy now at: kmkuslsw b5f47345 (empty) commit which should not be signed 2
+        Parent commit      : kpqxywon 90df08d3 (empty) commit which should not be signed 1
+        "#);
+    });
     let (stdout, stderr) = test_env.jj_cmd_ok(&workspace_root, &["log", "-T", template]);
     // Only commits which are being pushed should be signed
     insta::assert_snapshot!(stdout, @r#"
@@ -1545,6 +1562,55 @@ fn test_git_push_sign_on_push() {
     insta::assert_snapshot!(stderr, @"");
 }
 
+#[test]
+fn test_git_push_sign_on_push_failure() {
+    let (test_env, workspace_root) = set_up();
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["new", "bookmark2", "-m", "commit to be signed"],
+    );
+    test_env.jj_cmd_ok(&workspace_root, &["bookmark", "set", "bookmark2"]);
+    test_env.add_config(
+        r#"
+    git.sign-on-push = true
+    templates.commit_summary = 'description.first_line()'
+    "#,
+    );
+
+    // Signing isn't possible without a backend
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: Cannot sign commits before pushing: no signing backend is configured
+    Hint: Set `signing.backend`, or disable `git.sign-on-push` to push unsigned commits.
+    ");
+
+    // The ssh backend fails to sign without a key, and nothing is pushed
+    test_env.add_config(r#"signing.backend = "ssh""#);
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["git", "push"]);
+    insta::assert_snapshot!(stderr, @r"
+    Signing 1 commits before pushing:
+      commit to be signed
+    Error: Failed to sign commits, nothing was pushed
+    Caused by:
+    1: Could not write object of type commit
+    2: Signing error
+    3: Signing key required
+    Hint: Fix the signing configuration, or disable `git.sign-on-push` to push unsigned commits.
+    ");
+    let stdout = test_env.jj_cmd_success(
+        &workspace_root,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "bookmark2@origin",
+            "-T",
+            "description",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"description 2");
+}
+
 fn get_bookmark_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
     // --quiet to suppress deleted bookmarks hint
     test_env.jj_cmd_success(repo_path, &["bookmark", "list", "--all-remotes", "--quiet"])
@@ -0,0 +1,155 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use indoc::formatdoc;
+
+use crate::common::TestEnvironment;
+
+/// Adds a hook named `name` running the fake formatter with the given flags.
+fn add_fake_hook(test_env: &TestEnvironment, name: &str, event: &str, args: &[&str]) {
+    let formatter_path = assert_cmd::cargo::cargo_bin("fake-formatter");
+    assert!(formatter_path.is_file());
+    test_env.add_config(formatdoc! {"
+        [hooks.{name}]
+        event = '{event}'
+        command = {command}
+        ",
+        command = toml_edit::Value::from_iter(
+            [formatter_path.to_str().unwrap()]
+                .iter()
+                .chain(args)
+                .copied()
+        )
+    });
+}
+
+#[test]
+fn test_post_operation_hook() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let payload_path = test_env.env_root().join("payload.json");
+    add_fake_hook(
+        &test_env,
+        "record",
+        "post-operation",
+        &["--tee", payload_path.to_str().unwrap()],
+    );
+
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    let payload = std::fs::read_to_string(&payload_path).unwrap();
+    let payload: serde_json::Value = serde_json::from_str(payload.lines().last().unwrap()).unwrap();
+    assert_eq!(payload["event"], "post-operation");
+    assert_eq!(payload["operation"]["description"], "new empty commit");
+    assert_eq!(payload["operation"]["parents"].as_array().unwrap().len(), 1);
+    assert!(payload["workspace_root"].is_string());
+
+    // Disabled hooks aren't run
+    std::fs::remove_file(&payload_path).unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "--config", "hooks.record.enabled=false"],
+    );
+    assert!(!payload_path.exists());
+}
+
+#[test]
+fn test_post_operation_hook_failure() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_fake_hook(&test_env, "broken", "post-operation", &["--fail"]);
+
+    // The operation has already been committed, so the failure is only reported
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["new", "-m", "foo"]);
+    assert!(
+        stderr.ends_with("Warning: Hook `hooks.broken` failed: Exited with exit status: 1\n"),
+        "{stderr}"
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r@", "-Tdescription"]);
+    insta::assert_snapshot!(stdout, @r"
+    @  foo
+    │
+    ~
+    ");
+}
+
+#[test]
+fn test_pre_rewrite_hook() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    add_fake_hook(
+        &test_env,
+        "reject",
+        "pre-rewrite",
+        &["--stderr", "not allowed\n", "--fail"],
+    );
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["describe", "-m", "foo"]);
+    insta::assert_snapshot!(stderr, @r"
+    not allowed
+    Error: Hook `hooks.reject` rejected the pre-rewrite
+    Caused by: Exited with exit status: 1
+    ");
+
+    // Disabled hooks aren't run
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "describe",
+            "-m",
+            "foo",
+            "--config",
+            "hooks.reject.enabled=false",
+        ],
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-r@", "-Tdescription"]);
+    insta::assert_snapshot!(stdout, @r"
+    @  foo
+    │
+    ~
+    ");
+
+    // Hooks run once per operation, for the rewritten commits and their
+    // rebased descendants, but not for operations which don't rewrite any
+    let payload_path = test_env.env_root().join("payload.json");
+    add_fake_hook(
+        &test_env,
+        "record",
+        "pre-rewrite",
+        &["--tee", payload_path.to_str().unwrap()],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "--config", "hooks.reject.enabled=false"],
+    );
+    assert!(!payload_path.exists());
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "describe",
+            "-r@-",
+            "-m",
+            "bar",
+            "--config",
+            "hooks.reject.enabled=false",
+        ],
+    );
+    let payload = std::fs::read_to_string(&payload_path).unwrap();
+    assert_eq!(payload.lines().count(), 1, "{payload}");
+    let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+    assert_eq!(payload["event"], "pre-rewrite");
+    assert_eq!(payload["commits"].as_array().unwrap().len(), 2);
+}
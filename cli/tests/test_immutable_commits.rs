@@ -348,3 +348,52 @@ fn test_rewrite_immutable_commands() {
     Hint: Pass `--ignore-immutable` or configure the set of immutable commits via `revset-aliases.immutable_heads()`.
     "###);
 }
+
+#[test]
+fn test_protected_paths() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(r#"snapshot.protected-paths = "protected""#);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "parent"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+
+    // Changes to protected paths aren't snapshotted, but are left in the
+    // working copy
+    std::fs::write(repo_path.join("protected"), "changed\n").unwrap();
+    std::fs::write(repo_path.join("other"), "changed\n").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["status"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Refused to snapshot changes to protected paths:
+      protected
+    Hint: The changes are left in the working copy. Pass `--allow-protected-paths` to snapshot them, or configure the protected paths via `snapshot.protected-paths`.
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A other
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["status", "--allow-protected-paths"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A other
+    A protected
+    "###);
+
+    // The change which already modified the path may modify it further
+    std::fs::write(repo_path.join("protected"), "changed again\n").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "child"]);
+    assert!(!stderr.contains("protected"), "{stderr}");
+
+    // Moving the modification into another change isn't
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["squash"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Change qpvuntsmwlqt would modify protected path protected
+    Hint: Pass `--allow-protected-paths` or configure the protected paths via `snapshot.protected-paths`.
+    "###);
+    test_env.jj_cmd_ok(&repo_path, &["squash", "--allow-protected-paths"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "-r", "@-", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A other
+    A protected
+    "###);
+}
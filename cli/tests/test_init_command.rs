@@ -63,3 +63,37 @@ fn test_init_local() {
     Error: --at-op is not respected
     "###);
 }
+
+#[test]
+fn test_init_object_store() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(r#"ui.allow-init-native = true"#);
+    let bucket_path = test_env.env_root().join("bucket");
+    let url = format!("file://{}", bucket_path.to_str().unwrap());
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["init", "repo", &format!("--object-store={url}")],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Initialized repo in "repo"
+    "###);
+    let repo_path = test_env.env_root().join("repo");
+    let store_path = repo_path.join(".jj").join("repo").join("store");
+    assert_eq!(
+        std::fs::read_to_string(store_path.join("type")).unwrap(),
+        "object-store"
+    );
+    // The empty tree is stored in the object store
+    assert!(bucket_path.join("tree").is_dir());
+
+    std::fs::write(repo_path.join("file"), "content").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "add file"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "show", "-r@-", "file"]);
+    insta::assert_snapshot!(stdout, @"content");
+
+    let stderr = test_env.jj_cmd_failure(
+        test_env.env_root(),
+        &["init", "repo2", "--object-store=s3://bucket"],
+    );
+    assert!(stderr.contains("Unsupported object store URL: s3://bucket"));
+}
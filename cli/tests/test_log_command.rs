@@ -968,16 +968,17 @@ fn test_log_limit() {
     c
     "###);
 
-    // Applied on reversed DAG
+    // Applied before reversing the DAG. Because the node "a" is omitted, "c" and
+    // "b" are rendered as roots.
     let stdout = test_env.jj_cmd_success(
         &repo_path,
         &["log", "-T", "description", "--limit=3", "--reversed"],
     );
     insta::assert_snapshot!(stdout, @r###"
-    ◆
-    ○    a
-    ├─╮
-    │ ○  c
+    ○  c
+    │ ○  b
+    ├─╯
+    @  d
     "###);
     let stdout = test_env.jj_cmd_success(
         &repo_path,
@@ -991,8 +992,9 @@ fn test_log_limit() {
         ],
     );
     insta::assert_snapshot!(stdout, @r###"
-    a
     b
+    c
+    d
     "###);
 
     // Applied on filtered commits
@@ -1005,6 +1007,23 @@ fn test_log_limit() {
     │
     ~
     "###);
+
+    // The default limit can be configured
+    test_env.add_config("ui.log-default-limit = 2");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "description", "--no-graph"]);
+    insta::assert_snapshot!(stdout, @r###"
+    d
+    c
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-T", "description", "--no-graph", "--limit=3"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    d
+    c
+    b
+    "###);
 }
 
 #[test]
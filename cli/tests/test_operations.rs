@@ -74,11 +74,21 @@ fn test_op_log() {
     │  Changed commits:
     │  ○  + qpvuntsm 19611c99 (empty) description 0
     │     - qpvuntsm hidden 230dd059 (empty) (no description set)
+    │
+    │  Changed working copies:
+    │  default:
+    │  + qpvuntsm 19611c99 (empty) description 0
+    │  - qpvuntsm hidden 230dd059 (empty) (no description set)
     ○  eac759b9ab75 test-username@host.example.com 2001-02-03 04:05:07.000 +07:00 - 2001-02-03 04:05:07.000 +07:00
     │  add workspace 'default'
     │
     │  Changed commits:
     │  ○  + qpvuntsm 230dd059 (empty) (no description set)
+    │
+    │  Changed working copies:
+    │  default:
+    │  + qpvuntsm 230dd059 (empty) (no description set)
+    │  - (absent)
     ○  000000000000 root()
     "#);
 
@@ -155,6 +165,118 @@ fn test_op_log_with_no_template() {
     ");
 }
 
+#[test]
+fn test_op_log_opset() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "first"]);
+    std::fs::write(repo_path.join("file"), "contents").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "second"]);
+    let render = |opset: &str| {
+        test_env.jj_cmd_success(
+            &repo_path,
+            &[
+                "op",
+                "log",
+                "--no-graph",
+                "--ops",
+                opset,
+                "-T",
+                r#""[" ++ description ++ "]\n""#,
+            ],
+        )
+    };
+
+    insta::assert_snapshot!(render("snapshots()"), @"[snapshot working copy]");
+    insta::assert_snapshot!(render("@--.."), @r"
+    [new empty commit]
+    [snapshot working copy]
+    ");
+    insta::assert_snapshot!(render("..@-- | @"), @r"
+    [new empty commit]
+    [new empty commit]
+    [add workspace 'default']
+    []
+    ");
+    insta::assert_snapshot!(render(r#"description("new") ~ @"#), @"[new empty commit]");
+    insta::assert_snapshot!(render("~user(test-username)"), @"[]");
+    insta::assert_snapshot!(render(r#"before("2000-01-01")"#), @"[]");
+    insta::assert_snapshot!(render("ancestors(@-) & ~snapshots() & after(\"2000-01-01\")"), @r"
+    [new empty commit]
+    [add workspace 'default']
+    ");
+
+    // Single operation arguments accept opset expressions
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "log",
+            "--no-graph",
+            "--at-op",
+            "snapshots()",
+            "-T",
+            r#""[" ++ description ++ "]\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    [snapshot working copy]
+    [new empty commit]
+    [add workspace 'default']
+    []
+    ");
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "show", "description(nothing)"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: The "description(nothing)" expression resolved to no operations
+    "#);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "log", "--ops", "@ |"]);
+    assert!(stderr.starts_with("Error: Failed to parse opset: Syntax error\n"));
+    assert!(stderr.contains("Hint: See https://jj-vcs.github.io/jj/latest/operation-log/"));
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "log", "--ops", "foo()"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Failed to parse opset: Function "foo" doesn't exist
+    Caused by:  --> 1:1
+      |
+    1 | foo()
+      | ^-^
+      |
+      = Function "foo" doesn't exist
+    "#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "log", "--ops", "glob:foo"]);
+    insta::assert_snapshot!(stderr, @r#"
+    Error: Failed to parse opset: String pattern is only allowed as a function argument
+    Caused by:  --> 1:1
+      |
+    1 | glob:foo
+      | ^------^
+      |
+      = String pattern is only allowed as a function argument
+    "#);
+
+    // Relative dates are resolved against the configured timestamp
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "log",
+            "--no-graph",
+            "--ops",
+            r#"after("yesterday")"#,
+            "-T",
+            r#""[" ++ description ++ "]\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    [new empty commit]
+    [snapshot working copy]
+    [new empty commit]
+    [add workspace 'default']
+    ");
+}
+
 #[test]
 fn test_op_log_limit() {
     let test_env = TestEnvironment::default();
@@ -188,6 +310,11 @@ fn test_op_log_no_graph() {
 
     Changed commits:
     + qpvuntsm 230dd059 (empty) (no description set)
+
+    Changed working copies:
+    default:
+    + qpvuntsm 230dd059 (empty) (no description set)
+    - (absent)
     000000000000 root()
     "#);
 }
@@ -303,6 +430,12 @@ fn test_op_log_template() {
     ○  <Error: out of range integral type conversion attempted>|
     "#);
 
+    insta::assert_snapshot!(
+        render(r#"separate(" ", id.shortest(), id.shortest(4), id.shortest(4).prefix()) ++ "\n""#), @r#"
+    @  e eac7 e
+    ○  0 0000 0
+    "#);
+
     // Test the default template, i.e. with relative start time and duration. We
     // don't generally use that template because it depends on the current time,
     // so we need to reset the time range format here.
@@ -424,6 +557,13 @@ fn test_op_log_word_wrap() {
     │     description set)
     │     - qpvuntsm hidden 230dd059 (empty)
     │     (no description set)
+    │
+    │  Changed working copies:
+    │  default:
+    │  + qpvuntsm e292def1 (no description
+    │  set)
+    │  - qpvuntsm hidden 230dd059 (empty)
+    │  (no description set)
     ○  eac759b9ab75
     │  test-username@host.example.com
     │  2001-02-03 04:05:07.000 +07:00 -
@@ -433,6 +573,12 @@ fn test_op_log_word_wrap() {
     │  Changed commits:
     │  ○  + qpvuntsm 230dd059 (empty) (no
     │     description set)
+    │
+    │  Changed working copies:
+    │  default:
+    │  + qpvuntsm 230dd059 (empty) (no
+    │  description set)
+    │  - (absent)
     ○  000000000000 root()
     "#);
 
@@ -452,6 +598,13 @@ fn test_op_log_word_wrap() {
     │     (no description set)
     │     file1 | 100 +++++++++++++++++++
     │     1 file changed, 100 insertions(+), 0 deletions(-)
+    │
+    │  Changed working copies:
+    │  default:
+    │  + qpvuntsm e292def1 (no description
+    │  set)
+    │  - qpvuntsm hidden 230dd059 (empty)
+    │  (no description set)
     "#);
     insta::assert_snapshot!(render(&["op", "log", "-n1", "--no-graph", "--stat"], 40, true), @r#"
     b7cd3d0069f6
@@ -467,12 +620,33 @@ fn test_op_log_word_wrap() {
     description set)
     file1 | 100 +++++++++++++++++++++++++
     1 file changed, 100 insertions(+), 0 deletions(-)
+
+    Changed working copies:
+    default:
+    + qpvuntsm e292def1 (no description set)
+    - qpvuntsm hidden 230dd059 (empty) (no
+    description set)
     "#);
 
     // Nested graph widths should be subtracted from the term width
     let config = r#"templates.commit_summary='"0 1 2 3 4 5 6 7 8 9"'"#;
+    let change_config = r#"templates.op_diff_change='"0 1 2 3 4 5 6 7 8 9"'"#;
     insta::assert_snapshot!(
-        render(&["op", "log", "-T''", "--op-diff", "-n1", "--config", config], 15, true), @r#"
+        render(
+            &[
+                "op",
+                "log",
+                "-T''",
+                "--op-diff",
+                "-n1",
+                "--config",
+                config,
+                "--config",
+                change_config,
+            ],
+            15,
+            true,
+        ), @r#"
     @
     │
     │  Changed
@@ -483,9 +657,234 @@ fn test_op_log_word_wrap() {
     │     - 0 1 2 3
     │     4 5 6 7 8
     │     9
+    │
+    │  Changed
+    │  working
+    │  copies:
+    │  default:
+    │  + 0 1 2 3 4
+    │  5 6 7 8 9
+    │  - 0 1 2 3 4
+    │  5 6 7 8 9
     "#);
 }
 
+#[test]
+fn test_op_log_patch_paths() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        templates.commit_summary = 'change_id.short(8)'
+        templates.op_diff_change = 'change_id.short(8)'
+        "#,
+    );
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["debug", "snapshot"]);
+
+    let op_log = |extra_args: &[&str]| {
+        let mut args = vec![
+            "op",
+            "log",
+            "-n1",
+            "--no-graph",
+            "-T",
+            r#"description ++ "\n""#,
+            "--summary",
+        ];
+        args.extend_from_slice(extra_args);
+        test_env.jj_cmd_success(&repo_path, &args)
+    };
+    insta::assert_snapshot!(op_log(&[]), @r"
+    snapshot working copy
+
+    Changed commits:
+    + qpvuntsm
+    - qpvuntsm
+    A file1
+    A file2
+
+    Changed working copies:
+    default:
+    + qpvuntsm
+    - qpvuntsm
+    ");
+    insta::assert_snapshot!(op_log(&["--paths", "file1"]), @r"
+    snapshot working copy
+
+    Changed commits:
+    + qpvuntsm
+    - qpvuntsm
+    A file1
+
+    Changed working copies:
+    default:
+    + qpvuntsm
+    - qpvuntsm
+    ");
+    insta::assert_snapshot!(op_log(&["--paths", "nonexistent"]), @r"
+    snapshot working copy
+
+    Changed working copies:
+    default:
+    + qpvuntsm
+    - qpvuntsm
+    ");
+}
+
+#[test]
+fn test_op_log_kind() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "A"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file"), "contents\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "B"]);
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-T", r#"kind() ++ "\n""#],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    undo
+    rewrite
+    snapshot
+    other
+    rewrite
+    other
+    other
+    "###);
+}
+
+#[test]
+fn test_op_log_parents() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Create concurrent operations and merge them
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "A"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "B", "--at-op", "@-"]);
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+
+    let template = r#"
+        separate(" <- ",
+          if(root, "root", description.first_line()),
+          parents.map(|op| if(op.root(), "root", op.description().first_line())).join(", ")
+        ) ++ "\n"
+    "#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r###"
+    reconcile divergent operations <- describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22, describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22 <- add workspace 'default'
+    describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22 <- add workspace 'default'
+    add workspace 'default' <- root
+    root
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-T", r#"parents.len() ++ "\n""#],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    2
+    1
+    1
+    1
+    0
+    "###);
+}
+
+#[test]
+fn test_op_log_tags() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "A"]);
+
+    let template = r#"
+        separate(" | ",
+          tags.len(),
+          tags.keys().join(","),
+          tags.get("args"),
+          tags.contains_key("missing"),
+        ) ++ "\n"
+    "#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    1 | args | jj describe -m A | false
+    0 | false
+    0 | false
+    ");
+}
+
+#[test]
+fn test_op_log_workspaces() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["workspace", "add", "../secondary"]);
+    test_env.jj_cmd_ok(&repo_path, &["workspace", "forget", "secondary"]);
+
+    let template = r#"separate(" ", workspaces.len(), workspaces.keys().join(",")) ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r"
+    1 default
+    2 default,secondary
+    2 default,secondary
+    1 default
+    0
+    ");
+}
+
+#[test]
+fn test_op_log_diff_summary() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "A"]);
+    std::fs::write(repo_path.join("file"), "contents\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+
+    let template = r#"
+        separate(" ",
+          changed_commit_count(),
+          changed_bookmark_count(),
+          is_working_copy_snapshot_only()
+        ) ++ "\n"
+    "#;
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "log", "--no-graph", "-T", template]);
+    insta::assert_snapshot!(stdout, @r###"
+    1 0 false
+    0 1 false
+    2 0 true
+    2 0 false
+    1 0 false
+    0 0 false
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-T", r#"diff_summary() ++ "\n""#],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    1 commit created
+    bookmark b created
+    1 commit rewritten
+    1 commit rewritten
+    1 commit created
+    "###);
+}
+
 #[test]
 fn test_op_log_configurable() {
     let test_env = TestEnvironment::default();
@@ -732,6 +1131,146 @@ fn test_op_abandon_multiple_heads() {
     "###);
 }
 
+#[test]
+fn test_op_restore_what() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let get_log = |revset: &str| {
+        let template = r#"separate(" ", description.first_line(), bookmarks) ++ "\n""#;
+        test_env.jj_cmd_success(
+            &repo_path,
+            &["log", "--no-graph", "-r", revset, "-T", template],
+        )
+    };
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "A"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "a"]);
+    let op_id =
+        test_env.jj_cmd_success(&repo_path, &["op", "log", "--no-graph", "-n1", "-T", "id"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "B"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "set", "a", "-r", "@"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "C"]);
+
+    // Only the bookmark moves back
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["op", "restore", "--what", "bookmarks", &op_id],
+    );
+    insta::assert_snapshot!(get_log("all() ~ root()"), @r###"
+    C
+    B
+    A a
+    "###);
+    insta::assert_snapshot!(get_log("@"), @"C");
+
+    // Only the working copy moves back
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["op", "restore", "--what", "working-copy", &op_id],
+    );
+    insta::assert_snapshot!(get_log("all() ~ root()"), @r###"
+    C
+    B
+    A a
+    "###);
+    insta::assert_snapshot!(get_log("@"), @"A a");
+
+    // Only the visible commits are restored
+    test_env.jj_cmd_ok(&repo_path, &["op", "restore", "--what", "heads", &op_id]);
+    insta::assert_snapshot!(get_log("all() ~ root()"), @"A a");
+}
+
+#[test]
+fn test_op_revert() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let get_log = |revset: &str| {
+        let template = r#"separate(" ", description.first_line(), bookmarks) ++ "\n""#;
+        test_env.jj_cmd_success(
+            &repo_path,
+            &["log", "--no-graph", "-r", revset, "-T", template],
+        )
+    };
+    let get_head_op_id =
+        || test_env.jj_cmd_success(&repo_path, &["op", "log", "--no-graph", "-n1", "-T", "id"]);
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "A"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "a"]);
+    let create_op_id = get_head_op_id();
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "B"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "B2"]);
+    let describe_op_id = get_head_op_id();
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "b"]);
+
+    // Only the bookmark creation is reverted, the later operations are kept
+    test_env.jj_cmd_ok(&repo_path, &["op", "revert", &create_op_id]);
+    insta::assert_snapshot!(get_log("all() ~ root()"), @r###"
+    B2 b
+    A
+    "###);
+
+    // Reverting a rewrite brings back the previous commit, and the working copy
+    // and bookmarks follow it
+    test_env.jj_cmd_ok(&repo_path, &["op", "revert", &describe_op_id]);
+    insta::assert_snapshot!(get_log("all() ~ root()"), @r###"
+    B b
+    A
+    "###);
+    insta::assert_snapshot!(get_log("@"), @"B b");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["op", "revert", "0000000"]);
+    insta::assert_snapshot!(stderr, @"Error: Cannot revert repo initialization");
+}
+
+#[test]
+fn test_op_revert_tags_and_remote_bookmarks() {
+    let test_env = TestEnvironment::default();
+    let git_repo_path = test_env.env_root().join("git-repo");
+    init_bare_git_repo(&git_repo_path);
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let get_log = |revset: &str, template: &str| {
+        test_env.jj_cmd_success(
+            &repo_path,
+            &["log", "--no-graph", "-r", revset, "-T", template],
+        )
+    };
+    let get_head_op_id =
+        || test_env.jj_cmd_success(&repo_path, &["op", "log", "--no-graph", "-n1", "-T", "id"]);
+
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["git", "remote", "add", "origin", "../git-repo"],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["git", "fetch"]);
+    let fetch_op_id = get_head_op_id();
+    let git_repo = git2::Repository::open(repo_path.join(".jj/repo/store/git")).unwrap();
+    let commit_id = git_repo
+        .find_reference("refs/remotes/origin/bookmark-1")
+        .unwrap()
+        .target()
+        .unwrap();
+    let object = git_repo.find_object(commit_id, None).unwrap();
+    git_repo.tag_lightweight("v1", &object, false).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "import"]);
+    let import_op_id = get_head_op_id();
+    insta::assert_snapshot!(get_log("tags()", r#"tags ++ "\n""#), @"v1");
+    let stdout = get_log("remote_bookmarks()", r#"commit_id ++ "\n""#);
+    assert_eq!(stdout.lines().count(), 3, "{stdout}");
+
+    // The created tag is removed, the fetched remote bookmarks are kept
+    test_env.jj_cmd_ok(&repo_path, &["op", "revert", &import_op_id]);
+    insta::assert_snapshot!(get_log("tags()", r#"tags ++ "\n""#), @"");
+    let stdout = get_log("remote_bookmarks()", r#"commit_id ++ "\n""#);
+    assert_eq!(stdout.lines().count(), 3, "{stdout}");
+
+    // The fetched remote bookmarks are removed
+    test_env.jj_cmd_ok(&repo_path, &["op", "revert", &fetch_op_id]);
+    insta::assert_snapshot!(get_log("remote_bookmarks()", r#"commit_id ++ "\n""#), @"");
+}
+
 #[test]
 fn test_op_recover_from_bad_gc() {
     let test_env = TestEnvironment::default();
@@ -866,6 +1405,11 @@ fn test_op_summary_diff_template() {
 
     Changed commits:
     ○  [38;5;2m+[39m [1m[38;5;5mq[0m[38;5;8mpvuntsm[39m [1m[38;5;4m2[0m[38;5;8m30dd059[39m [38;5;2m(empty)[39m [38;5;2m(no description set)[39m
+
+    Changed working copies:
+    default:
+    [38;5;2m+[39m [1m[38;5;5mq[0m[38;5;8mpvuntsm[39m [1m[38;5;4m2[0m[38;5;8m30dd059[39m [38;5;2m(empty)[39m [38;5;2m(no description set)[39m
+    [38;5;1m-[39m (absent)
     ");
 
     // Tests with templates
@@ -893,6 +1437,11 @@ fn test_op_summary_diff_template() {
 
     Changed commits:
     ○  [38;5;2m<<diff added::+>>[39m [1m[38;5;5m<<change_id shortest prefix::q>>[0m[38;5;8m<<change_id shortest rest::pvuntsm>>[39m [1m[38;5;4m<<commit_id shortest prefix::2>>[0m[38;5;8m<<commit_id shortest rest::30dd059>>[39m [38;5;2m<<empty::(empty)>>[39m [38;5;2m<<empty description placeholder::(no description set)>>[39m
+
+    Changed working copies:
+    default:
+    [38;5;2m<<diff added::+>>[39m [1m[38;5;5m<<change_id shortest prefix::q>>[0m[38;5;8m<<change_id shortest rest::pvuntsm>>[39m [1m[38;5;4m<<commit_id shortest prefix::2>>[0m[38;5;8m<<commit_id shortest rest::30dd059>>[39m [38;5;2m<<empty::(empty)>>[39m [38;5;2m<<empty description placeholder::(no description set)>>[39m
+    [38;5;1m<<diff removed::->>[39m (absent)
     ");
 }
 
@@ -945,6 +1494,11 @@ fn test_op_diff() {
     ○  + sqpuoqvx 9708515f (empty) (no description set)
     ○  - qpvuntsm hidden 230dd059 (empty) (no description set)
 
+    Changed working copies:
+    default:
+    + sqpuoqvx 9708515f (empty) (no description set)
+    - qpvuntsm hidden 230dd059 (empty) (no description set)
+
     Changed local bookmarks:
     bookmark-1:
     + ulyvmwyz 1d843d1f bookmark-1 | Commit 1
@@ -970,6 +1524,11 @@ fn test_op_diff() {
     ○  + tqyxmszt 3e785984 bookmark-3@origin | Commit 3
     ○  + yuvsmzqk 3d9189bc bookmark-2@origin | Commit 2
 
+    Changed working copies:
+    default:
+    + sqpuoqvx 9708515f (empty) (no description set)
+    - (absent)
+
     Changed local bookmarks:
     bookmark-1:
     + ulyvmwyz 1d843d1f bookmark-1 | Commit 1
@@ -999,6 +1558,11 @@ fn test_op_diff() {
     ○  - tqyxmszt hidden 3e785984 Commit 3
     ○  - yuvsmzqk hidden 3d9189bc Commit 2
 
+    Changed working copies:
+    default:
+    + (absent)
+    - sqpuoqvx hidden 9708515f (empty) (no description set)
+
     Changed local bookmarks:
     bookmark-1:
     + (absent)
@@ -1086,6 +1650,11 @@ fn test_op_diff() {
     ○  + sqpuoqvx 9708515f (empty) (no description set)
     ○  - qpvuntsm hidden 230dd059 (empty) (no description set)
 
+    Changed working copies:
+    default:
+    + sqpuoqvx 9708515f (empty) (no description set)
+    - qpvuntsm hidden 230dd059 (empty) (no description set)
+
     Changed local bookmarks:
     bookmark-1:
     + (added) ulyvmwyz 1d843d1f bookmark-1?? bookmark-1@origin | Commit 1
@@ -1225,6 +1794,11 @@ fn test_op_diff() {
     Changed commits:
     ○  + wvuyspvk 358b82d6 (empty) new commit
     ○  - sqpuoqvx hidden 9708515f (empty) (no description set)
+
+    Changed working copies:
+    default:
+    + wvuyspvk 358b82d6 (empty) new commit
+    - sqpuoqvx hidden 9708515f (empty) (no description set)
     "#);
 
     // Test updating of local bookmark.
@@ -1285,6 +1859,11 @@ fn test_op_diff() {
     Changed commits:
     ○  + oupztwtk 2f0718a0 (empty) (no description set)
 
+    Changed working copies:
+    default:
+    + oupztwtk 2f0718a0 (empty) (no description set)
+    - wvuyspvk 358b82d6 bookmark-1 | (empty) new commit
+
     Changed remote bookmarks:
     bookmark-1@origin:
     + tracked wvuyspvk 358b82d6 bookmark-1 | (empty) new commit
@@ -1324,6 +1903,29 @@ fn test_op_diff_patch() {
        +++ b/file
        @@ -0,0 +1,1 @@
        +a
+
+    Changed working copies:
+    default:
+    + qpvuntsm 6b1027d2 (no description set)
+    - qpvuntsm hidden 230dd059 (empty) (no description set)
+    ");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "diff", "--op", "@-", "--stat"]);
+    insta::assert_snapshot!(&stdout, @r"
+    From operation: eac759b9ab75 (2001-02-03 08:05:07) add workspace 'default'
+      To operation: 187a5a9d8a22 (2001-02-03 08:05:08) snapshot working copy
+
+    Changed commits:
+    ○  + qpvuntsm 6b1027d2 (no description set)
+       - qpvuntsm hidden 230dd059 (empty) (no description set)
+       file | 1 +
+       1 file changed, 1 insertion(+), 0 deletions(-)
+
+    Changed working copies:
+    default:
+    + qpvuntsm 6b1027d2 (no description set)
+    - qpvuntsm hidden 230dd059 (empty) (no description set)
+
+    Total for 1 change: 1 file changed, 1 insertion(+), 0 deletions(-)
     ");
     let stdout = test_env.jj_cmd_success(&repo_path, &["op", "diff", "--op", "@", "-p", "--git"]);
     insta::assert_snapshot!(&stdout, @r#"
@@ -1332,6 +1934,11 @@ fn test_op_diff_patch() {
 
     Changed commits:
     ○  + rlvkpnrz 56950632 (empty) (no description set)
+
+    Changed working copies:
+    default:
+    + rlvkpnrz 56950632 (empty) (no description set)
+    - qpvuntsm 6b1027d2 (no description set)
     "#);
 
     // Squash the working copy commit.
@@ -1350,13 +1957,14 @@ fn test_op_diff_patch() {
     Changed commits:
     ○  + mzvwutvl 9f4fb57f (empty) (no description set)
     │ ○  - rlvkpnrz hidden 1d7f8f94 (no description set)
-    ├─╯  diff --git a/file b/file
-    │    index 7898192261..6178079822 100644
+    ├─╯  Reverse diff of removed commit 1d7f8f94:
+    │    diff --git a/file b/file
+    │    index 6178079822..7898192261 100644
     │    --- a/file
     │    +++ b/file
     │    @@ -1,1 +1,1 @@
-    │    -a
-    │    +b
+    │    -b
+    │    +a
     ○  + qpvuntsm 2ac85fd1 (no description set)
        - qpvuntsm hidden 6b1027d2 (no description set)
        diff --git a/file b/file
@@ -1366,6 +1974,11 @@ fn test_op_diff_patch() {
        @@ -1,1 +1,1 @@
        -a
        +b
+
+    Changed working copies:
+    default:
+    + mzvwutvl 9f4fb57f (empty) (no description set)
+    - rlvkpnrz hidden 1d7f8f94 (no description set)
     "#);
 
     // Abandon the working copy commit.
@@ -1384,9 +1997,297 @@ fn test_op_diff_patch() {
     Changed commits:
     ○  + yqosqzyt 33f321c4 (empty) (no description set)
     ○  - mzvwutvl hidden 9f4fb57f (empty) (no description set)
+
+    Changed working copies:
+    default:
+    + yqosqzyt 33f321c4 (empty) (no description set)
+    - mzvwutvl hidden 9f4fb57f (empty) (no description set)
     "#);
 }
 
+#[test]
+fn test_op_diff_paths() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        templates.commit_summary = 'change_id.short(8)'
+        templates.op_diff_change = 'change_id.short(8)'
+        templates.op_summary = 'description'
+        "#,
+    );
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file1"), "a\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["debug", "snapshot"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("file2"), "c\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["debug", "snapshot"]);
+
+    let op_diff = |paths: &[&str]| {
+        let mut args = vec!["op", "diff", "--from=@---", "--no-graph", "--summary"];
+        args.extend_from_slice(paths);
+        test_env.jj_cmd_success(&repo_path, &args)
+    };
+    insta::assert_snapshot!(op_diff(&["file1"]), @r"
+    From operation: add workspace 'default'
+      To operation: snapshot working copy
+
+    Changed commits:
+    + qpvuntsm
+    - qpvuntsm
+    A file1
+
+    Changed working copies:
+    default:
+    + rlvkpnrz
+    - qpvuntsm
+    ");
+    insta::assert_snapshot!(op_diff(&["file2"]), @r"
+    From operation: add workspace 'default'
+      To operation: snapshot working copy
+
+    Changed commits:
+    + rlvkpnrz
+    M file2
+    + qpvuntsm
+    - qpvuntsm
+    A file2
+
+    Changed working copies:
+    default:
+    + rlvkpnrz
+    - qpvuntsm
+    ");
+    insta::assert_snapshot!(op_diff(&["nonexistent"]), @r"
+    From operation: add workspace 'default'
+      To operation: snapshot working copy
+
+    Changed working copies:
+    default:
+    + rlvkpnrz
+    - qpvuntsm
+    ");
+
+    // A range of operations is diffed from its root to its head
+    for range in ["@---..@", "@---.."] {
+        let stdout = test_env.jj_cmd_success(
+            &repo_path,
+            &[
+                "op",
+                "diff",
+                "--operation",
+                range,
+                "--no-graph",
+                "--summary",
+                "file1",
+            ],
+        );
+        assert_eq!(stdout, op_diff(&["file1"]));
+    }
+}
+
+#[test]
+fn test_op_diff_template() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        templates.commit_summary = 'change_id.short(8)'
+        templates.op_summary = 'description'
+        "#,
+    );
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+
+    // The default template doesn't depend on templates.commit_summary
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "diff", "--no-graph"]);
+    let changed_commits = stdout.split("Changed working copies:").next().unwrap();
+    assert!(changed_commits.contains("(no description set)"), "{stdout}");
+    assert!(changed_commits.contains("first"), "{stdout}");
+
+    // Configured template
+    test_env
+        .add_config(r#"templates.op_diff_change = 'coalesce(description.first_line(), "(none)")'"#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "diff", "--no-graph"]);
+    insta::assert_snapshot!(stdout, @r"
+    From operation: add workspace 'default'
+      To operation: describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+
+    Changed commits:
+    + first
+    - (none)
+
+    Changed working copies:
+    default:
+    + qpvuntsm
+    - qpvuntsm
+    ");
+
+    // Template given on the command line takes precedence
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "diff",
+            "--no-graph",
+            "-T",
+            r#"change_id.short(4) ++ " " ++ empty"#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    From operation: add workspace 'default'
+      To operation: describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+
+    Changed commits:
+    + qpvu true
+    - qpvu true
+
+    Changed working copies:
+    default:
+    + qpvuntsm
+    - qpvuntsm
+    ");
+}
+
+#[test]
+fn test_op_diff_from_repo() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        templates.commit_summary = 'coalesce(description.first_line(), "(empty)")'
+        templates.op_diff_change = 'coalesce(description.first_line(), "(empty)")'
+        templates.op_summary = 'description'
+        "#,
+    );
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let backup_path = test_env.env_root().join("backup");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    copy_dir_all(&repo_path, &backup_path);
+
+    // Make some changes after the backup was taken
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "-r@-", "feature"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "second"]);
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "diff", "--no-graph", "--from-repo", "../backup"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    From repository: ../backup
+    From operation: new empty commit
+      To operation: new empty commit
+
+    Changed commits:
+    + (empty)
+    + second
+    - (empty)
+
+    Changed working copies:
+    default:
+    + (empty)
+    - (empty)
+
+    Changed local bookmarks:
+    feature:
+    + first
+    - (absent)
+    ");
+
+    // Comparing the backup with itself shows no changes
+    let stdout = test_env.jj_cmd_success(&backup_path, &["op", "diff", "--from-repo", "."]);
+    insta::assert_snapshot!(stdout, @r"
+    From repository: .
+    From operation: new empty commit
+      To operation: new empty commit
+    ");
+
+    let stderr =
+        test_env.jj_cmd_failure(&repo_path, &["op", "diff", "--from-repo", "../nonexistent"]);
+    insta::assert_snapshot!(stderr, @r#"Error: There is no jj repo in "../nonexistent""#);
+}
+
+#[test]
+fn test_op_diff_divergent_change() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+        templates.commit_summary = 'change_id.short(8)'
+        templates.op_diff_change = 'change_id.short(8)'
+        templates.op_summary = 'description'
+        "#,
+    );
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["debug", "snapshot"]);
+    let snapshot_op_id = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "-n1", "-Tid.short()"],
+    );
+
+    // Rewrite the change concurrently to make it divergent
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["file", "chmod", "x", "file", "--at-op", &snapshot_op_id],
+    );
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "diff",
+            "--from",
+            &snapshot_op_id,
+            "--no-graph",
+            "--summary",
+        ],
+    );
+    // Each of the divergent commits has its own diff
+    let (stdout, _) = stdout.split_once("\nChanged working copies:").unwrap();
+    assert_eq!(
+        stdout.lines().filter(|line| *line == "+ qpvuntsm").count(),
+        2,
+        "{stdout}"
+    );
+    assert_eq!(
+        stdout
+            .lines()
+            .filter(|line| line.starts_with("Commit "))
+            .count(),
+        2,
+        "{stdout}"
+    );
+    assert!(stdout.contains("\nM file\n"), "{stdout}");
+
+    // Abandoning the divergent commits shows the reverse patches of both
+    test_env.jj_cmd_ok(&repo_path, &["abandon", "qpvuntsm"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "diff", "--no-graph", "--summary"]);
+    let (stdout, _) = stdout.split_once("\nChanged working copies:").unwrap();
+    assert_eq!(
+        stdout.lines().filter(|line| *line == "- qpvuntsm").count(),
+        2,
+        "{stdout}"
+    );
+    assert_eq!(
+        stdout
+            .lines()
+            .filter(|line| line.starts_with("Reverse diff of removed commit "))
+            .count(),
+        2,
+        "{stdout}"
+    );
+    assert_eq!(
+        stdout.lines().filter(|line| *line == "D file").count(),
+        2,
+        "{stdout}"
+    );
+}
+
 #[test]
 fn test_op_diff_sibling() {
     let test_env = TestEnvironment::default();
@@ -1475,9 +2376,16 @@ fn test_op_diff_sibling() {
     ○    - mzvwutvl hidden 270db3d9 (empty) A
     ├─╮
     │ ○  - kkmpptxz hidden 8331e0a3 A.1
-    │    A file1
+    │    Reverse diff of removed commit 8331e0a3:
+    │    D file1
     ○  - zsuskuln hidden 8afecaef A.2
-       A file2
+       Reverse diff of removed commit 8afecaef:
+       D file2
+
+    Changed working copies:
+    default:
+    + qpvuntsm 02ef2bc4 (empty) B
+    - mzvwutvl hidden 270db3d9 (empty) A
     "#);
     let stdout = test_env.jj_cmd_success(
         &repo_path,
@@ -1505,6 +2413,11 @@ fn test_op_diff_sibling() {
     ○  + zsuskuln 8afecaef A.2
        A file2
     ○  - qpvuntsm hidden 02ef2bc4 (empty) B
+
+    Changed working copies:
+    default:
+    + mzvwutvl 270db3d9 (empty) A
+    - qpvuntsm hidden 02ef2bc4 (empty) B
     "#);
 }
 
@@ -1559,6 +2472,12 @@ fn test_op_diff_word_wrap() {
        (no description set)
        0 files changed, 0 insertions(+), 0 deletions(-)
 
+    Changed working copies:
+    default:
+    + sqpuoqvx 850efc9e (no description set)
+    - qpvuntsm hidden 230dd059 (empty) (no
+    description set)
+
     Changed local bookmarks:
     bookmark-1:
     + ulyvmwyz 1d843d1f bookmark-1 | Commit
@@ -1578,12 +2497,28 @@ fn test_op_diff_word_wrap() {
     + untracked tqyxmszt 3e785984
     bookmark-3@origin | Commit 3
     - untracked (absent)
+
+    Total for 5 changes: 4 files changed,
+    103 insertions(+), 0 deletions(-)
     "#);
 
     // Graph width should be subtracted from the term width
     let config = r#"templates.commit_summary='"0 1 2 3 4 5 6 7 8 9"'"#;
+    let change_config = r#"templates.op_diff_change='"0 1 2 3 4 5 6 7 8 9"'"#;
     insta::assert_snapshot!(
-        render(&["op", "diff", "--from=@---", "--config", config], 10, true), @r#"
+        render(
+            &[
+                "op",
+                "diff",
+                "--from=@---",
+                "--config",
+                config,
+                "--config",
+                change_config,
+            ],
+            10,
+            true,
+        ), @r#"
     From operation: eac759b9ab75 (2001-02-03 08:05:07) add workspace 'default'
       To operation: f3052392e08c (2001-02-03 08:05:08) snapshot working copy
 
@@ -1605,6 +2540,17 @@ fn test_op_diff_word_wrap() {
        3 4 5 6
        7 8 9
 
+    Changed
+    working
+    copies:
+    default:
+    + 0 1 2 3
+    4 5 6 7 8
+    9
+    - 0 1 2 3
+    4 5 6 7 8
+    9
+
     Changed
     local
     bookmarks:
@@ -1682,6 +2628,11 @@ fn test_op_show() {
     ○  + sqpuoqvx 9708515f (empty) (no description set)
     ○  - qpvuntsm hidden 230dd059 (empty) (no description set)
 
+    Changed working copies:
+    default:
+    + sqpuoqvx 9708515f (empty) (no description set)
+    - qpvuntsm hidden 230dd059 (empty) (no description set)
+
     Changed local bookmarks:
     bookmark-1:
     + ulyvmwyz 1d843d1f bookmark-1 | Commit 1
@@ -1876,6 +2827,11 @@ fn test_op_show() {
     Changed commits:
     ○  + xznxytkn eb6c2b21 (empty) new commit
     ○  - sqpuoqvx hidden 9708515f (empty) (no description set)
+
+    Changed working copies:
+    default:
+    + xznxytkn eb6c2b21 (empty) new commit
+    - sqpuoqvx hidden 9708515f (empty) (no description set)
     "#);
 
     // Test updating of local bookmark.
@@ -1939,6 +2895,11 @@ fn test_op_show() {
     Changed commits:
     ○  + pzsxstzt 7ab2d837 (empty) (no description set)
 
+    Changed working copies:
+    default:
+    + pzsxstzt 7ab2d837 (empty) (no description set)
+    - xznxytkn eb6c2b21 bookmark-1 | (empty) new commit
+
     Changed remote bookmarks:
     bookmark-1@origin:
     + tracked xznxytkn eb6c2b21 bookmark-1 | (empty) new commit
@@ -1979,6 +2940,11 @@ fn test_op_show_patch() {
        +++ b/file
        @@ -0,0 +1,1 @@
        +a
+
+    Changed working copies:
+    default:
+    + qpvuntsm 6b1027d2 (no description set)
+    - qpvuntsm hidden 230dd059 (empty) (no description set)
     ");
     let stdout = test_env.jj_cmd_success(&repo_path, &["op", "show", "@", "-p", "--git"]);
     insta::assert_snapshot!(&stdout, @r#"
@@ -1988,6 +2954,11 @@ fn test_op_show_patch() {
 
     Changed commits:
     ○  + rlvkpnrz 56950632 (empty) (no description set)
+
+    Changed working copies:
+    default:
+    + rlvkpnrz 56950632 (empty) (no description set)
+    - qpvuntsm 6b1027d2 (no description set)
     "#);
 
     // Squash the working copy commit.
@@ -2007,13 +2978,14 @@ fn test_op_show_patch() {
     Changed commits:
     ○  + mzvwutvl 9f4fb57f (empty) (no description set)
     │ ○  - rlvkpnrz hidden 1d7f8f94 (no description set)
-    ├─╯  diff --git a/file b/file
-    │    index 7898192261..6178079822 100644
+    ├─╯  Reverse diff of removed commit 1d7f8f94:
+    │    diff --git a/file b/file
+    │    index 6178079822..7898192261 100644
     │    --- a/file
     │    +++ b/file
     │    @@ -1,1 +1,1 @@
-    │    -a
-    │    +b
+    │    -b
+    │    +a
     ○  + qpvuntsm 2ac85fd1 (no description set)
        - qpvuntsm hidden 6b1027d2 (no description set)
        diff --git a/file b/file
@@ -2023,6 +2995,11 @@ fn test_op_show_patch() {
        @@ -1,1 +1,1 @@
        -a
        +b
+
+    Changed working copies:
+    default:
+    + mzvwutvl 9f4fb57f (empty) (no description set)
+    - rlvkpnrz hidden 1d7f8f94 (no description set)
     "#);
 
     // Abandon the working copy commit.
@@ -2042,6 +3019,11 @@ fn test_op_show_patch() {
     Changed commits:
     ○  + yqosqzyt 33f321c4 (empty) (no description set)
     ○  - mzvwutvl hidden 9f4fb57f (empty) (no description set)
+
+    Changed working copies:
+    default:
+    + yqosqzyt 33f321c4 (empty) (no description set)
+    - mzvwutvl hidden 9f4fb57f (empty) (no description set)
     "#);
 
     // Try again with "op log".
@@ -2054,6 +3036,11 @@ fn test_op_show_patch() {
     │  Changed commits:
     │  ○  + yqosqzyt 33f321c4 (empty) (no description set)
     │  ○  - mzvwutvl hidden 9f4fb57f (empty) (no description set)
+    │
+    │  Changed working copies:
+    │  default:
+    │  + yqosqzyt 33f321c4 (empty) (no description set)
+    │  - mzvwutvl hidden 9f4fb57f (empty) (no description set)
     ○  894c12d90345 test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
     │  squash commits into 6b1027d2770cd0a39c468e525e52bf8c47e1464a
     │  args: jj squash
@@ -2061,13 +3048,14 @@ fn test_op_show_patch() {
     │  Changed commits:
     │  ○  + mzvwutvl 9f4fb57f (empty) (no description set)
     │  │ ○  - rlvkpnrz hidden 1d7f8f94 (no description set)
-    │  ├─╯  diff --git a/file b/file
-    │  │    index 7898192261..6178079822 100644
+    │  ├─╯  Reverse diff of removed commit 1d7f8f94:
+    │  │    diff --git a/file b/file
+    │  │    index 6178079822..7898192261 100644
     │  │    --- a/file
     │  │    +++ b/file
     │  │    @@ -1,1 +1,1 @@
-    │  │    -a
-    │  │    +b
+    │  │    -b
+    │  │    +a
     │  ○  + qpvuntsm 2ac85fd1 (no description set)
     │     - qpvuntsm hidden 6b1027d2 (no description set)
     │     diff --git a/file b/file
@@ -2077,6 +3065,11 @@ fn test_op_show_patch() {
     │     @@ -1,1 +1,1 @@
     │     -a
     │     +b
+    │
+    │  Changed working copies:
+    │  default:
+    │  + mzvwutvl 9f4fb57f (empty) (no description set)
+    │  - rlvkpnrz hidden 1d7f8f94 (no description set)
     ○  15c3c5d0baf0 test-username@host.example.com 2001-02-03 04:05:11.000 +07:00 - 2001-02-03 04:05:11.000 +07:00
     │  snapshot working copy
     │  args: jj squash
@@ -2091,12 +3084,22 @@ fn test_op_show_patch() {
     │     @@ -1,1 +1,1 @@
     │     -a
     │     +b
+    │
+    │  Changed working copies:
+    │  default:
+    │  + rlvkpnrz 1d7f8f94 (no description set)
+    │  - rlvkpnrz hidden 56950632 (empty) (no description set)
     ○  a7e535e73c4b test-username@host.example.com 2001-02-03 04:05:08.000 +07:00 - 2001-02-03 04:05:08.000 +07:00
     │  new empty commit
     │  args: jj new
     │
     │  Changed commits:
     │  ○  + rlvkpnrz 56950632 (empty) (no description set)
+    │
+    │  Changed working copies:
+    │  default:
+    │  + rlvkpnrz 56950632 (empty) (no description set)
+    │  - qpvuntsm 6b1027d2 (no description set)
     ○  187a5a9d8a22 test-username@host.example.com 2001-02-03 04:05:08.000 +07:00 - 2001-02-03 04:05:08.000 +07:00
     │  snapshot working copy
     │  args: jj new
@@ -2111,11 +3114,21 @@ fn test_op_show_patch() {
     │     +++ b/file
     │     @@ -0,0 +1,1 @@
     │     +a
+    │
+    │  Changed working copies:
+    │  default:
+    │  + qpvuntsm 6b1027d2 (no description set)
+    │  - qpvuntsm hidden 230dd059 (empty) (no description set)
     ○  eac759b9ab75 test-username@host.example.com 2001-02-03 04:05:07.000 +07:00 - 2001-02-03 04:05:07.000 +07:00
     │  add workspace 'default'
     │
     │  Changed commits:
     │  ○  + qpvuntsm 230dd059 (empty) (no description set)
+    │
+    │  Changed working copies:
+    │  default:
+    │  + qpvuntsm 230dd059 (empty) (no description set)
+    │  - (absent)
     ○  000000000000 root()
     ");
 }
@@ -2233,3 +3246,16 @@ fn get_log_output(test_env: &TestEnvironment, repo_path: &Path, op_id: &str) ->
         &["log", "-T", "commit_id", "--at-op", op_id, "-r", "all()"],
     )
 }
+
+fn copy_dir_all(src: &Path, dst: &Path) {
+    std::fs::create_dir(dst).unwrap();
+    for entry in std::fs::read_dir(src).unwrap() {
+        let entry = entry.unwrap();
+        let dst_path = dst.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir_all(&entry.path(), &dst_path);
+        } else {
+            std::fs::copy(entry.path(), dst_path).unwrap();
+        }
+    }
+}
@@ -251,6 +251,7 @@ fn test_parallelize_disconnected_target_commits() {
     );
     insta::assert_snapshot!(stdout, @"");
     insta::assert_snapshot!(stderr, @r###"
+    Warning: Commit d3902619fade is not being parallelized, so 4cd999dfaac0 will remain a descendant of 8b64ddff700d
     Nothing changed.
     "###);
     insta::assert_snapshot!(get_log_output(&test_env, &workspace_path), @r###"
@@ -261,6 +262,91 @@ fn test_parallelize_disconnected_target_commits() {
     "###);
 }
 
+#[test]
+fn test_parallelize_blocked_by_multiple_intermediate_commits() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+
+    for n in 1..6 {
+        test_env.jj_cmd_ok(&workspace_path, &["commit", &format!("-m{n}")]);
+    }
+    test_env.jj_cmd_ok(&workspace_path, &["describe", "-m=6"]);
+    insta::assert_snapshot!(get_log_output(&test_env, &workspace_path), @r###"
+    @  02b7709cc4e9 6 parents: 5
+    ○  1b2f08d76b66 5 parents: 4
+    ○  e5c4cf44e237 4 parents: 3
+    ○  4cd999dfaac0 3 parents: 2
+    ○  d3902619fade 2 parents: 1
+    ○  8b64ddff700d 1 parents:
+    ◆  000000000000 parents:
+    "###);
+
+    // Both 2 and 4 block parallelization, not just the first of them.
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_path,
+        &[
+            "parallelize",
+            "description(1)",
+            "description(3)",
+            "description(5)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Commit e5c4cf44e237 is not being parallelized, so 1b2f08d76b66 will remain a descendant of 4cd999dfaac0
+    Warning: Commit d3902619fade is not being parallelized, so 4cd999dfaac0 will remain a descendant of 8b64ddff700d
+    Nothing changed.
+    "###);
+}
+
+#[test]
+fn test_parallelize_partially_blocked_by_intermediate_commit() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+
+    for n in 1..5 {
+        test_env.jj_cmd_ok(&workspace_path, &["commit", &format!("-m{n}")]);
+    }
+    test_env.jj_cmd_ok(&workspace_path, &["new", "root()"]);
+    let template = r#"
+    separate(" ",
+        description.first_line(),
+        "parents:",
+        parents.map(|c|c.description().first_line())
+    ) ++ "\n""#;
+    let log_args = ["log", "--no-graph", "-r", "~(root() | @)", "-T", template];
+    insta::assert_snapshot!(test_env.jj_cmd_success(&workspace_path, &log_args), @r###"
+    4 parents: 3
+    3 parents: 2
+    2 parents: 1
+    1 parents:
+    "###);
+
+    // 1 and 2 become siblings, but 4 stays on top of them because 3 isn't in
+    // the set.
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &workspace_path,
+        &[
+            "parallelize",
+            "description(1)",
+            "description(2)",
+            "description(4)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Commit 4cd999dfaac0 is not being parallelized, so e5c4cf44e237 will remain a descendant of d3902619fade
+    "###);
+    insta::assert_snapshot!(test_env.jj_cmd_success(&workspace_path, &log_args), @r###"
+    4 parents: 3
+    3 parents: 1 2
+    2 parents:
+    1 parents:
+    "###);
+}
+
 #[test]
 fn test_parallelize_head_is_a_merge() {
     let test_env = TestEnvironment::default();
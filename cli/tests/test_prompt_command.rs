@@ -0,0 +1,68 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::TestEnvironment;
+
+#[test]
+fn test_prompt() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first line\nsecond line"]);
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "main"]);
+
+    let op_id = test_env.jj_cmd_success(
+        &repo_path,
+        &["op", "log", "--no-graph", "--limit=1", "-T", "id.short()"],
+    );
+    let stdout = test_env.jj_cmd_success(&repo_path, &["prompt"]);
+    assert_eq!(stdout, format!("qpvuntsm main first line {op_id}"));
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["prompt", "-T", "change_id.short(4)"]);
+    insta::assert_snapshot!(stdout, @"qpvu");
+
+    test_env.add_config(r#"templates.prompt = 'bookmarks ++ "|" ++ workspace_name()'"#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["prompt"]);
+    insta::assert_snapshot!(stdout, @"main|default");
+}
+
+#[test]
+fn test_prompt_does_not_snapshot() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "contents").unwrap();
+
+    // The new file isn't picked up, and no operation is recorded
+    let stdout = test_env.jj_cmd_success(&repo_path, &["prompt", "-T", "empty"]);
+    insta::assert_snapshot!(stdout, @"true");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "op",
+            "log",
+            "--no-graph",
+            "--ignore-working-copy",
+            "-T",
+            r#"description ++ "\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    add workspace 'default'
+    ");
+
+    // Other commands still snapshot it
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-T", "empty"]);
+    insta::assert_snapshot!(stdout, @"false");
+}
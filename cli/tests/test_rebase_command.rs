@@ -2639,6 +2639,46 @@ fn test_rebase_skip_emptied() {
     "#);
 }
 
+#[test]
+fn test_rebase_merges_linearize() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "a", &[]);
+    create_commit(&test_env, &repo_path, "b", &["a"]);
+    create_commit(&test_env, &repo_path, "c", &["a"]);
+    create_commit(&test_env, &repo_path, "d", &["b", "c"]);
+    create_commit(&test_env, &repo_path, "e", &[]);
+
+    // The merge commit is rebased onto its first parent only, and the changes
+    // from "c" are dropped.
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["rebase", "-s=b", "-d=e", "--rebase-merges=linearize"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Warning: Linearized 1 merge commits; changes from their other parents were dropped
+    Rebased 2 commits onto destination
+    "#);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r#"
+    ○  d: b
+    ○  b: e
+    @  e
+    │ ○  c: a
+    │ ○  a
+    ├─╯
+    ◆
+    "#);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["file", "list", "-r=d"]);
+    insta::assert_snapshot!(stdout, @r#"
+    b
+    d
+    e
+    "#);
+}
+
 #[test]
 fn test_rebase_skip_emptied_descendants() {
     let test_env = TestEnvironment::default();
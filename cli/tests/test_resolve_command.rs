@@ -1726,3 +1726,66 @@ fn test_multiple_conflicts_with_error() {
     "#
     );
 }
+
+#[test]
+fn test_resolve_binary_conflict_keep_both() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(
+        &test_env,
+        &repo_path,
+        "base",
+        &[],
+        &[("image.png", "base\0"), ("text", "base\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "a",
+        &["base"],
+        &[("image.png", "a\0"), ("text", "a\n")],
+    );
+    create_commit(
+        &test_env,
+        &repo_path,
+        "b",
+        &["base"],
+        &[("image.png", "b\0"), ("text", "b\n")],
+    );
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+
+    // Binary conflicts are listed distinctly
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), @r"
+    image.png    2-sided conflict including binary content
+    text         2-sided conflict
+    ");
+    // Other commands don't read the file contents to detect binary conflicts
+    let stdout = test_env.jj_cmd_success(&repo_path, &["status"]);
+    assert!(stdout.contains("image.png    2-sided conflict\n"));
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve", "--keep-both", "image.png"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(
+        stderr.lines().next().unwrap(),
+        @"Kept sides of image.png as image.side1.png, image.side2.png");
+    assert!(!repo_path.join("image.png").exists());
+    assert_eq!(
+        std::fs::read(repo_path.join("image.side1.png")).unwrap(),
+        b"a\0"
+    );
+    assert_eq!(
+        std::fs::read(repo_path.join("image.side2.png")).unwrap(),
+        b"b\0"
+    );
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), @r"
+    text    2-sided conflict
+    ");
+
+    // The side files can't be overwritten
+    test_env.jj_cmd_ok(&repo_path, &["new", "a", "b"]);
+    std::fs::write(repo_path.join("image.side1.png"), "").unwrap();
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["resolve", "--keep-both", "image.png"]);
+    insta::assert_snapshot!(stderr, @"Error: Cannot keep both sides of image.png: image.side1.png already exists");
+}
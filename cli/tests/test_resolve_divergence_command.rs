@@ -0,0 +1,176 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::TestEnvironment;
+
+fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
+    let template = r#"description.first_line() ++ if(divergent, " !divergence!")"#;
+    test_env.jj_cmd_success(repo_path, &["log", "-T", template])
+}
+
+fn create_divergence(test_env: &TestEnvironment, repo_path: &Path) {
+    std::fs::write(repo_path.join("file"), "foo\n").unwrap();
+    test_env.jj_cmd_ok(repo_path, &["describe", "-m", "description 1"]);
+    test_env.jj_cmd_ok(
+        repo_path,
+        &["describe", "-m", "description 2", "--at-operation", "@-"],
+    );
+    std::fs::write(repo_path.join("file2"), "bar\n").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(repo_path, &["status"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Concurrent modification detected, resolving automatically.
+    "###);
+}
+
+#[test]
+fn test_resolve_divergence_show() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env
+        .add_config(r#"templates.commit_summary = 'if(root, "(root)", description.first_line())'"#);
+    create_divergence(&test_env, &repo_path);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  description 1 !divergence!
+    │ ○  description 2 !divergence!
+    ├─╯
+    ◆
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve-divergence", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Copy 1 of 2: description 1
+    A file
+    A file2
+
+    Copy 2 of 2: description 2
+    A file
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Hint: Resolve the divergence with `--keep`, `--merge`, or `--split`.
+    "###);
+
+    // Strategies are mutually exclusive
+    let stderr =
+        test_env.jj_cmd_cli_error(&repo_path, &["resolve-divergence", "--keep", "--split"]);
+    insta::assert_snapshot!(stderr.lines().next().unwrap(), @"error: the argument '--keep' cannot be used with '--split'");
+}
+
+#[test]
+fn test_resolve_divergence_keep() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env
+        .add_config(r#"templates.commit_summary = 'if(root, "(root)", description.first_line())'"#);
+    create_divergence(&test_env, &repo_path);
+
+    // The copy to keep must be unambiguous
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["resolve-divergence", "--keep", "description(description)"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Revset resolved to more than one copy of the change
+    Hint: Select the copy to keep by its commit ID.
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "resolve-divergence",
+            "--keep",
+            r#"description("description 2")"#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Kept description 2
+    Working copy now at: description 2
+    Parent commit      : (root)
+    Added 0 files, modified 0 files, removed 1 files
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  description 2
+    ◆
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["resolve-divergence"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Change qpvuntsmwlqt is not divergent
+    "###);
+}
+
+#[test]
+fn test_resolve_divergence_merge() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env
+        .add_config(r#"templates.commit_summary = 'if(root, "(root)", description.first_line())'"#);
+    create_divergence(&test_env, &repo_path);
+    // Leave the combined description as is
+    test_env.set_up_fake_editor();
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve-divergence", "--merge"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Merged into description 1
+    Working copy now at: description 1
+    Parent commit      : (root)
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    @  description 1
+    ◆
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r", "@", "-T", "description"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    description 1
+
+    description 2
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A file
+    A file2
+    "###);
+}
+
+#[test]
+fn test_resolve_divergence_split() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env
+        .add_config(r#"templates.commit_summary = 'if(root, "(root)", description.first_line())'"#);
+    create_divergence(&test_env, &repo_path);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve-divergence", "--split"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Gave new change IDs to:
+      description 2
+    "###);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ○  description 2
+    │ @  description 1
+    ├─╯
+    ◆
+    "###);
+}
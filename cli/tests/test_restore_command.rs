@@ -342,6 +342,49 @@ fn test_restore_restore_descendants() {
     "#);
 }
 
+#[test]
+fn test_restore_from_op() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "old\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["debug", "snapshot"]);
+    std::fs::write(repo_path.join("file1"), "new\n").unwrap();
+    std::fs::write(repo_path.join("file2"), "new\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["debug", "snapshot"]);
+
+    // Restores the paths from the working-copy commit at the operation
+    let (stdout, _stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["restore", "--from-op", "@-", "file1"]);
+    insta::assert_snapshot!(stdout, @"");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--git"]);
+    insta::assert_snapshot!(stdout, @r"
+    diff --git a/file1 b/file1
+    new file mode 100644
+    index 0000000000..3367afdbbf
+    --- /dev/null
+    +++ b/file1
+    @@ -0,0 +1,1 @@
+    +old
+    diff --git a/file2 b/file2
+    new file mode 100644
+    index 0000000000..3e75765776
+    --- /dev/null
+    +++ b/file2
+    @@ -0,0 +1,1 @@
+    +new
+    ");
+    assert_eq!(
+        std::fs::read_to_string(repo_path.join("file1")).unwrap(),
+        "old\n"
+    );
+
+    // The root operation has no working-copy commit
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["restore", "--from-op", "000000000000"]);
+    insta::assert_snapshot!(stderr, @"Error: Workspace 'default' doesn't have a working-copy commit at operation 000000000000");
+}
+
 #[test]
 fn test_restore_interactive() {
     let mut test_env = TestEnvironment::default();
@@ -0,0 +1,144 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::common::TestEnvironment;
+
+const LOG_TEMPLATE: &str = r#"
+if(signature,
+  description.first_line() ++ " [" ++ signature.key() ++ "]",
+  description.first_line(),
+) ++ "\n"
+"#;
+
+fn create_repo() -> (TestEnvironment, PathBuf) {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+    templates.commit_summary = 'description.first_line()'
+    signing.backend = "test"
+    signing.key = "impeccable"
+    "#,
+    );
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "A"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "B"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "C"]);
+    (test_env, repo_path)
+}
+
+fn get_log_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
+    test_env.jj_cmd_success(repo_path, &["log", "-T", LOG_TEMPLATE])
+}
+
+#[test]
+fn test_sign_default_revset() {
+    let (test_env, repo_path) = create_repo();
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["sign"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Signed 3 commits with key impeccable:
+      A
+      B
+      C
+    Working copy now at: C
+    Parent commit      : B
+    "#);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r#"
+    @  C [impeccable]
+    ○  B [impeccable]
+    ○  A [impeccable]
+    ◆
+    "#);
+}
+
+#[test]
+fn test_sign_with_key() {
+    let (test_env, repo_path) = create_repo();
+    test_env.jj_cmd_ok(&repo_path, &["sign", "-r", "description(A)"]);
+
+    // Re-signing replaces the signature, and descendants are rebased
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["sign", "-r", "description(A)", "--key", "other"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Signed 1 commits with key other:
+      A
+    Rebased 2 descendant commits
+    Working copy now at: C
+    Parent commit      : B
+    "#);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r#"
+    @  C
+    ○  B
+    ○  A [other]
+    ◆
+    "#);
+}
+
+#[test]
+fn test_sign_immutable() {
+    let (test_env, repo_path) = create_repo();
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["sign", "-r", "root()"]);
+    insta::assert_snapshot!(stderr, @"Error: The root commit 000000000000 is immutable");
+}
+
+#[test]
+fn test_sign_no_backend() {
+    let (test_env, repo_path) = create_repo();
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["sign", "--config", "signing.backend=none"]);
+    insta::assert_snapshot!(stderr, @r"
+    Error: No signing backend is configured
+    Hint: Set `signing.backend` to sign commits. See https://jj-vcs.github.io/jj/latest/config/#commit-signing
+    ");
+}
+
+#[test]
+fn test_unsign() {
+    let (test_env, repo_path) = create_repo();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["sign", "-r", "description(A) | description(B)"],
+    );
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["unsign", "-r", "::@"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r#"
+    Unsigned 2 commits:
+      A
+      B
+    Rebased 1 descendant commits
+    Working copy now at: C
+    Parent commit      : B
+    "#);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r#"
+    @  C
+    ○  B
+    ○  A
+    ◆
+    "#);
+
+    // Nothing left to unsign
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["unsign", "-r", "::@"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+}
@@ -14,6 +14,7 @@
 
 use std::io::Write;
 
+use crate::common::get_stderr_string;
 use crate::common::TestEnvironment;
 
 #[test]
@@ -175,6 +176,83 @@ fn test_sparse_manage_patterns() {
     "###);
 }
 
+#[test]
+fn test_sparse_set_from_file() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file1"), "contents").unwrap();
+    std::fs::write(repo_path.join("file2"), "contents").unwrap();
+    std::fs::write(repo_path.join("file3"), "contents").unwrap();
+
+    // Replace patterns with the ones read from stdin
+    let (stdout, stderr) = test_env.jj_cmd_stdin_ok(
+        &repo_path,
+        &["sparse", "set", "--clear", "--from-file", "-"],
+        "file1\n\n  file3  \n",
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Added 0 files, modified 0 files, removed 1 files
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["sparse", "list"]);
+    insta::assert_snapshot!(stdout, @r###"
+    file1
+    file3
+    "###);
+
+    // Append patterns read from a file
+    std::fs::write(test_env.env_root().join("patterns"), "file2\n").unwrap();
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "sparse",
+            "set",
+            "--remove",
+            "file1",
+            "--from-file",
+            "../patterns",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Added 1 files, modified 0 files, removed 1 files
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["sparse", "list"]);
+    insta::assert_snapshot!(stdout, @r###"
+    file2
+    file3
+    "###);
+
+    // Invalid patterns are rejected without changing the working copy
+    let assert = test_env
+        .jj_cmd_stdin(
+            &repo_path,
+            &["sparse", "set", "--clear", "--from-file", "-"],
+            "file1\n../file2\n",
+        )
+        .assert()
+        .code(1);
+    let stderr = test_env.normalize_output(&get_stderr_string(&assert));
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Failed to parse sparse pattern: ../file2
+    Caused by: Invalid component ".." in repo-relative path "../file2"
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["sparse", "list"]);
+    insta::assert_snapshot!(stdout, @r###"
+    file2
+    file3
+    "###);
+
+    // Missing file
+    let stderr =
+        test_env.jj_cmd_failure(&repo_path, &["sparse", "set", "--from-file", "nonexistent"]);
+    // The OS error message is platform-dependent
+    let first_line = stderr.lines().next().unwrap();
+    insta::assert_snapshot!(first_line, @"Error: Failed to read patterns from nonexistent");
+}
+
 #[test]
 fn test_sparse_editor_avoids_unc() {
     use std::path::PathBuf;
@@ -0,0 +1,153 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+
+use crate::common::TestEnvironment;
+
+fn create_repo() -> (TestEnvironment, PathBuf) {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::create_dir_all(repo_path.join("src").join("lib")).unwrap();
+    std::fs::create_dir(repo_path.join("docs")).unwrap();
+
+    std::fs::write(repo_path.join("README"), "readme\n").unwrap();
+    std::fs::write(repo_path.join("src").join("a.rs"), "a\nb\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "A"]);
+    std::fs::write(repo_path.join("src").join("a.rs"), "a\nc\n").unwrap();
+    std::fs::write(repo_path.join("docs").join("guide.md"), "1\n2\n3\n").unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "describe",
+            "-m",
+            "B",
+            "--author",
+            "Other <other@example.com>",
+        ],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["new"]);
+    std::fs::write(repo_path.join("src").join("lib").join("b.rs"), "b\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "C"]);
+    (test_env, repo_path)
+}
+
+#[test]
+fn test_stats() {
+    let (test_env, repo_path) = create_repo();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["stats"]);
+    insta::assert_snapshot!(stdout, @r"
+    4 commits, 8 lines added, 1 lines removed
+
+    Authors:
+          3 commits      +4      -0 Test User <test.user@example.com>
+          1 commits      +4      -1 Other <other@example.com>
+
+    Directories:
+          3 commits      +4      -1 src/
+          1 commits      +3      -0 docs/
+          1 commits      +1      -0 .
+
+    Files:
+          2 commits      +3      -1 src/a.rs
+          1 commits      +3      -0 docs/guide.md
+          1 commits      +1      -0 README
+          1 commits      +1      -0 src/lib/b.rs
+    ");
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["stats", "-r", "description(B)", "--depth=0", "--limit=1"],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    1 commits, 4 lines added, 1 lines removed
+
+    Authors:
+          1 commits      +4      -1 Other <other@example.com>
+
+    Directories:
+          1 commits      +4      -1 .
+
+    Files:
+          1 commits      +3      -0 docs/guide.md
+    ");
+}
+
+#[test]
+fn test_stats_template() {
+    let (test_env, repo_path) = create_repo();
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "stats",
+            "-r",
+            "description(C)",
+            "-T",
+            r#"kind ++ " " ++ name ++ " " ++ commits ++ "\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    1 commits, 1 lines added, 0 lines removed
+
+    Authors:
+    author Test User <test.user@example.com> 1
+
+    Directories:
+    directory src/ 1
+
+    Files:
+    file src/lib/b.rs 1
+    ");
+}
+
+#[test]
+fn test_stats_json() {
+    let (test_env, repo_path) = create_repo();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["stats", "-r", "description(C)", "--json"]);
+    insta::assert_snapshot!(stdout, @r#"
+    {
+      "commits": 1,
+      "added": 1,
+      "removed": 0,
+      "authors": [
+        {
+          "name": "Test User <test.user@example.com>",
+          "commits": 1,
+          "added": 1,
+          "removed": 0
+        }
+      ],
+      "directories": [
+        {
+          "name": "src/",
+          "commits": 1,
+          "added": 1,
+          "removed": 0
+        }
+      ],
+      "files": [
+        {
+          "name": "src/lib/b.rs",
+          "commits": 1,
+          "added": 1,
+          "removed": 0
+        }
+      ]
+    }
+    "#);
+}
@@ -522,6 +522,28 @@ fn test_templater_config_function() {
     ");
 }
 
+#[test]
+fn test_templater_hyperlink_function() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let template = r#"hyperlink("https://example.com/" ++ commit_id, "link")"#;
+
+    // Disabled by default
+    insta::assert_snapshot!(
+        get_colored_template_output(&test_env, &repo_path, "@-", template),
+        @"link");
+
+    // Hyperlinks are emitted only if the output is colorized
+    test_env.add_config("ui.hyperlinks = true");
+    insta::assert_snapshot!(
+        get_template_output(&test_env, &repo_path, "@-", template),
+        @"link");
+    insta::assert_snapshot!(
+        get_colored_template_output(&test_env, &repo_path, "@-", template),
+        @r"]8;;https://example.com/0000000000000000000000000000000000000000\link]8;;\");
+}
+
 fn get_template_output(
     test_env: &TestEnvironment,
     repo_path: &Path,
@@ -13,6 +13,7 @@
 // limitations under the License.
 use std::path::Path;
 
+use crate::common::get_stderr_string;
 use crate::common::TestEnvironment;
 
 #[test]
@@ -46,6 +47,71 @@ fn test_undo_rewrite_with_child() {
     "###);
 }
 
+#[test]
+fn test_undo_restores_rewritten_commit() {
+    // Undoing a rewrite which was followed by another rewrite of the same
+    // commit makes the original commit visible next to the latest rewrite.
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "initial"]);
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "modified"]);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "log"]);
+    let op_id_hex = stdout[3..15].to_string();
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "modified again"]);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["undo", &op_id_hex]);
+    assert!(
+        stderr.contains("Warning: Restored commits which have been rewritten since:"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("Hint: Use `jj abandon`"), "{stderr}");
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", r#"description ++ "\n""#]);
+    assert!(stdout.contains("modified again"), "{stdout}");
+    assert!(stdout.contains("initial"), "{stdout}");
+    let (stdout, _stderr) = test_env.jj_cmd_ok(&repo_path, &["resolve-divergence"]);
+    assert!(
+        stdout.contains("This copy was rewritten as copy"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_undo_interactive() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Only the first of the created bookmarks is undone
+    test_env.jj_cmd_ok(&repo_path, &["bookmark", "create", "a", "b"]);
+    let (_stdout, stderr) =
+        test_env.jj_cmd_stdin_ok(&repo_path, &["undo", "--interactive"], "y\nn\n");
+    assert!(stderr.contains("Bookmark a:\n+ qpvuntsm"), "{stderr}");
+    assert!(stderr.contains("- (absent)"), "{stderr}");
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["bookmark", "list", "-T", r#"name ++ "\n""#]);
+    insta::assert_snapshot!(stdout, @"b");
+
+    // Declining everything is an error
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "foo"]);
+    let assert = test_env
+        .jj_cmd_stdin(&repo_path, &["undo", "--interactive"], "n\nn\n")
+        .assert()
+        .code(1);
+    let stderr = get_stderr_string(&assert);
+    assert!(stderr.ends_with("Error: No changes selected\n"), "{stderr}");
+
+    // Undoing the rewrite restores the previous description, and the bookmark
+    // follows the rewritten commit back
+    test_env.jj_cmd_stdin_ok(&repo_path, &["undo", "--interactive"], "y\nn\n");
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-r", "@", "--no-graph", "-T", "description"],
+    );
+    insta::assert_snapshot!(stdout, @"");
+}
+
 #[test]
 fn test_git_push_undo() {
     let test_env = TestEnvironment::default();
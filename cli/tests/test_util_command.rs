@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use insta::assert_snapshot;
+use jj_lib::maintenance::MaintenanceState;
 
 use crate::common::strip_last_line;
 use crate::common::TestEnvironment;
@@ -59,7 +60,17 @@ fn test_gc_args() {
 
     let stderr = test_env.jj_cmd_failure(&repo_path, &["util", "gc", "--expire=foobar"]);
     insta::assert_snapshot!(stderr, @r###"
-    Error: --expire only accepts 'now'
+    Error: Invalid expiration date "foobar"
+    Caused by: expected week day or month name
+    "###);
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["util", "gc", "--expire=1 week ago"]);
+    insta::assert_snapshot!(stderr, @"");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["util", "gc", "--config=gc.expire=foo"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid expiration date "foo"
+    Caused by: expected week day or month name
     "###);
 }
 
@@ -96,6 +107,184 @@ fn test_gc_operation_log() {
     "#);
 }
 
+#[test]
+fn test_gc_dry_run() {
+    let test_env = TestEnvironment::default();
+    // Use the local backend because GitBackend::gc() depends on the git CLI.
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["init", "repo", "--config=ui.allow-init-native=true"],
+    );
+    let repo_path = test_env.env_root().join("repo");
+
+    // Nothing to remove in a new repo
+    let stdout = test_env.jj_cmd_success(&repo_path, &["util", "gc", "--dry-run", "--expire=now"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Operations to remove: 0
+    Views to remove: 0
+    Operation data to reclaim: 0 bytes
+    Commits to expire: 0
+    Object data to reclaim: unknown
+    Stale working-copy files to remove: 0
+    "###);
+
+    std::fs::write(repo_path.join("file"), "a change\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "a change"]);
+    let op_to_remove = test_env.current_operation_id(&repo_path);
+    std::fs::write(repo_path.join("file"), "another change\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "another change"]);
+    test_env.jj_cmd_ok(&repo_path, &["operation", "abandon", "..@-"]);
+    let temp_file_path = repo_path.join(".jj/working_copy/.tmpAbCdEf");
+    std::fs::write(&temp_file_path, "").unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["util", "gc", "--dry-run", "--expire=now"]);
+    assert!(stdout.contains(&format!("  {}\n", &op_to_remove[..12])));
+    assert!(stdout.contains("Stale working-copy files to remove: 1\n"));
+    // Nothing is removed
+    test_env.jj_cmd_ok(&repo_path, &["debug", "operation", &op_to_remove]);
+    assert!(temp_file_path.exists());
+
+    test_env.jj_cmd_ok(&repo_path, &["util", "gc", "--expire=now"]);
+    assert!(!temp_file_path.exists());
+    let stdout = test_env.jj_cmd_success(&repo_path, &["util", "gc", "--dry-run", "--expire=now"]);
+    assert!(stdout.starts_with("Operations to remove: 0\n"));
+}
+
+#[test]
+fn test_gc_op_log_retention() {
+    let test_env = TestEnvironment::default();
+    // Use the local backend because GitBackend::gc() depends on the git CLI.
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["init", "repo", "--config=ui.allow-init-native=true"],
+    );
+    let repo_path = test_env.env_root().join("repo");
+    let count_ops = || {
+        test_env
+            .jj_cmd_success(
+                &repo_path,
+                &["op", "log", "--no-graph", "-T", r#"id ++ "\n""#],
+            )
+            .lines()
+            .count()
+    };
+
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "commit 1"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "commit 2"]);
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "commit 3"]);
+    assert_eq!(count_ops(), 5);
+
+    // All operations are within the limit
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["util", "gc", "--config=op-log.keep-days=100000"],
+    );
+    insta::assert_snapshot!(stderr, @"");
+    assert_eq!(count_ops(), 5);
+
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["util", "gc", "--dry-run", "--config=op-log.keep-count=2"],
+    );
+    assert!(stdout.starts_with("Operations to abandon: 2\n"));
+    assert_eq!(count_ops(), 5);
+
+    // Old operations are abandoned, and the working-copy operation is updated
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["util", "gc", "--config=op-log.keep-count=2"]);
+    insta::assert_snapshot!(stderr, @"Abandoned 2 operations and reparented 2 descendant operations.");
+    assert_eq!(count_ops(), 3);
+    test_env.jj_cmd_ok(&repo_path, &["status"]);
+}
+
+#[test]
+fn test_maintenance_run() {
+    let test_env = TestEnvironment::default();
+    // Use the local backend because GitBackend::gc() depends on the git CLI.
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["init", "repo", "--config=ui.allow-init-native=true"],
+    );
+    let repo_path = test_env.env_root().join("repo");
+    let state_path = repo_path.join(".jj/repo/maintenance");
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["util", "maintenance", "run"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Pruned expired operations and objects.
+    Removed 0 unused index files.
+    Loaded 2 commits into caches.
+    "###);
+    assert!(state_path.exists());
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["util", "maintenance", "run", "--task=cache", "--task=index"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Removed 0 unused index files.
+    Loaded 2 commits into caches.
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["util", "maintenance", "run", "--at-op=@-"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Cannot run maintenance from a non-head operation
+    "###);
+
+    // Concurrent runs are skipped
+    let state = MaintenanceState::new(&repo_path.join(".jj/repo"));
+    let _lock = state.try_lock().unwrap().unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["util", "maintenance", "run"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Maintenance is already running.
+    "###);
+}
+
+#[test]
+fn test_maintenance_register() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(
+        test_env.env_root(),
+        &["init", "repo", "--config=ui.allow-init-native=true"],
+    );
+    let repo_path = test_env.env_root().join("repo");
+
+    // Automatic maintenance is disabled by default
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["util", "maintenance", "run", "--auto"]);
+    insta::assert_snapshot!(stderr, @"");
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["util", "maintenance", "register"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Enabled automatic maintenance for this repo.
+    "###);
+    let stdout = test_env.jj_cmd_success(&repo_path, &["config", "get", "maintenance.auto"]);
+    insta::assert_snapshot!(stdout, @r###"
+    true
+    "###);
+    assert!(repo_path.join(".jj/repo/maintenance").exists());
+
+    // Not due right after registering
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["util", "maintenance", "run", "--auto"]);
+    insta::assert_snapshot!(stderr, @"");
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "util",
+            "maintenance",
+            "run",
+            "--auto",
+            "--config=maintenance.auto-interval-hours=0",
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Pruned expired operations and objects.
+    Removed 0 unused index files.
+    Loaded 2 commits into caches.
+    "###);
+}
+
 #[test]
 fn test_shell_completions() {
     #[track_caller]
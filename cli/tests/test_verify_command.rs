@@ -0,0 +1,90 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+
+use crate::common::get_stderr_string;
+use crate::common::get_stdout_string;
+use crate::common::TestEnvironment;
+
+fn create_repo() -> (TestEnvironment, PathBuf) {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(
+        r#"
+    templates.commit_summary = 'description.first_line()'
+    signing.backend = "test"
+    signing.key = "impeccable"
+    "#,
+    );
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "A"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "B"]);
+    (test_env, repo_path)
+}
+
+#[test]
+fn test_verify_signed() {
+    let (test_env, repo_path) = create_repo();
+    test_env.jj_cmd_ok(&repo_path, &["sign", "-r", "::@ ~ root()"]);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["verify", "-r", "::@ ~ root()"]);
+    insta::assert_snapshot!(stdout, @r"
+    good     B (test-display, key impeccable)
+    good     A (test-display, key impeccable)
+    ");
+    insta::assert_snapshot!(stderr, @"");
+}
+
+#[test]
+fn test_verify_unsigned() {
+    let (test_env, repo_path) = create_repo();
+    test_env.jj_cmd_ok(&repo_path, &["sign", "-r", "description(A)"]);
+
+    let assert = test_env
+        .jj_cmd(&repo_path, &["verify", "-r", "::@ ~ root()"])
+        .assert()
+        .code(1);
+    insta::assert_snapshot!(get_stdout_string(&assert), @r"
+    unsigned B
+    good     A (test-display, key impeccable)
+    ");
+    insta::assert_snapshot!(get_stderr_string(&assert), @r"
+    Error: 1 commit doesn't have a good signature
+    ");
+}
+
+#[test]
+fn test_verify_json() {
+    let (test_env, repo_path) = create_repo();
+    test_env.jj_cmd_ok(&repo_path, &["sign", "-r", "description(A)"]);
+
+    let assert = test_env
+        .jj_cmd(&repo_path, &["verify", "-r", "::@ ~ root()", "--json"])
+        .assert()
+        .code(1);
+    let results: serde_json::Value = serde_json::from_str(&get_stdout_string(&assert)).unwrap();
+    let results = results.as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["status"], "unsigned");
+    assert_eq!(results[0]["key"], serde_json::Value::Null);
+    assert_eq!(results[1]["status"], "good");
+    assert_eq!(results[1]["key"], "impeccable");
+    assert_eq!(results[1]["display"], "test-display");
+    assert_eq!(
+        results[1]["change_id"].as_str().unwrap().len(),
+        32,
+        "full change id is reported"
+    );
+}
@@ -1267,6 +1267,62 @@ fn test_workspaces_rename_workspace() {
     "###);
 }
 
+#[test]
+fn test_workspaces_working_copies_revset() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "main"]);
+    let main_path = test_env.env_root().join("main");
+    test_env.jj_cmd_ok(
+        &main_path,
+        &["workspace", "add", "--name", "second", "../secondary"],
+    );
+
+    let template = r#"separate(" ", commit_id.short(), working_copies) ++ "\n""#;
+    let stdout = test_env.jj_cmd_success(
+        &main_path,
+        &[
+            "log",
+            "--no-graph",
+            "-T",
+            template,
+            "-r",
+            "working_copies()",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    57d63245a308 second@
+    230dd059e1b0 default@
+    ");
+
+    let stdout = test_env.jj_cmd_success(
+        &main_path,
+        &[
+            "log",
+            "--no-graph",
+            "-T",
+            template,
+            "-r",
+            "working_copies(sec)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r"
+    57d63245a308 second@
+    ");
+
+    let stdout = test_env.jj_cmd_success(
+        &main_path,
+        &[
+            "log",
+            "--no-graph",
+            "-T",
+            template,
+            "-r",
+            "working_copies(exact:nope)",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+}
+
 fn get_log_output(test_env: &TestEnvironment, cwd: &Path) -> String {
     let template = r#"
     separate(" ",
@@ -0,0 +1,87 @@
+/*
+ * Copyright 2026 The Jujutsu Authors
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/*
+ * C API of jj-lib, provided by the jj-lib-capi crate. See lib/capi/src/lib.rs
+ * for the documentation of each function.
+ */
+
+#ifndef JJ_LIB_H
+#define JJ_LIB_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum {
+  JJ_STATUS_OK = 0,
+  JJ_STATUS_ERROR = 1,
+  JJ_STATUS_PANIC = 2,
+} JjStatus;
+
+typedef enum {
+  JJ_DIFF_STATUS_ADDED = 0,
+  JJ_DIFF_STATUS_REMOVED = 1,
+  JJ_DIFF_STATUS_MODIFIED = 2,
+} JjDiffStatus;
+
+typedef struct JjRepo JjRepo;
+
+typedef struct {
+  char *commit_id;
+  char *change_id;
+  char **parent_ids;
+  size_t num_parents;
+  char *description;
+  char *author_name;
+  char *author_email;
+  int64_t author_timestamp;
+  int author_tz_offset;
+  char *committer_name;
+  char *committer_email;
+  int64_t committer_timestamp;
+  int committer_tz_offset;
+} JjCommitInfo;
+
+typedef int (*JjCommitIdCallback)(const char *commit_id, void *user_data);
+typedef int (*JjDiffCallback)(const char *path, JjDiffStatus status,
+                              void *user_data);
+
+const char *jj_last_error(void);
+
+JjStatus jj_repo_open(const char *path, JjRepo **out);
+JjStatus jj_repo_reload(JjRepo *repo);
+void jj_repo_free(JjRepo *repo);
+
+JjStatus jj_resolve_revset(const JjRepo *repo, const char *revset,
+                           JjCommitIdCallback callback, void *user_data);
+
+JjStatus jj_commit_info(const JjRepo *repo, const char *commit_id,
+                        JjCommitInfo *out);
+void jj_commit_info_free(JjCommitInfo *info);
+
+JjStatus jj_diff_commits(const JjRepo *repo, const char *from_commit_id,
+                         const char *to_commit_id, JjDiffCallback callback,
+                         void *user_data);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* JJ_LIB_H */
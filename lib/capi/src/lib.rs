@@ -0,0 +1,458 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! C API for linking jj-lib from non-Rust programs.
+//!
+//! Every fallible function returns a [`JjStatus`]. If the call failed, a
+//! description of the error can be obtained from [`jj_last_error()`]. Strings
+//! are NUL-terminated UTF-8. Objects returned by the library must be released
+//! by the corresponding `jj_*_free()` function. The declarations are available
+//! in `include/jj_lib.h`.
+//!
+//! A shared library can be built with `cargo build -p jj-lib-capi --release`.
+
+use std::cell::RefCell;
+use std::error::Error;
+use std::ffi::c_char;
+use std::ffi::c_int;
+use std::ffi::c_void;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::iter;
+use std::panic;
+use std::path::Path;
+use std::ptr;
+use std::sync::Arc;
+
+use futures::StreamExt as _;
+use itertools::Itertools as _;
+use jj_lib::backend::CommitId;
+use jj_lib::backend::Signature;
+use jj_lib::commit::Commit;
+use jj_lib::config::StackedConfig;
+use jj_lib::hex_util::encode_reverse_hex;
+use jj_lib::matchers::EverythingMatcher;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo::StoreFactories;
+use jj_lib::repo_path::RepoPathUiConverter;
+use jj_lib::revset;
+use jj_lib::revset::DefaultSymbolResolver;
+use jj_lib::revset::RevsetAliasesMap;
+use jj_lib::revset::RevsetDiagnostics;
+use jj_lib::revset::RevsetExtensions;
+use jj_lib::revset::RevsetParseContext;
+use jj_lib::revset::RevsetWorkspaceContext;
+use jj_lib::rewrite::merge_commit_trees;
+use jj_lib::settings::UserSettings;
+use jj_lib::workspace::default_working_copy_factories;
+use jj_lib::workspace::Workspace;
+use pollster::FutureExt as _;
+
+type CapiResult<T> = Result<T, Box<dyn Error>>;
+
+/// Outcome of a C API call.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JjStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// The call failed.
+    Error = 1,
+    /// The library panicked. Objects passed to the call shouldn't be used
+    /// other than to free them.
+    Panic = 2,
+}
+
+/// How a path differs between two trees.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JjDiffStatus {
+    /// The path only exists in the right tree.
+    Added = 0,
+    /// The path only exists in the left tree.
+    Removed = 1,
+    /// The path exists in both trees with different content or type.
+    Modified = 2,
+}
+
+/// A workspace and its repo, loaded at the latest operation.
+pub struct JjRepo {
+    workspace: Workspace,
+    repo: Arc<ReadonlyRepo>,
+    settings: UserSettings,
+    path_converter: RepoPathUiConverter,
+}
+
+/// Metadata of a commit. Must be released by `jj_commit_info_free()`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct JjCommitInfo {
+    /// Commit id in hex.
+    pub commit_id: *mut c_char,
+    /// Change id in the reverse hex format used by jj.
+    pub change_id: *mut c_char,
+    /// Array of parent commit ids in hex.
+    pub parent_ids: *mut *mut c_char,
+    /// Number of entries in `parent_ids`.
+    pub num_parents: usize,
+    /// Full commit description.
+    pub description: *mut c_char,
+    /// Author name.
+    pub author_name: *mut c_char,
+    /// Author email.
+    pub author_email: *mut c_char,
+    /// Author timestamp in milliseconds since the epoch.
+    pub author_timestamp: i64,
+    /// Author time zone offset in minutes.
+    pub author_tz_offset: c_int,
+    /// Committer name.
+    pub committer_name: *mut c_char,
+    /// Committer email.
+    pub committer_email: *mut c_char,
+    /// Committer timestamp in milliseconds since the epoch.
+    pub committer_timestamp: i64,
+    /// Committer time zone offset in minutes.
+    pub committer_tz_offset: c_int,
+}
+
+/// Called with each commit id in hex. Returning non-zero stops the iteration.
+pub type JjCommitIdCallback =
+    extern "C" fn(commit_id: *const c_char, user_data: *mut c_void) -> c_int;
+
+/// Called with each changed path. Returning non-zero stops the iteration.
+pub type JjDiffCallback =
+    extern "C" fn(path: *const c_char, status: JjDiffStatus, user_data: *mut c_void) -> c_int;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Runs `f`, recording the error message if it fails or panics.
+fn run(f: impl FnOnce() -> CapiResult<()>) -> JjStatus {
+    let (status, message) = match panic::catch_unwind(panic::AssertUnwindSafe(f)) {
+        Ok(Ok(())) => return JjStatus::Ok,
+        Ok(Err(err)) => (JjStatus::Error, format_error(err.as_ref())),
+        Err(_) => (JjStatus::Panic, "Internal error in jj-lib".to_owned()),
+    };
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(to_c_string(message)));
+    status
+}
+
+fn format_error(err: &dyn Error) -> String {
+    iter::successors(Some(err), |err| err.source()).join(": ")
+}
+
+/// Converts `s` to a C string, dropping any NUL bytes.
+fn to_c_string(s: impl Into<Vec<u8>>) -> CString {
+    let mut bytes = s.into();
+    bytes.retain(|&b| b != 0);
+    CString::new(bytes).unwrap()
+}
+
+fn into_raw_string(s: impl Into<Vec<u8>>) -> *mut c_char {
+    to_c_string(s).into_raw()
+}
+
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL-terminated string which outlives the
+/// returned reference.
+unsafe fn read_str<'a>(ptr: *const c_char, name: &str) -> CapiResult<&'a str> {
+    if ptr.is_null() {
+        return Err(format!("{name} must not be null").into());
+    }
+    Ok(CStr::from_ptr(ptr).to_str()?)
+}
+
+/// # Safety
+///
+/// `repo` must be null or a pointer returned by `jj_repo_open()`.
+unsafe fn read_repo<'a>(repo: *const JjRepo) -> CapiResult<&'a JjRepo> {
+    repo.as_ref().ok_or_else(|| "repo must not be null".into())
+}
+
+fn get_commit(repo: &JjRepo, hex: &str) -> CapiResult<Commit> {
+    let id = CommitId::try_from_hex(hex).map_err(|_| format!("Invalid commit id: {hex}"))?;
+    Ok(repo.repo.store().get_commit(&id)?)
+}
+
+/// Returns the message of the last failed call on this thread, or null if no
+/// call has failed.
+///
+/// The string is owned by the library and stays valid until the next failed
+/// call on the same thread.
+#[no_mangle]
+pub extern "C" fn jj_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Loads the workspace at `path` and its repo at the latest operation.
+///
+/// Only the built-in configuration is used, so revset aliases defined by the
+/// `jj` CLI aren't available. The working copy isn't snapshotted.
+///
+/// # Safety
+///
+/// `path` must be a NUL-terminated string, and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn jj_repo_open(path: *const c_char, out: *mut *mut JjRepo) -> JjStatus {
+    run(|| {
+        let path = read_str(path, "path")?;
+        if out.is_null() {
+            return Err("out must not be null".into());
+        }
+        let settings = UserSettings::from_config(StackedConfig::with_defaults())?;
+        let workspace = Workspace::load(
+            &settings,
+            Path::new(path),
+            &StoreFactories::default(),
+            &default_working_copy_factories(),
+        )?;
+        let repo = workspace.repo_loader().load_at_head()?;
+        let path_converter = RepoPathUiConverter::Fs {
+            cwd: workspace.workspace_root().to_owned(),
+            base: workspace.workspace_root().to_owned(),
+        };
+        let repo = JjRepo {
+            workspace,
+            repo,
+            settings,
+            path_converter,
+        };
+        *out = Box::into_raw(Box::new(repo));
+        Ok(())
+    })
+}
+
+/// Reloads the repo at the latest operation.
+///
+/// # Safety
+///
+/// `repo` must be a pointer returned by `jj_repo_open()`.
+#[no_mangle]
+pub unsafe extern "C" fn jj_repo_reload(repo: *mut JjRepo) -> JjStatus {
+    run(|| {
+        let repo = repo.as_mut().ok_or("repo must not be null")?;
+        repo.repo = repo.workspace.repo_loader().load_at_head()?;
+        Ok(())
+    })
+}
+
+/// Releases a repo returned by `jj_repo_open()`. Null is ignored.
+///
+/// # Safety
+///
+/// `repo` must be null or a pointer returned by `jj_repo_open()` which hasn't
+/// been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn jj_repo_free(repo: *mut JjRepo) {
+    if !repo.is_null() {
+        drop(Box::from_raw(repo));
+    }
+}
+
+/// Evaluates `revset` and calls `callback` with the id of each commit, in
+/// reverse topological order.
+///
+/// # Safety
+///
+/// `repo` must be a pointer returned by `jj_repo_open()`, and `revset` must be
+/// a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn jj_resolve_revset(
+    repo: *const JjRepo,
+    revset: *const c_char,
+    callback: JjCommitIdCallback,
+    user_data: *mut c_void,
+) -> JjStatus {
+    run(|| {
+        let repo = read_repo(repo)?;
+        let revset_str = read_str(revset, "revset")?;
+        let aliases_map = RevsetAliasesMap::new();
+        let extensions = RevsetExtensions::default();
+        let workspace_context = RevsetWorkspaceContext {
+            path_converter: &repo.path_converter,
+            workspace_id: repo.workspace.workspace_id(),
+        };
+        let context = RevsetParseContext::new(
+            &aliases_map,
+            repo.settings.user_email(),
+            chrono::Local::now().into(),
+            &extensions,
+            Some(workspace_context),
+        );
+        let expression = revset::parse(&mut RevsetDiagnostics::new(), revset_str, &context)?;
+        let symbol_resolver =
+            DefaultSymbolResolver::new(repo.repo.as_ref(), extensions.symbol_resolvers());
+        let revset = expression
+            .resolve_user_expression(repo.repo.as_ref(), &symbol_resolver)?
+            .evaluate(repo.repo.as_ref())?;
+        for commit_id in revset.iter() {
+            let commit_id = to_c_string(commit_id?.hex());
+            if callback(commit_id.as_ptr(), user_data) != 0 {
+                break;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Reads the metadata of the commit with the full hex id `commit_id`.
+///
+/// # Safety
+///
+/// `repo` must be a pointer returned by `jj_repo_open()`, `commit_id` must be
+/// a NUL-terminated string, and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn jj_commit_info(
+    repo: *const JjRepo,
+    commit_id: *const c_char,
+    out: *mut JjCommitInfo,
+) -> JjStatus {
+    run(|| {
+        let repo = read_repo(repo)?;
+        let commit = get_commit(repo, read_str(commit_id, "commit_id")?)?;
+        if out.is_null() {
+            return Err("out must not be null".into());
+        }
+        let parent_ids: Box<[*mut c_char]> = commit
+            .parent_ids()
+            .iter()
+            .map(|id| into_raw_string(id.hex()))
+            .collect();
+        let Signature {
+            name: author_name,
+            email: author_email,
+            timestamp: author_timestamp,
+        } = commit.author();
+        let Signature {
+            name: committer_name,
+            email: committer_email,
+            timestamp: committer_timestamp,
+        } = commit.committer();
+        out.write(JjCommitInfo {
+            commit_id: into_raw_string(commit.id().hex()),
+            change_id: into_raw_string(encode_reverse_hex(commit.change_id().as_bytes())),
+            num_parents: parent_ids.len(),
+            parent_ids: Box::into_raw(parent_ids).cast(),
+            description: into_raw_string(commit.description()),
+            author_name: into_raw_string(author_name.as_str()),
+            author_email: into_raw_string(author_email.as_str()),
+            author_timestamp: author_timestamp.timestamp.0,
+            author_tz_offset: author_timestamp.tz_offset,
+            committer_name: into_raw_string(committer_name.as_str()),
+            committer_email: into_raw_string(committer_email.as_str()),
+            committer_timestamp: committer_timestamp.timestamp.0,
+            committer_tz_offset: committer_timestamp.tz_offset,
+        });
+        Ok(())
+    })
+}
+
+/// Releases the strings owned by `info`, which was filled by
+/// `jj_commit_info()`. Null is ignored.
+///
+/// # Safety
+///
+/// `info` must be null or point to a `JjCommitInfo` filled by
+/// `jj_commit_info()` which hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn jj_commit_info_free(info: *mut JjCommitInfo) {
+    let Some(info) = info.as_mut() else {
+        return;
+    };
+    let parent_ids = Box::from_raw(ptr::slice_from_raw_parts_mut(
+        info.parent_ids,
+        info.num_parents,
+    ));
+    let strings = [
+        info.commit_id,
+        info.change_id,
+        info.description,
+        info.author_name,
+        info.author_email,
+        info.committer_name,
+        info.committer_email,
+    ];
+    for s in parent_ids.iter().chain(&strings) {
+        drop(CString::from_raw(*s));
+    }
+    info.parent_ids = ptr::null_mut();
+    info.num_parents = 0;
+    info.commit_id = ptr::null_mut();
+    info.change_id = ptr::null_mut();
+    info.description = ptr::null_mut();
+    info.author_name = ptr::null_mut();
+    info.author_email = ptr::null_mut();
+    info.committer_name = ptr::null_mut();
+    info.committer_email = ptr::null_mut();
+}
+
+/// Compares the trees of two commits and calls `callback` with each changed
+/// path, in the repo-internal format using `/` as separator.
+///
+/// If `from_commit_id` is null, the tree of `to_commit_id` is compared to its
+/// parents, like `jj diff -r` does.
+///
+/// # Safety
+///
+/// `repo` must be a pointer returned by `jj_repo_open()`, `from_commit_id`
+/// must be null or a NUL-terminated string, and `to_commit_id` must be a
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn jj_diff_commits(
+    repo: *const JjRepo,
+    from_commit_id: *const c_char,
+    to_commit_id: *const c_char,
+    callback: JjDiffCallback,
+    user_data: *mut c_void,
+) -> JjStatus {
+    run(|| {
+        let repo = read_repo(repo)?;
+        let to_commit = get_commit(repo, read_str(to_commit_id, "to_commit_id")?)?;
+        let from_tree = if from_commit_id.is_null() {
+            let parents: Vec<_> = to_commit.parents().try_collect()?;
+            merge_commit_trees(repo.repo.as_ref(), &parents)?
+        } else {
+            get_commit(repo, read_str(from_commit_id, "from_commit_id")?)?.tree()?
+        };
+        let to_tree = to_commit.tree()?;
+        let mut diff_stream = from_tree.diff_stream(&to_tree, &EverythingMatcher);
+        async {
+            while let Some(entry) = diff_stream.next().await {
+                let (before, after) = entry.values?;
+                let status = if before.is_absent() {
+                    JjDiffStatus::Added
+                } else if after.is_absent() {
+                    JjDiffStatus::Removed
+                } else {
+                    JjDiffStatus::Modified
+                };
+                let path = to_c_string(entry.path.as_internal_file_string());
+                if callback(path.as_ptr(), status, user_data) != 0 {
+                    break;
+                }
+            }
+            Ok::<_, Box<dyn Error>>(())
+        }
+        .block_on()
+    })
+}
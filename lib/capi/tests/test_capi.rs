@@ -0,0 +1,148 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ffi::c_char;
+use std::ffi::c_int;
+use std::ffi::c_void;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::mem::MaybeUninit;
+use std::path::Path;
+use std::ptr;
+
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPath;
+use jj_lib_capi::jj_commit_info;
+use jj_lib_capi::jj_commit_info_free;
+use jj_lib_capi::jj_diff_commits;
+use jj_lib_capi::jj_last_error;
+use jj_lib_capi::jj_repo_free;
+use jj_lib_capi::jj_repo_open;
+use jj_lib_capi::jj_resolve_revset;
+use jj_lib_capi::JjDiffStatus;
+use jj_lib_capi::JjRepo;
+use jj_lib_capi::JjStatus;
+use testutils::create_tree;
+use testutils::TestRepoBackend;
+use testutils::TestWorkspace;
+
+extern "C" fn collect_commit_id(commit_id: *const c_char, user_data: *mut c_void) -> c_int {
+    let commit_ids = unsafe { &mut *user_data.cast::<Vec<String>>() };
+    commit_ids.push(to_string(commit_id));
+    0
+}
+
+extern "C" fn collect_diff(
+    path: *const c_char,
+    status: JjDiffStatus,
+    user_data: *mut c_void,
+) -> c_int {
+    let entries = unsafe { &mut *user_data.cast::<Vec<(String, JjDiffStatus)>>() };
+    entries.push((to_string(path), status));
+    0
+}
+
+fn to_string(s: *const c_char) -> String {
+    unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_owned()
+}
+
+fn open_repo(workspace_root: &Path) -> *mut JjRepo {
+    let path = CString::new(workspace_root.to_str().unwrap()).unwrap();
+    let mut repo = ptr::null_mut();
+    let status = unsafe { jj_repo_open(path.as_ptr(), &mut repo) };
+    assert_eq!(status, JjStatus::Ok);
+    repo
+}
+
+#[test]
+fn test_capi() {
+    let test_workspace = TestWorkspace::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_workspace.repo;
+    let file1_path = RepoPath::from_internal_string("file1");
+    let file2_path = RepoPath::from_internal_string("file2");
+
+    let tree1 = create_tree(repo, &[(file1_path, "a\n")]);
+    let tree2 = create_tree(repo, &[(file1_path, "b\n"), (file2_path, "c\n")]);
+    let mut tx = repo.start_transaction();
+    let commit1 = tx
+        .repo_mut()
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree1.id())
+        .set_description("first\n")
+        .write()
+        .unwrap();
+    let commit2 = tx
+        .repo_mut()
+        .new_commit(vec![commit1.id().clone()], tree2.id())
+        .set_description("second\n")
+        .write()
+        .unwrap();
+    tx.commit("test").unwrap();
+
+    let capi_repo = open_repo(test_workspace.workspace.workspace_root());
+
+    // Revsets are evaluated against the latest operation
+    let revset = CString::new(format!("{}::", commit1.id().hex())).unwrap();
+    let mut commit_ids: Vec<String> = vec![];
+    let status = unsafe {
+        jj_resolve_revset(
+            capi_repo,
+            revset.as_ptr(),
+            collect_commit_id,
+            ptr::from_mut(&mut commit_ids).cast(),
+        )
+    };
+    assert_eq!(status, JjStatus::Ok);
+    assert_eq!(commit_ids, [commit2.id().hex(), commit1.id().hex()]);
+
+    let commit_id = CString::new(commit2.id().hex()).unwrap();
+    let mut info = MaybeUninit::uninit();
+    let status = unsafe { jj_commit_info(capi_repo, commit_id.as_ptr(), info.as_mut_ptr()) };
+    assert_eq!(status, JjStatus::Ok);
+    let mut info = unsafe { info.assume_init() };
+    assert_eq!(to_string(info.commit_id), commit2.id().hex());
+    assert_eq!(to_string(info.description), "second\n");
+    assert_eq!(to_string(info.author_name), "Test User");
+    assert_eq!(info.num_parents, 1);
+    assert_eq!(to_string(unsafe { *info.parent_ids }), commit1.id().hex());
+    unsafe { jj_commit_info_free(&mut info) };
+
+    // Without a "from" commit, the diff is against the parents
+    let mut entries: Vec<(String, JjDiffStatus)> = vec![];
+    let status = unsafe {
+        jj_diff_commits(
+            capi_repo,
+            ptr::null(),
+            commit_id.as_ptr(),
+            collect_diff,
+            ptr::from_mut(&mut entries).cast(),
+        )
+    };
+    assert_eq!(status, JjStatus::Ok);
+    assert_eq!(
+        entries,
+        [
+            ("file1".to_owned(), JjDiffStatus::Modified),
+            ("file2".to_owned(), JjDiffStatus::Added),
+        ]
+    );
+
+    let bad_commit_id = CString::new("zzz").unwrap();
+    let mut info = MaybeUninit::uninit();
+    let status = unsafe { jj_commit_info(capi_repo, bad_commit_id.as_ptr(), info.as_mut_ptr()) };
+    assert_eq!(status, JjStatus::Error);
+    assert_eq!(to_string(jj_last_error()), "Invalid commit id: zzz");
+
+    unsafe { jj_repo_free(capi_repo) };
+}
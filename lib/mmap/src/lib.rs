@@ -0,0 +1,97 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Read-only memory maps of immutable files.
+//!
+//! Mapping a file requires unsafe code, which is kept out of `jj-lib` so that
+//! crate can forbid it.
+
+#![warn(missing_docs)]
+
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+/// Read-only memory map of a file which is never modified once written.
+#[derive(Debug)]
+pub struct ImmutableMmap {
+    mmap: Mmap,
+}
+
+impl ImmutableMmap {
+    /// Maps the whole `file` into memory.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this or any other
+    /// process, while it is mapped. Otherwise the mapped contents may change
+    /// under the returned slice, or reading them may crash the process.
+    pub unsafe fn map(file: &File) -> io::Result<Self> {
+        // SAFETY: Upheld by the caller.
+        let mmap = unsafe { Mmap::map(file)? };
+        Ok(ImmutableMmap { mmap })
+    }
+
+    /// Opens the content-addressed file at `path` read-only and maps it into
+    /// memory.
+    ///
+    /// The file name must be the hex-encoded hash of the contents, such as an
+    /// index segment file. Content-addressed files are written to a temporary
+    /// file and atomically renamed into place, and are never modified
+    /// afterwards. They may be deleted, which doesn't affect existing maps.
+    pub fn map_content_addressed(path: &Path) -> io::Result<Self> {
+        let is_content_addressed =
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    !name.is_empty()
+                        && name
+                            .bytes()
+                            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+                });
+        if !is_content_addressed {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Not a content-addressed file: {}", path.display()),
+            ));
+        }
+        let file = File::open(path)?;
+        // SAFETY: Content-addressed files are never modified once written.
+        unsafe { Self::map(&file) }
+    }
+}
+
+impl Deref for ImmutableMmap {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.mmap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_content_addressed_rejects_other_files() {
+        for path in ["", "index.lock", "0123abcX", "dir/type"] {
+            let err = ImmutableMmap::map_content_addressed(Path::new(path)).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{path:?}");
+        }
+    }
+}
@@ -18,32 +18,39 @@
 //! TODO: Add support for different blame layers with a trait in the future.
 //! Like commit metadata and more.
 
-use std::collections::hash_map;
 use std::collections::HashMap;
+use std::io::Read as _;
 use std::iter;
 use std::ops::Range;
 use std::rc::Rc;
+use std::sync::Arc;
 
 use bstr::BStr;
 use bstr::BString;
+use futures::future::try_join_all;
+use futures::TryStreamExt as _;
 use itertools::Itertools as _;
 use pollster::FutureExt;
 
 use crate::backend::BackendError;
 use crate::backend::CommitId;
 use crate::commit::Commit;
-use crate::conflicts::materialize_merge_result_to_bytes;
 use crate::conflicts::materialize_tree_value;
 use crate::conflicts::ConflictMarkerStyle;
+use crate::conflicts::MaterializedMergeReader;
 use crate::conflicts::MaterializedTreeValue;
+use crate::diff::find_line_ranges;
+use crate::diff::CompareBytesIgnoreAllWhitespace;
 use crate::diff::Diff;
 use crate::diff::DiffHunkKind;
 use crate::fileset::FilesetExpression;
 use crate::graph::GraphEdge;
 use crate::graph::GraphEdgeType;
+use crate::graph::GraphNode;
 use crate::merged_tree::MergedTree;
 use crate::repo::Repo;
 use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
 use crate::revset::ResolvedRevsetExpression;
 use crate::revset::RevsetEvaluationError;
 use crate::revset::RevsetExpression;
@@ -131,15 +138,73 @@ impl Source {
         }
     }
 
-    fn load(commit: &Commit, file_path: &RepoPath) -> Result<Self, BackendError> {
-        let tree = commit.tree()?;
-        let text = get_file_contents(commit.store(), file_path, &tree)?;
+    async fn load(commit: &Commit, file_path: &RepoPath) -> Result<Self, BackendError> {
+        let tree = commit.tree_async().await?;
+        let text = get_file_contents(commit.store(), file_path, &tree).await?;
         Ok(Self::new(text))
     }
 
-    fn fill_line_map(&mut self) {
+    /// Loads sources at the given commits concurrently.
+    async fn load_many(
+        store: &Arc<Store>,
+        commit_ids: &[CommitId],
+        file_path: &RepoPath,
+    ) -> Result<Vec<Self>, BackendError> {
+        let commits = store.get_commits_async(commit_ids).await?;
+        try_join_all(commits.iter().map(|commit| Self::load(commit, file_path))).await
+    }
+
+    fn num_lines(&self) -> usize {
+        self.text.split_inclusive(|b| *b == b'\n').count()
+    }
+
+    /// Removes lines in common with the `parent_text` from the line map, and
+    /// returns the line map of these lines in the parent.
+    fn take_same_lines(
+        &mut self,
+        parent_text: &[u8],
+        ignore_whitespace: bool,
+    ) -> Vec<(usize, usize)> {
+        // For two versions of the same file, for all the lines in common,
+        // overwrite the new mapping in the results for the new commit. Let's
+        // say I have a file in commit A and commit B. We know that according to
+        // local line_map, in commit A, line 3 corresponds to line 7 of the
+        // original file. Now, line 3 in Commit A corresponds to line 6 in
+        // commit B. Then, we update local line_map to say that "Commit B line 6
+        // goes to line 7 of the original file". We repeat this for all lines in
+        // common in the two commits.
+        let mut current_lines = self.line_map.iter().copied().peekable();
+        let mut new_current_line_map = Vec::new();
+        let mut new_parent_line_map = Vec::new();
+        copy_same_lines_with(
+            &self.text,
+            parent_text,
+            ignore_whitespace,
+            |current_start, parent_start, count| {
+                new_current_line_map
+                    .extend(current_lines.peeking_take_while(|&(cur, _)| cur < current_start));
+                while let Some((current, original)) =
+                    current_lines.next_if(|&(cur, _)| cur < current_start + count)
+                {
+                    let parent = parent_start + (current - current_start);
+                    new_parent_line_map.push((parent, original));
+                }
+            },
+        );
+        new_current_line_map.extend(current_lines);
+        self.line_map = new_current_line_map;
+        new_parent_line_map
+    }
+
+    /// Maps lines to themselves. If `line_range` is specified, only the lines
+    /// in that range will be tracked.
+    fn fill_line_map(&mut self, line_range: Option<&Range<usize>>) {
         let lines = self.text.split_inclusive(|b| *b == b'\n');
-        self.line_map = lines.enumerate().map(|(i, _)| (i, i)).collect();
+        self.line_map = lines
+            .enumerate()
+            .map(|(i, _)| (i, i))
+            .filter(|(i, _)| line_range.map_or(true, |range| range.contains(i)))
+            .collect();
     }
 }
 
@@ -147,6 +212,22 @@ impl Source {
 /// original file.
 type OriginalLineMap = Vec<Option<CommitId>>;
 
+/// Options for [`get_annotation_for_file()`].
+#[derive(Clone, Debug, Default)]
+pub struct AnnotateOptions {
+    /// Range of lines (0-based line numbers at the starting commit) to
+    /// attribute. If specified, the other lines are left unresolved, and the
+    /// search stops as soon as all the requested lines are resolved.
+    pub line_range: Option<Range<usize>>,
+    /// Whether to continue attributing lines to the source file when the file
+    /// was copied or renamed from another path.
+    pub follow_renames: bool,
+    /// Whether to ignore whitespace when matching lines between revisions, so
+    /// that lines are attributed to the commits which introduced their
+    /// non-whitespace content.
+    pub ignore_whitespace: bool,
+}
+
 /// Get line by line annotations for a specific file path in the repo.
 ///
 /// The `domain` expression narrows the range of ancestors to search. It will be
@@ -159,9 +240,17 @@ pub fn get_annotation_for_file(
     starting_commit: &Commit,
     domain: &Rc<ResolvedRevsetExpression>,
     file_path: &RepoPath,
+    options: &AnnotateOptions,
 ) -> Result<FileAnnotation, RevsetEvaluationError> {
-    let source = Source::load(starting_commit, file_path)?;
-    compute_file_annotation(repo, starting_commit.id(), domain, file_path, source)
+    let source = Source::load(starting_commit, file_path).block_on()?;
+    compute_file_annotation(
+        repo,
+        starting_commit.id(),
+        domain,
+        file_path,
+        source,
+        options,
+    )
 }
 
 /// Get line by line annotations for a specific file path starting with the
@@ -179,7 +268,15 @@ pub fn get_annotation_with_file_content(
     starting_text: impl Into<Vec<u8>>,
 ) -> Result<FileAnnotation, RevsetEvaluationError> {
     let source = Source::new(BString::new(starting_text.into()));
-    compute_file_annotation(repo, starting_commit_id, domain, file_path, source)
+    let options = AnnotateOptions::default();
+    compute_file_annotation(
+        repo,
+        starting_commit_id,
+        domain,
+        file_path,
+        source,
+        &options,
+    )
 }
 
 fn compute_file_annotation(
@@ -188,23 +285,49 @@ fn compute_file_annotation(
     domain: &Rc<ResolvedRevsetExpression>,
     file_path: &RepoPath,
     mut source: Source,
+    options: &AnnotateOptions,
 ) -> Result<FileAnnotation, RevsetEvaluationError> {
-    source.fill_line_map();
+    source.fill_line_map(options.line_range.as_ref());
     let text = source.text.clone();
-    let line_map = process_commits(repo, starting_commit_id, source, domain, file_path)?;
+    let mut line_map = vec![None; source.num_lines()];
+    // Histories of the source files are processed one by one. If the file was
+    // renamed, the lines copied from the source file will be queued with the
+    // parent commit and the source path.
+    let mut pending_sources = vec![(starting_commit_id.clone(), file_path.to_owned(), source)];
+    while let Some((commit_id, path, source)) = pending_sources.pop() {
+        let renamed_sources = process_commits(
+            repo,
+            &commit_id,
+            source,
+            domain,
+            &path,
+            &mut line_map,
+            options,
+        )?;
+        pending_sources.extend(renamed_sources);
+    }
     Ok(FileAnnotation { line_map, text })
 }
 
+/// A file content to be processed from the given commit at the given path.
+type PendingSource = (CommitId, RepoPathBuf, Source);
+
 /// Starting at the starting commit, compute changes at that commit relative to
-/// it's direct parents, updating the mappings as we go. We return the final
-/// original line map that represents where each line of the original came from.
+/// it's direct parents, updating the mappings as we go. We record in the
+/// original line map where each line of the original came from.
+///
+/// If `options.follow_renames` is set, lines introduced by copying or renaming
+/// another file aren't attributed to the commit, but are returned with the
+/// source file to be processed next.
 fn process_commits(
     repo: &dyn Repo,
     starting_commit_id: &CommitId,
     starting_source: Source,
     domain: &Rc<ResolvedRevsetExpression>,
     file_name: &RepoPath,
-) -> Result<OriginalLineMap, RevsetEvaluationError> {
+    original_line_map: &mut OriginalLineMap,
+    options: &AnnotateOptions,
+) -> Result<Vec<PendingSource>, RevsetEvaluationError> {
     let predicate = RevsetFilterPredicate::File(FilesetExpression::file_path(file_name.to_owned()));
     // TODO: If the domain isn't a contiguous range, changes masked out by it
     // might not be caught by the closest ancestor revision. For example,
@@ -216,79 +339,122 @@ fn process_commits(
         .union(&domain.intersection(&ancestors).filtered(predicate))
         .evaluate(repo)?;
 
-    let mut original_line_map = vec![None; starting_source.line_map.len()];
     let mut commit_source_map = HashMap::from([(starting_commit_id.clone(), starting_source)]);
-
-    for node in revset.iter_graph() {
-        let (commit_id, edge_list) = node?;
-        process_commit(
-            repo,
-            file_name,
-            &mut original_line_map,
-            &mut commit_source_map,
-            &commit_id,
-            &edge_list,
-        )?;
-        if commit_source_map.is_empty() {
-            // No more lines to propagate to ancestors.
+    let mut renamed_sources = Vec::new();
+
+    // Commits are processed in batches, and the sources at the parents of the
+    // whole batch are fetched at once so that high-latency backends can serve
+    // them concurrently instead of one commit at a time.
+    let batch_size = repo.store().concurrency().max(1);
+    let mut graph_iter = revset.iter_graph();
+    loop {
+        let nodes: Vec<_> = graph_iter.by_ref().take(batch_size).try_collect()?;
+        if nodes.is_empty() {
             break;
         }
+        let mut prefetched_sources =
+            prefetch_parent_sources(repo, file_name, &commit_source_map, &nodes)?;
+        for (commit_id, edge_list) in nodes {
+            let remaining_source = process_commit(
+                repo,
+                file_name,
+                &mut commit_source_map,
+                &mut prefetched_sources,
+                &commit_id,
+                &edge_list,
+                options,
+            )?;
+            if let Some(mut source) = remaining_source {
+                if options.follow_renames {
+                    let new_sources =
+                        take_renamed_sources(repo, &commit_id, file_name, &mut source, options)?;
+                    renamed_sources.extend(new_sources);
+                }
+                // Any leftover lines must be original to the current commit, so
+                // we save this information in original_line_map.
+                for (_, original_line_number) in source.line_map {
+                    original_line_map[original_line_number] = Some(commit_id.clone());
+                }
+            }
+            if commit_source_map.is_empty() {
+                // No more lines to propagate to ancestors.
+                return Ok(renamed_sources);
+            }
+        }
     }
-    Ok(original_line_map)
+    Ok(renamed_sources)
+}
+
+/// Loads the sources at the parents of the given commits concurrently.
+///
+/// Parents whose sources are already known are skipped. Since only the
+/// commits which still have lines to propagate need their parents, some of the
+/// prefetched sources may end up unused.
+fn prefetch_parent_sources(
+    repo: &dyn Repo,
+    file_name: &RepoPath,
+    commit_source_map: &CommitSourceMap,
+    nodes: &[GraphNode<CommitId>],
+) -> Result<CommitSourceMap, BackendError> {
+    let parent_ids = nodes
+        .iter()
+        .flat_map(|(_, edges)| edges.iter().map(|edge| &edge.target))
+        .filter(|id| !commit_source_map.contains_key(*id))
+        .unique()
+        .cloned()
+        .collect_vec();
+    let sources = Source::load_many(repo.store(), &parent_ids, file_name).block_on()?;
+    Ok(iter::zip(parent_ids, sources).collect())
 }
 
 /// For a given commit, for each parent, we compare the version in the parent
 /// tree with the current version, updating the mappings for any lines in
 /// common. If the parent doesn't have the file, we skip it.
+///
+/// Returns the source of the current commit with the lines which aren't found
+/// in any parent, or `None` if there are no lines to process at this commit.
 fn process_commit(
     repo: &dyn Repo,
     file_name: &RepoPath,
-    original_line_map: &mut OriginalLineMap,
     commit_source_map: &mut CommitSourceMap,
+    prefetched_sources: &mut CommitSourceMap,
     current_commit_id: &CommitId,
     edges: &[GraphEdge<CommitId>],
-) -> Result<(), BackendError> {
+    options: &AnnotateOptions,
+) -> Result<Option<Source>, BackendError> {
     let Some(mut current_source) = commit_source_map.remove(current_commit_id) else {
-        return Ok(());
+        return Ok(None);
     };
 
+    // Parents are usually prefetched. The remaining ones (e.g. a parent which
+    // was prefetched for another commit in the batch, and has been dropped
+    // since) are fetched at once so that high-latency backends can serve them
+    // concurrently.
+    let mut missing_parent_ids = vec![];
+    for id in edges.iter().map(|edge| &edge.target).unique() {
+        if commit_source_map.contains_key(id) {
+            continue;
+        }
+        if let Some(source) = prefetched_sources.remove(id) {
+            commit_source_map.insert(id.clone(), source);
+        } else {
+            missing_parent_ids.push(id.clone());
+        }
+    }
+    let missing_sources =
+        Source::load_many(repo.store(), &missing_parent_ids, file_name).block_on()?;
+    commit_source_map.extend(iter::zip(missing_parent_ids, missing_sources));
+
     for parent_edge in edges {
         let parent_commit_id = &parent_edge.target;
-        let parent_source = match commit_source_map.entry(parent_commit_id.clone()) {
-            hash_map::Entry::Occupied(entry) => entry.into_mut(),
-            hash_map::Entry::Vacant(entry) => {
-                let commit = repo.store().get_commit(entry.key())?;
-                entry.insert(Source::load(&commit, file_name)?)
-            }
+        // All parents were loaded above, but a duplicated edge may point to a
+        // parent which has already been dropped.
+        let Some(parent_source) = commit_source_map.get_mut(parent_commit_id) else {
+            continue;
         };
 
-        // For two versions of the same file, for all the lines in common,
-        // overwrite the new mapping in the results for the new commit. Let's
-        // say I have a file in commit A and commit B. We know that according to
-        // local line_map, in commit A, line 3 corresponds to line 7 of the
-        // original file. Now, line 3 in Commit A corresponds to line 6 in
-        // commit B. Then, we update local line_map to say that "Commit B line 6
-        // goes to line 7 of the original file". We repeat this for all lines in
-        // common in the two commits.
-        let mut current_lines = current_source.line_map.iter().copied().peekable();
-        let mut new_current_line_map = Vec::new();
-        let mut new_parent_line_map = Vec::new();
-        copy_same_lines_with(
-            &current_source.text,
-            &parent_source.text,
-            |current_start, parent_start, count| {
-                new_current_line_map
-                    .extend(current_lines.peeking_take_while(|&(cur, _)| cur < current_start));
-                while let Some((current, original)) =
-                    current_lines.next_if(|&(cur, _)| cur < current_start + count)
-                {
-                    let parent = parent_start + (current - current_start);
-                    new_parent_line_map.push((parent, original));
-                }
-            },
-        );
-        new_current_line_map.extend(current_lines);
-        current_source.line_map = new_current_line_map;
+        let new_parent_line_map =
+            current_source.take_same_lines(&parent_source.text, options.ignore_whitespace);
         parent_source.line_map = if parent_source.line_map.is_empty() {
             new_parent_line_map
         } else {
@@ -302,24 +468,71 @@ fn process_commit(
         }
     }
 
-    // Once we've looked at all parents of a commit, any leftover lines must be
-    // original to the current commit, so we save this information in
-    // original_line_map.
-    for (_, original_line_number) in current_source.line_map {
-        original_line_map[original_line_number] = Some(current_commit_id.clone());
-    }
+    Ok(Some(current_source))
+}
 
-    Ok(())
+/// Looks up the files the current file was copied or renamed from, and moves
+/// the lines in common to the sources at the parent commits.
+///
+/// Only the parents which don't have the file at the same path are checked.
+fn take_renamed_sources(
+    repo: &dyn Repo,
+    current_commit_id: &CommitId,
+    file_name: &RepoPath,
+    current_source: &mut Source,
+    options: &AnnotateOptions,
+) -> Result<Vec<PendingSource>, BackendError> {
+    let store = repo.store();
+    let mut renamed_sources = Vec::new();
+    if current_source.line_map.is_empty() {
+        return Ok(renamed_sources);
+    }
+    let current_commit = store.get_commit(current_commit_id)?;
+    for parent in current_commit.parents() {
+        let parent = parent?;
+        if !parent.tree()?.path_value(file_name)?.is_absent() {
+            continue;
+        }
+        let paths = [file_name.to_owned()];
+        let copy_records: Vec<_> = store
+            .get_copy_records(Some(&paths), parent.id(), current_commit_id)?
+            .try_collect()
+            .block_on()?;
+        let Some(record) = copy_records.into_iter().find(|record| {
+            record.target.as_ref() == file_name && record.source.as_ref() != file_name
+        }) else {
+            continue;
+        };
+        let mut parent_source = Source::load(&parent, &record.source).block_on()?;
+        parent_source.line_map =
+            current_source.take_same_lines(&parent_source.text, options.ignore_whitespace);
+        if !parent_source.line_map.is_empty() {
+            renamed_sources.push((parent.id().clone(), record.source, parent_source));
+        }
+        if current_source.line_map.is_empty() {
+            break;
+        }
+    }
+    Ok(renamed_sources)
 }
 
 /// For two files, calls `copy(current_start, parent_start, count)` for each
 /// range of contiguous lines in common (e.g. line 8-10 maps to line 9-11.)
+///
+/// If `ignore_whitespace` is set, lines which differ only in whitespace are
+/// considered in common.
 fn copy_same_lines_with(
     current_contents: &[u8],
     parent_contents: &[u8],
+    ignore_whitespace: bool,
     mut copy: impl FnMut(usize, usize, usize),
 ) {
-    let diff = Diff::by_line([current_contents, parent_contents]);
+    let inputs = [current_contents, parent_contents];
+    let diff = if ignore_whitespace {
+        Diff::for_tokenizer(inputs, find_line_ranges, CompareBytesIgnoreAllWhitespace)
+    } else {
+        Diff::by_line(inputs)
+    };
     let mut current_line_counter: usize = 0;
     let mut parent_line_counter: usize = 0;
     for hunk in diff.hunks() {
@@ -340,16 +553,20 @@ fn copy_same_lines_with(
     }
 }
 
-fn get_file_contents(
+/// Reads the contents of the file at `path`, materializing conflicts.
+///
+/// Lines are diffed against the other sources, so the whole text has to be
+/// loaded. Conflicts are read hunk by hunk straight into the returned buffer.
+async fn get_file_contents(
     store: &Store,
     path: &RepoPath,
     tree: &MergedTree,
 ) -> Result<BString, BackendError> {
-    let file_value = tree.path_value(path)?;
-    let effective_file_value = materialize_tree_value(store, path, file_value).block_on()?;
+    let file_value = tree.path_value_async(path).await?;
+    let effective_file_value = materialize_tree_value(store, path, file_value).await?;
+    let mut file_contents = Vec::new();
     match effective_file_value {
         MaterializedTreeValue::File { mut reader, id, .. } => {
-            let mut file_contents = Vec::new();
             reader
                 .read_to_end(&mut file_contents)
                 .map_err(|e| BackendError::ReadFile {
@@ -357,13 +574,15 @@ fn get_file_contents(
                     id,
                     source: Box::new(e),
                 })?;
-            Ok(file_contents.into())
         }
-        MaterializedTreeValue::FileConflict { contents, .. } => Ok(
-            materialize_merge_result_to_bytes(&contents, ConflictMarkerStyle::default()),
-        ),
-        _ => Ok(BString::default()),
+        MaterializedTreeValue::FileConflict { contents, .. } => {
+            MaterializedMergeReader::new(&contents, ConflictMarkerStyle::default())
+                .read_to_end(&mut file_contents)
+                .expect("materializing a conflict in memory should never fail");
+        }
+        _ => {}
     }
+    Ok(file_contents.into())
 }
 
 #[cfg(test)]
@@ -193,6 +193,16 @@ pub struct CopyRecord {
     pub source_commit: CommitId,
 }
 
+/// Objects that [`Backend::gc()`] would remove, as reported by
+/// [`Backend::gc_dry_run()`].
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct BackendGcStats {
+    /// Commits that would no longer be protected from garbage collection.
+    pub expired_commit_ids: Vec<CommitId>,
+    /// Size of the objects that would be removed in bytes, if known.
+    pub reclaimable_bytes: Option<u64>,
+}
+
 /// Error that may occur during backend initialization.
 #[derive(Debug, Error)]
 #[error(transparent)]
@@ -445,6 +455,13 @@ pub trait Backend: Send + Sync + Debug {
 
     async fn read_commit(&self, id: &CommitId) -> BackendResult<Commit>;
 
+    /// Returns true if the history beyond the commit is missing, such as in
+    /// a shallow Git clone. The parents of such commits are unknown, so they
+    /// are reported to have the root commit as their only parent.
+    fn is_shallow_commit(&self, _id: &CommitId) -> BackendResult<bool> {
+        Ok(false)
+    }
+
     /// Writes a commit and returns its ID and the commit itself. The commit
     /// should contain the data that was actually written, which may differ
     /// from the data passed in. For example, the backend may change the
@@ -489,4 +506,16 @@ pub trait Backend: Send + Sync + Debug {
     /// objects created after `keep_newer` will be preserved. This mitigates a
     /// risk of deleting new commits created concurrently by another process.
     fn gc(&self, index: &dyn Index, keep_newer: SystemTime) -> BackendResult<()>;
+
+    /// Reports what [`Backend::gc()`] would do with the same arguments,
+    /// without removing anything.
+    ///
+    /// The default implementation reports nothing, with unknown size.
+    fn gc_dry_run(
+        &self,
+        _index: &dyn Index,
+        _keep_newer: SystemTime,
+    ) -> BackendResult<BackendGcStats> {
+        Ok(BackendGcStats::default())
+    }
 }
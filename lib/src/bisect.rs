@@ -0,0 +1,265 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Finding the first bad commit by bisection.
+//!
+//! The state of a bisection is recorded in the operation log. Each operation
+//! that changes the state has a [`BISECT_TAG`] tag listing the
+//! [`BisectEvent`]s it applies, so the state can be reconstructed by walking
+//! the operation log back to the most recent start of a bisection.
+
+use std::rc::Rc;
+
+use itertools::Itertools as _;
+use thiserror::Error;
+
+use crate::backend::CommitId;
+use crate::object_id::ObjectId as _;
+use crate::op_store::OpStoreResult;
+use crate::op_walk;
+use crate::operation::Operation;
+use crate::repo::Repo;
+use crate::revset::ResolvedRevsetExpression;
+use crate::revset::RevsetEvaluationError;
+use crate::revset::RevsetExpression;
+
+/// Operation tag recording changes to the bisection state.
+pub const BISECT_TAG: &str = "bisect";
+
+/// Change to the bisection state.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BisectEvent {
+    /// A new bisection was started, discarding the previous state.
+    Start,
+    /// The commit was found to be good.
+    Good(CommitId),
+    /// The commit was found to be bad.
+    Bad(CommitId),
+    /// The commit couldn't be tested.
+    Skip(CommitId),
+    /// The bisection was ended.
+    Reset,
+}
+
+impl BisectEvent {
+    fn to_line(&self) -> String {
+        match self {
+            BisectEvent::Start => "start".to_owned(),
+            BisectEvent::Good(id) => format!("good {}", id.hex()),
+            BisectEvent::Bad(id) => format!("bad {}", id.hex()),
+            BisectEvent::Skip(id) => format!("skip {}", id.hex()),
+            BisectEvent::Reset => "reset".to_owned(),
+        }
+    }
+
+    fn parse_line(line: &str) -> Option<Self> {
+        let (kind, hex) = match line.split_once(' ') {
+            Some((kind, hex)) => (kind, Some(hex)),
+            None => (line, None),
+        };
+        let id = || hex.and_then(|hex| CommitId::try_from_hex(hex).ok());
+        match kind {
+            "start" => Some(BisectEvent::Start),
+            "good" => Some(BisectEvent::Good(id()?)),
+            "bad" => Some(BisectEvent::Bad(id()?)),
+            "skip" => Some(BisectEvent::Skip(id()?)),
+            "reset" => Some(BisectEvent::Reset),
+            _ => None,
+        }
+    }
+
+    /// Formats `events` as the value of a [`BISECT_TAG`] tag.
+    pub fn format_tag_value(events: &[BisectEvent]) -> String {
+        events.iter().map(BisectEvent::to_line).join("\n")
+    }
+
+    /// Parses the value of a [`BISECT_TAG`] tag. Unknown events are ignored.
+    pub fn parse_tag_value(value: &str) -> Vec<BisectEvent> {
+        value.lines().filter_map(BisectEvent::parse_line).collect()
+    }
+}
+
+/// Commits marked good, bad, or skipped in an ongoing bisection.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BisectState {
+    /// Commits marked good.
+    pub good: Vec<CommitId>,
+    /// Commits marked bad.
+    pub bad: Vec<CommitId>,
+    /// Commits that couldn't be tested.
+    pub skipped: Vec<CommitId>,
+}
+
+impl BisectState {
+    /// Reconstructs the state of the bisection in progress at `op` from the
+    /// operation log. Returns `None` if no bisection is in progress.
+    pub fn load(op: &Operation) -> OpStoreResult<Option<Self>> {
+        // Events of the current bisection, latest first
+        let mut events = vec![];
+        for op in op_walk::walk_ancestors(std::slice::from_ref(op)) {
+            let op = op?;
+            let Some(value) = op.metadata().tags.get(BISECT_TAG) else {
+                continue;
+            };
+            for event in BisectEvent::parse_tag_value(value).into_iter().rev() {
+                match event {
+                    BisectEvent::Start => {
+                        let mut state = BisectState::default();
+                        for event in events.iter().rev() {
+                            state.apply(event);
+                        }
+                        return Ok(Some(state));
+                    }
+                    BisectEvent::Reset => return Ok(None),
+                    _ => events.push(event),
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Updates the state with `event`.
+    pub fn apply(&mut self, event: &BisectEvent) {
+        match event {
+            BisectEvent::Start | BisectEvent::Reset => *self = BisectState::default(),
+            BisectEvent::Good(id) => self.good.push(id.clone()),
+            BisectEvent::Bad(id) => self.bad.push(id.clone()),
+            BisectEvent::Skip(id) => self.skipped.push(id.clone()),
+        }
+    }
+}
+
+/// What to do next in a bisection.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NextStep {
+    /// The commit should be tested next.
+    Evaluate {
+        /// The commit to test.
+        commit_id: CommitId,
+        /// The number of untested commits that may be the first bad commit.
+        remaining: usize,
+    },
+    /// The bisection is finished.
+    Done(BisectionResult),
+}
+
+/// Outcome of a finished bisection.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BisectionResult {
+    /// The first bad commit is one of these. There's more than one only if
+    /// commits were skipped.
+    Found(Vec<CommitId>),
+    /// No commit could be the first bad commit, e.g. because all bad commits
+    /// are ancestors of good commits.
+    Indeterminate,
+}
+
+/// Error that prevents a bisection from picking the next commit to test.
+#[derive(Debug, Error)]
+pub enum BisectError {
+    /// The bisection state doesn't have any bad commit to search from.
+    #[error("No commit has been marked as bad")]
+    NoBadCommit,
+}
+
+/// Picks commits to test in a bisection.
+pub struct Bisector<'a> {
+    repo: &'a dyn Repo,
+    state: &'a BisectState,
+}
+
+impl<'a> Bisector<'a> {
+    /// Creates a bisector for the given state. Fails if the state doesn't
+    /// contain any bad commit.
+    pub fn new(repo: &'a dyn Repo, state: &'a BisectState) -> Result<Self, BisectError> {
+        if state.bad.is_empty() {
+            return Err(BisectError::NoBadCommit);
+        }
+        Ok(Bisector { repo, state })
+    }
+
+    /// Commits that may be the first bad commit: ancestors of all bad commits
+    /// that aren't ancestors of good commits.
+    fn range(&self) -> Rc<ResolvedRevsetExpression> {
+        let bad_ancestors = self
+            .state
+            .bad
+            .iter()
+            .map(|id| RevsetExpression::commit(id.clone()).ancestors())
+            .reduce(|acc, ancestors| acc.intersection(&ancestors))
+            .unwrap();
+        bad_ancestors.minus(&RevsetExpression::commits(self.state.good.clone()).ancestors())
+    }
+
+    /// Determines the next commit to test, or the result of the bisection.
+    pub fn next_step(&self) -> Result<NextStep, RevsetEvaluationError> {
+        let range = self.range();
+        let untested = range
+            .minus(&RevsetExpression::commits(self.state.bad.clone()))
+            .minus(&RevsetExpression::commits(self.state.skipped.clone()));
+        let untested_ids: Vec<CommitId> = untested.evaluate(self.repo)?.iter().try_collect()?;
+        if untested_ids.is_empty() {
+            let remaining_ids: Vec<CommitId> = range.evaluate(self.repo)?.iter().try_collect()?;
+            let result = if remaining_ids.is_empty() {
+                BisectionResult::Indeterminate
+            } else {
+                BisectionResult::Found(remaining_ids)
+            };
+            return Ok(NextStep::Done(result));
+        }
+        // The middle commit in topological order splits a linear range in
+        // half. This is only an approximation if the range contains merges.
+        Ok(NextStep::Evaluate {
+            commit_id: untested_ids[untested_ids.len() / 2].clone(),
+            remaining: untested_ids.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_value_roundtrip() {
+        let id = CommitId::from_hex("abc123");
+        let events = vec![
+            BisectEvent::Start,
+            BisectEvent::Bad(id.clone()),
+            BisectEvent::Good(id.clone()),
+            BisectEvent::Skip(id),
+            BisectEvent::Reset,
+        ];
+        let value = BisectEvent::format_tag_value(&events);
+        insta::assert_snapshot!(value, @r"
+        start
+        bad abc123
+        good abc123
+        skip abc123
+        reset
+        ");
+        assert_eq!(BisectEvent::parse_tag_value(&value), events);
+    }
+
+    #[test]
+    fn test_parse_tag_value_ignores_unknown_events() {
+        assert_eq!(
+            BisectEvent::parse_tag_value("start\nfrobnicate\ngood xyz\nbad 00"),
+            vec![
+                BisectEvent::Start,
+                BisectEvent::Bad(CommitId::from_hex("00"))
+            ]
+        );
+    }
+}
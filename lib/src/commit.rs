@@ -20,6 +20,7 @@ use std::fmt::Error;
 use std::fmt::Formatter;
 use std::hash::Hash;
 use std::hash::Hasher;
+use std::slice;
 use std::sync::Arc;
 
 use itertools::Itertools;
@@ -112,6 +113,10 @@ impl Commit {
         self.store.get_root_tree(&self.data.root_tree)
     }
 
+    pub async fn tree_async(&self) -> BackendResult<MergedTree> {
+        self.store.get_root_tree_async(&self.data.root_tree).await
+    }
+
     pub fn tree_id(&self) -> &MergedTreeId {
         &self.data.root_tree
     }
@@ -137,6 +142,16 @@ impl Commit {
         }
     }
 
+    /// Returns true if the commit is at the boundary of a shallow history,
+    /// i.e. its actual parents are unknown and the root commit is recorded as
+    /// its parent instead.
+    pub fn is_shallow(&self) -> BackendResult<bool> {
+        if self.parent_ids() != slice::from_ref(self.store.root_commit_id()) {
+            return Ok(false);
+        }
+        self.store.is_shallow_commit(&self.id)
+    }
+
     pub fn change_id(&self) -> &ChangeId {
         &self.data.change_id
     }
@@ -0,0 +1,235 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! On-disk cache of decoded commit metadata.
+//!
+//! Backends that store commits in a format that is relatively expensive to
+//! decode (such as the Git backend, which has to parse the Git commit object
+//! and then look up jj-specific metadata in a separate table) can use this
+//! cache to avoid doing that work every time a commit is read. Entries are
+//! keyed by commit id and populated lazily as commits are read.
+//!
+//! The cache is best-effort: failures to read or write it are logged and
+//! otherwise ignored, and deleting the cache directory is always safe.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use prost::Message as _;
+
+use crate::backend::Commit;
+use crate::backend::CommitId;
+use crate::local_backend::commit_from_proto;
+use crate::local_backend::commit_to_proto;
+use crate::object_id::ObjectId as _;
+use crate::stacked_table::MutableTable;
+use crate::stacked_table::ReadonlyTable;
+use crate::stacked_table::TableSegment as _;
+use crate::stacked_table::TableStore;
+use crate::stacked_table::TableStoreResult;
+
+/// Number of pending entries after which they're written to disk.
+const FLUSH_THRESHOLD: usize = 1000;
+
+/// Lazily populated on-disk cache of commit metadata, keyed by commit id.
+///
+/// Signed commits aren't cached since the signature has to be verified
+/// against the original encoding of the commit.
+pub struct CommitMetadataCache {
+    store: TableStore,
+    cached_table: Mutex<Option<Arc<ReadonlyTable>>>,
+    pending: Mutex<Vec<(CommitId, Vec<u8>)>>,
+}
+
+impl CommitMetadataCache {
+    /// Opens the cache stored in `dir`, creating the directory if needed.
+    pub fn load(dir: PathBuf, commit_id_length: usize) -> Self {
+        // The cache may be missing in repos created by older versions.
+        if let Err(err) = fs::create_dir_all(dir.join("heads")) {
+            tracing::warn!(
+                ?err,
+                ?dir,
+                "failed to create commit metadata cache directory"
+            );
+        }
+        CommitMetadataCache {
+            store: TableStore::load(dir, commit_id_length),
+            cached_table: Mutex::new(None),
+            pending: Mutex::new(vec![]),
+        }
+    }
+
+    fn table(&self) -> Option<Arc<ReadonlyTable>> {
+        let mut locked_table = self.cached_table.lock().unwrap();
+        if locked_table.is_none() {
+            match self.store.get_head() {
+                Ok(table) => *locked_table = Some(table),
+                Err(err) => {
+                    tracing::warn!(?err, "failed to load commit metadata cache");
+                    return None;
+                }
+            }
+        }
+        locked_table.clone()
+    }
+
+    /// Looks up the commit metadata for `id`.
+    pub fn get(&self, id: &CommitId) -> Option<Commit> {
+        let bytes = if let Some(bytes) = self.table()?.get_value(id.as_bytes()) {
+            bytes.to_vec()
+        } else {
+            let pending = self.pending.lock().unwrap();
+            let (_, bytes) = pending.iter().find(|(pending_id, _)| pending_id == id)?;
+            bytes.clone()
+        };
+        match crate::protos::local_store::Commit::decode(bytes.as_slice()) {
+            Ok(proto) => Some(commit_from_proto(proto)),
+            Err(err) => {
+                tracing::warn!(?err, ?id, "invalid commit metadata cache entry");
+                None
+            }
+        }
+    }
+
+    /// Records the metadata for `id`. The entry may not be written to disk
+    /// until [`Self::flush()`] is called or the cache is dropped.
+    pub fn insert(&self, id: &CommitId, commit: &Commit) {
+        if commit.secure_sig.is_some() {
+            return;
+        }
+        let bytes = commit_to_proto(commit).encode_to_vec();
+        let should_flush = {
+            let mut pending = self.pending.lock().unwrap();
+            pending.push((id.clone(), bytes));
+            pending.len() >= FLUSH_THRESHOLD
+        };
+        if should_flush {
+            self.flush();
+        }
+    }
+
+    /// Discards all cached entries.
+    pub fn clear(&self) -> TableStoreResult<()> {
+        self.pending.lock().unwrap().clear();
+        let _lock = self.store.get_head_locked()?;
+        self.store.reinit()?;
+        *self.cached_table.lock().unwrap() = None;
+        Ok(())
+    }
+
+    /// Writes pending entries to disk.
+    pub fn flush(&self) {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        if pending.is_empty() {
+            return;
+        }
+        let (table, _lock) = match self.store.get_head_locked() {
+            Ok(head) => head,
+            Err(err) => {
+                tracing::warn!(?err, "failed to lock commit metadata cache");
+                return;
+            }
+        };
+        let mut mut_table: MutableTable = table.start_mutation();
+        for (id, bytes) in pending {
+            if mut_table.get_value(id.as_bytes()).is_none() {
+                mut_table.add_entry(id.to_bytes(), bytes);
+            }
+        }
+        match self.store.save_table(mut_table) {
+            Ok(table) => *self.cached_table.lock().unwrap() = Some(table),
+            Err(err) => tracing::warn!(?err, "failed to save commit metadata cache"),
+        }
+    }
+}
+
+impl Drop for CommitMetadataCache {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::ChangeId;
+    use crate::backend::MergedTreeId;
+    use crate::backend::MillisSinceEpoch;
+    use crate::backend::SecureSig;
+    use crate::backend::Signature;
+    use crate::backend::Timestamp;
+    use crate::backend::TreeId;
+
+    fn create_commit(description: &str) -> Commit {
+        let signature = Signature {
+            name: "Someone".to_string(),
+            email: "someone@example.com".to_string(),
+            timestamp: Timestamp {
+                timestamp: MillisSinceEpoch(0),
+                tz_offset: 0,
+            },
+        };
+        Commit {
+            parents: vec![CommitId::from_hex("11")],
+            predecessors: vec![],
+            root_tree: MergedTreeId::Legacy(TreeId::from_hex("22")),
+            change_id: ChangeId::from_hex("33"),
+            description: description.to_string(),
+            author: signature.clone(),
+            committer: signature,
+            secure_sig: None,
+        }
+    }
+
+    #[test]
+    fn insert_and_reload() {
+        let temp_dir = testutils::new_temp_dir();
+        let dir = temp_dir.path().join("commit_cache");
+        let id1 = CommitId::from_hex("01");
+        let id2 = CommitId::from_hex("02");
+        let commit = create_commit("first\n\nbody\n");
+
+        let cache = CommitMetadataCache::load(dir.clone(), 1);
+        assert_eq!(cache.get(&id1), None);
+        cache.insert(&id1, &commit);
+        // Pending entries are visible before they're flushed
+        assert_eq!(cache.get(&id1), Some(commit.clone()));
+        drop(cache);
+
+        let cache = CommitMetadataCache::load(dir, 1);
+        assert_eq!(cache.get(&id1), Some(commit));
+        assert_eq!(cache.get(&id2), None);
+
+        cache.clear().unwrap();
+        assert_eq!(cache.get(&id1), None);
+    }
+
+    #[test]
+    fn signed_commit_not_cached() {
+        let temp_dir = testutils::new_temp_dir();
+        let id = CommitId::from_hex("01");
+        let mut commit = create_commit("signed\n");
+        commit.secure_sig = Some(SecureSig {
+            data: b"data".to_vec(),
+            sig: b"sig".to_vec(),
+        });
+
+        let cache = CommitMetadataCache::load(temp_dir.path().join("commit_cache"), 1);
+        cache.insert(&id, &commit);
+        cache.flush();
+        assert_eq!(cache.get(&id), None);
+    }
+}
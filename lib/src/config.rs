@@ -34,10 +34,15 @@ use thiserror::Error;
 use toml_edit::DocumentMut;
 use toml_edit::ImDocument;
 
+pub use crate::config_resolver::interpolate_env;
 pub use crate::config_resolver::migrate;
 pub use crate::config_resolver::resolve;
 pub use crate::config_resolver::ConfigMigrationRule;
 pub use crate::config_resolver::ConfigResolutionContext;
+pub use crate::config_resolver::EnvInterpolationMode;
+pub use crate::config_schema::ConfigSchema;
+pub use crate::config_schema::ConfigSchemaError;
+pub use crate::config_schema::ConfigSchemaParseError;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
 
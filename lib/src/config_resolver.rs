@@ -14,6 +14,8 @@
 
 //! Post-processing functions for [`StackedConfig`].
 
+use std::fmt;
+use std::fmt::Debug;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -26,10 +28,12 @@ use toml_edit::DocumentMut;
 use crate::config::ConfigGetError;
 use crate::config::ConfigLayer;
 use crate::config::ConfigNamePathBuf;
+use crate::config::ConfigSource;
 use crate::config::ConfigUpdateError;
 use crate::config::ConfigValue;
 use crate::config::StackedConfig;
 use crate::config::ToConfigNamePath;
+use crate::str_util::StringPattern;
 
 // Prefixed by "--" so these keys look unusual. It's also nice that "-" is
 // placed earlier than the other keys in lexicographical order.
@@ -37,12 +41,24 @@ const SCOPE_CONDITION_KEY: &str = "--when";
 const SCOPE_TABLE_KEY: &str = "--scope";
 
 /// Parameters to enable scoped config tables conditionally.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct ConfigResolutionContext<'a> {
     /// Home directory. `~` will be substituted with this path.
     pub home_dir: Option<&'a Path>,
     /// Repository path, which is usually `<workspace_root>/.jj/repo`.
     pub repo_path: Option<&'a Path>,
+    /// Function to look up the URLs of the Git remotes configured in the
+    /// repository. This is called only if a scope is conditioned on remotes.
+    pub remote_urls: &'a dyn Fn() -> &'a [String],
+}
+
+impl Debug for ConfigResolutionContext<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigResolutionContext")
+            .field("home_dir", &self.home_dir)
+            .field("repo_path", &self.repo_path)
+            .finish_non_exhaustive()
+    }
 }
 
 /// Conditions to enable the parent table.
@@ -57,6 +73,9 @@ pub struct ConfigResolutionContext<'a> {
 struct ScopeCondition {
     /// Paths to match the repository path prefix.
     pub repositories: Option<Vec<PathBuf>>,
+    /// Glob patterns to match the URL of any remote of the repository.
+    #[serde(deserialize_with = "deserialize_glob_patterns")]
+    pub remotes: Option<Vec<StringPattern>>,
     // TODO: maybe add "workspaces"?
 }
 
@@ -84,9 +103,24 @@ impl ScopeCondition {
 
     fn matches(&self, context: &ConfigResolutionContext) -> bool {
         matches_path_prefix(self.repositories.as_deref(), context.repo_path)
+            && matches_any_pattern(self.remotes.as_deref(), context.remote_urls)
     }
 }
 
+fn deserialize_glob_patterns<'de, D>(
+    deserializer: D,
+) -> Result<Option<Vec<StringPattern>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let sources = Vec::<String>::deserialize(deserializer)?;
+    let patterns = sources
+        .iter()
+        .map(|src| StringPattern::glob(src).map_err(<D::Error as serde::de::Error>::custom))
+        .try_collect()?;
+    Ok(Some(patterns))
+}
+
 fn expand_home(path: &Path, home_dir: Option<&Path>) -> Result<Option<PathBuf>, &'static str> {
     match path.strip_prefix("~") {
         Ok(tail) => {
@@ -105,6 +139,18 @@ fn matches_path_prefix(candidates: Option<&[PathBuf]>, actual: Option<&Path>) ->
     }
 }
 
+fn matches_any_pattern<'a>(
+    patterns: Option<&[StringPattern]>,
+    actual: impl FnOnce() -> &'a [String],
+) -> bool {
+    match patterns {
+        Some(patterns) => actual()
+            .iter()
+            .any(|value| patterns.iter().any(|pattern| pattern.matches(value))),
+        None => true, // no constraints
+    }
+}
+
 /// Evaluates condition for each layer and scope, flattens scoped tables.
 /// Returns new config that only contains enabled layers and tables.
 pub fn resolve(
@@ -177,6 +223,169 @@ fn pop_scope_tables(layer: &mut ConfigLayer) -> Result<toml_edit::ArrayOfTables,
         })
 }
 
+/// How to handle unexpandable `${NAME}` references in config values.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EnvInterpolationMode {
+    /// References to undefined variables are left as-is.
+    #[default]
+    Lenient,
+    /// References to undefined variables are errors.
+    Strict,
+}
+
+/// Substitutes `${NAME}` references in string values of user, repo, and
+/// command-line layers with the values returned by `lookup_env`. `$${` is
+/// unescaped to `${`.
+///
+/// Default and environment-variable layers are kept as-is.
+pub fn interpolate_env(
+    source_config: &StackedConfig,
+    mode: EnvInterpolationMode,
+    lookup_env: &dyn Fn(&str) -> Option<String>,
+) -> Result<StackedConfig, ConfigGetError> {
+    let mut config = source_config.clone();
+    for layer in config.layers_mut() {
+        match layer.source {
+            ConfigSource::Default | ConfigSource::EnvBase | ConfigSource::EnvOverrides => continue,
+            ConfigSource::User | ConfigSource::Repo | ConfigSource::CommandArg => {}
+        }
+        let layer_mut = Arc::make_mut(layer);
+        interpolate_env_in_table(layer_mut.data.as_table_mut(), mode, lookup_env).map_err(
+            |(rev_keys, message)| ConfigGetError::Type {
+                name: rev_keys
+                    .into_iter()
+                    .rev()
+                    .collect::<ConfigNamePathBuf>()
+                    .to_string(),
+                error: message.into(),
+                source_path: layer_mut.path.clone(),
+            },
+        )?;
+    }
+    Ok(config)
+}
+
+/// Error of the env interpolation, which consists of reversed key path and
+/// message.
+type EnvInterpolationError = (Vec<String>, String);
+
+fn interpolate_env_in_table(
+    table: &mut toml_edit::Table,
+    mode: EnvInterpolationMode,
+    lookup_env: &dyn Fn(&str) -> Option<String>,
+) -> Result<(), EnvInterpolationError> {
+    for (key, item) in table.iter_mut() {
+        interpolate_env_in_item(item, mode, lookup_env).map_err(|(mut rev_keys, message)| {
+            rev_keys.push(key.get().to_owned());
+            (rev_keys, message)
+        })?;
+    }
+    Ok(())
+}
+
+fn interpolate_env_in_item(
+    item: &mut toml_edit::Item,
+    mode: EnvInterpolationMode,
+    lookup_env: &dyn Fn(&str) -> Option<String>,
+) -> Result<(), EnvInterpolationError> {
+    match item {
+        toml_edit::Item::None => Ok(()),
+        toml_edit::Item::Value(value) => interpolate_env_in_value(value, mode, lookup_env),
+        toml_edit::Item::Table(table) => interpolate_env_in_table(table, mode, lookup_env),
+        toml_edit::Item::ArrayOfTables(tables) => tables
+            .iter_mut()
+            .try_for_each(|table| interpolate_env_in_table(table, mode, lookup_env)),
+    }
+}
+
+fn interpolate_env_in_value(
+    value: &mut toml_edit::Value,
+    mode: EnvInterpolationMode,
+    lookup_env: &dyn Fn(&str) -> Option<String>,
+) -> Result<(), EnvInterpolationError> {
+    match value {
+        toml_edit::Value::String(formatted) => {
+            if formatted.value().contains('$') {
+                let expanded = expand_env_vars(formatted.value(), mode, lookup_env)
+                    .map_err(|message| (vec![], message))?;
+                *value = expanded.into();
+            }
+            Ok(())
+        }
+        toml_edit::Value::Array(array) => array
+            .iter_mut()
+            .try_for_each(|value| interpolate_env_in_value(value, mode, lookup_env)),
+        toml_edit::Value::InlineTable(table) => {
+            for (key, value) in table.iter_mut() {
+                interpolate_env_in_value(value, mode, lookup_env).map_err(
+                    |(mut rev_keys, message)| {
+                        rev_keys.push(key.get().to_owned());
+                        (rev_keys, message)
+                    },
+                )?;
+            }
+            Ok(())
+        }
+        toml_edit::Value::Integer(_)
+        | toml_edit::Value::Float(_)
+        | toml_edit::Value::Boolean(_)
+        | toml_edit::Value::Datetime(_) => Ok(()),
+    }
+}
+
+fn expand_env_vars(
+    text: &str,
+    mode: EnvInterpolationMode,
+    lookup_env: &dyn Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find('$') {
+        expanded.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        if let Some(after) = rest.strip_prefix("$${") {
+            expanded.push_str("${");
+            rest = after;
+        } else if let Some(reference) = rest.strip_prefix("${") {
+            let parsed = reference
+                .split_once('}')
+                .filter(|(name, _)| is_env_var_name(name));
+            let Some((name, after)) = parsed else {
+                if mode == EnvInterpolationMode::Strict {
+                    return Err(format!(
+                        "Invalid environment variable reference in {text:?}"
+                    ));
+                }
+                expanded.push_str("${");
+                rest = reference;
+                continue;
+            };
+            match lookup_env(name) {
+                Some(value) => expanded.push_str(&value),
+                None if mode == EnvInterpolationMode::Strict => {
+                    return Err(format!("Environment variable {name} is not set"));
+                }
+                None => expanded.push_str(&rest[.."${}".len() + name.len()]),
+            }
+            rest = after;
+        } else {
+            expanded.push('$');
+            rest = &rest[1..];
+        }
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn is_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
 /// Rule to migrate deprecated config variables.
 pub struct ConfigMigrationRule {
     inner: MigrationRule,
@@ -272,7 +481,6 @@ mod tests {
     use indoc::indoc;
 
     use super::*;
-    use crate::config::ConfigSource;
 
     #[test]
     fn test_expand_home() {
@@ -297,11 +505,13 @@ mod tests {
         let context = ConfigResolutionContext {
             home_dir: None,
             repo_path: None,
+            remote_urls: &|| &[],
         };
         assert!(condition.matches(&context));
         let context = ConfigResolutionContext {
             home_dir: None,
             repo_path: Some(Path::new("/foo")),
+            remote_urls: &|| &[],
         };
         assert!(condition.matches(&context));
     }
@@ -310,31 +520,37 @@ mod tests {
     fn test_condition_repo_path() {
         let condition = ScopeCondition {
             repositories: Some(["/foo", "/bar"].map(PathBuf::from).into()),
+            remotes: None,
         };
 
         let context = ConfigResolutionContext {
             home_dir: None,
             repo_path: None,
+            remote_urls: &|| &[],
         };
         assert!(!condition.matches(&context));
         let context = ConfigResolutionContext {
             home_dir: None,
             repo_path: Some(Path::new("/foo")),
+            remote_urls: &|| &[],
         };
         assert!(condition.matches(&context));
         let context = ConfigResolutionContext {
             home_dir: None,
             repo_path: Some(Path::new("/fooo")),
+            remote_urls: &|| &[],
         };
         assert!(!condition.matches(&context));
         let context = ConfigResolutionContext {
             home_dir: None,
             repo_path: Some(Path::new("/foo/baz")),
+            remote_urls: &|| &[],
         };
         assert!(condition.matches(&context));
         let context = ConfigResolutionContext {
             home_dir: None,
             repo_path: Some(Path::new("/bar")),
+            remote_urls: &|| &[],
         };
         assert!(condition.matches(&context));
     }
@@ -343,30 +559,79 @@ mod tests {
     fn test_condition_repo_path_windows() {
         let condition = ScopeCondition {
             repositories: Some(["c:/foo", r"d:\bar/baz"].map(PathBuf::from).into()),
+            remotes: None,
         };
 
         let context = ConfigResolutionContext {
             home_dir: None,
             repo_path: Some(Path::new(r"c:\foo")),
+            remote_urls: &|| &[],
         };
         assert_eq!(condition.matches(&context), cfg!(windows));
         let context = ConfigResolutionContext {
             home_dir: None,
             repo_path: Some(Path::new(r"c:\foo\baz")),
+            remote_urls: &|| &[],
         };
         assert_eq!(condition.matches(&context), cfg!(windows));
         let context = ConfigResolutionContext {
             home_dir: None,
             repo_path: Some(Path::new(r"d:\foo")),
+            remote_urls: &|| &[],
         };
         assert!(!condition.matches(&context));
         let context = ConfigResolutionContext {
             home_dir: None,
             repo_path: Some(Path::new(r"d:/bar\baz")),
+            remote_urls: &|| &[],
         };
         assert_eq!(condition.matches(&context), cfg!(windows));
     }
 
+    #[test]
+    fn test_condition_remotes() {
+        // Remote URLs aren't looked up unless the condition needs them
+        let condition = ScopeCondition::default();
+        let context = ConfigResolutionContext {
+            home_dir: None,
+            repo_path: Some(Path::new("/foo")),
+            remote_urls: &|| panic!("remote URLs shouldn't be looked up"),
+        };
+        assert!(condition.matches(&context));
+
+        let condition = ScopeCondition {
+            repositories: None,
+            remotes: Some(vec![
+                StringPattern::glob("git@example.org:work/*").unwrap(),
+                StringPattern::glob("https://example.org/work/*").unwrap(),
+            ]),
+        };
+
+        let context = ConfigResolutionContext {
+            home_dir: None,
+            repo_path: Some(Path::new("/foo")),
+            remote_urls: &|| &[],
+        };
+        assert!(!condition.matches(&context));
+        let remote_urls = ["https://example.org/oss/foo".to_owned()];
+        let context = ConfigResolutionContext {
+            home_dir: None,
+            repo_path: Some(Path::new("/foo")),
+            remote_urls: &|| &remote_urls,
+        };
+        assert!(!condition.matches(&context));
+        let remote_urls = [
+            "https://example.org/oss/foo".to_owned(),
+            "git@example.org:work/foo".to_owned(),
+        ];
+        let context = ConfigResolutionContext {
+            home_dir: None,
+            repo_path: Some(Path::new("/foo")),
+            remote_urls: &|| &remote_urls,
+        };
+        assert!(condition.matches(&context));
+    }
+
     fn new_user_layer(text: &str) -> ConfigLayer {
         ConfigLayer::parse(ConfigSource::User, text).unwrap()
     }
@@ -380,6 +645,7 @@ mod tests {
         let context = ConfigResolutionContext {
             home_dir: None,
             repo_path: None,
+            remote_urls: &|| &[],
         };
         let resolved_config = resolve(&source_config, &context).unwrap();
         assert_eq!(resolved_config.layers().len(), 2);
@@ -416,6 +682,7 @@ mod tests {
         let context = ConfigResolutionContext {
             home_dir: None,
             repo_path: None,
+            remote_urls: &|| &[],
         };
         let resolved_config = resolve(&source_config, &context).unwrap();
         assert_eq!(resolved_config.layers().len(), 7);
@@ -454,6 +721,7 @@ mod tests {
         let context = ConfigResolutionContext {
             home_dir: Some(Path::new("/home/dir")),
             repo_path: None,
+            remote_urls: &|| &[],
         };
         let resolved_config = resolve(&source_config, &context).unwrap();
         assert_eq!(resolved_config.layers().len(), 1);
@@ -462,6 +730,7 @@ mod tests {
         let context = ConfigResolutionContext {
             home_dir: Some(Path::new("/home/dir")),
             repo_path: Some(Path::new("/foo/.jj/repo")),
+            remote_urls: &|| &[],
         };
         let resolved_config = resolve(&source_config, &context).unwrap();
         assert_eq!(resolved_config.layers().len(), 3);
@@ -472,6 +741,7 @@ mod tests {
         let context = ConfigResolutionContext {
             home_dir: Some(Path::new("/home/dir")),
             repo_path: Some(Path::new("/bar/.jj/repo")),
+            remote_urls: &|| &[],
         };
         let resolved_config = resolve(&source_config, &context).unwrap();
         assert_eq!(resolved_config.layers().len(), 2);
@@ -481,6 +751,7 @@ mod tests {
         let context = ConfigResolutionContext {
             home_dir: Some(Path::new("/home/dir")),
             repo_path: Some(Path::new("/home/dir/baz/.jj/repo")),
+            remote_urls: &|| &[],
         };
         let resolved_config = resolve(&source_config, &context).unwrap();
         assert_eq!(resolved_config.layers().len(), 2);
@@ -488,6 +759,52 @@ mod tests {
         insta::assert_snapshot!(resolved_config.layers()[1].data, @"a = 'a #1 baz'");
     }
 
+    #[test]
+    fn test_resolve_remotes() {
+        let mut source_config = StackedConfig::empty();
+        source_config.add_layer(new_user_layer(indoc! {"
+            a = 'a #0'
+            [[--scope]]
+            --when.remotes = ['*github.com?work/*']
+            a = 'a #0.1 work'
+            [[--scope]]
+            --when.remotes = ['*github.com?work/*']
+            --when.repositories = ['/foo']
+            a = 'a #0.2 work&foo'
+        "}));
+
+        let context = ConfigResolutionContext {
+            home_dir: Some(Path::new("/home/dir")),
+            repo_path: Some(Path::new("/foo/.jj/repo")),
+            remote_urls: &|| &[],
+        };
+        let resolved_config = resolve(&source_config, &context).unwrap();
+        assert_eq!(resolved_config.layers().len(), 1);
+        insta::assert_snapshot!(resolved_config.layers()[0].data, @"a = 'a #0'");
+
+        let remote_urls = ["git@github.com:work/repo.git".to_owned()];
+        let context = ConfigResolutionContext {
+            home_dir: Some(Path::new("/home/dir")),
+            repo_path: Some(Path::new("/bar/.jj/repo")),
+            remote_urls: &|| &remote_urls,
+        };
+        let resolved_config = resolve(&source_config, &context).unwrap();
+        assert_eq!(resolved_config.layers().len(), 2);
+        insta::assert_snapshot!(resolved_config.layers()[0].data, @"a = 'a #0'");
+        insta::assert_snapshot!(resolved_config.layers()[1].data, @"a = 'a #0.1 work'");
+
+        let remote_urls = ["https://github.com/work/repo".to_owned()];
+        let context = ConfigResolutionContext {
+            home_dir: Some(Path::new("/home/dir")),
+            repo_path: Some(Path::new("/foo/.jj/repo")),
+            remote_urls: &|| &remote_urls,
+        };
+        let resolved_config = resolve(&source_config, &context).unwrap();
+        assert_eq!(resolved_config.layers().len(), 3);
+        insta::assert_snapshot!(resolved_config.layers()[1].data, @"a = 'a #0.1 work'");
+        insta::assert_snapshot!(resolved_config.layers()[2].data, @"a = 'a #0.2 work&foo'");
+    }
+
     #[test]
     fn test_resolve_invalid_condition() {
         let new_config = |text: &str| {
@@ -498,11 +815,16 @@ mod tests {
         let context = ConfigResolutionContext {
             home_dir: Some(Path::new("/home/dir")),
             repo_path: Some(Path::new("/foo/.jj/repo")),
+            remote_urls: &|| &[],
         };
         assert_matches!(
             resolve(&new_config("--when.repositories = 0"), &context),
             Err(ConfigGetError::Type { .. })
         );
+        assert_matches!(
+            resolve(&new_config("--when.remotes = ['[']"), &context),
+            Err(ConfigGetError::Type { .. })
+        );
     }
 
     #[test]
@@ -515,6 +837,7 @@ mod tests {
         let context = ConfigResolutionContext {
             home_dir: Some(Path::new("/home/dir")),
             repo_path: Some(Path::new("/foo/.jj/repo")),
+            remote_urls: &|| &[],
         };
         assert_matches!(
             resolve(&new_config("[--scope]"), &context),
@@ -522,6 +845,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_expand_env_vars() {
+        let lookup_env = |name: &str| match name {
+            "FOO" => Some("foo".to_owned()),
+            "EMPTY" => Some("".to_owned()),
+            _ => None,
+        };
+        let expand = |text: &str, mode| expand_env_vars(text, mode, &lookup_env);
+        let lenient = EnvInterpolationMode::Lenient;
+        let strict = EnvInterpolationMode::Strict;
+
+        assert_eq!(expand("${FOO}", lenient).unwrap(), "foo");
+        assert_eq!(expand("a${FOO}b${EMPTY}c", lenient).unwrap(), "afoobc");
+        assert_eq!(expand("${FOO}${FOO}", strict).unwrap(), "foofoo");
+        // Escaped
+        assert_eq!(expand("$${FOO}", strict).unwrap(), "${FOO}");
+        assert_eq!(expand("$$${FOO}", strict).unwrap(), "$foo");
+        // Not a reference
+        assert_eq!(expand("$FOO $ $$", strict).unwrap(), "$FOO $ $$");
+
+        // Undefined variable
+        assert_eq!(expand("a${BAR}b", lenient).unwrap(), "a${BAR}b");
+        assert_eq!(
+            expand("a${BAR}b", strict).unwrap_err(),
+            "Environment variable BAR is not set"
+        );
+
+        // Invalid reference
+        assert_eq!(expand("${FOO", lenient).unwrap(), "${FOO");
+        assert_eq!(expand("${0}${FOO}", lenient).unwrap(), "${0}foo");
+        assert_eq!(expand("${FOO:-x}", lenient).unwrap(), "${FOO:-x}");
+        assert!(expand("${FOO", strict).is_err());
+        assert!(expand("${}", strict).is_err());
+    }
+
+    #[test]
+    fn test_interpolate_env() {
+        let lookup_env = |name: &str| (name == "FOO").then(|| "foo".to_owned());
+        let mut source_config = StackedConfig::empty();
+        source_config.add_layer(ConfigLayer::parse(ConfigSource::Default, "a = '${FOO}'").unwrap());
+        source_config.add_layer(new_user_layer(indoc! {"
+            b = '${FOO}'
+            c = ['x', '${FOO}/y']
+            d = { e = '${FOO}', f = 1 }
+            [[g]]
+            h = '$${FOO}'
+        "}));
+
+        let config =
+            interpolate_env(&source_config, EnvInterpolationMode::Strict, &lookup_env).unwrap();
+        // Default layer isn't interpolated
+        assert!(Arc::ptr_eq(&source_config.layers()[0], &config.layers()[0]));
+        assert_eq!(config.get::<String>("a").unwrap(), "${FOO}");
+        assert_eq!(config.get::<String>("b").unwrap(), "foo");
+        assert_eq!(config.get::<Vec<String>>("c").unwrap(), ["x", "foo/y"]);
+        assert_eq!(config.get::<String>("d.e").unwrap(), "foo");
+        assert_eq!(config.get::<i64>("d.f").unwrap(), 1);
+        assert_eq!(
+            config.layers()[1].data["g"][0]["h"].as_str(),
+            Some("${FOO}")
+        );
+
+        let mut source_config = StackedConfig::empty();
+        source_config.add_layer(new_user_layer("[a.b]\nc = ['${BAR}']"));
+        assert_matches!(
+            interpolate_env(&source_config, EnvInterpolationMode::Strict, &lookup_env),
+            Err(ConfigGetError::Type { name, .. }) if name == "a.b.c"
+        );
+        let config =
+            interpolate_env(&source_config, EnvInterpolationMode::Lenient, &lookup_env).unwrap();
+        assert_eq!(config.get::<Vec<String>>("a.b.c").unwrap(), ["${BAR}"]);
+    }
+
     #[test]
     fn test_migrate_noop() {
         let mut config = StackedConfig::empty();
@@ -0,0 +1,569 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validation of config variables against a schema of known names.
+
+use std::collections::BTreeMap;
+
+use itertools::Itertools as _;
+use regex::Regex;
+use thiserror::Error;
+
+use crate::config::ConfigItem;
+use crate::config::ConfigNamePathBuf;
+use crate::config::ConfigTableLike;
+use crate::config::ConfigValue;
+use crate::dsl_util::collect_similar;
+
+/// Error that can occur when parsing a config schema.
+#[derive(Debug, Error)]
+pub enum ConfigSchemaParseError {
+    /// Schema text cannot be parsed as JSON.
+    #[error("Config schema cannot be parsed as JSON")]
+    Json(#[source] serde_json::Error),
+    /// `$ref` doesn't point to a node in the same document.
+    #[error("Unresolved reference {0} in config schema")]
+    UnresolvedRef(String),
+    /// `pattern` is not a valid regular expression.
+    #[error("Invalid pattern in config schema")]
+    Pattern(#[source] regex::Error),
+}
+
+/// Problem found while validating config variables against a schema.
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+pub enum ConfigSchemaError {
+    /// Config name isn't defined in the schema.
+    #[error("Unknown config key {name}")]
+    UnknownName {
+        /// Dotted config name path.
+        name: String,
+        /// Similar names defined in the same table.
+        similar: Vec<String>,
+    },
+    /// Config value doesn't match the type or values allowed by the schema.
+    #[error("Invalid value for {name}: expected {expected}")]
+    InvalidValue {
+        /// Dotted config name path.
+        name: String,
+        /// Description of the allowed values.
+        expected: String,
+    },
+}
+
+/// Schema of known config variables.
+///
+/// The schema is loaded from a subset of JSON Schema. Only `type`, `enum`,
+/// `minimum`, `pattern`, `properties`, `additionalProperties`, `items`,
+/// `oneOf`, and local `$ref`s are interpreted. Other keywords are ignored.
+///
+/// Since users may define arbitrary top-level tables, unknown names are
+/// reported only within known tables that list their `properties`.
+#[derive(Clone, Debug)]
+pub struct ConfigSchema {
+    root: SchemaNode,
+}
+
+impl ConfigSchema {
+    /// Parses JSON Schema text.
+    ///
+    /// `$ref`s must not be recursive.
+    pub fn from_json(text: &str) -> Result<Self, ConfigSchemaParseError> {
+        let document: serde_json::Value =
+            serde_json::from_str(text).map_err(ConfigSchemaParseError::Json)?;
+        let root = SchemaNode::parse(&document, &document)?;
+        Ok(ConfigSchema { root })
+    }
+
+    /// Validates the `value` to be set to the variable of the given `name`.
+    pub fn validate_value(
+        &self,
+        name: &ConfigNamePathBuf,
+        value: &ConfigValue,
+    ) -> Vec<ConfigSchemaError> {
+        let mut errors = vec![];
+        if let Some(node) = self.look_up(name, &mut errors) {
+            let item = ConfigItem::Value(value.clone());
+            node.validate_item(name, &item, &mut errors);
+        }
+        errors
+    }
+
+    /// Validates all variables defined in the given top-level `table`.
+    ///
+    /// Tables in `[[--scope]]` are validated as if they were defined at the
+    /// top level.
+    pub fn validate_table(&self, table: &ConfigTableLike) -> Vec<ConfigSchemaError> {
+        let mut errors = vec![];
+        self.validate_root_table(table, &mut errors);
+        errors
+    }
+
+    fn validate_root_table(&self, table: &ConfigTableLike, errors: &mut Vec<ConfigSchemaError>) {
+        for (key, item) in table.iter() {
+            if key == "--scope" {
+                for scope_table in item.as_array_of_tables().into_iter().flatten() {
+                    self.validate_root_table(scope_table, errors);
+                }
+            } else if let Some(node) = self.root.child(key) {
+                let name = ConfigNamePathBuf::from_iter([key]);
+                node.validate_item(&name, item, errors);
+            }
+        }
+    }
+
+    fn look_up(
+        &self,
+        name: &ConfigNamePathBuf,
+        errors: &mut Vec<ConfigSchemaError>,
+    ) -> Option<&SchemaNode> {
+        let mut node = &self.root;
+        let mut parent = ConfigNamePathBuf::root();
+        for key in name.components() {
+            let Some(child) = node.child(key.get()) else {
+                if !parent.is_root() {
+                    errors.extend(node.unknown_name(&parent, key.get()));
+                }
+                return None;
+            };
+            node = child;
+            parent.push(key.clone());
+        }
+        Some(node)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ValueType {
+    Array,
+    Boolean,
+    Integer,
+    Number,
+    Object,
+    String,
+}
+
+impl ValueType {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "array" => Some(ValueType::Array),
+            "boolean" => Some(ValueType::Boolean),
+            "integer" => Some(ValueType::Integer),
+            "number" => Some(ValueType::Number),
+            "object" => Some(ValueType::Object),
+            "string" => Some(ValueType::String),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ValueType::Array => "array",
+            ValueType::Boolean => "boolean",
+            ValueType::Integer => "integer",
+            ValueType::Number => "number",
+            ValueType::Object => "table",
+            ValueType::String => "string",
+        }
+    }
+
+    fn matches(self, value: &ConfigValue) -> bool {
+        matches!(
+            (self, value),
+            (ValueType::Array, ConfigValue::Array(_))
+                | (ValueType::Boolean, ConfigValue::Boolean(_))
+                | (ValueType::Integer, ConfigValue::Integer(_))
+                | (
+                    ValueType::Number,
+                    ConfigValue::Integer(_) | ConfigValue::Float(_)
+                )
+                | (ValueType::Object, ConfigValue::InlineTable(_))
+                | (ValueType::String, ConfigValue::String(_))
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct SchemaNode {
+    types: Vec<ValueType>,
+    enum_values: Vec<serde_json::Value>,
+    minimum: Option<f64>,
+    pattern: Option<Regex>,
+    properties: Option<BTreeMap<String, SchemaNode>>,
+    additional_properties: Option<Box<SchemaNode>>,
+    items: Option<Box<SchemaNode>>,
+    one_of: Vec<SchemaNode>,
+}
+
+impl SchemaNode {
+    fn parse(
+        node: &serde_json::Value,
+        document: &serde_json::Value,
+    ) -> Result<Self, ConfigSchemaParseError> {
+        let node = resolve_ref(node, document)?;
+        let parse_child = |child: &serde_json::Value| SchemaNode::parse(child, document);
+        let types = match node.get("type") {
+            Some(serde_json::Value::String(name)) => {
+                ValueType::from_name(name).into_iter().collect()
+            }
+            Some(serde_json::Value::Array(names)) => names
+                .iter()
+                .filter_map(|name| ValueType::from_name(name.as_str()?))
+                .collect(),
+            _ => vec![],
+        };
+        let enum_values = node
+            .get("enum")
+            .and_then(|values| values.as_array())
+            .cloned()
+            .unwrap_or_default();
+        let minimum = node.get("minimum").and_then(|value| value.as_f64());
+        let pattern = node
+            .get("pattern")
+            .and_then(|value| value.as_str())
+            .map(Regex::new)
+            .transpose()
+            .map_err(ConfigSchemaParseError::Pattern)?;
+        let properties = node
+            .get("properties")
+            .and_then(|properties| properties.as_object())
+            .map(|properties| {
+                properties
+                    .iter()
+                    .map(|(key, child)| parse_child(child).map(|node| (key.clone(), node)))
+                    .try_collect()
+            })
+            .transpose()?;
+        let additional_properties = match node.get("additionalProperties") {
+            Some(serde_json::Value::Bool(true)) => Some(Box::default()),
+            Some(child @ serde_json::Value::Object(_)) => Some(Box::new(parse_child(child)?)),
+            _ => None,
+        };
+        let items = node
+            .get("items")
+            .map(parse_child)
+            .transpose()?
+            .map(Box::new);
+        let one_of = node
+            .get("oneOf")
+            .and_then(|alternatives| alternatives.as_array())
+            .map(|alternatives| alternatives.iter().map(parse_child).try_collect())
+            .transpose()?
+            .unwrap_or_default();
+        Ok(SchemaNode {
+            types,
+            enum_values,
+            minimum,
+            pattern,
+            properties,
+            additional_properties,
+            items,
+            one_of,
+        })
+    }
+
+    fn child(&self, key: &str) -> Option<&SchemaNode> {
+        self.properties
+            .as_ref()
+            .and_then(|properties| properties.get(key))
+            .or(self.additional_properties.as_deref())
+            .or_else(|| self.one_of.iter().find_map(|node| node.child(key)))
+    }
+
+    fn accepts_table(&self) -> bool {
+        (self.types.is_empty() || self.types.contains(&ValueType::Object))
+            && self.enum_values.is_empty()
+            && (self.one_of.is_empty() || self.one_of.iter().any(|node| node.accepts_table()))
+    }
+
+    fn accepts_value(&self, value: &ConfigValue) -> bool {
+        if !self.types.is_empty() && !self.types.iter().any(|ty| ty.matches(value)) {
+            return false;
+        }
+        if !self.enum_values.is_empty() && !self.enum_values.iter().any(|item| json_eq(item, value))
+        {
+            return false;
+        }
+        if !self.one_of.is_empty() && !self.one_of.iter().any(|node| node.accepts_value(value)) {
+            return false;
+        }
+        match value {
+            ConfigValue::String(s) => self
+                .pattern
+                .as_ref()
+                .map_or(true, |pattern| pattern.is_match(s.value())),
+            ConfigValue::Integer(n) => self.minimum.map_or(true, |min| *n.value() as f64 >= min),
+            ConfigValue::Float(n) => self.minimum.map_or(true, |min| *n.value() >= min),
+            ConfigValue::Boolean(_) | ConfigValue::Datetime(_) => true,
+            ConfigValue::Array(array) => self.items.as_ref().map_or(true, |node| {
+                array.iter().all(|item| node.accepts_value(item))
+            }),
+            ConfigValue::InlineTable(table) => table.iter().all(|(key, item)| {
+                self.child(key)
+                    .map_or(true, |node| node.accepts_value(item))
+            }),
+        }
+    }
+
+    fn describe(&self) -> String {
+        if !self.enum_values.is_empty() {
+            format!("one of {}", self.enum_values.iter().join(", "))
+        } else if !self.one_of.is_empty() {
+            self.one_of.iter().map(|node| node.describe()).join(" or ")
+        } else if !self.types.is_empty() {
+            let names = self.types.iter().map(|ty| ty.name()).join(" or ");
+            match (self.minimum, &self.pattern) {
+                (Some(min), _) => format!("{names} >= {min}"),
+                (None, Some(pattern)) => format!("{names} matching {pattern}"),
+                (None, None) => names,
+            }
+        } else {
+            "any value".to_owned()
+        }
+    }
+
+    fn validate_item(
+        &self,
+        name: &ConfigNamePathBuf,
+        item: &ConfigItem,
+        errors: &mut Vec<ConfigSchemaError>,
+    ) {
+        if let Some(table) = item.as_table_like() {
+            self.validate_table_like(name, table, errors);
+        } else if let Some(tables) = item.as_array_of_tables() {
+            if !self.types.is_empty() && !self.types.contains(&ValueType::Array) {
+                errors.push(self.invalid_value(name));
+            } else if let Some(node) = &self.items {
+                for table in tables.iter() {
+                    node.validate_table_like(name, table, errors);
+                }
+            }
+        } else if let Some(value) = item.as_value() {
+            if !self.accepts_value(value) {
+                errors.push(self.invalid_value(name));
+            }
+        }
+    }
+
+    fn validate_table_like(
+        &self,
+        name: &ConfigNamePathBuf,
+        table: &ConfigTableLike,
+        errors: &mut Vec<ConfigSchemaError>,
+    ) {
+        if !self.accepts_table() {
+            errors.push(self.invalid_value(name));
+            return;
+        }
+        for (key, item) in table.iter() {
+            if let Some(node) = self.child(key) {
+                let mut sub_name = name.clone();
+                sub_name.push(key);
+                node.validate_item(&sub_name, item, errors);
+            } else {
+                errors.extend(self.unknown_name(name, key));
+            }
+        }
+    }
+
+    fn invalid_value(&self, name: &ConfigNamePathBuf) -> ConfigSchemaError {
+        ConfigSchemaError::InvalidValue {
+            name: name.to_string(),
+            expected: self.describe(),
+        }
+    }
+
+    /// Returns error if this table defines a fixed set of properties.
+    fn unknown_name(&self, parent: &ConfigNamePathBuf, key: &str) -> Option<ConfigSchemaError> {
+        let properties = self.properties.as_ref()?;
+        let to_name = |key: &str| {
+            let mut name = parent.clone();
+            name.push(key);
+            name.to_string()
+        };
+        Some(ConfigSchemaError::UnknownName {
+            name: to_name(key),
+            similar: collect_similar(key, properties.keys())
+                .iter()
+                .map(|key| to_name(key))
+                .collect(),
+        })
+    }
+}
+
+fn resolve_ref<'a>(
+    mut node: &'a serde_json::Value,
+    document: &'a serde_json::Value,
+) -> Result<&'a serde_json::Value, ConfigSchemaParseError> {
+    while let Some(reference) = node.get("$ref").and_then(|value| value.as_str()) {
+        node = reference
+            .strip_prefix('#')
+            .and_then(|pointer| document.pointer(pointer))
+            .ok_or_else(|| ConfigSchemaParseError::UnresolvedRef(reference.to_owned()))?;
+    }
+    Ok(node)
+}
+
+fn json_eq(expected: &serde_json::Value, value: &ConfigValue) -> bool {
+    match (expected, value) {
+        (serde_json::Value::String(expected), ConfigValue::String(s)) => expected == s.value(),
+        (serde_json::Value::Bool(expected), ConfigValue::Boolean(b)) => expected == b.value(),
+        (serde_json::Value::Number(expected), ConfigValue::Integer(n)) => {
+            expected.as_i64() == Some(*n.value())
+        }
+        (serde_json::Value::Number(expected), ConfigValue::Float(n)) => {
+            expected.as_f64() == Some(*n.value())
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::config::ConfigLayer;
+    use crate::config::ConfigSource;
+
+    fn new_schema() -> ConfigSchema {
+        ConfigSchema::from_json(indoc! {r##"
+            {
+                "definitions": {
+                    "color": {
+                        "oneOf": [
+                            { "enum": ["red", "green"] },
+                            { "type": "string", "pattern": "^#[0-9a-f]{6}$" }
+                        ]
+                    }
+                },
+                "properties": {
+                    "ui": {
+                        "type": "object",
+                        "properties": {
+                            "color": { "enum": ["always", "never", "auto"] },
+                            "limit": { "type": "integer", "minimum": 0 },
+                            "command": {
+                                "oneOf": [
+                                    { "type": "string" },
+                                    { "type": "array", "items": { "type": "string" } }
+                                ]
+                            }
+                        }
+                    },
+                    "colors": {
+                        "type": "object",
+                        "additionalProperties": { "$ref": "#/definitions/color" }
+                    },
+                    "aliases": {
+                        "type": "object",
+                        "additionalProperties": true
+                    }
+                }
+            }
+        "##})
+        .unwrap()
+    }
+
+    fn validate_value(schema: &ConfigSchema, name: &str, value: &str) -> Vec<ConfigSchemaError> {
+        schema.validate_value(&name.parse().unwrap(), &value.parse().unwrap())
+    }
+
+    fn validate_text(schema: &ConfigSchema, text: &str) -> Vec<ConfigSchemaError> {
+        let layer = ConfigLayer::parse(ConfigSource::User, text).unwrap();
+        schema.validate_table(layer.data.as_table())
+    }
+
+    #[test]
+    fn test_validate_value() {
+        let schema = new_schema();
+        assert_eq!(validate_value(&schema, "ui.color", "'never'"), vec![]);
+        assert_eq!(validate_value(&schema, "ui.limit", "10"), vec![]);
+        assert_eq!(validate_value(&schema, "ui.command", "['log']"), vec![]);
+        assert_eq!(validate_value(&schema, "colors.foo", "'#00ff00'"), vec![]);
+        assert_eq!(validate_value(&schema, "aliases.x", "{ y = 1 }"), vec![]);
+        // Unknown top-level names are allowed
+        assert_eq!(validate_value(&schema, "foo.bar", "1"), vec![]);
+
+        assert_eq!(
+            validate_value(&schema, "ui.color", "'tru'"),
+            vec![ConfigSchemaError::InvalidValue {
+                name: "ui.color".to_owned(),
+                expected: r#"one of "always", "never", "auto""#.to_owned(),
+            }]
+        );
+        assert_eq!(
+            validate_value(&schema, "ui.limit", "-1"),
+            vec![ConfigSchemaError::InvalidValue {
+                name: "ui.limit".to_owned(),
+                expected: "integer >= 0".to_owned(),
+            }]
+        );
+        assert_eq!(
+            validate_value(&schema, "ui.command", "[1]"),
+            vec![ConfigSchemaError::InvalidValue {
+                name: "ui.command".to_owned(),
+                expected: "string or array".to_owned(),
+            }]
+        );
+        assert_eq!(
+            validate_value(&schema, "colors.foo", "'blue'"),
+            vec![ConfigSchemaError::InvalidValue {
+                name: "colors.foo".to_owned(),
+                expected: r#"one of "red", "green" or string matching ^#[0-9a-f]{6}$"#.to_owned(),
+            }]
+        );
+        assert_eq!(
+            validate_value(&schema, "ui.colour", "'never'"),
+            vec![ConfigSchemaError::UnknownName {
+                name: "ui.colour".to_owned(),
+                similar: vec!["ui.color".to_owned()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validate_table() {
+        let schema = new_schema();
+        let text = indoc! {"
+            foo.bar = 1
+            ui.color = 'never'
+            aliases.x = ['log']
+
+            [[--scope]]
+            --when.repositories = ['~/repo']
+            ui = { limit = 'x', colour = 'auto' }
+        "};
+        assert_eq!(
+            validate_text(&schema, text),
+            vec![
+                ConfigSchemaError::InvalidValue {
+                    name: "ui.limit".to_owned(),
+                    expected: "integer >= 0".to_owned(),
+                },
+                ConfigSchemaError::UnknownName {
+                    name: "ui.colour".to_owned(),
+                    similar: vec!["ui.color".to_owned()],
+                },
+            ]
+        );
+
+        assert_eq!(
+            validate_text(&schema, "ui.color.x = 'never'"),
+            vec![ConfigSchemaError::InvalidValue {
+                name: "ui.color".to_owned(),
+                expected: r#"one of "always", "never", "auto""#.to_owned(),
+            }]
+        );
+    }
+}
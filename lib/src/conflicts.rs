@@ -14,11 +14,13 @@
 
 #![allow(missing_docs)]
 
+use std::borrow::Cow;
 use std::io;
 use std::io::Read;
 use std::io::Write;
 use std::iter::zip;
 
+use bstr::BStr;
 use bstr::BString;
 use bstr::ByteSlice;
 use futures::stream::BoxStream;
@@ -42,7 +44,6 @@ use crate::diff::Diff;
 use crate::diff::DiffHunk;
 use crate::diff::DiffHunkKind;
 use crate::files;
-use crate::files::MergeResult;
 use crate::merge::Merge;
 use crate::merge::MergeBuilder;
 use crate::merge::MergedTreeValue;
@@ -108,6 +109,42 @@ async fn get_file_contents(
     }
 }
 
+/// Number of leading bytes inspected to determine whether file content is
+/// binary. This is the same heuristic as Git's.
+const BINARY_PEEK_SIZE: u64 = 8000;
+
+/// Returns true if any of the file terms of the `conflict` looks like binary
+/// content, which can't be resolved by line-based merge.
+///
+/// A file is considered binary if its first 8000 bytes contain a null byte. If
+/// the conflict involves non-file terms, this returns false.
+pub async fn is_binary_file_conflict(
+    store: &Store,
+    path: &RepoPath,
+    conflict: &MergedTreeValue,
+) -> BackendResult<bool> {
+    let Some(file_merge) = conflict.to_file_merge() else {
+        return Ok(false);
+    };
+    for id in file_merge.iter().flatten() {
+        let mut content = vec![];
+        store
+            .read_file_async(path, id)
+            .await?
+            .take(BINARY_PEEK_SIZE)
+            .read_to_end(&mut content)
+            .map_err(|err| BackendError::ReadFile {
+                path: path.to_owned(),
+                id: id.clone(),
+                source: err.into(),
+            })?;
+        if content.contains(&0) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 pub async fn extract_as_single_hunk(
     merge: &Merge<Option<FileId>>,
     store: &Store,
@@ -329,82 +366,145 @@ pub fn choose_materialized_conflict_marker_len<T: AsRef<[u8]>>(single_hunk: &Mer
         .max(MIN_CONFLICT_MARKER_LEN)
 }
 
+/// Materializes the merge of `single_hunk` with conflict markers, and writes
+/// it to the `output`.
+///
+/// The merge result is streamed hunk by hunk, so the whole materialized
+/// content isn't buffered in memory.
 pub fn materialize_merge_result<T: AsRef<[u8]>>(
     single_hunk: &Merge<T>,
     conflict_marker_style: ConflictMarkerStyle,
     output: &mut dyn Write,
 ) -> io::Result<()> {
-    let merge_result = files::merge(single_hunk);
-    match &merge_result {
-        MergeResult::Resolved(content) => output.write_all(content),
-        MergeResult::Conflict(hunks) => {
-            let conflict_marker_len = choose_materialized_conflict_marker_len(single_hunk);
-            materialize_conflict_hunks(hunks, conflict_marker_style, conflict_marker_len, output)
-        }
+    let hunks = files::merge_borrowed(single_hunk);
+    let conflict_marker_len = choose_conflict_marker_len_for_hunks(single_hunk, &hunks);
+    materialize_conflict_hunks(&hunks, conflict_marker_style, conflict_marker_len, output)
+}
+
+fn choose_conflict_marker_len_for_hunks<T: AsRef<[u8]>>(
+    single_hunk: &Merge<T>,
+    hunks: &[Merge<&BStr>],
+) -> usize {
+    // Choosing the marker length requires scanning all lines, which can be
+    // skipped if there are no conflicts.
+    if hunks.iter().all(|hunk| hunk.is_resolved()) {
+        MIN_CONFLICT_MARKER_LEN
+    } else {
+        choose_materialized_conflict_marker_len(single_hunk)
     }
 }
 
+/// Like `materialize_merge_result()`, but uses the given conflict marker
+/// length.
 pub fn materialize_merge_result_with_marker_len<T: AsRef<[u8]>>(
     single_hunk: &Merge<T>,
     conflict_marker_style: ConflictMarkerStyle,
     conflict_marker_len: usize,
     output: &mut dyn Write,
 ) -> io::Result<()> {
-    let merge_result = files::merge(single_hunk);
-    match &merge_result {
-        MergeResult::Resolved(content) => output.write_all(content),
-        MergeResult::Conflict(hunks) => {
-            materialize_conflict_hunks(hunks, conflict_marker_style, conflict_marker_len, output)
-        }
-    }
+    let hunks = files::merge_borrowed(single_hunk);
+    materialize_conflict_hunks(&hunks, conflict_marker_style, conflict_marker_len, output)
 }
 
+/// Materializes the merge of `single_hunk` with conflict markers into a
+/// buffer.
+///
+/// Prefer `materialize_merge_result()` if the content can be streamed.
 pub fn materialize_merge_result_to_bytes<T: AsRef<[u8]>>(
     single_hunk: &Merge<T>,
     conflict_marker_style: ConflictMarkerStyle,
 ) -> BString {
-    let merge_result = files::merge(single_hunk);
-    match merge_result {
-        MergeResult::Resolved(content) => content,
-        MergeResult::Conflict(hunks) => {
-            let conflict_marker_len = choose_materialized_conflict_marker_len(single_hunk);
-            let mut output = Vec::new();
-            materialize_conflict_hunks(
-                &hunks,
-                conflict_marker_style,
-                conflict_marker_len,
-                &mut output,
-            )
-            .expect("writing to an in-memory buffer should never fail");
-            output.into()
-        }
-    }
+    let mut output = Vec::new();
+    materialize_merge_result(single_hunk, conflict_marker_style, &mut output)
+        .expect("writing to an in-memory buffer should never fail");
+    output.into()
 }
 
+/// Like `materialize_merge_result_to_bytes()`, but uses the given conflict
+/// marker length.
 pub fn materialize_merge_result_to_bytes_with_marker_len<T: AsRef<[u8]>>(
     single_hunk: &Merge<T>,
     conflict_marker_style: ConflictMarkerStyle,
     conflict_marker_len: usize,
 ) -> BString {
-    let merge_result = files::merge(single_hunk);
-    match merge_result {
-        MergeResult::Resolved(content) => content,
-        MergeResult::Conflict(hunks) => {
-            let mut output = Vec::new();
-            materialize_conflict_hunks(
-                &hunks,
-                conflict_marker_style,
-                conflict_marker_len,
-                &mut output,
-            )
-            .expect("writing to an in-memory buffer should never fail");
-            output.into()
+    let mut output = Vec::new();
+    materialize_merge_result_with_marker_len(
+        single_hunk,
+        conflict_marker_style,
+        conflict_marker_len,
+        &mut output,
+    )
+    .expect("writing to an in-memory buffer should never fail");
+    output.into()
+}
+
+/// Reader of the merge of a single hunk materialized with conflict markers.
+///
+/// The content is materialized hunk by hunk as it is read, so the whole
+/// materialized content isn't buffered in memory. Resolved hunks are read
+/// directly from the input contents.
+pub struct MaterializedMergeReader<'a> {
+    hunks: std::vec::IntoIter<Merge<&'a BStr>>,
+    conflict_marker_style: ConflictMarkerStyle,
+    conflict_marker_len: usize,
+    num_conflicts: usize,
+    conflict_index: usize,
+    chunk: Cow<'a, [u8]>,
+    pos: usize,
+}
+
+impl<'a> MaterializedMergeReader<'a> {
+    pub fn new<T: AsRef<[u8]>>(
+        single_hunk: &'a Merge<T>,
+        conflict_marker_style: ConflictMarkerStyle,
+    ) -> Self {
+        let hunks = files::merge_borrowed(single_hunk);
+        let conflict_marker_len = choose_conflict_marker_len_for_hunks(single_hunk, &hunks);
+        let num_conflicts = hunks.iter().filter(|hunk| !hunk.is_resolved()).count();
+        MaterializedMergeReader {
+            hunks: hunks.into_iter(),
+            conflict_marker_style,
+            conflict_marker_len,
+            num_conflicts,
+            conflict_index: 0,
+            chunk: Cow::Borrowed(&[]),
+            pos: 0,
         }
     }
 }
 
+impl Read for MaterializedMergeReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos >= self.chunk.len() {
+            let Some(hunk) = self.hunks.next() else {
+                return Ok(0);
+            };
+            self.chunk = if let Some(&content) = hunk.as_resolved() {
+                Cow::Borrowed(content.as_bytes())
+            } else {
+                self.conflict_index += 1;
+                let mut output = Vec::new();
+                materialize_conflict_hunk(
+                    &hunk,
+                    self.conflict_index,
+                    self.num_conflicts,
+                    self.conflict_marker_style,
+                    self.conflict_marker_len,
+                    &mut output,
+                )?;
+                Cow::Owned(output)
+            };
+            self.pos = 0;
+        }
+        let len = buf.len().min(self.chunk.len() - self.pos);
+        buf[..len].copy_from_slice(&self.chunk[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
 fn materialize_conflict_hunks(
-    hunks: &[Merge<BString>],
+    hunks: &[Merge<&BStr>],
     conflict_marker_style: ConflictMarkerStyle,
     conflict_marker_len: usize,
     output: &mut dyn Write,
@@ -419,35 +519,48 @@ fn materialize_conflict_hunks(
             output.write_all(content)?;
         } else {
             conflict_index += 1;
-            let conflict_info = format!("Conflict {conflict_index} of {num_conflicts}");
-
-            match (conflict_marker_style, hunk.as_slice()) {
-                // 2-sided conflicts can use Git-style conflict markers
-                (ConflictMarkerStyle::Git, [left, base, right]) => {
-                    materialize_git_style_conflict(
-                        left,
-                        base,
-                        right,
-                        &conflict_info,
-                        conflict_marker_len,
-                        output,
-                    )?;
-                }
-                _ => {
-                    materialize_jj_style_conflict(
-                        hunk,
-                        &conflict_info,
-                        conflict_marker_style,
-                        conflict_marker_len,
-                        output,
-                    )?;
-                }
-            }
+            materialize_conflict_hunk(
+                hunk,
+                conflict_index,
+                num_conflicts,
+                conflict_marker_style,
+                conflict_marker_len,
+                output,
+            )?;
         }
     }
     Ok(())
 }
 
+fn materialize_conflict_hunk(
+    hunk: &Merge<&BStr>,
+    conflict_index: usize,
+    num_conflicts: usize,
+    conflict_marker_style: ConflictMarkerStyle,
+    conflict_marker_len: usize,
+    output: &mut dyn Write,
+) -> io::Result<()> {
+    let conflict_info = format!("Conflict {conflict_index} of {num_conflicts}");
+    match (conflict_marker_style, hunk.as_slice()) {
+        // 2-sided conflicts can use Git-style conflict markers
+        (ConflictMarkerStyle::Git, [left, base, right]) => materialize_git_style_conflict(
+            left,
+            base,
+            right,
+            &conflict_info,
+            conflict_marker_len,
+            output,
+        ),
+        _ => materialize_jj_style_conflict(
+            hunk,
+            &conflict_info,
+            conflict_marker_style,
+            conflict_marker_len,
+            output,
+        ),
+    }
+}
+
 fn materialize_git_style_conflict(
     left: &[u8],
     base: &[u8],
@@ -489,7 +602,7 @@ fn materialize_git_style_conflict(
 }
 
 fn materialize_jj_style_conflict(
-    hunk: &Merge<BString>,
+    hunk: &Merge<&BStr>,
     conflict_info: &str,
     conflict_marker_style: ConflictMarkerStyle,
     conflict_marker_len: usize,
@@ -90,6 +90,16 @@ pub(super) trait IndexSegment: Send + Sync {
     fn num_parents(&self, local_pos: LocalPosition) -> u32;
 
     fn parent_positions(&self, local_pos: LocalPosition) -> SmallIndexPositionsVec;
+
+    fn is_shallow(&self, local_pos: LocalPosition) -> bool;
+
+    /// Returns the local positions of the commits in this segment that have
+    /// `predecessor_id` as a predecessor, in ascending order.
+    fn successor_positions(&self, predecessor_id: &CommitId) -> SmallLocalPositionsVec;
+
+    /// Returns all `(predecessor_id, successor_pos)` pairs recorded in this
+    /// segment, sorted.
+    fn evolution_edges(&self) -> Vec<(CommitId, LocalPosition)>;
 }
 
 pub(super) type DynIndexSegment = dyn IndexSegment;
@@ -148,6 +158,11 @@ impl CompositeIndex {
         self.0.num_parent_commits() + self.0.num_local_commits()
     }
 
+    /// Iterates all entries in index position order, parents first.
+    pub fn entries(&self) -> impl Iterator<Item = IndexEntry<'_>> {
+        (0..self.num_commits()).map(|pos| self.entry_by_pos(IndexPosition(pos)))
+    }
+
     pub fn stats(&self) -> IndexStats {
         let num_commits = self.num_commits();
         let mut num_merges = 0;
@@ -206,6 +221,25 @@ impl CompositeIndex {
         })
     }
 
+    /// Returns the positions of the commits that were rewritten from
+    /// `predecessor_id`, in ascending order.
+    ///
+    /// The predecessor itself doesn't have to be indexed.
+    pub fn successor_positions(&self, predecessor_id: &CommitId) -> Vec<IndexPosition> {
+        let mut positions = self
+            .ancestor_index_segments()
+            .flat_map(|segment| {
+                let num_parent_commits = segment.num_parent_commits();
+                segment
+                    .successor_positions(predecessor_id)
+                    .into_iter()
+                    .map(move |LocalPosition(pos)| IndexPosition(pos + num_parent_commits))
+            })
+            .collect_vec();
+        positions.sort_unstable();
+        positions
+    }
+
     /// Suppose the given `commit_id` exists, returns the previous and next
     /// commit ids in lexicographical order.
     pub(super) fn resolve_neighbor_commit_ids(
@@ -292,17 +326,20 @@ impl CompositeIndex {
         ancestor_pos: IndexPosition,
         descendant_pos: IndexPosition,
     ) -> bool {
+        // Ancestors precede their descendants in the index, and have smaller
+        // generation numbers. Entries that fail either condition are pruned
+        // without visiting their parents.
         let ancestor_generation = self.entry_by_pos(ancestor_pos).generation_number();
         let mut work = vec![descendant_pos];
         let mut visited = HashSet::new();
         while let Some(descendant_pos) = work.pop() {
-            let descendant_entry = self.entry_by_pos(descendant_pos);
             if descendant_pos == ancestor_pos {
                 return true;
             }
-            if !visited.insert(descendant_entry.position()) {
+            if descendant_pos < ancestor_pos || !visited.insert(descendant_pos) {
                 continue;
             }
+            let descendant_entry = self.entry_by_pos(descendant_pos);
             if descendant_entry.generation_number() <= ancestor_generation {
                 continue;
             }
@@ -487,6 +524,13 @@ impl Index for &CompositeIndex {
         Ok(Box::new(self.all_heads()))
     }
 
+    fn successors(&self, commit_id: &CommitId) -> Vec<CommitId> {
+        self.successor_positions(commit_id)
+            .iter()
+            .map(|pos| self.entry_by_pos(*pos).commit_id())
+            .collect()
+    }
+
     fn heads(&self, candidate_ids: &mut dyn Iterator<Item = &CommitId>) -> Vec<CommitId> {
         let candidate_positions: BTreeSet<_> = candidate_ids
             .map(|id| self.commit_id_to_pos(id).unwrap())
@@ -114,6 +114,12 @@ impl<'a> IndexEntry<'a> {
         self.source.parent_positions(self.local_pos)
     }
 
+    /// Returns true if the history beyond this commit is missing. The root
+    /// commit is recorded as the parent of such commits.
+    pub fn is_shallow(&self) -> bool {
+        self.source.is_shallow(self.local_pos)
+    }
+
     pub fn parents(&self) -> impl ExactSizeIterator<Item = IndexEntry<'a>> {
         let composite = CompositeIndex::new(self.source);
         self.parent_positions()
@@ -42,6 +42,7 @@ pub use self::readonly::ReadonlyIndexLoadError;
 pub use self::store::DefaultIndexStore;
 pub use self::store::DefaultIndexStoreError;
 pub use self::store::DefaultIndexStoreInitError;
+pub use self::store::IndexCompactionPolicy;
 
 #[cfg(test)]
 mod tests {
@@ -55,6 +56,7 @@ mod tests {
     use super::composite::IndexSegment;
     use super::entry::SmallIndexPositionsVec;
     use super::mutable::MutableIndexSegment;
+    use super::readonly::ReadonlyIndexSegment;
     use super::*;
     use crate::backend::ChangeId;
     use crate::backend::CommitId;
@@ -972,6 +974,158 @@ mod tests {
         );
     }
 
+    #[test]
+    fn index_mapped_segment() {
+        let temp_dir = testutils::new_temp_dir();
+        let mut new_commit_id = commit_id_generator();
+        let mut new_change_id = change_id_generator();
+        // Large enough for the segment file to be mapped
+        let mut mutable_segment = MutableIndexSegment::full(16, 16);
+        let mut ids = vec![new_commit_id()];
+        mutable_segment.add_commit_data(ids[0].clone(), new_change_id(), &[]);
+        for _ in 1..5000 {
+            let id = new_commit_id();
+            let parent_id = ids.last().unwrap().clone();
+            mutable_segment.add_commit_data(id.clone(), new_change_id(), &[parent_id]);
+            ids.push(id);
+        }
+        let initial_file = mutable_segment.save_in(temp_dir.path()).unwrap();
+        let mut mutable_segment = MutableIndexSegment::incremental(initial_file);
+        let head_id = new_commit_id();
+        let head_change_id = new_change_id();
+        mutable_segment.add_commit_data(
+            head_id.clone(),
+            head_change_id.clone(),
+            &[ids.last().unwrap().clone()],
+        );
+        let saved_index = mutable_segment.save_in(temp_dir.path()).unwrap();
+
+        let segment =
+            ReadonlyIndexSegment::load(temp_dir.path(), saved_index.name().to_owned(), 16, 16)
+                .unwrap();
+        assert!(!segment.is_mapped());
+        assert!(segment.parent_file().unwrap().is_mapped());
+        let index = segment.as_composite();
+        assert_eq!(index.num_commits(), 5001);
+        for (pos, id) in ids.iter().enumerate() {
+            let entry = index.entry_by_id(id).unwrap();
+            assert_eq!(entry.position(), IndexPosition(pos.try_into().unwrap()));
+            assert_eq!(entry.generation_number(), pos.try_into().unwrap());
+        }
+        let head_entry = index.entry_by_id(&head_id).unwrap();
+        assert_eq!(head_entry.change_id(), head_change_id);
+        assert_eq!(
+            head_entry.parents().next().unwrap().commit_id(),
+            *ids.last().unwrap()
+        );
+        assert_eq!(
+            index.resolve_commit_id_prefix(&HexPrefix::new(&ids[1234].hex()).unwrap()),
+            PrefixResolution::SingleMatch(ids[1234].clone())
+        );
+    }
+
+    #[test]
+    fn index_shallow_commits() {
+        let temp_dir = testutils::new_temp_dir();
+        let mut new_change_id = change_id_generator();
+        let mut mutable_segment = MutableIndexSegment::full(3, 16);
+        let id_0 = CommitId::from_hex("000000");
+        let id_1 = CommitId::from_hex("111111");
+        let id_2 = CommitId::from_hex("222222");
+        let id_3 = CommitId::from_hex("333333");
+        mutable_segment.add_commit_data(id_0.clone(), new_change_id(), &[]);
+        mutable_segment.add_commit_entry(id_1.clone(), new_change_id(), &[id_0.clone()], &[], true);
+        mutable_segment.add_commit_data(id_2.clone(), new_change_id(), &[id_1.clone()]);
+        let initial_file = mutable_segment.save_in(temp_dir.path()).unwrap();
+        let mut mutable_segment = MutableIndexSegment::incremental(initial_file);
+        mutable_segment.add_commit_entry(id_3.clone(), new_change_id(), &[id_0.clone()], &[], true);
+        let saved_index = mutable_segment.save_in(temp_dir.path()).unwrap();
+
+        let is_shallow =
+            |index: &CompositeIndex, id: &CommitId| index.entry_by_id(id).unwrap().is_shallow();
+        let index = saved_index.as_composite();
+        assert!(!is_shallow(index, &id_0));
+        assert!(is_shallow(index, &id_1));
+        assert!(!is_shallow(index, &id_2));
+        assert!(is_shallow(index, &id_3));
+
+        // Flags are preserved when segments are squashed
+        let mut squashed = MutableIndexSegment::full(3, 16);
+        squashed.add_commits_from(saved_index.parent_file().unwrap().as_ref());
+        squashed.add_commits_from(saved_index.as_ref());
+        let squashed = squashed.save_in(temp_dir.path()).unwrap();
+        let index = squashed.as_composite();
+        assert!(!is_shallow(index, &id_0));
+        assert!(is_shallow(index, &id_1));
+        assert!(!is_shallow(index, &id_2));
+        assert!(is_shallow(index, &id_3));
+    }
+
+    #[test]
+    fn index_successors() {
+        let temp_dir = testutils::new_temp_dir();
+        let mut new_change_id = change_id_generator();
+        let mut mutable_segment = MutableIndexSegment::full(3, 16);
+        // 1 and 2 were rewritten from 0, 3 from 1 and 2, and 4 from 1 and
+        // from 9, which isn't indexed.
+        let id_0 = CommitId::from_hex("000000");
+        let id_1 = CommitId::from_hex("111111");
+        let id_2 = CommitId::from_hex("222222");
+        let id_3 = CommitId::from_hex("333333");
+        let id_4 = CommitId::from_hex("444444");
+        let id_9 = CommitId::from_hex("999999");
+        mutable_segment.add_commit_data(id_0.clone(), new_change_id(), &[]);
+        mutable_segment.add_commit_entry(
+            id_1.clone(),
+            new_change_id(),
+            &[],
+            &[id_0.clone()],
+            false,
+        );
+        mutable_segment.add_commit_entry(
+            id_2.clone(),
+            new_change_id(),
+            &[],
+            &[id_0.clone()],
+            false,
+        );
+        let initial_file = mutable_segment.save_in(temp_dir.path()).unwrap();
+        let mut mutable_segment = MutableIndexSegment::incremental(initial_file);
+        mutable_segment.add_commit_entry(
+            id_3.clone(),
+            new_change_id(),
+            &[],
+            &[id_1.clone(), id_2.clone()],
+            false,
+        );
+        mutable_segment.add_commit_entry(
+            id_4.clone(),
+            new_change_id(),
+            &[],
+            &[id_9.clone(), id_1.clone()],
+            false,
+        );
+
+        let check = |index: &CompositeIndex| {
+            assert_eq!(index.successors(&id_0), vec![id_1.clone(), id_2.clone()]);
+            assert_eq!(index.successors(&id_1), vec![id_3.clone(), id_4.clone()]);
+            assert_eq!(index.successors(&id_2), vec![id_3.clone()]);
+            assert_eq!(index.successors(&id_9), vec![id_4.clone()]);
+            assert!(index.successors(&id_3).is_empty());
+            assert!(index.successors(&CommitId::from_hex("555555")).is_empty());
+        };
+        check(mutable_segment.as_composite());
+        let saved_index = mutable_segment.save_in(temp_dir.path()).unwrap();
+        check(saved_index.as_composite());
+
+        // Edges are preserved when segments are squashed
+        let mut squashed = MutableIndexSegment::full(3, 16);
+        squashed.add_commits_from(saved_index.parent_file().unwrap().as_ref());
+        squashed.add_commits_from(saved_index.as_ref());
+        let squashed = squashed.save_in(temp_dir.path()).unwrap();
+        check(squashed.as_composite());
+    }
+
     #[test]
     fn test_is_ancestor() {
         let mut new_change_id = change_id_generator();
@@ -17,6 +17,7 @@
 use std::any::Any;
 use std::cmp::max;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::io;
 use std::io::Write;
@@ -44,6 +45,7 @@ use super::readonly::DefaultReadonlyIndex;
 use super::readonly::ReadonlyIndexSegment;
 use super::readonly::INDEX_SEGMENT_FILE_FORMAT_VERSION;
 use super::readonly::OVERFLOW_FLAG;
+use super::store::IndexCompactionPolicy;
 use crate::backend::ChangeId;
 use crate::backend::CommitId;
 use crate::commit::Commit;
@@ -67,6 +69,7 @@ struct MutableGraphEntry {
     change_id: ChangeId,
     generation_number: u32,
     parent_positions: SmallIndexPositionsVec,
+    is_shallow: bool,
 }
 
 pub(super) struct MutableIndexSegment {
@@ -77,6 +80,8 @@ pub(super) struct MutableIndexSegment {
     graph: Vec<MutableGraphEntry>,
     commit_lookup: BTreeMap<CommitId, LocalPosition>,
     change_lookup: BTreeMap<ChangeId, SmallLocalPositionsVec>,
+    // (predecessor id, successor position) pairs
+    evolution_edges: BTreeSet<(CommitId, LocalPosition)>,
 }
 
 impl MutableIndexSegment {
@@ -89,6 +94,7 @@ impl MutableIndexSegment {
             graph: vec![],
             commit_lookup: BTreeMap::new(),
             change_lookup: BTreeMap::new(),
+            evolution_edges: BTreeSet::new(),
         }
     }
 
@@ -104,6 +110,7 @@ impl MutableIndexSegment {
             graph: vec![],
             commit_lookup: BTreeMap::new(),
             change_lookup: BTreeMap::new(),
+            evolution_edges: BTreeSet::new(),
         }
     }
 
@@ -112,10 +119,16 @@ impl MutableIndexSegment {
     }
 
     pub(super) fn add_commit(&mut self, commit: &Commit) {
-        self.add_commit_data(
+        let is_shallow = commit.is_shallow().unwrap_or_else(|err| {
+            tracing::warn!(?err, "failed to check if commit is shallow");
+            false
+        });
+        self.add_commit_entry(
             commit.id().clone(),
             commit.change_id().clone(),
             commit.parent_ids(),
+            commit.predecessor_ids(),
+            is_shallow,
         );
     }
 
@@ -124,6 +137,17 @@ impl MutableIndexSegment {
         commit_id: CommitId,
         change_id: ChangeId,
         parent_ids: &[CommitId],
+    ) {
+        self.add_commit_entry(commit_id, change_id, parent_ids, &[], false);
+    }
+
+    pub(super) fn add_commit_entry(
+        &mut self,
+        commit_id: CommitId,
+        change_id: ChangeId,
+        parent_ids: &[CommitId],
+        predecessor_ids: &[CommitId],
+        is_shallow: bool,
     ) {
         if self.as_composite().has_id(&commit_id) {
             return;
@@ -133,6 +157,7 @@ impl MutableIndexSegment {
             change_id,
             generation_number: 0,
             parent_positions: SmallVec::new(),
+            is_shallow,
         };
         for parent_id in parent_ids {
             let parent_entry = self
@@ -153,6 +178,11 @@ impl MutableIndexSegment {
             // positions are inherently sorted
             .and_modify(|positions| positions.push(local_pos))
             .or_insert(smallvec![local_pos]);
+        self.evolution_edges.extend(
+            predecessor_ids
+                .iter()
+                .map(|predecessor_id| (predecessor_id.clone(), local_pos)),
+        );
         self.graph.push(entry);
     }
 
@@ -161,7 +191,21 @@ impl MutableIndexSegment {
         for pos in other_segment.num_parent_commits()..other.num_commits() {
             let entry = other.entry_by_pos(IndexPosition(pos));
             let parent_ids = entry.parents().map(|entry| entry.commit_id()).collect_vec();
-            self.add_commit_data(entry.commit_id(), entry.change_id(), &parent_ids);
+            self.add_commit_entry(
+                entry.commit_id(),
+                entry.change_id(),
+                &parent_ids,
+                &[],
+                entry.is_shallow(),
+            );
+        }
+        // Commits which were already indexed in a parent segment have their
+        // edges recorded there.
+        for (predecessor_id, other_pos) in other_segment.evolution_edges() {
+            let commit_id = other_segment.commit_id(other_pos);
+            if let Some(&local_pos) = self.commit_lookup.get(&commit_id) {
+                self.evolution_edges.insert((predecessor_id, local_pos));
+            }
         }
     }
 
@@ -211,7 +255,7 @@ impl MutableIndexSegment {
         }
     }
 
-    fn serialize_local_entries(&self, buf: &mut Vec<u8>) {
+    fn serialize_local_entries(&self, buf: &mut Vec<u8>) -> io::Result<()> {
         assert_eq!(self.graph.len(), self.commit_lookup.len());
         debug_assert_eq!(
             self.graph.len(),
@@ -227,6 +271,17 @@ impl MutableIndexSegment {
         buf.extend(0_u32.to_le_bytes());
         let change_overflow_offset = buf.len();
         buf.extend(0_u32.to_le_bytes());
+        let shallow_entries = self
+            .graph
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.is_shallow)
+            .map(|(i, _)| u32::try_from(i).unwrap())
+            .collect_vec();
+        let num_shallow_entries = u32::try_from(shallow_entries.len()).unwrap();
+        buf.extend(num_shallow_entries.to_le_bytes());
+        let num_evolution_edges = u32::try_from(self.evolution_edges.len()).unwrap();
+        buf.extend(num_evolution_edges.to_le_bytes());
 
         // Positions of change ids in the sorted table
         let change_id_pos_map: HashMap<&ChangeId, u32> = self
@@ -311,32 +366,66 @@ impl MutableIndexSegment {
         for LocalPosition(pos) in change_overflow {
             buf.extend(pos.to_le_bytes());
         }
-    }
 
-    /// If the MutableIndex has more than half the commits of its parent
-    /// ReadonlyIndex, return MutableIndex with the commits from both. This
-    /// is done recursively, so the stack of index files has O(log n) files.
-    fn maybe_squash_with_ancestors(self) -> MutableIndexSegment {
-        let mut num_new_commits = self.num_local_commits();
-        let mut files_to_squash = vec![];
-        let mut base_parent_file = None;
-        for parent_file in self.as_composite().ancestor_files_without_local() {
-            // TODO: We should probably also squash if the parent file has less than N
-            // commits, regardless of how many (few) are in `self`.
-            if 2 * num_new_commits < parent_file.num_local_commits() {
-                base_parent_file = Some(parent_file.clone());
+        for pos in shallow_entries {
+            buf.extend(pos.to_le_bytes());
+        }
+
+        for (predecessor_id, LocalPosition(pos)) in &self.evolution_edges {
+            // Predecessor ids come from the commit data, so they aren't
+            // guaranteed to be valid commit ids of this backend.
+            if predecessor_id.as_bytes().len() != self.commit_id_length {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Predecessor id {} has invalid length", predecessor_id.hex()),
+                ));
+            }
+            buf.extend_from_slice(predecessor_id.as_bytes());
+            buf.extend(pos.to_le_bytes());
+        }
+        Ok(())
+    }
+
+    /// Squashes ancestor segments into this segment as configured by the
+    /// `policy`, and returns the squashed segment.
+    ///
+    /// With the default policy, ancestors are squashed while this segment has
+    /// more than half the commits of its parent, so the stack of index files
+    /// has O(log n) files.
+    fn maybe_squash_with_ancestors(self, policy: &IndexCompactionPolicy) -> MutableIndexSegment {
+        let ancestor_files = self
+            .as_composite()
+            .ancestor_files_without_local()
+            .cloned()
+            .collect_vec();
+        let mut num_new_commits = u64::from(self.num_local_commits());
+        let mut num_files_to_squash = 0;
+        for parent_file in &ancestor_files {
+            let num_parent_commits = u64::from(parent_file.num_local_commits());
+            if num_parent_commits >= u64::from(policy.min_segment_commits)
+                && u64::from(policy.size_ratio) * num_new_commits < num_parent_commits
+            {
                 break;
             }
-            num_new_commits += parent_file.num_local_commits();
-            files_to_squash.push(parent_file.clone());
+            num_new_commits += num_parent_commits;
+            num_files_to_squash += 1;
+        }
+        // Squash more files if the resulting stack would still be too long.
+        // Nearest files are the smallest, so squashing them is the cheapest.
+        if policy.max_segments > 0 {
+            let num_segments = ancestor_files.len() - num_files_to_squash + 1;
+            if num_segments > policy.max_segments {
+                num_files_to_squash += num_segments - policy.max_segments;
+            }
         }
 
-        if files_to_squash.is_empty() {
+        if num_files_to_squash == 0 {
             return self;
         }
 
-        let mut squashed = if let Some(parent_file) = base_parent_file {
-            MutableIndexSegment::incremental(parent_file)
+        let (files_to_squash, base_files) = ancestor_files.split_at(num_files_to_squash);
+        let mut squashed = if let Some(parent_file) = base_files.first() {
+            MutableIndexSegment::incremental(parent_file.clone())
         } else {
             MutableIndexSegment::full(self.commit_id_length, self.change_id_length)
         };
@@ -356,7 +445,7 @@ impl MutableIndexSegment {
         buf.extend(INDEX_SEGMENT_FILE_FORMAT_VERSION.to_le_bytes());
         self.serialize_parent_filename(&mut buf);
         let local_entries_offset = buf.len();
-        self.serialize_local_entries(&mut buf);
+        self.serialize_local_entries(&mut buf)?;
         let mut hasher = Blake2b512::new();
         hasher.update(&buf);
         let index_file_id_hex = hex::encode(hasher.finalize());
@@ -368,7 +457,7 @@ impl MutableIndexSegment {
         persist_content_addressed_temp_file(temp_file, index_file_path)?;
 
         Ok(ReadonlyIndexSegment::load_with_parent_file(
-            &mut &buf[local_entries_offset..],
+            &buf[local_entries_offset..],
             index_file_id_hex,
             self.parent_file,
             self.commit_id_length,
@@ -452,6 +541,23 @@ impl IndexSegment for MutableIndexSegment {
     fn parent_positions(&self, local_pos: LocalPosition) -> SmallIndexPositionsVec {
         self.graph[local_pos.0 as usize].parent_positions.clone()
     }
+
+    fn is_shallow(&self, local_pos: LocalPosition) -> bool {
+        self.graph[local_pos.0 as usize].is_shallow
+    }
+
+    fn successor_positions(&self, predecessor_id: &CommitId) -> SmallLocalPositionsVec {
+        let min_key = (predecessor_id.clone(), LocalPosition(0));
+        self.evolution_edges
+            .range(min_key..)
+            .take_while(|(id, _)| id == predecessor_id)
+            .map(|&(_, pos)| pos)
+            .collect()
+    }
+
+    fn evolution_edges(&self) -> Vec<(CommitId, LocalPosition)> {
+        self.evolution_edges.iter().cloned().collect()
+    }
 }
 
 /// In-memory mutable records for the on-disk commit index backend.
@@ -468,6 +574,14 @@ impl DefaultMutableIndex {
         DefaultMutableIndex(mutable_segment)
     }
 
+    pub(super) fn commit_id_length(&self) -> usize {
+        self.0.commit_id_length
+    }
+
+    pub(super) fn change_id_length(&self) -> usize {
+        self.0.change_id_length
+    }
+
     #[cfg(test)]
     pub(crate) fn add_commit_data(
         &mut self,
@@ -478,8 +592,12 @@ impl DefaultMutableIndex {
         self.0.add_commit_data(commit_id, change_id, parent_ids);
     }
 
-    pub(super) fn squash_and_save_in(self, dir: &Path) -> io::Result<Arc<ReadonlyIndexSegment>> {
-        self.0.maybe_squash_with_ancestors().save_in(dir)
+    pub(super) fn squash_and_save_in(
+        self,
+        dir: &Path,
+        policy: &IndexCompactionPolicy,
+    ) -> io::Result<Arc<ReadonlyIndexSegment>> {
+        self.0.maybe_squash_with_ancestors(policy).save_in(dir)
     }
 }
 
@@ -517,6 +635,10 @@ impl Index for DefaultMutableIndex {
         Ok(Box::new(self.as_composite().all_heads()))
     }
 
+    fn successors(&self, commit_id: &CommitId) -> Vec<CommitId> {
+        self.as_composite().successors(commit_id)
+    }
+
     fn heads(&self, candidates: &mut dyn Iterator<Item = &CommitId>) -> Vec<CommitId> {
         self.as_composite().heads(candidates)
     }
@@ -21,9 +21,12 @@ use std::fmt::Formatter;
 use std::fs::File;
 use std::io;
 use std::io::Read;
+use std::ops::Deref;
 use std::path::Path;
 use std::sync::Arc;
+use std::sync::OnceLock;
 
+use jj_lib_mmap::ImmutableMmap;
 use smallvec::smallvec;
 use thiserror::Error;
 
@@ -36,6 +39,8 @@ use super::entry::LocalPosition;
 use super::entry::SmallIndexPositionsVec;
 use super::entry::SmallLocalPositionsVec;
 use super::mutable::DefaultMutableIndex;
+use super::revset_engine;
+use super::revset_engine::RevsetCache;
 use crate::backend::ChangeId;
 use crate::backend::CommitId;
 use crate::index::AllHeadsForGcUnsupported;
@@ -84,6 +89,16 @@ impl ReadonlyIndexLoadError {
         }
     }
 
+    /// Returns true if a segment file doesn't exist.
+    pub(super) fn is_not_found(&self) -> bool {
+        match self {
+            ReadonlyIndexLoadError::UnexpectedVersion { .. } => false,
+            ReadonlyIndexLoadError::Other { name: _, error } => {
+                error.kind() == io::ErrorKind::NotFound
+            }
+        }
+    }
+
     /// Returns true if the underlying error suggests data corruption.
     pub(super) fn is_corrupt_or_not_found(&self) -> bool {
         match self {
@@ -103,11 +118,66 @@ impl ReadonlyIndexLoadError {
 }
 
 /// Current format version of the index segment file.
-pub(crate) const INDEX_SEGMENT_FILE_FORMAT_VERSION: u32 = 6;
+pub(crate) const INDEX_SEGMENT_FILE_FORMAT_VERSION: u32 = 8;
 
 /// If set, the value is stored in the overflow table.
 pub(crate) const OVERFLOW_FLAG: u32 = 0x8000_0000;
 
+/// Segment files smaller than this are read into memory instead of being
+/// mapped. Mapping has a fixed cost that isn't worth paying for small files.
+const MMAP_THRESHOLD: u64 = 64 * 1024;
+
+/// Contents of an index segment file.
+///
+/// Large segment files are mapped into memory, so only the pages of the
+/// tables that are actually looked up are read from disk, and the pages can
+/// be shared with other processes through the page cache.
+enum SegmentData {
+    Owned(Vec<u8>),
+    Mapped { mmap: ImmutableMmap, offset: usize },
+}
+
+impl SegmentData {
+    fn load(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let len = file.metadata()?.len();
+        if len < MMAP_THRESHOLD {
+            let mut buf = Vec::with_capacity(len as usize);
+            file.read_to_end(&mut buf)?;
+            return Ok(SegmentData::Owned(buf));
+        }
+        // Segment files are content-addressed and are never modified once
+        // written, so the mapped contents don't change while in use.
+        let mmap = ImmutableMmap::map_content_addressed(path)?;
+        Ok(SegmentData::Mapped { mmap, offset: 0 })
+    }
+
+    /// Drops the first `len` bytes.
+    fn advance(&mut self, len: usize) {
+        match self {
+            SegmentData::Owned(buf) => {
+                buf.drain(..len);
+            }
+            SegmentData::Mapped { offset, .. } => *offset += len,
+        }
+    }
+
+    fn is_mapped(&self) -> bool {
+        matches!(self, SegmentData::Mapped { .. })
+    }
+}
+
+impl Deref for SegmentData {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            SegmentData::Owned(buf) => buf,
+            SegmentData::Mapped { mmap, offset } => &mmap[*offset..],
+        }
+    }
+}
+
 /// Global index position of parent entry, or overflow pointer.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 struct ParentIndexPosition(u32);
@@ -173,6 +243,25 @@ impl CommitGraphEntry<'_> {
     }
 }
 
+struct EvolutionEntry<'a> {
+    data: &'a [u8],
+}
+
+impl EvolutionEntry<'_> {
+    fn size(commit_id_length: usize) -> usize {
+        commit_id_length + 4
+    }
+
+    fn predecessor_id_bytes(&self) -> &[u8] {
+        &self.data[..self.data.len() - 4]
+    }
+
+    fn successor_pos(&self) -> LocalPosition {
+        let pos_bytes = &self.data[self.data.len() - 4..];
+        LocalPosition(u32::from_le_bytes(pos_bytes.try_into().unwrap()))
+    }
+}
+
 /// Commit index segment backed by immutable file.
 ///
 /// File format:
@@ -185,6 +274,8 @@ impl CommitGraphEntry<'_> {
 /// u32: number of local change ids
 /// u32: number of overflow parent entries
 /// u32: number of overflow change id positions
+/// u32: number of shallow entries
+/// u32: number of evolution entries
 /// for each entry, in some topological order with parents first:
 ///   u32: generation number
 ///   if number of parents <= 2:
@@ -210,8 +301,22 @@ impl CommitGraphEntry<'_> {
 ///   u32: global index position
 /// for each overflow change id entry:
 ///   u32: local position in the graph entries table
+/// for each shallow entry, sorted:
+///   u32: local position in the graph entries table
+/// for each evolution entry, sorted by predecessor commit id and position:
+///   <commit id length number of bytes>: predecessor commit id
+///   u32: local position of the successor in the graph entries table
 /// ```
 ///
+/// Shallow entries are commits whose history was cut off, such as the
+/// boundary commits of a shallow Git clone. They are recorded with the root
+/// commit as their only parent.
+///
+/// Evolution entries map the predecessors of the local commits to the local
+/// commits, so the successors of a commit can be looked up without reading
+/// commit objects. The predecessors may belong to any segment, or may not be
+/// indexed at all.
+///
 /// Note that u32 fields are 4-byte aligned so long as the parent file name
 /// (which is hexadecimal hash) and commit/change ids aren't of exotic length.
 // TODO: replace the table by a trie so we don't have to repeat the full commit
@@ -227,19 +332,24 @@ pub(super) struct ReadonlyIndexSegment {
     num_local_commits: u32,
     num_local_change_ids: u32,
     num_change_overflow_entries: u32,
+    num_shallow_entries: u32,
+    num_evolution_entries: u32,
     // Base data offsets in bytes:
     commit_lookup_base: usize,
     change_id_table_base: usize,
     change_pos_table_base: usize,
     parent_overflow_base: usize,
     change_overflow_base: usize,
-    data: Vec<u8>,
+    shallow_base: usize,
+    evolution_base: usize,
+    data: SegmentData,
 }
 
 impl Debug for ReadonlyIndexSegment {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
         f.debug_struct("ReadonlyIndexSegment")
             .field("name", &self.name)
+            .field("mapped", &self.data.is_mapped())
             .field("parent_file", &self.parent_file)
             .finish()
     }
@@ -253,37 +363,42 @@ impl ReadonlyIndexSegment {
         commit_id_length: usize,
         change_id_length: usize,
     ) -> Result<Arc<ReadonlyIndexSegment>, ReadonlyIndexLoadError> {
-        let mut file = File::open(dir.join(&name))
-            .map_err(|err| ReadonlyIndexLoadError::from_io_err(&name, err))?;
-        Self::load_from(&mut file, dir, name, commit_id_length, change_id_length)
+        let from_io_err = |err| ReadonlyIndexLoadError::from_io_err(&name, err);
+        let data = SegmentData::load(&dir.join(&name)).map_err(from_io_err)?;
+        Self::load_from(data, dir, name, commit_id_length, change_id_length)
     }
 
-    /// Loads both parent segments and local entries from the given `file`.
-    pub(super) fn load_from(
-        file: &mut dyn Read,
+    /// Loads both parent segments and local entries from the given file
+    /// `data`.
+    fn load_from(
+        mut data: SegmentData,
         dir: &Path,
         name: String,
         commit_id_length: usize,
         change_id_length: usize,
     ) -> Result<Arc<ReadonlyIndexSegment>, ReadonlyIndexLoadError> {
         let from_io_err = |err| ReadonlyIndexLoadError::from_io_err(&name, err);
-        let read_u32 = |file: &mut dyn Read| {
+        let read_u32 = |file: &mut &[u8]| {
             let mut buf = [0; 4];
             file.read_exact(&mut buf).map_err(from_io_err)?;
             Ok(u32::from_le_bytes(buf))
         };
-        let format_version = read_u32(file)?;
+        let mut header: &[u8] = &data;
+        let format_version = read_u32(&mut header)?;
         if format_version != INDEX_SEGMENT_FILE_FORMAT_VERSION {
             return Err(ReadonlyIndexLoadError::UnexpectedVersion {
                 found_version: format_version,
                 expected_version: INDEX_SEGMENT_FILE_FORMAT_VERSION,
             });
         }
-        let parent_filename_len = read_u32(file)?;
+        let parent_filename_len = read_u32(&mut header)?;
+        let mut parent_filename_bytes = vec![0; parent_filename_len as usize];
+        header
+            .read_exact(&mut parent_filename_bytes)
+            .map_err(from_io_err)?;
+        let header_len = data.len() - header.len();
+        data.advance(header_len);
         let maybe_parent_file = if parent_filename_len > 0 {
-            let mut parent_filename_bytes = vec![0; parent_filename_len as usize];
-            file.read_exact(&mut parent_filename_bytes)
-                .map_err(from_io_err)?;
             let parent_filename = String::from_utf8(parent_filename_bytes).map_err(|_| {
                 ReadonlyIndexLoadError::invalid_data(&name, "parent file name is not valid UTF-8")
             })?;
@@ -297,8 +412,8 @@ impl ReadonlyIndexSegment {
         } else {
             None
         };
-        Self::load_with_parent_file(
-            file,
+        Self::load_local_entries(
+            data,
             name,
             maybe_parent_file,
             commit_id_length,
@@ -306,17 +421,33 @@ impl ReadonlyIndexSegment {
         )
     }
 
-    /// Loads local entries from the given `file`, returns new segment linked to
-    /// the given `parent_file`.
+    /// Loads local entries from the given serialized `buf`, returns new
+    /// segment linked to the given `parent_file`.
     pub(super) fn load_with_parent_file(
-        file: &mut dyn Read,
+        buf: &[u8],
+        name: String,
+        parent_file: Option<Arc<ReadonlyIndexSegment>>,
+        commit_id_length: usize,
+        change_id_length: usize,
+    ) -> Result<Arc<ReadonlyIndexSegment>, ReadonlyIndexLoadError> {
+        Self::load_local_entries(
+            SegmentData::Owned(buf.to_vec()),
+            name,
+            parent_file,
+            commit_id_length,
+            change_id_length,
+        )
+    }
+
+    fn load_local_entries(
+        mut data: SegmentData,
         name: String,
         parent_file: Option<Arc<ReadonlyIndexSegment>>,
         commit_id_length: usize,
         change_id_length: usize,
     ) -> Result<Arc<ReadonlyIndexSegment>, ReadonlyIndexLoadError> {
         let from_io_err = |err| ReadonlyIndexLoadError::from_io_err(&name, err);
-        let read_u32 = |file: &mut dyn Read| {
+        let read_u32 = |file: &mut &[u8]| {
             let mut buf = [0; 4];
             file.read_exact(&mut buf).map_err(from_io_err)?;
             Ok(u32::from_le_bytes(buf))
@@ -324,12 +455,15 @@ impl ReadonlyIndexSegment {
         let num_parent_commits = parent_file
             .as_ref()
             .map_or(0, |segment| segment.as_composite().num_commits());
-        let num_local_commits = read_u32(file)?;
-        let num_local_change_ids = read_u32(file)?;
-        let num_parent_overflow_entries = read_u32(file)?;
-        let num_change_overflow_entries = read_u32(file)?;
-        let mut data = vec![];
-        file.read_to_end(&mut data).map_err(from_io_err)?;
+        let mut header: &[u8] = &data;
+        let num_local_commits = read_u32(&mut header)?;
+        let num_local_change_ids = read_u32(&mut header)?;
+        let num_parent_overflow_entries = read_u32(&mut header)?;
+        let num_change_overflow_entries = read_u32(&mut header)?;
+        let num_shallow_entries = read_u32(&mut header)?;
+        let num_evolution_entries = read_u32(&mut header)?;
+        let header_len = data.len() - header.len();
+        data.advance(header_len);
 
         let commit_graph_entry_size = CommitGraphEntry::size(commit_id_length);
         let graph_size = (num_local_commits as usize) * commit_graph_entry_size;
@@ -338,6 +472,9 @@ impl ReadonlyIndexSegment {
         let change_pos_table_size = (num_local_change_ids as usize) * 4;
         let parent_overflow_size = (num_parent_overflow_entries as usize) * 4;
         let change_overflow_size = (num_change_overflow_entries as usize) * 4;
+        let shallow_size = (num_shallow_entries as usize) * 4;
+        let evolution_size =
+            (num_evolution_entries as usize) * EvolutionEntry::size(commit_id_length);
 
         let graph_base = 0;
         let commit_lookup_base = graph_base + graph_size;
@@ -345,7 +482,9 @@ impl ReadonlyIndexSegment {
         let change_pos_table_base = change_id_table_base + change_id_table_size;
         let parent_overflow_base = change_pos_table_base + change_pos_table_size;
         let change_overflow_base = parent_overflow_base + parent_overflow_size;
-        let expected_size = change_overflow_base + change_overflow_size;
+        let shallow_base = change_overflow_base + change_overflow_size;
+        let evolution_base = shallow_base + shallow_size;
+        let expected_size = evolution_base + evolution_size;
 
         if data.len() != expected_size {
             return Err(ReadonlyIndexLoadError::invalid_data(
@@ -363,11 +502,15 @@ impl ReadonlyIndexSegment {
             num_local_commits,
             num_local_change_ids,
             num_change_overflow_entries,
+            num_shallow_entries,
+            num_evolution_entries,
             commit_lookup_base,
             change_id_table_base,
             change_pos_table_base,
             parent_overflow_base,
             change_overflow_base,
+            shallow_base,
+            evolution_base,
             data,
         }))
     }
@@ -380,6 +523,11 @@ impl ReadonlyIndexSegment {
         &self.name
     }
 
+    #[cfg(test)]
+    pub(super) fn is_mapped(&self) -> bool {
+        self.data.is_mapped()
+    }
+
     pub(super) fn commit_id_length(&self) -> usize {
         self.commit_id_length
     }
@@ -432,13 +580,28 @@ impl ReadonlyIndexSegment {
 
     /// Scans graph entry positions stored in the overflow change ids table.
     fn overflow_changes_from(&self, overflow_pos: u32) -> impl Iterator<Item = LocalPosition> + '_ {
-        let table = &self.data[self.change_overflow_base..];
+        let table = &self.data[self.change_overflow_base..self.shallow_base];
         let offset = (overflow_pos as usize) * 4;
         table[offset..]
             .chunks_exact(4)
             .map(|chunk| LocalPosition(u32::from_le_bytes(chunk.try_into().unwrap())))
     }
 
+    fn shallow_entry_pos(&self, shallow_pos: u32) -> LocalPosition {
+        let table = &self.data[self.shallow_base..self.evolution_base];
+        let offset = (shallow_pos as usize) * 4;
+        LocalPosition(u32::from_le_bytes(table[offset..][..4].try_into().unwrap()))
+    }
+
+    fn evolution_entry(&self, evolution_pos: u32) -> EvolutionEntry {
+        let table = &self.data[self.evolution_base..];
+        let entry_size = EvolutionEntry::size(self.commit_id_length);
+        let offset = (evolution_pos as usize) * entry_size;
+        EvolutionEntry {
+            data: &table[offset..][..entry_size],
+        }
+    }
+
     /// Binary searches commit id by `prefix`. Returns the lookup position.
     fn commit_id_byte_prefix_to_lookup_pos(&self, prefix: &[u8]) -> PositionLookupResult {
         binary_search_pos_by(self.num_local_commits, |pos| {
@@ -583,25 +746,73 @@ impl IndexSegment for ReadonlyIndexSegment {
             self.overflow_parents(overflow_pos, num_parents)
         }
     }
+
+    fn is_shallow(&self, local_pos: LocalPosition) -> bool {
+        binary_search_pos_by(self.num_shallow_entries, |pos| {
+            self.shallow_entry_pos(pos).cmp(&local_pos)
+        })
+        .ok()
+        .is_some()
+    }
+
+    fn successor_positions(&self, predecessor_id: &CommitId) -> SmallLocalPositionsVec {
+        // Never compare equal so the search stops at the first matching entry.
+        let first_pos = binary_search_pos_by(self.num_evolution_entries, |pos| {
+            let entry = self.evolution_entry(pos);
+            entry
+                .predecessor_id_bytes()
+                .cmp(predecessor_id.as_bytes())
+                .then(Ordering::Greater)
+        })
+        .result
+        .unwrap_err();
+        (first_pos..self.num_evolution_entries)
+            .map(|pos| self.evolution_entry(pos))
+            .take_while(|entry| entry.predecessor_id_bytes() == predecessor_id.as_bytes())
+            .map(|entry| entry.successor_pos())
+            .collect()
+    }
+
+    fn evolution_edges(&self) -> Vec<(CommitId, LocalPosition)> {
+        (0..self.num_evolution_entries)
+            .map(|pos| {
+                let entry = self.evolution_entry(pos);
+                (
+                    CommitId::from_bytes(entry.predecessor_id_bytes()),
+                    entry.successor_pos(),
+                )
+            })
+            .collect()
+    }
 }
 
 /// Commit index backend which stores data on local disk.
+///
+/// Since the index doesn't change once loaded, revset results are cached and
+/// shared between clones of the index.
 #[derive(Clone, Debug)]
-pub struct DefaultReadonlyIndex(Arc<ReadonlyIndexSegment>);
+pub struct DefaultReadonlyIndex {
+    segment: Arc<ReadonlyIndexSegment>,
+    // Sized by the memory budget of the store passed in on first evaluation
+    revset_cache: Arc<OnceLock<RevsetCache>>,
+}
 
 impl DefaultReadonlyIndex {
     pub(super) fn from_segment(segment: Arc<ReadonlyIndexSegment>) -> Self {
-        DefaultReadonlyIndex(segment)
+        DefaultReadonlyIndex {
+            segment,
+            revset_cache: Arc::new(OnceLock::new()),
+        }
     }
 
     pub(super) fn as_segment(&self) -> &Arc<ReadonlyIndexSegment> {
-        &self.0
+        &self.segment
     }
 }
 
 impl AsCompositeIndex for DefaultReadonlyIndex {
     fn as_composite(&self) -> &CompositeIndex {
-        self.0.as_composite()
+        self.segment.as_composite()
     }
 }
 
@@ -633,6 +844,10 @@ impl Index for DefaultReadonlyIndex {
         Ok(Box::new(self.as_composite().all_heads()))
     }
 
+    fn successors(&self, commit_id: &CommitId) -> Vec<CommitId> {
+        self.as_composite().successors(commit_id)
+    }
+
     fn heads(&self, candidates: &mut dyn Iterator<Item = &CommitId>) -> Vec<CommitId> {
         self.as_composite().heads(candidates)
     }
@@ -642,7 +857,12 @@ impl Index for DefaultReadonlyIndex {
         expression: &ResolvedExpression,
         store: &Arc<Store>,
     ) -> Result<Box<dyn Revset + 'index>, RevsetEvaluationError> {
-        self.as_composite().evaluate_revset(expression, store)
+        let cache = self
+            .revset_cache
+            .get_or_init(|| RevsetCache::with_memory_budget(store.memory_budget()));
+        let revset_impl =
+            revset_engine::evaluate_with_cache(expression, store, self.as_composite(), cache)?;
+        Ok(Box::new(revset_impl))
     }
 }
 
@@ -663,7 +883,7 @@ impl ReadonlyIndex for DefaultReadonlyIndex {
     }
 
     fn start_modification(&self) -> Box<dyn MutableIndex> {
-        Box::new(DefaultMutableIndex::incremental(self.0.clone()))
+        Box::new(DefaultMutableIndex::incremental(self.segment.clone()))
     }
 }
 
@@ -15,6 +15,7 @@
 #![allow(missing_docs)]
 
 use std::cmp::max;
+use std::cmp::min;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
 use std::collections::HashMap;
@@ -418,17 +419,27 @@ impl<'a> RevWalkBuilder<'a> {
 
     /// Walks ancestors.
     pub fn ancestors(self) -> RevWalkAncestors<'a> {
-        self.ancestors_with_min_pos(IndexPosition::MIN)
+        self.ancestors_with_min_bounds(IndexPosition::MIN, 0)
     }
 
-    fn ancestors_with_min_pos(self, min_pos: IndexPosition) -> RevWalkAncestors<'a> {
+    /// Walks ancestors, but doesn't visit entries before `min_pos` nor the
+    /// parents of entries whose generation number is `min_generation` or
+    /// less.
+    fn ancestors_with_min_bounds(
+        self,
+        min_pos: IndexPosition,
+        min_generation: u32,
+    ) -> RevWalkAncestors<'a> {
         let index = self.index;
         let mut queue = RevWalkQueue::with_min_pos(min_pos);
         queue.extend_wanted(self.wanted, ());
         queue.extend_unwanted(self.unwanted);
         RevWalkBorrowedIndexIter {
             index,
-            walk: RevWalkImpl { queue },
+            walk: RevWalkImpl {
+                queue,
+                min_generation,
+            },
         }
     }
 
@@ -462,14 +473,20 @@ impl<'a> RevWalkBuilder<'a> {
         self,
         root_positions: impl IntoIterator<Item = IndexPosition>,
     ) -> RevWalkAncestors<'a> {
-        // We can also make it stop visiting based on the generation number. Maybe
-        // it will perform better for unbalanced branchy history.
-        // https://github.com/jj-vcs/jj/pull/1492#discussion_r1160678325
-        let min_pos = root_positions
-            .into_iter()
-            .min()
-            .unwrap_or(IndexPosition::MAX);
-        self.ancestors_with_min_pos(min_pos)
+        // Entries before the first root can't be descendants of the roots.
+        // Neither can the parents of entries whose generation number is the
+        // minimum of the roots' or less, which prunes long side branches in
+        // unbalanced branchy history.
+        let mut min_pos = IndexPosition::MAX;
+        let mut min_generation = u32::MAX;
+        for pos in root_positions {
+            min_pos = min(min_pos, pos);
+            min_generation = min(
+                min_generation,
+                self.index.entry_by_pos(pos).generation_number(),
+            );
+        }
+        self.ancestors_with_min_bounds(min_pos, min_generation)
     }
 
     /// Fully consumes ancestors and walks back from the `root_positions`.
@@ -532,17 +549,20 @@ pub(super) type RevWalkAncestors<'a> =
 #[must_use]
 pub(super) struct RevWalkImpl<P> {
     queue: RevWalkQueue<P, ()>,
+    min_generation: u32,
 }
 
-impl<I: RevWalkIndex + ?Sized> RevWalk<I> for RevWalkImpl<I::Position> {
-    type Item = I::Position;
+impl RevWalk<CompositeIndex> for RevWalkImpl<IndexPosition> {
+    type Item = IndexPosition;
 
-    fn next(&mut self, index: &I) -> Option<Self::Item> {
+    fn next(&mut self, index: &CompositeIndex) -> Option<Self::Item> {
         while let Some(item) = self.queue.pop() {
             self.queue.skip_while_eq(&item.pos);
             if item.is_wanted() {
-                self.queue
-                    .extend_wanted(index.adjacent_positions(item.pos), ());
+                let entry = index.entry_by_pos(item.pos);
+                if entry.generation_number() > self.min_generation {
+                    self.queue.extend_wanted(entry.parent_positions(), ());
+                }
                 return Some(item.pos);
             } else if self.queue.items.len() == self.queue.unwanted_count {
                 // No more wanted entries to walk
@@ -1009,6 +1029,66 @@ mod tests {
         assert!(iter.walk.queue.items.is_empty()); // no ids should be queued
     }
 
+    #[test]
+    fn test_walk_ancestors_until_roots_pruned_by_generation() {
+        let mut new_change_id = change_id_generator();
+        let mut index = DefaultMutableIndex::full(3, 16);
+        //   7
+        //  /|
+        // 6 |
+        // 5 |
+        // 4 |
+        // | 3
+        // | 2
+        // | 1
+        // |/
+        // 0
+        let id_0 = CommitId::from_hex("000000");
+        let id_1 = CommitId::from_hex("111111");
+        let id_2 = CommitId::from_hex("222222");
+        let id_3 = CommitId::from_hex("333333");
+        let id_4 = CommitId::from_hex("444444");
+        let id_5 = CommitId::from_hex("555555");
+        let id_6 = CommitId::from_hex("666666");
+        let id_7 = CommitId::from_hex("777777");
+        index.add_commit_data(id_0.clone(), new_change_id(), &[]);
+        index.add_commit_data(id_1.clone(), new_change_id(), &[id_0.clone()]);
+        index.add_commit_data(id_2.clone(), new_change_id(), &[id_1.clone()]);
+        index.add_commit_data(id_3.clone(), new_change_id(), &[id_2.clone()]);
+        index.add_commit_data(id_4.clone(), new_change_id(), &[id_0.clone()]);
+        index.add_commit_data(id_5.clone(), new_change_id(), &[id_4.clone()]);
+        index.add_commit_data(id_6.clone(), new_change_id(), &[id_5.clone()]);
+        index.add_commit_data(id_7.clone(), new_change_id(), &[id_6.clone(), id_3.clone()]);
+
+        let index = index.as_composite();
+        let walk_commit_ids = |heads: &[CommitId], roots: &[CommitId]| {
+            RevWalkBuilder::new(index)
+                .wanted_heads(to_positions_vec(index, heads))
+                .ancestors_until_roots(to_positions_vec(index, roots))
+                .map(|pos| index.entry_by_pos(pos).commit_id())
+                .collect_vec()
+        };
+
+        // id_4 and id_5 come after the root id_3 in the index, but the
+        // generation number of id_6 is no greater than id_3's, so they can't be
+        // descendants of id_3.
+        assert_eq!(
+            walk_commit_ids(&[id_7.clone()], &[id_3.clone()]),
+            vec![id_7.clone(), id_6.clone(), id_3.clone()]
+        );
+        // With the root id_2, the walk has to go one level deeper.
+        assert_eq!(
+            walk_commit_ids(&[id_7.clone()], &[id_2.clone()]),
+            vec![
+                id_7.clone(),
+                id_6.clone(),
+                id_5.clone(),
+                id_3.clone(),
+                id_2.clone(),
+            ]
+        );
+    }
+
     #[test]
     fn test_walk_ancestors_filtered_by_generation() {
         let mut new_change_id = change_id_generator();
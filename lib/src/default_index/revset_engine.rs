@@ -17,16 +17,22 @@
 use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::cmp::Reverse;
+use std::collections::hash_map::RandomState;
 use std::collections::BTreeSet;
 use std::collections::BinaryHeap;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
 use std::iter;
+use std::num::NonZeroUsize;
 use std::ops::Range;
 use std::rc::Rc;
 use std::str;
 use std::sync::Arc;
+use std::sync::Mutex;
 
+use clru::CLruCache;
+use clru::WeightScale;
 use futures::StreamExt as _;
 use itertools::Itertools;
 use pollster::FutureExt as _;
@@ -63,6 +69,7 @@ use crate::revset::RevsetEvaluationError;
 use crate::revset::RevsetFilterPredicate;
 use crate::revset::GENERATION_RANGE_FULL;
 use crate::rewrite;
+use crate::settings::MemoryBudget;
 use crate::store::Store;
 use crate::str_util::StringPattern;
 use crate::union_find;
@@ -769,14 +776,234 @@ pub fn evaluate<I: AsCompositeIndex + Clone>(
     let context = EvaluationContext {
         store: store.clone(),
         index: index.as_composite(),
+        cache: None,
     };
     let internal_revset = context.evaluate(expression)?;
     Ok(RevsetImpl::new(internal_revset, index))
 }
 
+/// Like [`evaluate()`], but reuses results of sub-expressions that have
+/// already been evaluated against the same `index`.
+pub(super) fn evaluate_with_cache<I: AsCompositeIndex + Clone>(
+    expression: &ResolvedExpression,
+    store: &Arc<Store>,
+    index: I,
+    cache: &RevsetCache,
+) -> Result<RevsetImpl<I>, RevsetEvaluationError> {
+    let context = EvaluationContext {
+        store: store.clone(),
+        index: index.as_composite(),
+        cache: Some(cache),
+    };
+    let internal_revset = context.evaluate(expression)?;
+    Ok(RevsetImpl::new(internal_revset, index))
+}
+
+/// Default maximum number of index positions held by [`RevsetCache`].
+const REVSET_CACHE_CAPACITY: usize = 1 << 22;
+
+/// Memoized results of revset sub-expressions evaluated against an index
+/// that doesn't change.
+///
+/// Only graph walks whose results don't depend on commit contents are
+/// cached. An expression is materialized on its second evaluation, so that
+/// lazily-consumed revsets (such as `::@` in `jj log -n1`) aren't walked to
+/// the end just to be cached. The total number of cached positions is bounded,
+/// and the least recently used results are evicted first.
+pub(super) struct RevsetCache {
+    entries: Mutex<CLruCache<CacheKey, CacheEntry, RandomState, CacheEntryScale>>,
+}
+
+/// Structural key of a graph-only expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum CacheKey {
+    Commits(Vec<CommitId>),
+    Ancestors {
+        heads: Box<CacheKey>,
+        generation: Range<u64>,
+    },
+    Range {
+        roots: Box<CacheKey>,
+        heads: Box<CacheKey>,
+        generation: Range<u64>,
+    },
+    DagRange {
+        roots: Box<CacheKey>,
+        heads: Box<CacheKey>,
+        generation_from_roots: Range<u64>,
+    },
+    Reachable {
+        sources: Box<CacheKey>,
+        domain: Box<CacheKey>,
+    },
+    Heads(Box<CacheKey>),
+    Roots(Box<CacheKey>),
+    ForkPoint(Box<CacheKey>),
+    Latest {
+        candidates: Box<CacheKey>,
+        count: usize,
+    },
+    Coalesce(Box<CacheKey>, Box<CacheKey>),
+    Union(Box<CacheKey>, Box<CacheKey>),
+    Intersection(Box<CacheKey>, Box<CacheKey>),
+    Difference(Box<CacheKey>, Box<CacheKey>),
+}
+
+enum CacheEntry {
+    Seen,
+    Evaluated(Arc<[IndexPosition]>),
+}
+
+struct CacheEntryScale;
+
+impl WeightScale<CacheKey, CacheEntry> for CacheEntryScale {
+    fn weight(&self, _key: &CacheKey, entry: &CacheEntry) -> usize {
+        match entry {
+            CacheEntry::Seen => 0,
+            CacheEntry::Evaluated(positions) => positions.len(),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum CacheLookup {
+    First,
+    Repeated,
+    Hit(Arc<[IndexPosition]>),
+}
+
+impl RevsetCache {
+    /// Creates a cache which holds up to `capacity` index positions.
+    pub(super) fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        RevsetCache {
+            entries: Mutex::new(CLruCache::with_scale(capacity, CacheEntryScale)),
+        }
+    }
+
+    /// Creates a cache sized to fit in the `budget`.
+    pub(super) fn with_memory_budget(budget: &MemoryBudget) -> Self {
+        Self::new(budget.revset_cache_capacity(REVSET_CACHE_CAPACITY))
+    }
+
+    fn lookup(&self, key: &CacheKey) -> CacheLookup {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            None => {
+                // Ignore the error, which means the cache has no capacity.
+                entries.put_with_weight(key.clone(), CacheEntry::Seen).ok();
+                CacheLookup::First
+            }
+            Some(CacheEntry::Seen) => CacheLookup::Repeated,
+            Some(CacheEntry::Evaluated(positions)) => CacheLookup::Hit(positions.clone()),
+        }
+    }
+
+    fn insert(&self, key: CacheKey, positions: Arc<[IndexPosition]>) {
+        let mut entries = self.entries.lock().unwrap();
+        // Results too large for the cache aren't cached.
+        entries
+            .put_with_weight(key, CacheEntry::Evaluated(positions))
+            .ok();
+    }
+
+    #[cfg(test)]
+    fn num_evaluated(&self) -> usize {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .filter(|(_, entry)| matches!(entry, CacheEntry::Evaluated(_)))
+            .count()
+    }
+}
+
+impl Default for RevsetCache {
+    fn default() -> Self {
+        Self::with_memory_budget(&MemoryBudget::default())
+    }
+}
+
+impl fmt::Debug for RevsetCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RevsetCache").finish_non_exhaustive()
+    }
+}
+
+/// Returns the cache key of `expression` if it's worth caching and its result
+/// depends only on the commit graph.
+fn cache_key(expression: &ResolvedExpression) -> Option<CacheKey> {
+    match expression {
+        ResolvedExpression::Ancestors { .. }
+        | ResolvedExpression::Range { .. }
+        | ResolvedExpression::DagRange { .. }
+        | ResolvedExpression::Reachable { .. }
+        | ResolvedExpression::Heads(_)
+        | ResolvedExpression::Roots(_)
+        | ResolvedExpression::ForkPoint(_) => graph_expression_key(expression),
+        _ => None,
+    }
+}
+
+/// Returns the structural key of `expression`, or `None` if the expression
+/// depends on anything but the commit graph.
+fn graph_expression_key(expression: &ResolvedExpression) -> Option<CacheKey> {
+    let key = |expression: &ResolvedExpression| graph_expression_key(expression).map(Box::new);
+    let key = match expression {
+        ResolvedExpression::Commits(commit_ids) => CacheKey::Commits(commit_ids.clone()),
+        ResolvedExpression::Ancestors { heads, generation } => CacheKey::Ancestors {
+            heads: key(heads)?,
+            generation: generation.clone(),
+        },
+        ResolvedExpression::Range {
+            roots,
+            heads,
+            generation,
+        } => CacheKey::Range {
+            roots: key(roots)?,
+            heads: key(heads)?,
+            generation: generation.clone(),
+        },
+        ResolvedExpression::DagRange {
+            roots,
+            heads,
+            generation_from_roots,
+        } => CacheKey::DagRange {
+            roots: key(roots)?,
+            heads: key(heads)?,
+            generation_from_roots: generation_from_roots.clone(),
+        },
+        ResolvedExpression::Reachable { sources, domain } => CacheKey::Reachable {
+            sources: key(sources)?,
+            domain: key(domain)?,
+        },
+        ResolvedExpression::Heads(candidates) => CacheKey::Heads(key(candidates)?),
+        ResolvedExpression::Roots(candidates) => CacheKey::Roots(key(candidates)?),
+        ResolvedExpression::ForkPoint(candidates) => CacheKey::ForkPoint(key(candidates)?),
+        ResolvedExpression::Latest { candidates, count } => CacheKey::Latest {
+            candidates: key(candidates)?,
+            count: *count,
+        },
+        ResolvedExpression::Coalesce(expression1, expression2) => {
+            CacheKey::Coalesce(key(expression1)?, key(expression2)?)
+        }
+        ResolvedExpression::Union(expression1, expression2) => {
+            CacheKey::Union(key(expression1)?, key(expression2)?)
+        }
+        ResolvedExpression::Intersection(expression1, expression2) => {
+            CacheKey::Intersection(key(expression1)?, key(expression2)?)
+        }
+        ResolvedExpression::Difference(expression1, expression2) => {
+            CacheKey::Difference(key(expression1)?, key(expression2)?)
+        }
+        ResolvedExpression::FilterWithin { .. } => return None,
+    };
+    Some(key)
+}
+
 struct EvaluationContext<'index> {
     store: Arc<Store>,
     index: &'index CompositeIndex,
+    cache: Option<&'index RevsetCache>,
 }
 
 fn to_u32_generation_range(range: &Range<u64>) -> Result<Range<u32>, RevsetEvaluationError> {
@@ -793,6 +1020,30 @@ impl EvaluationContext<'_> {
     fn evaluate(
         &self,
         expression: &ResolvedExpression,
+    ) -> Result<Box<dyn InternalRevset>, RevsetEvaluationError> {
+        let Some((cache, key)) = self
+            .cache
+            .and_then(|cache| Some((cache, cache_key(expression)?)))
+        else {
+            return self.evaluate_uncached(expression);
+        };
+        match cache.lookup(&key) {
+            CacheLookup::First => self.evaluate_uncached(expression),
+            CacheLookup::Repeated => {
+                let set = self.evaluate_uncached(expression)?;
+                let positions: Vec<_> = set.positions().attach(self.index).try_collect()?;
+                cache.insert(key, positions.clone().into());
+                Ok(Box::new(EagerRevset { positions }))
+            }
+            CacheLookup::Hit(positions) => Ok(Box::new(EagerRevset {
+                positions: positions.to_vec(),
+            })),
+        }
+    }
+
+    fn evaluate_uncached(
+        &self,
+        expression: &ResolvedExpression,
     ) -> Result<Box<dyn InternalRevset>, RevsetEvaluationError> {
         let index = self.index;
         match expression {
@@ -1227,6 +1478,9 @@ fn build_predicate_fn(
             let commit = store.get_commit(&entry.commit_id())?;
             Ok(commit.has_conflict()?)
         }),
+        RevsetFilterPredicate::Shallow => {
+            box_pure_predicate_fn(move |index, pos| Ok(index.entry_by_pos(pos).is_shallow()))
+        }
         RevsetFilterPredicate::Extension(ext) => {
             let ext = ext.clone();
             box_pure_predicate_fn(move |index, pos| {
@@ -1358,6 +1612,8 @@ fn to_file_content(path: &RepoPath, value: MaterializedTreeValue) -> BackendResu
 
 #[cfg(test)]
 mod tests {
+    use assert_matches::assert_matches;
+
     use super::*;
     use crate::default_index::DefaultMutableIndex;
 
@@ -1680,4 +1936,75 @@ mod tests {
 
         assert!(positions_accum.contains(&id_1).unwrap());
     }
+
+    #[test]
+    fn test_revset_cache_lookup() {
+        let cache = RevsetCache::default();
+        let key_a = CacheKey::Commits(vec![CommitId::from_hex("aaaaaa")]);
+        let key_b = CacheKey::Commits(vec![CommitId::from_hex("bbbbbb")]);
+        let positions: Arc<[IndexPosition]> = vec![IndexPosition(1), IndexPosition(0)].into();
+        assert_matches!(cache.lookup(&key_a), CacheLookup::First);
+        assert_matches!(cache.lookup(&key_a), CacheLookup::Repeated);
+        assert_eq!(cache.num_evaluated(), 0);
+        cache.insert(key_a.clone(), positions.clone());
+        assert_eq!(cache.num_evaluated(), 1);
+        assert_matches!(cache.lookup(&key_a), CacheLookup::Hit(p) if p == positions);
+        assert_matches!(cache.lookup(&key_b), CacheLookup::First);
+    }
+
+    #[test]
+    fn test_revset_cache_eviction() {
+        let cache = RevsetCache::new(6);
+        let key = |hex: &str| CacheKey::Commits(vec![CommitId::from_hex(hex)]);
+        let positions =
+            |len: u32| -> Arc<[IndexPosition]> { (0..len).map(IndexPosition).collect() };
+        cache.insert(key("aaaaaa"), positions(2));
+        cache.insert(key("bbbbbb"), positions(2));
+        assert_eq!(cache.num_evaluated(), 2);
+        // The least recently used entry is evicted to make room.
+        assert_matches!(cache.lookup(&key("aaaaaa")), CacheLookup::Hit(_));
+        cache.insert(key("cccccc"), positions(1));
+        assert_eq!(cache.num_evaluated(), 2);
+        assert_matches!(cache.lookup(&key("bbbbbb")), CacheLookup::First);
+        assert_matches!(cache.lookup(&key("aaaaaa")), CacheLookup::Hit(_));
+        // Results larger than the cache aren't cached.
+        cache.insert(key("dddddd"), positions(6));
+        assert_matches!(cache.lookup(&key("dddddd")), CacheLookup::First);
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let commits = || {
+            Box::new(ResolvedExpression::Commits(vec![CommitId::from_hex(
+                "111111",
+            )]))
+        };
+        let ancestors = |heads| ResolvedExpression::Ancestors {
+            heads,
+            generation: GENERATION_RANGE_FULL,
+        };
+        assert!(cache_key(&commits()).is_none());
+        assert!(cache_key(&ancestors(commits())).is_some());
+        assert!(cache_key(&ResolvedExpression::Heads(Box::new(
+            ResolvedExpression::Union(Box::new(ancestors(commits())), commits())
+        )))
+        .is_some());
+        // Union of walks isn't cached itself, but its operands are.
+        assert!(cache_key(&ResolvedExpression::Union(
+            Box::new(ancestors(commits())),
+            commits()
+        ))
+        .is_none());
+        // Structurally equal expressions have equal keys.
+        assert_eq!(
+            cache_key(&ancestors(commits())),
+            cache_key(&ancestors(commits()))
+        );
+        // Results of filters depend on commit contents.
+        let filtered = ResolvedExpression::FilterWithin {
+            candidates: commits(),
+            predicate: ResolvedPredicateExpression::Filter(RevsetFilterPredicate::HasConflict),
+        };
+        assert!(cache_key(&ancestors(Box::new(filtered))).is_none());
+    }
 }
@@ -67,6 +67,10 @@ type IndexGraphEdge = GraphEdge<IndexPosition>;
 /// commit to also be missing. In the example above, that means that "B" will
 /// have a missing edge to "d" rather than to the root.
 ///
+/// Commits at the boundary of a shallow clone are recorded with the root
+/// commit as their parent, but the history between them is unknown. Edges
+/// from such commits are always "missing".
+///
 /// `RevsetGraphWalk` can be configured to skip transitive edges that it would
 /// otherwise return. In this mode (which is the default), the edge from "A" to
 /// "E" in the example above would be excluded because there's also a transitive
@@ -170,6 +174,9 @@ impl<'a> RevsetGraphWalk<'a> {
         index: &CompositeIndex,
         index_entry: &IndexEntry,
     ) -> Result<Vec<IndexGraphEdge>, RevsetEvaluationError> {
+        if index_entry.is_shallow() {
+            return Ok(shallow_edges(index_entry));
+        }
         let mut edges = Vec::new();
         let mut known_ancestors = HashSet::new();
         for parent in index_entry.parents() {
@@ -209,6 +216,12 @@ impl<'a> RevsetGraphWalk<'a> {
                 stack.pop().unwrap();
                 continue;
             }
+            if entry.is_shallow() {
+                let edges = shallow_edges(entry);
+                stack.pop().unwrap();
+                self.edges.insert(position, edges);
+                continue;
+            }
             let mut edges = Vec::new();
             let mut known_ancestors = HashSet::new();
             let mut parents_complete = true;
@@ -344,3 +357,13 @@ impl RevWalk<CompositeIndex> for RevsetGraphWalk<'_> {
         self.try_next(index).transpose()
     }
 }
+
+/// Edges from a shallow commit. The history beyond it is unknown, so the edge
+/// to the root commit isn't a real edge.
+fn shallow_edges(index_entry: &IndexEntry) -> Vec<IndexGraphEdge> {
+    index_entry
+        .parent_positions()
+        .into_iter()
+        .map(IndexGraphEdge::missing)
+        .collect()
+}
@@ -22,11 +22,14 @@ use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use itertools::Itertools;
 use tempfile::NamedTempFile;
 use thiserror::Error;
 
+use super::composite::CompositeIndex;
+use super::composite::IndexSegment as _;
 use super::mutable::DefaultMutableIndex;
 use super::readonly::DefaultReadonlyIndex;
 use super::readonly::ReadonlyIndexLoadError;
@@ -35,6 +38,7 @@ use crate::backend::BackendError;
 use crate::backend::BackendInitError;
 use crate::backend::CommitId;
 use crate::commit::CommitByCommitterTimestamp;
+use crate::config::ConfigGetError;
 use crate::dag_walk;
 use crate::file_util;
 use crate::file_util::persist_content_addressed_temp_file;
@@ -46,10 +50,14 @@ use crate::index::IndexStore;
 use crate::index::IndexWriteError;
 use crate::index::MutableIndex;
 use crate::index::ReadonlyIndex;
+use crate::lock::FileLock;
+use crate::lock::FileLockError;
 use crate::object_id::ObjectId;
+use crate::op_store::OpStore;
 use crate::op_store::OpStoreError;
 use crate::op_store::OperationId;
 use crate::operation::Operation;
+use crate::settings::UserSettings;
 use crate::store::Store;
 
 // BLAKE2b-512 hash length in hex string
@@ -86,11 +94,67 @@ pub enum DefaultIndexStoreError {
     },
     #[error(transparent)]
     OpStore(#[from] OpStoreError),
+    #[error("Failed to remove unused commit index files")]
+    Gc(#[from] PathError),
+    #[error("Failed to lock commit index store")]
+    Lock(#[from] FileLockError),
 }
 
+/// Policy for squashing index segments into fewer, larger segments.
+///
+/// Segments are squashed when a new segment is written, and when compacting
+/// the index explicitly.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IndexCompactionPolicy {
+    /// A parent segment is squashed unless it has more than `size_ratio` times
+    /// as many commits as the segments to be squashed.
+    pub size_ratio: u32,
+    /// Parent segments with fewer commits than this are always squashed.
+    pub min_segment_commits: u32,
+    /// Maximum number of segments to keep. 0 means unlimited.
+    pub max_segments: usize,
+}
+
+impl IndexCompactionPolicy {
+    /// Policy that squashes all segments into one.
+    pub fn full() -> Self {
+        IndexCompactionPolicy {
+            max_segments: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Loads the policy from the `index.compaction` settings.
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        Ok(IndexCompactionPolicy {
+            size_ratio: settings.get("index.compaction.size-ratio")?,
+            min_segment_commits: settings.get("index.compaction.min-segment-commits")?,
+            max_segments: settings.get("index.compaction.max-segments")?,
+        })
+    }
+}
+
+impl Default for IndexCompactionPolicy {
+    fn default() -> Self {
+        IndexCompactionPolicy {
+            size_ratio: 2,
+            min_segment_commits: 0,
+            max_segments: 0,
+        }
+    }
+}
+
+/// Commit index stored in `segments` files, and links from operations to
+/// the segment files in `operations`.
+///
+/// Segment files are content-addressed and never modified, and links are
+/// replaced atomically, so readers don't need to lock the store. Processes
+/// that write segments or links, or remove files, hold the store lock so that
+/// files aren't removed while another process is linking to them.
 #[derive(Debug)]
 pub struct DefaultIndexStore {
     dir: PathBuf,
+    compaction_policy: IndexCompactionPolicy,
 }
 
 impl DefaultIndexStore {
@@ -101,6 +165,7 @@ impl DefaultIndexStore {
     pub fn init(dir: &Path) -> Result<Self, DefaultIndexStoreInitError> {
         let store = DefaultIndexStore {
             dir: dir.to_owned(),
+            compaction_policy: IndexCompactionPolicy::default(),
         };
         store.ensure_base_dirs()?;
         Ok(store)
@@ -109,12 +174,23 @@ impl DefaultIndexStore {
     pub fn load(dir: &Path) -> DefaultIndexStore {
         DefaultIndexStore {
             dir: dir.to_owned(),
+            compaction_policy: IndexCompactionPolicy::default(),
         }
     }
 
+    /// Sets the policy used to squash segments when writing new segments.
+    pub fn with_compaction_policy(mut self, policy: IndexCompactionPolicy) -> Self {
+        self.compaction_policy = policy;
+        self
+    }
+
     pub fn reinit(&self) -> Result<(), DefaultIndexStoreInitError> {
         // Create base directories in case the store was initialized by old jj.
         self.ensure_base_dirs()?;
+        let _lock = self.lock().map_err(|err| PathError {
+            path: err.path,
+            error: err.err,
+        })?;
         // Remove all operation links to trigger rebuilding.
         file_util::remove_dir_contents(&self.operations_dir())?;
         // Remove index segments to save disk space. If raced, new segment file
@@ -140,6 +216,11 @@ impl DefaultIndexStore {
         Ok(())
     }
 
+    /// Takes the lock that must be held while writing or removing files.
+    fn lock(&self) -> Result<FileLock, FileLockError> {
+        FileLock::lock(self.dir.join("lock"))
+    }
+
     fn operations_dir(&self) -> PathBuf {
         self.dir.join("operations")
     }
@@ -148,11 +229,44 @@ impl DefaultIndexStore {
         self.dir.join("segments")
     }
 
+    /// Returns the size in bytes of the segment file of the given `name`.
+    pub fn segment_file_size(&self, name: &str) -> io::Result<u64> {
+        Ok(self.segments_dir().join(name).metadata()?.len())
+    }
+
     fn load_index_segments_at_operation(
         &self,
         op_id: &OperationId,
         commit_id_length: usize,
         change_id_length: usize,
+    ) -> Result<Arc<ReadonlyIndexSegment>, DefaultIndexStoreError> {
+        match self.load_index_segments_at_operation_unlocked(
+            op_id,
+            commit_id_length,
+            change_id_length,
+        ) {
+            Err(DefaultIndexStoreError::LoadIndex(err)) if err.is_not_found() => {
+                // The link may have been replaced by concurrent compaction,
+                // and the segments it pointed to removed by gc, after we read
+                // it. Nothing is removed while we hold the lock, so read the
+                // link again.
+                tracing::debug!(?err, "index segment disappeared while loading, retrying");
+                let _lock = self.lock()?;
+                self.load_index_segments_at_operation_unlocked(
+                    op_id,
+                    commit_id_length,
+                    change_id_length,
+                )
+            }
+            result => result,
+        }
+    }
+
+    fn load_index_segments_at_operation_unlocked(
+        &self,
+        op_id: &OperationId,
+        commit_id_length: usize,
+        change_id_length: usize,
     ) -> Result<Arc<ReadonlyIndexSegment>, DefaultIndexStoreError> {
         let op_id_file = self.operations_dir().join(op_id.hex());
         let index_file_id_hex =
@@ -288,8 +402,25 @@ impl DefaultIndexStore {
         mutable_index: DefaultMutableIndex,
         op_id: &OperationId,
     ) -> Result<Arc<ReadonlyIndexSegment>, DefaultIndexStoreError> {
+        let _lock = self.lock()?;
+        // Another process may have indexed the same operation in the meantime.
+        // Reuse its segments instead of writing duplicates.
+        if self.operations_dir().join(op_id.hex()).is_file() {
+            match self.load_index_segments_at_operation_unlocked(
+                op_id,
+                mutable_index.commit_id_length(),
+                mutable_index.change_id_length(),
+            ) {
+                Ok(index_segment) => return Ok(index_segment),
+                Err(err) => {
+                    tracing::warn!(?err, "failed to load concurrently written index segment");
+                }
+            }
+        }
+        let policy =
+            self.effective_compaction_policy(mutable_index.as_composite(), &self.compaction_policy);
         let index_segment = mutable_index
-            .squash_and_save_in(&self.segments_dir())
+            .squash_and_save_in(&self.segments_dir(), &policy)
             .map_err(DefaultIndexStoreError::SaveIndex)?;
         self.associate_file_with_operation(&index_segment, op_id)
             .map_err(|source| DefaultIndexStoreError::AssociateIndex {
@@ -299,6 +430,181 @@ impl DefaultIndexStore {
         Ok(index_segment)
     }
 
+    /// Returns the `policy` to squash segments on top of the given `index`.
+    ///
+    /// The segments of the `index` are loaded before the lock is taken, so
+    /// they may have been removed by gc since. If any of them is missing, all
+    /// segments are squashed so the new segment doesn't refer to it.
+    fn effective_compaction_policy(
+        &self,
+        index: &CompositeIndex,
+        policy: &IndexCompactionPolicy,
+    ) -> IndexCompactionPolicy {
+        let segments_dir = self.segments_dir();
+        let has_missing_file = index
+            .ancestor_files_without_local()
+            .any(|segment| !segments_dir.join(segment.name()).is_file());
+        if has_missing_file {
+            tracing::info!("index segment was removed concurrently, squashing all segments");
+            IndexCompactionPolicy::full()
+        } else {
+            policy.clone()
+        }
+    }
+
+    /// Removes links to operations that no longer exist in the `op_store`,
+    /// and index segments that are no longer used by any operation. Files
+    /// modified after `keep_newer` are preserved since they may be in use by
+    /// concurrent processes.
+    ///
+    /// Returns the number of removed files.
+    #[tracing::instrument(skip(self, op_store, store))]
+    pub fn gc(
+        &self,
+        op_store: &dyn OpStore,
+        store: &Store,
+        keep_newer: SystemTime,
+    ) -> Result<usize, DefaultIndexStoreError> {
+        let is_old = |entry: &fs::DirEntry| -> Result<bool, PathError> {
+            let path = entry.path();
+            let metadata = entry.metadata().context(&path)?;
+            let mtime = metadata.modified().expect("unsupported platform?");
+            Ok(mtime <= keep_newer)
+        };
+        let _lock = self.lock()?;
+        let mut removed_count = 0;
+        let mut live_segment_names = HashSet::new();
+
+        let operations_dir = self.operations_dir();
+        let entries = operations_dir.read_dir().context(&operations_dir)?;
+        for entry in entries {
+            let entry = entry.context(&operations_dir)?;
+            let path = entry.path();
+            let Some(op_id) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| OperationId::try_from_hex(name).ok())
+            else {
+                continue;
+            };
+            match op_store.read_operation(&op_id) {
+                Ok(_) => {}
+                // The operation may have been written concurrently. Keep the
+                // link and the segments it refers to if it's new.
+                Err(OpStoreError::ObjectNotFound { .. }) if is_old(&entry)? => {
+                    fs::remove_file(&path).context(&path)?;
+                    removed_count += 1;
+                    continue;
+                }
+                Err(OpStoreError::ObjectNotFound { .. }) => {}
+                Err(err) => return Err(err.into()),
+            }
+            let name = fs::read_to_string(&path).context(&path)?;
+            if live_segment_names.contains(&name) {
+                continue;
+            }
+            let segment = match ReadonlyIndexSegment::load(
+                &self.segments_dir(),
+                name,
+                store.commit_id_length(),
+                store.change_id_length(),
+            ) {
+                Ok(segment) => segment,
+                Err(err) => {
+                    // Parent segments can't be determined, so don't remove any
+                    // segment files.
+                    tracing::warn!(?err, ?path, "failed to load index segment");
+                    return Ok(removed_count);
+                }
+            };
+            let mut maybe_segment = Some(&segment);
+            while let Some(segment) = maybe_segment {
+                if !live_segment_names.insert(segment.name().to_owned()) {
+                    break;
+                }
+                maybe_segment = segment.parent_file();
+            }
+        }
+
+        let segments_dir = self.segments_dir();
+        for entry in segments_dir.read_dir().context(&segments_dir)? {
+            let entry = entry.context(&segments_dir)?;
+            let path = entry.path();
+            let Some(name) = entry.file_name().to_str().map(ToOwned::to_owned) else {
+                continue;
+            };
+            if live_segment_names.contains(&name) || !is_old(&entry)? {
+                continue;
+            }
+            match fs::remove_file(&path) {
+                Ok(()) => removed_count += 1,
+                // On Windows, files mapped by other processes can't be removed.
+                Err(err) if err.kind() == io::ErrorKind::PermissionDenied => {
+                    tracing::debug!(?err, ?path, "failed to remove index segment");
+                }
+                Err(err) => return Err(PathError { path, error: err }.into()),
+            }
+        }
+        tracing::info!(
+            live_segment_count = live_segment_names.len(),
+            removed_count,
+            "removed unused index files"
+        );
+        Ok(removed_count)
+    }
+
+    /// Squashes the index segments at the given `operation` as configured by
+    /// the `policy`, and associates the result with the operation.
+    ///
+    /// Returns the number of segments before and after compaction.
+    pub fn compact_index_at_operation(
+        &self,
+        operation: &Operation,
+        store: &Arc<Store>,
+        policy: &IndexCompactionPolicy,
+    ) -> Result<(usize, usize), DefaultIndexStoreError> {
+        let index_segment = match self.load_index_segments_at_operation(
+            operation.id(),
+            store.commit_id_length(),
+            store.change_id_length(),
+        ) {
+            Err(DefaultIndexStoreError::LoadAssociation(err))
+                if err.kind() == io::ErrorKind::NotFound =>
+            {
+                self.build_index_segments_at_operation(operation, store)?
+            }
+            result => result?,
+        };
+        let count_segments = |segment: &ReadonlyIndexSegment| {
+            segment
+                .as_composite()
+                .ancestor_files_without_local()
+                .count()
+                + 1
+        };
+        let num_segments_before = count_segments(&index_segment);
+        let _lock = self.lock()?;
+        let mutable_index = DefaultMutableIndex::incremental(index_segment.clone());
+        let policy = self.effective_compaction_policy(mutable_index.as_composite(), policy);
+        let compacted_segment = mutable_index
+            .squash_and_save_in(&self.segments_dir(), &policy)
+            .map_err(DefaultIndexStoreError::SaveIndex)?;
+        let num_segments_after = count_segments(&compacted_segment);
+        if compacted_segment.name() != index_segment.name() {
+            self.reassociate_file_with_operation(&compacted_segment, operation.id())
+                .map_err(|source| DefaultIndexStoreError::AssociateIndex {
+                    op_id: operation.id().clone(),
+                    source,
+                })?;
+        }
+        tracing::info!(
+            num_segments_before,
+            num_segments_after,
+            "compacted index segments"
+        );
+        Ok((num_segments_before, num_segments_after))
+    }
+
     /// Records a link from the given operation to the this index version.
     fn associate_file_with_operation(
         &self,
@@ -311,6 +617,21 @@ impl DefaultIndexStore {
         persist_content_addressed_temp_file(temp_file, self.operations_dir().join(op_id.hex()))?;
         Ok(())
     }
+
+    /// Like `associate_file_with_operation()`, but replaces the existing
+    /// association.
+    fn reassociate_file_with_operation(
+        &self,
+        index: &ReadonlyIndexSegment,
+        op_id: &OperationId,
+    ) -> io::Result<()> {
+        let mut temp_file = NamedTempFile::new_in(&self.dir)?;
+        temp_file.write_all(index.name().as_bytes())?;
+        temp_file
+            .persist(self.operations_dir().join(op_id.hex()))
+            .map_err(|err| err.error)?;
+        Ok(())
+    }
 }
 
 impl IndexStore for DefaultIndexStore {
@@ -202,6 +202,36 @@ pub fn merge<T: AsRef<[u8]>>(slices: &Merge<T>) -> MergeResult {
     merge_hunks(&Diff::by_line(diff_inputs), num_diffs)
 }
 
+/// Merges the given `slices` line by line, returning hunks that borrow the
+/// input contents.
+///
+/// Unlike `merge()`, the contents aren't copied, and adjacent resolved hunks
+/// aren't concatenated. This is useful for streaming the merge result to a
+/// writer.
+pub fn merge_borrowed<T: AsRef<[u8]>>(slices: &Merge<T>) -> Vec<Merge<&BStr>> {
+    let num_diffs = slices.removes().len();
+    let diff_inputs = slices.removes().chain(slices.adds());
+    let diff = Diff::by_line(diff_inputs);
+    diff.hunks()
+        .map(|diff_hunk| match diff_hunk.kind {
+            DiffHunkKind::Matching => {
+                debug_assert!(diff_hunk.contents.iter().all_equal());
+                Merge::resolved(diff_hunk.contents[0])
+            }
+            DiffHunkKind::Different => {
+                let merge = Merge::from_removes_adds(
+                    diff_hunk.contents[..num_diffs].iter().copied(),
+                    diff_hunk.contents[num_diffs..].iter().copied(),
+                );
+                match merge.resolve_trivial() {
+                    Some(&resolved) => Merge::resolved(resolved),
+                    None => merge,
+                }
+            }
+        })
+        .collect()
+}
+
 fn merge_hunks(diff: &Diff, num_diffs: usize) -> MergeResult {
     let mut resolved_hunk = BString::new(vec![]);
     let mut merge_hunks: Vec<Merge<BString>> = vec![];
@@ -524,4 +554,36 @@ b {
             ))
         );
     }
+
+    #[test]
+    fn test_merge_borrowed() {
+        let merge_borrowed = |removes: &[&[u8]], adds: &[&[u8]]| {
+            super::merge_borrowed(&Merge::from_removes_adds(removes, adds))
+                .into_iter()
+                .map(|hunk| hunk.map(|content| content.to_vec()))
+                .collect_vec()
+        };
+
+        // Resolved hunks aren't concatenated
+        assert_eq!(
+            merge_borrowed(&[b"a\nb\nc\n"], &[b"a2\nb\nc\n", b"a\nb\nc2\n"]),
+            vec![
+                Merge::resolved(b"a2\n".to_vec()),
+                Merge::resolved(b"b\n".to_vec()),
+                Merge::resolved(b"c2\n".to_vec()),
+            ]
+        );
+        // Conflict with non-conflicting lines around
+        assert_eq!(
+            merge_borrowed(&[b"a\nb\nc\n"], &[b"a\nb1\nc\n", b"a\nb2\nc\n"]),
+            vec![
+                Merge::resolved(b"a\n".to_vec()),
+                Merge::from_removes_adds(
+                    vec![b"b\n".to_vec()],
+                    vec![b"b1\n".to_vec(), b"b2\n".to_vec()]
+                ),
+                Merge::resolved(b"c\n".to_vec()),
+            ]
+        );
+    }
 }
@@ -0,0 +1,406 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integrity checks of a repository.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use itertools::Itertools as _;
+
+use crate::backend::BackendError;
+use crate::backend::BackendResult;
+use crate::backend::CommitId;
+use crate::backend::TreeId;
+use crate::backend::TreeValue;
+use crate::commit::Commit;
+use crate::default_index::AsCompositeIndex as _;
+use crate::default_index::DefaultReadonlyIndex;
+use crate::default_index::IndexEntry;
+use crate::local_backend::LocalBackend;
+use crate::local_pack::PackObjectKind;
+use crate::object_id::ObjectId as _;
+use crate::op_store::OperationId;
+use crate::op_walk;
+use crate::repo::ReadonlyRepo;
+use crate::repo::Repo as _;
+use crate::repo_path::RepoPathBuf;
+use crate::store::Store;
+
+/// Problem found by [`check_repo()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FsckIssue {
+    /// Operation or its view couldn't be loaded.
+    Operation {
+        /// Id of the operation.
+        id: OperationId,
+        /// Description of the error.
+        message: String,
+    },
+    /// Commit referenced by the view of an operation doesn't exist.
+    MissingViewCommit {
+        /// Id of the operation.
+        op_id: OperationId,
+        /// Id of the missing commit.
+        commit_id: CommitId,
+    },
+    /// Commit couldn't be read.
+    Commit {
+        /// Id of the commit.
+        id: CommitId,
+        /// Description of the error.
+        message: String,
+    },
+    /// Tree, file, symlink, or conflict object couldn't be read.
+    TreeEntry {
+        /// Commit the entry was reached from.
+        commit_id: CommitId,
+        /// Path to the entry.
+        path: RepoPathBuf,
+        /// Description of the error.
+        message: String,
+    },
+    /// Stored object contents don't match its id.
+    CorruptObject {
+        /// Type of the object.
+        object_type: String,
+        /// Hex id of the object.
+        hash: String,
+    },
+    /// Objects in the store couldn't be enumerated.
+    Store {
+        /// Description of the error.
+        message: String,
+    },
+    /// Commit index is inconsistent with the commits in the store.
+    Index {
+        /// Description of the inconsistency.
+        message: String,
+    },
+}
+
+impl FsckIssue {
+    /// Whether the issue can be fixed by rebuilding derived data such as the
+    /// index and caches.
+    pub fn is_repairable(&self) -> bool {
+        matches!(self, FsckIssue::Index { .. })
+    }
+}
+
+impl fmt::Display for FsckIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FsckIssue::Operation { id, message } => {
+                write!(f, "Operation {}: {message}", id.hex())
+            }
+            FsckIssue::MissingViewCommit { op_id, commit_id } => write!(
+                f,
+                "Operation {} references missing commit {}",
+                op_id.hex(),
+                commit_id.hex()
+            ),
+            FsckIssue::Commit { id, message } => write!(f, "Commit {}: {message}", id.hex()),
+            FsckIssue::TreeEntry {
+                commit_id,
+                path,
+                message,
+            } => write!(
+                f,
+                "Commit {}: path {:?}: {message}",
+                commit_id.hex(),
+                path.as_internal_file_string()
+            ),
+            FsckIssue::CorruptObject { object_type, hash } => {
+                write!(f, "Corrupt {object_type} object {hash}")
+            }
+            FsckIssue::Store { message } => write!(f, "Store: {message}"),
+            FsckIssue::Index { message } => write!(f, "Index: {message}"),
+        }
+    }
+}
+
+/// Result of [`check_repo()`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FsckReport {
+    /// Number of operations checked.
+    pub operations_checked: usize,
+    /// Number of commits checked.
+    pub commits_checked: usize,
+    /// Number of distinct trees checked.
+    pub trees_checked: usize,
+    /// Problems found.
+    pub issues: Vec<FsckIssue>,
+}
+
+/// Checks the integrity of the repo at `repo`'s operation and all its
+/// ancestor operations.
+///
+/// This verifies that:
+/// * all operations and views can be loaded,
+/// * all commits referenced by the views exist, and all commits, trees, and
+///   files reachable from them can be read,
+/// * object contents match their ids (if the backend supports it), and
+/// * the commit index agrees with the commits in the store (if the default
+///   index is used).
+///
+/// Commits referenced by the views are reported as missing only if the backend
+/// says they don't exist. Other backend errors are returned as is.
+pub fn check_repo(repo: &Arc<ReadonlyRepo>) -> BackendResult<FsckReport> {
+    let store = repo.store();
+    let mut report = FsckReport::default();
+
+    let mut head_ids = HashSet::new();
+    for op in op_walk::walk_ancestors(std::slice::from_ref(repo.operation())) {
+        let op = match op {
+            Ok(op) => op,
+            Err(err) => {
+                report.issues.push(FsckIssue::Operation {
+                    id: repo.op_id().clone(),
+                    message: err.to_string(),
+                });
+                break;
+            }
+        };
+        report.operations_checked += 1;
+        let view = match op.view() {
+            Ok(view) => view,
+            Err(err) => {
+                report.issues.push(FsckIssue::Operation {
+                    id: op.id().clone(),
+                    message: err.to_string(),
+                });
+                continue;
+            }
+        };
+        for commit_id in view.all_referenced_commit_ids() {
+            if head_ids.contains(commit_id) {
+                continue;
+            }
+            match store.get_commit(commit_id) {
+                Ok(_) => {
+                    head_ids.insert(commit_id.clone());
+                }
+                Err(BackendError::ObjectNotFound { .. }) => {
+                    report.issues.push(FsckIssue::MissingViewCommit {
+                        op_id: op.id().clone(),
+                        commit_id: commit_id.clone(),
+                    });
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    let commit_ids = check_commits(store, head_ids, &mut report);
+
+    if let Some(backend) = store.backend_impl().downcast_ref::<LocalBackend>() {
+        match backend.verify_objects() {
+            Ok(corrupt_objects) => {
+                for (kind, id) in corrupt_objects {
+                    let object_type = match kind {
+                        PackObjectKind::Commit => "commit",
+                        PackObjectKind::Tree => "tree",
+                        PackObjectKind::File => "file",
+                        PackObjectKind::Symlink => "symlink",
+                        PackObjectKind::Conflict => "conflict",
+                    };
+                    report.issues.push(FsckIssue::CorruptObject {
+                        object_type: object_type.to_owned(),
+                        hash: hex::encode(id),
+                    });
+                }
+            }
+            Err(err) => report.issues.push(FsckIssue::Store {
+                message: err.to_string(),
+            }),
+        }
+    }
+
+    if let Some(index) = repo
+        .readonly_index()
+        .as_any()
+        .downcast_ref::<DefaultReadonlyIndex>()
+    {
+        check_index(store, index, &commit_ids, &mut report);
+    }
+
+    Ok(report)
+}
+
+/// Walks commits and their trees from `head_ids`. Returns the ids of the
+/// commits that could be read.
+fn check_commits(
+    store: &Arc<Store>,
+    head_ids: HashSet<CommitId>,
+    report: &mut FsckReport,
+) -> HashSet<CommitId> {
+    let mut visited_commits = HashSet::new();
+    let mut visited_trees = HashSet::new();
+    let mut pending = head_ids.into_iter().collect_vec();
+    while let Some(id) = pending.pop() {
+        if !visited_commits.insert(id.clone()) {
+            continue;
+        }
+        let commit = match store.get_commit(&id) {
+            Ok(commit) => commit,
+            Err(err) => {
+                visited_commits.remove(&id);
+                report.issues.push(FsckIssue::Commit {
+                    id,
+                    message: err.to_string(),
+                });
+                continue;
+            }
+        };
+        report.commits_checked += 1;
+        pending.extend(commit.parent_ids().iter().cloned());
+        for tree_id in commit.tree_id().to_merge().iter() {
+            check_tree(
+                store,
+                &id,
+                RepoPathBuf::root(),
+                tree_id,
+                &mut visited_trees,
+                report,
+            );
+        }
+    }
+    report.trees_checked = visited_trees.len();
+    visited_commits
+}
+
+fn check_tree(
+    store: &Arc<Store>,
+    commit_id: &CommitId,
+    dir: RepoPathBuf,
+    tree_id: &TreeId,
+    visited_trees: &mut HashSet<TreeId>,
+    report: &mut FsckReport,
+) {
+    if !visited_trees.insert(tree_id.clone()) {
+        return;
+    }
+    let tree = match store.get_tree(dir.clone(), tree_id) {
+        Ok(tree) => tree,
+        Err(err) => {
+            report.issues.push(FsckIssue::TreeEntry {
+                commit_id: commit_id.clone(),
+                path: dir,
+                message: err.to_string(),
+            });
+            return;
+        }
+    };
+    for entry in tree.entries_non_recursive() {
+        let path = dir.join(entry.name());
+        let result = match entry.value() {
+            TreeValue::Tree(id) => {
+                check_tree(store, commit_id, path, id, visited_trees, report);
+                continue;
+            }
+            TreeValue::File { id, .. } => store.read_file(&path, id).map(|_| ()),
+            TreeValue::Symlink(id) => store.read_symlink(&path, id).map(|_| ()),
+            TreeValue::Conflict(id) => store.read_conflict(&path, id).map(|_| ()),
+            TreeValue::GitSubmodule(_) => Ok(()),
+        };
+        if let Err(err) = result {
+            report.issues.push(FsckIssue::TreeEntry {
+                commit_id: commit_id.clone(),
+                path,
+                message: err.to_string(),
+            });
+        }
+    }
+}
+
+fn check_index(
+    store: &Arc<Store>,
+    index: &DefaultReadonlyIndex,
+    commit_ids: &HashSet<CommitId>,
+    report: &mut FsckReport,
+) {
+    let index = index.as_composite();
+    for id in commit_ids.iter().sorted() {
+        let Some(entry) = index.entry_by_id(id) else {
+            report.issues.push(FsckIssue::Index {
+                message: format!("commit {} is not indexed", id.hex()),
+            });
+            continue;
+        };
+        // Commits were read successfully by check_commits().
+        let commit = store.get_commit(id).unwrap();
+        check_index_entry(&entry, &commit, report);
+    }
+}
+
+fn check_index_entry(entry: &IndexEntry, commit: &Commit, report: &mut FsckReport) {
+    let indexed_parent_ids = entry
+        .parents()
+        .map(|parent| parent.commit_id())
+        .collect_vec();
+    if indexed_parent_ids != commit.parent_ids() {
+        report.issues.push(FsckIssue::Index {
+            message: format!("commit {} has wrong parents", commit.id().hex()),
+        });
+    }
+    if entry.change_id() != *commit.change_id() {
+        report.issues.push(FsckIssue::Index {
+            message: format!("commit {} has wrong change id", commit.id().hex()),
+        });
+    }
+}
+
+/// Checks every commit in the commit `index` against the store.
+///
+/// Unlike [`check_repo()`], this also covers indexed commits that are no
+/// longer reachable from any operation, and doesn't walk operations or trees.
+/// It verifies that:
+/// * all indexed commits can be read from the store,
+/// * their indexed parents and change ids match the stored commits, and
+/// * parents are indexed before their children, with smaller generation
+///   numbers.
+pub fn check_index_entries(store: &Arc<Store>, index: &DefaultReadonlyIndex) -> FsckReport {
+    let mut report = FsckReport::default();
+    for entry in index.as_composite().entries() {
+        report.commits_checked += 1;
+        let id = entry.commit_id();
+        let expected_generation = entry
+            .parents()
+            .map(|parent| parent.generation_number() + 1)
+            .max()
+            .unwrap_or(0);
+        if entry.generation_number() != expected_generation {
+            report.issues.push(FsckIssue::Index {
+                message: format!("commit {} has wrong generation number", id.hex()),
+            });
+        }
+        if entry
+            .parent_positions()
+            .iter()
+            .any(|pos| *pos >= entry.position())
+        {
+            report.issues.push(FsckIssue::Index {
+                message: format!("commit {} is indexed before its parents", id.hex()),
+            });
+        }
+        match store.get_commit(&id) {
+            Ok(commit) => check_index_entry(&entry, &commit, &mut report),
+            Err(err) => report.issues.push(FsckIssue::Index {
+                message: format!("commit {} can't be read: {err}", id.hex()),
+            }),
+        }
+    }
+    report
+}
@@ -23,6 +23,7 @@
 #![warn(missing_docs)]
 
 use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::config::ConfigGetError;
 use crate::settings::UserSettings;
@@ -32,6 +33,9 @@ use crate::settings::UserSettings;
 pub struct WatchmanConfig {
     /// Whether to use triggers to monitor for changes in the background.
     pub register_trigger: bool,
+    /// How long the changes reported by Watchman are trusted before the working
+    /// copy is scanned in full again. `None` means forever.
+    pub full_scan_interval: Option<Duration>,
 }
 
 /// The recognized kinds of filesystem monitors.
@@ -59,10 +63,17 @@ impl FsmonitorSettings {
     pub fn from_settings(settings: &UserSettings) -> Result<FsmonitorSettings, ConfigGetError> {
         let name = "core.fsmonitor";
         match settings.get_string(name)?.as_ref() {
-            "watchman" => Ok(Self::Watchman(WatchmanConfig {
-                // TODO: rename to "register-snapshot-trigger" for consistency?
-                register_trigger: settings.get_bool("core.watchman.register_snapshot_trigger")?,
-            })),
+            "watchman" => {
+                let full_scan_hours: u64 =
+                    settings.get("core.watchman.full-scan-interval-hours")?;
+                Ok(Self::Watchman(WatchmanConfig {
+                    // TODO: rename to "register-snapshot-trigger" for consistency?
+                    register_trigger: settings
+                        .get_bool("core.watchman.register_snapshot_trigger")?,
+                    full_scan_interval: (full_scan_hours > 0)
+                        .then(|| Duration::from_secs(full_scan_hours.saturating_mul(60 * 60))),
+                }))
+            }
             "test" => Err(ConfigGetError::Type {
                 name: name.to_owned(),
                 error: "Cannot use test fsmonitor in real repository".into(),
@@ -1367,6 +1367,8 @@ pub enum GitFetchError {
         chars = INVALID_REFSPEC_CHARS.iter().join("`, `")
     )]
     InvalidBranchPattern,
+    #[error("Remote-tracking bookmark '{0}' would be fetched from more than one remote branch")]
+    ConflictingBranchMapping(String),
     #[error("Failed to import Git refs")]
     GitImportError(#[from] GitImportError),
     // TODO: I'm sure there are other errors possible, such as transport-level errors.
@@ -1391,6 +1393,19 @@ fn fetch_options(
     fetch_options
 }
 
+/// Remote branch to be fetched under a different remote-tracking branch name.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GitFetchBranchMapping {
+    /// Name of the branch on the remote.
+    pub remote_branch: String,
+    /// Name of the remote-tracking branch to store the fetched branch as.
+    pub local_branch: String,
+}
+
+fn is_valid_refspec_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(INVALID_REFSPEC_CHARS) && !name.contains('*')
+}
+
 struct FetchedBranches {
     branches: Vec<StringPattern>,
     remote: String,
@@ -1423,11 +1438,16 @@ impl<'a> GitFetch<'a> {
     /// Perform a `git fetch` on the local git repo, updating the
     /// remote-tracking branches in the git repo.
     ///
+    /// Branches matching `branch_mappings` are stored under the mapped
+    /// remote-tracking branch names, taking precedence over the remote
+    /// branches of the same names matched by `branch_names`.
+    ///
     /// Keeps track of the {branch_names, remote_name} pair the refs can be
     /// subsequently imported into the `jj` repo by calling `import_refs()`.
     fn fetch(
         &mut self,
         branch_names: &[StringPattern],
+        branch_mappings: &[GitFetchBranchMapping],
         remote_name: &str,
     ) -> Result<Option<String>, GitFetchError> {
         let mut remote = self.git_repo.find_remote(remote_name).map_err(|err| {
@@ -1439,7 +1459,7 @@ impl<'a> GitFetch<'a> {
         })?;
         // At this point, we are only updating Git's remote tracking branches, not the
         // local branches.
-        let refspecs: Vec<_> = branch_names
+        let mut refspecs: Vec<_> = branch_names
             .iter()
             .map(|pattern| {
                 pattern
@@ -1453,6 +1473,29 @@ impl<'a> GitFetch<'a> {
             })
             .collect::<Option<_>>()
             .ok_or(GitFetchError::InvalidBranchPattern)?;
+        for (i, mapping) in branch_mappings.iter().enumerate() {
+            let GitFetchBranchMapping {
+                remote_branch,
+                local_branch,
+            } = mapping;
+            if !is_valid_refspec_name(remote_branch) || !is_valid_refspec_name(local_branch) {
+                return Err(GitFetchError::InvalidBranchPattern);
+            }
+            // Two mappings to the same destination would be ambiguous. If the
+            // destination is also matched by a pattern, the mapping wins
+            // because its refspec is applied last.
+            if branch_mappings[..i]
+                .iter()
+                .any(|other| other.local_branch == *local_branch)
+            {
+                return Err(GitFetchError::ConflictingBranchMapping(
+                    local_branch.clone(),
+                ));
+            }
+            refspecs.push(format!(
+                "+refs/heads/{remote_branch}:refs/remotes/{remote_name}/{local_branch}"
+            ));
+        }
         if refspecs.is_empty() {
             // Don't fall back to the base refspecs.
             return Ok(None);
@@ -1471,7 +1514,15 @@ impl<'a> GitFetch<'a> {
         )?;
 
         self.fetched.push(FetchedBranches {
-            branches: branch_names.to_vec(),
+            branches: branch_names
+                .iter()
+                .cloned()
+                .chain(
+                    branch_mappings
+                        .iter()
+                        .map(|mapping| StringPattern::exact(&mapping.local_branch)),
+                )
+                .collect(),
             remote: remote_name.to_string(),
         });
 
@@ -1539,12 +1590,21 @@ pub struct GitFetchStats {
     pub import_stats: GitImportStats,
 }
 
+/// Fetches branches matching `branch_names` from the remote, and imports the
+/// fetched refs.
+///
+/// Each of the `branch_mappings` fetches a single remote branch under a
+/// different remote-tracking branch name. Since the remote-tracking branch
+/// doesn't exist on the remote, it will be pruned by a later fetch of a
+/// matching pattern unless the mapping is specified again.
+#[allow(clippy::too_many_arguments)]
 #[tracing::instrument(skip(mut_repo, git_repo, callbacks))]
 pub fn fetch(
     mut_repo: &mut MutableRepo,
     git_repo: &git2::Repository,
     remote_name: &str,
     branch_names: &[StringPattern],
+    branch_mappings: &[GitFetchBranchMapping],
     callbacks: RemoteCallbacks<'_>,
     git_settings: &GitSettings,
     depth: Option<NonZeroU32>,
@@ -1555,7 +1615,7 @@ pub fn fetch(
         git_settings,
         fetch_options(callbacks, depth),
     );
-    let default_branch = git_fetch.fetch(branch_names, remote_name)?;
+    let default_branch = git_fetch.fetch(branch_names, branch_mappings, remote_name)?;
     let import_stats = git_fetch.import_refs()?;
     let stats = GitFetchStats {
         default_branch,
@@ -48,6 +48,7 @@ use thiserror::Error;
 use crate::backend::make_root_commit;
 use crate::backend::Backend;
 use crate::backend::BackendError;
+use crate::backend::BackendGcStats;
 use crate::backend::BackendInitError;
 use crate::backend::BackendLoadError;
 use crate::backend::BackendResult;
@@ -69,6 +70,7 @@ use crate::backend::Timestamp;
 use crate::backend::Tree;
 use crate::backend::TreeId;
 use crate::backend::TreeValue;
+use crate::commit_metadata_cache::CommitMetadataCache;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
 use crate::index::Index;
@@ -159,6 +161,7 @@ pub struct GitBackend {
     empty_tree_id: TreeId,
     extra_metadata_store: TableStore,
     cached_extra_metadata: Mutex<Option<Arc<ReadonlyTable>>>,
+    commit_metadata_cache: CommitMetadataCache,
 }
 
 impl GitBackend {
@@ -166,7 +169,11 @@ impl GitBackend {
         "git"
     }
 
-    fn new(base_repo: gix::ThreadSafeRepository, extra_metadata_store: TableStore) -> Self {
+    fn new(
+        base_repo: gix::ThreadSafeRepository,
+        extra_metadata_store: TableStore,
+        commit_metadata_cache: CommitMetadataCache,
+    ) -> Self {
         let repo = Mutex::new(base_repo.to_thread_local());
         let root_commit_id = CommitId::from_bytes(&[0; HASH_LENGTH]);
         let root_change_id = ChangeId::from_bytes(&[0; CHANGE_ID_LENGTH]);
@@ -179,6 +186,7 @@ impl GitBackend {
             empty_tree_id,
             extra_metadata_store,
             cached_extra_metadata: Mutex::new(None),
+            commit_metadata_cache,
         }
     }
 
@@ -271,30 +279,33 @@ impl GitBackend {
                 .map_err(GitBackendInitError::Path)?;
         };
         let extra_metadata_store = TableStore::init(extra_path, HASH_LENGTH);
-        Ok(GitBackend::new(git_repo, extra_metadata_store))
+        let commit_metadata_cache =
+            CommitMetadataCache::load(store_path.join("commit_cache"), HASH_LENGTH);
+        Ok(GitBackend::new(
+            git_repo,
+            extra_metadata_store,
+            commit_metadata_cache,
+        ))
     }
 
     pub fn load(
         settings: &UserSettings,
         store_path: &Path,
     ) -> Result<Self, Box<GitBackendLoadError>> {
-        let git_repo_path = {
-            let target_path = store_path.join("git_target");
-            let git_repo_path_str = fs::read_to_string(&target_path)
-                .context(&target_path)
-                .map_err(GitBackendLoadError::Path)?;
-            let git_repo_path = store_path.join(git_repo_path_str);
-            canonicalize_git_repo_path(&git_repo_path)
-                .context(&git_repo_path)
-                .map_err(GitBackendLoadError::Path)?
-        };
+        let git_repo_path = read_git_target(store_path).map_err(GitBackendLoadError::Path)?;
         let repo = gix::ThreadSafeRepository::open_opts(
             git_repo_path,
             gix_open_opts_from_settings(settings),
         )
         .map_err(GitBackendLoadError::OpenRepository)?;
         let extra_metadata_store = TableStore::load(store_path.join("extra"), HASH_LENGTH);
-        Ok(GitBackend::new(repo, extra_metadata_store))
+        let commit_metadata_cache =
+            CommitMetadataCache::load(store_path.join("commit_cache"), HASH_LENGTH);
+        Ok(GitBackend::new(
+            repo,
+            extra_metadata_store,
+            commit_metadata_cache,
+        ))
     }
 
     fn lock_git_repo(&self) -> MutexGuard<'_, gix::Repository> {
@@ -359,6 +370,17 @@ impl GitBackend {
         Ok(())
     }
 
+    /// Discards the cached commit metadata. The cache will be repopulated as
+    /// commits are read.
+    pub fn clear_commit_metadata_cache(&self) -> Result<(), TableStoreError> {
+        self.commit_metadata_cache.clear()
+    }
+
+    /// Writes commit metadata read so far to the on-disk cache.
+    pub fn flush_commit_metadata_cache(&self) {
+        self.commit_metadata_cache.flush();
+    }
+
     /// Imports the given commits and ancestors from the backing Git repo.
     ///
     /// The `head_ids` may contain commits that have already been imported, but
@@ -478,6 +500,37 @@ pub fn canonicalize_git_repo_path(path: &Path) -> io::Result<PathBuf> {
     }
 }
 
+fn read_git_target(store_path: &Path) -> Result<PathBuf, PathError> {
+    let target_path = store_path.join("git_target");
+    let git_repo_path_str = fs::read_to_string(&target_path).context(&target_path)?;
+    let git_repo_path = store_path.join(git_repo_path_str);
+    canonicalize_git_repo_path(&git_repo_path).context(&git_repo_path)
+}
+
+/// Reads fetch URLs of the remotes configured in the Git repository backing
+/// the store at `store_path`, without loading the backend.
+///
+/// This is intended to be used before the user settings are resolved. Returns
+/// an empty list if the store isn't backed by Git.
+pub fn read_remote_urls(store_path: &Path) -> Result<Vec<String>, Box<GitBackendLoadError>> {
+    if !store_path.join("git_target").exists() {
+        return Ok(vec![]);
+    }
+    let git_repo_path = read_git_target(store_path).map_err(GitBackendLoadError::Path)?;
+    let repo = gix::open_opts(git_repo_path, gix::open::Options::isolated())
+        .map_err(GitBackendLoadError::OpenRepository)?;
+    let urls = repo
+        .remote_names()
+        .iter()
+        .filter_map(|name| repo.find_remote(name.as_ref()).ok())
+        .filter_map(|remote| {
+            let url = remote.url(gix::remote::Direction::Fetch)?;
+            Some(url.to_bstring().to_string())
+        })
+        .collect();
+    Ok(urls)
+}
+
 fn gix_open_opts_from_settings(settings: &UserSettings) -> gix::open::Options {
     let user_name = settings.user_name();
     let user_email = settings.user_email();
@@ -741,6 +794,27 @@ fn recreate_no_gc_refs(
 ) -> BackendResult<()> {
     // Calculate diff between existing no-gc refs and new heads.
     let new_heads: HashSet<CommitId> = new_heads.into_iter().collect();
+    let no_gc_refs_to_delete = collect_no_gc_refs_to_delete(git_repo, &new_heads, keep_newer)?;
+
+    // It's slow to delete packed refs one by one, so update refs all at once.
+    let ref_edits = itertools::chain(
+        no_gc_refs_to_delete.into_iter().map(to_ref_deletion),
+        new_heads.iter().map(to_no_gc_ref_update),
+    );
+    git_repo
+        .edit_references(ref_edits)
+        .map_err(|err| BackendError::Other(err.into()))?;
+
+    Ok(())
+}
+
+/// Returns no-gc refs which are no longer needed to keep `new_heads` and were
+/// last updated before `keep_newer`.
+fn collect_no_gc_refs_to_delete(
+    git_repo: &gix::Repository,
+    new_heads: &HashSet<CommitId>,
+    keep_newer: SystemTime,
+) -> BackendResult<Vec<gix::refs::Reference>> {
     let mut no_gc_refs_to_keep_count: usize = 0;
     let mut no_gc_refs_to_delete: Vec<gix::refs::Reference> = Vec::new();
     let git_references = git_repo
@@ -789,17 +863,7 @@ fn recreate_no_gc_refs(
         no_gc_refs_to_delete_count = no_gc_refs_to_delete.len(),
         "collected reachable refs"
     );
-
-    // It's slow to delete packed refs one by one, so update refs all at once.
-    let ref_edits = itertools::chain(
-        no_gc_refs_to_delete.into_iter().map(to_ref_deletion),
-        new_heads.iter().map(to_no_gc_ref_update),
-    );
-    git_repo
-        .edit_references(ref_edits)
-        .map_err(|err| BackendError::Other(err.into()))?;
-
-    Ok(())
+    Ok(no_gc_refs_to_delete)
 }
 
 fn run_git_gc(git_dir: &Path) -> Result<(), GitGcError> {
@@ -1154,13 +1218,17 @@ impl Backend for GitBackend {
             ));
         }
         let git_commit_id = validate_git_object_id(id)?;
+        if let Some(commit) = self.commit_metadata_cache.get(id) {
+            return Ok(commit);
+        }
 
+        let mut is_shallow = false;
         let mut commit = {
             let locked_repo = self.lock_git_repo();
             let git_object = locked_repo
                 .find_object(git_commit_id)
                 .map_err(|err| map_not_found_err(err, id))?;
-            let is_shallow = locked_repo
+            is_shallow = locked_repo
                 .shallow_commits()
                 .ok()
                 .flatten()
@@ -1185,9 +1253,22 @@ impl Backend for GitBackend {
             let extras = table.get_value(id.as_bytes()).unwrap();
             deserialize_extras(&mut commit, extras);
         }
+        // Parents of shallow commits may change once more history is fetched.
+        if !is_shallow {
+            self.commit_metadata_cache.insert(id, &commit);
+        }
         Ok(commit)
     }
 
+    fn is_shallow_commit(&self, id: &CommitId) -> BackendResult<bool> {
+        let git_commit_id = validate_git_object_id(id)?;
+        let locked_repo = self.lock_git_repo();
+        let shallow_commits = locked_repo
+            .shallow_commits()
+            .map_err(|err| BackendError::Other(err.into()))?;
+        Ok(shallow_commits.is_some_and(|shallow| shallow.contains(&git_commit_id)))
+    }
+
     async fn write_commit(
         &self,
         mut contents: Commit,
@@ -1398,6 +1479,33 @@ impl Backend for GitBackend {
         git_repo.refs.force_refresh_packed_buffer().ok();
         Ok(())
     }
+
+    fn gc_dry_run(
+        &self,
+        index: &dyn Index,
+        keep_newer: SystemTime,
+    ) -> BackendResult<BackendGcStats> {
+        let git_repo = self.lock_git_repo();
+        let new_heads: HashSet<CommitId> = index
+            .all_heads_for_gc()
+            .map_err(|err| BackendError::Other(err.into()))?
+            .filter(|id| *id != self.root_commit_id)
+            .collect();
+        let expired_commit_ids = collect_no_gc_refs_to_delete(&git_repo, &new_heads, keep_newer)?
+            .iter()
+            .filter_map(|git_ref| git_ref.target.try_id())
+            .map(|oid| CommitId::from_bytes(oid.as_bytes()))
+            // Refs with bad names are recreated for the new heads.
+            .filter(|id| !new_heads.contains(id))
+            .unique()
+            .collect();
+        // Objects are pruned by "git gc", which doesn't report how much space
+        // would be reclaimed.
+        Ok(BackendGcStats {
+            expired_commit_ids,
+            reclaimable_bytes: None,
+        })
+    }
 }
 
 /// Write a tree conflict as a special tree with `.jjconflict-base-N` and
@@ -1743,6 +1851,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn read_commit_from_metadata_cache() {
+        let settings = user_settings();
+        let temp_dir = testutils::new_temp_dir();
+        let store_path = temp_dir.path();
+        let git_repo_path = temp_dir.path().join("git");
+        let git_repo = git2::Repository::init(git_repo_path).unwrap();
+
+        let signature = git2::Signature::now("Someone", "someone@example.com").unwrap();
+        let empty_tree_id = Oid::from_str("4b825dc642cb6eb9a060e54bf8d69288fbee4904").unwrap();
+        let empty_tree = git_repo.find_tree(empty_tree_id).unwrap();
+        let git_commit_id = git_repo
+            .commit(
+                Some("refs/heads/main"),
+                &signature,
+                &signature,
+                "git commit message",
+                &empty_tree,
+                &[],
+            )
+            .unwrap();
+        let commit_id = CommitId::from_bytes(git_commit_id.as_bytes());
+
+        let backend = GitBackend::init_external(&settings, store_path, git_repo.path()).unwrap();
+        let commit = backend.read_commit(&commit_id).block_on().unwrap();
+        // Flushes the pending cache entries
+        drop(backend);
+
+        let backend = GitBackend::load(&settings, store_path).unwrap();
+        assert_eq!(
+            backend.commit_metadata_cache.get(&commit_id),
+            Some(commit.clone())
+        );
+        assert_eq!(backend.read_commit(&commit_id).block_on().unwrap(), commit);
+    }
+
     #[test]
     fn read_signed_git_commit() {
         let settings = user_settings();
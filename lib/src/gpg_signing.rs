@@ -74,45 +74,110 @@ fn parse_gpg_verify_output(
         .ok_or(SignError::InvalidSignatureFormat)
 }
 
-fn run_sign_command(command: &mut Command, input: &[u8]) -> Result<Vec<u8>, GpgError> {
-    tracing::info!(?command, "running GPG signing command");
-    let process = command.stderr(Stdio::piped()).spawn()?;
-    let write_result = process.stdin.as_ref().unwrap().write_all(input);
-    let output = process.wait_with_output()?;
-    tracing::info!(?command, ?output.status, "GPG signing command exited");
-    if output.status.success() {
-        write_result?;
-        Ok(output.stdout)
-    } else {
-        Err(GpgError::Command {
-            exit_status: output.status,
-            stderr: String::from_utf8_lossy(&output.stderr).trim_end().into(),
-        })
-    }
+/// Error from running a GPG-compatible program.
+#[derive(Debug)]
+enum ProgramError {
+    Command {
+        exit_status: ExitStatus,
+        stderr: String,
+    },
+    Io(io::Error),
 }
 
-fn run_verify_command(command: &mut Command, input: &[u8]) -> Result<Vec<u8>, GpgError> {
-    tracing::info!(?command, "running GPG signing command");
-    let process = command.stderr(Stdio::null()).spawn()?;
-    let write_result = process.stdin.as_ref().unwrap().write_all(input);
-    let output = process.wait_with_output()?;
-    tracing::info!(?command, ?output.status, "GPG signing command exited");
-    match write_result {
-        Ok(()) => Ok(output.stdout),
-        // If the signature format is invalid, gpg will terminate early. Writing
-        // more input data will fail in that case.
-        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(vec![]),
-        Err(err) => Err(err.into()),
+impl From<io::Error> for ProgramError {
+    fn from(err: io::Error) -> Self {
+        ProgramError::Io(err)
     }
 }
 
+/// Runs a program accepting GPG-compatible arguments, which is shared by the
+/// OpenPGP and X.509 backends.
 #[derive(Debug)]
-pub struct GpgBackend {
+struct GpgProgram {
     program: OsString,
     allow_expired_keys: bool,
     extra_args: Vec<OsString>,
 }
 
+impl GpgProgram {
+    fn new(program: OsString, allow_expired_keys: bool) -> Self {
+        Self {
+            program,
+            allow_expired_keys,
+            extra_args: vec![],
+        }
+    }
+
+    fn create_command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .args(&self.extra_args);
+        command
+    }
+
+    fn sign(&self, data: &[u8], args: &[&str]) -> Result<Vec<u8>, ProgramError> {
+        let mut command = self.create_command();
+        command.args(args);
+        tracing::info!(?command, "running GPG signing command");
+        let process = command.stderr(Stdio::piped()).spawn()?;
+        let write_result = process.stdin.as_ref().unwrap().write_all(data);
+        let output = process.wait_with_output()?;
+        tracing::info!(?command, ?output.status, "GPG signing command exited");
+        if output.status.success() {
+            write_result?;
+            Ok(output.stdout)
+        } else {
+            Err(ProgramError::Command {
+                exit_status: output.status,
+                stderr: String::from_utf8_lossy(&output.stderr).trim_end().into(),
+            })
+        }
+    }
+
+    /// Verifies the `signature` and returns the `--status-fd=1` output.
+    fn verify(
+        &self,
+        data: &[u8],
+        signature: &[u8],
+        temp_file_prefix: &str,
+        args: &[&str],
+    ) -> Result<Vec<u8>, ProgramError> {
+        let mut signature_file = tempfile::Builder::new()
+            .prefix(temp_file_prefix)
+            .tempfile()?;
+        signature_file.write_all(signature)?;
+        signature_file.flush()?;
+
+        let sig_path = signature_file.into_temp_path();
+
+        let mut command = self.create_command();
+        command
+            .args(args)
+            .args(["--status-fd=1", "--verify"])
+            .arg(&sig_path)
+            .arg("-");
+        tracing::info!(?command, "running GPG signing command");
+        let process = command.stderr(Stdio::null()).spawn()?;
+        let write_result = process.stdin.as_ref().unwrap().write_all(data);
+        let output = process.wait_with_output()?;
+        tracing::info!(?command, ?output.status, "GPG signing command exited");
+        match write_result {
+            Ok(()) => Ok(output.stdout),
+            // If the signature format is invalid, gpg will terminate early. Writing
+            // more input data will fail in that case.
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(vec![]),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GpgBackend {
+    program: GpgProgram,
+}
+
 #[derive(Debug, Error)]
 pub enum GpgError {
     #[error("GPG failed with {exit_status}:\n{stderr}")]
@@ -124,6 +189,21 @@ pub enum GpgError {
     Io(#[from] std::io::Error),
 }
 
+impl From<ProgramError> for GpgError {
+    fn from(err: ProgramError) -> Self {
+        match err {
+            ProgramError::Command {
+                exit_status,
+                stderr,
+            } => GpgError::Command {
+                exit_status,
+                stderr,
+            },
+            ProgramError::Io(err) => GpgError::Io(err),
+        }
+    }
+}
+
 impl From<GpgError> for SignError {
     fn from(e: GpgError) -> Self {
         SignError::Backend(Box::new(e))
@@ -133,15 +213,13 @@ impl From<GpgError> for SignError {
 impl GpgBackend {
     pub fn new(program: OsString, allow_expired_keys: bool) -> Self {
         Self {
-            program,
-            allow_expired_keys,
-            extra_args: vec![],
+            program: GpgProgram::new(program, allow_expired_keys),
         }
     }
 
     /// Primarily intended for testing
     pub fn with_extra_args(mut self, args: &[OsString]) -> Self {
-        self.extra_args.extend_from_slice(args);
+        self.program.extra_args.extend_from_slice(args);
         self
     }
 
@@ -150,15 +228,6 @@ impl GpgBackend {
         let allow_expired_keys = settings.get_bool("signing.backends.gpg.allow-expired-keys")?;
         Ok(Self::new(program.into(), allow_expired_keys))
     }
-
-    fn create_command(&self) -> Command {
-        let mut command = Command::new(&self.program);
-        command
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .args(&self.extra_args);
-        command
-    }
 }
 
 impl SigningBackend for GpgBackend {
@@ -171,31 +240,111 @@ impl SigningBackend for GpgBackend {
     }
 
     fn sign(&self, data: &[u8], key: Option<&str>) -> Result<Vec<u8>, SignError> {
-        Ok(match key {
-            Some(key) => run_sign_command(self.create_command().args(["-abu", key]), data)?,
-            None => run_sign_command(self.create_command().arg("-ab"), data)?,
-        })
+        let signature = match key {
+            Some(key) => self.program.sign(data, &["-abu", key]),
+            None => self.program.sign(data, &["-ab"]),
+        };
+        Ok(signature.map_err(GpgError::from)?)
     }
 
     fn verify(&self, data: &[u8], signature: &[u8]) -> Result<Verification, SignError> {
-        let mut signature_file = tempfile::Builder::new()
-            .prefix(".jj-gpg-sig-tmp-")
-            .tempfile()
-            .map_err(GpgError::Io)?;
-        signature_file.write_all(signature).map_err(GpgError::Io)?;
-        signature_file.flush().map_err(GpgError::Io)?;
+        let output = self
+            .program
+            .verify(
+                data,
+                signature,
+                ".jj-gpg-sig-tmp-",
+                &["--keyid-format=long"],
+            )
+            .map_err(GpgError::from)?;
+        parse_gpg_verify_output(&output, self.program.allow_expired_keys)
+    }
+}
 
-        let sig_path = signature_file.into_temp_path();
+/// Signing backend for X.509 certificates, using `gpgsm` or a compatible
+/// program such as `smimesign`.
+#[derive(Debug)]
+pub struct X509Backend {
+    program: GpgProgram,
+}
+
+#[derive(Debug, Error)]
+pub enum X509Error {
+    #[error("X.509 signing program failed with {exit_status}:\n{stderr}")]
+    Command {
+        exit_status: ExitStatus,
+        stderr: String,
+    },
+    #[error("Failed to run X.509 signing program")]
+    Io(#[from] std::io::Error),
+}
 
-        let output = run_verify_command(
-            self.create_command()
-                .args(["--keyid-format=long", "--status-fd=1", "--verify"])
-                .arg(&sig_path)
-                .arg("-"),
-            data,
-        )?;
+impl From<ProgramError> for X509Error {
+    fn from(err: ProgramError) -> Self {
+        match err {
+            ProgramError::Command {
+                exit_status,
+                stderr,
+            } => X509Error::Command {
+                exit_status,
+                stderr,
+            },
+            ProgramError::Io(err) => X509Error::Io(err),
+        }
+    }
+}
 
-        parse_gpg_verify_output(&output, self.allow_expired_keys)
+impl From<X509Error> for SignError {
+    fn from(e: X509Error) -> Self {
+        SignError::Backend(Box::new(e))
+    }
+}
+
+impl X509Backend {
+    pub fn new(program: OsString, allow_expired_keys: bool) -> Self {
+        Self {
+            program: GpgProgram::new(program, allow_expired_keys),
+        }
+    }
+
+    /// Primarily intended for testing
+    pub fn with_extra_args(mut self, args: &[OsString]) -> Self {
+        self.program.extra_args.extend_from_slice(args);
+        self
+    }
+
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        let program = settings.get_string("signing.backends.x509.program")?;
+        let allow_expired_keys = settings.get_bool("signing.backends.x509.allow-expired-keys")?;
+        Ok(Self::new(program.into(), allow_expired_keys))
+    }
+}
+
+impl SigningBackend for X509Backend {
+    fn name(&self) -> &str {
+        "x509"
+    }
+
+    fn can_read(&self, signature: &[u8]) -> bool {
+        signature.starts_with(b"-----BEGIN SIGNED MESSAGE-----")
+    }
+
+    fn sign(&self, data: &[u8], key: Option<&str>) -> Result<Vec<u8>, SignError> {
+        // Same arguments as git uses for gpg.format=x509, which both gpgsm and
+        // smimesign understand.
+        let signature = match key {
+            Some(key) => self.program.sign(data, &["-bsau", key]),
+            None => self.program.sign(data, &["-bsa"]),
+        };
+        Ok(signature.map_err(X509Error::from)?)
+    }
+
+    fn verify(&self, data: &[u8], signature: &[u8]) -> Result<Verification, SignError> {
+        let output = self
+            .program
+            .verify(data, signature, ".jj-x509-sig-tmp-", &[])
+            .map_err(X509Error::from)?;
+        parse_gpg_verify_output(&output, self.program.allow_expired_keys)
     }
 }
 
@@ -248,4 +397,28 @@ mod tests {
             Verification::new(SigStatus::Bad, Some("123".into()), Some("456".into()))
         );
     }
+
+    #[test]
+    fn x509_can_read() {
+        let backend = X509Backend::new("gpgsm".into(), false);
+        assert!(backend.can_read(b"-----BEGIN SIGNED MESSAGE-----\nMIAGCSqGSIb3DQEHAqCAMIAC"));
+        assert!(!backend.can_read(b"-----BEGIN PGP SIGNATURE-----\n"));
+    }
+
+    #[test]
+    fn x509_verify_good_signature() {
+        // gpgsm reports the certificate fingerprint followed by the subject
+        assert_eq!(
+            parse_gpg_verify_output(
+                b"[GNUPG:] NEWSIG\n[GNUPG:] GOODSIG 1A2B3C4D /CN=Some One/O=Example",
+                false
+            )
+            .unwrap(),
+            Verification::new(
+                SigStatus::Good,
+                Some("1A2B3C4D".into()),
+                Some("/CN=Some One/O=Example".into())
+            )
+        );
+    }
 }
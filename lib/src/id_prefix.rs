@@ -29,6 +29,7 @@ use crate::hex_util;
 use crate::object_id::HexPrefix;
 use crate::object_id::ObjectId;
 use crate::object_id::PrefixResolution;
+use crate::op_store::OperationId;
 use crate::repo::Repo;
 use crate::revset::DefaultSymbolResolver;
 use crate::revset::RevsetEvaluationError;
@@ -108,6 +109,48 @@ impl IdIndexSourceEntry<ChangeId> for &'_ (CommitId, ChangeId) {
     }
 }
 
+impl<'a> IdIndexSource<u32> for &'a [OperationId] {
+    type Entry = &'a OperationId;
+
+    fn entry_at(&self, pointer: &u32) -> Self::Entry {
+        &self[*pointer as usize]
+    }
+}
+
+impl IdIndexSourceEntry<OperationId> for &'_ OperationId {
+    fn to_key(&self) -> OperationId {
+        (*self).clone()
+    }
+}
+
+/// Index to compute unambiguous prefixes of operation IDs.
+#[derive(Clone, Debug)]
+pub struct OperationIdPrefixIndex {
+    op_ids: Vec<OperationId>,
+    index: IdIndex<OperationId, u32, 4>,
+}
+
+impl OperationIdPrefixIndex {
+    /// Builds index of the given operation IDs, which are typically all
+    /// operations in the store.
+    pub fn new(op_ids: Vec<OperationId>) -> Self {
+        let mut builder = IdIndex::with_capacity(op_ids.len());
+        for (i, op_id) in op_ids.iter().enumerate() {
+            let i: u32 = i.try_into().unwrap();
+            builder.insert(op_id, i);
+        }
+        let index = builder.build();
+        OperationIdPrefixIndex { op_ids, index }
+    }
+
+    /// Returns the length of the shortest prefix of the `op_id` which doesn't
+    /// match any other operation in the index.
+    pub fn shortest_unique_prefix_len(&self, op_id: &OperationId) -> usize {
+        self.index
+            .shortest_unique_prefix_len(self.op_ids.as_slice(), op_id)
+    }
+}
+
 /// Manages configuration and cache of commit/change ID disambiguation index.
 #[derive(Default)]
 pub struct IdPrefixContext {
@@ -738,4 +781,34 @@ mod tests {
             1
         );
     }
+
+    #[test]
+    fn test_operation_id_prefix_index() {
+        let op_ids = vec![
+            OperationId::from_hex("00000000"),
+            OperationId::from_hex("12340000"),
+            OperationId::from_hex("12350000"),
+            OperationId::from_hex("ff000000"),
+        ];
+        let index = OperationIdPrefixIndex::new(op_ids);
+        assert_eq!(
+            index.shortest_unique_prefix_len(&OperationId::from_hex("00000000")),
+            1
+        );
+        assert_eq!(
+            index.shortest_unique_prefix_len(&OperationId::from_hex("12340000")),
+            4
+        );
+        assert_eq!(
+            index.shortest_unique_prefix_len(&OperationId::from_hex("ff000000")),
+            1
+        );
+
+        // No crash if empty
+        let index = OperationIdPrefixIndex::new(vec![]);
+        assert_eq!(
+            index.shortest_unique_prefix_len(&OperationId::from_hex("00000000")),
+            1
+        );
+    }
 }
@@ -114,6 +114,16 @@ pub trait Index: Send + Sync {
         &self,
     ) -> Result<Box<dyn Iterator<Item = CommitId> + '_>, AllHeadsForGcUnsupported>;
 
+    /// Returns the commits that were rewritten from `commit_id`, that is, the
+    /// indexed commits that have `commit_id` as one of their predecessors.
+    ///
+    /// The successors are returned in the order they were indexed. Hidden
+    /// commits are included. Indexes which don't record rewrites return an
+    /// empty list.
+    fn successors(&self, _commit_id: &CommitId) -> Vec<CommitId> {
+        vec![]
+    }
+
     /// Returns the subset of commit IDs in `candidates` which are not ancestors
     /// of other commits in `candidates`. If a commit id is duplicated in the
     /// `candidates` list it will appear at most once in the output.
@@ -31,10 +31,13 @@ pub mod content_hash;
 pub mod absorb;
 pub mod annotate;
 pub mod backend;
+pub mod bisect;
 pub mod commit;
 pub mod commit_builder;
+pub mod commit_metadata_cache;
 pub mod config;
 mod config_resolver;
+mod config_schema;
 pub mod conflicts;
 pub mod copies;
 pub mod dag_walk;
@@ -48,6 +51,7 @@ pub mod files;
 pub mod fileset;
 mod fileset_parser;
 pub mod fmt_util;
+pub mod fsck;
 pub mod fsmonitor;
 #[cfg(feature = "git")]
 pub mod git;
@@ -72,21 +76,28 @@ pub mod hex_util;
 pub mod id_prefix;
 pub mod index;
 pub mod local_backend;
+pub mod local_chunks;
+pub mod local_pack;
 pub mod local_working_copy;
 pub mod lock;
+pub mod maintenance;
 pub mod matchers;
 pub mod merge;
 pub mod merged_tree;
 pub mod object_id;
+pub mod object_store_backend;
 pub mod op_heads_store;
 pub mod op_store;
 pub mod op_walk;
 pub mod operation;
+pub mod opset;
+mod opset_parser;
 #[allow(missing_docs)]
 pub mod protos;
 pub mod refs;
 pub mod repo;
 pub mod repo_path;
+pub mod repro_bundle;
 pub mod revset;
 mod revset_parser;
 pub mod rewrite;
@@ -104,6 +115,7 @@ pub mod submodule_store;
 #[cfg(feature = "testing")]
 pub mod test_signing_backend;
 pub mod time_util;
+pub mod trailer;
 pub mod transaction;
 pub mod tree;
 pub mod tree_builder;
@@ -18,7 +18,11 @@ use std::any::Any;
 use std::fmt::Debug;
 use std::fs;
 use std::fs::File;
+use std::io;
+use std::io::Cursor;
 use std::io::Read;
+use std::io::Seek as _;
+use std::io::SeekFrom;
 use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
@@ -36,6 +40,7 @@ use tempfile::NamedTempFile;
 use crate::backend::make_root_commit;
 use crate::backend::Backend;
 use crate::backend::BackendError;
+use crate::backend::BackendGcStats;
 use crate::backend::BackendResult;
 use crate::backend::ChangeId;
 use crate::backend::Commit;
@@ -58,14 +63,27 @@ use crate::backend::TreeValue;
 use crate::content_hash::blake2b_hash;
 use crate::file_util::persist_content_addressed_temp_file;
 use crate::index::Index;
+use crate::local_chunks::ChunkStats;
+use crate::local_chunks::ChunkStore;
+use crate::local_pack::PackObjectKind;
+use crate::local_pack::PackSet;
+use crate::local_pack::PackStats;
 use crate::merge::MergeBuilder;
 use crate::object_id::ObjectId;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
 use crate::repo_path::RepoPathComponentBuf;
 
-const COMMIT_ID_LENGTH: usize = 64;
-const CHANGE_ID_LENGTH: usize = 16;
+pub(crate) const COMMIT_ID_LENGTH: usize = 64;
+pub(crate) const CHANGE_ID_LENGTH: usize = 16;
+pub(crate) const EMPTY_TREE_ID_HEX: &str = "482ae5a29fbe856c7272f2071b8b0f0359ee2d89ff392b8a900643fbd0836eccd067b8bf41909e206c90d45d6e7d8b6686b93ecaee5fe1a9060d87b672101310";
+
+/// Loose objects larger than this aren't packed.
+const PACK_OBJECT_SIZE_LIMIT: u64 = 1 << 20;
+/// Packs larger than this aren't merged by repack.
+const MERGED_PACK_SIZE_LIMIT: u64 = 64 << 20;
+/// Files larger than this are stored as compressed chunks.
+pub(crate) const CHUNKED_FILE_SIZE_THRESHOLD: u64 = 1 << 20;
 
 fn map_not_found_err(err: std::io::Error, id: &impl ObjectId) -> BackendError {
     if err.kind() == std::io::ErrorKind::NotFound {
@@ -87,12 +105,36 @@ fn to_other_err(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Bac
     BackendError::Other(err.into())
 }
 
+/// Statistics of [`LocalBackend::repack()`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RepackStats {
+    /// Number of loose objects moved into the new pack.
+    pub packed_loose_objects: usize,
+    /// Number of small old packs merged into the new pack.
+    pub merged_packs: usize,
+}
+
+/// Summary of the objects in a [`LocalBackend`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LocalStoreStats {
+    /// Number of loose object files.
+    pub loose_object_count: usize,
+    /// Total size of the loose object files in bytes.
+    pub loose_bytes: u64,
+    /// Objects stored in packs.
+    pub packs: PackStats,
+    /// Large files stored as compressed chunks.
+    pub chunks: ChunkStats,
+}
+
 #[derive(Debug)]
 pub struct LocalBackend {
     path: PathBuf,
     root_commit_id: CommitId,
     root_change_id: ChangeId,
     empty_tree_id: TreeId,
+    packs: PackSet,
+    chunks: ChunkStore,
 }
 
 impl LocalBackend {
@@ -106,6 +148,7 @@ impl LocalBackend {
         fs::create_dir(store_path.join("files")).unwrap();
         fs::create_dir(store_path.join("symlinks")).unwrap();
         fs::create_dir(store_path.join("conflicts")).unwrap();
+        fs::create_dir(store_path.join("packs")).unwrap();
         let backend = Self::load(store_path);
         let empty_tree_id = backend
             .write_tree(RepoPath::root(), &Tree::default())
@@ -118,14 +161,16 @@ impl LocalBackend {
     pub fn load(store_path: &Path) -> Self {
         let root_commit_id = CommitId::from_bytes(&[0; COMMIT_ID_LENGTH]);
         let root_change_id = ChangeId::from_bytes(&[0; CHANGE_ID_LENGTH]);
-        let empty_tree_id = TreeId::from_hex(
-            "482ae5a29fbe856c7272f2071b8b0f0359ee2d89ff392b8a900643fbd0836eccd067b8bf41909e206c90d45d6e7d8b6686b93ecaee5fe1a9060d87b672101310",
-        );
+        let empty_tree_id = TreeId::from_hex(EMPTY_TREE_ID_HEX);
         LocalBackend {
             path: store_path.to_path_buf(),
             root_commit_id,
             root_change_id,
             empty_tree_id,
+            // Stores created before packs were introduced don't have the
+            // directory. It will be created on the first repack.
+            packs: PackSet::load(&store_path.join("packs")),
+            chunks: ChunkStore::load(&store_path.join("chunks")),
         }
     }
 
@@ -148,6 +193,180 @@ impl LocalBackend {
     fn conflict_path(&self, id: &ConflictId) -> PathBuf {
         self.path.join("conflicts").join(id.hex())
     }
+
+    /// Reads object contents from the loose file at `path`, or from packs if
+    /// the loose file doesn't exist.
+    fn read_object_data(
+        &self,
+        kind: PackObjectKind,
+        path: &Path,
+        id: &impl ObjectId,
+    ) -> BackendResult<Vec<u8>> {
+        match fs::read(path) {
+            Ok(buf) => Ok(buf),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                match self.packs.read_object(kind, id.as_bytes()) {
+                    Ok(Some(buf)) => Ok(buf),
+                    Ok(None) => Err(map_not_found_err(err, id)),
+                    Err(err) => Err(map_not_found_err(err, id)),
+                }
+            }
+            Err(err) => Err(map_not_found_err(err, id)),
+        }
+    }
+
+    /// Returns the packs of this store.
+    pub fn packs(&self) -> &PackSet {
+        &self.packs
+    }
+
+    /// Returns the storage of large files.
+    pub fn chunks(&self) -> &ChunkStore {
+        &self.chunks
+    }
+
+    /// Returns summary of the objects in this store.
+    pub fn stats(&self) -> io::Result<LocalStoreStats> {
+        let mut stats = LocalStoreStats {
+            packs: self.packs.stats()?,
+            chunks: self.chunks.stats()?,
+            ..Default::default()
+        };
+        for kind in PackObjectKind::ALL {
+            let entries = match fs::read_dir(self.path.join(kind.dir_name())) {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+            for entry in entries {
+                stats.loose_object_count += 1;
+                stats.loose_bytes += entry?.metadata()?.len();
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Checks that the ids of all stored objects match their contents. Returns
+    /// the kinds and ids of the corrupt objects.
+    pub fn verify_objects(&self) -> io::Result<Vec<(PackObjectKind, Vec<u8>)>> {
+        let mut corrupt_objects = vec![];
+        for kind in PackObjectKind::ALL {
+            let entries = match fs::read_dir(self.path.join(kind.dir_name())) {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+            for entry in entries {
+                let entry = entry?;
+                let Some(id) = entry.file_name().to_str().and_then(|s| hex::decode(s).ok()) else {
+                    continue;
+                };
+                let data = match fs::read(entry.path()) {
+                    Ok(data) => data,
+                    // Packed by concurrent gc
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                    Err(err) => return Err(err),
+                };
+                if object_hash(kind, &data).as_ref() != Some(&id) {
+                    corrupt_objects.push((kind, id));
+                }
+            }
+        }
+        for object in self.packs.objects()? {
+            let (kind, id, data) = object?;
+            if object_hash(kind, &data).as_ref() != Some(&id) {
+                corrupt_objects.push((kind, id));
+            }
+        }
+        for id in self.chunks.file_ids()? {
+            let mut hasher = Blake2b512::new();
+            let is_valid = match self.chunks.read_file(&id) {
+                Ok(Some(mut reader)) => {
+                    io::copy(&mut reader, &mut hasher).is_ok() && hasher.finalize()[..] == id[..]
+                }
+                Ok(None) | Err(_) => false,
+            };
+            if !is_valid {
+                corrupt_objects.push((PackObjectKind::File, id));
+            }
+        }
+        Ok(corrupt_objects)
+    }
+
+    /// Moves small loose objects and small existing packs into a new pack.
+    ///
+    /// Large packs are kept as is so that repack doesn't have to rewrite the
+    /// whole store every time. Objects written concurrently remain loose, and
+    /// will be packed by the next repack.
+    pub fn repack(&self) -> io::Result<RepackStats> {
+        self.repack_with_limit(MERGED_PACK_SIZE_LIMIT)
+    }
+
+    fn repack_with_limit(&self, merged_pack_size_limit: u64) -> io::Result<RepackStats> {
+        let mut loose_objects = vec![];
+        for kind in PackObjectKind::ALL {
+            let dir = self.path.join(kind.dir_name());
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+            for entry in entries {
+                let entry = entry?;
+                let Some(id) = entry.file_name().to_str().and_then(|s| hex::decode(s).ok()) else {
+                    continue;
+                };
+                if entry.metadata()?.len() <= PACK_OBJECT_SIZE_LIMIT {
+                    loose_objects.push((kind, id, entry.path()));
+                }
+            }
+        }
+        let old_pack_names: Vec<String> = self
+            .packs
+            .pack_sizes()?
+            .into_iter()
+            .filter(|(_, size)| *size <= merged_pack_size_limit)
+            .map(|(name, _)| name)
+            .collect();
+        if loose_objects.is_empty() && old_pack_names.len() <= 1 {
+            return Ok(RepackStats::default());
+        }
+
+        fs::create_dir_all(self.packs.dir())?;
+        let mut packed_loose_objects = 0;
+        let loose_iter = loose_objects
+            .iter()
+            .filter_map(|(kind, id, path)| match fs::read(path) {
+                Ok(data) => {
+                    packed_loose_objects += 1;
+                    Some(Ok((*kind, id.clone(), data)))
+                }
+                // Packed and removed by concurrent repack
+                Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+                Err(err) => Some(Err(err)),
+            });
+        let new_pack_name = self
+            .packs
+            .write_pack(self.packs.objects_in(&old_pack_names)?.chain(loose_iter))?;
+        // Only the packs merged above can be removed. Packs written by
+        // concurrent repack may contain objects we haven't seen.
+        let merged_pack_names: Vec<String> = old_pack_names
+            .into_iter()
+            .filter(|name| Some(name) != new_pack_name.as_ref())
+            .collect();
+        let merged_packs = self.packs.remove_packs(&merged_pack_names)?;
+        for (_, _, path) in &loose_objects {
+            match fs::remove_file(path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(RepackStats {
+            packed_loose_objects,
+            merged_packs,
+        })
+    }
 }
 
 #[async_trait]
@@ -186,8 +405,21 @@ impl Backend for LocalBackend {
 
     async fn read_file(&self, _path: &RepoPath, id: &FileId) -> BackendResult<Box<dyn Read>> {
         let path = self.file_path(id);
-        let file = File::open(path).map_err(|err| map_not_found_err(err, id))?;
-        Ok(Box::new(file))
+        match File::open(&path) {
+            Ok(file) => Ok(Box::new(file)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                if let Some(reader) = self
+                    .chunks
+                    .read_file(id.as_bytes())
+                    .map_err(|err| map_not_found_err(err, id))?
+                {
+                    return Ok(Box::new(reader));
+                }
+                let buf = self.read_object_data(PackObjectKind::File, &path, id)?;
+                Ok(Box::new(Cursor::new(buf)))
+            }
+            Err(err) => Err(map_not_found_err(err, id)),
+        }
     }
 
     async fn write_file(
@@ -199,6 +431,7 @@ impl Backend for LocalBackend {
         let mut file = temp_file.as_file();
         let mut hasher = Blake2b512::new();
         let mut buff: Vec<u8> = vec![0; 1 << 14];
+        let mut size: u64 = 0;
         loop {
             let bytes_read = contents.read(&mut buff).map_err(to_other_err)?;
             if bytes_read == 0 {
@@ -207,18 +440,33 @@ impl Backend for LocalBackend {
             let bytes = &buff[..bytes_read];
             file.write_all(bytes).map_err(to_other_err)?;
             hasher.update(bytes);
+            size += bytes_read as u64;
         }
         file.flush().map_err(to_other_err)?;
         let id = FileId::new(hasher.finalize().to_vec());
 
-        persist_content_addressed_temp_file(temp_file, self.file_path(&id))
-            .map_err(to_other_err)?;
+        if size > CHUNKED_FILE_SIZE_THRESHOLD {
+            if !self.chunks.contains(id.as_bytes()) {
+                file.seek(SeekFrom::Start(0)).map_err(to_other_err)?;
+                self.chunks
+                    .write_file(id.as_bytes(), file)
+                    .map_err(to_other_err)?;
+            }
+        } else {
+            persist_content_addressed_temp_file(temp_file, self.file_path(&id))
+                .map_err(to_other_err)?;
+        }
         Ok(id)
     }
 
     async fn read_symlink(&self, _path: &RepoPath, id: &SymlinkId) -> BackendResult<String> {
         let path = self.symlink_path(id);
-        let target = fs::read_to_string(path).map_err(|err| map_not_found_err(err, id))?;
+        let buf = self.read_object_data(PackObjectKind::Symlink, &path, id)?;
+        let target = String::from_utf8(buf).map_err(|err| BackendError::ReadObject {
+            object_type: id.object_type(),
+            hash: id.hex(),
+            source: Box::new(err),
+        })?;
         Ok(target)
     }
 
@@ -238,7 +486,7 @@ impl Backend for LocalBackend {
 
     async fn read_tree(&self, _path: &RepoPath, id: &TreeId) -> BackendResult<Tree> {
         let path = self.tree_path(id);
-        let buf = fs::read(path).map_err(|err| map_not_found_err(err, id))?;
+        let buf = self.read_object_data(PackObjectKind::Tree, &path, id)?;
 
         let proto = crate::protos::local_store::Tree::decode(&*buf).map_err(to_other_err)?;
         Ok(tree_from_proto(proto))
@@ -262,7 +510,7 @@ impl Backend for LocalBackend {
 
     fn read_conflict(&self, _path: &RepoPath, id: &ConflictId) -> BackendResult<Conflict> {
         let path = self.conflict_path(id);
-        let buf = fs::read(path).map_err(|err| map_not_found_err(err, id))?;
+        let buf = self.read_object_data(PackObjectKind::Conflict, &path, id)?;
 
         let proto = crate::protos::local_store::Conflict::decode(&*buf).map_err(to_other_err)?;
         Ok(conflict_from_proto(proto))
@@ -293,7 +541,7 @@ impl Backend for LocalBackend {
         }
 
         let path = self.commit_path(id);
-        let buf = fs::read(path).map_err(|err| map_not_found_err(err, id))?;
+        let buf = self.read_object_data(PackObjectKind::Commit, &path, id)?;
 
         let proto = crate::protos::local_store::Commit::decode(&*buf).map_err(to_other_err)?;
         Ok(commit_from_proto(proto))
@@ -343,8 +591,43 @@ impl Backend for LocalBackend {
     }
 
     fn gc(&self, _index: &dyn Index, _keep_newer: SystemTime) -> BackendResult<()> {
+        self.repack().map_err(to_other_err)?;
         Ok(())
     }
+
+    fn gc_dry_run(
+        &self,
+        _index: &dyn Index,
+        _keep_newer: SystemTime,
+    ) -> BackendResult<BackendGcStats> {
+        // Nothing is removed. Loose objects and packs are merged into a new
+        // pack, which may or may not save space.
+        Ok(BackendGcStats {
+            expired_commit_ids: vec![],
+            reclaimable_bytes: None,
+        })
+    }
+}
+
+/// Computes the id of an object from its stored contents. Returns `None` if
+/// the contents can't be decoded.
+fn object_hash(kind: PackObjectKind, data: &[u8]) -> Option<Vec<u8>> {
+    let hash = match kind {
+        PackObjectKind::File | PackObjectKind::Symlink => Blake2b512::digest(data),
+        PackObjectKind::Commit => {
+            let proto = crate::protos::local_store::Commit::decode(data).ok()?;
+            blake2b_hash(&commit_from_proto(proto))
+        }
+        PackObjectKind::Tree => {
+            let proto = crate::protos::local_store::Tree::decode(data).ok()?;
+            blake2b_hash(&tree_from_proto(proto))
+        }
+        PackObjectKind::Conflict => {
+            let proto = crate::protos::local_store::Conflict::decode(data).ok()?;
+            blake2b_hash(&conflict_from_proto(proto))
+        }
+    };
+    Some(hash.to_vec())
 }
 
 #[allow(unknown_lints)] // XXX FIXME (aseipp): nightly bogons; re-test this occasionally
@@ -373,7 +656,7 @@ pub fn commit_to_proto(commit: &Commit) -> crate::protos::local_store::Commit {
     proto
 }
 
-fn commit_from_proto(mut proto: crate::protos::local_store::Commit) -> Commit {
+pub(crate) fn commit_from_proto(mut proto: crate::protos::local_store::Commit) -> Commit {
     // Note how .take() sets the secure_sig field to None before we encode the data.
     // Needs to be done first since proto is partially moved a bunch below
     let secure_sig = proto.secure_sig.take().map(|sig| SecureSig {
@@ -403,7 +686,7 @@ fn commit_from_proto(mut proto: crate::protos::local_store::Commit) -> Commit {
     }
 }
 
-fn tree_to_proto(tree: &Tree) -> crate::protos::local_store::Tree {
+pub(crate) fn tree_to_proto(tree: &Tree) -> crate::protos::local_store::Tree {
     let mut proto = crate::protos::local_store::Tree::default();
     for entry in tree.entries() {
         proto.entries.push(crate::protos::local_store::tree::Entry {
@@ -414,7 +697,7 @@ fn tree_to_proto(tree: &Tree) -> crate::protos::local_store::Tree {
     proto
 }
 
-fn tree_from_proto(proto: crate::protos::local_store::Tree) -> Tree {
+pub(crate) fn tree_from_proto(proto: crate::protos::local_store::Tree) -> Tree {
     let mut tree = Tree::default();
     for proto_entry in proto.entries {
         let value = tree_value_from_proto(proto_entry.value.unwrap());
@@ -499,7 +782,7 @@ fn signature_from_proto(proto: crate::protos::local_store::commit::Signature) ->
     }
 }
 
-fn conflict_to_proto(conflict: &Conflict) -> crate::protos::local_store::Conflict {
+pub(crate) fn conflict_to_proto(conflict: &Conflict) -> crate::protos::local_store::Conflict {
     let mut proto = crate::protos::local_store::Conflict::default();
     for term in &conflict.removes {
         proto.removes.push(conflict_term_to_proto(term));
@@ -510,7 +793,7 @@ fn conflict_to_proto(conflict: &Conflict) -> crate::protos::local_store::Conflic
     proto
 }
 
-fn conflict_from_proto(proto: crate::protos::local_store::Conflict) -> Conflict {
+pub(crate) fn conflict_from_proto(proto: crate::protos::local_store::Conflict) -> Conflict {
     let mut conflict = Conflict::default();
     for term in proto.removes {
         conflict.removes.push(conflict_term_from_proto(term));
@@ -594,6 +877,240 @@ mod tests {
         assert_eq!(root_merge_commit, commit);
     }
 
+    #[test]
+    fn repack_objects() {
+        let temp_dir = testutils::new_temp_dir();
+        let store_path = temp_dir.path();
+
+        let backend = LocalBackend::init(store_path);
+        let file_path = RepoPath::from_internal_string("file");
+        let file_id = backend
+            .write_file(file_path, &mut &b"content"[..])
+            .block_on()
+            .unwrap();
+        let symlink_id = backend
+            .write_symlink(file_path, "target")
+            .block_on()
+            .unwrap();
+        let commit = Commit {
+            parents: vec![backend.root_commit_id().clone()],
+            predecessors: vec![],
+            root_tree: MergedTreeId::resolved(backend.empty_tree_id().clone()),
+            change_id: ChangeId::from_hex("abc123"),
+            description: "".to_string(),
+            author: create_signature(),
+            committer: create_signature(),
+            secure_sig: None,
+        };
+        let (commit_id, _) = backend
+            .write_commit(commit.clone(), None)
+            .block_on()
+            .unwrap();
+
+        // All loose objects (including the empty tree) are packed
+        let stats = backend.repack().unwrap();
+        assert_eq!(stats.packed_loose_objects, 4);
+        assert_eq!(stats.merged_packs, 0);
+        assert!(!backend.file_path(&file_id).exists());
+        assert!(!backend.commit_path(&commit_id).exists());
+        assert_eq!(backend.packs().stats().unwrap().pack_count, 1);
+
+        // Objects can be read from the pack, even by another instance
+        let backend = LocalBackend::load(store_path);
+        let mut content = vec![];
+        backend
+            .read_file(file_path, &file_id)
+            .block_on()
+            .unwrap()
+            .read_to_end(&mut content)
+            .unwrap();
+        assert_eq!(content, b"content");
+        assert_eq!(
+            backend
+                .read_symlink(file_path, &symlink_id)
+                .block_on()
+                .unwrap(),
+            "target"
+        );
+        assert_eq!(backend.read_commit(&commit_id).block_on().unwrap(), commit);
+        let empty_tree_id = backend.empty_tree_id().clone();
+        assert!(backend
+            .read_tree(RepoPath::root(), &empty_tree_id)
+            .block_on()
+            .is_ok());
+
+        // Nothing to do
+        assert_eq!(backend.repack().unwrap(), RepackStats::default());
+
+        // New loose objects and the existing pack are merged
+        let file_id2 = backend
+            .write_file(file_path, &mut &b"content 2"[..])
+            .block_on()
+            .unwrap();
+        let stats = backend.repack().unwrap();
+        assert_eq!(stats.packed_loose_objects, 1);
+        assert_eq!(stats.merged_packs, 1);
+        assert_eq!(backend.packs().stats().unwrap().pack_count, 1);
+        assert!(backend.read_file(file_path, &file_id).block_on().is_ok());
+        assert!(backend.read_file(file_path, &file_id2).block_on().is_ok());
+
+        // Missing objects are still reported as such
+        let missing_id = FileId::new(vec![0; 64]);
+        assert_matches!(
+            backend.read_file(file_path, &missing_id).block_on(),
+            Err(BackendError::ObjectNotFound { .. })
+        );
+    }
+
+    #[test]
+    fn repack_keeps_large_packs() {
+        let temp_dir = testutils::new_temp_dir();
+        let store_path = temp_dir.path();
+
+        let backend = LocalBackend::init(store_path);
+        let file_path = RepoPath::from_internal_string("file");
+        let file_id1 = backend
+            .write_file(file_path, &mut &b"content 1"[..])
+            .block_on()
+            .unwrap();
+        backend.repack().unwrap();
+        assert_eq!(backend.packs().stats().unwrap().pack_count, 1);
+
+        // Packs larger than the limit aren't rewritten
+        let file_id2 = backend
+            .write_file(file_path, &mut &b"content 2"[..])
+            .block_on()
+            .unwrap();
+        let stats = backend.repack_with_limit(0).unwrap();
+        assert_eq!(stats.packed_loose_objects, 1);
+        assert_eq!(stats.merged_packs, 0);
+        assert_eq!(backend.packs().stats().unwrap().pack_count, 2);
+
+        // Small packs are merged
+        let stats = backend.repack_with_limit(u64::MAX).unwrap();
+        assert_eq!(stats.packed_loose_objects, 0);
+        assert_eq!(stats.merged_packs, 2);
+        assert_eq!(backend.packs().stats().unwrap().pack_count, 1);
+        assert!(backend.read_file(file_path, &file_id1).block_on().is_ok());
+        assert!(backend.read_file(file_path, &file_id2).block_on().is_ok());
+    }
+
+    #[test]
+    fn repack_with_concurrent_writes() {
+        let temp_dir = testutils::new_temp_dir();
+        let store_path = temp_dir.path();
+
+        let backend = LocalBackend::init(store_path);
+        let file_path = RepoPath::from_internal_string("file");
+        let file_ids = std::thread::scope(|s| {
+            let writer = s.spawn(|| {
+                let backend = LocalBackend::load(store_path);
+                (0..100)
+                    .map(|i| {
+                        let content = format!("content {i}");
+                        backend
+                            .write_file(file_path, &mut content.as_bytes())
+                            .block_on()
+                            .unwrap()
+                    })
+                    .collect::<Vec<_>>()
+            });
+            // Repack concurrently, both from this instance and another one.
+            let other_backend = LocalBackend::load(store_path);
+            while !writer.is_finished() {
+                backend.repack().unwrap();
+                other_backend.repack().unwrap();
+            }
+            writer.join().unwrap()
+        });
+        backend.repack().unwrap();
+
+        let backend = LocalBackend::load(store_path);
+        for (i, file_id) in file_ids.iter().enumerate() {
+            let mut content = vec![];
+            backend
+                .read_file(file_path, file_id)
+                .block_on()
+                .unwrap()
+                .read_to_end(&mut content)
+                .unwrap();
+            assert_eq!(content, format!("content {i}").as_bytes());
+        }
+    }
+
+    #[test]
+    fn write_large_file_as_chunks() {
+        let temp_dir = testutils::new_temp_dir();
+        let store_path = temp_dir.path();
+
+        let backend = LocalBackend::init(store_path);
+        let file_path = RepoPath::from_internal_string("file");
+        let content = b"large file\n".repeat(1 << 18);
+        let file_id = backend
+            .write_file(file_path, &mut content.as_slice())
+            .block_on()
+            .unwrap();
+        assert!(!backend.file_path(&file_id).exists());
+        let stats = backend.stats().unwrap();
+        assert_eq!(stats.chunks.file_count, 1);
+        assert_eq!(stats.chunks.logical_bytes, content.len() as u64);
+        assert!(stats.chunks.stored_bytes < stats.chunks.logical_bytes);
+
+        let mut read_content = vec![];
+        backend
+            .read_file(file_path, &file_id)
+            .block_on()
+            .unwrap()
+            .read_to_end(&mut read_content)
+            .unwrap();
+        assert_eq!(read_content, content);
+
+        // Writing the same content again doesn't add chunks
+        let file_id2 = backend
+            .write_file(file_path, &mut content.as_slice())
+            .block_on()
+            .unwrap();
+        assert_eq!(file_id2, file_id);
+        assert_eq!(backend.stats().unwrap(), stats);
+    }
+
+    #[test]
+    fn verify_objects() {
+        let temp_dir = testutils::new_temp_dir();
+        let store_path = temp_dir.path();
+
+        let backend = LocalBackend::init(store_path);
+        let file_path = RepoPath::from_internal_string("file");
+        let file_id = backend
+            .write_file(file_path, &mut &b"content"[..])
+            .block_on()
+            .unwrap();
+        let commit = Commit {
+            parents: vec![backend.root_commit_id().clone()],
+            predecessors: vec![],
+            root_tree: MergedTreeId::resolved(backend.empty_tree_id().clone()),
+            change_id: ChangeId::from_hex("abc123"),
+            description: "".to_string(),
+            author: create_signature(),
+            committer: create_signature(),
+            secure_sig: None,
+        };
+        backend.write_commit(commit, None).block_on().unwrap();
+        assert_eq!(backend.verify_objects().unwrap(), vec![]);
+
+        // Corrupt objects are reported whether they're loose or packed
+        fs::write(backend.file_path(&file_id), b"corrupt").unwrap();
+        assert_eq!(
+            backend.verify_objects().unwrap(),
+            vec![(PackObjectKind::File, file_id.to_bytes())]
+        );
+        backend.repack().unwrap();
+        assert_eq!(
+            backend.verify_objects().unwrap(),
+            vec![(PackObjectKind::File, file_id.to_bytes())]
+        );
+    }
+
     fn create_signature() -> Signature {
         Signature {
             name: "Someone".to_string(),
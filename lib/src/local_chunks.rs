@@ -0,0 +1,324 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Chunked storage of large files used by the local (native) backend.
+//!
+//! Large file contents are split into content-defined chunks, so that a small
+//! edit to a big file only produces a few new chunks. Each chunk is stored
+//! zstd-compressed in `data/<hash>`, where `<hash>` is the BLAKE2b hash of the
+//! uncompressed chunk. The list of chunks making up a file is stored in
+//! `manifests/<file id>`.
+//!
+//! The chunking and the manifest format are shared with the object-store
+//! backend.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::io::Cursor;
+use std::io::Read;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use blake2::Blake2b512;
+use blake2::Digest;
+use fastcdc::v2020::StreamCDC;
+use tempfile::NamedTempFile;
+
+use crate::file_util::persist_content_addressed_temp_file;
+
+const MANIFEST_MAGIC: &[u8; 8] = b"JJCHNK01";
+const CHUNK_HASH_LENGTH: usize = 64;
+const MIN_CHUNK_SIZE: u32 = 64 * 1024;
+const AVG_CHUNK_SIZE: u32 = 256 * 1024;
+const MAX_CHUNK_SIZE: u32 = 1024 * 1024;
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Summary of chunked files.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ChunkStats {
+    /// Number of files stored as chunks.
+    pub file_count: usize,
+    /// Total size of the chunked files' contents in bytes.
+    pub logical_bytes: u64,
+    /// Number of distinct chunks.
+    pub chunk_count: usize,
+    /// Total size of the compressed chunks on disk in bytes.
+    pub stored_bytes: u64,
+}
+
+/// Chunk of a file listed in a manifest.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct ManifestEntry {
+    /// BLAKE2b hash of the uncompressed chunk.
+    pub hash: Vec<u8>,
+    /// Size of the uncompressed chunk in bytes.
+    pub len: u64,
+}
+
+fn invalid_manifest() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "corrupt chunk manifest")
+}
+
+pub(crate) fn decode_manifest(buf: &[u8]) -> io::Result<Vec<ManifestEntry>> {
+    let rest = buf
+        .strip_prefix(MANIFEST_MAGIC)
+        .ok_or_else(invalid_manifest)?;
+    let entry_size = CHUNK_HASH_LENGTH + 8;
+    if rest.len() % entry_size != 0 {
+        return Err(invalid_manifest());
+    }
+    Ok(rest
+        .chunks_exact(entry_size)
+        .map(|entry| {
+            let (hash, len) = entry.split_at(CHUNK_HASH_LENGTH);
+            ManifestEntry {
+                hash: hash.to_vec(),
+                len: u64::from_le_bytes(len.try_into().unwrap()),
+            }
+        })
+        .collect())
+}
+
+pub(crate) fn encode_manifest(entries: &[ManifestEntry]) -> Vec<u8> {
+    let mut buf = MANIFEST_MAGIC.to_vec();
+    for entry in entries {
+        buf.extend(&entry.hash);
+        buf.extend(entry.len.to_le_bytes());
+    }
+    buf
+}
+
+/// Splits `contents` into content-defined chunks.
+pub(crate) fn split_into_chunks(contents: impl Read) -> impl Iterator<Item = io::Result<Vec<u8>>> {
+    StreamCDC::new(contents, MIN_CHUNK_SIZE, AVG_CHUNK_SIZE, MAX_CHUNK_SIZE)
+        .map(|chunk| Ok(chunk.map_err(io::Error::from)?.data))
+}
+
+pub(crate) fn compress_chunk(data: &[u8]) -> io::Result<Vec<u8>> {
+    zstd::encode_all(data, COMPRESSION_LEVEL)
+}
+
+pub(crate) fn decompress_chunk(compressed: &[u8]) -> io::Result<Vec<u8>> {
+    zstd::decode_all(compressed)
+}
+
+/// Chunk storage in a directory.
+#[derive(Debug)]
+pub struct ChunkStore {
+    dir: PathBuf,
+}
+
+impl ChunkStore {
+    /// Loads the chunk store in the given directory. The directory will be
+    /// created when the first file is written.
+    pub fn load(dir: &Path) -> Self {
+        ChunkStore {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    fn manifest_path(&self, id: &[u8]) -> PathBuf {
+        self.dir.join("manifests").join(hex::encode(id))
+    }
+
+    fn chunk_path(&self, hash: &[u8]) -> PathBuf {
+        self.dir.join("data").join(hex::encode(hash))
+    }
+
+    /// Returns true if the file `id` is stored as chunks.
+    pub fn contains(&self, id: &[u8]) -> bool {
+        self.manifest_path(id).exists()
+    }
+
+    /// Splits `contents` into chunks and stores them as file `id`. The caller
+    /// is responsible for computing `id` from the contents.
+    pub fn write_file(&self, id: &[u8], contents: impl Read) -> io::Result<()> {
+        fs::create_dir_all(self.dir.join("manifests"))?;
+        fs::create_dir_all(self.dir.join("data"))?;
+        let mut entries = vec![];
+        for chunk in split_into_chunks(contents) {
+            let chunk = chunk?;
+            let hash = Blake2b512::digest(&chunk).to_vec();
+            let chunk_path = self.chunk_path(&hash);
+            if !chunk_path.exists() {
+                let compressed = compress_chunk(&chunk)?;
+                let mut temp_file = NamedTempFile::new_in(&self.dir)?;
+                temp_file.write_all(&compressed)?;
+                persist_content_addressed_temp_file(temp_file, chunk_path)?;
+            }
+            entries.push(ManifestEntry {
+                hash,
+                len: chunk.len() as u64,
+            });
+        }
+        // The manifest is written last, so the file becomes visible only when
+        // all of its chunks exist.
+        let mut temp_file = NamedTempFile::new_in(&self.dir)?;
+        temp_file.write_all(&encode_manifest(&entries))?;
+        persist_content_addressed_temp_file(temp_file, self.manifest_path(id))?;
+        Ok(())
+    }
+
+    /// Opens the file `id` for reading. Returns `None` if the file isn't
+    /// stored as chunks.
+    pub fn read_file(&self, id: &[u8]) -> io::Result<Option<ChunkedFileReader>> {
+        let buf = match fs::read(self.manifest_path(id)) {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let chunk_paths = decode_manifest(&buf)?
+            .iter()
+            .map(|entry| self.chunk_path(&entry.hash))
+            .collect();
+        Ok(Some(ChunkedFileReader {
+            chunk_paths,
+            next_chunk: 0,
+            current: Cursor::new(vec![]),
+        }))
+    }
+
+    /// Returns the ids of all chunked files.
+    pub fn file_ids(&self) -> io::Result<Vec<Vec<u8>>> {
+        let entries = match fs::read_dir(self.dir.join("manifests")) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err),
+        };
+        let mut ids = vec![];
+        for entry in entries {
+            if let Some(id) = entry?
+                .file_name()
+                .to_str()
+                .and_then(|s| hex::decode(s).ok())
+            {
+                ids.push(id);
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Returns summary of the chunked files.
+    pub fn stats(&self) -> io::Result<ChunkStats> {
+        let mut stats = ChunkStats::default();
+        let manifests = match fs::read_dir(self.dir.join("manifests")) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(stats),
+            Err(err) => return Err(err),
+        };
+        let mut chunk_hashes = HashSet::new();
+        for entry in manifests {
+            let entries = decode_manifest(&fs::read(entry?.path())?)?;
+            stats.file_count += 1;
+            stats.logical_bytes += entries.iter().map(|entry| entry.len).sum::<u64>();
+            chunk_hashes.extend(entries.into_iter().map(|entry| entry.hash));
+        }
+        stats.chunk_count = chunk_hashes.len();
+        for hash in &chunk_hashes {
+            stats.stored_bytes += fs::metadata(self.chunk_path(hash))?.len();
+        }
+        Ok(stats)
+    }
+}
+
+/// Reader of a chunked file. Chunks are decompressed on demand.
+#[derive(Debug)]
+pub struct ChunkedFileReader {
+    chunk_paths: Vec<PathBuf>,
+    next_chunk: usize,
+    current: Cursor<Vec<u8>>,
+}
+
+impl Read for ChunkedFileReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let n = self.current.read(buf)?;
+            if n > 0 || buf.is_empty() {
+                return Ok(n);
+            }
+            let Some(path) = self.chunk_paths.get(self.next_chunk) else {
+                return Ok(0);
+            };
+            let compressed = fs::read(path)?;
+            self.current = Cursor::new(decompress_chunk(&compressed)?);
+            self.next_chunk += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng as _;
+    use rand::SeedableRng as _;
+
+    use super::*;
+
+    fn random_bytes(seed: u64, len: usize) -> Vec<u8> {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(seed);
+        let mut buf = vec![0; len];
+        rng.fill(buf.as_mut_slice());
+        buf
+    }
+
+    fn read_all(store: &ChunkStore, id: &[u8]) -> Vec<u8> {
+        let mut buf = vec![];
+        store
+            .read_file(id)
+            .unwrap()
+            .unwrap()
+            .read_to_end(&mut buf)
+            .unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_write_and_read_chunked_file() {
+        let temp_dir = testutils::new_temp_dir();
+        let store = ChunkStore::load(&temp_dir.path().join("chunks"));
+        assert_eq!(store.stats().unwrap(), ChunkStats::default());
+        assert!(store.read_file(b"missing").unwrap().is_none());
+
+        let content1 = random_bytes(0, 4 * 1024 * 1024);
+        store.write_file(b"file1", content1.as_slice()).unwrap();
+        assert!(store.contains(b"file1"));
+        assert_eq!(read_all(&store, b"file1"), content1);
+        let stats1 = store.stats().unwrap();
+        assert_eq!(stats1.file_count, 1);
+        assert_eq!(stats1.logical_bytes, content1.len() as u64);
+        assert!(stats1.chunk_count > 1);
+
+        // A small edit in the middle shares most chunks with the original file
+        let mut content2 = content1.clone();
+        content2[2 * 1024 * 1024] ^= 1;
+        store.write_file(b"file2", content2.as_slice()).unwrap();
+        assert_eq!(read_all(&store, b"file2"), content2);
+        let stats2 = store.stats().unwrap();
+        assert_eq!(stats2.file_count, 2);
+        assert_eq!(stats2.logical_bytes, 2 * content1.len() as u64);
+        assert!(stats2.chunk_count <= stats1.chunk_count + 2);
+    }
+
+    #[test]
+    fn test_compressed_chunks() {
+        let temp_dir = testutils::new_temp_dir();
+        let store = ChunkStore::load(&temp_dir.path().join("chunks"));
+        let content = b"0123456789abcdef".repeat(256 * 1024);
+        store.write_file(b"file", content.as_slice()).unwrap();
+        assert_eq!(read_all(&store, b"file"), content);
+        let stats = store.stats().unwrap();
+        assert!(stats.stored_bytes < stats.logical_bytes / 10);
+    }
+}
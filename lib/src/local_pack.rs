@@ -0,0 +1,534 @@
+// Copyright 2025 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Packfiles used by the local (native) backend.
+//!
+//! Small objects are initially written as loose files, and are later packed
+//! into append-only packfiles by garbage collection. A pack consists of two
+//! files:
+//!
+//! * `<name>.pack`: concatenated object contents prefixed by a magic header.
+//! * `<name>.idx`: sorted table of `(kind, id, offset, length)` entries.
+//!
+//! Pack files are never modified once written. The index file is written after
+//! the pack file, so a pack is visible to readers only when it is complete.
+
+use std::collections::HashSet;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use blake2::Blake2b512;
+use blake2::Digest;
+use tempfile::NamedTempFile;
+
+use crate::file_util::persist_content_addressed_temp_file;
+
+const PACK_MAGIC: &[u8; 8] = b"JJPACK01";
+const INDEX_MAGIC: &[u8; 8] = b"JJPIDX01";
+const PACK_FILE_EXT: &str = "pack";
+const INDEX_FILE_EXT: &str = "idx";
+
+/// Type of object stored in a pack.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum PackObjectKind {
+    /// Commit object.
+    Commit,
+    /// Tree object.
+    Tree,
+    /// File contents.
+    File,
+    /// Symlink target.
+    Symlink,
+    /// Legacy conflict object.
+    Conflict,
+}
+
+impl PackObjectKind {
+    /// All object kinds.
+    pub const ALL: [Self; 5] = [
+        Self::Commit,
+        Self::Tree,
+        Self::File,
+        Self::Symlink,
+        Self::Conflict,
+    ];
+
+    fn to_byte(self) -> u8 {
+        match self {
+            Self::Commit => 0,
+            Self::Tree => 1,
+            Self::File => 2,
+            Self::Symlink => 3,
+            Self::Conflict => 4,
+        }
+    }
+
+    fn from_byte(b: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.to_byte() == b)
+    }
+
+    /// Name of the directory where loose objects of this kind are stored.
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            Self::Commit => "commits",
+            Self::Tree => "trees",
+            Self::File => "files",
+            Self::Symlink => "symlinks",
+            Self::Conflict => "conflicts",
+        }
+    }
+}
+
+/// Object kind, id, and contents.
+pub type PackObject = (PackObjectKind, Vec<u8>, Vec<u8>);
+
+#[derive(Clone, Debug)]
+struct PackIndexEntry {
+    kind: PackObjectKind,
+    id: Vec<u8>,
+    offset: u64,
+    len: u64,
+}
+
+/// Loaded pack index.
+#[derive(Debug)]
+struct Pack {
+    name: String,
+    pack_path: PathBuf,
+    /// Entries sorted by `(kind, id)`.
+    entries: Vec<PackIndexEntry>,
+}
+
+impl Pack {
+    fn load(dir: &Path, name: &str) -> io::Result<Self> {
+        let index_path = dir.join(name).with_extension(INDEX_FILE_EXT);
+        let buf = fs::read(index_path)?;
+        let entries = decode_index(&buf)?;
+        Ok(Pack {
+            name: name.to_owned(),
+            pack_path: dir.join(name).with_extension(PACK_FILE_EXT),
+            entries,
+        })
+    }
+
+    fn find(&self, kind: PackObjectKind, id: &[u8]) -> Option<&PackIndexEntry> {
+        let pos = self
+            .entries
+            .binary_search_by(|entry| (entry.kind, entry.id.as_slice()).cmp(&(kind, id)))
+            .ok()?;
+        Some(&self.entries[pos])
+    }
+
+    fn read_entry(&self, entry: &PackIndexEntry) -> io::Result<Vec<u8>> {
+        let mut file = File::open(&self.pack_path)?;
+        file.seek(SeekFrom::Start(entry.offset))?;
+        let mut buf = vec![0; usize::try_from(entry.len).map_err(io::Error::other)?];
+        file.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+fn split_bytes<'a>(rest: &mut &'a [u8], n: usize) -> io::Result<&'a [u8]> {
+    if rest.len() < n {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "truncated pack index",
+        ));
+    }
+    let (head, tail) = rest.split_at(n);
+    *rest = tail;
+    Ok(head)
+}
+
+fn decode_index(buf: &[u8]) -> io::Result<Vec<PackIndexEntry>> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "corrupt pack index");
+    let mut rest = buf.strip_prefix(INDEX_MAGIC).ok_or_else(invalid)?;
+    let rest = &mut rest;
+    let count = u32::from_le_bytes(split_bytes(rest, 4)?.try_into().unwrap());
+    let mut entries = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let kind = PackObjectKind::from_byte(split_bytes(rest, 1)?[0]).ok_or_else(invalid)?;
+        let id_len = u16::from_le_bytes(split_bytes(rest, 2)?.try_into().unwrap());
+        let id = split_bytes(rest, id_len.into())?.to_vec();
+        let offset = u64::from_le_bytes(split_bytes(rest, 8)?.try_into().unwrap());
+        let len = u64::from_le_bytes(split_bytes(rest, 8)?.try_into().unwrap());
+        entries.push(PackIndexEntry {
+            kind,
+            id,
+            offset,
+            len,
+        });
+    }
+    Ok(entries)
+}
+
+fn encode_index(entries: &[PackIndexEntry]) -> Vec<u8> {
+    let mut buf = INDEX_MAGIC.to_vec();
+    buf.extend(u32::try_from(entries.len()).unwrap().to_le_bytes());
+    for entry in entries {
+        buf.push(entry.kind.to_byte());
+        buf.extend(u16::try_from(entry.id.len()).unwrap().to_le_bytes());
+        buf.extend(&entry.id);
+        buf.extend(entry.offset.to_le_bytes());
+        buf.extend(entry.len.to_le_bytes());
+    }
+    buf
+}
+
+/// Summary of packed objects.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PackStats {
+    /// Number of packs.
+    pub pack_count: usize,
+    /// Number of objects stored in packs.
+    pub object_count: usize,
+    /// Total size of the object contents in bytes.
+    pub total_bytes: u64,
+}
+
+/// Set of packs stored in a directory.
+#[derive(Debug)]
+pub struct PackSet {
+    dir: PathBuf,
+    packs: Mutex<Vec<Arc<Pack>>>,
+}
+
+impl PackSet {
+    /// Creates the pack directory if needed and loads the packs in it.
+    pub fn init(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self::load(dir))
+    }
+
+    /// Loads packs lazily from the given directory.
+    pub fn load(dir: &Path) -> Self {
+        PackSet {
+            dir: dir.to_path_buf(),
+            packs: Mutex::new(vec![]),
+        }
+    }
+
+    /// Path to the pack directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn list_pack_names(&self) -> io::Result<Vec<String>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err),
+        };
+        let mut names = vec![];
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == INDEX_FILE_EXT) {
+                if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+                    names.push(stem.to_owned());
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Reloads the pack list from disk. Already-loaded indexes are reused.
+    fn reload(&self) -> io::Result<Vec<Arc<Pack>>> {
+        let names = self.list_pack_names()?;
+        let mut packs = self.packs.lock().unwrap();
+        let mut new_packs = Vec::with_capacity(names.len());
+        for name in names {
+            if let Some(pack) = packs.iter().find(|pack| pack.name == name) {
+                new_packs.push(pack.clone());
+                continue;
+            }
+            match Pack::load(&self.dir, &name) {
+                Ok(pack) => new_packs.push(Arc::new(pack)),
+                // Removed by concurrent repack
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+        *packs = new_packs.clone();
+        Ok(new_packs)
+    }
+
+    fn find_in(
+        packs: &[Arc<Pack>],
+        kind: PackObjectKind,
+        id: &[u8],
+    ) -> io::Result<Option<Vec<u8>>> {
+        for pack in packs {
+            if let Some(entry) = pack.find(kind, id) {
+                match pack.read_entry(entry) {
+                    Ok(data) => return Ok(Some(data)),
+                    // Removed by concurrent repack
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Reads the object contents from packs. Returns `None` if not found.
+    pub fn read_object(&self, kind: PackObjectKind, id: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        let packs = self.packs.lock().unwrap().clone();
+        if let Some(data) = Self::find_in(&packs, kind, id)? {
+            return Ok(Some(data));
+        }
+        // The object may have been packed since the last load.
+        let packs = self.reload()?;
+        Self::find_in(&packs, kind, id)
+    }
+
+    /// Names of the packs currently stored on disk.
+    pub fn pack_names(&self) -> io::Result<Vec<String>> {
+        self.list_pack_names()
+    }
+
+    /// Names and total object sizes of the packs currently stored on disk.
+    pub fn pack_sizes(&self) -> io::Result<Vec<(String, u64)>> {
+        let packs = self.reload()?;
+        Ok(packs
+            .iter()
+            .map(|pack| {
+                let size = pack.entries.iter().map(|entry| entry.len).sum();
+                (pack.name.clone(), size)
+            })
+            .collect())
+    }
+
+    /// Iterates over all objects stored in the currently visible packs.
+    pub fn objects(&self) -> io::Result<impl Iterator<Item = io::Result<PackObject>>> {
+        let packs = self.reload()?;
+        Ok(packs.into_iter().flat_map(|pack| {
+            let len = pack.entries.len();
+            (0..len).map(move |i| {
+                let entry = &pack.entries[i];
+                let data = pack.read_entry(entry)?;
+                Ok((entry.kind, entry.id.clone(), data))
+            })
+        }))
+    }
+
+    /// Iterates over all objects stored in the specified packs. Packs removed
+    /// concurrently are skipped.
+    pub fn objects_in<'a>(
+        &self,
+        names: &'a [String],
+    ) -> io::Result<impl Iterator<Item = io::Result<PackObject>> + 'a> {
+        let packs = self.reload()?;
+        Ok(packs
+            .into_iter()
+            .filter(move |pack| names.contains(&pack.name))
+            .flat_map(|pack| {
+                let len = pack.entries.len();
+                (0..len).map(move |i| {
+                    let entry = &pack.entries[i];
+                    let data = pack.read_entry(entry)?;
+                    Ok((entry.kind, entry.id.clone(), data))
+                })
+            })
+            .filter(|object| !matches!(object, Err(err) if err.kind() == io::ErrorKind::NotFound)))
+    }
+
+    /// Returns summary of the packs.
+    pub fn stats(&self) -> io::Result<PackStats> {
+        let packs = self.reload()?;
+        let entries = packs.iter().flat_map(|pack| &pack.entries);
+        Ok(PackStats {
+            pack_count: packs.len(),
+            object_count: entries.clone().count(),
+            total_bytes: entries.map(|entry| entry.len).sum(),
+        })
+    }
+
+    /// Writes a new pack containing the given objects. Duplicated objects are
+    /// stored once. Returns the name of the new pack, or `None` if there were
+    /// no objects.
+    pub fn write_pack(
+        &self,
+        objects: impl IntoIterator<Item = io::Result<PackObject>>,
+    ) -> io::Result<Option<String>> {
+        let temp_pack = NamedTempFile::new_in(&self.dir)?;
+        let mut writer = BufWriter::new(temp_pack.as_file());
+        writer.write_all(PACK_MAGIC)?;
+        let mut offset = PACK_MAGIC.len() as u64;
+        let mut seen = HashSet::new();
+        let mut entries = vec![];
+        for object in objects {
+            let (kind, id, data) = object?;
+            if !seen.insert((kind, id.clone())) {
+                continue;
+            }
+            writer.write_all(&data)?;
+            let len = data.len() as u64;
+            entries.push(PackIndexEntry {
+                kind,
+                id,
+                offset,
+                len,
+            });
+            offset += len;
+        }
+        writer.flush()?;
+        drop(writer);
+        if entries.is_empty() {
+            return Ok(None);
+        }
+        entries.sort_by(|a, b| (a.kind, &a.id).cmp(&(b.kind, &b.id)));
+        let index_data = encode_index(&entries);
+        let name = format!(
+            "pack-{}",
+            hex::encode(&Blake2b512::digest(&index_data)[..20])
+        );
+
+        temp_pack.as_file().sync_data()?;
+        persist_content_addressed_temp_file(
+            temp_pack,
+            self.dir.join(&name).with_extension(PACK_FILE_EXT),
+        )?;
+        let mut temp_index = NamedTempFile::new_in(&self.dir)?;
+        temp_index.write_all(&index_data)?;
+        temp_index.as_file().sync_data()?;
+        persist_content_addressed_temp_file(
+            temp_index,
+            self.dir.join(&name).with_extension(INDEX_FILE_EXT),
+        )?;
+        self.reload()?;
+        Ok(Some(name))
+    }
+
+    /// Removes the specified packs. Packs already removed by concurrent repack
+    /// are ignored. Returns the number of removed packs.
+    pub fn remove_packs(&self, names: &[String]) -> io::Result<usize> {
+        let mut count = 0;
+        for name in names {
+            // Remove the index first so the pack becomes invisible.
+            match fs::remove_file(self.dir.join(name).with_extension(INDEX_FILE_EXT)) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            }
+            match fs::remove_file(self.dir.join(name).with_extension(PACK_FILE_EXT)) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+            count += 1;
+        }
+        self.reload()?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(kind: PackObjectKind, id: &[u8], data: &[u8]) -> io::Result<PackObject> {
+        Ok((kind, id.to_vec(), data.to_vec()))
+    }
+
+    #[test]
+    fn test_write_and_read_pack() {
+        let temp_dir = testutils::new_temp_dir();
+        let packs = PackSet::init(&temp_dir.path().join("packs")).unwrap();
+        assert_eq!(packs.write_pack([]).unwrap(), None);
+
+        let name = packs
+            .write_pack([
+                object(PackObjectKind::File, b"\x01", b"file 1"),
+                object(PackObjectKind::Tree, b"\x01", b"tree 1"),
+                object(PackObjectKind::File, b"\x00", b""),
+                object(PackObjectKind::File, b"\x01", b"file 1"),
+            ])
+            .unwrap()
+            .unwrap();
+        assert!(name.starts_with("pack-"));
+
+        let read = |kind, id: &[u8]| packs.read_object(kind, id).unwrap();
+        assert_eq!(
+            read(PackObjectKind::File, b"\x01"),
+            Some(b"file 1".to_vec())
+        );
+        assert_eq!(
+            read(PackObjectKind::Tree, b"\x01"),
+            Some(b"tree 1".to_vec())
+        );
+        assert_eq!(read(PackObjectKind::File, b"\x00"), Some(vec![]));
+        assert_eq!(read(PackObjectKind::Commit, b"\x01"), None);
+        assert_eq!(read(PackObjectKind::File, b"\x02"), None);
+        assert_eq!(
+            packs.stats().unwrap(),
+            PackStats {
+                pack_count: 1,
+                object_count: 3,
+                total_bytes: 12,
+            }
+        );
+
+        // Pack written by another instance should be found
+        let other_packs = PackSet::load(packs.dir());
+        other_packs
+            .write_pack([object(PackObjectKind::Commit, b"\x02", b"commit 2")])
+            .unwrap();
+        assert_eq!(
+            read(PackObjectKind::Commit, b"\x02"),
+            Some(b"commit 2".to_vec())
+        );
+
+        // Packs other than the specified ones are kept
+        let other_name = packs
+            .pack_names()
+            .unwrap()
+            .into_iter()
+            .find(|n| *n != name)
+            .unwrap();
+        assert_eq!(packs.remove_packs(&[name.clone()]).unwrap(), 1);
+        assert_eq!(packs.remove_packs(&[name.clone()]).unwrap(), 0);
+        assert_eq!(read(PackObjectKind::File, b"\x01"), None);
+        assert_eq!(
+            read(PackObjectKind::Commit, b"\x02"),
+            Some(b"commit 2".to_vec())
+        );
+
+        // Removed pack should no longer be found
+        assert_eq!(packs.remove_packs(&[other_name]).unwrap(), 1);
+        assert_eq!(read(PackObjectKind::Commit, b"\x02"), None);
+        assert_eq!(
+            other_packs
+                .read_object(PackObjectKind::Commit, b"\x02")
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            other_packs
+                .read_object(PackObjectKind::File, b"\x01")
+                .unwrap(),
+            None
+        );
+    }
+}
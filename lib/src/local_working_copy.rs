@@ -25,6 +25,7 @@ use std::fs::File;
 use std::fs::Metadata;
 use std::fs::OpenOptions;
 use std::io;
+use std::io::BufWriter;
 use std::io::Read;
 use std::io::Write;
 use std::iter;
@@ -39,6 +40,7 @@ use std::sync::mpsc::channel;
 use std::sync::mpsc::Sender;
 use std::sync::Arc;
 use std::sync::OnceLock;
+use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 
 use either::Either;
@@ -67,7 +69,7 @@ use crate::backend::TreeValue;
 use crate::commit::Commit;
 use crate::conflicts;
 use crate::conflicts::choose_materialized_conflict_marker_len;
-use crate::conflicts::materialize_merge_result_to_bytes_with_marker_len;
+use crate::conflicts::materialize_merge_result_with_marker_len;
 use crate::conflicts::materialize_tree_value;
 use crate::conflicts::ConflictMarkerStyle;
 use crate::conflicts::MaterializedTreeValue;
@@ -421,6 +423,8 @@ pub struct TreeState {
     /// the repo is configured to use the Watchman filesystem monitor and
     /// Watchman has been queried at least once.
     watchman_clock: Option<crate::protos::working_copy::WatchmanClock>,
+    /// When the working copy was last scanned in full while using Watchman.
+    last_full_scan: MillisSinceEpoch,
 }
 
 fn file_state_from_proto(proto: &crate::protos::working_copy::FileState) -> FileState {
@@ -719,6 +723,47 @@ fn file_state(metadata: &Metadata) -> Option<FileState> {
 struct FsmonitorMatcher {
     matcher: Option<Box<dyn Matcher>>,
     watchman_clock: Option<crate::protos::working_copy::WatchmanClock>,
+    /// Whether `watchman_clock` differs from the saved one.
+    clock_changed: bool,
+}
+
+/// Decides whether the changes reported by Watchman since `saved_clock` can be
+/// trusted, and which clock to save for the next query.
+///
+/// If nothing changed, the saved clock is kept so the state needn't be saved.
+/// If the working copy hasn't been scanned in full for longer than the
+/// configured interval, the reported changes are ignored.
+#[cfg(feature = "watchman")]
+fn trust_watchman_changes(
+    config: &WatchmanConfig,
+    saved_clock: Option<&crate::protos::working_copy::WatchmanClock>,
+    last_full_scan: MillisSinceEpoch,
+    now: MillisSinceEpoch,
+    new_clock: crate::protos::working_copy::WatchmanClock,
+    changed_files: Option<Vec<PathBuf>>,
+) -> (
+    Option<crate::protos::working_copy::WatchmanClock>,
+    Option<Vec<PathBuf>>,
+    bool,
+) {
+    let full_scan_due = config.full_scan_interval.is_some_and(|interval| {
+        let elapsed = now.0.saturating_sub(last_full_scan.0);
+        u128::try_from(elapsed).unwrap_or(0) >= interval.as_millis()
+    });
+    let changed_files = changed_files.filter(|_| !full_scan_due);
+    match saved_clock {
+        Some(saved_clock) if changed_files.as_ref().is_some_and(|files| files.is_empty()) => {
+            (Some(saved_clock.clone()), changed_files, false)
+        }
+        _ => (Some(new_clock), changed_files, true),
+    }
+}
+
+fn now_millis() -> MillisSinceEpoch {
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    MillisSinceEpoch(i64::try_from(since_epoch.as_millis()).unwrap_or(i64::MAX))
 }
 
 #[derive(Debug, Error)]
@@ -781,6 +826,7 @@ impl TreeState {
             own_mtime: MillisSinceEpoch(0),
             symlink_support: check_symlink_support().unwrap_or(false),
             watchman_clock: None,
+            last_full_scan: MillisSinceEpoch(0),
         }
     }
 
@@ -844,6 +890,16 @@ impl TreeState {
             FileStatesMap::from_proto(proto.file_states, proto.is_file_states_sorted);
         self.sparse_patterns = sparse_patterns_from_proto(proto.sparse_patterns.as_ref());
         self.watchman_clock = proto.watchman_clock;
+        if self.watchman_clock.is_some() {
+            // The state may have been saved by a version that doesn't record
+            // the freshness and drops it, in which case the file states may
+            // not match the clock.
+            let freshness = proto.fsmonitor_freshness.unwrap_or_default();
+            if freshness.watchman_clock != self.watchman_clock {
+                self.watchman_clock = None;
+            }
+            self.last_full_scan = MillisSinceEpoch(freshness.full_scan_millis_since_epoch);
+        }
         Ok(())
     }
 
@@ -871,6 +927,12 @@ impl TreeState {
         }
         proto.sparse_patterns = Some(sparse_patterns);
         proto.watchman_clock = self.watchman_clock.clone();
+        if self.watchman_clock.is_some() {
+            proto.fsmonitor_freshness = Some(crate::protos::working_copy::FsmonitorFreshness {
+                watchman_clock: self.watchman_clock.clone(),
+                full_scan_millis_since_epoch: self.last_full_scan.0,
+            });
+        }
 
         let mut temp_file = NamedTempFile::new_in(&self.state_path).unwrap();
         temp_file
@@ -960,12 +1022,15 @@ impl TreeState {
 
         let sparse_matcher = self.sparse_matcher();
 
-        let fsmonitor_clock_needs_save = *fsmonitor_settings != FsmonitorSettings::None;
-        let mut is_dirty = fsmonitor_clock_needs_save;
         let FsmonitorMatcher {
             matcher: fsmonitor_matcher,
             watchman_clock,
+            clock_changed,
         } = self.make_fsmonitor_matcher(fsmonitor_settings)?;
+        let mut is_dirty = clock_changed;
+        if watchman_clock.is_some() && fsmonitor_matcher.is_none() {
+            self.last_full_scan = now_millis();
+        }
         let fsmonitor_matcher = match fsmonitor_matcher.as_ref() {
             None => &EverythingMatcher,
             Some(fsmonitor_matcher) => fsmonitor_matcher.as_ref(),
@@ -1064,15 +1129,22 @@ impl TreeState {
         &self,
         fsmonitor_settings: &FsmonitorSettings,
     ) -> Result<FsmonitorMatcher, SnapshotError> {
-        let (watchman_clock, changed_files) = match fsmonitor_settings {
-            FsmonitorSettings::None => (None, None),
-            FsmonitorSettings::Test { changed_files } => (None, Some(changed_files.clone())),
+        let (watchman_clock, changed_files, clock_changed) = match fsmonitor_settings {
+            FsmonitorSettings::None => (None, None, false),
+            FsmonitorSettings::Test { changed_files } => (None, Some(changed_files.clone()), true),
             #[cfg(feature = "watchman")]
             FsmonitorSettings::Watchman(config) => match self.query_watchman(config) {
-                Ok((watchman_clock, changed_files)) => (Some(watchman_clock.into()), changed_files),
+                Ok((watchman_clock, changed_files)) => trust_watchman_changes(
+                    config,
+                    self.watchman_clock.as_ref(),
+                    self.last_full_scan,
+                    now_millis(),
+                    watchman_clock.into(),
+                    changed_files,
+                ),
                 Err(err) => {
                     tracing::warn!(?err, "Failed to query filesystem monitor");
-                    (None, None)
+                    (None, None, true)
                 }
             },
             #[cfg(not(feature = "watchman"))]
@@ -1101,6 +1173,7 @@ impl TreeState {
         Ok(FsmonitorMatcher {
             matcher,
             watchman_clock,
+            clock_changed,
         })
     }
 }
@@ -1629,14 +1702,18 @@ impl TreeState {
         Ok(FileState::for_symlink(&metadata))
     }
 
+    /// Writes conflict data produced by `write_data` to a new file.
+    ///
+    /// The data is streamed to the file in chunks, so large conflicts don't
+    /// have to be materialized in memory first.
     fn write_conflict(
         &self,
         disk_path: &Path,
-        conflict_data: Vec<u8>,
+        write_data: impl FnOnce(&mut dyn Write) -> io::Result<()>,
         executable: bool,
         materialized_conflict_data: Option<MaterializedConflictData>,
     ) -> Result<FileState, CheckoutError> {
-        let mut file = OpenOptions::new()
+        let file = OpenOptions::new()
             .write(true)
             .create_new(true) // Don't overwrite un-ignored file. Don't follow symlink.
             .open(disk_path)
@@ -1644,16 +1721,19 @@ impl TreeState {
                 message: format!("Failed to open file {} for writing", disk_path.display()),
                 err: err.into(),
             })?;
-        file.write_all(&conflict_data)
+        let mut writer = BufWriter::new(file);
+        write_data(&mut writer)
+            .and_then(|()| writer.flush())
             .map_err(|err| CheckoutError::Other {
                 message: format!("Failed to write conflict to file {}", disk_path.display()),
                 err: err.into(),
             })?;
-        let size = conflict_data.len() as u64;
         self.set_executable(disk_path, executable)?;
-        let metadata = file
+        let metadata = writer
+            .get_ref()
             .metadata()
             .map_err(|err| checkout_error_for_stat_error(err, disk_path))?;
+        let size = metadata.len();
         Ok(FileState::for_file(
             executable,
             size,
@@ -1851,18 +1931,19 @@ impl TreeState {
                     executable,
                 } => {
                     let conflict_marker_len = choose_materialized_conflict_marker_len(&contents);
-                    let data = materialize_merge_result_to_bytes_with_marker_len(
-                        &contents,
-                        conflict_marker_style,
-                        conflict_marker_len,
-                    )
-                    .into();
                     let materialized_conflict_data = MaterializedConflictData {
                         conflict_marker_len: conflict_marker_len.try_into().unwrap_or(u32::MAX),
                     };
                     self.write_conflict(
                         &disk_path,
-                        data,
+                        |output| {
+                            materialize_merge_result_with_marker_len(
+                                &contents,
+                                conflict_marker_style,
+                                conflict_marker_len,
+                                output,
+                            )
+                        },
                         executable,
                         Some(materialized_conflict_data),
                     )?
@@ -1870,9 +1951,14 @@ impl TreeState {
                 MaterializedTreeValue::OtherConflict { id } => {
                     // Unless all terms are regular files, we can't do much
                     // better than trying to describe the merge.
-                    let data = id.describe().into_bytes();
+                    let data = id.describe();
                     let executable = false;
-                    self.write_conflict(&disk_path, data, executable, None)?
+                    self.write_conflict(
+                        &disk_path,
+                        |output| output.write_all(data.as_bytes()),
+                        executable,
+                        None,
+                    )?
                 }
             };
             changed_file_states.push((path, file_state));
@@ -2082,6 +2168,23 @@ impl LocalWorkingCopy {
         &self.state_path
     }
 
+    /// Returns temporary files in the state directory that were left behind by
+    /// interrupted commands and were last modified before `keep_newer`.
+    pub fn stale_temp_files(&self, keep_newer: SystemTime) -> io::Result<Vec<PathBuf>> {
+        let mut paths = vec![];
+        for entry in self.state_path.read_dir()? {
+            let entry = entry?;
+            // Named by tempfile::NamedTempFile
+            if !entry.file_name().to_string_lossy().starts_with(".tmp") {
+                continue;
+            }
+            if entry.metadata()?.modified()? <= keep_newer {
+                paths.push(entry.path());
+            }
+        }
+        Ok(paths)
+    }
+
     fn write_proto(&self, proto: crate::protos::working_copy::Checkout) {
         let mut temp_file = NamedTempFile::new_in(&self.state_path).unwrap();
         temp_file
@@ -2375,6 +2478,8 @@ mod tests {
     use maplit::hashset;
 
     use super::*;
+    use crate::local_backend::LocalBackend;
+    use crate::signing::Signer;
 
     fn repo_path(value: &str) -> &RepoPath {
         RepoPath::from_internal_string(value)
@@ -2571,4 +2676,141 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn test_watchman_clock_freshness() {
+        let temp_dir = testutils::new_temp_dir();
+        let path = temp_dir.path().to_owned();
+        let store_path = path.join("store");
+        fs::create_dir(&store_path).unwrap();
+        let backend = LocalBackend::init(&store_path);
+        let store = Store::new(Box::new(backend), Signer::new(None, vec![]));
+        let clock = crate::protos::working_copy::WatchmanClock {
+            watchman_clock: Some(
+                crate::protos::working_copy::watchman_clock::WatchmanClock::UnixTimestamp(1),
+            ),
+        };
+        let mut tree_state = TreeState::init(store.clone(), path.clone(), path.clone()).unwrap();
+        tree_state.watchman_clock = Some(clock.clone());
+        tree_state.last_full_scan = MillisSinceEpoch(123);
+        tree_state.save().unwrap();
+
+        // The clock is trusted if it was saved along with the freshness record
+        let tree_state = TreeState::load(store.clone(), path.clone(), path.clone()).unwrap();
+        assert_eq!(tree_state.watchman_clock, Some(clock));
+        assert_eq!(tree_state.last_full_scan, MillisSinceEpoch(123));
+
+        // The clock is dropped if the file states were written without the
+        // freshness record
+        let tree_state_path = path.join("tree_state");
+        let buf = fs::read(&tree_state_path).unwrap();
+        let mut proto = crate::protos::working_copy::TreeState::decode(&*buf).unwrap();
+        proto.fsmonitor_freshness = None;
+        fs::write(&tree_state_path, proto.encode_to_vec()).unwrap();
+        let tree_state = TreeState::load(store.clone(), path.clone(), path.clone()).unwrap();
+        assert_eq!(tree_state.watchman_clock, None);
+
+        // The clock is dropped if the freshness record was saved with another
+        // clock
+        proto.fsmonitor_freshness = Some(crate::protos::working_copy::FsmonitorFreshness {
+            watchman_clock: Some(crate::protos::working_copy::WatchmanClock {
+                watchman_clock: Some(
+                    crate::protos::working_copy::watchman_clock::WatchmanClock::UnixTimestamp(2),
+                ),
+            }),
+            full_scan_millis_since_epoch: 123,
+        });
+        fs::write(&tree_state_path, proto.encode_to_vec()).unwrap();
+        let tree_state = TreeState::load(store.clone(), path.clone(), path).unwrap();
+        assert_eq!(tree_state.watchman_clock, None);
+    }
+
+    #[cfg(feature = "watchman")]
+    #[test]
+    fn test_trust_watchman_changes() {
+        use std::time::Duration;
+
+        let clock = |timestamp| crate::protos::working_copy::WatchmanClock {
+            watchman_clock: Some(
+                crate::protos::working_copy::watchman_clock::WatchmanClock::UnixTimestamp(
+                    timestamp,
+                ),
+            ),
+        };
+        let config = WatchmanConfig {
+            register_trigger: false,
+            full_scan_interval: Some(Duration::from_millis(1000)),
+        };
+        let hour = MillisSinceEpoch(60 * 60 * 1000);
+        let recent = MillisSinceEpoch(hour.0 - 10);
+
+        // Nothing changed since the saved clock, which is kept
+        assert_eq!(
+            trust_watchman_changes(
+                &config,
+                Some(&clock(1)),
+                recent,
+                hour,
+                clock(2),
+                Some(vec![])
+            ),
+            (Some(clock(1)), Some(vec![]), false)
+        );
+
+        // Changed files are trusted, and the new clock is saved
+        assert_eq!(
+            trust_watchman_changes(
+                &config,
+                Some(&clock(1)),
+                recent,
+                hour,
+                clock(2),
+                Some(vec![PathBuf::from("file")])
+            ),
+            (Some(clock(2)), Some(vec![PathBuf::from("file")]), true)
+        );
+
+        // Without a saved clock, the new clock is saved
+        assert_eq!(
+            trust_watchman_changes(&config, None, recent, hour, clock(2), Some(vec![])),
+            (Some(clock(2)), Some(vec![]), true)
+        );
+
+        // Watchman asked for a full scan
+        assert_eq!(
+            trust_watchman_changes(&config, Some(&clock(1)), recent, hour, clock(2), None),
+            (Some(clock(2)), None, true)
+        );
+
+        // The full scan is due, so the reported changes are ignored
+        let stale = MillisSinceEpoch(hour.0 - 1000);
+        assert_eq!(
+            trust_watchman_changes(
+                &config,
+                Some(&clock(1)),
+                stale,
+                hour,
+                clock(2),
+                Some(vec![])
+            ),
+            (Some(clock(2)), None, true)
+        );
+
+        // Full scans are disabled
+        let config = WatchmanConfig {
+            full_scan_interval: None,
+            ..config
+        };
+        assert_eq!(
+            trust_watchman_changes(
+                &config,
+                Some(&clock(1)),
+                stale,
+                hour,
+                clock(2),
+                Some(vec![])
+            ),
+            (Some(clock(1)), Some(vec![]), false)
+        );
+    }
 }
@@ -93,6 +93,28 @@ impl FileLock {
             }
         }
     }
+
+    /// Like [`FileLock::lock()`], but returns `None` instead of waiting if the
+    /// lock is held by someone else.
+    pub fn try_lock(path: PathBuf) -> Result<Option<FileLock>, FileLockError> {
+        let mut options = OpenOptions::new();
+        options.create_new(true);
+        options.write(true);
+        match options.open(&path) {
+            Ok(file) => Ok(Some(FileLock { path, _file: file })),
+            Err(err)
+                if err.kind() == std::io::ErrorKind::AlreadyExists
+                    || (cfg!(windows) && err.kind() == std::io::ErrorKind::PermissionDenied) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(FileLockError {
+                message: "Failed to create lock file",
+                path,
+                err,
+            }),
+        }
+    }
 }
 
 impl Drop for FileLock {
@@ -61,6 +61,28 @@ mod tests {
         assert!(!lock_path.exists());
     }
 
+    #[test_case(FileLock::lock, FileLock::try_lock)]
+    #[cfg_attr(
+        unix,
+        test_case(fallback::FileLock::lock, fallback::FileLock::try_lock)
+    )]
+    fn try_lock_held<T>(
+        lock_fn: fn(PathBuf) -> Result<T, FileLockError>,
+        try_lock_fn: fn(PathBuf) -> Result<Option<T>, FileLockError>,
+    ) {
+        let temp_dir = testutils::new_temp_dir();
+        let lock_path = temp_dir.path().join("test.lock");
+        {
+            let _lock = lock_fn(lock_path.clone()).unwrap();
+            assert!(try_lock_fn(lock_path.clone()).unwrap().is_none());
+        }
+        let lock = try_lock_fn(lock_path.clone()).unwrap();
+        assert!(lock.is_some());
+        assert!(lock_path.exists());
+        drop(lock);
+        assert!(!lock_path.exists());
+    }
+
     #[test_case(FileLock::lock)]
     #[cfg_attr(unix, test_case(fallback::FileLock::lock))]
     fn lock_concurrent<T>(lock_fn: fn(PathBuf) -> Result<T, FileLockError>) {
@@ -0,0 +1,206 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Scheduling of automatic repository maintenance.
+//!
+//! The operation at which maintenance last ran is recorded in the
+//! `maintenance` file in the repo directory, and the modification time of
+//! that file is the time of the last run. Maintenance is due once enough
+//! operations have been added or enough time has passed since then.
+//!
+//! Two locks are used. `maintenance.lock` is held while maintenance runs so
+//! that at most one process runs it at a time. `maintenance.state.lock` is held
+//! briefly while the state file is read or updated, so that concurrent
+//! `register` and `run` commands don't overwrite each other's state.
+
+use std::fs;
+use std::io;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::slice;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use tempfile::NamedTempFile;
+
+use crate::lock::FileLock;
+use crate::lock::FileLockError;
+use crate::object_id::ObjectId as _;
+use crate::op_store::OperationId;
+use crate::op_walk;
+use crate::operation::Operation;
+
+/// Thresholds after which maintenance is due.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MaintenanceSchedule {
+    /// Number of operations since the last run. 0 disables this threshold.
+    pub operations: usize,
+    /// Time since the last run.
+    pub interval: Duration,
+}
+
+/// Maintenance state stored in a repo directory.
+#[derive(Debug)]
+pub struct MaintenanceState {
+    repo_path: PathBuf,
+}
+
+impl MaintenanceState {
+    /// Creates a handle for the state in the given repo directory.
+    pub fn new(repo_path: &Path) -> Self {
+        MaintenanceState {
+            repo_path: repo_path.to_path_buf(),
+        }
+    }
+
+    fn state_path(&self) -> PathBuf {
+        self.repo_path.join("maintenance")
+    }
+
+    fn lock_state(&self) -> io::Result<FileLock> {
+        FileLock::lock(self.repo_path.join("maintenance.state.lock")).map_err(io::Error::other)
+    }
+
+    /// Returns the operation and time of the last recorded run, or `None` if
+    /// maintenance has never run.
+    pub fn last_run(&self) -> io::Result<Option<(OperationId, SystemTime)>> {
+        let _lock = self.lock_state()?;
+        self.last_run_unlocked()
+    }
+
+    fn last_run_unlocked(&self) -> io::Result<Option<(OperationId, SystemTime)>> {
+        let path = self.state_path();
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let op_id = OperationId::try_from_hex(content.trim())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid maintenance state"))?;
+        let time = fs::metadata(&path)?.modified()?;
+        Ok(Some((op_id, time)))
+    }
+
+    /// Records that maintenance has run at the given operation.
+    pub fn record_run(&self, op_id: &OperationId) -> io::Result<()> {
+        let _lock = self.lock_state()?;
+        self.record_run_unlocked(op_id)
+    }
+
+    /// Records the given operation as the last run unless a run has already
+    /// been recorded. Returns true if the state was written.
+    pub fn record_run_if_unset(&self, op_id: &OperationId) -> io::Result<bool> {
+        let _lock = self.lock_state()?;
+        if self.last_run_unlocked()?.is_some() {
+            return Ok(false);
+        }
+        self.record_run_unlocked(op_id)?;
+        Ok(true)
+    }
+
+    fn record_run_unlocked(&self, op_id: &OperationId) -> io::Result<()> {
+        let mut temp_file = NamedTempFile::new_in(&self.repo_path)?;
+        writeln!(temp_file, "{}", op_id.hex())?;
+        temp_file
+            .persist(self.state_path())
+            .map_err(|err| err.error)?;
+        Ok(())
+    }
+
+    /// Returns true if maintenance should run at the given operation.
+    ///
+    /// Maintenance is due if it has never run, or if either threshold of the
+    /// `schedule` has been reached since the last run.
+    pub fn is_due(
+        &self,
+        operation: &Operation,
+        schedule: &MaintenanceSchedule,
+    ) -> io::Result<bool> {
+        let Some((last_op_id, last_time)) = self.last_run()? else {
+            return Ok(true);
+        };
+        let elapsed = SystemTime::now()
+            .duration_since(last_time)
+            .unwrap_or(Duration::ZERO);
+        if elapsed >= schedule.interval {
+            return Ok(true);
+        }
+        if schedule.operations == 0 {
+            return Ok(false);
+        }
+        // Count the operations added since the last run, stopping as soon as
+        // the threshold is reached.
+        let mut count = 0;
+        for op in op_walk::walk_ancestors(slice::from_ref(operation)) {
+            let op = op.map_err(io::Error::other)?;
+            if *op.id() == last_op_id {
+                return Ok(false);
+            }
+            count += 1;
+            if count >= schedule.operations {
+                return Ok(true);
+            }
+        }
+        // The last run's operation is no longer reachable, which means the
+        // operation log has been rewritten since then.
+        Ok(true)
+    }
+
+    /// Tries to take the lock that prevents concurrent maintenance runs.
+    /// Returns `None` if another process holds the lock.
+    pub fn try_lock(&self) -> Result<Option<FileLock>, FileLockError> {
+        FileLock::try_lock(self.repo_path.join("maintenance.lock"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_run() {
+        let temp_dir = testutils::new_temp_dir();
+        let state = MaintenanceState::new(temp_dir.path());
+        assert!(state.last_run().unwrap().is_none());
+
+        let op_id = OperationId::new(vec![0x12; 64]);
+        assert!(state.record_run_if_unset(&op_id).unwrap());
+        let (last_op_id, _) = state.last_run().unwrap().unwrap();
+        assert_eq!(last_op_id, op_id);
+
+        // An existing run isn't overwritten
+        let other_op_id = OperationId::new(vec![0x34; 64]);
+        assert!(!state.record_run_if_unset(&other_op_id).unwrap());
+        let (last_op_id, _) = state.last_run().unwrap().unwrap();
+        assert_eq!(last_op_id, op_id);
+        state.record_run(&other_op_id).unwrap();
+        let (last_op_id, _) = state.last_run().unwrap().unwrap();
+        assert_eq!(last_op_id, other_op_id);
+
+        fs::write(temp_dir.path().join("maintenance"), "garbage").unwrap();
+        assert!(state.last_run().is_err());
+    }
+
+    #[test]
+    fn test_try_lock() {
+        let temp_dir = testutils::new_temp_dir();
+        let state = MaintenanceState::new(temp_dir.path());
+        let lock = state.try_lock().unwrap();
+        assert!(lock.is_some());
+        assert!(state.try_lock().unwrap().is_none());
+        drop(lock);
+        assert!(state.try_lock().unwrap().is_some());
+    }
+}
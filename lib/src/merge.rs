@@ -20,11 +20,13 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Write as _;
+use std::future::Future;
 use std::hash::Hash;
 use std::iter::zip;
 use std::slice;
 use std::sync::Arc;
 
+use futures::future::try_join_all;
 use itertools::Itertools;
 use smallvec::smallvec_inline;
 use smallvec::SmallVec;
@@ -364,6 +366,22 @@ impl<T> Merge<T> {
         let values = self.values.iter().map(f).try_collect()?;
         Ok(Merge { values })
     }
+
+    /// Creates a new merge by applying async function `f` to each remove and
+    /// add, returning `Err` if `f` returns `Err` for any of them. The futures
+    /// are polled concurrently.
+    pub async fn try_map_async<'a, F, U, E>(
+        &'a self,
+        f: impl FnMut(&'a T) -> F,
+    ) -> Result<Merge<U>, E>
+    where
+        F: Future<Output = Result<U, E>>,
+    {
+        let values = try_join_all(self.values.iter().map(f)).await?;
+        Ok(Merge {
+            values: values.into(),
+        })
+    }
 }
 
 /// Helper for consuming items from an iterator and then creating a `Merge`.
@@ -680,6 +698,8 @@ fn describe_conflict_term(value: &TreeValue) -> String {
 
 #[cfg(test)]
 mod tests {
+    use pollster::FutureExt as _;
+
     use super::*;
 
     fn c<T: Clone>(terms: &[T]) -> Merge<T> {
@@ -1153,6 +1173,25 @@ mod tests {
         assert_eq!(c(&[1, -4, 9]).try_map(sqrt), Err(()));
     }
 
+    #[test]
+    fn test_try_map_async() {
+        async fn sqrt(i: &i32) -> Result<i32, ()> {
+            if *i >= 0 {
+                Ok((*i as f64).sqrt() as i32)
+            } else {
+                Err(())
+            }
+        }
+        let try_map = |merge: Merge<i32>| merge.try_map_async(sqrt).block_on();
+        // 1-way merge
+        assert_eq!(try_map(c(&[1])), Ok(c(&[1])));
+        assert_eq!(try_map(c(&[-1])), Err(()));
+        // 3-way merge
+        assert_eq!(try_map(c(&[1, 4, 9])), Ok(c(&[1, 2, 3])));
+        assert_eq!(try_map(c(&[-1, 4, 9])), Err(()));
+        assert_eq!(try_map(c(&[1, -4, 9])), Err(()));
+    }
+
     #[test]
     fn test_flatten() {
         // 1-way merge of 1-way merge
@@ -28,6 +28,7 @@ use std::task::Poll;
 use std::vec;
 
 use either::Either;
+use futures::future::try_join_all;
 use futures::future::BoxFuture;
 use futures::stream::BoxStream;
 use futures::stream::StreamExt;
@@ -161,7 +162,13 @@ impl MergedTree {
     /// Tries to resolve any conflicts, resolving any conflicts that can be
     /// automatically resolved and leaving the rest unresolved.
     pub fn resolve(&self) -> BackendResult<MergedTree> {
-        let merged = merge_trees(&self.trees)?;
+        self.resolve_async().block_on()
+    }
+
+    /// Async version of [`MergedTree::resolve()`]. Entries in each directory
+    /// are merged concurrently.
+    pub async fn resolve_async(&self) -> BackendResult<MergedTree> {
+        let merged = merge_trees(&self.trees).await?;
         // If the result can be resolved, then `merge_trees()` above would have returned
         // a resolved merge. However, that function will always preserve the arity of
         // conflicts it cannot resolve. So we simplify the conflict again
@@ -171,7 +178,7 @@ impl MergedTree {
         // particular,  that this last simplification doesn't enable further automatic
         // resolutions
         if cfg!(debug_assertions) {
-            let re_merged = merge_trees(&simplified).unwrap();
+            let re_merged = merge_trees(&simplified).await.unwrap();
             debug_assert_eq!(re_merged, simplified);
         }
         Ok(MergedTree { trees: simplified })
@@ -195,25 +202,34 @@ impl MergedTree {
     /// doesn't correspond to a tree in any of the inputs to the merge, then
     /// that entry will be replace by an empty tree in the result.
     pub fn sub_tree(&self, name: &RepoPathComponent) -> BackendResult<Option<MergedTree>> {
+        self.sub_tree_async(name).block_on()
+    }
+
+    /// Async version of [`MergedTree::sub_tree()`]. Trees of the conflict
+    /// terms are loaded concurrently.
+    pub async fn sub_tree_async(
+        &self,
+        name: &RepoPathComponent,
+    ) -> BackendResult<Option<MergedTree>> {
         match self.value(name).into_resolved() {
             Ok(Some(TreeValue::Tree(sub_tree_id))) => {
                 let subdir = self.dir().join(name);
-                Ok(Some(MergedTree::resolved(
-                    self.store().get_tree(subdir, sub_tree_id)?,
-                )))
+                let tree = self.store().get_tree_async(subdir, sub_tree_id).await?;
+                Ok(Some(MergedTree::resolved(tree)))
             }
             Ok(_) => Ok(None),
             Err(merge) => {
-                let trees = merge.try_map(|value| match value {
-                    Some(TreeValue::Tree(sub_tree_id)) => {
+                let trees = merge
+                    .try_map_async(|value| async move {
                         let subdir = self.dir().join(name);
-                        self.store().get_tree(subdir, sub_tree_id)
-                    }
-                    _ => {
-                        let subdir = self.dir().join(name);
-                        Ok(Tree::empty(self.store().clone(), subdir))
-                    }
-                })?;
+                        match value {
+                            Some(TreeValue::Tree(sub_tree_id)) => {
+                                self.store().get_tree_async(subdir, sub_tree_id).await
+                            }
+                            _ => Ok(Tree::empty(self.store().clone(), subdir)),
+                        }
+                    })
+                    .await?;
                 Ok(Some(MergedTree { trees }))
             }
         }
@@ -223,9 +239,14 @@ impl MergedTree {
     /// `self` is a `Conflict`, which happens if the value at the path can be
     /// trivially merged.
     pub fn path_value(&self, path: &RepoPath) -> BackendResult<MergedTreeValue> {
+        self.path_value_async(path).block_on()
+    }
+
+    /// Async version of [`MergedTree::path_value()`].
+    pub async fn path_value_async(&self, path: &RepoPath) -> BackendResult<MergedTreeValue> {
         assert_eq!(self.dir(), RepoPath::root());
         match path.split() {
-            Some((dir, basename)) => match self.sub_tree_recursive(dir)? {
+            Some((dir, basename)) => match self.sub_tree_recursive_async(dir).await? {
                 None => Ok(Merge::absent()),
                 Some(tree) => Ok(tree.value(basename).cloned()),
             },
@@ -242,9 +263,17 @@ impl MergedTree {
 
     /// Look up the tree at the given path.
     pub fn sub_tree_recursive(&self, path: &RepoPath) -> BackendResult<Option<MergedTree>> {
+        self.sub_tree_recursive_async(path).block_on()
+    }
+
+    /// Async version of [`MergedTree::sub_tree_recursive()`].
+    pub async fn sub_tree_recursive_async(
+        &self,
+        path: &RepoPath,
+    ) -> BackendResult<Option<MergedTree>> {
         let mut current_tree = self.clone();
         for name in path.components() {
-            match current_tree.sub_tree(name)? {
+            match current_tree.sub_tree_async(name).await? {
                 None => {
                     return Ok(None);
                 }
@@ -325,6 +354,15 @@ impl MergedTree {
         self.merge_no_resolve(base, other).resolve()
     }
 
+    /// Async version of [`MergedTree::merge()`].
+    pub async fn merge_async(
+        &self,
+        base: &MergedTree,
+        other: &MergedTree,
+    ) -> BackendResult<MergedTree> {
+        self.merge_no_resolve(base, other).resolve_async().await
+    }
+
     /// Merges this tree with `other`, using `base` as base, without attempting
     /// to resolve file conflicts.
     pub fn merge_no_resolve(&self, base: &MergedTree, other: &MergedTree) -> MergedTree {
@@ -438,7 +476,7 @@ fn trees_value<'a>(trees: &'a Merge<Tree>, basename: &RepoPathComponent) -> Merg
 
 /// The returned conflict will either be resolved or have the same number of
 /// sides as the input.
-fn merge_trees(merge: &Merge<Tree>) -> BackendResult<Merge<Tree>> {
+async fn merge_trees(merge: &Merge<Tree>) -> BackendResult<Merge<Tree>> {
     if let Some(tree) = merge.resolve_trivial() {
         return Ok(Merge::resolved(tree.clone()));
     }
@@ -451,10 +489,16 @@ fn merge_trees(merge: &Merge<Tree>) -> BackendResult<Merge<Tree>> {
     // any conflicts.
     let mut new_tree = backend::Tree::default();
     let mut conflicts = vec![];
-    // TODO: Merge values concurrently
-    for (basename, path_merge) in all_merged_tree_entries(merge) {
-        let path = dir.join(basename);
-        let path_merge = merge_tree_values(store, &path, &path_merge).block_on()?;
+    let entries = all_merged_tree_entries(merge).collect_vec();
+    let merged_values: Vec<_> = futures::stream::iter(&entries)
+        .map(|(basename, path_merge)| async move {
+            let path = dir.join(basename);
+            merge_tree_values(store, &path, path_merge).await
+        })
+        .buffered(store.concurrency().max(1))
+        .try_collect()
+        .await?;
+    for ((basename, _), path_merge) in zip(&entries, merged_values) {
         match path_merge.into_resolved() {
             Ok(value) => {
                 new_tree.set_or_remove(basename, value);
@@ -465,21 +509,26 @@ fn merge_trees(merge: &Merge<Tree>) -> BackendResult<Merge<Tree>> {
         };
     }
     if conflicts.is_empty() {
-        let new_tree_id = store.write_tree(dir, new_tree).block_on()?;
+        let new_tree_id = store.write_tree(dir, new_tree).await?;
         Ok(Merge::resolved(new_tree_id))
     } else {
         // For each side of the conflict, overwrite the entries in `new_tree` with the
         // values from  `conflicts`. Entries that are not in `conflicts` will remain
         // unchanged and will be reused for each side.
         let tree_count = merge.iter().len();
-        let mut new_trees = Vec::with_capacity(tree_count);
+        let mut new_tree_data = Vec::with_capacity(tree_count);
         for _ in 0..tree_count {
             for (basename, path_conflict) in &mut conflicts {
                 new_tree.set_or_remove(basename, path_conflict.next().unwrap());
             }
-            let tree = store.write_tree(dir, new_tree.clone()).block_on()?;
-            new_trees.push(tree);
+            new_tree_data.push(new_tree.clone());
         }
+        let new_trees = try_join_all(
+            new_tree_data
+                .into_iter()
+                .map(|data| store.write_tree(dir, data)),
+        )
+        .await?;
         Ok(Merge::from_vec(new_trees))
     }
 }
@@ -501,7 +550,8 @@ async fn merge_tree_values(
         // If all sides are trees or missing, merge the trees recursively, treating
         // missing trees as empty.
         let empty_tree_id = store.empty_tree_id();
-        let merged_tree = merge_trees(&trees)?;
+        // Boxed because the future is recursive
+        let merged_tree = Box::pin(merge_trees(&trees)).await?;
         Ok(merged_tree
             .map(|tree| (tree.id() != empty_tree_id).then(|| TreeValue::Tree(tree.id().clone()))))
     } else {
@@ -0,0 +1,1033 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Commit backend storing objects in a (remote) object store.
+//!
+//! Objects are encoded in the same format as the local backend, and stored
+//! under `<kind>/<hex id>` keys. Since objects are content-addressed, they are
+//! cached locally without invalidation.
+//!
+//! Large files are split into compressed chunks as in the local backend's
+//! chunk store, so they are streamed in and out of the object store in batches
+//! of chunks instead of being buffered as a whole.
+//!
+//! The object store client is abstracted by the [`ObjectStore`] trait. Only a
+//! filesystem-based store (e.g. a mounted bucket) is built in. Clients for
+//! cloud object stores can be plugged in by registering a backend factory
+//! that passes a custom [`ObjectStore`] to
+//! [`ObjectStoreBackend::with_object_store()`].
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::fs;
+use std::future::Future;
+use std::io;
+use std::io::Cursor;
+use std::io::Read;
+use std::io::Write as _;
+use std::mem;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use blake2::Blake2b512;
+use blake2::Digest as _;
+use futures::channel::oneshot;
+use futures::future;
+use futures::stream;
+use futures::stream::BoxStream;
+use pollster::FutureExt as _;
+use prost::Message as _;
+use tempfile::NamedTempFile;
+use thiserror::Error;
+
+use crate::backend::make_root_commit;
+use crate::backend::Backend;
+use crate::backend::BackendError;
+use crate::backend::BackendGcStats;
+use crate::backend::BackendInitError;
+use crate::backend::BackendLoadError;
+use crate::backend::BackendResult;
+use crate::backend::ChangeId;
+use crate::backend::Commit;
+use crate::backend::CommitId;
+use crate::backend::Conflict;
+use crate::backend::ConflictId;
+use crate::backend::CopyRecord;
+use crate::backend::FileId;
+use crate::backend::SecureSig;
+use crate::backend::SigningFn;
+use crate::backend::SymlinkId;
+use crate::backend::Tree;
+use crate::backend::TreeId;
+use crate::config::ConfigGetResultExt as _;
+use crate::content_hash::blake2b_hash;
+use crate::file_util::persist_content_addressed_temp_file;
+use crate::index::Index;
+use crate::local_backend::commit_from_proto;
+use crate::local_backend::commit_to_proto;
+use crate::local_backend::conflict_from_proto;
+use crate::local_backend::conflict_to_proto;
+use crate::local_backend::tree_from_proto;
+use crate::local_backend::tree_to_proto;
+use crate::local_backend::CHANGE_ID_LENGTH;
+use crate::local_backend::CHUNKED_FILE_SIZE_THRESHOLD;
+use crate::local_backend::COMMIT_ID_LENGTH;
+use crate::local_backend::EMPTY_TREE_ID_HEX;
+use crate::local_chunks::compress_chunk;
+use crate::local_chunks::decode_manifest;
+use crate::local_chunks::decompress_chunk;
+use crate::local_chunks::encode_manifest;
+use crate::local_chunks::split_into_chunks;
+use crate::local_chunks::ManifestEntry;
+use crate::object_id::ObjectId;
+use crate::repo_path::RepoPath;
+use crate::repo_path::RepoPathBuf;
+use crate::settings::UserSettings;
+
+const URL_FILE_NAME: &str = "object_store_url";
+const CACHE_DIR_NAME: &str = "cache";
+
+/// Error reported by [`ObjectStore`].
+#[derive(Debug, Error)]
+pub enum ObjectStoreError {
+    /// Error that may succeed if retried, such as timeout or throttling.
+    #[error("Transient object store error")]
+    Transient(#[source] Box<dyn std::error::Error + Send + Sync>),
+    /// Error that won't be resolved by retrying.
+    #[error("Object store error")]
+    Permanent(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Key-value storage of immutable blobs.
+#[async_trait]
+pub trait ObjectStore: Debug + Send + Sync {
+    /// Reads the blob at `key`. Returns `None` if the key doesn't exist.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, ObjectStoreError>;
+
+    /// Writes the blob at `key`. Since blobs are content-addressed, an
+    /// existing blob may be overwritten with the same data.
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), ObjectStoreError>;
+
+    /// Reads the blobs at `keys`. Returns `None` for keys that don't exist.
+    ///
+    /// By default, the blobs are requested concurrently. Clients of stores
+    /// with a batch API can override this to use fewer round trips.
+    async fn get_many(&self, keys: &[String]) -> Result<Vec<Option<Vec<u8>>>, ObjectStoreError> {
+        future::try_join_all(keys.iter().map(|key| self.get(key))).await
+    }
+
+    /// Writes the given blobs.
+    ///
+    /// By default, the blobs are written concurrently. Clients of stores with
+    /// a batch API can override this to use fewer round trips.
+    async fn put_many(&self, blobs: Vec<(String, Vec<u8>)>) -> Result<(), ObjectStoreError> {
+        future::try_join_all(
+            blobs
+                .into_iter()
+                .map(|(key, data)| async move { self.put(&key, data).await }),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Object store backed by a local directory, such as a mounted bucket or a
+/// network file system.
+#[derive(Debug)]
+pub struct FileSystemObjectStore {
+    root: PathBuf,
+}
+
+impl FileSystemObjectStore {
+    /// Creates store rooted at the given directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FileSystemObjectStore { root: root.into() }
+    }
+
+    fn map_io_err(err: io::Error) -> ObjectStoreError {
+        match err.kind() {
+            io::ErrorKind::Interrupted | io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => {
+                ObjectStoreError::Transient(err.into())
+            }
+            _ => ObjectStoreError::Permanent(err.into()),
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for FileSystemObjectStore {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, ObjectStoreError> {
+        match fs::read(self.root.join(key)) {
+            Ok(data) => Ok(Some(data)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(Self::map_io_err(err)),
+        }
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), ObjectStoreError> {
+        let path = self.root.join(key);
+        let dir = path.parent().unwrap();
+        fs::create_dir_all(dir).map_err(Self::map_io_err)?;
+        let mut temp_file = NamedTempFile::new_in(dir).map_err(Self::map_io_err)?;
+        temp_file.write_all(&data).map_err(Self::map_io_err)?;
+        persist_content_addressed_temp_file(temp_file, path).map_err(Self::map_io_err)?;
+        Ok(())
+    }
+}
+
+/// How failed requests to the object store are retried.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry. Doubled for each subsequent retry.
+    pub initial_backoff: Duration,
+    /// Upper bound of the delay.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn from_settings(settings: &UserSettings) -> Result<Self, BackendLoadError> {
+        let mut policy = RetryPolicy::default();
+        if let Some(max_attempts) = settings
+            .get::<u32>("object-store.max-attempts")
+            .optional()
+            .map_err(|err| BackendLoadError(err.into()))?
+        {
+            policy.max_attempts = max_attempts;
+        }
+        Ok(policy)
+    }
+}
+
+/// Options of [`ObjectStoreBackend`].
+#[derive(Clone, Debug)]
+pub struct ObjectStoreOptions {
+    /// Retry policy of requests.
+    pub retry: RetryPolicy,
+    /// Number of requests the backend is expected to issue concurrently. This
+    /// is also the number of chunks of a large file read or written in one
+    /// batch.
+    pub concurrency: usize,
+}
+
+impl Default for ObjectStoreOptions {
+    fn default() -> Self {
+        ObjectStoreOptions {
+            retry: RetryPolicy::default(),
+            concurrency: 32,
+        }
+    }
+}
+
+impl ObjectStoreOptions {
+    /// Loads options from `object-store.*` settings.
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, BackendLoadError> {
+        let mut options = ObjectStoreOptions {
+            retry: RetryPolicy::from_settings(settings)?,
+            ..Default::default()
+        };
+        if let Some(concurrency) = settings
+            .get::<usize>("object-store.concurrency")
+            .optional()
+            .map_err(|err| BackendLoadError(err.into()))?
+        {
+            if concurrency == 0 {
+                return Err(BackendLoadError(
+                    "object-store.concurrency must be a positive integer".into(),
+                ));
+            }
+            options.concurrency = concurrency;
+        }
+        Ok(options)
+    }
+}
+
+/// [`ObjectStore`] client that retries failed requests and caches the blobs
+/// locally.
+#[derive(Debug)]
+struct CachingObjectStore {
+    object_store: Arc<dyn ObjectStore>,
+    cache_path: PathBuf,
+    options: ObjectStoreOptions,
+}
+
+impl CachingObjectStore {
+    fn is_cached(&self, key: &str) -> bool {
+        self.cache_path.join(key).exists()
+    }
+
+    fn read_cache(&self, key: &str) -> Option<Vec<u8>> {
+        fs::read(self.cache_path.join(key)).ok()
+    }
+
+    fn write_cache(&self, key: &str, data: &[u8]) {
+        let write = || -> io::Result<()> {
+            let path = self.cache_path.join(key);
+            let dir = path.parent().unwrap();
+            fs::create_dir_all(dir)?;
+            let mut temp_file = NamedTempFile::new_in(dir)?;
+            temp_file.write_all(data)?;
+            persist_content_addressed_temp_file(temp_file, path)?;
+            Ok(())
+        };
+        // The cache is best-effort
+        if let Err(err) = write() {
+            tracing::warn!(?err, ?key, "failed to write object store cache");
+        }
+    }
+
+    /// Runs `f` until it succeeds or fails with a permanent error.
+    async fn with_retry<T, F: Future<Output = Result<T, ObjectStoreError>>>(
+        &self,
+        mut f: impl FnMut() -> F,
+    ) -> Result<T, ObjectStoreError> {
+        let policy = &self.options.retry;
+        let mut backoff = policy.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            match f().await {
+                Err(ObjectStoreError::Transient(err)) if attempt < policy.max_attempts => {
+                    tracing::debug!(?err, attempt, "retrying object store request");
+                    sleep(backoff).await;
+                    backoff = (backoff * 2).min(policy.max_backoff);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, ObjectStoreError> {
+        if let Some(data) = self.read_cache(key) {
+            return Ok(Some(data));
+        }
+        let data = self.with_retry(|| self.object_store.get(key)).await?;
+        if let Some(data) = &data {
+            self.write_cache(key, data);
+        }
+        Ok(data)
+    }
+
+    /// Reads the blobs at `keys`, requesting the uncached ones in one batch.
+    async fn get_many(&self, keys: &[String]) -> Result<Vec<Option<Vec<u8>>>, ObjectStoreError> {
+        let mut blobs: Vec<_> = keys.iter().map(|key| self.read_cache(key)).collect();
+        let missing_indices: Vec<_> = (0..keys.len()).filter(|&i| blobs[i].is_none()).collect();
+        if missing_indices.is_empty() {
+            return Ok(blobs);
+        }
+        let missing_keys: Vec<_> = missing_indices.iter().map(|&i| keys[i].clone()).collect();
+        let fetched = self
+            .with_retry(|| self.object_store.get_many(&missing_keys))
+            .await?;
+        for (i, data) in missing_indices.into_iter().zip(fetched) {
+            if let Some(data) = &data {
+                self.write_cache(&keys[i], data);
+            }
+            blobs[i] = data;
+        }
+        Ok(blobs)
+    }
+
+    /// Writes the uncached blobs in one batch.
+    async fn put_many(&self, blobs: Vec<(String, Vec<u8>)>) -> Result<(), ObjectStoreError> {
+        let blobs: Vec<_> = blobs
+            .into_iter()
+            .filter(|(key, _)| !self.is_cached(key))
+            .collect();
+        if blobs.is_empty() {
+            return Ok(());
+        }
+        self.with_retry(|| self.object_store.put_many(blobs.clone()))
+            .await?;
+        for (key, data) in &blobs {
+            self.write_cache(key, data);
+        }
+        Ok(())
+    }
+}
+
+/// Commit backend storing objects in an [`ObjectStore`] with a local cache.
+#[derive(Debug)]
+pub struct ObjectStoreBackend {
+    store: Arc<CachingObjectStore>,
+    root_commit_id: CommitId,
+    root_change_id: ChangeId,
+    empty_tree_id: TreeId,
+}
+
+impl ObjectStoreBackend {
+    /// "object-store"
+    pub fn name() -> &'static str {
+        "object-store"
+    }
+
+    /// Initializes backend which stores objects at the given URL. Only
+    /// `file://` URLs are supported by the built-in object store.
+    pub fn init(
+        settings: &UserSettings,
+        store_path: &Path,
+        url: &str,
+    ) -> Result<Self, BackendInitError> {
+        let object_store =
+            object_store_from_url(url).map_err(|BackendLoadError(err)| BackendInitError(err))?;
+        fs::write(store_path.join(URL_FILE_NAME), url)
+            .map_err(|err| BackendInitError(err.into()))?;
+        let options = ObjectStoreOptions::from_settings(settings)
+            .map_err(|BackendLoadError(err)| BackendInitError(err))?;
+        let backend = Self::with_object_store(store_path, object_store, options);
+        fs::create_dir_all(&backend.store.cache_path)
+            .map_err(|err| BackendInitError(err.into()))?;
+        let empty_tree_id = backend
+            .write_tree(RepoPath::root(), &Tree::default())
+            .block_on()
+            .map_err(|err| BackendInitError(err.into()))?;
+        assert_eq!(empty_tree_id, backend.empty_tree_id);
+        Ok(backend)
+    }
+
+    /// Loads backend using the object store URL recorded in the store
+    /// directory.
+    pub fn load(settings: &UserSettings, store_path: &Path) -> Result<Self, BackendLoadError> {
+        let url = fs::read_to_string(store_path.join(URL_FILE_NAME))
+            .map_err(|err| BackendLoadError(err.into()))?;
+        let object_store = object_store_from_url(url.trim())?;
+        let options = ObjectStoreOptions::from_settings(settings)?;
+        Ok(Self::with_object_store(store_path, object_store, options))
+    }
+
+    /// Creates backend with the given object store client. The local cache is
+    /// stored in `store_path`.
+    pub fn with_object_store(
+        store_path: &Path,
+        object_store: Arc<dyn ObjectStore>,
+        options: ObjectStoreOptions,
+    ) -> Self {
+        let store = CachingObjectStore {
+            object_store,
+            cache_path: store_path.join(CACHE_DIR_NAME),
+            options,
+        };
+        ObjectStoreBackend {
+            store: Arc::new(store),
+            root_commit_id: CommitId::from_bytes(&[0; COMMIT_ID_LENGTH]),
+            root_change_id: ChangeId::from_bytes(&[0; CHANGE_ID_LENGTH]),
+            empty_tree_id: TreeId::from_hex(EMPTY_TREE_ID_HEX),
+        }
+    }
+
+    fn object_key(id: &impl ObjectId) -> String {
+        format!("{}/{}", id.object_type(), id.hex())
+    }
+
+    fn manifest_key(id: &FileId) -> String {
+        format!("file-manifest/{}", id.hex())
+    }
+
+    fn chunk_key(hash: &[u8]) -> String {
+        format!("chunk/{}", hex::encode(hash))
+    }
+
+    async fn read_object(&self, id: &(impl ObjectId + Sync)) -> BackendResult<Vec<u8>> {
+        self.store
+            .get(&Self::object_key(id))
+            .await
+            .map_err(|err| BackendError::ReadObject {
+                object_type: id.object_type(),
+                hash: id.hex(),
+                source: err.into(),
+            })?
+            .ok_or_else(|| BackendError::ObjectNotFound {
+                object_type: id.object_type(),
+                hash: id.hex(),
+                source: "Object not found in object store".into(),
+            })
+    }
+
+    async fn write_object(
+        &self,
+        object_type: &'static str,
+        id: &(impl ObjectId + Sync),
+        data: Vec<u8>,
+    ) -> BackendResult<()> {
+        self.store
+            .put_many(vec![(Self::object_key(id), data)])
+            .await
+            .map_err(|err| BackendError::WriteObject {
+                object_type,
+                source: err.into(),
+            })
+    }
+
+    /// Uploads `contents` as chunks followed by a manifest listing them.
+    /// Chunks are uploaded in batches, so the file is never buffered as a
+    /// whole.
+    async fn write_chunked_file(&self, contents: impl Read) -> BackendResult<FileId> {
+        let to_write_err =
+            |err: Box<dyn std::error::Error + Send + Sync>| BackendError::WriteObject {
+                object_type: "file",
+                source: err,
+            };
+        let mut hasher = Blake2b512::new();
+        let mut entries = vec![];
+        let mut batch = vec![];
+        for chunk in split_into_chunks(contents) {
+            let chunk = chunk.map_err(|err| to_write_err(err.into()))?;
+            hasher.update(&chunk);
+            let hash = Blake2b512::digest(&chunk).to_vec();
+            let key = Self::chunk_key(&hash);
+            if !self.store.is_cached(&key) {
+                let compressed = compress_chunk(&chunk).map_err(|err| to_write_err(err.into()))?;
+                batch.push((key, compressed));
+            }
+            entries.push(ManifestEntry {
+                hash,
+                len: chunk.len() as u64,
+            });
+            if batch.len() >= self.store.options.concurrency {
+                self.store
+                    .put_many(mem::take(&mut batch))
+                    .await
+                    .map_err(|err| to_write_err(err.into()))?;
+            }
+        }
+        self.store
+            .put_many(batch)
+            .await
+            .map_err(|err| to_write_err(err.into()))?;
+        let id = FileId::new(hasher.finalize().to_vec());
+        // The manifest is written last, so the file becomes readable only when
+        // all of its chunks exist.
+        self.store
+            .put_many(vec![(Self::manifest_key(&id), encode_manifest(&entries))])
+            .await
+            .map_err(|err| to_write_err(err.into()))?;
+        Ok(id)
+    }
+}
+
+/// Reader of a file stored as chunks. Chunks are fetched in batches and
+/// decompressed on demand.
+#[derive(Debug)]
+struct ChunkedObjectReader {
+    store: Arc<CachingObjectStore>,
+    chunk_keys: VecDeque<String>,
+    fetched_chunks: VecDeque<Vec<u8>>,
+    current: Cursor<Vec<u8>>,
+}
+
+impl Read for ChunkedObjectReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let n = self.current.read(buf)?;
+            if n > 0 || buf.is_empty() {
+                return Ok(n);
+            }
+            if self.fetched_chunks.is_empty() {
+                if self.chunk_keys.is_empty() {
+                    return Ok(0);
+                }
+                let count = self.chunk_keys.len().min(self.store.options.concurrency);
+                let keys: Vec<_> = self.chunk_keys.drain(..count).collect();
+                let chunks = self
+                    .store
+                    .get_many(&keys)
+                    .block_on()
+                    .map_err(io::Error::other)?;
+                for (key, chunk) in keys.iter().zip(chunks) {
+                    let chunk = chunk.ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::NotFound,
+                            format!("Chunk {key} not found in object store"),
+                        )
+                    })?;
+                    self.fetched_chunks.push_back(chunk);
+                }
+            }
+            let compressed = self.fetched_chunks.pop_front().unwrap();
+            self.current = Cursor::new(decompress_chunk(&compressed)?);
+        }
+    }
+}
+
+/// Waits for the given duration without blocking the executor thread. The
+/// timer runs on a helper thread so this works with any executor.
+async fn sleep(duration: Duration) {
+    let (sender, receiver) = oneshot::channel();
+    thread::spawn(move || {
+        thread::sleep(duration);
+        sender.send(()).ok();
+    });
+    receiver.await.ok();
+}
+
+fn object_store_from_url(url: &str) -> Result<Arc<dyn ObjectStore>, BackendLoadError> {
+    if let Some(path) = url.strip_prefix("file://") {
+        Ok(Arc::new(FileSystemObjectStore::new(path)))
+    } else {
+        Err(BackendLoadError(
+            format!(
+                "Unsupported object store URL: {url} (only file:// URLs are built in; other \
+                 object stores require a custom backend factory)"
+            )
+            .into(),
+        ))
+    }
+}
+
+#[async_trait]
+impl Backend for ObjectStoreBackend {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        Self::name()
+    }
+
+    fn commit_id_length(&self) -> usize {
+        COMMIT_ID_LENGTH
+    }
+
+    fn change_id_length(&self) -> usize {
+        CHANGE_ID_LENGTH
+    }
+
+    fn root_commit_id(&self) -> &CommitId {
+        &self.root_commit_id
+    }
+
+    fn root_change_id(&self) -> &ChangeId {
+        &self.root_change_id
+    }
+
+    fn empty_tree_id(&self) -> &TreeId {
+        &self.empty_tree_id
+    }
+
+    fn concurrency(&self) -> usize {
+        self.store.options.concurrency
+    }
+
+    async fn read_file(&self, _path: &RepoPath, id: &FileId) -> BackendResult<Box<dyn Read>> {
+        let to_read_err = |err: ObjectStoreError| BackendError::ReadObject {
+            object_type: id.object_type(),
+            hash: id.hex(),
+            source: err.into(),
+        };
+        let manifest_key = Self::manifest_key(id);
+        // Small files are stored as a single object, large files as chunks.
+        let manifest = if self.store.is_cached(&manifest_key) {
+            self.store.get(&manifest_key).await.map_err(to_read_err)?
+        } else if let Some(data) = self
+            .store
+            .get(&Self::object_key(id))
+            .await
+            .map_err(to_read_err)?
+        {
+            return Ok(Box::new(Cursor::new(data)));
+        } else {
+            self.store.get(&manifest_key).await.map_err(to_read_err)?
+        };
+        let manifest = manifest.ok_or_else(|| BackendError::ObjectNotFound {
+            object_type: id.object_type(),
+            hash: id.hex(),
+            source: "Object not found in object store".into(),
+        })?;
+        let entries = decode_manifest(&manifest).map_err(|err| BackendError::ReadObject {
+            object_type: id.object_type(),
+            hash: id.hex(),
+            source: err.into(),
+        })?;
+        Ok(Box::new(ChunkedObjectReader {
+            store: self.store.clone(),
+            chunk_keys: entries
+                .iter()
+                .map(|entry| Self::chunk_key(&entry.hash))
+                .collect(),
+            fetched_chunks: VecDeque::new(),
+            current: Cursor::new(vec![]),
+        }))
+    }
+
+    async fn write_file(
+        &self,
+        _path: &RepoPath,
+        contents: &mut (dyn Read + Send),
+    ) -> BackendResult<FileId> {
+        let mut data = vec![];
+        (&mut *contents)
+            .take(CHUNKED_FILE_SIZE_THRESHOLD + 1)
+            .read_to_end(&mut data)
+            .map_err(|err| BackendError::Other(err.into()))?;
+        if data.len() as u64 > CHUNKED_FILE_SIZE_THRESHOLD {
+            return self
+                .write_chunked_file(Cursor::new(data).chain(contents))
+                .await;
+        }
+        let id = FileId::new(Blake2b512::digest(&data).to_vec());
+        self.write_object("file", &id, data).await?;
+        Ok(id)
+    }
+
+    async fn read_symlink(&self, _path: &RepoPath, id: &SymlinkId) -> BackendResult<String> {
+        let data = self.read_object(id).await?;
+        String::from_utf8(data).map_err(|err| BackendError::InvalidUtf8 {
+            object_type: id.object_type(),
+            hash: id.hex(),
+            source: err.utf8_error(),
+        })
+    }
+
+    async fn write_symlink(&self, _path: &RepoPath, target: &str) -> BackendResult<SymlinkId> {
+        let id = SymlinkId::new(Blake2b512::digest(target.as_bytes()).to_vec());
+        self.write_object("symlink", &id, target.as_bytes().to_vec())
+            .await?;
+        Ok(id)
+    }
+
+    async fn read_tree(&self, _path: &RepoPath, id: &TreeId) -> BackendResult<Tree> {
+        let data = self.read_object(id).await?;
+        let proto = crate::protos::local_store::Tree::decode(&*data)
+            .map_err(|err| BackendError::Other(err.into()))?;
+        Ok(tree_from_proto(proto))
+    }
+
+    async fn write_tree(&self, _path: &RepoPath, tree: &Tree) -> BackendResult<TreeId> {
+        let id = TreeId::new(blake2b_hash(tree).to_vec());
+        self.write_object("tree", &id, tree_to_proto(tree).encode_to_vec())
+            .await?;
+        Ok(id)
+    }
+
+    fn read_conflict(&self, _path: &RepoPath, id: &ConflictId) -> BackendResult<Conflict> {
+        let data = self.read_object(id).block_on()?;
+        let proto = crate::protos::local_store::Conflict::decode(&*data)
+            .map_err(|err| BackendError::Other(err.into()))?;
+        Ok(conflict_from_proto(proto))
+    }
+
+    fn write_conflict(&self, _path: &RepoPath, conflict: &Conflict) -> BackendResult<ConflictId> {
+        let id = ConflictId::new(blake2b_hash(conflict).to_vec());
+        self.write_object("conflict", &id, conflict_to_proto(conflict).encode_to_vec())
+            .block_on()?;
+        Ok(id)
+    }
+
+    async fn read_commit(&self, id: &CommitId) -> BackendResult<Commit> {
+        if *id == self.root_commit_id {
+            return Ok(make_root_commit(
+                self.root_change_id.clone(),
+                self.empty_tree_id.clone(),
+            ));
+        }
+        let data = self.read_object(id).await?;
+        let proto = crate::protos::local_store::Commit::decode(&*data)
+            .map_err(|err| BackendError::Other(err.into()))?;
+        Ok(commit_from_proto(proto))
+    }
+
+    async fn write_commit(
+        &self,
+        mut commit: Commit,
+        sign_with: Option<&mut SigningFn>,
+    ) -> BackendResult<(CommitId, Commit)> {
+        assert!(commit.secure_sig.is_none(), "commit.secure_sig was set");
+
+        if commit.parents.is_empty() {
+            return Err(BackendError::Other(
+                "Cannot write a commit with no parents".into(),
+            ));
+        }
+        let mut proto = commit_to_proto(&commit);
+        if let Some(sign) = sign_with {
+            let data = proto.encode_to_vec();
+            let sig = sign(&data).map_err(|err| BackendError::Other(err.into()))?;
+            proto.secure_sig = Some(sig.clone());
+            commit.secure_sig = Some(SecureSig { data, sig });
+        }
+        let id = CommitId::new(blake2b_hash(&commit).to_vec());
+        self.write_object("commit", &id, proto.encode_to_vec())
+            .await?;
+        Ok((id, commit))
+    }
+
+    fn get_copy_records(
+        &self,
+        _paths: Option<&[RepoPathBuf]>,
+        _root: &CommitId,
+        _head: &CommitId,
+    ) -> BackendResult<BoxStream<BackendResult<CopyRecord>>> {
+        Ok(Box::pin(stream::empty()))
+    }
+
+    fn gc(&self, _index: &dyn Index, _keep_newer: SystemTime) -> BackendResult<()> {
+        // Objects may be shared with other repos, so nothing is deleted from
+        // the object store. The local cache can be rebuilt at any time.
+        Ok(())
+    }
+
+    fn gc_dry_run(
+        &self,
+        _index: &dyn Index,
+        _keep_newer: SystemTime,
+    ) -> BackendResult<BackendGcStats> {
+        Ok(BackendGcStats {
+            expired_commit_ids: vec![],
+            reclaimable_bytes: Some(0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use assert_matches::assert_matches;
+    use rand::Rng as _;
+    use rand::SeedableRng as _;
+
+    use super::*;
+    use crate::backend::MergedTreeId;
+    use crate::backend::MillisSinceEpoch;
+    use crate::backend::Signature;
+    use crate::backend::Timestamp;
+
+    /// Object store that fails the first `failures` requests.
+    #[derive(Debug)]
+    struct FlakyObjectStore {
+        inner: FileSystemObjectStore,
+        failures: AtomicUsize,
+        requests: AtomicUsize,
+    }
+
+    impl FlakyObjectStore {
+        fn check(&self) -> Result<(), ObjectStoreError> {
+            self.requests.fetch_add(1, Ordering::Relaxed);
+            let remaining = self.failures.load(Ordering::Relaxed);
+            if remaining > 0 {
+                self.failures.store(remaining - 1, Ordering::Relaxed);
+                return Err(ObjectStoreError::Transient("throttled".into()));
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for FlakyObjectStore {
+        async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, ObjectStoreError> {
+            self.check()?;
+            self.inner.get(key).await
+        }
+
+        async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), ObjectStoreError> {
+            self.check()?;
+            self.inner.put(key, data).await
+        }
+
+        // Batches count as one request
+        async fn get_many(
+            &self,
+            keys: &[String],
+        ) -> Result<Vec<Option<Vec<u8>>>, ObjectStoreError> {
+            self.check()?;
+            self.inner.get_many(keys).await
+        }
+
+        async fn put_many(&self, blobs: Vec<(String, Vec<u8>)>) -> Result<(), ObjectStoreError> {
+            self.check()?;
+            self.inner.put_many(blobs).await
+        }
+    }
+
+    fn test_options() -> ObjectStoreOptions {
+        ObjectStoreOptions {
+            retry: RetryPolicy {
+                max_attempts: 3,
+                initial_backoff: Duration::ZERO,
+                max_backoff: Duration::ZERO,
+            },
+            concurrency: 2,
+        }
+    }
+
+    fn create_signature() -> Signature {
+        Signature {
+            name: "Someone".to_string(),
+            email: "someone@example.com".to_string(),
+            timestamp: Timestamp {
+                timestamp: MillisSinceEpoch(0),
+                tz_offset: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_write_read_with_cache_and_retry() {
+        let temp_dir = testutils::new_temp_dir();
+        let bucket_path = temp_dir.path().join("bucket");
+        let object_store = Arc::new(FlakyObjectStore {
+            inner: FileSystemObjectStore::new(&bucket_path),
+            failures: AtomicUsize::new(0),
+            requests: AtomicUsize::new(0),
+        });
+        let store_path1 = temp_dir.path().join("store1");
+        fs::create_dir(&store_path1).unwrap();
+        let backend1 = ObjectStoreBackend::with_object_store(
+            &store_path1,
+            object_store.clone(),
+            test_options(),
+        );
+
+        let file_path = RepoPath::from_internal_string("file");
+        object_store.failures.store(2, Ordering::Relaxed);
+        let file_id = backend1
+            .write_file(file_path, &mut &b"content"[..])
+            .block_on()
+            .unwrap();
+        assert_eq!(object_store.requests.load(Ordering::Relaxed), 3);
+
+        let commit = Commit {
+            parents: vec![backend1.root_commit_id().clone()],
+            predecessors: vec![],
+            root_tree: MergedTreeId::resolved(backend1.empty_tree_id().clone()),
+            change_id: ChangeId::from_hex("abc123"),
+            description: "".to_string(),
+            author: create_signature(),
+            committer: create_signature(),
+            secure_sig: None,
+        };
+        let (commit_id, _) = backend1
+            .write_commit(commit.clone(), None)
+            .block_on()
+            .unwrap();
+
+        // Another workspace sharing the object store can read the objects
+        let store_path2 = temp_dir.path().join("store2");
+        fs::create_dir(&store_path2).unwrap();
+        let backend2 = ObjectStoreBackend::with_object_store(
+            &store_path2,
+            object_store.clone(),
+            test_options(),
+        );
+        assert_eq!(backend2.read_commit(&commit_id).block_on().unwrap(), commit);
+        let requests = object_store.requests.load(Ordering::Relaxed);
+        assert_eq!(backend2.read_commit(&commit_id).block_on().unwrap(), commit);
+        // Served by the cache
+        assert_eq!(object_store.requests.load(Ordering::Relaxed), requests);
+
+        let mut content = vec![];
+        backend2
+            .read_file(file_path, &file_id)
+            .block_on()
+            .unwrap()
+            .read_to_end(&mut content)
+            .unwrap();
+        assert_eq!(content, b"content");
+
+        // Retries are exhausted
+        object_store.failures.store(3, Ordering::Relaxed);
+        let file_id2 = FileId::new(vec![1; 64]);
+        assert_matches!(
+            backend2.read_file(file_path, &file_id2).block_on(),
+            Err(BackendError::ReadObject { .. })
+        );
+        assert_matches!(
+            backend2.read_file(file_path, &file_id2).block_on(),
+            Err(BackendError::ObjectNotFound { .. })
+        );
+    }
+
+    #[test]
+    fn test_write_read_large_file_in_batches() {
+        let temp_dir = testutils::new_temp_dir();
+        let bucket_path = temp_dir.path().join("bucket");
+        let object_store = Arc::new(FlakyObjectStore {
+            inner: FileSystemObjectStore::new(&bucket_path),
+            failures: AtomicUsize::new(0),
+            requests: AtomicUsize::new(0),
+        });
+        let store_path1 = temp_dir.path().join("store1");
+        fs::create_dir(&store_path1).unwrap();
+        let backend1 = ObjectStoreBackend::with_object_store(
+            &store_path1,
+            object_store.clone(),
+            test_options(),
+        );
+
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(0);
+        let mut content = vec![0; 4 << 20];
+        rng.fill(content.as_mut_slice());
+        let file_path = RepoPath::from_internal_string("file");
+        let file_id = backend1
+            .write_file(file_path, &mut content.as_slice())
+            .block_on()
+            .unwrap();
+        assert_eq!(file_id, FileId::new(Blake2b512::digest(&content).to_vec()));
+
+        // The file is stored as chunks, uploaded two at a time, followed by
+        // the manifest
+        assert!(!bucket_path
+            .join(ObjectStoreBackend::object_key(&file_id))
+            .exists());
+        let manifest =
+            fs::read(bucket_path.join(ObjectStoreBackend::manifest_key(&file_id))).unwrap();
+        let chunk_count = decode_manifest(&manifest).unwrap().len();
+        assert!(chunk_count > 2);
+        assert_eq!(
+            object_store.requests.load(Ordering::Relaxed),
+            chunk_count.div_ceil(2) + 1
+        );
+
+        // Another workspace downloads the chunks in batches
+        let store_path2 = temp_dir.path().join("store2");
+        fs::create_dir(&store_path2).unwrap();
+        let backend2 = ObjectStoreBackend::with_object_store(
+            &store_path2,
+            object_store.clone(),
+            test_options(),
+        );
+        let requests = object_store.requests.load(Ordering::Relaxed);
+        let mut read_content = vec![];
+        backend2
+            .read_file(file_path, &file_id)
+            .block_on()
+            .unwrap()
+            .read_to_end(&mut read_content)
+            .unwrap();
+        assert!(read_content == content);
+        // The single-object key and the manifest are looked up first
+        assert_eq!(
+            object_store.requests.load(Ordering::Relaxed) - requests,
+            2 + chunk_count.div_ceil(2)
+        );
+    }
+}
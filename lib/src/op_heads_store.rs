@@ -18,6 +18,8 @@ use std::any::Any;
 use std::collections::HashSet;
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 use itertools::Itertools;
 use thiserror::Error;
@@ -73,6 +75,69 @@ pub trait OpHeadsStore: Send + Sync + Debug {
     fn lock(&self) -> Result<Box<dyn OpHeadsStoreLock + '_>, OpHeadsStoreError>;
 }
 
+/// Watches an `OpHeadsStore` for new operations committed by any process.
+///
+/// Changes are detected by polling the op heads, so this works with any
+/// `OpHeadsStore` implementation.
+#[derive(Debug)]
+pub struct OpHeadsWatcher {
+    op_heads_store: Arc<dyn OpHeadsStore>,
+    poll_interval: Duration,
+    op_heads: Vec<OperationId>,
+}
+
+impl OpHeadsWatcher {
+    /// Creates a watcher which reports changes relative to the current op
+    /// heads.
+    pub fn new(
+        op_heads_store: Arc<dyn OpHeadsStore>,
+        poll_interval: Duration,
+    ) -> Result<Self, OpHeadsStoreError> {
+        let op_heads = get_sorted_op_heads(op_heads_store.as_ref())?;
+        Ok(OpHeadsWatcher {
+            op_heads_store,
+            poll_interval,
+            op_heads,
+        })
+    }
+
+    /// The op heads as of the last check, sorted by id.
+    pub fn op_heads(&self) -> &[OperationId] {
+        &self.op_heads
+    }
+
+    /// Checks the op heads without blocking. Returns the new op heads if they
+    /// changed since the last check.
+    pub fn poll(&mut self) -> Result<Option<&[OperationId]>, OpHeadsStoreError> {
+        let op_heads = get_sorted_op_heads(self.op_heads_store.as_ref())?;
+        // The heads can be transiently empty while another process is updating
+        // them. See resolve_op_heads().
+        if op_heads.is_empty() || op_heads == self.op_heads {
+            return Ok(None);
+        }
+        self.op_heads = op_heads;
+        Ok(Some(&self.op_heads))
+    }
+
+    /// Blocks until the op heads change, and returns the new op heads.
+    pub fn wait(&mut self) -> Result<&[OperationId], OpHeadsStoreError> {
+        loop {
+            if self.poll()?.is_some() {
+                return Ok(&self.op_heads);
+            }
+            thread::sleep(self.poll_interval);
+        }
+    }
+}
+
+fn get_sorted_op_heads(
+    op_heads_store: &dyn OpHeadsStore,
+) -> Result<Vec<OperationId>, OpHeadsStoreError> {
+    let mut op_heads = op_heads_store.get_op_heads()?;
+    op_heads.sort();
+    Ok(op_heads)
+}
+
 // Given an OpHeadsStore, fetch and resolve its op heads down to one under a
 // lock.
 //
@@ -467,6 +467,18 @@ pub enum OpStoreError {
 
 pub type OpStoreResult<T> = Result<T, OpStoreError>;
 
+/// Operations and views that [`OpStore::gc()`] would remove, as reported by
+/// [`OpStore::gc_dry_run()`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OpStoreGcStats {
+    /// Operations that would be removed.
+    pub expired_operation_ids: Vec<OperationId>,
+    /// Number of views that would be removed.
+    pub expired_view_count: usize,
+    /// Total size of the operation and view files in bytes, if known.
+    pub reclaimable_bytes: Option<u64>,
+}
+
 pub trait OpStore: Send + Sync + Debug {
     fn as_any(&self) -> &dyn Any;
 
@@ -488,6 +500,12 @@ pub trait OpStore: Send + Sync + Debug {
         prefix: &HexPrefix,
     ) -> OpStoreResult<PrefixResolution<OperationId>>;
 
+    /// Returns the IDs of all operations in the store, including unreachable
+    /// ones. These are the operations which
+    /// [`OpStore::resolve_operation_id_prefix()`] disambiguates prefixes
+    /// against.
+    fn operation_ids(&self) -> OpStoreResult<Vec<OperationId>>;
+
     /// Prunes unreachable operations and views.
     ///
     /// All operations and views reachable from the `head_ids` won't be
@@ -496,6 +514,18 @@ pub trait OpStore: Send + Sync + Debug {
     /// concurrently by another process.
     // TODO: return stats?
     fn gc(&self, head_ids: &[OperationId], keep_newer: SystemTime) -> OpStoreResult<()>;
+
+    /// Reports what [`OpStore::gc()`] would remove with the same arguments,
+    /// without removing anything.
+    ///
+    /// The default implementation reports nothing, with unknown size.
+    fn gc_dry_run(
+        &self,
+        _head_ids: &[OperationId],
+        _keep_newer: SystemTime,
+    ) -> OpStoreResult<OpStoreGcStats> {
+        Ok(OpStoreGcStats::default())
+    }
 }
 
 #[cfg(test)]
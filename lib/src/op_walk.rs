@@ -35,13 +35,22 @@ use crate::op_store::OpStoreError;
 use crate::op_store::OpStoreResult;
 use crate::op_store::OperationId;
 use crate::operation::Operation;
+use crate::opset;
+use crate::opset::NeighborOp;
+use crate::opset::OpsetExpression;
+use crate::opset::OpsetParseError;
+use crate::opset::ParsedOpset;
 use crate::repo::ReadonlyRepo;
 use crate::repo::Repo as _;
 use crate::repo::RepoLoader;
+use crate::time_util::DatePatternContext;
 
 /// Error that may occur during evaluation of operation set expression.
 #[derive(Debug, Error)]
 pub enum OpsetEvaluationError {
+    /// Failed to parse operation set expression.
+    #[error(transparent)]
+    OpsetParse(#[from] OpsetParseError),
     /// Failed to resolve operation set expression.
     #[error(transparent)]
     OpsetResolution(#[from] OpsetResolutionError),
@@ -85,9 +94,12 @@ pub enum OpsetResolutionError {
 }
 
 /// Resolves operation set expression without loading a repo.
+///
+/// Dates in the expression are interpreted relative to the `date_context`.
 pub fn resolve_op_for_load(
     repo_loader: &RepoLoader,
     op_str: &str,
+    date_context: &DatePatternContext,
 ) -> Result<Operation, OpsetEvaluationError> {
     let op_store = repo_loader.op_store();
     let op_heads_store = repo_loader.op_heads_store().as_ref();
@@ -101,7 +113,8 @@ pub fn resolve_op_for_load(
         })
     };
     let get_head_ops = || get_current_head_ops(op_store, op_heads_store);
-    resolve_single_op(op_store, get_current_op, get_head_ops, op_str)
+    let expression = parse_single_op(op_str, date_context)?;
+    resolve_single_op(op_store, get_current_op, get_head_ops, &expression, op_str)
 }
 
 /// Resolves operation set expression against the loaded repo.
@@ -110,8 +123,29 @@ pub fn resolve_op_for_load(
 pub fn resolve_op_with_repo(
     repo: &ReadonlyRepo,
     op_str: &str,
+    date_context: &DatePatternContext,
 ) -> Result<Operation, OpsetEvaluationError> {
-    resolve_op_at(repo.op_store(), slice::from_ref(repo.operation()), op_str)
+    resolve_op_at(
+        repo.op_store(),
+        slice::from_ref(repo.operation()),
+        op_str,
+        date_context,
+    )
+}
+
+/// Resolves parsed operation set expression against the loaded repo.
+///
+/// The "@" symbol will be resolved to the operation the repo was loaded at.
+pub fn resolve_parsed_op_with_repo(
+    repo: &ReadonlyRepo,
+    opset: &ParsedOpset,
+) -> Result<Operation, OpsetEvaluationError> {
+    resolve_expression_at(
+        repo.op_store(),
+        slice::from_ref(repo.operation()),
+        opset.expression(),
+        opset.text(),
+    )
 }
 
 /// Resolves operation set expression at the given head operations.
@@ -119,8 +153,30 @@ pub fn resolve_op_at(
     op_store: &Arc<dyn OpStore>,
     head_ops: &[Operation],
     op_str: &str,
+    date_context: &DatePatternContext,
 ) -> Result<Operation, OpsetEvaluationError> {
-    let get_current_op = || match head_ops {
+    let expression = parse_single_op(op_str, date_context)?;
+    resolve_expression_at(op_store, head_ops, &expression, op_str)
+}
+
+/// Resolves parsed operation set expression at the given head operations.
+///
+/// The `op_str` is the source text of the expression, which is used in error
+/// messages.
+pub fn resolve_expression_at(
+    op_store: &Arc<dyn OpStore>,
+    head_ops: &[Operation],
+    expression: &OpsetExpression,
+    op_str: &str,
+) -> Result<Operation, OpsetEvaluationError> {
+    let get_current_op = || single_head_op(head_ops);
+    let get_head_ops = || Ok(head_ops.to_vec());
+    resolve_single_op(op_store, get_current_op, get_head_ops, expression, op_str)
+}
+
+/// Resolves the "@" symbol at the given head operations.
+fn single_head_op(head_ops: &[Operation]) -> Result<Operation, OpsetEvaluationError> {
+    match head_ops {
         [head_op] => Ok(head_op.clone()),
         [] => Err(OpsetResolutionError::EmptyOperations("@".to_owned()).into()),
         _ => Err(OpsetResolutionError::MultipleOperations {
@@ -128,31 +184,95 @@ pub fn resolve_op_at(
             candidates: head_ops.iter().map(|op| op.id().clone()).collect(),
         }
         .into()),
-    };
-    let get_head_ops = || Ok(head_ops.to_vec());
-    resolve_single_op(op_store, get_current_op, get_head_ops, op_str)
+    }
+}
+
+fn parse_single_op(
+    op_str: &str,
+    date_context: &DatePatternContext,
+) -> Result<OpsetExpression, OpsetEvaluationError> {
+    // An empty string isn't a valid expression, but report it as an invalid
+    // operation ID as it would be passed where an ID is expected.
+    if op_str.is_empty() {
+        return Err(OpsetResolutionError::InvalidIdPrefix(op_str.to_owned()).into());
+    }
+    Ok(opset::parse(op_str, date_context)?)
 }
 
 /// Resolves operation set expression with the given "@" symbol resolution
-/// callbacks.
+/// callbacks. The expression must resolve to exactly one operation.
 fn resolve_single_op(
     op_store: &Arc<dyn OpStore>,
-    get_current_op: impl FnOnce() -> Result<Operation, OpsetEvaluationError>,
-    get_head_ops: impl FnOnce() -> Result<Vec<Operation>, OpsetEvaluationError>,
+    get_current_op: impl Fn() -> Result<Operation, OpsetEvaluationError>,
+    get_head_ops: impl Fn() -> Result<Vec<Operation>, OpsetEvaluationError>,
+    expression: &OpsetExpression,
     op_str: &str,
 ) -> Result<Operation, OpsetEvaluationError> {
-    let op_symbol = op_str.trim_end_matches(['-', '+']);
-    let op_postfix = &op_str[op_symbol.len()..];
-    let head_ops = op_postfix.contains('+').then(get_head_ops).transpose()?;
-    let mut operation = match op_symbol {
+    let resolve_symbol = |symbol: &str| resolve_op_symbol(op_store, &get_current_op, symbol);
+    if let Some((symbol, neighbor_ops)) = expression.as_neighbors_of_symbol() {
+        // Resolve neighbors directly, so the operation needn't be reachable
+        // from the heads.
+        let operation = resolve_symbol(symbol)?;
+        return resolve_neighbor_ops(operation, &neighbor_ops, &get_head_ops, op_str);
+    }
+    let mut ops: Vec<_> = expression
+        .evaluate(&get_head_ops()?, &resolve_symbol)?
+        .try_collect()?;
+    match ops.len() {
+        0 => Err(OpsetResolutionError::EmptyOperations(op_str.to_owned()).into()),
+        1 => Ok(ops.pop().unwrap()),
+        _ => Err(OpsetResolutionError::MultipleOperations {
+            expr: op_str.to_owned(),
+            candidates: ops.iter().map(|op| op.id().clone()).collect(),
+        }
+        .into()),
+    }
+}
+
+/// Resolves operation set expression at the given head operations, which may
+/// select any number of operations.
+///
+/// Operations are returned in reverse topological order. The operation log is
+/// walked lazily as the returned iterator is consumed.
+pub fn resolve_opset_at<'a>(
+    op_store: &Arc<dyn OpStore>,
+    head_ops: &[Operation],
+    expression: &'a OpsetExpression,
+) -> Result<impl Iterator<Item = Result<Operation, OpsetEvaluationError>> + 'a, OpsetEvaluationError>
+{
+    let get_current_op = || single_head_op(head_ops);
+    let resolve_symbol = |symbol: &str| resolve_op_symbol(op_store, &get_current_op, symbol);
+    expression.evaluate(head_ops, &resolve_symbol)
+}
+
+/// Resolves a single operation symbol, `@` or an operation ID prefix.
+fn resolve_op_symbol(
+    op_store: &Arc<dyn OpStore>,
+    get_current_op: &dyn Fn() -> Result<Operation, OpsetEvaluationError>,
+    symbol: &str,
+) -> Result<Operation, OpsetEvaluationError> {
+    match symbol {
         "@" => get_current_op(),
         s => resolve_single_op_from_store(op_store, s),
-    }?;
-    for c in op_postfix.chars() {
-        let mut neighbor_ops = match c {
-            '-' => operation.parents().try_collect()?,
-            '+' => find_child_ops(head_ops.as_ref().unwrap(), operation.id())?,
-            _ => unreachable!(),
+    }
+}
+
+/// Applies the `-`/`+` operators to the `operation`. Each step must resolve to
+/// exactly one operation.
+fn resolve_neighbor_ops(
+    mut operation: Operation,
+    neighbor_ops: &[NeighborOp],
+    get_head_ops: &dyn Fn() -> Result<Vec<Operation>, OpsetEvaluationError>,
+    op_str: &str,
+) -> Result<Operation, OpsetEvaluationError> {
+    let head_ops = neighbor_ops
+        .contains(&NeighborOp::Children)
+        .then(get_head_ops)
+        .transpose()?;
+    for neighbor_op in neighbor_ops {
+        let mut neighbor_ops = match neighbor_op {
+            NeighborOp::Parents => operation.parents().try_collect()?,
+            NeighborOp::Children => find_child_ops(head_ops.as_ref().unwrap(), operation.id())?,
         };
         operation = match neighbor_ops.len() {
             0 => Err(OpsetResolutionError::EmptyOperations(op_str.to_owned()))?,
@@ -0,0 +1,767 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Operation set language.
+//!
+//! An opset expression selects operations from the operation log, similar to
+//! how a revset selects commits. The supported syntax is:
+//!
+//! * `x`: operation ID prefix or `@`.
+//! * `x-`, `x+`: parent and child operations of `x`.
+//! * `x..y`: ancestors of `y` that aren't ancestors of `x`. Either side may
+//!   be omitted.
+//! * `x | y`, `x & y`, `x ~ y`, `~x`: union, intersection, difference, and
+//!   complement.
+//! * `all()`, `ancestors(x)`, `snapshots()`, `user(pattern)`,
+//!   `description(pattern)`, `after(date)`, `before(date)`: functions.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::iter;
+use std::iter::Peekable;
+use std::rc::Rc;
+
+use itertools::Itertools as _;
+use once_cell::sync::Lazy;
+
+use crate::dsl_util::collect_similar;
+use crate::op_store::OpStoreResult;
+use crate::op_store::OperationId;
+use crate::op_walk;
+use crate::op_walk::OpsetEvaluationError;
+use crate::operation::Operation;
+use crate::opset_parser;
+use crate::opset_parser::BinaryOp;
+use crate::opset_parser::ExpressionKind;
+use crate::opset_parser::ExpressionNode;
+use crate::opset_parser::FunctionCallNode;
+pub use crate::opset_parser::OpsetParseError;
+pub use crate::opset_parser::OpsetParseErrorKind;
+pub use crate::opset_parser::OpsetParseResult;
+use crate::opset_parser::UnaryOp;
+use crate::str_util::StringPattern;
+use crate::time_util::DatePattern;
+use crate::time_util::DatePatternContext;
+
+/// Parsed operation set expression.
+#[derive(Clone, Debug)]
+pub enum OpsetExpression {
+    /// Operation ID prefix or `@`.
+    Symbol(String),
+    /// All operations reachable from the head operations.
+    All,
+    /// Operations which are pure snapshots of the working copy.
+    Snapshots,
+    /// Operations whose `username@hostname` matches the pattern.
+    User(StringPattern),
+    /// Operations whose description matches the pattern.
+    Description(StringPattern),
+    /// Operations which started within the date range.
+    StartTime(DatePattern),
+    /// Parent operations.
+    Parents(Box<OpsetExpression>),
+    /// Child operations.
+    Children(Box<OpsetExpression>),
+    /// Ancestors of the operations, including themselves.
+    Ancestors(Box<OpsetExpression>),
+    /// Ancestors of `heads` that aren't ancestors of `roots`.
+    Range {
+        /// Excluded ancestors.
+        roots: Box<OpsetExpression>,
+        /// Included ancestors.
+        heads: Box<OpsetExpression>,
+    },
+    /// Union of the operations.
+    Union(Box<OpsetExpression>, Box<OpsetExpression>),
+    /// Intersection of the operations.
+    Intersection(Box<OpsetExpression>, Box<OpsetExpression>),
+    /// Operations in the first set but not in the second.
+    Difference(Box<OpsetExpression>, Box<OpsetExpression>),
+}
+
+impl OpsetExpression {
+    /// Evaluates the expression against the `head_ops` and their ancestors.
+    ///
+    /// Symbols are resolved by `resolve_symbol` upfront, but the operation log
+    /// is walked lazily as the returned iterator is consumed. Operations are
+    /// returned in reverse topological order, the same order as the operation
+    /// log.
+    pub fn evaluate<'a>(
+        &'a self,
+        head_ops: &[Operation],
+        resolve_symbol: &dyn Fn(&str) -> Result<Operation, OpsetEvaluationError>,
+    ) -> Result<
+        impl Iterator<Item = Result<Operation, OpsetEvaluationError>> + 'a,
+        OpsetEvaluationError,
+    > {
+        let walk = Rc::new(RefCell::new(OpWalk::new(head_ops)));
+        let evaluator = Evaluator {
+            walk: walk.clone(),
+            head_ops,
+            resolve_symbol,
+        };
+        let positions = evaluator.evaluate(self)?;
+        Ok(positions.map(move |pos| Ok(walk.borrow().op_at(pos?).clone())))
+    }
+
+    /// Returns the symbol and the `-`/`+` operators applied to it if this
+    /// expression selects neighbors of a single symbol. The operators are
+    /// listed in application order.
+    pub(crate) fn as_neighbors_of_symbol(&self) -> Option<(&str, Vec<NeighborOp>)> {
+        let mut ops = vec![];
+        let mut expression = self;
+        loop {
+            match expression {
+                OpsetExpression::Symbol(symbol) => {
+                    ops.reverse();
+                    return Some((symbol, ops));
+                }
+                OpsetExpression::Parents(inner) => {
+                    ops.push(NeighborOp::Parents);
+                    expression = inner;
+                }
+                OpsetExpression::Children(inner) => {
+                    ops.push(NeighborOp::Children);
+                    expression = inner;
+                }
+                _ => return None,
+            }
+        }
+    }
+}
+
+/// `-` or `+` operator applied to a symbol.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum NeighborOp {
+    Parents,
+    Children,
+}
+
+/// Opset expression paired with its source text.
+#[derive(Clone)]
+pub struct ParsedOpset {
+    text: String,
+    expression: OpsetExpression,
+}
+
+impl ParsedOpset {
+    /// Parses the `text` as an opset expression.
+    pub fn parse(text: &str, date_context: &DatePatternContext) -> OpsetParseResult<Self> {
+        let expression = parse(text, date_context)?;
+        Ok(ParsedOpset {
+            text: text.to_owned(),
+            expression,
+        })
+    }
+
+    /// Source text of the expression.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Parsed expression.
+    pub fn expression(&self) -> &OpsetExpression {
+        &self.expression
+    }
+}
+
+impl fmt::Debug for ParsedOpset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The expression is derived from the text.
+        fmt::Debug::fmt(&self.text, f)
+    }
+}
+
+/// Operations reachable from the head operations, loaded on demand.
+///
+/// Operations are numbered by their position in reverse topological order, so
+/// sets of operations can be evaluated as sorted streams of positions.
+struct OpWalk {
+    iter: Box<dyn Iterator<Item = OpStoreResult<Operation>>>,
+    ops: Vec<Operation>,
+    positions: HashMap<OperationId, usize>,
+}
+
+impl OpWalk {
+    fn new(head_ops: &[Operation]) -> Self {
+        OpWalk {
+            iter: Box::new(op_walk::walk_ancestors(head_ops)),
+            ops: vec![],
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Returns the operation at `pos`, or `None` if there are fewer
+    /// operations.
+    fn get(&mut self, pos: usize) -> OpStoreResult<Option<&Operation>> {
+        while self.ops.len() <= pos {
+            let Some(op) = self.iter.next().transpose()? else {
+                return Ok(None);
+            };
+            self.positions.insert(op.id().clone(), self.ops.len());
+            self.ops.push(op);
+        }
+        Ok(Some(&self.ops[pos]))
+    }
+
+    /// Returns the operation at `pos`, which must have been loaded.
+    fn op_at(&self, pos: usize) -> &Operation {
+        &self.ops[pos]
+    }
+
+    /// Looks up the position of the operation. Returns `None` if the
+    /// operation isn't reachable from the head operations.
+    fn position(&mut self, id: &OperationId) -> OpStoreResult<Option<usize>> {
+        loop {
+            if let Some(&pos) = self.positions.get(id) {
+                return Ok(Some(pos));
+            }
+            if self.get(self.ops.len())?.is_none() {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+type PositionIter<'a> = Box<dyn Iterator<Item = Result<usize, OpsetEvaluationError>> + 'a>;
+
+struct Evaluator<'b> {
+    walk: Rc<RefCell<OpWalk>>,
+    head_ops: &'b [Operation],
+    resolve_symbol: &'b dyn Fn(&str) -> Result<Operation, OpsetEvaluationError>,
+}
+
+impl Evaluator<'_> {
+    fn evaluate<'a>(
+        &self,
+        expression: &'a OpsetExpression,
+    ) -> Result<PositionIter<'a>, OpsetEvaluationError> {
+        let positions = match expression {
+            OpsetExpression::Symbol(symbol) => {
+                let op = (self.resolve_symbol)(symbol)?;
+                self.positions_of(vec![op])
+            }
+            OpsetExpression::All => self.all(),
+            OpsetExpression::Snapshots => self.filter(|op| op.metadata().is_snapshot),
+            OpsetExpression::User(pattern) => self.filter(move |op| {
+                let metadata = op.metadata();
+                pattern.matches(&format!("{}@{}", metadata.username, metadata.hostname))
+            }),
+            OpsetExpression::Description(pattern) => {
+                self.filter(move |op| pattern.matches(&op.metadata().description))
+            }
+            OpsetExpression::StartTime(pattern) => {
+                self.filter(move |op| pattern.matches(&op.metadata().start_time))
+            }
+            OpsetExpression::Parents(inner) => self.parents(self.evaluate(inner)?),
+            OpsetExpression::Children(inner) => self.children(self.evaluate(inner)?),
+            OpsetExpression::Ancestors(heads) => {
+                self.range(Box::new(iter::empty()), self.evaluate(heads)?)
+            }
+            OpsetExpression::Range { roots, heads } => {
+                let roots = self.evaluate(roots)?;
+                let heads = match heads.as_ref() {
+                    // All operations are ancestors of the head operations, which
+                    // are cheaper to walk from.
+                    OpsetExpression::All => self.positions_of(self.head_ops.to_vec()),
+                    heads => self.evaluate(heads)?,
+                };
+                self.range(roots, heads)
+            }
+            OpsetExpression::Union(expression1, expression2) => {
+                union_positions(self.evaluate(expression1)?, self.evaluate(expression2)?)
+            }
+            OpsetExpression::Intersection(expression1, expression2) => {
+                intersection_positions(self.evaluate(expression1)?, self.evaluate(expression2)?)
+            }
+            OpsetExpression::Difference(expression1, expression2) => {
+                difference_positions(self.evaluate(expression1)?, self.evaluate(expression2)?)
+            }
+        };
+        Ok(positions)
+    }
+
+    fn all(&self) -> PositionIter<'static> {
+        let walk = self.walk.clone();
+        Box::new((0..).map_while(move |pos| {
+            let mut walk = walk.borrow_mut();
+            walk.get(pos)
+                .map(|op| op.map(|_| pos))
+                .map_err(OpsetEvaluationError::from)
+                .transpose()
+        }))
+    }
+
+    fn filter<'a>(&self, predicate: impl Fn(&Operation) -> bool + 'a) -> PositionIter<'a> {
+        let walk = self.walk.clone();
+        Box::new(
+            self.all()
+                .filter_ok(move |&pos| predicate(walk.borrow().op_at(pos))),
+        )
+    }
+
+    /// Looks up positions of the `ops` on the first iteration. Operations
+    /// unreachable from the head operations are omitted.
+    fn positions_of(&self, ops: Vec<Operation>) -> PositionIter<'static> {
+        let walk = self.walk.clone();
+        let positions = iter::once_with(move || -> Result<Vec<usize>, OpsetEvaluationError> {
+            let mut walk = walk.borrow_mut();
+            let mut positions: Vec<usize> = ops
+                .iter()
+                .map(|op| walk.position(op.id()))
+                .flatten_ok()
+                .try_collect()?;
+            positions.sort_unstable();
+            positions.dedup();
+            Ok(positions)
+        });
+        Box::new(positions.flatten_ok())
+    }
+
+    fn parents<'a>(&self, inner: PositionIter<'a>) -> PositionIter<'a> {
+        let walk = self.walk.clone();
+        let mut inner = inner.peekable();
+        let mut pending = BinaryHeap::new();
+        from_step(move || loop {
+            let next_inner = match inner.peek() {
+                Some(Ok(pos)) => Some(*pos),
+                Some(Err(_)) => return inner.next().transpose(),
+                None => None,
+            };
+            // Parents are positioned after their children, so the pending
+            // parent can be emitted once the inner positions move past it.
+            let emit = match (pending.peek(), next_inner) {
+                (Some(&Reverse(pos)), None) => Some(pos),
+                (Some(&Reverse(pos)), Some(inner_pos)) if pos < inner_pos => Some(pos),
+                _ => None,
+            };
+            if let Some(pos) = emit {
+                while pending.peek() == Some(&Reverse(pos)) {
+                    pending.pop();
+                }
+                return Ok(Some(pos));
+            }
+            let Some(inner_pos) = next_inner else {
+                return Ok(None);
+            };
+            inner.next();
+            let mut walk = walk.borrow_mut();
+            let parent_ids = walk.op_at(inner_pos).parent_ids().to_vec();
+            for id in &parent_ids {
+                if let Some(pos) = walk.position(id)? {
+                    pending.push(Reverse(pos));
+                }
+            }
+        })
+    }
+
+    fn children<'a>(&self, inner: PositionIter<'a>) -> PositionIter<'a> {
+        let walk = self.walk.clone();
+        let mut inner = inner.peekable();
+        let mut members = HashSet::new();
+        let mut last_member = None;
+        let mut pos = 0;
+        from_step(move || loop {
+            // Children are positioned before their parents, so there are no
+            // more children after the last member.
+            if inner.peek().is_none() && last_member.map_or(true, |last| pos >= last) {
+                return Ok(None);
+            }
+            let current = pos;
+            pos += 1;
+            let parent_positions: Vec<usize> = {
+                let mut walk = walk.borrow_mut();
+                let Some(op) = walk.get(current)? else {
+                    return Ok(None);
+                };
+                let parent_ids = op.parent_ids().to_vec();
+                parent_ids
+                    .iter()
+                    .map(|id| walk.position(id))
+                    .flatten_ok()
+                    .try_collect()?
+            };
+            for parent_pos in parent_positions {
+                while let Some(member) =
+                    inner.next_if(|res| res.as_ref().map_or(true, |&pos| pos <= parent_pos))
+                {
+                    let member = member?;
+                    members.insert(member);
+                    last_member = Some(member);
+                }
+                if members.contains(&parent_pos) {
+                    return Ok(Some(current));
+                }
+            }
+        })
+    }
+
+    /// Walks ancestors of the `heads` excluding ancestors of the `roots`. The
+    /// walk stops as soon as no more operations can be reached from the
+    /// `heads`.
+    fn range<'a>(&self, roots: PositionIter<'a>, heads: PositionIter<'a>) -> PositionIter<'a> {
+        let walk = self.walk.clone();
+        let mut roots = roots.peekable();
+        let mut heads = heads.peekable();
+        // Unvisited operations reachable from the heads and the roots.
+        let mut included = HashSet::new();
+        let mut excluded = HashSet::new();
+        let mut pos = 0;
+        from_step(move || loop {
+            if included.is_empty() && heads.peek().is_none() {
+                return Ok(None);
+            }
+            let current = pos;
+            pos += 1;
+            let Some(op) = walk.borrow_mut().get(current)?.cloned() else {
+                return Ok(None);
+            };
+            let is_head = skip_to(&mut heads, current)?;
+            let is_root = skip_to(&mut roots, current)?;
+            let is_included = included.remove(op.id()) || is_head;
+            if excluded.remove(op.id()) || is_root {
+                excluded.extend(op.parent_ids().iter().cloned());
+            } else if is_included {
+                included.extend(op.parent_ids().iter().cloned());
+                return Ok(Some(current));
+            }
+        })
+    }
+}
+
+fn from_step<'a>(
+    mut next: impl FnMut() -> Result<Option<usize>, OpsetEvaluationError> + 'a,
+) -> PositionIter<'a> {
+    Box::new(iter::from_fn(move || next().transpose()))
+}
+
+/// Advances the sorted `positions` up to `pos`, and returns true if `pos` is
+/// included.
+fn skip_to(
+    positions: &mut Peekable<PositionIter<'_>>,
+    pos: usize,
+) -> Result<bool, OpsetEvaluationError> {
+    while let Some(res) = positions.next_if(|res| res.as_ref().map_or(true, |&p| p < pos)) {
+        res?;
+    }
+    Ok(positions
+        .next_if(|res| matches!(res, Ok(p) if *p == pos))
+        .is_some())
+}
+
+fn union_positions<'a>(iter1: PositionIter<'a>, iter2: PositionIter<'a>) -> PositionIter<'a> {
+    let mut iter1 = iter1.peekable();
+    let mut iter2 = iter2.peekable();
+    Box::new(iter::from_fn(move || match (iter1.peek(), iter2.peek()) {
+        (Some(Ok(pos1)), Some(Ok(pos2))) => match pos1.cmp(pos2) {
+            Ordering::Less => iter1.next(),
+            Ordering::Equal => {
+                iter2.next();
+                iter1.next()
+            }
+            Ordering::Greater => iter2.next(),
+        },
+        (Some(Err(_)), _) | (Some(_), None) => iter1.next(),
+        _ => iter2.next(),
+    }))
+}
+
+fn intersection_positions<'a>(
+    mut iter1: PositionIter<'a>,
+    iter2: PositionIter<'a>,
+) -> PositionIter<'a> {
+    let mut iter2 = iter2.peekable();
+    from_step(move || {
+        for pos in &mut iter1 {
+            let pos = pos?;
+            if iter2.peek().is_none() {
+                break;
+            }
+            if skip_to(&mut iter2, pos)? {
+                return Ok(Some(pos));
+            }
+        }
+        Ok(None)
+    })
+}
+
+fn difference_positions<'a>(
+    mut iter1: PositionIter<'a>,
+    iter2: PositionIter<'a>,
+) -> PositionIter<'a> {
+    let mut iter2 = iter2.peekable();
+    from_step(move || {
+        for pos in &mut iter1 {
+            let pos = pos?;
+            if !skip_to(&mut iter2, pos)? {
+                return Ok(Some(pos));
+            }
+        }
+        Ok(None)
+    })
+}
+
+type OpsetFunction =
+    fn(&FunctionCallNode, &DatePatternContext) -> OpsetParseResult<OpsetExpression>;
+
+static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, OpsetFunction>> = Lazy::new(|| {
+    // Not using maplit::hashmap!{} or custom declarative macro here because
+    // code completion inside macro is quite restricted.
+    let mut map: HashMap<&'static str, OpsetFunction> = HashMap::new();
+    map.insert("all", |function, _context| {
+        function.expect_no_arguments()?;
+        Ok(OpsetExpression::All)
+    });
+    map.insert("ancestors", |function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let heads = resolve_expression(arg, context)?;
+        Ok(OpsetExpression::Ancestors(Box::new(heads)))
+    });
+    map.insert("snapshots", |function, _context| {
+        function.expect_no_arguments()?;
+        Ok(OpsetExpression::Snapshots)
+    });
+    map.insert("user", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        Ok(OpsetExpression::User(expect_string_pattern(arg)?))
+    });
+    map.insert("description", |function, _context| {
+        let [arg] = function.expect_exact_arguments()?;
+        Ok(OpsetExpression::Description(expect_string_pattern(arg)?))
+    });
+    map.insert("after", |function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_date_pattern(arg, function.name, context)?;
+        Ok(OpsetExpression::StartTime(pattern))
+    });
+    map.insert("before", |function, context| {
+        let [arg] = function.expect_exact_arguments()?;
+        let pattern = expect_date_pattern(arg, function.name, context)?;
+        Ok(OpsetExpression::StartTime(pattern))
+    });
+    map
+});
+
+fn expect_string_pattern(node: &ExpressionNode) -> OpsetParseResult<StringPattern> {
+    opset_parser::expect_pattern_with("string pattern", node, |value, kind| match kind {
+        Some(kind) => StringPattern::from_str_kind(value, kind),
+        None => Ok(StringPattern::Substring(value.to_owned())),
+    })
+}
+
+fn expect_date_pattern(
+    node: &ExpressionNode,
+    function_name: &str,
+    context: &DatePatternContext,
+) -> OpsetParseResult<DatePattern> {
+    opset_parser::expect_pattern_with(
+        "date pattern",
+        node,
+        |value, kind| -> Result<_, Box<dyn std::error::Error + Send + Sync>> {
+            match kind {
+                None => Ok(context.parse_relative(value, function_name)?),
+                Some(_) => Err("Date pattern kind is specified by the function name".into()),
+            }
+        },
+    )
+}
+
+fn resolve_function(
+    function: &FunctionCallNode,
+    context: &DatePatternContext,
+) -> OpsetParseResult<OpsetExpression> {
+    if let Some(func) = BUILTIN_FUNCTION_MAP.get(function.name) {
+        func(function, context)
+    } else {
+        Err(OpsetParseError::new(
+            OpsetParseErrorKind::NoSuchFunction {
+                name: function.name.to_owned(),
+                candidates: collect_similar(function.name, BUILTIN_FUNCTION_MAP.keys()),
+            },
+            function.name_span,
+        ))
+    }
+}
+
+fn resolve_expression(
+    node: &ExpressionNode,
+    context: &DatePatternContext,
+) -> OpsetParseResult<OpsetExpression> {
+    let expression = match &node.kind {
+        ExpressionKind::Identifier(name) => OpsetExpression::Symbol((*name).to_owned()),
+        ExpressionKind::AtCurrent => OpsetExpression::Symbol("@".to_owned()),
+        ExpressionKind::String(name) => OpsetExpression::Symbol(name.clone()),
+        ExpressionKind::StringPattern { .. } => {
+            return Err(OpsetParseError::expression(
+                "String pattern is only allowed as a function argument",
+                node.span,
+            ));
+        }
+        ExpressionKind::RangeAll => OpsetExpression::All,
+        ExpressionKind::Unary(op, arg_node) => {
+            let arg = Box::new(resolve_expression(arg_node, context)?);
+            match op {
+                UnaryOp::Negate => OpsetExpression::Difference(Box::new(OpsetExpression::All), arg),
+                UnaryOp::RangePre => OpsetExpression::Ancestors(arg),
+                UnaryOp::RangePost => OpsetExpression::Range {
+                    roots: arg,
+                    heads: Box::new(OpsetExpression::All),
+                },
+                UnaryOp::Parents => OpsetExpression::Parents(arg),
+                UnaryOp::Children => OpsetExpression::Children(arg),
+            }
+        }
+        ExpressionKind::Binary(op, lhs_node, rhs_node) => {
+            let lhs = Box::new(resolve_expression(lhs_node, context)?);
+            let rhs = Box::new(resolve_expression(rhs_node, context)?);
+            match op {
+                BinaryOp::Intersection => OpsetExpression::Intersection(lhs, rhs),
+                BinaryOp::Difference => OpsetExpression::Difference(lhs, rhs),
+                BinaryOp::Range => OpsetExpression::Range {
+                    roots: lhs,
+                    heads: rhs,
+                },
+            }
+        }
+        ExpressionKind::UnionAll(nodes) => {
+            let expressions: Vec<_> = nodes
+                .iter()
+                .map(|node| resolve_expression(node, context))
+                .try_collect()?;
+            expressions
+                .into_iter()
+                .reduce(|lhs, rhs| OpsetExpression::Union(Box::new(lhs), Box::new(rhs)))
+                .expect("union should have at least two operands")
+        }
+        ExpressionKind::FunctionCall(function) => resolve_function(function, context)?,
+    };
+    Ok(expression)
+}
+
+/// Parses the `text` as an opset expression.
+///
+/// Dates in the expression are interpreted relative to the `date_context`.
+pub fn parse(text: &str, date_context: &DatePatternContext) -> OpsetParseResult<OpsetExpression> {
+    let node = opset_parser::parse_program(text)?;
+    resolve_expression(&node, date_context)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::DateTime;
+
+    use super::*;
+
+    fn parse_str(text: &str) -> OpsetParseResult<OpsetExpression> {
+        let now = DateTime::parse_from_rfc3339("2024-01-01T00:00:00+00:00").unwrap();
+        parse(text, &now.into())
+    }
+
+    #[test]
+    fn test_parse_symbol() {
+        assert_matches!(parse_str("@"), Ok(OpsetExpression::Symbol(s)) if s == "@");
+        assert_matches!(
+            parse_str(" abc123-+ ").unwrap().as_neighbors_of_symbol(),
+            Some(("abc123", ops)) if ops == [NeighborOp::Parents, NeighborOp::Children]
+        );
+        assert_matches!(
+            parse_str("user(user-name)"),
+            Ok(OpsetExpression::User(pattern)) if pattern.as_str() == "user-name"
+        );
+        assert_matches!(parse_str("(@ | abc)-").unwrap().as_neighbors_of_symbol(), None);
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_matches!(
+            parse_str("abc..@"),
+            Ok(OpsetExpression::Range { roots, heads })
+                if matches!(*roots, OpsetExpression::Symbol(ref s) if s == "abc")
+                && matches!(*heads, OpsetExpression::Symbol(ref s) if s == "@")
+        );
+        assert_matches!(
+            parse_str("@-.."),
+            Ok(OpsetExpression::Range { heads, .. })
+                if matches!(*heads, OpsetExpression::All)
+        );
+        assert_matches!(parse_str("..@"), Ok(OpsetExpression::Ancestors(_)));
+        assert_matches!(parse_str(".."), Ok(OpsetExpression::All));
+    }
+
+    #[test]
+    fn test_parse_operators() {
+        assert_matches!(
+            parse_str("snapshots() | @ & all()"),
+            Ok(OpsetExpression::Union(lhs, rhs))
+                if matches!(*lhs, OpsetExpression::Snapshots)
+                && matches!(*rhs, OpsetExpression::Intersection(..))
+        );
+        assert_matches!(
+            parse_str("~snapshots()"),
+            Ok(OpsetExpression::Difference(lhs, rhs))
+                if matches!(*lhs, OpsetExpression::All)
+                && matches!(*rhs, OpsetExpression::Snapshots)
+        );
+        assert_matches!(
+            parse_str(r#"ancestors(@-) ~ user(glob:"*@host")"#),
+            Ok(OpsetExpression::Difference(lhs, rhs))
+                if matches!(*lhs, OpsetExpression::Ancestors(_))
+                && matches!(*rhs, OpsetExpression::User(StringPattern::Glob(_)))
+        );
+        assert_matches!(
+            parse_str(r#"after("2023-12-01")"#),
+            Ok(OpsetExpression::StartTime(DatePattern::AtOrAfter(_)))
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_matches!(
+            parse_str(""),
+            Err(err) if *err.kind() == OpsetParseErrorKind::SyntaxError
+        );
+        assert_matches!(
+            parse_str("snapshot()"),
+            Err(err) if *err.kind() == OpsetParseErrorKind::NoSuchFunction {
+                name: "snapshot".to_owned(),
+                candidates: vec!["snapshots".to_owned()],
+            }
+        );
+        assert_matches!(
+            parse_str("all(@)"),
+            Err(err) if matches!(
+                err.kind(),
+                OpsetParseErrorKind::InvalidArguments { name, .. } if name == "all"
+            )
+        );
+        assert_matches!(
+            parse_str(r#"user(bad:"x")"#),
+            Err(err) if matches!(err.kind(), OpsetParseErrorKind::Expression(_))
+        );
+        assert_matches!(
+            parse_str(r#"after(at:"2023-12-01")"#),
+            Err(err) if matches!(err.kind(), OpsetParseErrorKind::Expression(_))
+        );
+        assert_matches!(
+            parse_str("glob:foo"),
+            Err(err) if matches!(err.kind(), OpsetParseErrorKind::Expression(_))
+        );
+    }
+}
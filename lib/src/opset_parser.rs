@@ -0,0 +1,493 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Parser for the opset language.
+
+use std::error;
+
+use itertools::Itertools as _;
+use once_cell::sync::Lazy;
+use pest::iterators::Pair;
+use pest::iterators::Pairs;
+use pest::pratt_parser::Assoc;
+use pest::pratt_parser::Op;
+use pest::pratt_parser::PrattParser;
+use pest::Parser;
+use pest_derive::Parser;
+use thiserror::Error;
+
+use crate::dsl_util;
+use crate::dsl_util::InvalidArguments;
+use crate::dsl_util::StringLiteralParser;
+
+#[derive(Parser)]
+#[grammar = "opset.pest"]
+struct OpsetParser;
+
+const STRING_LITERAL_PARSER: StringLiteralParser<Rule> = StringLiteralParser {
+    content_rule: Rule::string_content,
+    escape_rule: Rule::string_escape,
+};
+
+impl Rule {
+    fn to_symbol(self) -> Option<&'static str> {
+        match self {
+            Rule::EOI => None,
+            Rule::whitespace => None,
+            Rule::identifier_part => None,
+            Rule::identifier => None,
+            Rule::string_escape => None,
+            Rule::string_content_char => None,
+            Rule::string_content => None,
+            Rule::string_literal => None,
+            Rule::raw_string_content => None,
+            Rule::raw_string_literal => None,
+            Rule::at_op => Some("@"),
+            Rule::pattern_kind_op => Some(":"),
+            Rule::parents_op => Some("-"),
+            Rule::children_op => Some("+"),
+            Rule::range_op => Some(".."),
+            Rule::range_pre_op | Rule::range_post_op | Rule::range_all_op => Some(".."),
+            Rule::negate_op => Some("~"),
+            Rule::union_op => Some("|"),
+            Rule::intersection_op => Some("&"),
+            Rule::difference_op => Some("~"),
+            Rule::infix_op => None,
+            Rule::function => None,
+            Rule::function_name => None,
+            Rule::function_arguments => None,
+            Rule::string_pattern => None,
+            Rule::primary => None,
+            Rule::neighbors_expression => None,
+            Rule::range_expression => None,
+            Rule::expression => None,
+            Rule::program => None,
+        }
+    }
+}
+
+/// Result of opset parsing.
+pub type OpsetParseResult<T> = Result<T, OpsetParseError>;
+
+/// Error occurred during opset parsing.
+#[derive(Debug, Error)]
+#[error("{pest_error}")]
+pub struct OpsetParseError {
+    kind: OpsetParseErrorKind,
+    pest_error: Box<pest::error::Error<Rule>>,
+    source: Option<Box<dyn error::Error + Send + Sync>>,
+}
+
+/// Categories of opset parsing error.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum OpsetParseErrorKind {
+    #[error("Syntax error")]
+    SyntaxError,
+    #[error(r#"Function "{name}" doesn't exist"#)]
+    NoSuchFunction {
+        name: String,
+        candidates: Vec<String>,
+    },
+    #[error(r#"Function "{name}": {message}"#)]
+    InvalidArguments { name: String, message: String },
+    #[error("{0}")]
+    Expression(String),
+}
+
+impl OpsetParseError {
+    pub(super) fn new(kind: OpsetParseErrorKind, span: pest::Span<'_>) -> Self {
+        let message = kind.to_string();
+        let pest_error = Box::new(pest::error::Error::new_from_span(
+            pest::error::ErrorVariant::CustomError { message },
+            span,
+        ));
+        OpsetParseError {
+            kind,
+            pest_error,
+            source: None,
+        }
+    }
+
+    pub(super) fn with_source(
+        mut self,
+        source: impl Into<Box<dyn error::Error + Send + Sync>>,
+    ) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Some other expression error.
+    pub(super) fn expression(message: impl Into<String>, span: pest::Span<'_>) -> Self {
+        OpsetParseError::new(OpsetParseErrorKind::Expression(message.into()), span)
+    }
+
+    /// Category of the underlying error.
+    pub fn kind(&self) -> &OpsetParseErrorKind {
+        &self.kind
+    }
+}
+
+impl From<pest::error::Error<Rule>> for OpsetParseError {
+    fn from(err: pest::error::Error<Rule>) -> Self {
+        OpsetParseError {
+            kind: OpsetParseErrorKind::SyntaxError,
+            pest_error: Box::new(rename_rules_in_pest_error(err)),
+            source: None,
+        }
+    }
+}
+
+impl From<InvalidArguments<'_>> for OpsetParseError {
+    fn from(err: InvalidArguments<'_>) -> Self {
+        let kind = OpsetParseErrorKind::InvalidArguments {
+            name: err.name.to_owned(),
+            message: err.message,
+        };
+        Self::new(kind, err.span)
+    }
+}
+
+fn rename_rules_in_pest_error(err: pest::error::Error<Rule>) -> pest::error::Error<Rule> {
+    err.renamed_rules(|rule| {
+        rule.to_symbol()
+            .map(|sym| format!("`{sym}`"))
+            .unwrap_or_else(|| format!("<{rule:?}>"))
+    })
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExpressionKind<'i> {
+    /// Operation ID prefix or name.
+    Identifier(&'i str),
+    /// `@`
+    AtCurrent,
+    String(String),
+    StringPattern {
+        kind: &'i str,
+        value: String,
+    },
+    /// `..`
+    RangeAll,
+    Unary(UnaryOp, Box<ExpressionNode<'i>>),
+    Binary(BinaryOp, Box<ExpressionNode<'i>>, Box<ExpressionNode<'i>>),
+    /// `x | y | ..`
+    UnionAll(Vec<ExpressionNode<'i>>),
+    FunctionCall(Box<FunctionCallNode<'i>>),
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum UnaryOp {
+    /// `~`
+    Negate,
+    /// `..x`
+    RangePre,
+    /// `x..`
+    RangePost,
+    /// `x-`
+    Parents,
+    /// `x+`
+    Children,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BinaryOp {
+    /// `&`
+    Intersection,
+    /// `~`
+    Difference,
+    /// `..`
+    Range,
+}
+
+pub type ExpressionNode<'i> = dsl_util::ExpressionNode<'i, ExpressionKind<'i>>;
+pub type FunctionCallNode<'i> = dsl_util::FunctionCallNode<'i, ExpressionKind<'i>>;
+
+fn union_nodes<'i>(lhs: ExpressionNode<'i>, rhs: ExpressionNode<'i>) -> ExpressionNode<'i> {
+    let span = lhs.span.start_pos().span(&rhs.span.end_pos());
+    let expr = match lhs.kind {
+        // Flatten "x | y | z" to save recursion stack.
+        ExpressionKind::UnionAll(mut nodes) => {
+            nodes.push(rhs);
+            ExpressionKind::UnionAll(nodes)
+        }
+        _ => ExpressionKind::UnionAll(vec![lhs, rhs]),
+    };
+    ExpressionNode::new(expr, span)
+}
+
+fn parse_function_call_node(pair: Pair<Rule>) -> OpsetParseResult<FunctionCallNode> {
+    assert_eq!(pair.as_rule(), Rule::function);
+    let (name_pair, args_pair) = pair.into_inner().collect_tuple().unwrap();
+    assert_eq!(name_pair.as_rule(), Rule::function_name);
+    assert_eq!(args_pair.as_rule(), Rule::function_arguments);
+    let name_span = name_pair.as_span();
+    let args_span = args_pair.as_span();
+    let name = name_pair.as_str();
+    let args = args_pair
+        .into_inner()
+        .map(|pair| parse_expression_node(pair.into_inner()))
+        .try_collect()?;
+    Ok(FunctionCallNode {
+        name,
+        name_span,
+        args,
+        keyword_args: vec![], // unsupported
+        args_span,
+    })
+}
+
+fn parse_as_string_literal(pair: Pair<Rule>) -> String {
+    match pair.as_rule() {
+        Rule::identifier => pair.as_str().to_owned(),
+        Rule::string_literal => STRING_LITERAL_PARSER.parse(pair.into_inner()),
+        Rule::raw_string_literal => {
+            let (content,) = pair.into_inner().collect_tuple().unwrap();
+            assert_eq!(content.as_rule(), Rule::raw_string_content);
+            content.as_str().to_owned()
+        }
+        r => panic!("unexpected string literal rule: {r:?}"),
+    }
+}
+
+fn parse_primary_node(pair: Pair<Rule>) -> OpsetParseResult<ExpressionNode> {
+    assert_eq!(pair.as_rule(), Rule::primary);
+    let first = pair.into_inner().next().unwrap();
+    let span = first.as_span();
+    let expr = match first.as_rule() {
+        Rule::expression => return parse_expression_node(first.into_inner()),
+        Rule::function => {
+            let function = Box::new(parse_function_call_node(first)?);
+            ExpressionKind::FunctionCall(function)
+        }
+        Rule::string_pattern => {
+            let (lhs, op, rhs) = first.into_inner().collect_tuple().unwrap();
+            assert_eq!(lhs.as_rule(), Rule::identifier);
+            assert_eq!(op.as_rule(), Rule::pattern_kind_op);
+            let kind = lhs.as_str();
+            let value = parse_as_string_literal(rhs);
+            ExpressionKind::StringPattern { kind, value }
+        }
+        Rule::identifier => ExpressionKind::Identifier(first.as_str()),
+        Rule::string_literal | Rule::raw_string_literal => {
+            ExpressionKind::String(parse_as_string_literal(first))
+        }
+        Rule::at_op => ExpressionKind::AtCurrent,
+        r => panic!("unexpected primary rule: {r:?}"),
+    };
+    Ok(ExpressionNode::new(expr, span))
+}
+
+fn parse_expression_node(pairs: Pairs<Rule>) -> OpsetParseResult<ExpressionNode> {
+    static PRATT: Lazy<PrattParser<Rule>> = Lazy::new(|| {
+        PrattParser::new()
+            .op(Op::infix(Rule::union_op, Assoc::Left))
+            .op(Op::infix(Rule::intersection_op, Assoc::Left)
+                | Op::infix(Rule::difference_op, Assoc::Left))
+            .op(Op::prefix(Rule::negate_op))
+            // Ranges can't be nested without parentheses. Associativity doesn't matter.
+            .op(Op::infix(Rule::range_op, Assoc::Left))
+            .op(Op::prefix(Rule::range_pre_op))
+            .op(Op::postfix(Rule::range_post_op))
+            // Neighbors
+            .op(Op::postfix(Rule::parents_op) | Op::postfix(Rule::children_op))
+    });
+    PRATT
+        .map_primary(|primary| {
+            let expr = match primary.as_rule() {
+                Rule::primary => return parse_primary_node(primary),
+                Rule::range_all_op => ExpressionKind::RangeAll,
+                r => panic!("unexpected primary rule {r:?}"),
+            };
+            Ok(ExpressionNode::new(expr, primary.as_span()))
+        })
+        .map_prefix(|op, rhs| {
+            let op_kind = match op.as_rule() {
+                Rule::negate_op => UnaryOp::Negate,
+                Rule::range_pre_op => UnaryOp::RangePre,
+                r => panic!("unexpected prefix operator rule {r:?}"),
+            };
+            let rhs = Box::new(rhs?);
+            let span = op.as_span().start_pos().span(&rhs.span.end_pos());
+            let expr = ExpressionKind::Unary(op_kind, rhs);
+            Ok(ExpressionNode::new(expr, span))
+        })
+        .map_postfix(|lhs, op| {
+            let op_kind = match op.as_rule() {
+                Rule::range_post_op => UnaryOp::RangePost,
+                Rule::parents_op => UnaryOp::Parents,
+                Rule::children_op => UnaryOp::Children,
+                r => panic!("unexpected postfix operator rule {r:?}"),
+            };
+            let lhs = Box::new(lhs?);
+            let span = lhs.span.start_pos().span(&op.as_span().end_pos());
+            let expr = ExpressionKind::Unary(op_kind, lhs);
+            Ok(ExpressionNode::new(expr, span))
+        })
+        .map_infix(|lhs, op, rhs| {
+            let op_kind = match op.as_rule() {
+                Rule::union_op => return Ok(union_nodes(lhs?, rhs?)),
+                Rule::intersection_op => BinaryOp::Intersection,
+                Rule::difference_op => BinaryOp::Difference,
+                Rule::range_op => BinaryOp::Range,
+                r => panic!("unexpected infix operator rule {r:?}"),
+            };
+            let lhs = Box::new(lhs?);
+            let rhs = Box::new(rhs?);
+            let span = lhs.span.start_pos().span(&rhs.span.end_pos());
+            let expr = ExpressionKind::Binary(op_kind, lhs, rhs);
+            Ok(ExpressionNode::new(expr, span))
+        })
+        .parse(pairs)
+}
+
+/// Parses text into expression tree. No name resolution is made at this stage.
+pub fn parse_program(text: &str) -> OpsetParseResult<ExpressionNode> {
+    let mut pairs = OpsetParser::parse(Rule::program, text)?;
+    let first = pairs.next().unwrap();
+    parse_expression_node(first.into_inner())
+}
+
+/// Parses the `node` as a string pattern, and passes the value and optional
+/// pattern kind to the `parse_pattern` function.
+pub(super) fn expect_pattern_with<T, E: Into<Box<dyn error::Error + Send + Sync>>>(
+    type_name: &str,
+    node: &ExpressionNode,
+    parse_pattern: impl FnOnce(&str, Option<&str>) -> Result<T, E>,
+) -> OpsetParseResult<T> {
+    let wrap_error = |err: E| {
+        OpsetParseError::expression(format!("Invalid {type_name}"), node.span).with_source(err)
+    };
+    match &node.kind {
+        ExpressionKind::Identifier(name) => parse_pattern(name, None).map_err(wrap_error),
+        ExpressionKind::String(name) => parse_pattern(name, None).map_err(wrap_error),
+        ExpressionKind::StringPattern { kind, value } => {
+            parse_pattern(value, Some(kind)).map_err(wrap_error)
+        }
+        _ => Err(OpsetParseError::expression(
+            format!("Expected expression of {type_name}"),
+            node.span,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    fn parse_into_kind(text: &str) -> Result<ExpressionKind, OpsetParseErrorKind> {
+        parse_program(text)
+            .map(|node| node.kind)
+            .map_err(|err| err.kind)
+    }
+
+    #[test]
+    fn test_parse_symbol() {
+        assert_eq!(parse_into_kind("@"), Ok(ExpressionKind::AtCurrent));
+        assert_eq!(
+            parse_into_kind(" abc123 "),
+            Ok(ExpressionKind::Identifier("abc123"))
+        );
+        assert_eq!(
+            parse_into_kind("user-name"),
+            Ok(ExpressionKind::Identifier("user-name"))
+        );
+        assert_eq!(
+            parse_into_kind(r#""foo bar""#),
+            Ok(ExpressionKind::String("foo bar".to_owned()))
+        );
+        assert_eq!(
+            parse_into_kind(r#"glob:"*@host""#),
+            Ok(ExpressionKind::StringPattern {
+                kind: "glob",
+                value: "*@host".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_neighbors() {
+        assert_matches!(
+            parse_into_kind("abc123-+"),
+            Ok(ExpressionKind::Unary(UnaryOp::Children, node))
+                if matches!(
+                    node.kind,
+                    ExpressionKind::Unary(UnaryOp::Parents, ref node)
+                        if node.kind == ExpressionKind::Identifier("abc123")
+                )
+        );
+        assert_matches!(
+            parse_into_kind("@--"),
+            Ok(ExpressionKind::Unary(UnaryOp::Parents, _))
+        );
+        assert_matches!(
+            parse_into_kind("(@ | abc)-"),
+            Ok(ExpressionKind::Unary(UnaryOp::Parents, _))
+        );
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_matches!(
+            parse_into_kind("abc..@"),
+            Ok(ExpressionKind::Binary(BinaryOp::Range, _, _))
+        );
+        assert_matches!(
+            parse_into_kind("@-.."),
+            Ok(ExpressionKind::Unary(UnaryOp::RangePost, _))
+        );
+        assert_matches!(
+            parse_into_kind("..@"),
+            Ok(ExpressionKind::Unary(UnaryOp::RangePre, _))
+        );
+        assert_eq!(parse_into_kind(".."), Ok(ExpressionKind::RangeAll));
+        // Ranges can't be nested without parentheses
+        assert_eq!(
+            parse_into_kind("a..b..c"),
+            Err(OpsetParseErrorKind::SyntaxError)
+        );
+    }
+
+    #[test]
+    fn test_parse_operators() {
+        assert_matches!(
+            parse_into_kind("snapshots() | @ & all()"),
+            Ok(ExpressionKind::UnionAll(nodes))
+                if matches!(nodes[1].kind, ExpressionKind::Binary(BinaryOp::Intersection, _, _))
+        );
+        assert_matches!(
+            parse_into_kind("~snapshots()"),
+            Ok(ExpressionKind::Unary(UnaryOp::Negate, _))
+        );
+        assert_matches!(
+            parse_into_kind("ancestors(@-) ~ user(glob:'*@host')"),
+            Ok(ExpressionKind::Binary(BinaryOp::Difference, _, _))
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(parse_into_kind(""), Err(OpsetParseErrorKind::SyntaxError));
+        assert_eq!(parse_into_kind("@ @"), Err(OpsetParseErrorKind::SyntaxError));
+        assert_eq!(parse_into_kind("(@"), Err(OpsetParseErrorKind::SyntaxError));
+        assert_eq!(parse_into_kind("@ |"), Err(OpsetParseErrorKind::SyntaxError));
+        assert_eq!(
+            parse_into_kind("5foo(x)"),
+            Err(OpsetParseErrorKind::SyntaxError)
+        );
+    }
+}
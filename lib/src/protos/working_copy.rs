@@ -53,6 +53,22 @@ pub struct TreeState {
     pub sparse_patterns: ::core::option::Option<SparsePatterns>,
     #[prost(message, optional, tag = "4")]
     pub watchman_clock: ::core::option::Option<WatchmanClock>,
+    /// Set along with watchman_clock.
+    #[prost(message, optional, tag = "7")]
+    pub fsmonitor_freshness: ::core::option::Option<FsmonitorFreshness>,
+}
+/// Records what the Watchman clock was saved with, to tell whether the clock can
+/// be trusted instead of scanning the working copy.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FsmonitorFreshness {
+    /// Copy of the clock the freshness was recorded with. Versions that don't
+    /// know about this message drop it when saving the state.
+    #[prost(message, optional, tag = "1")]
+    pub watchman_clock: ::core::option::Option<WatchmanClock>,
+    /// When the working copy was last scanned in full.
+    #[prost(int64, tag = "2")]
+    pub full_scan_millis_since_epoch: i64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
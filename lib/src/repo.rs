@@ -42,9 +42,11 @@ use crate::commit::Commit;
 use crate::commit::CommitByCommitterTimestamp;
 use crate::commit_builder::CommitBuilder;
 use crate::commit_builder::DetachedCommitBuilder;
+use crate::config::ConfigGetError;
 use crate::dag_walk;
 use crate::default_index::DefaultIndexStore;
 use crate::default_index::DefaultMutableIndex;
+use crate::default_index::IndexCompactionPolicy;
 use crate::default_submodule_store::DefaultSubmoduleStore;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
@@ -59,6 +61,7 @@ use crate::merge::MergeBuilder;
 use crate::object_id::HexPrefix;
 use crate::object_id::ObjectId;
 use crate::object_id::PrefixResolution;
+use crate::object_store_backend::ObjectStoreBackend;
 use crate::op_heads_store;
 use crate::op_heads_store::OpHeadResolutionError;
 use crate::op_heads_store::OpHeadsStore;
@@ -152,11 +155,15 @@ pub enum RepoInitError {
     OpHeadsStore(#[from] OpHeadsStoreError),
     #[error(transparent)]
     Path(#[from] PathError),
+    #[error(transparent)]
+    Config(#[from] ConfigGetError),
 }
 
 impl ReadonlyRepo {
     pub fn default_op_store_initializer() -> &'static OpStoreInitializer<'static> {
-        &|_settings, store_path, root_data| Box::new(SimpleOpStore::init(store_path, root_data))
+        &|settings, store_path, root_data| {
+            Box::new(SimpleOpStore::init(store_path, root_data).with_settings(settings))
+        }
     }
 
     pub fn default_op_heads_store_initializer() -> &'static OpHeadsStoreInitializer<'static> {
@@ -167,7 +174,12 @@ impl ReadonlyRepo {
     }
 
     pub fn default_index_store_initializer() -> &'static IndexStoreInitializer<'static> {
-        &|_settings, store_path| Ok(Box::new(DefaultIndexStore::init(store_path)?))
+        &|settings, store_path| {
+            let policy = IndexCompactionPolicy::from_settings(settings)
+                .map_err(|err| BackendInitError(err.into()))?;
+            let store = DefaultIndexStore::init(store_path)?.with_compaction_policy(policy);
+            Ok(Box::new(store))
+        }
     }
 
     pub fn default_submodule_store_initializer() -> &'static SubmoduleStoreInitializer<'static> {
@@ -192,7 +204,7 @@ impl ReadonlyRepo {
         let backend = backend_initializer(settings, &store_path)?;
         let backend_path = store_path.join("type");
         fs::write(&backend_path, backend.name()).context(&backend_path)?;
-        let store = Store::new(backend, signer);
+        let store = Store::with_memory_budget(backend, signer, &settings.memory_budget()?);
 
         let op_store_path = repo_path.join("op_store");
         fs::create_dir(&op_store_path).context(&op_store_path)?;
@@ -392,6 +404,12 @@ impl Default for StoreFactories {
             LocalBackend::name(),
             Box::new(|_settings, store_path| Ok(Box::new(LocalBackend::load(store_path)))),
         );
+        factories.add_backend(
+            ObjectStoreBackend::name(),
+            Box::new(|settings, store_path| {
+                Ok(Box::new(ObjectStoreBackend::load(settings, store_path)?))
+            }),
+        );
         #[cfg(feature = "git")]
         factories.add_backend(
             crate::git_backend::GitBackend::name(),
@@ -414,8 +432,8 @@ impl Default for StoreFactories {
         // OpStores
         factories.add_op_store(
             SimpleOpStore::name(),
-            Box::new(|_settings, store_path, root_data| {
-                Box::new(SimpleOpStore::load(store_path, root_data))
+            Box::new(|settings, store_path, root_data| {
+                Box::new(SimpleOpStore::load(store_path, root_data).with_settings(settings))
             }),
         );
 
@@ -428,7 +446,12 @@ impl Default for StoreFactories {
         // Index
         factories.add_index_store(
             DefaultIndexStore::name(),
-            Box::new(|_settings, store_path| Ok(Box::new(DefaultIndexStore::load(store_path)))),
+            Box::new(|settings, store_path| {
+                let policy = IndexCompactionPolicy::from_settings(settings)
+                    .map_err(|err| BackendLoadError(err.into()))?;
+                let store = DefaultIndexStore::load(store_path).with_compaction_policy(policy);
+                Ok(Box::new(store))
+            }),
         );
 
         // SubmoduleStores
@@ -457,6 +480,8 @@ pub enum StoreLoadError {
     Backend(#[from] BackendLoadError),
     #[error(transparent)]
     Signing(#[from] SignInitError),
+    #[error(transparent)]
+    Config(#[from] ConfigGetError),
 }
 
 impl StoreFactories {
@@ -655,9 +680,10 @@ impl RepoLoader {
         repo_path: &Path,
         store_factories: &StoreFactories,
     ) -> Result<Self, StoreLoadError> {
-        let store = Store::new(
+        let store = Store::with_memory_budget(
             store_factories.load_backend(settings, &repo_path.join("store"))?,
             Signer::from_settings(settings)?,
+            &settings.memory_budget()?,
         );
         let root_op_data = RootOperationData {
             root_commit_id: store.root_commit_id().clone(),
@@ -1531,7 +1557,7 @@ impl MutableRepo {
             .set_remote_bookmark(name, remote_name, remote_ref);
     }
 
-    fn merge_remote_bookmark(
+    pub fn merge_remote_bookmark(
         &mut self,
         name: &str,
         remote_name: &str,
@@ -1615,6 +1641,60 @@ impl MutableRepo {
         self.view.mark_dirty();
     }
 
+    /// Restores the visible heads from `view`, leaving the refs as they are.
+    ///
+    /// The current working-copy commits and local bookmark and tag targets are
+    /// kept visible. Commits hidden by this aren't recorded as abandoned, so
+    /// their descendants aren't rebased.
+    pub fn restore_heads(&mut self, view: &op_store::View) -> BackendResult<()> {
+        let current_view = self.view();
+        let ref_target_ids = itertools::chain(
+            current_view.local_bookmarks().map(|(_, target)| target),
+            current_view.tags().values(),
+        )
+        .flat_map(|target| target.added_ids());
+        let ref_commits: Vec<_> =
+            itertools::chain(current_view.wc_commit_ids().values(), ref_target_ids)
+                .unique()
+                .map(|id| self.store().get_commit(id))
+                .try_collect()?;
+        self.view_mut().store_view_mut().head_ids = view.head_ids.clone();
+        self.view.mark_dirty();
+        self.add_heads(&ref_commits)
+    }
+
+    /// Restores all local bookmarks from `view`.
+    pub fn restore_local_bookmarks(&mut self, view: &op_store::View) {
+        self.view_mut().store_view_mut().local_bookmarks = view.local_bookmarks.clone();
+        self.view.mark_dirty();
+    }
+
+    /// Restores all tags from `view`.
+    pub fn restore_tags(&mut self, view: &op_store::View) {
+        self.view_mut().store_view_mut().tags = view.tags.clone();
+        self.view.mark_dirty();
+    }
+
+    /// Restores the remote bookmarks and their tracking state from `view`.
+    pub fn restore_remote_views(&mut self, view: &op_store::View) {
+        self.view_mut().store_view_mut().remote_views = view.remote_views.clone();
+        self.view.mark_dirty();
+    }
+
+    /// Restores the working-copy commits of all workspaces from `view`.
+    ///
+    /// The restored working-copy commits are made visible if they aren't.
+    pub fn restore_wc_commits(&mut self, view: &op_store::View) -> BackendResult<()> {
+        let wc_commits: Vec<_> = view
+            .wc_commit_ids
+            .values()
+            .map(|id| self.store().get_commit(id))
+            .try_collect()?;
+        self.view_mut().store_view_mut().wc_commit_ids = view.wc_commit_ids.clone();
+        self.view.mark_dirty();
+        self.add_heads(&wc_commits)
+    }
+
     pub fn merge(&mut self, base_repo: &ReadonlyRepo, other_repo: &ReadonlyRepo) {
         // First, merge the index, so we can take advantage of a valid index when
         // merging the view. Merging in base_repo's index isn't typically
@@ -0,0 +1,585 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Privacy-scrubbed bundles for reproducing bugs in other repositories.
+//!
+//! A [`ReproBundle`] captures the shape of a repository: its operation log,
+//! the views of all operations, and the commit graph. Descriptions, author
+//! names, bookmark names, and file contents are left out. File paths are only
+//! included on request, and each path component is replaced by a salted hash.
+//! Loading a bundle into an empty repository recreates the same graph of
+//! operations and commits, though with different commit IDs.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use blake2::Blake2b512;
+use digest::Digest as _;
+use itertools::Itertools as _;
+use pollster::FutureExt as _;
+use serde::Deserialize;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::backend;
+use crate::backend::BackendError;
+use crate::backend::ChangeId;
+use crate::backend::CommitId;
+use crate::backend::MergedTreeId;
+use crate::backend::MillisSinceEpoch;
+use crate::backend::Signature;
+use crate::backend::Timestamp;
+use crate::backend::TreeValue;
+use crate::dag_walk;
+use crate::default_index::AsCompositeIndex as _;
+use crate::default_index::DefaultReadonlyIndex;
+use crate::merge::Merge;
+use crate::object_id::ObjectId as _;
+use crate::op_heads_store::OpHeadsStoreError;
+use crate::op_store;
+use crate::op_store::OpStoreError;
+use crate::op_store::OperationId;
+use crate::op_store::OperationMetadata;
+use crate::op_store::RefTarget;
+use crate::op_store::RemoteRef;
+use crate::op_store::RemoteRefState;
+use crate::op_store::RemoteView;
+use crate::op_store::WorkspaceId;
+use crate::op_walk;
+use crate::repo::ReadonlyRepo;
+use crate::repo::Repo as _;
+use crate::repo_path::RepoPathBuf;
+use crate::store::Store;
+
+/// Version of the bundle format written by this version of jj.
+pub const REPRO_BUNDLE_VERSION: u32 = 1;
+
+/// Error while creating or loading a [`ReproBundle`].
+#[derive(Debug, Error)]
+pub enum ReproBundleError {
+    /// The bundle was written in a format this version of jj can't read.
+    #[error("Unsupported bundle format version {0}")]
+    UnsupportedVersion(u32),
+    /// The bundle refers to a commit it doesn't contain.
+    #[error("Bundle refers to unknown commit {0}")]
+    UnknownCommit(String),
+    /// The bundle refers to an operation it doesn't contain.
+    #[error("Bundle refers to unknown operation {0}")]
+    UnknownOperation(String),
+    /// The bundle contains a malformed ID or path.
+    #[error("Bundle contains invalid value {0:?}")]
+    InvalidValue(String),
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+    #[error(transparent)]
+    OpStore(#[from] OpStoreError),
+    #[error(transparent)]
+    OpHeadsStore(#[from] OpHeadsStoreError),
+}
+
+/// Privacy-scrubbed snapshot of a repository's operation log and commit graph.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReproBundle {
+    /// Format version, see [`REPRO_BUNDLE_VERSION`].
+    pub version: u32,
+    /// ID of the root commit in the bundled repository.
+    pub root_commit_id: String,
+    /// ID of the root operation in the bundled repository.
+    pub root_operation_id: String,
+    /// Statistics of the commit index, if it's the default index.
+    pub index: Option<BundleIndexStats>,
+    /// Operations, with parents before their children.
+    pub operations: Vec<BundleOperation>,
+    /// Commits, with parents and predecessors before the commits referring to
+    /// them. The root commit isn't included.
+    pub commits: Vec<BundleCommit>,
+}
+
+/// Statistics of the default commit index.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleIndexStats {
+    pub num_commits: u32,
+    pub num_merges: u32,
+    pub max_generation_number: u32,
+    pub num_heads: u32,
+    pub num_changes: u32,
+    /// Number of commits in each segment, from the newest segment.
+    pub level_num_commits: Vec<u32>,
+}
+
+/// Scrubbed operation. Descriptions, tags, and user names are left out.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleOperation {
+    pub id: String,
+    pub parents: Vec<String>,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub is_snapshot: bool,
+    pub view: BundleView,
+}
+
+/// Scrubbed view, with ref and workspace names replaced by hashes.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BundleView {
+    pub head_ids: Vec<String>,
+    pub local_bookmarks: BTreeMap<String, BundleRefTarget>,
+    pub tags: BTreeMap<String, BundleRefTarget>,
+    pub remote_bookmarks: BTreeMap<String, BTreeMap<String, BundleRemoteRef>>,
+    pub wc_commit_ids: BTreeMap<String, String>,
+}
+
+/// Terms of a ref target, in the order of [`Merge::iter()`].
+pub type BundleRefTarget = Vec<Option<String>>;
+
+/// Scrubbed remote ref.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleRemoteRef {
+    pub target: BundleRefTarget,
+    pub tracking: bool,
+}
+
+/// Scrubbed commit. The description and contents are left out, and the author
+/// is replaced by a hash.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleCommit {
+    pub id: String,
+    pub change_id: String,
+    pub parents: Vec<String>,
+    pub predecessors: Vec<String>,
+    pub author: String,
+    pub author_time: i64,
+    pub committer_time: i64,
+    /// Hashed paths of the files in the commit, mapped to hashes of their
+    /// contents. Only present if trees were included.
+    pub files: Option<BTreeMap<String, String>>,
+}
+
+/// Replaces private strings with salted hashes, so equal strings map to equal
+/// hashes within a bundle, but can't be recovered by guessing.
+struct Scrubber {
+    salt: [u8; 16],
+}
+
+impl Scrubber {
+    fn hash(&self, kind: &str, value: &[u8]) -> String {
+        let digest = Blake2b512::new()
+            .chain_update(self.salt)
+            .chain_update(kind.as_bytes())
+            .chain_update(value)
+            .finalize();
+        hex::encode(&digest[..6])
+    }
+
+    fn name(&self, kind: &str, name: &str) -> String {
+        format!("{kind}-{}", self.hash(kind, name.as_bytes()))
+    }
+
+    fn workspace_id(&self, workspace_id: &WorkspaceId) -> String {
+        // The default workspace is kept so the bundle can be loaded into a new
+        // repo with a working copy.
+        if workspace_id == &WorkspaceId::default() {
+            workspace_id.as_str().to_owned()
+        } else {
+            self.name("workspace", workspace_id.as_str())
+        }
+    }
+
+    fn remote_name(&self, remote_name: &str) -> String {
+        // The "git" remote is built in and says nothing about the repo.
+        if remote_name == "git" {
+            remote_name.to_owned()
+        } else {
+            self.name("remote", remote_name)
+        }
+    }
+
+    fn path(&self, path: &RepoPathBuf) -> String {
+        path.components()
+            .map(|component| self.hash("path", component.as_internal_str().as_bytes()))
+            .join("/")
+    }
+
+    fn ref_target(target: &RefTarget) -> BundleRefTarget {
+        target
+            .as_merge()
+            .iter()
+            .map(|id| id.as_ref().map(|id| id.hex()))
+            .collect()
+    }
+
+    fn view(&self, view: &op_store::View) -> BundleView {
+        BundleView {
+            head_ids: view.head_ids.iter().map(|id| id.hex()).sorted().collect(),
+            local_bookmarks: view
+                .local_bookmarks
+                .iter()
+                .map(|(name, target)| (self.name("bookmark", name), Self::ref_target(target)))
+                .collect(),
+            tags: view
+                .tags
+                .iter()
+                .map(|(name, target)| (self.name("tag", name), Self::ref_target(target)))
+                .collect(),
+            remote_bookmarks: view
+                .remote_views
+                .iter()
+                .map(|(remote_name, remote_view)| {
+                    let bookmarks = remote_view
+                        .bookmarks
+                        .iter()
+                        .map(|(name, remote_ref)| {
+                            let remote_ref = BundleRemoteRef {
+                                target: Self::ref_target(&remote_ref.target),
+                                tracking: remote_ref.is_tracking(),
+                            };
+                            (self.name("bookmark", name), remote_ref)
+                        })
+                        .collect();
+                    (self.remote_name(remote_name), bookmarks)
+                })
+                .collect(),
+            wc_commit_ids: view
+                .wc_commit_ids
+                .iter()
+                .map(|(workspace_id, id)| (self.workspace_id(workspace_id), id.hex()))
+                .collect(),
+        }
+    }
+}
+
+/// Commit IDs referenced by `view`.
+fn view_commit_ids(view: &op_store::View) -> impl Iterator<Item = &CommitId> {
+    let ref_targets = itertools::chain!(
+        view.local_bookmarks.values(),
+        view.tags.values(),
+        view.remote_views
+            .values()
+            .flat_map(|remote_view| remote_view.bookmarks.values())
+            .map(|remote_ref| &remote_ref.target),
+    );
+    itertools::chain!(
+        &view.head_ids,
+        view.wc_commit_ids.values(),
+        ref_targets.flat_map(|target| target.added_ids().chain(target.removed_ids())),
+    )
+}
+
+impl ReproBundle {
+    /// Creates a bundle of `repo`'s operation log up to its current operation,
+    /// and of all commits referenced by the views of those operations. If
+    /// `include_trees` is true, the hashed file paths of each commit are
+    /// included.
+    pub fn collect(repo: &ReadonlyRepo, include_trees: bool) -> Result<Self, ReproBundleError> {
+        let scrubber = Scrubber {
+            salt: rand::random(),
+        };
+        let store = repo.store();
+
+        let mut operations = vec![];
+        let mut referenced_ids = HashSet::new();
+        for op in op_walk::walk_ancestors(std::slice::from_ref(repo.operation())) {
+            let op = op?;
+            let view = op.view()?;
+            let view = view.store_view();
+            referenced_ids.extend(view_commit_ids(view).cloned());
+            let metadata = op.metadata();
+            operations.push(BundleOperation {
+                id: op.id().hex(),
+                parents: op.parent_ids().iter().map(|id| id.hex()).collect(),
+                start_time: metadata.start_time.timestamp.0,
+                end_time: metadata.end_time.timestamp.0,
+                is_snapshot: metadata.is_snapshot,
+                view: scrubber.view(view),
+            });
+        }
+        // Parents first
+        operations.reverse();
+
+        // Predecessors may have been garbage collected, so only the parents of
+        // referenced commits must exist.
+        let commits = dag_walk::topo_order_forward_ok(
+            referenced_ids
+                .into_iter()
+                .sorted()
+                .map(|id| store.get_commit(&id)),
+            |commit| commit.id().clone(),
+            |commit| {
+                let predecessors = commit
+                    .predecessor_ids()
+                    .iter()
+                    .filter_map(|id| store.get_commit(id).ok())
+                    .map(Ok)
+                    .collect_vec();
+                commit.parents().chain(predecessors).collect_vec()
+            },
+        )?;
+        let commits = commits
+            .into_iter()
+            .filter(|commit| commit.id() != store.root_commit_id())
+            .map(|commit| {
+                let files = if include_trees {
+                    let files = commit
+                        .tree()?
+                        .entries()
+                        .map(|(path, value)| {
+                            let content =
+                                scrubber.hash("content", format!("{:?}", value?).as_bytes());
+                            Ok((scrubber.path(&path), content))
+                        })
+                        .collect::<Result<_, BackendError>>()?;
+                    Some(files)
+                } else {
+                    None
+                };
+                let author = commit.author();
+                Ok(BundleCommit {
+                    id: commit.id().hex(),
+                    change_id: commit.change_id().hex(),
+                    parents: commit.parent_ids().iter().map(|id| id.hex()).collect(),
+                    predecessors: commit
+                        .predecessor_ids()
+                        .iter()
+                        .filter(|id| store.get_commit(id).is_ok())
+                        .map(|id| id.hex())
+                        .collect(),
+                    author: scrubber.name("author", &author.email),
+                    author_time: author.timestamp.timestamp.0,
+                    committer_time: commit.committer().timestamp.timestamp.0,
+                    files,
+                })
+            })
+            .collect::<Result<_, ReproBundleError>>()?;
+
+        let index = repo
+            .readonly_index()
+            .as_any()
+            .downcast_ref::<DefaultReadonlyIndex>()
+            .map(|index| {
+                let stats = index.as_composite().stats();
+                BundleIndexStats {
+                    num_commits: stats.num_commits,
+                    num_merges: stats.num_merges,
+                    max_generation_number: stats.max_generation_number,
+                    num_heads: stats.num_heads,
+                    num_changes: stats.num_changes,
+                    level_num_commits: stats.levels.iter().map(|level| level.num_commits).collect(),
+                }
+            });
+
+        Ok(ReproBundle {
+            version: REPRO_BUNDLE_VERSION,
+            root_commit_id: store.root_commit_id().hex(),
+            root_operation_id: repo.op_store().root_operation_id().hex(),
+            index,
+            operations,
+            commits,
+        })
+    }
+
+    /// Writes the bundled commits and operations to `repo`, and makes the
+    /// bundle's latest operation the only head of its operation log. Returns
+    /// the ID of that operation.
+    ///
+    /// `repo` should be a new repository. Its existing operations are left
+    /// out of the operation log.
+    pub fn load_into(&self, repo: &ReadonlyRepo) -> Result<OperationId, ReproBundleError> {
+        if self.version != REPRO_BUNDLE_VERSION {
+            return Err(ReproBundleError::UnsupportedVersion(self.version));
+        }
+        let store = repo.store();
+        let mut commit_map: HashMap<&str, CommitId> = HashMap::new();
+        commit_map.insert(&self.root_commit_id, store.root_commit_id().clone());
+        for bundle_commit in &self.commits {
+            let commit_id = write_commit(store, &commit_map, bundle_commit)?;
+            commit_map.insert(&bundle_commit.id, commit_id);
+        }
+
+        let op_store = repo.op_store();
+        let mut op_map: HashMap<&str, OperationId> = HashMap::new();
+        op_map.insert(
+            &self.root_operation_id,
+            op_store.root_operation_id().clone(),
+        );
+        let mut head_op_id = op_store.root_operation_id().clone();
+        for bundle_op in &self.operations {
+            if bundle_op.id == self.root_operation_id {
+                continue;
+            }
+            let view_id = op_store.write_view(&map_view(&commit_map, &bundle_op.view)?)?;
+            let parents = bundle_op
+                .parents
+                .iter()
+                .map(|id| {
+                    op_map
+                        .get(id.as_str())
+                        .cloned()
+                        .ok_or_else(|| ReproBundleError::UnknownOperation(id.clone()))
+                })
+                .try_collect()?;
+            let timestamp = |millis| Timestamp {
+                timestamp: MillisSinceEpoch(millis),
+                tz_offset: 0,
+            };
+            let metadata = OperationMetadata {
+                start_time: timestamp(bundle_op.start_time),
+                end_time: timestamp(bundle_op.end_time),
+                description: format!("bundled operation {}", bundle_op.id),
+                hostname: String::new(),
+                username: String::new(),
+                is_snapshot: bundle_op.is_snapshot,
+                tags: HashMap::new(),
+            };
+            let operation = op_store::Operation {
+                view_id,
+                parents,
+                metadata,
+            };
+            head_op_id = op_store.write_operation(&operation)?;
+            op_map.insert(&bundle_op.id, head_op_id.clone());
+        }
+        repo.op_heads_store()
+            .update_op_heads(std::slice::from_ref(repo.op_id()), &head_op_id)?;
+        Ok(head_op_id)
+    }
+}
+
+fn map_commit_id(
+    commit_map: &HashMap<&str, CommitId>,
+    id: &str,
+) -> Result<CommitId, ReproBundleError> {
+    commit_map
+        .get(id)
+        .cloned()
+        .ok_or_else(|| ReproBundleError::UnknownCommit(id.to_owned()))
+}
+
+fn write_commit(
+    store: &Arc<Store>,
+    commit_map: &HashMap<&str, CommitId>,
+    bundle_commit: &BundleCommit,
+) -> Result<CommitId, ReproBundleError> {
+    let root_tree = match &bundle_commit.files {
+        Some(files) => {
+            let mut tree_builder = store.tree_builder(store.empty_tree_id().clone());
+            for (path, content) in files {
+                if path.is_empty() || path.split('/').any(|component| component.is_empty()) {
+                    return Err(ReproBundleError::InvalidValue(path.clone()));
+                }
+                let path = RepoPathBuf::from_internal_string(path.as_str());
+                let id = store
+                    .write_file(&path, &mut content.as_bytes())
+                    .block_on()?;
+                tree_builder.set(
+                    path,
+                    TreeValue::File {
+                        id,
+                        executable: false,
+                    },
+                );
+            }
+            MergedTreeId::resolved(tree_builder.write_tree()?)
+        }
+        None => store.empty_merged_tree_id(),
+    };
+    let change_id = ChangeId::try_from_hex(&bundle_commit.change_id)
+        .map_err(|_| ReproBundleError::InvalidValue(bundle_commit.change_id.clone()))?;
+    let signature = |millis| Signature {
+        name: bundle_commit.author.clone(),
+        email: format!("{}@example.com", bundle_commit.author),
+        timestamp: Timestamp {
+            timestamp: MillisSinceEpoch(millis),
+            tz_offset: 0,
+        },
+    };
+    let commit = backend::Commit {
+        parents: bundle_commit
+            .parents
+            .iter()
+            .map(|id| map_commit_id(commit_map, id))
+            .try_collect()?,
+        predecessors: bundle_commit
+            .predecessors
+            .iter()
+            .map(|id| map_commit_id(commit_map, id))
+            .try_collect()?,
+        root_tree,
+        change_id,
+        description: String::new(),
+        author: signature(bundle_commit.author_time),
+        committer: signature(bundle_commit.committer_time),
+        secure_sig: None,
+    };
+    let commit = store.write_commit(commit, None).block_on()?;
+    Ok(commit.id().clone())
+}
+
+fn map_ref_target(
+    commit_map: &HashMap<&str, CommitId>,
+    target: &BundleRefTarget,
+) -> Result<RefTarget, ReproBundleError> {
+    if target.len() % 2 == 0 {
+        return Err(ReproBundleError::InvalidValue(format!("{target:?}")));
+    }
+    let terms: Vec<_> = target
+        .iter()
+        .map(|id| {
+            id.as_deref()
+                .map(|id| map_commit_id(commit_map, id))
+                .transpose()
+        })
+        .try_collect()?;
+    Ok(RefTarget::from_merge(Merge::from_vec(terms)))
+}
+
+fn map_view(
+    commit_map: &HashMap<&str, CommitId>,
+    bundle_view: &BundleView,
+) -> Result<op_store::View, ReproBundleError> {
+    let mut view = op_store::View::empty();
+    for id in &bundle_view.head_ids {
+        view.head_ids.insert(map_commit_id(commit_map, id)?);
+    }
+    for (name, target) in &bundle_view.local_bookmarks {
+        let target = map_ref_target(commit_map, target)?;
+        view.local_bookmarks.insert(name.clone(), target);
+    }
+    for (name, target) in &bundle_view.tags {
+        view.tags
+            .insert(name.clone(), map_ref_target(commit_map, target)?);
+    }
+    for (remote_name, bookmarks) in &bundle_view.remote_bookmarks {
+        let mut remote_view = RemoteView::default();
+        for (name, remote_ref) in bookmarks {
+            let remote_ref = RemoteRef {
+                target: map_ref_target(commit_map, &remote_ref.target)?,
+                state: if remote_ref.tracking {
+                    RemoteRefState::Tracking
+                } else {
+                    RemoteRefState::New
+                },
+            };
+            remote_view.bookmarks.insert(name.clone(), remote_ref);
+        }
+        view.remote_views.insert(remote_name.clone(), remote_view);
+    }
+    for (workspace_id, id) in &bundle_view.wc_commit_ids {
+        view.wc_commit_ids.insert(
+            WorkspaceId::new(workspace_id.clone()),
+            map_commit_id(commit_map, id)?,
+        );
+    }
+    Ok(view)
+}
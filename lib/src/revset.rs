@@ -46,6 +46,7 @@ use crate::object_id::PrefixResolution;
 use crate::op_store::RemoteRefState;
 use crate::op_store::WorkspaceId;
 use crate::op_walk;
+use crate::opset::ParsedOpset;
 use crate::repo::ReadonlyRepo;
 use crate::repo::Repo;
 use crate::repo::RepoLoaderError;
@@ -125,7 +126,7 @@ pub enum RevsetModifier {
 #[derive(Clone, Debug)]
 pub enum RevsetCommitRef {
     WorkingCopy(WorkspaceId),
-    WorkingCopies,
+    WorkingCopies(StringPattern),
     Symbol(String),
     RemoteSymbol {
         name: String,
@@ -173,6 +174,9 @@ pub enum RevsetFilterPredicate {
     },
     /// Commits with conflicts
     HasConflict,
+    /// Commits at the boundary of a shallow history, whose actual parents are
+    /// unknown
+    Shallow,
     /// Custom predicates provided by extensions
     Extension(Rc<dyn RevsetFilterExtension>),
 }
@@ -197,7 +201,7 @@ use private::UserExpressionState;
 
 impl ExpressionState for UserExpressionState {
     type CommitRef = RevsetCommitRef;
-    type Operation = String;
+    type Operation = ParsedOpset;
 }
 
 impl ExpressionState for ResolvedExpressionState {
@@ -319,8 +323,10 @@ impl<St: ExpressionState<CommitRef = RevsetCommitRef>> RevsetExpression<St> {
         Rc::new(Self::CommitRef(RevsetCommitRef::WorkingCopy(workspace_id)))
     }
 
-    pub fn working_copies() -> Rc<Self> {
-        Rc::new(Self::CommitRef(RevsetCommitRef::WorkingCopies))
+    pub fn working_copies(workspace_pattern: StringPattern) -> Rc<Self> {
+        Rc::new(Self::CommitRef(RevsetCommitRef::WorkingCopies(
+            workspace_pattern,
+        )))
     }
 
     pub fn symbol(value: String) -> Rc<Self> {
@@ -699,9 +705,14 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
         function.expect_no_arguments()?;
         Ok(RevsetExpression::all())
     });
-    map.insert("working_copies", |_diagnostics, function, _context| {
-        function.expect_no_arguments()?;
-        Ok(RevsetExpression::working_copies())
+    map.insert("working_copies", |diagnostics, function, _context| {
+        let ([], [opt_arg]) = function.expect_arguments()?;
+        let pattern = if let Some(arg) = opt_arg {
+            expect_string_pattern(diagnostics, arg)?
+        } else {
+            StringPattern::everything()
+        };
+        Ok(RevsetExpression::working_copies(pattern))
     });
     map.insert("heads", |diagnostics, function, context| {
         let [arg] = function.expect_exact_arguments()?;
@@ -934,6 +945,10 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
     });
     // TODO: Remove in jj 0.28+
     map.insert("conflict", map["conflicts"]);
+    map.insert("shallow", |_diagnostics, function, _context| {
+        function.expect_no_arguments()?;
+        Ok(RevsetExpression::filter(RevsetFilterPredicate::Shallow))
+    });
     map.insert("present", |diagnostics, function, context| {
         let [arg] = function.expect_exact_arguments()?;
         let expression = lower_expression(diagnostics, arg, context)?;
@@ -941,10 +956,14 @@ static BUILTIN_FUNCTION_MAP: Lazy<HashMap<&'static str, RevsetFunction>> = Lazy:
     });
     map.insert("at_operation", |diagnostics, function, context| {
         let [op_arg, cand_arg] = function.expect_exact_arguments()?;
-        // TODO: Parse "opset" here if we add proper language support.
         let operation =
             revset_parser::expect_expression_with(diagnostics, op_arg, |_diagnostics, node| {
-                Ok(node.span.as_str().to_owned())
+                ParsedOpset::parse(node.span.as_str(), context.date_pattern_context()).map_err(
+                    |err| {
+                        RevsetParseError::expression("Invalid operation set", node.span)
+                            .with_source(err)
+                    },
+                )
             })?;
         let candidates = lower_expression(diagnostics, cand_arg, context)?;
         Ok(Rc::new(RevsetExpression::AtOperation {
@@ -1823,13 +1842,13 @@ pub fn walk_revs<'index>(
 
 fn reload_repo_at_operation(
     repo: &dyn Repo,
-    op_str: &str,
+    opset: &ParsedOpset,
 ) -> Result<Arc<ReadonlyRepo>, RevsetResolutionError> {
     // TODO: Maybe we should ensure that the resolved operation is an ancestor
     // of the current operation. If it weren't, there might be commits unknown
     // to the outer repo.
     let base_repo = repo.base_repo();
-    let operation = op_walk::resolve_op_with_repo(base_repo, op_str)
+    let operation = op_walk::resolve_parsed_op_with_repo(base_repo, opset)
         .map_err(|err| RevsetResolutionError::Other(err.into()))?;
     base_repo.reload_at(&operation).map_err(|err| match err {
         RepoLoaderError::Backend(err) => RevsetResolutionError::StoreError(err),
@@ -2136,8 +2155,14 @@ fn resolve_commit_ref(
                 })
             }
         }
-        RevsetCommitRef::WorkingCopies => {
-            let wc_commits = repo.view().wc_commit_ids().values().cloned().collect_vec();
+        RevsetCommitRef::WorkingCopies(workspace_pattern) => {
+            let wc_commits = repo
+                .view()
+                .wc_commit_ids()
+                .iter()
+                .filter(|(workspace_id, _)| workspace_pattern.matches(workspace_id.as_str()))
+                .map(|(_, commit_id)| commit_id.clone())
+                .collect_vec();
             Ok(wc_commits)
         }
         RevsetCommitRef::Bookmarks(pattern) => {
@@ -2248,7 +2273,7 @@ impl ExpressionStateFolder<UserExpressionState, ResolvedExpressionState>
 
     fn fold_at_operation(
         &mut self,
-        operation: &String,
+        operation: &ParsedOpset,
         candidates: &UserRevsetExpression,
     ) -> Result<Rc<ResolvedRevsetExpression>, Self::Error> {
         let repo = reload_repo_at_operation(self.repo(), operation)?;
@@ -3219,6 +3244,13 @@ mod tests {
             parse("root()").unwrap(),
             @"Root");
         assert!(parse("root(a)").is_err());
+        insta::assert_debug_snapshot!(
+            parse("working_copies()").unwrap(),
+            @r#"CommitRef(WorkingCopies(Substring("")))"#);
+        insta::assert_debug_snapshot!(
+            parse("working_copies(exact:ws1)").unwrap(),
+            @r#"CommitRef(WorkingCopies(Exact("ws1")))"#);
+        assert!(parse("working_copies(a, b)").is_err());
         insta::assert_debug_snapshot!(
             parse(r#"description("")"#).unwrap(),
             @r###"Filter(Description(Substring("")))"###);
@@ -16,8 +16,12 @@
 
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::io::Read as _;
 use std::sync::Arc;
 
+use bstr::BString;
+use bstr::ByteSlice as _;
 use futures::StreamExt;
 use indexmap::IndexMap;
 use indexmap::IndexSet;
@@ -29,13 +33,18 @@ use crate::backend::BackendError;
 use crate::backend::BackendResult;
 use crate::backend::CommitId;
 use crate::backend::MergedTreeId;
+use crate::backend::TreeValue;
 use crate::commit::Commit;
 use crate::commit::CommitIteratorExt;
 use crate::commit_builder::CommitBuilder;
+use crate::conflicts::extract_as_single_hunk;
 use crate::dag_walk;
+use crate::files;
+use crate::files::MergeResult;
 use crate::index::Index;
 use crate::matchers::Matcher;
 use crate::matchers::Visit;
+use crate::merge::Merge;
 use crate::merged_tree::MergedTree;
 use crate::merged_tree::MergedTreeBuilder;
 use crate::merged_tree::TreeDiffEntry;
@@ -86,6 +95,149 @@ pub fn merge_commit_trees_no_resolve_without_repo(
     }
 }
 
+/// Resolutions of conflicting hunks, keyed by the conflicting hunk.
+type HunkResolutions = HashMap<Merge<BString>, BString>;
+
+/// Learns how the conflicting hunks in `hunks` were resolved in `resolved`.
+///
+/// Returns `None` if the resolved content doesn't keep the non-conflicting
+/// hunks as they were, in which case the resolutions can't be told apart from
+/// other changes.
+fn learn_hunk_resolutions(hunks: &[Merge<BString>], resolved: &[u8]) -> Option<HunkResolutions> {
+    let mut resolutions = HashMap::new();
+    let mut rest = resolved;
+    let mut pending_conflict: Option<&Merge<BString>> = None;
+    for (i, hunk) in hunks.iter().enumerate() {
+        let Some(content) = hunk.as_resolved() else {
+            if pending_conflict.is_some() {
+                return None;
+            }
+            pending_conflict = Some(hunk);
+            continue;
+        };
+        let pos = match pending_conflict.take() {
+            None => rest.starts_with(content).then_some(0)?,
+            Some(conflict) => {
+                let pos = if i == hunks.len() - 1 {
+                    let pos = rest.len().checked_sub(content.len())?;
+                    rest.ends_with(content).then_some(pos)?
+                } else {
+                    rest.find(content)?
+                };
+                resolutions.insert(conflict.clone(), BString::from(&rest[..pos]));
+                pos
+            }
+        };
+        rest = &rest[pos + content.len()..];
+    }
+    if let Some(conflict) = pending_conflict {
+        resolutions.insert(conflict.clone(), BString::from(rest));
+    } else if !rest.is_empty() {
+        return None;
+    }
+    Some(resolutions)
+}
+
+/// Resolves the conflicting hunks in `hunks` with the recorded resolutions.
+/// Returns `None` if any of them weren't resolved before.
+fn apply_hunk_resolutions(
+    hunks: &[Merge<BString>],
+    resolutions: &HunkResolutions,
+) -> Option<BString> {
+    let mut content = BString::default();
+    for hunk in hunks {
+        match hunk.as_resolved() {
+            Some(resolved) => content.extend_from_slice(resolved),
+            None => content.extend_from_slice(resolutions.get(hunk)?),
+        }
+    }
+    Some(content)
+}
+
+/// Merges the file contents of a conflicted tree value. Returns `None` if the
+/// value isn't a conflict between files, or if the contents can be merged.
+async fn file_conflict_hunks(
+    store: &Store,
+    path: &RepoPath,
+    value: &Merge<Option<TreeValue>>,
+) -> BackendResult<Option<Vec<Merge<BString>>>> {
+    if value.is_resolved() {
+        return Ok(None);
+    }
+    let Some(file_merge) = value.to_file_merge() else {
+        return Ok(None);
+    };
+    let contents = extract_as_single_hunk(&file_merge.simplify(), store, path).await?;
+    match files::merge(&contents) {
+        MergeResult::Resolved(_) => Ok(None),
+        MergeResult::Conflict(hunks) => Ok(Some(hunks)),
+    }
+}
+
+/// Resolves conflicts in `new_tree`, a rebased merge commit, the same way the
+/// old merge commit resolved them, like `git rerere` does.
+///
+/// For each conflicted path, the conflicting hunks between the new parents
+/// (`new_base_tree`) are resolved if the old merge commit (`old_tree`) resolved
+/// the same hunks between its old parents (`old_base_tree`).
+async fn reuse_merge_resolutions(
+    store: &Arc<Store>,
+    old_base_tree: &MergedTree,
+    old_tree: &MergedTree,
+    new_base_tree: &MergedTree,
+    new_tree: MergedTree,
+) -> BackendResult<MergedTree> {
+    let mut tree_builder = MergedTreeBuilder::new(new_tree.id());
+    let mut num_resolved = 0;
+    for (path, value) in new_tree.conflicts() {
+        value?;
+        let Some(Some(TreeValue::File { id, executable: _ })) =
+            old_tree.path_value_async(&path).await?.into_resolved().ok()
+        else {
+            continue;
+        };
+        let old_base_value = old_base_tree.path_value_async(&path).await?;
+        let Some(old_hunks) = file_conflict_hunks(store, &path, &old_base_value).await? else {
+            continue;
+        };
+        let mut resolved_content = vec![];
+        store
+            .read_file_async(&path, &id)
+            .await?
+            .read_to_end(&mut resolved_content)
+            .map_err(|err| BackendError::ReadFile {
+                path: path.clone(),
+                id: id.clone(),
+                source: err.into(),
+            })?;
+        let Some(resolutions) = learn_hunk_resolutions(&old_hunks, &resolved_content) else {
+            continue;
+        };
+        let new_base_value = new_base_tree.path_value_async(&path).await?;
+        let Some(&executable) = new_base_value
+            .to_executable_merge()
+            .as_ref()
+            .and_then(Merge::resolve_trivial)
+        else {
+            continue;
+        };
+        let Some(new_hunks) = file_conflict_hunks(store, &path, &new_base_value).await? else {
+            continue;
+        };
+        let Some(content) = apply_hunk_resolutions(&new_hunks, &resolutions) else {
+            continue;
+        };
+        let id = store.write_file(&path, &mut content.as_slice()).await?;
+        tree_builder.set_or_remove(path, Merge::normal(TreeValue::File { id, executable }));
+        num_resolved += 1;
+    }
+    if num_resolved == 0 {
+        return Ok(new_tree);
+    }
+    let new_tree_id = tree_builder.write_tree(store)?;
+    store.get_root_tree(&new_tree_id)
+}
+
 /// Restore matching paths from the source into the destination.
 pub fn restore_tree(
     source: &MergedTree,
@@ -222,6 +374,14 @@ impl<'repo> CommitRewriter<'repo> {
     pub fn rebase_with_empty_behavior(
         self,
         empty: EmptyBehaviour,
+    ) -> BackendResult<Option<CommitBuilder<'repo>>> {
+        self.rebase_impl(empty, false)
+    }
+
+    fn rebase_impl(
+        self,
+        empty: EmptyBehaviour,
+        reuse_merge_resolutions: bool,
     ) -> BackendResult<Option<CommitBuilder<'repo>>> {
         let old_parents: Vec<_> = self.old_commit.parents().try_collect()?;
         let old_parent_trees = old_parents
@@ -250,9 +410,24 @@ impl<'repo> CommitRewriter<'repo> {
             let old_base_tree = merge_commit_trees(self.mut_repo, &old_parents)?;
             let new_base_tree = merge_commit_trees(self.mut_repo, &new_parents)?;
             let old_tree = self.old_commit.tree()?;
+            let mut new_tree = new_base_tree.merge(&old_base_tree, &old_tree)?;
+            if reuse_merge_resolutions
+                && old_parents.len() > 1
+                && new_parents.len() > 1
+                && new_tree.has_conflict()
+            {
+                new_tree = reuse_merge_resolutions(
+                    self.mut_repo.store(),
+                    &old_base_tree,
+                    &old_tree,
+                    &new_base_tree,
+                    new_tree,
+                )
+                .block_on()?;
+            }
             (
                 old_base_tree.id() == *self.old_commit.tree_id(),
-                new_base_tree.merge(&old_base_tree, &old_tree)?.id(),
+                new_tree.id(),
             )
         };
         // Ensure we don't abandon commits with multiple parents (merge commits), even
@@ -312,7 +487,7 @@ pub fn rebase_commit_with_options(
         _ => None,
     };
     let new_parents_len = rewriter.new_parents.len();
-    if let Some(builder) = rewriter.rebase_with_empty_behavior(options.empty)? {
+    if let Some(builder) = rewriter.rebase_impl(options.empty, options.reuse_merge_resolutions)? {
         let new_commit = builder.write()?;
         Ok(RebasedCommit::Rewritten(new_commit))
     } else {
@@ -371,6 +546,14 @@ pub struct RebaseOptions {
     /// If a merge commit would end up with one parent being an ancestor of the
     /// other, then filter out the ancestor.
     pub simplify_ancestor_merge: bool,
+    /// If a moved merge commit has another moved commit as a parent, then
+    /// rebase it onto its first parent only. Changes merged in from the other
+    /// parents are dropped.
+    pub linearize_merges: bool,
+    /// When the new parents of a merge commit are merged again, resolve the
+    /// conflicts between them the same way the merge commit resolved the same
+    /// conflicts between its old parents, like `git rerere` does.
+    pub reuse_merge_resolutions: bool,
 }
 
 #[derive(Default)]
@@ -384,6 +567,9 @@ pub struct MoveCommitsStats {
     pub num_skipped_rebases: u32,
     /// The number of commits which were abandoned.
     pub num_abandoned: u32,
+    /// The number of merge commits which were rebased onto their first parent
+    /// only.
+    pub num_linearized_merges: u32,
 }
 
 pub enum MoveCommitsTarget {
@@ -638,6 +824,7 @@ pub fn move_commits(
         .map(|commit| (commit.id().clone(), commit))
         .collect();
 
+    let mut num_linearized_merges = 0;
     let to_visit_commits_new_parents: HashMap<_, _> = to_visit_commits
         .iter()
         .map(|(commit_id, commit)| {
@@ -672,6 +859,10 @@ pub fn move_commits(
                             new_parents.push(parent_id.clone());
                         }
                     }
+                    if options.linearize_merges && new_parents.len() > 1 {
+                        new_parents.truncate(1);
+                        num_linearized_merges += 1;
+                    }
                    new_parents
                 }
             }
@@ -729,6 +920,8 @@ pub fn move_commits(
     let rebase_descendant_options = &RebaseOptions {
         empty: EmptyBehaviour::Keep,
         simplify_ancestor_merge: options.simplify_ancestor_merge,
+        linearize_merges: false,
+        reuse_merge_resolutions: options.reuse_merge_resolutions,
     };
 
     // Rebase each commit onto its new parents in the reverse topological order
@@ -766,6 +959,7 @@ pub fn move_commits(
         num_rebased_descendants,
         num_skipped_rebases,
         num_abandoned,
+        num_linearized_merges,
     })
 }
 
@@ -786,6 +980,9 @@ pub struct DuplicateCommitsStats {
 /// `children_commit_ids` is not empty, the `children_commit_ids` will be
 /// rebased onto the heads of the duplicated target commits.
 ///
+/// If `target_descriptions` contains an entry for a target commit, the
+/// duplicated commit will use that description instead of the original one.
+///
 /// This assumes that commits in `children_commit_ids` can be rewritten. There
 /// should also be no cycles in the resulting graph, i.e. `children_commit_ids`
 /// should not be ancestors of `parent_commit_ids`. Commits in `target_commits`
@@ -793,6 +990,7 @@ pub struct DuplicateCommitsStats {
 pub fn duplicate_commits(
     mut_repo: &mut MutableRepo,
     target_commits: &[CommitId],
+    target_descriptions: &HashMap<CommitId, String>,
     parent_commit_ids: &[CommitId],
     children_commit_ids: &[CommitId],
 ) -> BackendResult<DuplicateCommitsStats> {
@@ -864,10 +1062,13 @@ pub fn duplicate_commits(
                 })
                 .collect()
         };
-        let new_commit = CommitRewriter::new(mut_repo, original_commit, new_parent_ids)
+        let mut new_commit_builder = CommitRewriter::new(mut_repo, original_commit, new_parent_ids)
             .rebase()?
-            .generate_new_change_id()
-            .write()?;
+            .generate_new_change_id();
+        if let Some(desc) = target_descriptions.get(original_commit_id) {
+            new_commit_builder = new_commit_builder.set_description(desc);
+        }
+        let new_commit = new_commit_builder.write()?;
         duplicated_old_to_new.insert(original_commit_id.clone(), new_commit);
     }
 
@@ -917,11 +1118,15 @@ pub fn duplicate_commits(
 /// Duplicates the given `target_commits` onto their original parents or other
 /// duplicated commits.
 ///
+/// If `target_descriptions` contains an entry for a target commit, the
+/// duplicated commit will use that description instead of the original one.
+///
 /// Commits in `target_commits` should be in reverse topological order (children
 /// before parents).
 pub fn duplicate_commits_onto_parents(
     mut_repo: &mut MutableRepo,
     target_commits: &[CommitId],
+    target_descriptions: &HashMap<CommitId, String>,
 ) -> BackendResult<DuplicateCommitsStats> {
     if target_commits.is_empty() {
         return Ok(DuplicateCommitsStats::default());
@@ -943,11 +1148,14 @@ pub fn duplicate_commits_onto_parents(
                     .clone()
             })
             .collect();
-        let new_commit = mut_repo
+        let mut new_commit_builder = mut_repo
             .rewrite_commit(&original_commit)
             .generate_new_change_id()
-            .set_parents(new_parent_ids)
-            .write()?;
+            .set_parents(new_parent_ids);
+        if let Some(desc) = target_descriptions.get(original_commit_id) {
+            new_commit_builder = new_commit_builder.set_description(desc);
+        }
+        let new_commit = new_commit_builder.write()?;
         duplicated_old_to_new.insert(original_commit_id.clone(), new_commit);
     }
 
@@ -1131,3 +1339,34 @@ where
 
     Ok(SquashResult::NewCommit(destination))
 }
+
+/// Follows the rewrites of `commit_id` recorded in the index up to the first
+/// visible commits, and returns them.
+///
+/// The result is empty if the commit wasn't rewritten, or if all rewritten
+/// commits have been abandoned since.
+pub fn find_visible_successors(
+    repo: &dyn Repo,
+    commit_id: &CommitId,
+) -> BackendResult<Vec<CommitId>> {
+    let mut visible_ids = vec![];
+    let mut visited = HashSet::from([commit_id.clone()]);
+    let mut pending = VecDeque::from([commit_id.clone()]);
+    while let Some(id) = pending.pop_front() {
+        for successor_id in repo.index().successors(&id) {
+            if !visited.insert(successor_id.clone()) {
+                continue;
+            }
+            let successor = repo.store().get_commit(&successor_id)?;
+            let is_visible = repo
+                .resolve_change_id(successor.change_id())
+                .is_some_and(|ids| ids.contains(&successor_id));
+            if is_visible {
+                visible_ids.push(successor_id);
+            } else {
+                pending.push_back(successor_id);
+            }
+        }
+    }
+    Ok(visible_ids)
+}
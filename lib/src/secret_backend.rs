@@ -24,6 +24,7 @@ use futures::stream::BoxStream;
 
 use crate::backend::Backend;
 use crate::backend::BackendError;
+use crate::backend::BackendGcStats;
 use crate::backend::BackendLoadError;
 use crate::backend::BackendResult;
 use crate::backend::ChangeId;
@@ -196,4 +197,12 @@ impl Backend for SecretBackend {
     fn gc(&self, index: &dyn Index, keep_newer: SystemTime) -> BackendResult<()> {
         self.inner.gc(index, keep_newer)
     }
+
+    fn gc_dry_run(
+        &self,
+        index: &dyn Index,
+        keep_newer: SystemTime,
+    ) -> BackendResult<BackendGcStats> {
+        self.inner.gc_dry_run(index, keep_newer)
+    }
 }
@@ -69,6 +69,66 @@ impl GitSettings {
     }
 }
 
+/// Limits on memory used by in-memory caches and by operations that load whole
+/// files.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MemoryBudget {
+    /// Total number of bytes, or `None` if unlimited.
+    pub total: Option<u64>,
+}
+
+// Rough sizes of cached objects, used to turn the budget into cache
+// capacities.
+const APPROX_COMMIT_SIZE: u64 = 1024;
+const APPROX_TREE_SIZE: u64 = 8 * 1024;
+const APPROX_INDEX_POSITION_SIZE: u64 = 4;
+
+impl MemoryBudget {
+    pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
+        let HumanByteSize(total) =
+            settings.get_value_with("core.memory-budget", TryInto::try_into)?;
+        Ok(MemoryBudget {
+            total: (total != 0).then_some(total),
+        })
+    }
+
+    /// Returns the number of entries of roughly `entry_size` bytes that fit in
+    /// `1 / divisor` of the budget, but no more than `default`.
+    fn cache_capacity(&self, default: usize, entry_size: u64, divisor: u64) -> usize {
+        let Some(total) = self.total else {
+            return default;
+        };
+        let capacity = total / divisor / entry_size;
+        usize::try_from(capacity)
+            .unwrap_or(usize::MAX)
+            .clamp(1, default)
+    }
+
+    /// Capacity of the commit cache, which may use 1/16 of the budget.
+    pub fn commit_cache_capacity(&self, default: usize) -> usize {
+        self.cache_capacity(default, APPROX_COMMIT_SIZE, 16)
+    }
+
+    /// Capacity of the tree cache, which may use 1/8 of the budget.
+    pub fn tree_cache_capacity(&self, default: usize) -> usize {
+        self.cache_capacity(default, APPROX_TREE_SIZE, 8)
+    }
+
+    /// Number of index positions the revset cache may hold, using 1/8 of the
+    /// budget.
+    pub fn revset_cache_capacity(&self, default: usize) -> usize {
+        self.cache_capacity(default, APPROX_INDEX_POSITION_SIZE, 8)
+    }
+
+    /// Maximum size of file contents to load in full, e.g. to compute a diff.
+    ///
+    /// Both sides of a diff and the diff itself have to fit in memory, so this
+    /// is a quarter of the budget.
+    pub fn max_file_content_size(&self) -> Option<u64> {
+        self.total.map(|total| total / 4)
+    }
+}
+
 impl Default for GitSettings {
     fn default() -> Self {
         GitSettings {
@@ -233,6 +293,10 @@ impl UserSettings {
         GitSettings::from_settings(self)
     }
 
+    pub fn memory_budget(&self) -> Result<MemoryBudget, ConfigGetError> {
+        MemoryBudget::from_settings(self)
+    }
+
     // separate from sign_settings as those two are needed in pretty different
     // places
     pub fn signing_backend(&self) -> Result<Option<String>, ConfigGetError> {
@@ -434,4 +498,28 @@ mod tests {
             Err("Integer out of range")
         );
     }
+
+    #[test]
+    fn memory_budget_capacities() {
+        let unlimited = MemoryBudget::default();
+        assert_eq!(unlimited.commit_cache_capacity(100), 100);
+        assert_eq!(unlimited.tree_cache_capacity(1000), 1000);
+        assert_eq!(unlimited.max_file_content_size(), None);
+
+        let budget = MemoryBudget {
+            total: Some(512 * 1024),
+        };
+        assert_eq!(budget.commit_cache_capacity(100), 32);
+        assert_eq!(budget.tree_cache_capacity(1000), 8);
+        assert_eq!(budget.revset_cache_capacity(1 << 20), 16 * 1024);
+        assert_eq!(budget.max_file_content_size(), Some(128 * 1024));
+
+        // Caches aren't grown beyond their defaults, and never disabled.
+        let large = MemoryBudget {
+            total: Some(1 << 40),
+        };
+        assert_eq!(large.commit_cache_capacity(100), 100);
+        let tiny = MemoryBudget { total: Some(1) };
+        assert_eq!(tiny.tree_cache_capacity(1000), 1);
+    }
 }
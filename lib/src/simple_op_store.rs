@@ -20,6 +20,7 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt::Debug;
 use std::fs;
+use std::fs::File;
 use std::io;
 use std::io::ErrorKind;
 use std::io::Write;
@@ -47,6 +48,7 @@ use crate::object_id::PrefixResolution;
 use crate::op_store;
 use crate::op_store::OpStore;
 use crate::op_store::OpStoreError;
+use crate::op_store::OpStoreGcStats;
 use crate::op_store::OpStoreResult;
 use crate::op_store::Operation;
 use crate::op_store::OperationId;
@@ -59,11 +61,18 @@ use crate::op_store::RootOperationData;
 use crate::op_store::View;
 use crate::op_store::ViewId;
 use crate::op_store::WorkspaceId;
+use crate::settings::UserSettings;
 
 // BLAKE2b-512 hash length in bytes
 const OPERATION_ID_LENGTH: usize = 64;
 const VIEW_ID_LENGTH: usize = 64;
 
+/// Prefix of zstd-compressed operation and view files, followed by the
+/// compressed proto. A leading zero byte can't start a valid proto message, so
+/// uncompressed files are still readable.
+const COMPRESSED_OBJECT_MAGIC: &[u8] = b"\0jjzstd1";
+const COMPRESSION_LEVEL: i32 = 3;
+
 #[derive(Debug, Error)]
 #[error("Failed to read {kind} with ID {id}")]
 struct DecodeError {
@@ -79,12 +88,26 @@ impl From<DecodeError> for OpStoreError {
     }
 }
 
+/// Operation or view file to be removed by gc.
+#[derive(Debug)]
+struct ExpiredFile {
+    path: PathBuf,
+    len: u64,
+}
+
+#[derive(Debug)]
+struct ExpiredFiles {
+    operations: Vec<(OperationId, ExpiredFile)>,
+    views: Vec<ExpiredFile>,
+}
+
 #[derive(Debug)]
 pub struct SimpleOpStore {
     path: PathBuf,
     root_data: RootOperationData,
     root_operation_id: OperationId,
     root_view_id: ViewId,
+    compress_objects: bool,
 }
 
 impl SimpleOpStore {
@@ -106,9 +129,32 @@ impl SimpleOpStore {
             root_data,
             root_operation_id: OperationId::from_bytes(&[0; OPERATION_ID_LENGTH]),
             root_view_id: ViewId::from_bytes(&[0; VIEW_ID_LENGTH]),
+            compress_objects: false,
         }
     }
 
+    /// Sets whether new operation and view files are written compressed.
+    /// Compressed files can't be read by older versions of `jj`.
+    ///
+    /// Files are always read regardless of this setting.
+    pub fn with_compression(mut self, compress_objects: bool) -> Self {
+        self.compress_objects = compress_objects;
+        self
+    }
+
+    /// Applies the `operation.compress-objects` setting.
+    pub fn with_settings(self, settings: &UserSettings) -> Self {
+        let compress_objects = settings
+            .get_bool("operation.compress-objects")
+            .unwrap_or(false);
+        self.with_compression(compress_objects)
+    }
+
+    /// Whether new operation and view files are written compressed.
+    pub fn compresses_objects(&self) -> bool {
+        self.compress_objects
+    }
+
     fn view_path(&self, id: &ViewId) -> PathBuf {
         self.path.join("views").join(id.hex())
     }
@@ -116,6 +162,147 @@ impl SimpleOpStore {
     fn operation_path(&self, id: &OperationId) -> PathBuf {
         self.path.join("operations").join(id.hex())
     }
+
+    /// Writes an operation or view file, compressing it if enabled.
+    fn write_object_file(&self, mut file: &File, data: &[u8]) -> io::Result<()> {
+        if self.compress_objects {
+            write_compressed(file, data)
+        } else {
+            file.write_all(data)
+        }
+    }
+
+    /// Rewrites uncompressed operation and view files in the compressed format.
+    /// Returns the number of rewritten files.
+    ///
+    /// The object IDs don't change since they're hashes of the contents, and
+    /// the modification times are preserved so gc isn't affected.
+    pub fn compress_objects(&self) -> OpStoreResult<usize> {
+        let compress_dir = |dir: &Path| -> Result<usize, PathError> {
+            let mut count = 0;
+            for entry in dir.read_dir().context(dir)? {
+                let path = entry.context(dir)?.path();
+                let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                if !name.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    continue; // Skip invalid file name
+                }
+                let buf = fs::read(&path).context(&path)?;
+                if buf.starts_with(COMPRESSED_OBJECT_MAGIC) {
+                    continue;
+                }
+                let mtime = fs::metadata(&path)
+                    .and_then(|metadata| metadata.modified())
+                    .context(&path)?;
+                let temp_file = NamedTempFile::new_in(&self.path).context(&self.path)?;
+                write_compressed(temp_file.as_file(), &buf).context(temp_file.path())?;
+                temp_file
+                    .as_file()
+                    .set_modified(mtime)
+                    .context(temp_file.path())?;
+                temp_file
+                    .persist(&path)
+                    .map_err(|err| err.error)
+                    .context(&path)?;
+                count += 1;
+            }
+            Ok(count)
+        };
+        let count = compress_dir(&self.path.join("operations"))
+            .and_then(|ops| Ok(ops + compress_dir(&self.path.join("views"))?))
+            .map_err(|err| OpStoreError::Other(err.into()))?;
+        Ok(count)
+    }
+
+    /// Lists operation and view files that are unreachable from `head_ids` and
+    /// older than `keep_newer`.
+    fn collect_expired_files(
+        &self,
+        head_ids: &[OperationId],
+        keep_newer: SystemTime,
+    ) -> OpStoreResult<ExpiredFiles> {
+        let to_op_id = |entry: &fs::DirEntry| -> Option<OperationId> {
+            let name = entry.file_name().into_string().ok()?;
+            OperationId::try_from_hex(&name).ok()
+        };
+        let to_view_id = |entry: &fs::DirEntry| -> Option<ViewId> {
+            let name = entry.file_name().into_string().ok()?;
+            ViewId::try_from_hex(&name).ok()
+        };
+        let to_expired_file = |entry: &fs::DirEntry| -> Result<Option<ExpiredFile>, PathError> {
+            let path = entry.path();
+            // Check timestamp, but there's still TOCTOU problem if an existing
+            // file is renewed.
+            let metadata = entry.metadata().context(&path)?;
+            let mtime = metadata.modified().expect("unsupported platform?");
+            if mtime > keep_newer {
+                tracing::trace!(?path, "not removing");
+                Ok(None)
+            } else {
+                let len = metadata.len();
+                Ok(Some(ExpiredFile { path, len }))
+            }
+        };
+
+        // Reachable objects are resolved without considering the keep_newer
+        // parameter. We could collect ancestors of the "new" operations here,
+        // but more files can be added anyway after that.
+        let read_op = |id: &OperationId| self.read_operation(id).map(|data| (id.clone(), data));
+        let reachable_ops: HashMap<OperationId, Operation> = dag_walk::dfs_ok(
+            head_ids.iter().map(read_op),
+            |(id, _)| id.clone(),
+            |(_, data)| data.parents.iter().map(read_op).collect_vec(),
+        )
+        .try_collect()?;
+        let reachable_views: HashSet<&ViewId> =
+            reachable_ops.values().map(|data| &data.view_id).collect();
+        tracing::info!(
+            reachable_op_count = reachable_ops.len(),
+            reachable_view_count = reachable_views.len(),
+            "collected reachable objects"
+        );
+
+        let collect_ops = || -> Result<Vec<(OperationId, ExpiredFile)>, PathError> {
+            let op_dir = self.path.join("operations");
+            let mut expired = vec![];
+            for entry in op_dir.read_dir().context(&op_dir)? {
+                let entry = entry.context(&op_dir)?;
+                let Some(id) = to_op_id(&entry) else {
+                    tracing::trace!(?entry, "skipping invalid file name");
+                    continue;
+                };
+                if reachable_ops.contains_key(&id) {
+                    continue;
+                }
+                if let Some(file) = to_expired_file(&entry)? {
+                    expired.push((id, file));
+                }
+            }
+            Ok(expired)
+        };
+        let operations = collect_ops().map_err(|err| OpStoreError::Other(err.into()))?;
+
+        let collect_views = || -> Result<Vec<ExpiredFile>, PathError> {
+            let view_dir = self.path.join("views");
+            let mut expired = vec![];
+            for entry in view_dir.read_dir().context(&view_dir)? {
+                let entry = entry.context(&view_dir)?;
+                let Some(id) = to_view_id(&entry) else {
+                    tracing::trace!(?entry, "skipping invalid file name");
+                    continue;
+                };
+                if reachable_views.contains(&id) {
+                    continue;
+                }
+                expired.extend(to_expired_file(&entry)?);
+            }
+            Ok(expired)
+        };
+        let views = collect_views().map_err(|err| OpStoreError::Other(err.into()))?;
+
+        Ok(ExpiredFiles { operations, views })
+    }
 }
 
 impl OpStore for SimpleOpStore {
@@ -137,7 +324,7 @@ impl OpStore for SimpleOpStore {
         }
 
         let path = self.view_path(id);
-        let buf = fs::read(path).map_err(|err| io_to_read_error(err, id))?;
+        let buf = read_object_file(&path).map_err(|err| io_to_read_error(err, id))?;
 
         let proto = crate::protos::op_store::View::decode(&*buf).map_err(|err| DecodeError {
             kind: "view",
@@ -152,9 +339,7 @@ impl OpStore for SimpleOpStore {
             NamedTempFile::new_in(&self.path).map_err(|err| io_to_write_error(err, "view"))?;
 
         let proto = view_to_proto(view);
-        temp_file
-            .as_file()
-            .write_all(&proto.encode_to_vec())
+        self.write_object_file(temp_file.as_file(), &proto.encode_to_vec())
             .map_err(|err| io_to_write_error(err, "view"))?;
 
         let id = ViewId::new(blake2b_hash(view).to_vec());
@@ -170,7 +355,7 @@ impl OpStore for SimpleOpStore {
         }
 
         let path = self.operation_path(id);
-        let buf = fs::read(path).map_err(|err| io_to_read_error(err, id))?;
+        let buf = read_object_file(&path).map_err(|err| io_to_read_error(err, id))?;
 
         let proto =
             crate::protos::op_store::Operation::decode(&*buf).map_err(|err| DecodeError {
@@ -193,9 +378,7 @@ impl OpStore for SimpleOpStore {
             NamedTempFile::new_in(&self.path).map_err(|err| io_to_write_error(err, "operation"))?;
 
         let proto = operation_to_proto(operation);
-        temp_file
-            .as_file()
-            .write_all(&proto.encode_to_vec())
+        self.write_object_file(temp_file.as_file(), &proto.encode_to_vec())
             .map_err(|err| io_to_write_error(err, "operation"))?;
 
         let id = OperationId::new(blake2b_hash(operation).to_vec());
@@ -250,88 +433,78 @@ impl OpStore for SimpleOpStore {
             .map_err(|err| OpStoreError::Other(err.into()))
     }
 
-    #[tracing::instrument(skip(self))]
-    fn gc(&self, head_ids: &[OperationId], keep_newer: SystemTime) -> OpStoreResult<()> {
-        let to_op_id = |entry: &fs::DirEntry| -> Option<OperationId> {
-            let name = entry.file_name().into_string().ok()?;
-            OperationId::try_from_hex(&name).ok()
-        };
-        let to_view_id = |entry: &fs::DirEntry| -> Option<ViewId> {
-            let name = entry.file_name().into_string().ok()?;
-            ViewId::try_from_hex(&name).ok()
-        };
-        let remove_file_if_not_new = |entry: &fs::DirEntry| -> Result<(), PathError> {
-            let path = entry.path();
-            // Check timestamp, but there's still TOCTOU problem if an existing
-            // file is renewed.
-            let metadata = entry.metadata().context(&path)?;
-            let mtime = metadata.modified().expect("unsupported platform?");
-            if mtime > keep_newer {
-                tracing::trace!(?path, "not removing");
-                Ok(())
-            } else {
-                tracing::trace!(?path, "removing");
-                fs::remove_file(&path).context(&path)
-            }
-        };
-
-        // Reachable objects are resolved without considering the keep_newer
-        // parameter. We could collect ancestors of the "new" operations here,
-        // but more files can be added anyway after that.
-        let read_op = |id: &OperationId| self.read_operation(id).map(|data| (id.clone(), data));
-        let reachable_ops: HashMap<OperationId, Operation> = dag_walk::dfs_ok(
-            head_ids.iter().map(read_op),
-            |(id, _)| id.clone(),
-            |(_, data)| data.parents.iter().map(read_op).collect_vec(),
-        )
-        .try_collect()?;
-        let reachable_views: HashSet<&ViewId> =
-            reachable_ops.values().map(|data| &data.view_id).collect();
-        tracing::info!(
-            reachable_op_count = reachable_ops.len(),
-            reachable_view_count = reachable_views.len(),
-            "collected reachable objects"
-        );
-
-        let prune_ops = || -> Result<(), PathError> {
-            let op_dir = self.path.join("operations");
-            for entry in op_dir.read_dir().context(&op_dir)? {
-                let entry = entry.context(&op_dir)?;
-                let Some(id) = to_op_id(&entry) else {
-                    tracing::trace!(?entry, "skipping invalid file name");
-                    continue;
+    fn operation_ids(&self) -> OpStoreResult<Vec<OperationId>> {
+        let op_dir = self.path.join("operations");
+        let list = || -> io::Result<_> {
+            let mut op_ids = vec![self.root_operation_id.clone()];
+            for entry in op_dir.read_dir()? {
+                let Ok(name) = entry?.file_name().into_string() else {
+                    continue; // Skip invalid UTF-8
                 };
-                if reachable_ops.contains_key(&id) {
-                    continue;
-                }
-                // If the operation was added after collecting reachable_views,
-                // its view mtime would also be renewed. So there's no need to
-                // update the reachable_views set to preserve the view.
-                remove_file_if_not_new(&entry)?;
+                let Ok(id) = OperationId::try_from_hex(&name) else {
+                    continue; // Skip invalid hex
+                };
+                op_ids.push(id);
             }
-            Ok(())
+            Ok(op_ids)
         };
-        prune_ops().map_err(|err| OpStoreError::Other(err.into()))?;
+        list()
+            .context(&op_dir)
+            .map_err(|err| OpStoreError::Other(err.into()))
+    }
 
-        let prune_views = || -> Result<(), PathError> {
-            let view_dir = self.path.join("views");
-            for entry in view_dir.read_dir().context(&view_dir)? {
-                let entry = entry.context(&view_dir)?;
-                let Some(id) = to_view_id(&entry) else {
-                    tracing::trace!(?entry, "skipping invalid file name");
-                    continue;
-                };
-                if reachable_views.contains(&id) {
-                    continue;
-                }
-                remove_file_if_not_new(&entry)?;
+    #[tracing::instrument(skip(self))]
+    fn gc(&self, head_ids: &[OperationId], keep_newer: SystemTime) -> OpStoreResult<()> {
+        let expired = self.collect_expired_files(head_ids, keep_newer)?;
+        // If an operation was added after collecting reachable views, its view
+        // mtime would also be renewed. So there's no need to update the
+        // reachable views to preserve the view.
+        let remove_files = || -> Result<(), PathError> {
+            let op_files = expired.operations.iter().map(|(_, file)| file);
+            for file in op_files.chain(&expired.views) {
+                tracing::trace!(path = ?file.path, "removing");
+                fs::remove_file(&file.path).context(&file.path)?;
             }
             Ok(())
         };
-        prune_views().map_err(|err| OpStoreError::Other(err.into()))?;
-
+        remove_files().map_err(|err| OpStoreError::Other(err.into()))?;
         Ok(())
     }
+
+    fn gc_dry_run(
+        &self,
+        head_ids: &[OperationId],
+        keep_newer: SystemTime,
+    ) -> OpStoreResult<OpStoreGcStats> {
+        let expired = self.collect_expired_files(head_ids, keep_newer)?;
+        let reclaimable_bytes = expired
+            .operations
+            .iter()
+            .map(|(_, file)| file)
+            .chain(&expired.views)
+            .map(|file| file.len)
+            .sum::<u64>();
+        Ok(OpStoreGcStats {
+            expired_operation_ids: expired.operations.into_iter().map(|(id, _)| id).collect(),
+            expired_view_count: expired.views.len(),
+            reclaimable_bytes: Some(reclaimable_bytes),
+        })
+    }
+}
+
+/// Reads an operation or view file, decompressing it if needed.
+fn read_object_file(path: &Path) -> io::Result<Vec<u8>> {
+    let buf = fs::read(path)?;
+    if let Some(compressed) = buf.strip_prefix(COMPRESSED_OBJECT_MAGIC) {
+        zstd::decode_all(compressed)
+    } else {
+        Ok(buf)
+    }
+}
+
+fn write_compressed(mut file: &File, data: &[u8]) -> io::Result<()> {
+    file.write_all(COMPRESSED_OBJECT_MAGIC)?;
+    file.write_all(&zstd::encode_all(data, COMPRESSION_LEVEL)?)
 }
 
 fn io_to_read_error(err: std::io::Error, id: &impl ObjectId) -> OpStoreError {
@@ -801,6 +974,65 @@ mod tests {
         assert_eq!(read_operation, operation);
     }
 
+    #[test]
+    fn test_read_write_compressed() {
+        let temp_dir = testutils::new_temp_dir();
+        let root_data = RootOperationData {
+            root_commit_id: CommitId::from_hex("000000"),
+        };
+        let store = SimpleOpStore::init(temp_dir.path(), root_data);
+        // Files are written uncompressed unless enabled
+        assert!(!store.compresses_objects());
+        let view = create_view();
+        let view_id = store.write_view(&view).unwrap();
+        let view_buf = fs::read(store.view_path(&view_id)).unwrap();
+        assert!(!view_buf.starts_with(COMPRESSED_OBJECT_MAGIC));
+
+        let store = store.with_compression(true);
+        let operation = create_operation();
+        let op_id = store.write_operation(&operation).unwrap();
+        let op_buf = fs::read(store.operation_path(&op_id)).unwrap();
+        assert!(op_buf.starts_with(COMPRESSED_OBJECT_MAGIC));
+        assert_eq!(store.read_view(&view_id).unwrap(), view);
+        assert_eq!(store.read_operation(&op_id).unwrap(), operation);
+    }
+
+    #[test]
+    fn test_read_compress_legacy_objects() {
+        let temp_dir = testutils::new_temp_dir();
+        let root_data = RootOperationData {
+            root_commit_id: CommitId::from_hex("000000"),
+        };
+        let store = SimpleOpStore::init(temp_dir.path(), root_data).with_compression(true);
+        let view = create_view();
+        let operation = create_operation();
+        let view_id = ViewId::new(blake2b_hash(&view).to_vec());
+        let op_id = OperationId::new(blake2b_hash(&operation).to_vec());
+        // Write uncompressed files as older versions do
+        let view_path = store.view_path(&view_id);
+        let op_path = store.operation_path(&op_id);
+        fs::write(&view_path, view_to_proto(&view).encode_to_vec()).unwrap();
+        fs::write(&op_path, operation_to_proto(&operation).encode_to_vec()).unwrap();
+        assert_eq!(store.read_view(&view_id).unwrap(), view);
+        assert_eq!(store.read_operation(&op_id).unwrap(), operation);
+
+        let op_mtime = fs::metadata(&op_path).unwrap().modified().unwrap();
+        assert_eq!(store.compress_objects().unwrap(), 2);
+        assert!(fs::read(&view_path)
+            .unwrap()
+            .starts_with(COMPRESSED_OBJECT_MAGIC));
+        assert!(fs::read(&op_path)
+            .unwrap()
+            .starts_with(COMPRESSED_OBJECT_MAGIC));
+        assert_eq!(
+            fs::metadata(&op_path).unwrap().modified().unwrap(),
+            op_mtime
+        );
+        assert_eq!(store.read_view(&view_id).unwrap(), view);
+        assert_eq!(store.read_operation(&op_id).unwrap(), operation);
+        assert_eq!(store.compress_objects().unwrap(), 0);
+    }
+
     #[test]
     fn test_bookmark_views_legacy_roundtrip() {
         let new_remote_ref = |target: &RefTarget| RemoteRef {
@@ -23,7 +23,12 @@ use std::process::Command;
 use std::process::ExitStatus;
 use std::process::Stdio;
 
+use base64::prelude::BASE64_STANDARD;
+use base64::prelude::BASE64_STANDARD_NO_PAD;
+use base64::Engine as _;
 use either::Either;
+use sha2::Digest as _;
+use sha2::Sha256;
 use thiserror::Error;
 
 use crate::config::ConfigGetError;
@@ -38,6 +43,7 @@ use crate::signing::Verification;
 pub struct SshBackend {
     program: OsString,
     allowed_signers: Option<OsString>,
+    agent_program: OsString,
 }
 
 #[derive(Debug, Error)]
@@ -53,6 +59,8 @@ pub enum SshError {
     Io(#[from] std::io::Error),
     #[error("Signing key required")]
     MissingKey,
+    #[error("No key matching {0} found in ssh-agent")]
+    AgentKeyNotFound(String),
 }
 
 impl From<SshError> for SignError {
@@ -111,20 +119,97 @@ fn ensure_key_as_file(key: &str) -> SshResult<Either<PathBuf, tempfile::TempPath
     Ok(either::Right(pub_key_path))
 }
 
+/// Prefix of signing keys that select an ssh-agent key by its comment.
+const AGENT_KEY_PREFIX: &str = "agent:";
+
+/// Whether the signing key refers to a key loaded in ssh-agent, either by
+/// its SHA256 fingerprint or by its comment.
+fn is_agent_key_selector(key: &str) -> bool {
+    key.starts_with("SHA256:") || key.starts_with(AGENT_KEY_PREFIX)
+}
+
+/// Computes the `SHA256:<base64>` fingerprint of a base64-encoded public key
+/// blob, as printed by `ssh-add -l -E sha256`.
+fn agent_key_fingerprint(key_data: &str) -> SshResult<String> {
+    let blob = BASE64_STANDARD
+        .decode(key_data)
+        .map_err(|_| SshError::BadResult)?;
+    let digest = Sha256::digest(blob);
+    Ok(format!("SHA256:{}", BASE64_STANDARD_NO_PAD.encode(digest)))
+}
+
+/// Finds the public key selected by `selector` in the output of `ssh-add -L`.
+fn select_agent_key(public_keys: &str, selector: &str) -> SshResult<String> {
+    for public_key in public_keys.lines().filter(|line| !line.is_empty()) {
+        // <type> <base64 data> [comment]
+        let mut key_parts = public_key.splitn(3, ' ');
+        let (Some(key_type), Some(key_data)) = (key_parts.next(), key_parts.next()) else {
+            return Err(SshError::BadResult);
+        };
+        let comment = key_parts.next().unwrap_or("").trim();
+        let matches = match selector.strip_prefix(AGENT_KEY_PREFIX) {
+            Some(wanted_comment) => comment == wanted_comment,
+            None => agent_key_fingerprint(key_data)? == selector,
+        };
+        if matches {
+            return Ok(format!("{key_type} {key_data}"));
+        }
+    }
+    Err(SshError::AgentKeyNotFound(selector.to_owned()))
+}
+
 impl SshBackend {
     pub fn new(program: OsString, allowed_signers: Option<OsString>) -> Self {
         Self {
             program,
             allowed_signers,
+            agent_program: "ssh-add".into(),
         }
     }
 
+    /// Sets the program used to list the keys loaded in ssh-agent.
+    pub fn with_agent_program(mut self, agent_program: OsString) -> Self {
+        self.agent_program = agent_program;
+        self
+    }
+
     pub fn from_settings(settings: &UserSettings) -> Result<Self, ConfigGetError> {
         let program = settings.get_string("signing.backends.ssh.program")?;
         let allowed_signers = settings
             .get_string("signing.backends.ssh.allowed-signers")
             .optional()?;
-        Ok(Self::new(program.into(), allowed_signers.map(|v| v.into())))
+        let agent_program = settings.get_string("signing.backends.ssh.agent-program")?;
+        Ok(Self::new(program.into(), allowed_signers.map(|v| v.into()))
+            .with_agent_program(agent_program.into()))
+    }
+
+    /// Lists the public keys loaded in ssh-agent, one per line.
+    fn list_agent_keys(&self) -> SshResult<String> {
+        let mut command = Command::new(&self.agent_program);
+        command
+            .arg("-L")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        tracing::info!(?command, "running ssh-agent listing command");
+        let output = command.output()?;
+        tracing::info!(?command, ?output.status, "ssh-agent listing command exited");
+        match output.status.code() {
+            Some(0) => parse_utf8_string(output.stdout),
+            // ssh-add exits with 1 if the agent has no identities, and with 2
+            // if the agent couldn't be contacted.
+            Some(1) => Ok(String::new()),
+            _ => Err(SshError::Command {
+                exit_status: output.status,
+                stderr: String::from_utf8_lossy(&output.stderr).trim_end().into(),
+            }),
+        }
+    }
+
+    /// Looks up the public key of an ssh-agent key by fingerprint or comment.
+    fn find_agent_key(&self, selector: &str) -> SshResult<String> {
+        let public_keys = self.list_agent_keys()?;
+        select_agent_key(&public_keys, selector)
     }
 
     fn create_command(&self) -> Command {
@@ -190,12 +275,20 @@ impl SigningBackend for SshBackend {
             return Err(SshError::MissingKey.into());
         };
 
+        // Keys in ssh-agent are passed to ssh-keygen as public keys, and `-U`
+        // tells it that the private key lives in the agent rather than on disk.
+        let (key, use_agent) = if is_agent_key_selector(key) {
+            (self.find_agent_key(key)?, true)
+        } else {
+            (key.to_owned(), false)
+        };
+
         // The ssh-keygen `-f` flag expects to be given a file which contains either a
         // private or public key.
         //
         // As it expects a file and we might have an inlined public key instead, we need
         // to ensure it is written to a file first.
-        let pub_key_path = ensure_key_as_file(key)?;
+        let pub_key_path = ensure_key_as_file(&key)?;
         let mut command = self.create_command();
 
         let path = match &pub_key_path {
@@ -210,6 +303,9 @@ impl SigningBackend for SshBackend {
             .arg(path)
             .arg("-n")
             .arg("git");
+        if use_agent {
+            command.arg("-U");
+        }
 
         Ok(run_command(&mut command, data)?)
     }
@@ -279,6 +375,8 @@ mod tests {
     use std::fs::File;
     use std::io::Read;
 
+    use assert_matches::assert_matches;
+
     use super::*;
 
     #[test]
@@ -313,4 +411,63 @@ mod tests {
             path.left().unwrap().to_str().unwrap()
         );
     }
+
+    const AGENT_PUBLIC_KEYS: &str = "\
+ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGj+J6N6SO+4P8dOZqfR1oiay2yxhhHnagH52avUqw5h work laptop
+ssh-rsa AAAAB3NzaC1yc2EAAAADAQABAAABAQC7 yubikey
+";
+
+    #[test]
+    fn test_agent_key_fingerprint() {
+        assert_eq!(
+            agent_key_fingerprint(
+                "AAAAC3NzaC1lZDI1NTE5AAAAIGj+J6N6SO+4P8dOZqfR1oiay2yxhhHnagH52avUqw5h"
+            )
+            .unwrap(),
+            "SHA256:CaeelDOMvTqGZPjAS9fdbnACrLg68N1Bb9ux5y6GjGw"
+        );
+        assert_matches!(
+            agent_key_fingerprint("not base64!"),
+            Err(SshError::BadResult)
+        );
+    }
+
+    #[test]
+    fn test_select_agent_key_by_fingerprint() {
+        assert_eq!(
+            select_agent_key(
+                AGENT_PUBLIC_KEYS,
+                "SHA256:HPlRPaJS3AalL0f2B3TkvOVkd9tmwMs8k9hR+TLJWRQ"
+            )
+            .unwrap(),
+            "ssh-rsa AAAAB3NzaC1yc2EAAAADAQABAAABAQC7"
+        );
+        assert_matches!(
+            select_agent_key(
+                AGENT_PUBLIC_KEYS,
+                "SHA256:4YgCr4Ol7Cp5YJi0AkaAo3yIHrYfDNqHCFvu1SNxQhU"
+            ),
+            Err(SshError::AgentKeyNotFound(_))
+        );
+    }
+
+    #[test]
+    fn test_select_agent_key_by_comment() {
+        assert_eq!(
+            select_agent_key(AGENT_PUBLIC_KEYS, "agent:work laptop").unwrap(),
+            "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGj+J6N6SO+4P8dOZqfR1oiay2yxhhHnagH52avUqw5h"
+        );
+        assert_matches!(
+            select_agent_key(AGENT_PUBLIC_KEYS, "agent:work"),
+            Err(SshError::AgentKeyNotFound(_))
+        );
+    }
+
+    #[test]
+    fn test_select_agent_key_no_identities() {
+        assert_matches!(
+            select_agent_key("", "agent:work laptop"),
+            Err(SshError::AgentKeyNotFound(_))
+        );
+    }
 }
@@ -417,9 +417,14 @@ impl TableStore {
         }
     }
 
-    pub fn reinit(&self) {
-        std::fs::remove_dir_all(self.dir.join("heads")).unwrap();
-        TableStore::init(self.dir.clone(), self.key_size);
+    pub fn reinit(&self) -> TableStoreResult<()> {
+        let heads_dir = self.dir.join("heads");
+        match std::fs::remove_dir_all(&heads_dir) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(TableStoreError::SaveHeads(err)),
+        }
+        std::fs::create_dir(&heads_dir).map_err(TableStoreError::SaveHeads)
     }
 
     pub fn key_size(&self) -> usize {
@@ -23,11 +23,15 @@ use std::sync::Mutex;
 use std::time::SystemTime;
 
 use clru::CLruCache;
+use futures::stream;
 use futures::stream::BoxStream;
+use futures::StreamExt as _;
+use futures::TryStreamExt as _;
 use pollster::FutureExt;
 
 use crate::backend;
 use crate::backend::Backend;
+use crate::backend::BackendGcStats;
 use crate::backend::BackendResult;
 use crate::backend::ChangeId;
 use crate::backend::CommitId;
@@ -45,6 +49,7 @@ use crate::merge::MergedTreeValue;
 use crate::merged_tree::MergedTree;
 use crate::repo_path::RepoPath;
 use crate::repo_path::RepoPathBuf;
+use crate::settings::MemoryBudget;
 use crate::signing::Signer;
 use crate::tree::Tree;
 use crate::tree_builder::TreeBuilder;
@@ -59,6 +64,7 @@ const TREE_CACHE_CAPACITY: usize = 1000;
 pub struct Store {
     backend: Box<dyn Backend>,
     signer: Signer,
+    memory_budget: MemoryBudget,
     commit_cache: Mutex<CLruCache<CommitId, Arc<backend::Commit>>>,
     tree_cache: Mutex<CLruCache<(RepoPathBuf, TreeId), Arc<backend::Tree>>>,
 }
@@ -73,11 +79,23 @@ impl Debug for Store {
 
 impl Store {
     pub fn new(backend: Box<dyn Backend>, signer: Signer) -> Arc<Self> {
+        Self::with_memory_budget(backend, signer, &MemoryBudget::default())
+    }
+
+    /// Creates a store whose caches are sized to fit in the `budget`.
+    pub fn with_memory_budget(
+        backend: Box<dyn Backend>,
+        signer: Signer,
+        budget: &MemoryBudget,
+    ) -> Arc<Self> {
+        let commit_cache_capacity = budget.commit_cache_capacity(COMMIT_CACHE_CAPACITY);
+        let tree_cache_capacity = budget.tree_cache_capacity(TREE_CACHE_CAPACITY);
         Arc::new(Store {
             backend,
             signer,
-            commit_cache: Mutex::new(CLruCache::new(COMMIT_CACHE_CAPACITY.try_into().unwrap())),
-            tree_cache: Mutex::new(CLruCache::new(TREE_CACHE_CAPACITY.try_into().unwrap())),
+            memory_budget: *budget,
+            commit_cache: Mutex::new(CLruCache::new(commit_cache_capacity.try_into().unwrap())),
+            tree_cache: Mutex::new(CLruCache::new(tree_cache_capacity.try_into().unwrap())),
         })
     }
 
@@ -89,6 +107,11 @@ impl Store {
         &self.signer
     }
 
+    /// Limits on memory used by caches of this store and of the repo.
+    pub fn memory_budget(&self) -> &MemoryBudget {
+        &self.memory_budget
+    }
+
     pub fn get_copy_records(
         &self,
         paths: Option<&[RepoPathBuf]>,
@@ -126,6 +149,15 @@ impl Store {
         MergedTreeId::resolved(self.backend.empty_tree_id().clone())
     }
 
+    /// Returns true if the parents of the commit were cut off, in which case
+    /// the root commit is reported as its parent instead.
+    pub fn is_shallow_commit(&self, id: &CommitId) -> BackendResult<bool> {
+        if id == self.root_commit_id() {
+            return Ok(false);
+        }
+        self.backend.is_shallow_commit(id)
+    }
+
     pub fn root_commit(self: &Arc<Self>) -> Commit {
         self.get_commit(self.backend.root_commit_id()).unwrap()
     }
@@ -139,6 +171,19 @@ impl Store {
         Ok(Commit::new(self.clone(), id.clone(), data))
     }
 
+    /// Loads commits in batch. Up to `concurrency()` requests are issued to the
+    /// backend at a time. The returned commits are in the same order as `ids`.
+    pub async fn get_commits_async<'a>(
+        self: &Arc<Self>,
+        ids: impl IntoIterator<Item = &'a CommitId>,
+    ) -> BackendResult<Vec<Commit>> {
+        stream::iter(ids)
+            .map(|id| self.get_commit_async(id))
+            .buffered(self.concurrency().max(1))
+            .try_collect()
+            .await
+    }
+
     async fn get_backend_commit(&self, id: &CommitId) -> BackendResult<Arc<backend::Commit>> {
         {
             let mut locked_cache = self.commit_cache.lock().unwrap();
@@ -203,13 +248,22 @@ impl Store {
     }
 
     pub fn get_root_tree(self: &Arc<Self>, id: &MergedTreeId) -> BackendResult<MergedTree> {
+        self.get_root_tree_async(id).block_on()
+    }
+
+    pub async fn get_root_tree_async(
+        self: &Arc<Self>,
+        id: &MergedTreeId,
+    ) -> BackendResult<MergedTree> {
         match &id {
             MergedTreeId::Legacy(id) => {
-                let tree = self.get_tree(RepoPathBuf::root(), id)?;
+                let tree = self.get_tree_async(RepoPathBuf::root(), id).await?;
                 MergedTree::from_legacy_tree(tree)
             }
             MergedTreeId::Merge(ids) => {
-                let trees = ids.try_map(|id| self.get_tree(RepoPathBuf::root(), id))?;
+                let trees = ids
+                    .try_map_async(|id| self.get_tree_async(RepoPathBuf::root(), id))
+                    .await?;
                 Ok(MergedTree::new(trees))
             }
         }
@@ -291,4 +345,12 @@ impl Store {
     pub fn gc(&self, index: &dyn Index, keep_newer: SystemTime) -> BackendResult<()> {
         self.backend.gc(index, keep_newer)
     }
+
+    pub fn gc_dry_run(
+        &self,
+        index: &dyn Index,
+        keep_newer: SystemTime,
+    ) -> BackendResult<BackendGcStats> {
+        self.backend.gc_dry_run(index, keep_newer)
+    }
 }
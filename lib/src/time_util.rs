@@ -103,9 +103,7 @@ impl DatePattern {
     where
         Tz::Offset: Copy,
     {
-        let d =
-            parse_date_string(s, now, Dialect::Us).map_err(DatePatternParseError::ParseError)?;
-        let millis_since_epoch = MillisSinceEpoch(d.timestamp_millis());
+        let millis_since_epoch = parse_datetime(s, now)?;
         match kind {
             "after" => Ok(DatePattern::AtOrAfter(millis_since_epoch)),
             "before" => Ok(DatePattern::Before(millis_since_epoch)),
@@ -122,6 +120,19 @@ impl DatePattern {
     }
 }
 
+/// Parses a date string such as "2024-02-01" or "2 days ago" relative to
+/// `now`.
+pub fn parse_datetime<Tz: TimeZone>(
+    s: &str,
+    now: DateTime<Tz>,
+) -> Result<MillisSinceEpoch, DateError>
+where
+    Tz::Offset: Copy,
+{
+    let d = parse_date_string(s, now, Dialect::Us)?;
+    Ok(MillisSinceEpoch(d.timestamp_millis()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
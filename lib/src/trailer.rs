@@ -0,0 +1,221 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing trailers from commit descriptions.
+
+use itertools::Itertools as _;
+use thiserror::Error;
+
+/// A key-value pair found at the end of a commit description, such as
+/// `Signed-off-by: Alice <alice@example.com>`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Trailer {
+    /// Trailer key, e.g. `Signed-off-by`.
+    pub key: String,
+    /// Trailer value, with surrounding whitespace trimmed.
+    pub value: String,
+}
+
+/// Error occurred while parsing trailers.
+#[derive(Debug, Error)]
+#[error("Invalid trailer line: {line}")]
+pub struct TrailerParseError {
+    /// The line that isn't a `Key: value` pair.
+    pub line: String,
+}
+
+/// Parses the trailers from the last paragraph of the description.
+///
+/// The last paragraph is considered a trailer block only if every line in it
+/// is a trailer and it isn't the first paragraph (i.e. the subject). Otherwise,
+/// an empty list is returned.
+pub fn parse_description_trailers(description: &str) -> Vec<Trailer> {
+    match last_paragraph(description) {
+        Some(paragraph) => parse_trailers(paragraph).unwrap_or_default(),
+        None => vec![],
+    }
+}
+
+/// Parses each non-empty line of the text as a `Key: value` trailer.
+pub fn parse_trailers(text: &str) -> Result<Vec<Trailer>, TrailerParseError> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            parse_trailer_line(line).ok_or_else(|| TrailerParseError {
+                line: line.to_owned(),
+            })
+        })
+        .try_collect()
+}
+
+/// Appends the trailers to the description, skipping the ones that are
+/// already present in its trailer block.
+///
+/// Trailers are added to the existing trailer block if there is one, or to a
+/// new paragraph otherwise. An empty description is returned unchanged.
+pub fn add_trailers(description: &str, trailers: &[Trailer]) -> String {
+    if description.trim().is_empty() {
+        return description.to_owned();
+    }
+    let mut existing = parse_description_trailers(description);
+    let has_trailer_block = !existing.is_empty();
+    let mut new_trailers = vec![];
+    for trailer in trailers {
+        if !existing.contains(trailer) {
+            existing.push(trailer.clone());
+            new_trailers.push(trailer);
+        }
+    }
+    if new_trailers.is_empty() {
+        return description.to_owned();
+    }
+    let mut output = description.trim_end().to_owned();
+    output.push('\n');
+    if !has_trailer_block {
+        output.push('\n');
+    }
+    for Trailer { key, value } in new_trailers {
+        output.push_str(&format!("{key}: {value}\n"));
+    }
+    output
+}
+
+fn last_paragraph(description: &str) -> Option<&str> {
+    let trimmed = description.trim_end();
+    let (_, paragraph) = trimmed.rsplit_once("\n\n")?;
+    (!paragraph.trim().is_empty()).then_some(paragraph)
+}
+
+fn parse_trailer_line(line: &str) -> Option<Trailer> {
+    let (key, value) = line.split_once(':')?;
+    let is_valid_key = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    is_valid_key.then(|| Trailer {
+        key: key.to_owned(),
+        value: value.trim().to_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn trailer(key: &str, value: &str) -> Trailer {
+        Trailer {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_parse_description_trailers() {
+        assert_eq!(parse_description_trailers(""), vec![]);
+        // The subject line is never a trailer
+        assert_eq!(parse_description_trailers("Fixes: bug\n"), vec![]);
+        assert_eq!(
+            parse_description_trailers(indoc! {"
+                subject
+
+                body text
+            "}),
+            vec![]
+        );
+        assert_eq!(
+            parse_description_trailers(indoc! {"
+                subject
+
+                Reviewed-by: Bob <bob@example.com>
+                Change-Id: I1234
+            "}),
+            vec![
+                trailer("Reviewed-by", "Bob <bob@example.com>"),
+                trailer("Change-Id", "I1234"),
+            ]
+        );
+        // Only the last paragraph is considered
+        assert_eq!(
+            parse_description_trailers(indoc! {"
+                subject
+
+                Change-Id: I1234
+
+                not a trailer
+            "}),
+            vec![]
+        );
+        // Keys can't contain spaces
+        assert_eq!(
+            parse_description_trailers(indoc! {"
+                subject
+
+                Not a trailer: value
+            "}),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_parse_trailers() {
+        assert_eq!(
+            parse_trailers("Change-Id: I1234\n\nFoo:bar\n").unwrap(),
+            vec![trailer("Change-Id", "I1234"), trailer("Foo", "bar")]
+        );
+        assert_eq!(
+            parse_trailers("Change-Id I1234\n").unwrap_err().line,
+            "Change-Id I1234"
+        );
+    }
+
+    #[test]
+    fn test_add_trailers() {
+        let signoff = trailer("Signed-off-by", "Alice <alice@example.com>");
+        assert_eq!(add_trailers("", &[signoff.clone()]), "");
+        assert_eq!(
+            add_trailers("subject", &[signoff.clone()]),
+            indoc! {"
+                subject
+
+                Signed-off-by: Alice <alice@example.com>
+            "}
+        );
+        // Appended to the existing trailer block
+        assert_eq!(
+            add_trailers(
+                "subject\n\nChange-Id: I1234\n\n",
+                &[trailer("Change-Id", "I1234"), signoff.clone()]
+            ),
+            indoc! {"
+                subject
+
+                Change-Id: I1234
+                Signed-off-by: Alice <alice@example.com>
+            "}
+        );
+        // Existing trailers aren't duplicated
+        let description = add_trailers("subject\n", &[signoff.clone(), signoff.clone()]);
+        assert_eq!(add_trailers(&description, &[signoff]), description);
+        assert_eq!(
+            description,
+            indoc! {"
+                subject
+
+                Signed-off-by: Alice <alice@example.com>
+            "}
+        );
+    }
+}
@@ -28,11 +28,13 @@ use thiserror::Error;
 use crate::backend::BackendInitError;
 use crate::backend::MergedTreeId;
 use crate::commit::Commit;
+use crate::config::ConfigGetError;
 use crate::file_util::IoResultExt as _;
 use crate::file_util::PathError;
 use crate::local_backend::LocalBackend;
 use crate::local_working_copy::LocalWorkingCopy;
 use crate::local_working_copy::LocalWorkingCopyFactory;
+use crate::object_store_backend::ObjectStoreBackend;
 use crate::op_heads_store::OpHeadsStoreError;
 use crate::op_store::OperationId;
 use crate::op_store::WorkspaceId;
@@ -79,6 +81,8 @@ pub enum WorkspaceInitError {
     Backend(#[from] BackendInitError),
     #[error(transparent)]
     SignInit(#[from] SignInitError),
+    #[error(transparent)]
+    Config(#[from] ConfigGetError),
 }
 
 #[derive(Error, Debug)]
@@ -190,6 +194,22 @@ impl Workspace {
         Self::init_with_backend(user_settings, workspace_root, backend_initializer, signer)
     }
 
+    /// Initializes a workspace with a new object store backend which stores
+    /// objects at the given URL.
+    pub fn init_object_store(
+        user_settings: &UserSettings,
+        workspace_root: &Path,
+        url: &str,
+    ) -> Result<(Self, Arc<ReadonlyRepo>), WorkspaceInitError> {
+        let backend_initializer: &BackendInitializer = &|settings, store_path| {
+            Ok(Box::new(ObjectStoreBackend::init(
+                settings, store_path, url,
+            )?))
+        };
+        let signer = Signer::from_settings(user_settings)?;
+        Self::init_with_backend(user_settings, workspace_root, backend_initializer, signer)
+    }
+
     /// Initializes a workspace with a new Git backend and bare Git repo in
     /// `.jj/repo/store/git`.
     #[cfg(feature = "git")]
@@ -307,6 +327,7 @@ impl Workspace {
                 RepoInitError::Backend(err) => WorkspaceInitError::Backend(err),
                 RepoInitError::OpHeadsStore(err) => WorkspaceInitError::OpHeadsStore(err),
                 RepoInitError::Path(err) => WorkspaceInitError::Path(err),
+                RepoInitError::Config(err) => WorkspaceInitError::Config(err),
             })?;
             let (working_copy, repo) = init_working_copy(
                 &repo,
@@ -8,10 +8,12 @@ fn test_no_forgotten_test_files() {
 
 mod test_annotate;
 mod test_bad_locking;
+mod test_bisect;
 mod test_commit_builder;
 mod test_commit_concurrent;
 mod test_conflicts;
 mod test_default_revset_graph_iterator;
+mod test_fsck;
 mod test_git;
 mod test_git_backend;
 mod test_gpg;
@@ -22,11 +24,13 @@ mod test_load_repo;
 mod test_local_working_copy;
 mod test_local_working_copy_concurrent;
 mod test_local_working_copy_sparse;
+mod test_maintenance;
 mod test_merge_trees;
 mod test_merged_tree;
 mod test_mut_repo;
 mod test_operations;
 mod test_refs;
+mod test_repro_bundle;
 mod test_revset;
 mod test_rewrite;
 mod test_rewrite_duplicate;
@@ -35,3 +39,4 @@ mod test_signing;
 mod test_ssh_signing;
 mod test_view;
 mod test_workspace;
+mod test_x509_signing;
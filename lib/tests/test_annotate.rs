@@ -13,10 +13,12 @@
 // limitations under the License.
 
 use std::fmt::Write as _;
+use std::ops::Range;
 use std::rc::Rc;
 
 use jj_lib::annotate::get_annotation_for_file;
 use jj_lib::annotate::get_annotation_with_file_content;
+use jj_lib::annotate::AnnotateOptions;
 use jj_lib::annotate::FileAnnotation;
 use jj_lib::backend::CommitId;
 use jj_lib::backend::MergedTreeId;
@@ -32,6 +34,7 @@ use jj_lib::revset::ResolvedRevsetExpression;
 use jj_lib::revset::RevsetExpression;
 use testutils::create_tree;
 use testutils::TestRepo;
+use testutils::TestRepoBackend;
 
 fn create_commit_fn(
     mut_repo: &mut MutableRepo,
@@ -68,7 +71,43 @@ fn annotate_within(
     domain: &Rc<ResolvedRevsetExpression>,
     file_path: &RepoPath,
 ) -> String {
-    let annotation = get_annotation_for_file(repo, commit, domain, file_path).unwrap();
+    let options = AnnotateOptions::default();
+    let annotation = get_annotation_for_file(repo, commit, domain, file_path, &options).unwrap();
+    format_annotation(repo, &annotation)
+}
+
+fn annotate_lines(
+    repo: &dyn Repo,
+    commit: &Commit,
+    file_path: &RepoPath,
+    line_range: Range<usize>,
+) -> String {
+    let domain = RevsetExpression::all();
+    let options = AnnotateOptions {
+        line_range: Some(line_range),
+        ..Default::default()
+    };
+    let annotation = get_annotation_for_file(repo, commit, &domain, file_path, &options).unwrap();
+    format_annotation(repo, &annotation)
+}
+
+fn annotate_following_renames(repo: &dyn Repo, commit: &Commit, file_path: &RepoPath) -> String {
+    let domain = RevsetExpression::all();
+    let options = AnnotateOptions {
+        follow_renames: true,
+        ..Default::default()
+    };
+    let annotation = get_annotation_for_file(repo, commit, &domain, file_path, &options).unwrap();
+    format_annotation(repo, &annotation)
+}
+
+fn annotate_ignoring_whitespace(repo: &dyn Repo, commit: &Commit, file_path: &RepoPath) -> String {
+    let domain = RevsetExpression::all();
+    let options = AnnotateOptions {
+        ignore_whitespace: true,
+        ..Default::default()
+    };
+    let annotation = get_annotation_for_file(repo, commit, &domain, file_path, &options).unwrap();
     format_annotation(repo, &annotation)
 }
 
@@ -138,6 +177,88 @@ fn test_annotate_linear() {
     "#);
 }
 
+#[test]
+fn test_annotate_line_range() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path = RepoPath::from_internal_string("file");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let content1 = "1a\n1b\n1c\n";
+    let content2 = "1a\n2\n1c\n";
+    let content3 = "1a\n2\n1c\n3\n";
+    let tree1 = create_tree(repo, &[(file_path, content1)]);
+    let tree2 = create_tree(repo, &[(file_path, content2)]);
+    let tree3 = create_tree(repo, &[(file_path, content3)]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1.id());
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2.id());
+    let commit3 = create_commit("commit3", &[commit2.id()], tree3.id());
+    drop(create_commit);
+
+    // Lines outside of the range are left unresolved
+    insta::assert_snapshot!(annotate_lines(tx.repo(), &commit3, file_path, 1..3), @r#"
+    *******: 1a
+    commit2: 2
+    commit1: 1c
+    *******: 3
+    "#);
+    insta::assert_snapshot!(annotate_lines(tx.repo(), &commit3, file_path, 3..4), @r#"
+    *******: 1a
+    *******: 2
+    *******: 1c
+    commit3: 3
+    "#);
+
+    // Out-of-bounds range
+    insta::assert_snapshot!(annotate_lines(tx.repo(), &commit3, file_path, 3..10), @r#"
+    *******: 1a
+    *******: 2
+    *******: 1c
+    commit3: 3
+    "#);
+    insta::assert_snapshot!(annotate_lines(tx.repo(), &commit3, file_path, 5..10), @r#"
+    *******: 1a
+    *******: 2
+    *******: 1c
+    *******: 3
+    "#);
+}
+
+#[test]
+fn test_annotate_ignore_whitespace() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path = RepoPath::from_internal_string("file");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let content1 = "1a\n1b\n1c\n";
+    let content2 = "  1a\n1 b\n2\n  1c\n";
+    let tree1 = create_tree(repo, &[(file_path, content1)]);
+    let tree2 = create_tree(repo, &[(file_path, content2)]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1.id());
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2.id()); // reformat
+    drop(create_commit);
+
+    insta::assert_snapshot!(annotate(tx.repo(), &commit2, file_path), @r#"
+    commit2:   1a
+    commit2: 1 b
+    commit2: 2
+    commit2:   1c
+    "#);
+    insta::assert_snapshot!(annotate_ignoring_whitespace(tx.repo(), &commit2, file_path), @r#"
+    commit1:   1a
+    commit1: 1 b
+    commit2: 2
+    commit1:   1c
+    "#);
+}
+
 #[test]
 fn test_annotate_merge_simple() {
     let test_repo = TestRepo::init();
@@ -377,3 +498,53 @@ fn test_annotate_file_directory_transition() {
     commit2: 2
     "#);
 }
+
+#[test]
+fn test_annotate_follow_renames() {
+    // Copy tracking is implemented by the Git backend
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let repo = &test_repo.repo;
+
+    let root_commit_id = repo.store().root_commit_id();
+    let file_path1 = RepoPath::from_internal_string("file1");
+    let file_path2 = RepoPath::from_internal_string("file2");
+
+    let mut tx = repo.start_transaction();
+    let mut create_commit = create_commit_fn(tx.repo_mut());
+    let content1 = "1a\n1b\n1c\n1d\n1e\n";
+    let content2 = "1a\n1b\n1c\n1d\n1e\n2\n";
+    let content3 = "1a\n3\n1c\n1d\n1e\n2\n";
+    let tree1 = create_tree(repo, &[(file_path1, content1)]);
+    let tree2 = create_tree(repo, &[(file_path2, content2)]);
+    let tree3 = create_tree(repo, &[(file_path2, content3)]);
+    let commit1 = create_commit("commit1", &[root_commit_id], tree1.id());
+    let commit2 = create_commit("commit2", &[commit1.id()], tree2.id()); // rename
+    let commit3 = create_commit("commit3", &[commit2.id()], tree3.id());
+    drop(create_commit);
+
+    // Without rename tracking, lines are attributed to the renaming commit
+    insta::assert_snapshot!(annotate(tx.repo(), &commit3, file_path2), @r#"
+    commit2: 1a
+    commit3: 3
+    commit2: 1c
+    commit2: 1d
+    commit2: 1e
+    commit2: 2
+    "#);
+    insta::assert_snapshot!(annotate_following_renames(tx.repo(), &commit3, file_path2), @r#"
+    commit1: 1a
+    commit3: 3
+    commit1: 1c
+    commit1: 1d
+    commit1: 1e
+    commit2: 2
+    "#);
+    // Unrelated new file isn't followed
+    insta::assert_snapshot!(annotate_following_renames(tx.repo(), &commit1, file_path1), @r#"
+    commit1: 1a
+    commit1: 1b
+    commit1: 1c
+    commit1: 1d
+    commit1: 1e
+    "#);
+}
@@ -0,0 +1,178 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use jj_lib::backend::CommitId;
+use jj_lib::bisect::BisectError;
+use jj_lib::bisect::BisectEvent;
+use jj_lib::bisect::BisectState;
+use jj_lib::bisect::BisectionResult;
+use jj_lib::bisect::Bisector;
+use jj_lib::bisect::NextStep;
+use jj_lib::bisect::BISECT_TAG;
+use jj_lib::commit::Commit;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo;
+use testutils::create_random_commit;
+use testutils::write_random_commit;
+use testutils::TestRepo;
+
+fn state(good: &[&Commit], bad: &[&Commit], skipped: &[&Commit]) -> BisectState {
+    let ids = |commits: &[&Commit]| commits.iter().map(|c| c.id().clone()).collect();
+    BisectState {
+        good: ids(good),
+        bad: ids(bad),
+        skipped: ids(skipped),
+    }
+}
+
+fn evaluate(commit: &Commit) -> NextStep {
+    NextStep::Evaluate {
+        commit_id: commit.id().clone(),
+        remaining: 0,
+    }
+}
+
+fn next_step(repo: &dyn Repo, state: &BisectState) -> NextStep {
+    match Bisector::new(repo, state).unwrap().next_step().unwrap() {
+        // The exact count is checked separately
+        NextStep::Evaluate { commit_id, .. } => NextStep::Evaluate {
+            commit_id,
+            remaining: 0,
+        },
+        step => step,
+    }
+}
+
+#[test]
+fn test_bisect_linear() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    // A-B-C-D-E
+    let mut tx = repo.start_transaction();
+    let mut commits: Vec<Commit> = vec![];
+    for _ in 0..5 {
+        let mut builder = create_random_commit(tx.repo_mut());
+        if let Some(parent) = commits.last() {
+            builder = builder.set_parents(vec![parent.id().clone()]);
+        }
+        commits.push(builder.write().unwrap());
+    }
+    let repo = tx.commit("test").unwrap();
+    let [a, b, c, d, e] = &commits[..] else {
+        unreachable!()
+    };
+
+    // B, C, D are untested; C is in the middle
+    assert_eq!(
+        Bisector::new(repo.as_ref(), &state(&[a], &[e], &[]))
+            .unwrap()
+            .next_step()
+            .unwrap(),
+        NextStep::Evaluate {
+            commit_id: c.id().clone(),
+            remaining: 3,
+        }
+    );
+    assert_eq!(
+        next_step(repo.as_ref(), &state(&[a, c], &[e], &[])),
+        evaluate(d)
+    );
+    assert_eq!(
+        next_step(repo.as_ref(), &state(&[a, c, d], &[e], &[])),
+        NextStep::Done(BisectionResult::Found(vec![e.id().clone()]))
+    );
+    assert_eq!(
+        next_step(repo.as_ref(), &state(&[a], &[e, c], &[])),
+        evaluate(b)
+    );
+    assert_eq!(
+        next_step(repo.as_ref(), &state(&[a, b], &[e, c], &[])),
+        NextStep::Done(BisectionResult::Found(vec![c.id().clone()]))
+    );
+
+    // Skipped commits can't be ruled out
+    assert_eq!(
+        next_step(repo.as_ref(), &state(&[a], &[c], &[b])),
+        NextStep::Done(BisectionResult::Found(vec![c.id().clone(), b.id().clone()]))
+    );
+
+    // A bad commit that is an ancestor of a good commit
+    assert_eq!(
+        next_step(repo.as_ref(), &state(&[e], &[c], &[])),
+        NextStep::Done(BisectionResult::Indeterminate)
+    );
+}
+
+#[test]
+fn test_bisect_without_bad_commit() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let commit = write_random_commit(tx.repo_mut());
+    let repo = tx.commit("test").unwrap();
+
+    assert_matches!(
+        Bisector::new(repo.as_ref(), &state(&[&commit], &[], &[])),
+        Err(BisectError::NoBadCommit)
+    );
+}
+
+#[test]
+fn test_bisect_state_from_operation_log() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    assert_eq!(BisectState::load(repo.operation()).unwrap(), None);
+
+    let mut tx = repo.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    let commit_b = write_random_commit(tx.repo_mut());
+    let repo = tx.commit("test").unwrap();
+
+    let record = |repo: &Arc<ReadonlyRepo>, events: &[BisectEvent]| {
+        let mut tx = repo.start_transaction();
+        tx.set_tag(BISECT_TAG.to_owned(), BisectEvent::format_tag_value(events));
+        tx.commit("bisect").unwrap()
+    };
+
+    // Events before the latest start are ignored
+    let repo = record(
+        &repo,
+        &[BisectEvent::Start, BisectEvent::Bad(commit_a.id().clone())],
+    );
+    let repo = record(
+        &repo,
+        &[BisectEvent::Start, BisectEvent::Bad(commit_b.id().clone())],
+    );
+    let repo = record(&repo, &[BisectEvent::Good(commit_a.id().clone())]);
+    let mut tx = repo.start_transaction();
+    write_random_commit(tx.repo_mut());
+    let repo = tx.commit("unrelated").unwrap();
+    let repo = record(&repo, &[BisectEvent::Skip(CommitId::from_hex("00"))]);
+    assert_eq!(
+        BisectState::load(repo.operation()).unwrap(),
+        Some(BisectState {
+            good: vec![commit_a.id().clone()],
+            bad: vec![commit_b.id().clone()],
+            skipped: vec![CommitId::from_hex("00")],
+        })
+    );
+
+    let repo = record(&repo, &[BisectEvent::Reset]);
+    assert_eq!(BisectState::load(repo.operation()).unwrap(), None);
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Read as _;
+
 use indoc::indoc;
 use itertools::Itertools;
 use jj_lib::backend::FileId;
@@ -21,6 +23,7 @@ use jj_lib::conflicts::materialize_merge_result_to_bytes;
 use jj_lib::conflicts::parse_conflict;
 use jj_lib::conflicts::update_from_content;
 use jj_lib::conflicts::ConflictMarkerStyle;
+use jj_lib::conflicts::MaterializedMergeReader;
 use jj_lib::conflicts::MIN_CONFLICT_MARKER_LEN;
 use jj_lib::merge::Merge;
 use jj_lib::repo::Repo;
@@ -2240,6 +2243,12 @@ fn materialize_conflict_string(
     let contents = extract_as_single_hunk(conflict, store, path)
         .block_on()
         .unwrap();
-    String::from_utf8(materialize_merge_result_to_bytes(&contents, conflict_marker_style).into())
-        .unwrap()
+    let materialized = materialize_merge_result_to_bytes(&contents, conflict_marker_style);
+    // The streaming reader should produce the same content
+    let mut streamed = Vec::new();
+    MaterializedMergeReader::new(&contents, conflict_marker_style)
+        .read_to_end(&mut streamed)
+        .unwrap();
+    assert_eq!(streamed, materialized);
+    String::from_utf8(materialized.into()).unwrap()
 }
@@ -0,0 +1,125 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use jj_lib::default_index::DefaultReadonlyIndex;
+use jj_lib::fsck::check_index_entries;
+use jj_lib::fsck::check_repo;
+use jj_lib::fsck::FsckIssue;
+use jj_lib::fsck::FsckReport;
+use jj_lib::object_id::ObjectId as _;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPath;
+use test_case::test_case;
+use testutils::create_tree;
+use testutils::write_random_commit;
+use testutils::TestRepo;
+use testutils::TestRepoBackend;
+
+#[test_case(TestRepoBackend::Local ; "local backend")]
+#[test_case(TestRepoBackend::Git ; "git backend")]
+fn test_check_repo_clean(backend: TestRepoBackend) {
+    let test_repo = TestRepo::init_with_backend(backend);
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let commit1 = write_random_commit(tx.repo_mut());
+    tx.repo_mut()
+        .new_commit(vec![commit1.id().clone()], commit1.tree_id().clone())
+        .write()
+        .unwrap();
+    let repo = tx.commit("test").unwrap();
+
+    let report = check_repo(&repo).unwrap();
+    assert_eq!(report.issues, vec![]);
+    // Root commit and the two new commits
+    assert_eq!(report.commits_checked, 3);
+}
+
+#[test]
+fn test_check_repo_missing_file() {
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Local);
+    let repo = &test_repo.repo;
+    let path = RepoPath::from_internal_string("file");
+
+    let tree = create_tree(repo, &[(path, "contents")]);
+    let mut tx = repo.start_transaction();
+    let commit = tx
+        .repo_mut()
+        .new_commit(vec![repo.store().root_commit_id().clone()], tree.id())
+        .write()
+        .unwrap();
+    let repo = tx.commit("test").unwrap();
+    assert_eq!(check_repo(&repo).unwrap().issues, vec![]);
+
+    let file_id = testutils::write_file(repo.store(), path, "contents");
+    std::fs::remove_file(
+        test_repo
+            .repo_path()
+            .join("store")
+            .join("files")
+            .join(file_id.hex()),
+    )
+    .unwrap();
+    let issues = check_repo(&repo).unwrap().issues;
+    assert_eq!(issues.len(), 1);
+    assert!(matches!(
+        &issues[0],
+        FsckIssue::TreeEntry { commit_id, path: issue_path, .. }
+            if commit_id == commit.id() && issue_path.as_ref() == path
+    ));
+    assert!(!issues[0].is_repairable());
+}
+
+fn check_index(repo: &ReadonlyRepo) -> FsckReport {
+    let index: &DefaultReadonlyIndex = repo.readonly_index().as_any().downcast_ref().unwrap();
+    check_index_entries(repo.store(), index)
+}
+
+#[test]
+fn test_check_index_entries() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Local);
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    write_random_commit(tx.repo_mut());
+    let commit2 = write_random_commit(tx.repo_mut());
+    let repo = tx.commit("test").unwrap();
+    let mut tx = repo.start_transaction();
+    tx.repo_mut().record_abandoned_commit(commit2.id().clone());
+    tx.repo_mut().rebase_descendants().unwrap();
+    let repo = tx.commit("test").unwrap();
+
+    let report = check_index(&repo);
+    assert_eq!(report.issues, vec![]);
+    // The abandoned commit is still indexed
+    assert_eq!(report.commits_checked, 3);
+
+    std::fs::remove_file(
+        test_repo
+            .repo_path()
+            .join("store")
+            .join("commits")
+            .join(commit2.id().hex()),
+    )
+    .unwrap();
+    let repo = test_repo
+        .env
+        .load_repo_at_head(&settings, test_repo.repo_path());
+    let issues = check_index(&repo).issues;
+    assert_eq!(issues.len(), 1);
+    assert!(issues[0].is_repairable());
+    assert!(issues[0].to_string().contains(&commit2.id().hex()));
+}
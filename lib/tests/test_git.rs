@@ -37,9 +37,11 @@ use jj_lib::backend::Timestamp;
 use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
 use jj_lib::commit_builder::CommitBuilder;
+use jj_lib::default_index::DefaultIndexStore;
 use jj_lib::git;
 use jj_lib::git::FailedRefExportReason;
 use jj_lib::git::GitBranchPushTargets;
+use jj_lib::git::GitFetchBranchMapping;
 use jj_lib::git::GitFetchError;
 use jj_lib::git::GitImportError;
 use jj_lib::git::GitPushError;
@@ -47,6 +49,8 @@ use jj_lib::git::GitRefUpdate;
 use jj_lib::git::RefName;
 use jj_lib::git::SubmoduleConfig;
 use jj_lib::git_backend::GitBackend;
+use jj_lib::graph::GraphEdge;
+use jj_lib::graph::GraphNode;
 use jj_lib::object_id::ObjectId;
 use jj_lib::op_store::BookmarkTarget;
 use jj_lib::op_store::RefTarget;
@@ -58,6 +62,8 @@ use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::repo_path::RepoPathBuf;
+use jj_lib::revset::ResolvedRevsetExpression;
+use jj_lib::revset::RevsetFilterPredicate;
 use jj_lib::settings::GitSettings;
 use jj_lib::settings::UserSettings;
 use jj_lib::signing::Signer;
@@ -2535,6 +2541,7 @@ fn test_fetch_empty_repo() {
         &test_data.git_repo,
         "origin",
         &[StringPattern::everything()],
+        &[],
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2562,6 +2569,7 @@ fn test_fetch_initial_commit_head_is_not_set() {
         &test_data.git_repo,
         "origin",
         &[StringPattern::everything()],
+        &[],
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2623,6 +2631,7 @@ fn test_fetch_initial_commit_head_is_set() {
         &test_data.git_repo,
         "origin",
         &[StringPattern::everything()],
+        &[],
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2648,6 +2657,7 @@ fn test_fetch_success() {
         &test_data.git_repo,
         "origin",
         &[StringPattern::everything()],
+        &[],
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2672,6 +2682,7 @@ fn test_fetch_success() {
         &test_data.git_repo,
         "origin",
         &[StringPattern::everything()],
+        &[],
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2730,6 +2741,7 @@ fn test_fetch_prune_deleted_ref() {
         &test_data.git_repo,
         "origin",
         &[StringPattern::everything()],
+        &[],
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2754,6 +2766,7 @@ fn test_fetch_prune_deleted_ref() {
         &test_data.git_repo,
         "origin",
         &[StringPattern::everything()],
+        &[],
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2782,6 +2795,7 @@ fn test_fetch_no_default_branch() {
         &test_data.git_repo,
         "origin",
         &[StringPattern::everything()],
+        &[],
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2806,6 +2820,7 @@ fn test_fetch_no_default_branch() {
         &test_data.git_repo,
         "origin",
         &[StringPattern::everything()],
+        &[],
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2828,6 +2843,7 @@ fn test_fetch_empty_refspecs() {
         &test_data.git_repo,
         "origin",
         &[],
+        &[],
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -2845,6 +2861,150 @@ fn test_fetch_empty_refspecs() {
         .is_absent());
 }
 
+#[test]
+fn test_fetch_branch_mapping() {
+    let test_data = GitRepoData::create();
+    let git_settings = GitSettings::default();
+    let main_git_commit = empty_git_commit(&test_data.origin_repo, "refs/heads/main", &[]);
+    empty_git_commit(&test_data.origin_repo, "refs/heads/feature", &[]);
+
+    let mut tx = test_data.repo.start_transaction();
+    git::fetch(
+        tx.repo_mut(),
+        &test_data.git_repo,
+        "origin",
+        &[],
+        &[GitFetchBranchMapping {
+            remote_branch: "main".to_owned(),
+            local_branch: "upstream-main".to_owned(),
+        }],
+        git::RemoteCallbacks::default(),
+        &git_settings,
+        None,
+    )
+    .unwrap();
+    let repo = tx.commit("test").unwrap();
+    let view = repo.view();
+    // Only the mapped branch is fetched, under the new name
+    assert_eq!(
+        view.get_remote_bookmark("upstream-main", "origin").target,
+        RefTarget::normal(jj_id(&main_git_commit))
+    );
+    assert!(view.get_remote_bookmark("main", "origin").is_absent());
+    assert!(view.get_remote_bookmark("feature", "origin").is_absent());
+    assert!(test_data
+        .git_repo
+        .find_reference("refs/remotes/origin/upstream-main")
+        .is_ok());
+    assert!(test_data
+        .git_repo
+        .find_reference("refs/remotes/origin/main")
+        .is_err());
+}
+
+#[test]
+fn test_fetch_branch_mapping_with_patterns() {
+    let test_data = GitRepoData::create();
+    let git_settings = GitSettings::default();
+    let main_git_commit = empty_git_commit(&test_data.origin_repo, "refs/heads/main", &[]);
+    empty_git_commit(&test_data.origin_repo, "refs/heads/feature", &[]);
+    let mapping = GitFetchBranchMapping {
+        remote_branch: "main".to_owned(),
+        local_branch: "upstream-main".to_owned(),
+    };
+
+    let fetch = |repo: &Arc<ReadonlyRepo>, mappings: &[GitFetchBranchMapping]| {
+        let mut tx = repo.start_transaction();
+        git::fetch(
+            tx.repo_mut(),
+            &test_data.git_repo,
+            "origin",
+            &[StringPattern::everything()],
+            mappings,
+            git::RemoteCallbacks::default(),
+            &git_settings,
+            None,
+        )
+        .unwrap();
+        tx.commit("test").unwrap()
+    };
+
+    // Branches matching the pattern are fetched along with the mapped branch
+    let repo = fetch(&test_data.repo, &[mapping.clone()]);
+    assert_eq!(
+        repo.view()
+            .get_remote_bookmark("upstream-main", "origin")
+            .target,
+        RefTarget::normal(jj_id(&main_git_commit))
+    );
+    assert!(repo
+        .view()
+        .get_remote_bookmark("main", "origin")
+        .is_present());
+    assert!(repo
+        .view()
+        .get_remote_bookmark("feature", "origin")
+        .is_present());
+
+    // The mapped branch isn't pruned as long as the mapping is specified
+    let repo = fetch(&repo, &[mapping]);
+    assert!(repo
+        .view()
+        .get_remote_bookmark("upstream-main", "origin")
+        .is_present());
+
+    // Without the mapping, it no longer exists on the remote
+    let repo = fetch(&repo, &[]);
+    assert!(repo
+        .view()
+        .get_remote_bookmark("upstream-main", "origin")
+        .is_absent());
+}
+
+#[test]
+fn test_fetch_branch_mapping_conflicts() {
+    let test_data = GitRepoData::create();
+    let git_settings = GitSettings::default();
+    empty_git_commit(&test_data.origin_repo, "refs/heads/main", &[]);
+
+    let mapping = GitFetchBranchMapping {
+        remote_branch: "main".to_owned(),
+        local_branch: "feature".to_owned(),
+    };
+    // The destination is used by another mapping
+    let mut tx = test_data.repo.start_transaction();
+    let result = git::fetch(
+        tx.repo_mut(),
+        &test_data.git_repo,
+        "origin",
+        &[],
+        &[mapping.clone(), mapping],
+        git::RemoteCallbacks::default(),
+        &git_settings,
+        None,
+    );
+    assert_matches!(
+        result,
+        Err(GitFetchError::ConflictingBranchMapping(name)) if name == "feature"
+    );
+
+    // Globs can't be mapped
+    let result = git::fetch(
+        tx.repo_mut(),
+        &test_data.git_repo,
+        "origin",
+        &[],
+        &[GitFetchBranchMapping {
+            remote_branch: "*".to_owned(),
+            local_branch: "upstream-*".to_owned(),
+        }],
+        git::RemoteCallbacks::default(),
+        &git_settings,
+        None,
+    );
+    assert_matches!(result, Err(GitFetchError::InvalidBranchPattern));
+}
+
 #[test]
 fn test_fetch_no_such_remote() {
     let test_data = GitRepoData::create();
@@ -2855,6 +3015,7 @@ fn test_fetch_no_such_remote() {
         &test_data.git_repo,
         "invalid-remote",
         &[StringPattern::everything()],
+        &[],
         git::RemoteCallbacks::default(),
         &git_settings,
         None,
@@ -3872,3 +4033,76 @@ fn test_shallow_commits_lack_parents() {
     // FIXME: new ancestors should be indexed
     assert!(!repo.index().has_id(&jj_id(&a)));
 }
+
+#[test]
+fn test_shallow_commits_graph_edges() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init_with_backend(TestRepoBackend::Git);
+    let test_env = &test_repo.env;
+    let repo = &test_repo.repo;
+    let git_repo = get_git_repo(repo);
+
+    // C (`main`)
+    // |
+    // B // shallow boundary
+    // |
+    // A
+    let a = empty_git_commit(&git_repo, "refs/heads/main", &[]);
+    let b = empty_git_commit(&git_repo, "refs/heads/main", &[&a]);
+    let c = empty_git_commit(&git_repo, "refs/heads/main", &[&b]);
+
+    let shallow_file = get_git_backend(repo).git_repo().shallow_file();
+    fs::write(shallow_file, format!("{}\n", b.id())).unwrap();
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+
+    let mut tx = repo.start_transaction();
+    git::import_refs(tx.repo_mut(), &GitSettings::default()).unwrap();
+    let repo = tx.commit("import").unwrap();
+    let root_id = repo.store().root_commit_id().clone();
+
+    let graph: Vec<GraphNode<CommitId>> = ResolvedRevsetExpression::all()
+        .evaluate(repo.as_ref())
+        .unwrap()
+        .iter_graph()
+        .try_collect()
+        .unwrap();
+    // The edge from the shallow commit to the root isn't a real edge.
+    assert_eq!(
+        graph,
+        vec![
+            (jj_id(&c), vec![GraphEdge::direct(jj_id(&b))]),
+            (jj_id(&b), vec![GraphEdge::missing(root_id.clone())]),
+            (root_id, vec![]),
+        ]
+    );
+
+    // Only the boundary commit is reported as shallow.
+    let store = repo.store();
+    assert!(!store.get_commit(&jj_id(&c)).unwrap().is_shallow().unwrap());
+    assert!(store.get_commit(&jj_id(&b)).unwrap().is_shallow().unwrap());
+    assert!(!store.root_commit().is_shallow().unwrap());
+    let shallow_ids: Vec<CommitId> =
+        ResolvedRevsetExpression::filter(RevsetFilterPredicate::Shallow)
+            .evaluate(repo.as_ref())
+            .unwrap()
+            .iter()
+            .try_collect()
+            .unwrap();
+    assert_eq!(shallow_ids, vec![jj_id(&b)]);
+
+    // The shallow boundary is preserved when the index is rebuilt.
+    let default_index_store: &DefaultIndexStore =
+        repo.index_store().as_any().downcast_ref().unwrap();
+    default_index_store.reinit().unwrap();
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    let graph: Vec<GraphNode<CommitId>> = ResolvedRevsetExpression::all()
+        .evaluate(repo.as_ref())
+        .unwrap()
+        .iter_graph()
+        .try_collect()
+        .unwrap();
+    assert_eq!(
+        graph[1].1,
+        vec![GraphEdge::missing(repo.store().root_commit_id().clone())]
+    );
+}
@@ -209,6 +209,18 @@ fn test_gc() {
     mut_index.add_commit(&commit_b);
     mut_index.add_commit(&commit_c);
     mut_index.add_commit(&commit_f);
+    let stats = repo
+        .store()
+        .gc_dry_run(mut_index.as_index(), now())
+        .unwrap();
+    assert_eq!(
+        stats.expired_commit_ids.into_iter().collect::<HashSet<_>>(),
+        hashset! {
+            commit_d.id().clone(),
+            commit_e.id().clone(),
+            commit_h.id().clone(),
+        },
+    );
     repo.store().gc(mut_index.as_index(), now()).unwrap();
     assert_eq!(
         collect_no_gc_refs(git_repo_path),
@@ -15,6 +15,8 @@
 use std::collections::HashSet;
 use std::fs;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
 
 use assert_matches::assert_matches;
 use jj_lib::backend::ChangeId;
@@ -631,13 +633,61 @@ fn test_reindex_corrupt_segment_files() {
         // u32: number of local change ids
         // u32: number of overflow parent entries
         // u32: number of overflow change id positions
-        fs::write(entry.path(), b"\0".repeat(24)).unwrap();
+        // u32: number of shallow entries
+        // u32: number of evolution entries
+        fs::write(entry.path(), b"\0".repeat(32)).unwrap();
     }
 
     let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
     assert!(repo.index().has_id(commit_a.id()));
 }
 
+#[test]
+fn test_index_successors() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let test_env = &test_repo.env;
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    let repo = tx.commit("test").unwrap();
+
+    let mut tx = repo.start_transaction();
+    let commit_b = tx
+        .repo_mut()
+        .rewrite_commit(&commit_a)
+        .set_description("b")
+        .write()
+        .unwrap();
+    let commit_c = tx
+        .repo_mut()
+        .rewrite_commit(&commit_a)
+        .set_description("c")
+        .write()
+        .unwrap();
+    tx.repo_mut().rebase_descendants().unwrap();
+    let repo = tx.commit("test").unwrap();
+
+    let successors = |repo: &Arc<ReadonlyRepo>, id: &CommitId| -> HashSet<CommitId> {
+        repo.index().successors(id).into_iter().collect()
+    };
+    let expected = hashset! {commit_b.id().clone(), commit_c.id().clone()};
+    assert_eq!(successors(&repo, commit_a.id()), expected);
+    assert!(successors(&repo, commit_b.id()).is_empty());
+
+    // Successors are persisted in the index
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    assert_eq!(successors(&repo, commit_a.id()), expected);
+
+    // and are restored when reindexing
+    let default_index_store: &DefaultIndexStore =
+        repo.index_store().as_any().downcast_ref().unwrap();
+    default_index_store.reinit().unwrap();
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    assert_eq!(successors(&repo, commit_a.id()), expected);
+}
+
 #[test]
 fn test_reindex_from_merged_operation() {
     let test_repo = TestRepo::init();
@@ -719,6 +769,87 @@ fn test_reindex_missing_commit() {
     assert_matches!(err, DefaultIndexStoreError::IndexCommits { op_id, .. } if op_id == *bad_op_id);
 }
 
+#[test]
+fn test_index_store_gc() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let test_env = &test_repo.env;
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    let repo = tx.commit("test").unwrap();
+
+    // Simulate index files of an operation that has been pruned
+    let index_dir = test_repo.repo_path().join("index");
+    let pruned_op_link = index_dir.join("operations").join("01".repeat(64));
+    let unused_segment = index_dir.join("segments").join("02".repeat(64));
+    fs::write(&pruned_op_link, "02".repeat(64)).unwrap();
+    fs::write(&unused_segment, b"").unwrap();
+
+    let default_index_store: &DefaultIndexStore =
+        repo.index_store().as_any().downcast_ref().unwrap();
+    // New files are preserved
+    let removed_count = default_index_store
+        .gc(
+            repo.op_store().as_ref(),
+            repo.store(),
+            SystemTime::UNIX_EPOCH,
+        )
+        .unwrap();
+    assert_eq!(removed_count, 0);
+    assert!(pruned_op_link.exists());
+
+    let keep_newer = SystemTime::now() + Duration::from_secs(1);
+    let removed_count = default_index_store
+        .gc(repo.op_store().as_ref(), repo.store(), keep_newer)
+        .unwrap();
+    assert_eq!(removed_count, 2);
+    assert!(!pruned_op_link.exists());
+    assert!(!unused_segment.exists());
+
+    // The index of the existing operations is still usable
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    assert!(repo.index().has_id(commit_a.id()));
+    assert_eq!(
+        default_index_store
+            .gc(repo.op_store().as_ref(), repo.store(), keep_newer)
+            .unwrap(),
+        0
+    );
+}
+
+#[test]
+fn test_index_store_parent_segment_removed_concurrently() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init();
+    let test_env = &test_repo.env;
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    let repo = tx.commit("test").unwrap();
+
+    // Start a transaction on top of the current segments, then remove them as
+    // concurrent compaction and gc would.
+    let mut tx = repo.start_transaction();
+    let commit_b = write_random_commit(tx.repo_mut());
+    let segments_dir = test_repo.repo_path().join("index").join("segments");
+    for entry in segments_dir.read_dir().unwrap() {
+        fs::remove_file(entry.unwrap().path()).unwrap();
+    }
+    let repo = tx.commit("test").unwrap();
+
+    // The new segment doesn't depend on the removed ones, so the index can be
+    // loaded without reindexing, which would remove the other links.
+    let op_links_dir = test_repo.repo_path().join("index").join("operations");
+    let num_op_links = op_links_dir.read_dir().unwrap().count();
+    let repo = test_env.load_repo_at_head(&settings, test_repo.repo_path());
+    assert!(repo.index().has_id(commit_a.id()));
+    assert!(repo.index().has_id(commit_b.id()));
+    assert_eq!(op_links_dir.read_dir().unwrap().count(), num_op_links);
+}
+
 /// Test that .jj/repo/index/type is created when the repo is created.
 #[test]
 fn test_index_store_type() {
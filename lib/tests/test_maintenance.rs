@@ -0,0 +1,72 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use jj_lib::maintenance::MaintenanceSchedule;
+use jj_lib::maintenance::MaintenanceState;
+use jj_lib::repo::Repo as _;
+use testutils::write_random_commit;
+use testutils::TestRepo;
+
+#[test]
+fn test_maintenance_due_after_operations() {
+    let test_repo = TestRepo::init();
+    let mut repo = test_repo.repo.clone();
+    let state = MaintenanceState::new(test_repo.repo_path());
+    let schedule = MaintenanceSchedule {
+        operations: 3,
+        interval: Duration::from_secs(3600),
+    };
+
+    // Never run
+    assert!(state.is_due(repo.operation(), &schedule).unwrap());
+
+    state.record_run(repo.op_id()).unwrap();
+    assert!(!state.is_due(repo.operation(), &schedule).unwrap());
+
+    for i in 0..3 {
+        assert!(!state.is_due(repo.operation(), &schedule).unwrap());
+        let mut tx = repo.start_transaction();
+        write_random_commit(tx.repo_mut());
+        repo = tx.commit(format!("transaction {i}")).unwrap();
+    }
+    assert!(state.is_due(repo.operation(), &schedule).unwrap());
+
+    // Disabled operation threshold
+    let schedule = MaintenanceSchedule {
+        operations: 0,
+        ..schedule
+    };
+    assert!(!state.is_due(repo.operation(), &schedule).unwrap());
+}
+
+#[test]
+fn test_maintenance_due_after_interval() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let state = MaintenanceState::new(test_repo.repo_path());
+    state.record_run(repo.op_id()).unwrap();
+
+    let schedule = MaintenanceSchedule {
+        operations: 100,
+        interval: Duration::from_secs(3600),
+    };
+    assert!(!state.is_due(repo.operation(), &schedule).unwrap());
+    let schedule = MaintenanceSchedule {
+        operations: 100,
+        interval: Duration::ZERO,
+    };
+    assert!(state.is_due(repo.operation(), &schedule).unwrap());
+}
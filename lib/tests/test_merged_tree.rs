@@ -1340,6 +1340,11 @@ fn test_merge_partial_resolution() {
 
     let merged = side1_merged.merge(&base1_merged, &side2_merged).unwrap();
     assert_eq!(merged, expected_merged);
+    let merged = side1_merged
+        .merge_async(&base1_merged, &side2_merged)
+        .block_on()
+        .unwrap();
+    assert_eq!(merged, expected_merged);
 }
 
 /// Merge 3 trees where each one is a 3-way conflict and the result is arrived
@@ -443,6 +443,44 @@ fn test_remove_head() {
     assert!(repo.index().has_id(commit3.id()));
 }
 
+#[test]
+fn test_restore_heads() {
+    // Test that MutableRepo::restore_heads() restores the visible heads, but
+    // keeps the current working-copy commit and bookmark targets visible.
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit1 = graph_builder.initial_commit();
+    let repo = tx.commit("test").unwrap();
+    let old_view = repo.view().store_view().clone();
+
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.initial_commit();
+    let commit4 = graph_builder.initial_commit();
+    let mut_repo = tx.repo_mut();
+    let ws_id = WorkspaceId::default();
+    mut_repo
+        .set_wc_commit(ws_id.clone(), commit3.id().clone())
+        .unwrap();
+    mut_repo.set_local_bookmark_target("main", RefTarget::normal(commit4.id().clone()));
+    mut_repo.restore_heads(&old_view).unwrap();
+    assert_eq!(
+        *mut_repo.view().heads(),
+        hashset! {commit1.id().clone(), commit3.id().clone(), commit4.id().clone()}
+    );
+    assert!(mut_repo.index().has_id(commit2.id()));
+    let repo = tx.commit("test").unwrap();
+    assert_eq!(
+        *repo.view().heads(),
+        hashset! {commit1.id().clone(), commit3.id().clone(), commit4.id().clone()}
+    );
+    assert_eq!(repo.view().get_wc_commit_id(&ws_id), Some(commit3.id()));
+}
+
 #[test]
 fn test_has_changed() {
     // Test that MutableRepo::has_changed() reports changes iff the view has changed
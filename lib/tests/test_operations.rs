@@ -12,24 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::path::Path;
 use std::slice;
 use std::sync::Arc;
+use std::time::Duration;
 use std::time::SystemTime;
 
 use assert_matches::assert_matches;
+use chrono::DateTime;
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::config::ConfigLayer;
 use jj_lib::config::ConfigSource;
 use jj_lib::object_id::ObjectId;
+use jj_lib::op_heads_store::OpHeadsWatcher;
 use jj_lib::op_walk;
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::op_walk::OpsetResolutionError;
 use jj_lib::operation::Operation;
+use jj_lib::opset;
 use jj_lib::repo::ReadonlyRepo;
 use jj_lib::repo::Repo;
 use jj_lib::settings::UserSettings;
+use jj_lib::time_util::DatePatternContext;
 use testutils::create_random_commit;
 use testutils::write_random_commit;
 use testutils::TestRepo;
@@ -152,6 +158,34 @@ fn test_concurrent_operations() {
     assert_eq!(list_dir(&op_heads_dir), vec![merged_op_id.hex()]);
 }
 
+#[test]
+fn test_op_heads_watcher() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let op_heads_store = repo.loader().op_heads_store().clone();
+
+    let mut watcher = OpHeadsWatcher::new(op_heads_store, Duration::ZERO).unwrap();
+    assert_eq!(watcher.op_heads(), slice::from_ref(repo.op_id()));
+    assert_eq!(watcher.poll().unwrap(), None);
+
+    let mut tx1 = repo.start_transaction();
+    write_random_commit(tx1.repo_mut());
+    let repo1 = tx1.commit("transaction 1").unwrap();
+    assert_eq!(
+        watcher.poll().unwrap(),
+        Some(slice::from_ref(repo1.op_id()))
+    );
+    assert_eq!(watcher.poll().unwrap(), None);
+
+    // Concurrent operations are reported as multiple heads
+    let mut tx2 = repo.start_transaction();
+    write_random_commit(tx2.repo_mut());
+    let repo2 = tx2.commit("transaction 2").unwrap();
+    let mut expected_heads = vec![repo1.op_id().clone(), repo2.op_id().clone()];
+    expected_heads.sort();
+    assert_eq!(watcher.wait().unwrap(), expected_heads);
+}
+
 fn assert_heads(repo: &dyn Repo, expected: Vec<&CommitId>) {
     let expected = expected.iter().cloned().cloned().collect();
     assert_eq!(*repo.view().heads(), expected);
@@ -428,6 +462,12 @@ fn stable_op_id_settings() -> UserSettings {
     UserSettings::from_config(config).unwrap()
 }
 
+fn date_context() -> DatePatternContext {
+    DateTime::parse_from_rfc3339("2001-02-03T04:05:06+07:00")
+        .unwrap()
+        .into()
+}
+
 #[test]
 fn test_resolve_op_id() {
     let settings = stable_op_id_settings();
@@ -456,7 +496,7 @@ fn test_resolve_op_id() {
     "#);
 
     let repo_loader = repo.loader();
-    let resolve = |op_str: &str| op_walk::resolve_op_for_load(repo_loader, op_str);
+    let resolve = |op_str: &str| op_walk::resolve_op_for_load(repo_loader, op_str, &date_context());
 
     // Full id
     assert_eq!(resolve(&operations[0].id().hex()).unwrap(), operations[0]);
@@ -504,6 +544,27 @@ fn test_resolve_op_id() {
     );
 }
 
+#[test]
+fn test_operation_ids() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let op_store = repo.op_store();
+
+    let root_op_id = op_store.root_operation_id().clone();
+    let op_id0 = repo.op_id().clone();
+    let repo1 = repo.start_transaction().commit("transaction 1").unwrap();
+    // Operations which aren't reachable from the op heads are included
+    let unpublished_op = repo.start_transaction().write("transaction 2");
+    let op_id2 = unpublished_op.operation().id().clone();
+    drop(unpublished_op);
+
+    let op_ids: HashSet<_> = op_store.operation_ids().unwrap().into_iter().collect();
+    assert_eq!(
+        op_ids,
+        HashSet::from([root_op_id, op_id0, repo1.op_id().clone(), op_id2])
+    );
+}
+
 #[test]
 fn test_resolve_current_op() {
     let settings = stable_op_id_settings();
@@ -511,7 +572,7 @@ fn test_resolve_current_op() {
     let repo = test_repo.repo;
 
     assert_eq!(
-        op_walk::resolve_op_with_repo(&repo, "@").unwrap(),
+        op_walk::resolve_op_with_repo(&repo, "@", &date_context()).unwrap(),
         *repo.operation()
     );
 }
@@ -534,16 +595,16 @@ fn test_resolve_op_parents_children() {
     // Parent
     let op2_id_hex = operations[2].id().hex();
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}-")).unwrap(),
+        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}-"), &date_context()).unwrap(),
         *operations[1]
     );
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}--")).unwrap(),
+        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}--"), &date_context()).unwrap(),
         *operations[0]
     );
     // "{op2_id_hex}----" is the root operation
     assert_matches!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}-----")),
+        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}-----"), &date_context()),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::EmptyOperations(_)
         ))
@@ -552,15 +613,15 @@ fn test_resolve_op_parents_children() {
     // Child
     let op0_id_hex = operations[0].id().hex();
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}+")).unwrap(),
+        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}+"), &date_context()).unwrap(),
         *operations[1]
     );
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}++")).unwrap(),
+        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}++"), &date_context()).unwrap(),
         *operations[2]
     );
     assert_matches!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}+++")),
+        op_walk::resolve_op_with_repo(repo, &format!("{op0_id_hex}+++"), &date_context()),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::EmptyOperations(_)
         ))
@@ -568,17 +629,18 @@ fn test_resolve_op_parents_children() {
 
     // Child of parent
     assert_eq!(
-        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}--+")).unwrap(),
+        op_walk::resolve_op_with_repo(repo, &format!("{op2_id_hex}--+"), &date_context()).unwrap(),
         *operations[1]
     );
 
     // Child at old repo: new operations shouldn't be visible
     assert_eq!(
-        op_walk::resolve_op_with_repo(&repos[1], &format!("{op0_id_hex}+")).unwrap(),
+        op_walk::resolve_op_with_repo(&repos[1], &format!("{op0_id_hex}+"), &date_context())
+            .unwrap(),
         *operations[1]
     );
     assert_matches!(
-        op_walk::resolve_op_with_repo(&repos[0], &format!("{op0_id_hex}+")),
+        op_walk::resolve_op_with_repo(&repos[0], &format!("{op0_id_hex}+"), &date_context()),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::EmptyOperations(_)
         ))
@@ -590,20 +652,106 @@ fn test_resolve_op_parents_children() {
     let repo = testutils::commit_transactions(vec![tx1, tx2]);
     let op5_id_hex = repo.operation().id().hex();
     assert_matches!(
-        op_walk::resolve_op_with_repo(&repo, &format!("{op5_id_hex}-")),
+        op_walk::resolve_op_with_repo(&repo, &format!("{op5_id_hex}-"), &date_context()),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::MultipleOperations { .. }
         ))
     );
     let op2_id_hex = operations[2].id().hex();
     assert_matches!(
-        op_walk::resolve_op_with_repo(&repo, &format!("{op2_id_hex}+")),
+        op_walk::resolve_op_with_repo(&repo, &format!("{op2_id_hex}+"), &date_context()),
         Err(OpsetEvaluationError::OpsetResolution(
             OpsetResolutionError::MultipleOperations { .. }
         ))
     );
 }
 
+#[test]
+fn test_resolve_opset() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let mut repo = &test_repo.repo;
+    let init_op = repo.operation().clone();
+
+    let mut repos = Vec::new();
+    for i in 0..3 {
+        let tx = repo.start_transaction();
+        repos.push(tx.commit(format!("test {i}")).unwrap());
+        repo = repos.last().unwrap();
+    }
+    let operations = repos
+        .iter()
+        .map(|repo| repo.operation().clone())
+        .collect_vec();
+    let root_op = repo.loader().root_operation();
+
+    let resolve = |text: &str| -> Vec<Operation> {
+        let expression = opset::parse(text, &date_context()).unwrap();
+        op_walk::resolve_opset_at(
+            repo.op_store(),
+            slice::from_ref(repo.operation()),
+            &expression,
+        )
+        .unwrap()
+        .try_collect()
+        .unwrap()
+    };
+
+    // Symbols and neighbors
+    assert_eq!(resolve("@"), [operations[2].clone()]);
+    assert_eq!(resolve("@--"), [operations[0].clone()]);
+    assert_eq!(resolve("@-+"), [operations[2].clone()]);
+    assert_eq!(
+        resolve("(@ | @-)-"),
+        operations[..2].iter().rev().cloned().collect_vec()
+    );
+
+    // Ranges
+    assert_eq!(
+        resolve("@--..@"),
+        operations[1..].iter().rev().cloned().collect_vec()
+    );
+    assert_eq!(
+        resolve("@--.."),
+        operations[1..].iter().rev().cloned().collect_vec()
+    );
+    assert_eq!(
+        resolve("..@-"),
+        [
+            operations[1].clone(),
+            operations[0].clone(),
+            init_op.clone(),
+            root_op.clone(),
+        ]
+    );
+    assert!(resolve("@..@--").is_empty());
+
+    // Filters
+    assert_eq!(resolve("description(\"test 1\")"), [operations[1].clone()]);
+    assert_eq!(
+        resolve("description(\"test\")"),
+        operations.iter().rev().cloned().collect_vec()
+    );
+    assert_eq!(
+        resolve("~description(\"test\")"),
+        [init_op.clone(), root_op.clone()]
+    );
+    assert_eq!(
+        resolve("description(glob:\"test [01]\") & @-.."),
+        [operations[1].clone()]
+    );
+
+    // The operation log is walked lazily
+    let expression = opset::parse("all()", &date_context()).unwrap();
+    let mut ops = op_walk::resolve_opset_at(
+        repo.op_store(),
+        slice::from_ref(repo.operation()),
+        &expression,
+    )
+    .unwrap();
+    assert_eq!(ops.next().unwrap().unwrap(), operations[2]);
+}
+
 #[test]
 fn test_gc() {
     let settings = stable_op_id_settings();
@@ -655,6 +803,19 @@ fn test_gc() {
     assert_eq!(list_dir(&view_dir), expected_view_entries);
 
     // E|F are no longer reachable, but E's view is still reachable
+    let stats = op_store
+        .gc_dry_run(slice::from_ref(repo_d.op_id()), now)
+        .unwrap();
+    assert_eq!(
+        stats.expired_operation_ids.iter().sorted().collect_vec(),
+        [repo_e.op_id(), repo_f.op_id()]
+            .into_iter()
+            .sorted()
+            .collect_vec()
+    );
+    assert_eq!(stats.expired_view_count, 1);
+    assert!(stats.reclaimable_bytes.unwrap() > 0);
+    assert_eq!(list_dir(&op_dir), expected_op_entries);
     op_store.gc(slice::from_ref(repo_d.op_id()), now).unwrap();
     expected_op_entries
         .retain(|name| *name != repo_e.op_id().hex() && *name != repo_f.op_id().hex());
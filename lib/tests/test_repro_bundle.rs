@@ -0,0 +1,129 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use assert_matches::assert_matches;
+use itertools::Itertools as _;
+use jj_lib::op_store::RefTarget;
+use jj_lib::op_walk;
+use jj_lib::repo::ReadonlyRepo;
+use jj_lib::repo::Repo as _;
+use jj_lib::repo_path::RepoPath;
+use jj_lib::repro_bundle::ReproBundle;
+use jj_lib::repro_bundle::ReproBundleError;
+use testutils::create_tree;
+use testutils::write_random_commit;
+use testutils::TestRepo;
+
+fn num_operations(repo: &ReadonlyRepo) -> usize {
+    op_walk::walk_ancestors(std::slice::from_ref(repo.operation())).count()
+}
+
+#[test]
+fn test_repro_bundle_roundtrip() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let path = RepoPath::from_internal_string("secret/plan.txt");
+    let tree = create_tree(repo, &[(path, "top secret")]);
+    let mut tx = repo.start_transaction();
+    let commit_a = write_random_commit(tx.repo_mut());
+    let commit_b = tx
+        .repo_mut()
+        .new_commit(vec![commit_a.id().clone()], tree.id())
+        .set_description("Secret project")
+        .write()
+        .unwrap();
+    tx.repo_mut()
+        .set_local_bookmark_target("secret-feature", RefTarget::normal(commit_b.id().clone()));
+    let repo = tx.commit("add secret").unwrap();
+    let mut tx = repo.start_transaction();
+    write_random_commit(tx.repo_mut());
+    let repo = tx.commit("add another commit").unwrap();
+
+    let bundle = ReproBundle::collect(&repo, true).unwrap();
+    assert_eq!(bundle.operations.len(), num_operations(&repo));
+    let json = serde_json::to_string(&bundle).unwrap();
+    for secret in [
+        "secret",
+        "plan.txt",
+        "Secret project",
+        "add another",
+        "test.user",
+    ] {
+        assert!(!json.contains(secret), "{secret:?} leaked into {json}");
+    }
+
+    let dest_repo = TestRepo::init();
+    let op_id = bundle.load_into(&dest_repo.repo).unwrap();
+    let loaded = dest_repo.repo.loader().load_at_head().unwrap();
+    assert_eq!(loaded.op_id(), &op_id);
+    assert_eq!(num_operations(&loaded), num_operations(&repo));
+    assert_eq!(loaded.view().heads().len(), repo.view().heads().len());
+
+    // The bookmark is renamed, and its commit keeps the change ID and the
+    // shape of its tree, but not the description or paths
+    let (name, target) = loaded.view().local_bookmarks().exactly_one().ok().unwrap();
+    assert!(name.starts_with("bookmark-"), "{name}");
+    let loaded_b = loaded
+        .store()
+        .get_commit(target.as_normal().unwrap())
+        .unwrap();
+    assert_eq!(loaded_b.change_id(), commit_b.change_id());
+    assert_eq!(loaded_b.description(), "");
+    let loaded_a = loaded_b.parents().exactly_one().ok().unwrap().unwrap();
+    assert_eq!(loaded_a.change_id(), commit_a.change_id());
+    let paths = loaded_b
+        .tree()
+        .unwrap()
+        .entries()
+        .map(|(path, _)| path)
+        .collect_vec();
+    assert_eq!(paths.len(), 1);
+    assert_eq!(paths[0].components().count(), 2);
+    assert_ne!(paths[0].as_ref(), path);
+}
+
+#[test]
+fn test_repro_bundle_without_trees() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let mut tx = repo.start_transaction();
+    write_random_commit(tx.repo_mut());
+    let repo = tx.commit("test").unwrap();
+
+    let bundle = ReproBundle::collect(&repo, false).unwrap();
+    assert!(bundle.commits.iter().all(|commit| commit.files.is_none()));
+    assert_eq!(bundle.index.as_ref().unwrap().num_commits, 2);
+
+    let dest_repo = TestRepo::init();
+    bundle.load_into(&dest_repo.repo).unwrap();
+    let loaded = dest_repo.repo.loader().load_at_head().unwrap();
+    for commit_id in loaded.view().heads() {
+        let commit = loaded.store().get_commit(commit_id).unwrap();
+        assert_eq!(commit.tree_id(), loaded.store().empty_merged_tree_id());
+    }
+}
+
+#[test]
+fn test_repro_bundle_unsupported_version() {
+    let test_repo = TestRepo::init();
+    let mut bundle = ReproBundle::collect(&test_repo.repo, false).unwrap();
+    bundle.version += 1;
+
+    let dest_repo = TestRepo::init();
+    assert_matches!(
+        bundle.load_into(&dest_repo.repo),
+        Err(ReproBundleError::UnsupportedVersion(_))
+    );
+}
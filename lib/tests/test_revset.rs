@@ -52,6 +52,7 @@ use jj_lib::revset::RevsetWorkspaceContext;
 use jj_lib::revset::SymbolResolver;
 use jj_lib::revset::SymbolResolverExtension;
 use jj_lib::settings::GitSettings;
+use jj_lib::str_util::StringPattern;
 use jj_lib::workspace::Workspace;
 use test_case::test_case;
 use testutils::create_random_commit;
@@ -505,8 +506,8 @@ fn test_resolve_working_copies() {
     mut_repo
         .set_wc_commit(ws2.clone(), commit2.id().clone())
         .unwrap();
-    let resolve = || -> Vec<CommitId> {
-        RevsetExpression::working_copies()
+    let resolve = |pattern: StringPattern| -> Vec<CommitId> {
+        RevsetExpression::working_copies(pattern)
             .resolve_user_expression(mut_repo, &FailingSymbolResolver)
             .unwrap()
             .evaluate(mut_repo)
@@ -517,7 +518,16 @@ fn test_resolve_working_copies() {
     };
 
     // ensure our output has those two commits
-    assert_eq!(resolve(), vec![commit2.id().clone(), commit1.id().clone()]);
+    assert_eq!(
+        resolve(StringPattern::everything()),
+        vec![commit2.id().clone(), commit1.id().clone()]
+    );
+    // filter by workspace name
+    assert_eq!(
+        resolve(StringPattern::exact("ws1")),
+        vec![commit1.id().clone()]
+    );
+    assert_eq!(resolve(StringPattern::exact("ws3")), vec![]);
 }
 
 #[test]
@@ -1071,6 +1081,45 @@ fn test_evaluate_expression_heads() {
     );
 }
 
+#[test]
+fn test_evaluate_expression_repeated_on_readonly_repo() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let mut graph_builder = CommitGraphBuilder::new(tx.repo_mut());
+    let commit1 = graph_builder.initial_commit();
+    let commit2 = graph_builder.commit_with_parents(&[&commit1]);
+    let commit3 = graph_builder.commit_with_parents(&[&commit2]);
+    let commit4 = graph_builder.commit_with_parents(&[&commit1]);
+    let repo = tx.commit("test").unwrap();
+
+    // Results of repeated sub-expressions are cached by the readonly index,
+    // which must not change the results.
+    let revset_str = format!(
+        "heads(::{c3} | ::{c4}) | (::{c3} ~ ::{c4}) | roots(::{c3})",
+        c3 = commit3.id(),
+        c4 = commit4.id()
+    );
+    let expected = vec![
+        commit4.id().clone(),
+        commit3.id().clone(),
+        commit2.id().clone(),
+        repo.store().root_commit_id().clone(),
+    ];
+    for _ in 0..3 {
+        assert_eq!(resolve_commit_ids(repo.as_ref(), &revset_str), expected);
+    }
+    assert_eq!(
+        resolve_commit_ids(repo.as_ref(), &format!("::{}", commit4.id())),
+        vec![
+            commit4.id().clone(),
+            commit1.id().clone(),
+            repo.store().root_commit_id().clone(),
+        ]
+    );
+}
+
 #[test]
 fn test_evaluate_expression_roots() {
     let test_repo = TestRepo::init();
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use itertools::Itertools as _;
+use jj_lib::backend::TreeValue;
 use jj_lib::commit::Commit;
 use jj_lib::matchers::EverythingMatcher;
 use jj_lib::matchers::FilesMatcher;
@@ -28,6 +29,7 @@ use jj_lib::rewrite::restore_tree;
 use jj_lib::rewrite::CommitRewriter;
 use jj_lib::rewrite::EmptyBehaviour;
 use jj_lib::rewrite::RebaseOptions;
+use jj_lib::rewrite::RebasedCommit;
 use maplit::hashmap;
 use maplit::hashset;
 use test_case::test_case;
@@ -35,6 +37,7 @@ use testutils::assert_abandoned_with_parent;
 use testutils::assert_rebased_onto;
 use testutils::create_random_commit;
 use testutils::create_tree;
+use testutils::read_file;
 use testutils::write_random_commit;
 use testutils::CommitGraphBuilder;
 use testutils::TestRepo;
@@ -533,6 +536,7 @@ fn test_rebase_descendants_abandon_degenerate_merge_simplify() {
         .repo_mut()
         .rebase_descendants_with_options_return_map(RebaseOptions {
             simplify_ancestor_merge: true,
+            linearize_merges: false,
             ..Default::default()
         })
         .unwrap();
@@ -570,6 +574,7 @@ fn test_rebase_descendants_abandon_degenerate_merge_preserve() {
         .repo_mut()
         .rebase_descendants_with_options_return_map(RebaseOptions {
             simplify_ancestor_merge: false,
+            linearize_merges: false,
             ..Default::default()
         })
         .unwrap();
@@ -1575,6 +1580,8 @@ fn test_empty_commit_option(empty_behavior: EmptyBehaviour) {
         .rebase_descendants_with_options_return_map(RebaseOptions {
             empty: empty_behavior,
             simplify_ancestor_merge: true,
+            linearize_merges: false,
+            reuse_merge_resolutions: false,
         })
         .unwrap();
 
@@ -1707,6 +1714,8 @@ fn test_rebase_abandoning_empty() {
     let rebase_options = RebaseOptions {
         empty: EmptyBehaviour::AbandonAllEmpty,
         simplify_ancestor_merge: true,
+        linearize_merges: false,
+        reuse_merge_resolutions: false,
     };
     let rewriter = CommitRewriter::new(tx.repo_mut(), commit_b, vec![commit_b2.id().clone()]);
     rebase_commit_with_options(rewriter, &rebase_options).unwrap();
@@ -1737,3 +1746,81 @@ fn test_rebase_abandoning_empty() {
         hashset! {new_commit_f.id().clone(), new_wc_commit_id.clone()}
     );
 }
+
+#[test_case(false; "without resolutions")]
+#[test_case(true; "with resolutions")]
+fn test_rebase_merge_reuse_resolutions(reuse_merge_resolutions: bool) {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+    let store = repo.store();
+    let path = RepoPath::from_internal_string("file");
+
+    // The conflict between B and C is resolved in D. B2 and C2 change the line
+    // after the conflict the same way, which conflicts with the resolution
+    // when D is rebased onto them, but not when B2 and C2 are merged again.
+    let tree_a = create_tree(repo, &[(path, "1\n2\n3\n")]);
+    let tree_b = create_tree(repo, &[(path, "1\nb\n3\n")]);
+    let tree_c = create_tree(repo, &[(path, "1\nc\n3\n")]);
+    let tree_d = create_tree(repo, &[(path, "1\nbc\n3\n")]);
+    let tree_e = create_tree(repo, &[(path, "1\n2\nx\n")]);
+    let tree_b2 = create_tree(repo, &[(path, "1\nb\nx\n")]);
+    let tree_c2 = create_tree(repo, &[(path, "1\nc\nx\n")]);
+
+    let mut tx = repo.start_transaction();
+    let mut_repo = tx.repo_mut();
+    let commit_a = mut_repo
+        .new_commit(vec![store.root_commit_id().clone()], tree_a.id())
+        .write()
+        .unwrap();
+    let commit_b = mut_repo
+        .new_commit(vec![commit_a.id().clone()], tree_b.id())
+        .write()
+        .unwrap();
+    let commit_c = mut_repo
+        .new_commit(vec![commit_a.id().clone()], tree_c.id())
+        .write()
+        .unwrap();
+    let commit_d = mut_repo
+        .new_commit(
+            vec![commit_b.id().clone(), commit_c.id().clone()],
+            tree_d.id(),
+        )
+        .write()
+        .unwrap();
+    let commit_e = mut_repo
+        .new_commit(vec![commit_a.id().clone()], tree_e.id())
+        .write()
+        .unwrap();
+    let commit_b2 = mut_repo
+        .new_commit(vec![commit_e.id().clone()], tree_b2.id())
+        .write()
+        .unwrap();
+    let commit_c2 = mut_repo
+        .new_commit(vec![commit_e.id().clone()], tree_c2.id())
+        .write()
+        .unwrap();
+
+    let rebase_options = RebaseOptions {
+        reuse_merge_resolutions,
+        ..Default::default()
+    };
+    let rewriter = CommitRewriter::new(
+        mut_repo,
+        commit_d,
+        vec![commit_b2.id().clone(), commit_c2.id().clone()],
+    );
+    let RebasedCommit::Rewritten(new_commit_d) =
+        rebase_commit_with_options(rewriter, &rebase_options).unwrap()
+    else {
+        panic!("merge commit shouldn't be abandoned");
+    };
+    let value = new_commit_d.tree().unwrap().path_value(path).unwrap();
+    if reuse_merge_resolutions {
+        let Some(Some(TreeValue::File { id, .. })) = value.as_resolved() else {
+            panic!("unexpected value: {value:?}");
+        };
+        assert_eq!(read_file(store, path, id), b"1\nbc\nx\n");
+    } else {
+        assert!(!value.is_resolved());
+    }
+}
@@ -12,11 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use itertools::Itertools as _;
 use jj_lib::backend::CommitId;
 use jj_lib::repo::Repo;
 use jj_lib::repo_path::RepoPath;
 use jj_lib::rewrite::duplicate_commits;
+use jj_lib::rewrite::duplicate_commits_onto_parents;
 use jj_lib::transaction::Transaction;
 use testutils::create_tree;
 use testutils::TestRepo;
@@ -76,6 +79,7 @@ fn test_duplicate_linear_contents() {
         duplicate_commits(
             tx.repo_mut(),
             &target_commits.iter().copied().cloned().collect_vec(),
+            &HashMap::new(),
             &parent_commit_ids.iter().copied().cloned().collect_vec(),
             &children_commit_ids.iter().copied().cloned().collect_vec(),
         )
@@ -177,3 +181,60 @@ fn test_duplicate_linear_contents() {
         &tree_1_2.id()
     );
 }
+
+#[test]
+fn test_duplicate_descriptions() {
+    let test_repo = TestRepo::init();
+    let repo = &test_repo.repo;
+
+    let mut tx = repo.start_transaction();
+    let root_commit_id = repo.store().root_commit_id().clone();
+    let commit_a = tx
+        .repo_mut()
+        .new_commit(
+            vec![root_commit_id.clone()],
+            repo.store().empty_merged_tree_id(),
+        )
+        .set_description("a\n")
+        .write()
+        .unwrap();
+    let commit_b = tx
+        .repo_mut()
+        .new_commit(
+            vec![commit_a.id().clone()],
+            repo.store().empty_merged_tree_id(),
+        )
+        .set_description("b\n")
+        .write()
+        .unwrap();
+    let repo = tx.commit("test").unwrap();
+
+    // Only the commits with a new description are updated
+    let target_descriptions = HashMap::from([(commit_b.id().clone(), "new b\n".to_owned())]);
+    let mut tx = repo.start_transaction();
+    let stats = duplicate_commits_onto_parents(
+        tx.repo_mut(),
+        &[commit_b.id().clone(), commit_a.id().clone()],
+        &target_descriptions,
+    )
+    .unwrap();
+    assert_eq!(stats.duplicated_commits[commit_a.id()].description(), "a\n");
+    assert_eq!(
+        stats.duplicated_commits[commit_b.id()].description(),
+        "new b\n"
+    );
+
+    let mut tx = repo.start_transaction();
+    let stats = duplicate_commits(
+        tx.repo_mut(),
+        &[commit_b.id().clone()],
+        &target_descriptions,
+        &[root_commit_id],
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        stats.duplicated_commits[commit_b.id()].description(),
+        "new b\n"
+    );
+}
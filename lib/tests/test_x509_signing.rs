@@ -0,0 +1,136 @@
+// Copyright 2026 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the X.509 signing backend, using a fake signing program which
+//! accepts the same arguments and prints the same status lines as `gpgsm`.
+
+#![cfg(unix)]
+
+use std::fs;
+use std::path::Path;
+
+use jj_lib::gpg_signing::X509Backend;
+use jj_lib::signing::SigStatus;
+use jj_lib::signing::SignError;
+use jj_lib::signing::SigningBackend;
+
+static FAKE_GPGSM: &str = r#"
+printf '%s\n' "$*" >> "$(dirname "$0")/args"
+case "$1" in
+-bsa | -bsau)
+    if [ "$2" = "unknown" ]; then
+        echo "gpgsm: error: no secret key" >&2
+        exit 2
+    fi
+    data=$(cat)
+    printf -- '-----BEGIN SIGNED MESSAGE-----\n%s\n-----END SIGNED MESSAGE-----\n' "$data"
+    ;;
+--status-fd=1)
+    data=$(cat)
+    if grep -q -x -F -e "$data" "$3"; then
+        echo '[GNUPG:] NEWSIG'
+        echo '[GNUPG:] GOODSIG 1A2B3C4D /CN=Someone/O=jj test signing key'
+    else
+        echo '[GNUPG:] BADSIG 1A2B3C4D /CN=Someone/O=jj test signing key'
+    fi
+    ;;
+*)
+    echo "unexpected arguments: $*" >&2
+    exit 2
+    ;;
+esac
+"#;
+
+fn fake_gpgsm(dir: &Path) -> X509Backend {
+    // Run the script through the shell so it doesn't have to be executable.
+    let script = dir.join("gpgsm");
+    fs::write(&script, FAKE_GPGSM).unwrap();
+    X509Backend::new("sh".into(), false).with_extra_args(&[script.into()])
+}
+
+fn recorded_args(dir: &Path) -> Vec<String> {
+    let args = fs::read_to_string(dir.join("args")).unwrap();
+    args.lines()
+        .map(|line| {
+            // Mask the path to the temporary signature file
+            match line.split_once(" --verify ") {
+                Some((head, _)) => format!("{head} --verify <signature> -"),
+                None => line.to_owned(),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn x509_signing_roundtrip() {
+    let temp_dir = testutils::new_temp_dir();
+    let backend = fake_gpgsm(temp_dir.path());
+    let data = b"hello world";
+    let signature = backend.sign(data, None).unwrap();
+    assert!(backend.can_read(&signature));
+
+    let check = backend.verify(data, &signature).unwrap();
+    assert_eq!(check.status, SigStatus::Good);
+    assert_eq!(check.key.unwrap(), "1A2B3C4D");
+    assert_eq!(check.display.unwrap(), "/CN=Someone/O=jj test signing key");
+
+    let check = backend.verify(b"so so bad", &signature).unwrap();
+    assert_eq!(check.status, SigStatus::Bad);
+
+    assert_eq!(
+        recorded_args(temp_dir.path()),
+        [
+            "-bsa",
+            "--status-fd=1 --verify <signature> -",
+            "--status-fd=1 --verify <signature> -",
+        ]
+    );
+}
+
+#[test]
+fn x509_signing_explicit_key() {
+    let temp_dir = testutils::new_temp_dir();
+    let backend = fake_gpgsm(temp_dir.path());
+    let signature = backend.sign(b"hello world", Some("someone@example.com"));
+    assert!(signature.is_ok());
+    assert_eq!(
+        recorded_args(temp_dir.path()),
+        ["-bsau someone@example.com"]
+    );
+}
+
+#[test]
+fn x509_signing_failure() {
+    let temp_dir = testutils::new_temp_dir();
+    let backend = fake_gpgsm(temp_dir.path());
+    let err = backend.sign(b"hello world", Some("unknown")).unwrap_err();
+    let SignError::Backend(source) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    insta::assert_snapshot!(source.to_string(), @r"
+    X.509 signing program failed with exit status: 2:
+    gpgsm: error: no secret key
+    ");
+}
+
+#[test]
+fn x509_program_not_found() {
+    let temp_dir = testutils::new_temp_dir();
+    let backend = X509Backend::new(temp_dir.path().join("missing").into(), false);
+    let err = backend.sign(b"hello world", None).unwrap_err();
+    let SignError::Backend(source) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(source.to_string(), "Failed to run X.509 signing program");
+}